@@ -14,7 +14,8 @@ use self::access::gen_state_access_trace;
 use crate::{
     error::Error,
     evm::opcodes::{gen_associated_ops, gen_associated_steps},
-    operation::{CallContextField, Operation, RWCounter, StartOp, RW},
+    operation::{AccountField, CallContextField, Operation, RWCounter, StartOp, RW},
+    precompile::PrecompileCalls,
     rpc::GethClient,
     state_db::{self, CodeDB, StateDB},
 };
@@ -23,9 +24,13 @@ pub use block::{Block, BlockContext};
 pub use call::{Call, CallContext, CallKind};
 use core::fmt::Debug;
 use eth_types::{
-    self, geth_types,
+    self,
+    evm_types::{BlockReward, Hardfork},
+    geth_types,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
-    Address, GethExecStep, GethExecTrace, ToWord, Word,
+    state_trie::{StateAccount, StateTrie},
+    trie::ordered_trie_nodes,
+    Address, GethExecStep, GethExecTrace, ToBigEndian, ToWord, Word, H256,
 };
 use ethers_providers::JsonRpcClient;
 pub use execution::{
@@ -34,7 +39,7 @@ pub use execution::{
 pub use input_state_ref::CircuitInputStateRef;
 use itertools::Itertools;
 use log::warn;
-use std::collections::HashMap;
+use std::{collections::HashMap, iter};
 pub use transaction::{Transaction, TransactionContext};
 
 /// Circuit Setup Parameters
@@ -68,6 +73,41 @@ pub struct CircuitsParams {
     /// calculated, so the same circuit will not be able to prove different
     /// witnesses.
     pub max_keccak_rows: usize,
+    /// Maximum number of calls to the ecRecover precompile supported by the
+    /// EcRecover Circuit.  When 0, it will be dynamically calculated from the
+    /// number of calls in the block.
+    pub max_ecrecover_calls: usize,
+    /// Maximum number of rows that the SHA-256 Circuit can use.
+    /// Each SHA-256 block requires 72 rows, so the number of blocks supported
+    /// is `max_sha256_rows / 72`.  When 0, it will be dynamically calculated
+    /// from the inputs in the block.
+    pub max_sha256_rows: usize,
+    /// Maximum number of calls to the MODEXP precompile supported by the
+    /// ModExp Circuit, each of them using 512 rows.  When 0, it will be
+    /// dynamically calculated from the number of calls in the block.
+    pub max_modexp_calls: usize,
+    /// Maximum number of successful calls to the ecAdd precompile supported by
+    /// the ECC Circuit.  When 0, it will be dynamically calculated from the
+    /// number of calls in the block.
+    pub max_ec_add_calls: usize,
+    /// Maximum number of successful calls to the ecMul precompile supported by
+    /// the ECC Circuit.  When 0, it will be dynamically calculated from the
+    /// number of calls in the block.
+    pub max_ec_mul_calls: usize,
+    /// Maximum number of successful calls to the ecPairing precompile
+    /// supported by the ECC Circuit.  When 0, it will be dynamically
+    /// calculated from the number of calls in the block.
+    pub max_ec_pairing_calls: usize,
+    /// Maximum number of rows of the nodes, of the keys and of the steps of
+    /// the proofs in the MPT Circuit.  When 0, it will be dynamically
+    /// calculated from the updates of the tries in the block.
+    pub max_mpt_rows: usize,
+    /// Hardfork whose rules select the opcodes, the gas costs, the refunds and
+    /// the precompiles of the block.
+    pub hardfork: Hardfork,
+    /// Rewards credited at the end of the block to the miners of the block
+    /// and of its uncles, which depend on the consensus of the network.
+    pub block_reward: BlockReward,
 }
 
 impl Default for CircuitsParams {
@@ -84,6 +124,15 @@ impl Default for CircuitsParams {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            hardfork: Hardfork::default(),
+            block_reward: BlockReward::default(),
         }
     }
 }
@@ -204,21 +253,85 @@ impl<'a> CircuitInputBuilder {
             self.handle_tx(tx, geth_trace, tx_index + 1 == eth_block.transactions.len())?;
         }
         self.set_value_ops_call_context_rwc_eor();
-        self.set_end_block();
+        self.set_end_block()?;
         Ok(())
     }
 
-    fn set_end_block(&mut self) {
+    fn set_end_block(&mut self) -> Result<(), Error> {
         let max_rws = self.block.circuits_params.max_rws;
         let mut end_block_not_last = self.block.block_steps.end_block_not_last.clone();
         let mut end_block_last = self.block.block_steps.end_block_last.clone();
-        end_block_not_last.rwc = self.block_ctx.rwc;
-        end_block_last.rwc = self.block_ctx.rwc;
 
         let mut dummy_tx = Transaction::default();
         let mut dummy_tx_ctx = TransactionContext::default();
         let mut state = self.state_ref(&mut dummy_tx, &mut dummy_tx_ctx);
 
+        // Apply the withdrawals (EIP-4895) in a Withdrawal step each, which
+        // increases the balance of the recipient.  The log_id of the steps
+        // counts the withdrawals applied before them.
+        let mut withdrawal_steps = Vec::new();
+        for (log_id, withdrawal) in state.block.withdrawals.clone().into_iter().enumerate() {
+            let mut withdrawal_step = ExecStep {
+                exec_state: ExecState::Withdrawal,
+                rwc: state.block_ctx.rwc,
+                log_id,
+                ..ExecStep::default()
+            };
+            let balance_prev = state.sdb.get_account(&withdrawal.address).1.balance;
+            state.account_write(
+                &mut withdrawal_step,
+                withdrawal.address,
+                AccountField::Balance,
+                balance_prev + withdrawal.amount_wei(),
+                balance_prev,
+            )?;
+            withdrawal_steps.push(withdrawal_step);
+        }
+
+        // Credit the block reward to the coinbase and the uncle rewards to the
+        // miners of the uncles in the BlockReward step.
+        let mut block_reward_step = ExecStep {
+            exec_state: ExecState::BlockReward,
+            rwc: state.block_ctx.rwc,
+            log_id: withdrawal_steps.len(),
+            ..ExecStep::default()
+        };
+        let CircuitsParams {
+            hardfork,
+            block_reward,
+            ..
+        } = state.block.circuits_params;
+        if !block_reward.block_reward(hardfork).is_zero() {
+            let number = state.block.number.as_u64();
+            let uncles = state.block.uncles.clone();
+            let rewards = iter::once((
+                state.block.coinbase,
+                block_reward.block_reward(hardfork)
+                    + block_reward.nephew_reward(hardfork) * uncles.len() as u64,
+            ))
+            .chain(uncles.iter().map(|uncle| {
+                (
+                    uncle.coinbase,
+                    block_reward.uncle_reward(hardfork, number, uncle.number),
+                )
+            }));
+            for (address, reward) in rewards {
+                let balance_prev = state.sdb.get_account(&address).1.balance;
+                state.account_write(
+                    &mut block_reward_step,
+                    address,
+                    AccountField::Balance,
+                    balance_prev + reward,
+                    balance_prev,
+                )?;
+            }
+        }
+
+        for end_block in [&mut end_block_not_last, &mut end_block_last] {
+            end_block.rwc = state.block_ctx.rwc;
+            end_block.log_id = withdrawal_steps.len();
+        }
+
         if let Some(call_id) = state.block.txs.last().map(|tx| tx.calls[0].call_id) {
             state.call_context_read(
                 &mut end_block_last,
@@ -252,8 +365,11 @@ impl<'a> CircuitInputBuilder {
             StartOp {},
         );
 
+        self.block.block_steps.withdrawals = withdrawal_steps;
+        self.block.block_steps.block_reward = block_reward_step;
         self.block.block_steps.end_block_not_last = end_block_not_last;
         self.block.block_steps.end_block_last = end_block_last;
+        Ok(())
     }
 
     /// Handle a transaction with its corresponding execution trace to generate
@@ -277,6 +393,20 @@ impl<'a> CircuitInputBuilder {
         )?;
         tx.steps_mut().push(begin_tx_step);
 
+        // A transaction sent to a precompiled contract has no geth steps, the
+        // precompile is executed right after BeginTx.
+        if let Some(precompile) = tx
+            .tx
+            .to
+            .and_then(|address| PrecompileCalls::try_from(address).ok())
+        {
+            let precompile_step = gen_associated_steps(
+                &mut self.state_ref(&mut tx, &mut tx_ctx),
+                ExecState::Precompile(precompile),
+            )?;
+            tx.steps_mut().push(precompile_step);
+        }
+
         for (index, geth_step) in geth_trace.struct_logs.iter().enumerate() {
             let mut state_ref = self.state_ref(&mut tx, &mut tx_ctx);
             log::trace!("handle {}th opcode {:?} ", index, geth_step.op);
@@ -293,6 +423,16 @@ impl<'a> CircuitInputBuilder {
             gen_associated_steps(&mut self.state_ref(&mut tx, &mut tx_ctx), ExecState::EndTx)?;
         tx.steps_mut().push(end_tx_step);
 
+        // Generate a DestructAccount step for every account self destructed in
+        // the tx
+        for _ in self.sdb.destructed_accounts() {
+            let destruct_account_step = gen_associated_steps(
+                &mut self.state_ref(&mut tx, &mut tx_ctx),
+                ExecState::DestructAccount,
+            )?;
+            tx.steps_mut().push(destruct_account_step);
+        }
+
         self.sdb.commit_tx();
         self.block.txs.push(tx);
 
@@ -313,8 +453,24 @@ pub fn keccak_inputs(block: &Block, code_db: &CodeDB) -> Result<Vec<Vec<u8>>, Er
     }
     // EVM Circuit
     keccak_inputs.extend_from_slice(&block.sha3_inputs);
+    // EcRecover Circuit
+    let ecrecover_sign_datas: Vec<SignData> = block
+        .ecrecover_events()
+        .iter()
+        .filter_map(|event| event.sign_data())
+        .collect();
+    keccak_inputs.extend_from_slice(&keccak_inputs_sign_verify(&ecrecover_sign_datas));
+    // PI Circuit
+    keccak_inputs.extend(keccak_inputs_pi_circuit(
+        &block.header(),
+        &block.uncles,
+        &txs,
+        &block.receipts(),
+        &block.withdrawals,
+        block.chain_id.as_u64(),
+    ));
     // MPT Circuit
-    // TODO https://github.com/privacy-scaling-explorations/zkevm-circuits/issues/696
+    keccak_inputs.extend(keccak_inputs_mpt_circuit(block)?);
     Ok(keccak_inputs)
 }
 
@@ -356,8 +512,125 @@ pub fn keccak_inputs_tx_circuit(
     // Keccak inputs from SignVerify Chip
     let sign_verify_inputs = keccak_inputs_sign_verify(&sign_datas);
     inputs.extend_from_slice(&sign_verify_inputs);
-    // NOTE: We don't verify the Tx Hash in the circuit yet, so we don't have more
-    // hash inputs.
+    // Keccak inputs from the Tx Hash, including the padding tx
+    inputs.extend(txs.iter().map(|tx| tx.rlp_signed(chain_id)));
+    inputs.push(geth_types::Transaction::padding(chain_id).rlp_signed(chain_id));
+    Ok(inputs)
+}
+
+/// Generate the keccak inputs required by the PI Circuit to compute the block
+/// hash, the uncles hash, the transactions, receipts and withdrawals roots and
+/// the logs bloom: the encodings of the header, of the headers of the uncles,
+/// of the receipts, of the withdrawals and of the nodes of the three tries,
+/// and the addresses and topics of the logs.  The encodings of the
+/// transactions are already inputs of the Tx Circuit.
+pub fn keccak_inputs_pi_circuit(
+    header: &geth_types::BlockHeader,
+    uncles: &[geth_types::BlockHeader],
+    txs: &[geth_types::Transaction],
+    receipts: &[geth_types::Receipt],
+    withdrawals: &[geth_types::Withdrawal],
+    chain_id: u64,
+) -> Vec<Vec<u8>> {
+    let tx_rlps: Vec<Vec<u8>> = txs.iter().map(|tx| tx.rlp_signed(chain_id)).collect();
+    let receipt_rlps: Vec<Vec<u8>> = receipts.iter().map(|receipt| receipt.rlp()).collect();
+    let withdrawal_rlps: Vec<Vec<u8>> = withdrawals
+        .iter()
+        .map(|withdrawal| withdrawal.rlp())
+        .collect();
+    let mut inputs = vec![header.rlp(), geth_types::BlockHeader::uncles_rlp(uncles)];
+    inputs.extend_from_slice(&receipt_rlps);
+    inputs.extend_from_slice(&withdrawal_rlps);
+    for values in [tx_rlps, receipt_rlps, withdrawal_rlps] {
+        inputs.extend(
+            ordered_trie_nodes(&values)
+                .iter()
+                .map(|(_, node)| node.rlp()),
+        );
+    }
+    inputs.extend(
+        receipts
+            .iter()
+            .flat_map(|receipt| &receipt.logs)
+            .flat_map(|log| log.bloom_inputs())
+            .map(|input| input.to_vec()),
+    );
+    inputs
+}
+
+/// Generate the keccak inputs required by the MPT Circuit to prove the updates
+/// of the state trie and of the storage tries: the encodings of the nodes of
+/// the paths to the updated keys before and after every update, and the
+/// addresses and storage keys hashed into the keys of the tries.  The final
+/// writes of the block are replayed on the state trie in the order of the rows
+/// of the State Circuit, which sets the intermediate roots of the updates:
+/// the storage writes and clears by tx, address, clear flag and key, then the
+/// account writes by address and field.
+pub fn keccak_inputs_mpt_circuit(block: &Block) -> Result<Vec<Vec<u8>>, Error> {
+    let mut state_trie = block.state_trie.clone();
+    let mut updates = Vec::new();
+    let mut keys = Vec::new();
+    let storage_ops = block.container.storage.iter().sorted_by_key(|op| {
+        let storage_op = op.op();
+        (
+            storage_op.tx_id,
+            storage_op.address,
+            storage_op.is_clear,
+            storage_op.key,
+            op.rwc(),
+        )
+    });
+    for (_, ops) in &storage_ops.group_by(|op| {
+        let storage_op = op.op();
+        (
+            storage_op.tx_id,
+            storage_op.address,
+            storage_op.is_clear,
+            storage_op.key,
+        )
+    }) {
+        let storage_op = ops.last().expect("group is not empty").op();
+        keys.push(storage_op.address.as_bytes().to_vec());
+        if storage_op.is_clear {
+            updates.push(state_trie.clear_storage(&storage_op.address)?);
+            continue;
+        }
+        let (storage_update, account_update) =
+            state_trie.set_storage(&storage_op.address, &storage_op.key, storage_op.value)?;
+        updates.extend([storage_update, account_update]);
+        keys.push(storage_op.key.to_be_bytes().to_vec());
+    }
+    let account_ops = block
+        .container
+        .account
+        .iter()
+        .sorted_by_key(|op| (op.op().address, op.op().field, op.rwc()));
+    for (_, ops) in &account_ops.group_by(|op| (op.op().address, op.op().field)) {
+        let account_op = ops.last().expect("group is not empty").op();
+        let mut account = state_trie.account(&account_op.address)?;
+        match account_op.field {
+            AccountField::Nonce => account.nonce = account_op.value.low_u64(),
+            AccountField::Balance => account.balance = account_op.value,
+            AccountField::CodeHash => account.code_hash = H256::from_uint(&account_op.value),
+        }
+        updates.push(state_trie.set_account(&account_op.address, account)?);
+        keys.push(account_op.address.as_bytes().to_vec());
+    }
+
+    let mut inputs: Vec<Vec<u8>> = updates
+        .iter()
+        .flat_map(|update| {
+            update
+                .old_path
+                .iter()
+                .chain(&update.new_path)
+                .chain(&update.merged)
+        })
+        .map(|node| node.rlp())
+        .chain(keys)
+        .collect();
+    inputs.sort();
+    inputs.dedup();
     Ok(inputs)
 }
 
@@ -420,15 +693,62 @@ pub fn get_state_accesses(
         let tx_access_trace = gen_state_access_trace(eth_block, tx, geth_trace)?;
         block_access_trace.extend(tx_access_trace);
     }
+    // The balances of the miners of the uncles and of the recipients of the
+    // withdrawals are increased at the end of the block.
+    block_access_trace.extend(
+        geth_types::uncles(eth_block)
+            .into_iter()
+            .map(|uncle| uncle.coinbase)
+            .chain(
+                geth_types::withdrawals(eth_block)
+                    .into_iter()
+                    .map(|withdrawal| withdrawal.address),
+            )
+            .map(|address| Access::new(None, RW::WRITE, AccessValue::Account { address })),
+    );
 
     Ok(AccessSet::from(block_access_trace))
 }
 
-/// Build a partial StateDB from step 3
+/// Build a partial StateDB from step 3, with the partial state trie of the
+/// nodes of the proofs, which are verified against the previous state root.
 pub fn build_state_code_db(
     proofs: Vec<eth_types::EIP1186ProofResponse>,
     codes: HashMap<Address, Vec<u8>>,
-) -> (StateDB, CodeDB) {
+    prev_state_root: Word,
+) -> Result<(StateDB, CodeDB, StateTrie), Error> {
+    let mut state_trie = StateTrie::new(H256::from_uint(&prev_state_root));
+    for proof in &proofs {
+        let storage_nodes = proof
+            .storage_proof
+            .iter()
+            .flat_map(|storage_proof| &storage_proof.proof);
+        state_trie.add_nodes(
+            proof
+                .account_proof
+                .iter()
+                .chain(storage_nodes)
+                .map(|node| node.as_ref()),
+        )?;
+    }
+    for proof in &proofs {
+        let account = state_trie.account(&proof.address)?;
+        // The proofs of missing accounts may have any storage and code hashes
+        let is_valid = account.nonce == proof.nonce.as_u64()
+            && account.balance == proof.balance
+            && (account == StateAccount::default()
+                || (account.storage_root == proof.storage_hash
+                    && account.code_hash == proof.code_hash));
+        if !is_valid {
+            return Err(Error::InvalidStateProof(proof.address));
+        }
+        for storage_proof in &proof.storage_proof {
+            if state_trie.storage(&proof.address, &storage_proof.key)? != storage_proof.value {
+                return Err(Error::InvalidStateProof(proof.address));
+            }
+        }
+    }
+
     let mut sdb = StateDB::new();
     for proof in proofs {
         let mut storage = HashMap::new();
@@ -450,7 +770,7 @@ pub fn build_state_code_db(
     for (_address, code) in codes {
         code_db.insert(code.clone());
     }
-    (sdb, code_db)
+    Ok((sdb, code_db, state_trie))
 }
 
 impl<P: JsonRpcClient> BuilderClient<P> {
@@ -474,9 +794,26 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         &self,
         block_num: u64,
     ) -> Result<(EthBlock, Vec<eth_types::GethExecTrace>, Vec<Word>, Word), Error> {
-        let eth_block = self.cli.get_block_by_number(block_num.into()).await?;
+        let mut eth_block = self.cli.get_block_by_number(block_num.into()).await?;
         let geth_traces = self.cli.trace_block_by_number(block_num.into()).await?;
 
+        // The block only has the hashes of the uncles, but their rewards
+        // depend on their headers.
+        if !eth_block.uncles.is_empty() {
+            let hash = eth_block
+                .hash
+                .ok_or(Error::EthTypeError(eth_types::Error::IncompleteBlock))?;
+            let mut uncles = Vec::with_capacity(eth_block.uncles.len());
+            for index in 0..eth_block.uncles.len() {
+                let uncle = self
+                    .cli
+                    .get_uncle_by_block_hash_and_index(hash, (index as u64).into())
+                    .await?;
+                uncles.push(geth_types::BlockHeader::from(&uncle));
+            }
+            geth_types::set_uncles(&mut eth_block, &uncles);
+        }
+
         // fetch up to 256 blocks
         let mut n_blocks = std::cmp::min(256, block_num as usize);
         let mut next_hash = eth_block.parent_hash;
@@ -560,8 +897,9 @@ impl<P: JsonRpcClient> BuilderClient<P> {
     pub fn build_state_code_db(
         proofs: Vec<eth_types::EIP1186ProofResponse>,
         codes: HashMap<Address, Vec<u8>>,
-    ) -> (StateDB, CodeDB) {
-        build_state_code_db(proofs, codes)
+        prev_state_root: Word,
+    ) -> Result<(StateDB, CodeDB, StateTrie), Error> {
+        build_state_code_db(proofs, codes, prev_state_root)
     }
 
     /// Step 5. For each step in TxExecTraces, gen the associated ops and state
@@ -573,12 +911,12 @@ impl<P: JsonRpcClient> BuilderClient<P> {
         eth_block: &EthBlock,
         geth_traces: &[eth_types::GethExecTrace],
         history_hashes: Vec<Word>,
-        prev_state_root: Word,
+        state_trie: StateTrie,
     ) -> Result<CircuitInputBuilder, Error> {
         let block = Block::new(
             self.chain_id,
             history_hashes,
-            prev_state_root,
+            state_trie,
             eth_block,
             self.circuits_params,
         )?;
@@ -602,14 +940,15 @@ impl<P: JsonRpcClient> BuilderClient<P> {
             self.get_block(block_num).await?;
         let access_set = Self::get_state_accesses(&eth_block, &geth_traces)?;
         let (proofs, codes) = self.get_state(block_num, access_set).await?;
-        let (state_db, code_db) = Self::build_state_code_db(proofs, codes);
+        let (state_db, code_db, state_trie) =
+            Self::build_state_code_db(proofs, codes, prev_state_root)?;
         let builder = self.gen_inputs_from_state(
             state_db,
             code_db,
             &eth_block,
             &geth_traces,
             history_hashes,
-            prev_state_root,
+            state_trie,
         )?;
         Ok((builder, eth_block))
     }
@@ -4,10 +4,21 @@ use super::{
     execution::ExecState, transaction::Transaction, CircuitsParams, CopyEvent, ExecStep, ExpEvent,
 };
 use crate::{
-    operation::{OperationContainer, RWCounter},
+    operation::{OperationContainer, RWCounter, TxLogField, TxReceiptField, RW},
+    precompile::{
+        EcAddAuxData, EcMulAuxData, EcPairingAuxData, EcrecoverAuxData, ModExpAuxData, ModExpEvent,
+        PrecompileCalls, PrecompileEvent, EC_PAIRING_PAIR_SIZE,
+    },
     Error,
 };
-use eth_types::{evm_unimplemented, Address, Word};
+use eth_types::{
+    evm_types::gas_utils::blob_base_fee,
+    evm_unimplemented,
+    geth_types::{excess_blob_gas, uncles, withdrawals, BlockHeader, Log, Receipt, Withdrawal},
+    state_trie::StateTrie,
+    trie::ordered_trie_root,
+    Address, ToAddress, ToBigEndian, ToWord, Word, H256,
+};
 use std::collections::HashMap;
 
 /// Context of a [`Block`] which can mutate in a [`Transaction`].
@@ -44,6 +55,11 @@ impl BlockContext {
 /// Block-wise execution steps that don't belong to any Transaction.
 #[derive(Debug)]
 pub struct BlockSteps {
+    /// Withdrawal steps that follow the last transaction, one for every
+    /// withdrawal of the block.
+    pub withdrawals: Vec<ExecStep>,
+    /// BlockReward step that follows the withdrawals.
+    pub block_reward: ExecStep,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
@@ -70,10 +86,24 @@ pub struct Block {
     pub timestamp: Word,
     /// gas limit
     pub difficulty: Word,
+    /// prevRandao (EIP-4399), the mix hash of the block, which the DIFFICULTY
+    /// opcode returns from the merge on
+    pub prevrandao: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas (EIP-4844)
+    pub excess_blob_gas: u64,
+    /// blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
+    /// Headers of the uncles, whose miners are rewarded at the end of the block
+    pub uncles: Vec<BlockHeader>,
+    /// Withdrawals (EIP-4895), applied at the end of the block
+    pub withdrawals: Vec<Withdrawal>,
     /// State root of the previous block
     pub prev_state_root: Word,
+    /// State trie of the previous block, known through the nodes of the
+    /// accessed accounts and storage slots
+    pub state_trie: StateTrie,
     /// Container of operations done in this block.
     pub container: OperationContainer,
     /// Transactions contained in the block
@@ -86,6 +116,8 @@ pub struct Block {
     pub sha3_inputs: Vec<Vec<u8>>,
     /// Exponentiation events in the block.
     pub exp_events: Vec<ExpEvent>,
+    /// Precompile call events in the block.
+    pub precompile_events: Vec<PrecompileEvent>,
     /// Circuits Setup Paramteres
     pub circuits_params: CircuitsParams,
     /// Original block from geth
@@ -97,7 +129,7 @@ impl Block {
     pub fn new(
         chain_id: Word,
         history_hashes: Vec<Word>,
-        prev_state_root: Word,
+        state_trie: StateTrie,
         eth_block: &eth_types::Block<eth_types::Transaction>,
         circuits_params: CircuitsParams,
     ) -> Result<Self, Error> {
@@ -108,6 +140,8 @@ impl Block {
             );
         }
 
+        let excess_blob_gas = excess_blob_gas(eth_block);
+
         Ok(Self {
             chain_id,
             history_hashes,
@@ -122,11 +156,22 @@ impl Block {
                 .into(),
             timestamp: eth_block.timestamp,
             difficulty: eth_block.difficulty,
+            prevrandao: eth_block.mix_hash.unwrap_or_default().to_word(),
             base_fee: eth_block.base_fee_per_gas.unwrap_or_default(),
-            prev_state_root,
+            excess_blob_gas,
+            blob_base_fee: blob_base_fee(excess_blob_gas),
+            uncles: uncles(eth_block),
+            withdrawals: withdrawals(eth_block),
+            prev_state_root: state_trie.root().to_word(),
+            state_trie,
             container: OperationContainer::new(),
             txs: Vec::new(),
             block_steps: BlockSteps {
+                withdrawals: Vec::new(),
+                block_reward: ExecStep {
+                    exec_state: ExecState::BlockReward,
+                    ..ExecStep::default()
+                },
                 end_block_not_last: ExecStep {
                     exec_state: ExecState::EndBlock,
                     ..ExecStep::default()
@@ -138,6 +183,7 @@ impl Block {
             },
             copy_events: Vec::new(),
             exp_events: Vec::new(),
+            precompile_events: Vec::new(),
             sha3_inputs: Vec::new(),
             circuits_params,
             eth_block: eth_block.clone(),
@@ -164,4 +210,148 @@ impl Block {
     pub fn add_exp_event(&mut self, event: ExpEvent) {
         self.exp_events.push(event);
     }
+    /// Push a precompile call event to the block.
+    pub fn add_precompile_event(&mut self, event: PrecompileEvent) {
+        self.precompile_events.push(event);
+    }
+
+    /// Auxiliary data of the calls to the ecRecover precompile in the block.
+    pub fn ecrecover_events(&self) -> Vec<EcrecoverAuxData> {
+        self.precompile_events
+            .iter()
+            .filter(|event| event.kind == PrecompileCalls::ECRecover)
+            .map(|event| EcrecoverAuxData::new(&event.input, &event.output))
+            .collect()
+    }
+
+    /// Inputs of the successful calls to the SHA-256 precompile in the block.
+    pub fn sha256_inputs(&self) -> Vec<Vec<u8>> {
+        self.precompile_events
+            .iter()
+            .filter(|event| event.kind == PrecompileCalls::Sha256 && event.is_success)
+            .map(|event| event.input.clone())
+            .collect()
+    }
+
+    /// Modular exponentiations of the successful calls to the MODEXP
+    /// precompile in the block which are supported by the ModExp circuit and
+    /// have a non-zero modulus.
+    pub fn modexp_events(&self) -> Vec<ModExpEvent> {
+        self.precompile_events
+            .iter()
+            .filter(|event| event.kind == PrecompileCalls::Modexp && event.is_success)
+            .map(|event| ModExpAuxData::new(&event.input, &event.output))
+            .filter(|aux_data| aux_data.is_supported() && !aux_data.modulus.is_zero())
+            .map(|aux_data| {
+                let event = ModExpEvent::new(aux_data.base, aux_data.exponent, aux_data.modulus);
+                debug_assert_eq!(event.result, aux_data.output);
+                event
+            })
+            .collect()
+    }
+
+    /// Auxiliary data of the calls to the ecAdd precompile in the block.
+    pub fn ec_add_events(&self) -> Vec<EcAddAuxData> {
+        self.precompile_events
+            .iter()
+            .filter(|event| event.kind == PrecompileCalls::Bn128Add)
+            .map(|event| EcAddAuxData::new(&event.input, &event.output))
+            .collect()
+    }
+
+    /// Auxiliary data of the calls to the ecMul precompile in the block.
+    pub fn ec_mul_events(&self) -> Vec<EcMulAuxData> {
+        self.precompile_events
+            .iter()
+            .filter(|event| event.kind == PrecompileCalls::Bn128Mul)
+            .map(|event| EcMulAuxData::new(&event.input, &event.output))
+            .collect()
+    }
+
+    /// Auxiliary data of the calls to the ecPairing precompile in the block
+    /// whose input length is a multiple of the size of a pair, as the others
+    /// fail without reading the points.
+    pub fn ec_pairing_events(&self) -> Vec<EcPairingAuxData> {
+        self.precompile_events
+            .iter()
+            .filter(|event| {
+                event.kind == PrecompileCalls::Bn128Pairing
+                    && event.input.len() % EC_PAIRING_PAIR_SIZE == 0
+            })
+            .map(|event| EcPairingAuxData::new(&event.input, &event.output))
+            .collect()
+    }
+
+    /// Receipts of the transactions of the block, built from the values
+    /// written to the TxReceipt and TxLog targets.
+    pub fn receipts(&self) -> Vec<Receipt> {
+        self.txs
+            .iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                let tx_id = index + 1;
+                let mut receipt = Receipt {
+                    transaction_type: tx.tx.transaction_type,
+                    ..Default::default()
+                };
+                for op in self.container.tx_receipt.iter() {
+                    // The cumulative gas used of the previous tx is also read
+                    if op.rw() != RW::WRITE || op.op().tx_id != tx_id {
+                        continue;
+                    }
+                    match op.op().field {
+                        TxReceiptField::PostStateOrStatus => receipt.status = op.op().value,
+                        TxReceiptField::CumulativeGasUsed => {
+                            receipt.cumulative_gas_used = op.op().value
+                        }
+                        TxReceiptField::LogLength => {
+                            receipt.logs = vec![Log::default(); op.op().value as usize]
+                        }
+                    }
+                }
+                let mut data = vec![vec![]; receipt.logs.len()];
+                for op in self.container.tx_log.iter().map(|op| op.op()) {
+                    if op.tx_id != tx_id {
+                        continue;
+                    }
+                    let log = &mut receipt.logs[op.log_id - 1];
+                    match op.field {
+                        TxLogField::Address => log.address = op.value.to_address(),
+                        TxLogField::Topic => log.topics.push(H256(op.value.to_be_bytes())),
+                        TxLogField::Data => data[op.log_id - 1].push((op.index, op.value.byte(0))),
+                    }
+                }
+                for (log, mut bytes) in receipt.logs.iter_mut().zip(data) {
+                    bytes.sort_unstable();
+                    log.data = bytes
+                        .into_iter()
+                        .map(|(_, byte)| byte)
+                        .collect::<Vec<_>>()
+                        .into();
+                }
+                receipt
+            })
+            .collect()
+    }
+
+    /// Return the header of the block, with the uncles hash of its uncles,
+    /// the transactions and receipts roots and the logs bloom of its
+    /// transactions and receipts.
+    pub fn header(&self) -> BlockHeader {
+        let chain_id = self.chain_id.as_u64();
+        let tx_rlps: Vec<Vec<u8>> = self
+            .txs
+            .iter()
+            .map(|tx| tx.tx.rlp_signed(chain_id))
+            .collect();
+        let receipts = self.receipts();
+        let receipt_rlps: Vec<Vec<u8>> = receipts.iter().map(Receipt::rlp).collect();
+        BlockHeader {
+            uncles_hash: BlockHeader::uncles_hash(&self.uncles),
+            transactions_root: ordered_trie_root(&tx_rlps),
+            receipts_root: ordered_trie_root(&receipt_rlps),
+            logs_bloom: Receipt::block_logs_bloom(&receipts),
+            ..BlockHeader::from(&self.eth_block)
+        }
+    }
 }
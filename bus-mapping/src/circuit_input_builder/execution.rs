@@ -2,9 +2,9 @@
 
 use crate::{
     circuit_input_builder::CallContext, error::ExecError, exec_trace::OperationRef,
-    operation::RWCounter,
+    operation::RWCounter, precompile::PrecompileCalls,
 };
-use eth_types::{evm_types::OpcodeId, GethExecStep, Word, H256};
+use eth_types::{evm_types::OpcodeId, Address, GethExecStep, Word, H256};
 use gadgets::impl_expr;
 use halo2_proofs::plonk::Expression;
 use strum_macros::EnumIter;
@@ -128,8 +128,19 @@ pub enum ExecState {
     BeginTx,
     /// Virtual step End Tx
     EndTx,
+    /// Virtual step Destruct Account, which follows EndTx once for every
+    /// account self destructed in the tx
+    DestructAccount,
+    /// Virtual step Withdrawal, which follows the last tx once for every
+    /// withdrawal (EIP-4895) of the block
+    Withdrawal,
+    /// Virtual step Block Reward, which credits the rewards of the miners of
+    /// the block and of its uncles before the End Block
+    BlockReward,
     /// Virtual step End Block
     EndBlock,
+    /// Virtual step Precompile call
+    Precompile(PrecompileCalls),
 }
 
 impl Default for ExecState {
@@ -190,10 +201,17 @@ pub enum CopyDataType {
     TxCalldata,
     /// When the destination for the copy event is tx's log.
     TxLog,
-    /// When the destination rows are not directly for copying but for a special
-    /// scenario where we wish to accumulate the value (RLC) over all rows.
-    /// This is used for Copy Lookup from SHA3 opcode verification.
+    /// When the source/destination rows are not directly for copying but for a
+    /// special scenario where we wish to accumulate the value (RLC) over all
+    /// rows. This is used for Copy Lookup from SHA3 opcode verification, and
+    /// to move the input and output of precompile calls.
     RlcAcc,
+    /// When the destination is the access list of the tx, to which the
+    /// addresses of the tx access list (EIP-2930) are added.
+    AccessListAddresses,
+    /// When the destination is the access list of the tx, to which the
+    /// storage keys of the tx access list (EIP-2930) are added.
+    AccessListStorageKeys,
 }
 
 impl From<CopyDataType> for usize {
@@ -256,9 +274,32 @@ pub struct CopyEvent {
     pub rw_counter_start: RWCounter,
     /// Represents the list of (bytes, is_code) copied during this copy event
     pub bytes: Vec<(u8, bool)>,
+    /// Represents the list of (address, storage_key, is_warm_prev) of the
+    /// entries added to the access list in case of the destination being
+    /// AccessListAddresses or AccessListStorageKeys, which take the place of
+    /// the bytes.
+    pub access_list: Vec<(Address, Word, bool)>,
 }
 
 impl CopyEvent {
+    /// Number of values copied by this copy event, which are bytes or access
+    /// list entries
+    pub fn copy_length(&self) -> usize {
+        if self.is_access_list() {
+            self.access_list.len()
+        } else {
+            self.bytes.len()
+        }
+    }
+
+    /// Whether the copy event adds entries to the access list of the tx
+    pub fn is_access_list(&self) -> bool {
+        matches!(
+            self.dst_type,
+            CopyDataType::AccessListAddresses | CopyDataType::AccessListStorageKeys
+        )
+    }
+
     /// rw counter at step index
     pub fn rw_counter(&self, step_index: usize) -> u64 {
         u64::try_from(self.rw_counter_start.0).unwrap() + self.rw_counter_increase(step_index)
@@ -266,29 +307,60 @@ impl CopyEvent {
 
     /// rw counter increase left at step index
     pub fn rw_counter_increase_left(&self, step_index: usize) -> u64 {
-        self.rw_counter(self.bytes.len() * 2) - self.rw_counter(step_index)
+        if self.is_memory_copy() {
+            // The rw counter of a memory copy jumps back from a write step to
+            // the next read step, so count the steps left instead.
+            return u64::try_from(self.copy_length() * 2 - step_index).unwrap();
+        }
+        self.rw_counter(self.copy_length() * 2) - self.rw_counter(step_index)
     }
 
     /// Number of rw operations performed by this copy event
     pub fn rw_counter_delta(&self) -> u64 {
-        self.rw_counter_increase(self.bytes.len() * 2)
+        self.rw_counter_increase(self.copy_length() * 2)
+    }
+
+    /// Whether the copy event copies memory within the same call, as MCOPY
+    /// does. The source and destination ranges may overlap, so all the bytes
+    /// are read before any of them is written.
+    pub fn is_memory_copy(&self) -> bool {
+        self.src_type == CopyDataType::Memory
+            && self.dst_type == CopyDataType::Memory
+            && self.src_id == self.dst_id
     }
 
     // increase in rw counter from the start of the copy event to step index
     fn rw_counter_increase(&self, step_index: usize) -> u64 {
+        if self.is_memory_copy() {
+            let length = u64::try_from(self.bytes.len()).unwrap();
+            let step_index = u64::try_from(step_index).unwrap();
+            return if step_index >= length * 2 {
+                length * 2
+            } else if step_index % 2 == 0 {
+                step_index / 2
+            } else {
+                length + step_index / 2
+            };
+        }
         let source_rw_increase = match self.src_type {
-            CopyDataType::Bytecode | CopyDataType::TxCalldata => 0,
+            CopyDataType::Bytecode | CopyDataType::TxCalldata | CopyDataType::RlcAcc => 0,
             CopyDataType::Memory => std::cmp::min(
                 u64::try_from(step_index + 1).unwrap() / 2,
                 self.src_addr_end
                     .checked_sub(self.src_addr)
                     .unwrap_or_default(),
             ),
-            CopyDataType::RlcAcc | CopyDataType::TxLog | CopyDataType::Padding => unreachable!(),
+            CopyDataType::TxLog
+            | CopyDataType::Padding
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => unreachable!(),
         };
         let destination_rw_increase = match self.dst_type {
             CopyDataType::RlcAcc | CopyDataType::Bytecode => 0,
-            CopyDataType::TxLog | CopyDataType::Memory => u64::try_from(step_index).unwrap() / 2,
+            CopyDataType::TxLog
+            | CopyDataType::Memory
+            | CopyDataType::AccessListAddresses
+            | CopyDataType::AccessListStorageKeys => u64::try_from(step_index).unwrap() / 2,
             CopyDataType::TxCalldata | CopyDataType::Padding => unreachable!(),
         };
         source_rw_increase + destination_rw_increase
@@ -13,9 +13,10 @@ use crate::{
     exec_trace::OperationRef,
     operation::{
         AccountField, AccountOp, CallContextField, CallContextOp, MemoryOp, Op, OpEnum, Operation,
-        StackOp, Target, TxAccessListAccountOp, TxLogField, TxLogOp, TxReceiptField, TxReceiptOp,
-        RW,
+        StackOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxAccountFlagField,
+        TxLogField, TxLogOp, TxReceiptField, TxReceiptOp, RW,
     },
+    precompile::is_precompiled,
     state_db::{CodeDB, StateDB},
     Error,
 };
@@ -96,6 +97,22 @@ impl<'a> CircuitInputStateRef<'a> {
         }
     }
 
+    /// Create a new DestructAccount step
+    pub fn new_destruct_account_step(&self) -> ExecStep {
+        let prev_step = self
+            .tx
+            .steps()
+            .last()
+            .expect("steps should have at least one EndTx step");
+        ExecStep {
+            exec_state: ExecState::DestructAccount,
+            gas_left: prev_step.gas_left,
+            rwc: self.block_ctx.rwc,
+            log_id: prev_step.log_id,
+            ..Default::default()
+        }
+    }
+
     /// Push an [`Operation`](crate::operation::Operation) into the
     /// [`OperationContainer`](crate::operation::OperationContainer) with the
     /// next [`RWCounter`](crate::operation::RWCounter) and then adds a
@@ -464,6 +481,35 @@ impl<'a> CircuitInputStateRef<'a> {
         Ok(())
     }
 
+    /// Push a write type [`TxAccessListAccountStorageOp`] into the
+    /// [`OperationContainer`](crate::operation::OperationContainer) with the
+    /// next [`RWCounter`](crate::operation::RWCounter), and then
+    /// adds a reference to the stored operation ([`OperationRef`]) inside
+    /// the bus-mapping instance of the current [`ExecStep`].  Then increase
+    /// the `block_ctx` [`RWCounter`](crate::operation::RWCounter)  by one.
+    pub fn tx_accesslist_account_storage_write(
+        &mut self,
+        step: &mut ExecStep,
+        tx_id: usize,
+        address: Address,
+        key: Word,
+        is_warm: bool,
+        is_warm_prev: bool,
+    ) -> Result<(), Error> {
+        self.push_op(
+            step,
+            RW::WRITE,
+            TxAccessListAccountStorageOp {
+                tx_id,
+                address,
+                key,
+                is_warm,
+                is_warm_prev,
+            },
+        );
+        Ok(())
+    }
+
     /// Push 2 reversible [`AccountOp`] to update `sender` and `receiver`'s
     /// balance by `value`. If `fee` is existing (not None), also need to push 1
     /// non-reversible [`AccountOp`] to update `sender` balance by `fee`.
@@ -517,8 +563,10 @@ impl<'a> CircuitInputStateRef<'a> {
             sender_balance_prev,
             sender_balance
         );
-        // If receiver doesn't exist, create it
-        if (!receiver_exists && !value.is_zero()) || must_create {
+        // If receiver doesn't exist, create it.  An existing receiver already
+        // has the empty code hash when it must be created, since a non-empty
+        // code hash would be an address collision.
+        if !receiver_exists && (!value.is_zero() || must_create) {
             self.push_op_reversible(
                 step,
                 AccountOp {
@@ -693,7 +741,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Check if address is a precompiled or not.
     pub fn is_precompiled(&self, address: &Address) -> bool {
-        address.0[0..19] == [0u8; 19] && (1..=9).contains(&address.0[19])
+        is_precompiled(address, self.block.circuits_params.hardfork)
     }
 
     /// Parse [`Call`] from a *CALL*/CREATE* step.
@@ -804,6 +852,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TransientStorage, idx) => {
+                let operation = &self.block.container.transient_storage[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TransientStorage(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             OperationRef(Target::TxAccessListAccount, idx) => {
                 let operation = &self.block.container.tx_access_list_account[*idx];
                 if operation.rw().is_write() && operation.reversible() {
@@ -820,6 +876,14 @@ impl<'a> CircuitInputStateRef<'a> {
                     None
                 }
             }
+            OperationRef(Target::TxAccountFlag, idx) => {
+                let operation = &self.block.container.tx_account_flag[*idx];
+                if operation.rw().is_write() && operation.reversible() {
+                    Some(OpEnum::TxAccountFlag(operation.op().reverse()))
+                } else {
+                    None
+                }
+            }
             OperationRef(Target::TxRefund, idx) => {
                 let operation = &self.block.container.tx_refund[*idx];
                 if operation.rw().is_write() && operation.reversible() {
@@ -846,6 +910,10 @@ impl<'a> CircuitInputStateRef<'a> {
             OpEnum::Storage(op) => {
                 self.sdb.set_storage(&op.address, &op.key, &op.value);
             }
+            OpEnum::TransientStorage(op) => {
+                self.sdb
+                    .set_transient_storage(&op.address, &op.key, &op.value);
+            }
             OpEnum::TxAccessListAccount(op) => {
                 if !op.is_warm_prev && op.is_warm {
                     self.sdb.add_account_to_access_list(op.address);
@@ -864,6 +932,12 @@ impl<'a> CircuitInputStateRef<'a> {
                         .remove_account_storage_from_access_list(&(op.address, op.key));
                 }
             }
+            OpEnum::TxAccountFlag(op) => match op.field {
+                TxAccountFlagField::Created => self.sdb.set_account_created(op.address, op.value),
+                TxAccountFlagField::Destructed => {
+                    self.sdb.set_account_destructed(op.address, op.value)
+                }
+            },
             OpEnum::Account(op) => self.check_update_sdb_account(RW::WRITE, op),
             OpEnum::TxRefund(op) => {
                 self.sdb.set_refund(op.value);
@@ -916,12 +990,16 @@ impl<'a> CircuitInputStateRef<'a> {
             self.handle_restore_context(exec_step, geth_steps)?;
         }
 
-        let step = &geth_steps[0];
+        let is_precompile = matches!(exec_step.exec_state, ExecState::Precompile(_));
         // handle return_data
         let (return_data_offset, return_data_length) = {
             if !self.call()?.is_root {
-                let (offset, length) = match step.op {
+                let (offset, length) = match geth_steps[0].op {
+                    // The output of a precompile call has already been set as the
+                    // caller's return data.
+                    _ if is_precompile => (0, self.caller_ctx()?.return_data.len()),
                     OpcodeId::RETURN | OpcodeId::REVERT => {
+                        let step = &geth_steps[0];
                         let offset = step.stack.nth_last(0)?.as_usize();
                         let length = step.stack.nth_last(1)?.as_usize();
                         // At the moment it conflicts with `call_ctx` and `caller_ctx`.
@@ -950,10 +1028,11 @@ impl<'a> CircuitInputStateRef<'a> {
         let call = self.call()?.clone();
         let call_ctx = self.call_ctx()?;
         let call_success_create: bool =
-            call.is_create() && call.is_success && step.op == OpcodeId::RETURN;
+            call.is_create() && call.is_success && geth_steps[0].op == OpcodeId::RETURN;
 
         // Store deployed code if it's a successful create
         if call_success_create {
+            let step = &geth_steps[0];
             let offset = step.stack.nth_last(0)?;
             let length = step.stack.nth_last(1)?;
             let code = call_ctx
@@ -1005,10 +1084,14 @@ impl<'a> CircuitInputStateRef<'a> {
         steps: &[GethExecStep],
     ) -> Result<(), Error> {
         let call = self.call()?.clone();
-        let geth_step = steps
-            .get(0)
-            .ok_or(Error::InternalError("invalid index 0"))?;
-        let is_return_revert = geth_step.op == OpcodeId::REVERT || geth_step.op == OpcodeId::RETURN;
+        let is_precompile = matches!(exec_step.exec_state, ExecState::Precompile(_));
+        // The root call to a precompile has no geth steps at all.
+        let is_return_revert = !is_precompile && {
+            let geth_step = steps
+                .get(0)
+                .ok_or(Error::InternalError("invalid index 0"))?;
+            geth_step.op == OpcodeId::REVERT || geth_step.op == OpcodeId::RETURN
+        };
 
         if !is_return_revert && !call.is_success {
             // add call failure ops for exception cases
@@ -1036,6 +1119,9 @@ impl<'a> CircuitInputStateRef<'a> {
         }
 
         let caller = self.caller()?.clone();
+        let geth_step = steps
+            .get(0)
+            .ok_or(Error::InternalError("invalid index 0"))?;
         let geth_step_next = steps
             .get(1)
             .ok_or(Error::InternalError("invalid index 1"))?;
@@ -1047,6 +1133,7 @@ impl<'a> CircuitInputStateRef<'a> {
         );
 
         let [last_callee_return_data_offset, last_callee_return_data_length] = match geth_step.op {
+            _ if is_precompile => [Word::zero(), self.caller_ctx()?.return_data.len().into()],
             OpcodeId::STOP => [Word::zero(); 2],
             OpcodeId::REVERT | OpcodeId::RETURN => {
                 let offset = geth_step.stack.nth_last(0)?;
@@ -1063,7 +1150,8 @@ impl<'a> CircuitInputStateRef<'a> {
         };
 
         let curr_memory_word_size = (exec_step.memory_size as u64) / 32;
-        let next_memory_word_size = if !last_callee_return_data_length.is_zero() {
+        // A precompile returns its output without expanding the callee memory.
+        let next_memory_word_size = if !is_precompile && !last_callee_return_data_length.is_zero() {
             std::cmp::max(
                 (last_callee_return_data_offset + last_callee_return_data_length + 31).as_u64()
                     / 32,
@@ -1080,9 +1168,19 @@ impl<'a> CircuitInputStateRef<'a> {
         } else {
             0
         };
-        let gas_refund = geth_step.gas - memory_expansion_gas_cost - code_deposit_cost;
+        // SELFDESTRUCT pays its own gas cost before returning the rest to the caller.
+        let selfdestruct_gas_cost = if geth_step.op == OpcodeId::SELFDESTRUCT {
+            exec_step.gas_cost
+        } else {
+            0
+        };
+        let gas_refund = if is_precompile {
+            exec_step.gas_left - exec_step.gas_cost
+        } else {
+            geth_step.gas - memory_expansion_gas_cost - code_deposit_cost - selfdestruct_gas_cost
+        };
 
-        let caller_gas_left = if is_return_revert || call.is_success {
+        let caller_gas_left = if is_return_revert || call.is_success || is_precompile {
             geth_step_next.gas - gas_refund
         } else {
             geth_step_next.gas
@@ -1141,7 +1239,7 @@ impl<'a> CircuitInputStateRef<'a> {
 
     /// Push a copy event to the state.
     pub fn push_copy(&mut self, step: &mut ExecStep, event: CopyEvent) {
-        step.copy_rw_counter_delta = event.rw_counter_delta();
+        step.copy_rw_counter_delta += event.rw_counter_delta();
         self.block.add_copy_event(event);
     }
 
@@ -1150,16 +1248,42 @@ impl<'a> CircuitInputStateRef<'a> {
         self.block.add_exp_event(event)
     }
 
+    /// Returns whether a CREATE or CREATE2 step fails because its init code
+    /// is larger than the limit of EIP-3860.  Geth reports it as a gas uint64
+    /// overflow, after checking that the memory expansion does not overflow.
+    fn is_init_code_too_large(&self, step: &GethExecStep) -> Result<bool, Error> {
+        // Largest memory size whose expansion gas cost fits in a u64.
+        const MAX_MEMORY_SIZE: u64 = 0x1FFFFFFFE0;
+
+        if !matches!(step.op, OpcodeId::CREATE | OpcodeId::CREATE2) {
+            return Ok(false);
+        }
+        let max_init_code_size = match self.block.circuits_params.hardfork.max_init_code_size() {
+            Some(max_init_code_size) => max_init_code_size,
+            None => return Ok(false),
+        };
+        let offset = step.stack.nth_last(1)?;
+        let length = step.stack.nth_last(2)?;
+        let is_memory_in_range = offset
+            .checked_add(length)
+            .map_or(false, |end| end <= Word::from(MAX_MEMORY_SIZE));
+
+        Ok(is_memory_in_range && length > Word::from(max_init_code_size))
+    }
+
     pub(crate) fn get_step_err(
         &self,
         step: &GethExecStep,
         next_step: Option<&GethExecStep>,
     ) -> Result<Option<ExecError>, Error> {
         if let Some(error) = &step.error {
+            if self.is_init_code_too_large(step)? {
+                return Ok(Some(ExecError::MaxInitCodeSizeExceeded));
+            }
             return Ok(Some(get_step_reported_error(&step.op, error)));
         }
 
-        if matches!(step.op, OpcodeId::INVALID(_)) {
+        if !step.op.is_enabled(self.block.circuits_params.hardfork) {
             return Ok(Some(ExecError::InvalidOpcode));
         }
 
@@ -1208,6 +1332,7 @@ impl<'a> CircuitInputStateRef<'a> {
                     OpcodeId::RETURNDATACOPY => Some(ExecError::ReturnDataOutOfBounds),
                     // Break write protection (CALL with value will be handled below)
                     OpcodeId::SSTORE
+                    | OpcodeId::TSTORE
                     | OpcodeId::CREATE
                     | OpcodeId::CREATE2
                     | OpcodeId::SELFDESTRUCT
@@ -6,6 +6,7 @@ use crate::{
         GETH_ERR_GAS_UINT_OVERFLOW, GETH_ERR_OUT_OF_GAS, GETH_ERR_STACK_OVERFLOW,
         GETH_ERR_STACK_UNDERFLOW,
     },
+    operation::AccountOp,
     state_db::Account,
 };
 use eth_types::{
@@ -17,7 +18,7 @@ use eth_types::{
 use lazy_static::lazy_static;
 use mock::{
     test_ctx::{helpers::*, LoggerConfig, TestContext},
-    MOCK_COINBASE,
+    MOCK_ACCOUNTS, MOCK_COINBASE, MOCK_WITHDRAWALS,
 };
 use pretty_assertions::assert_eq;
 use std::collections::HashSet;
@@ -2210,3 +2211,194 @@ fn test_gen_access_trace_create_push_call_stack() {
         }
     )
 }
+
+#[test]
+fn test_withdrawals_in_end_block() {
+    let block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(bytecode! { STOP }),
+        tx_from_1_to_0,
+        |block, _tx| {
+            block
+                .hardfork(Hardfork::Shanghai)
+                .withdrawals(MOCK_WITHDRAWALS.clone())
+        },
+    )
+    .unwrap()
+    .into();
+
+    let access_set = get_state_accesses(&block.eth_block, &block.geth_traces).unwrap();
+    for withdrawal in MOCK_WITHDRAWALS.iter() {
+        assert!(access_set.state.contains_key(&withdrawal.address));
+    }
+
+    let mut builder = crate::mock::BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            hardfork: Hardfork::Shanghai,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    // Every withdrawal increases the balance of its recipient in a Withdrawal
+    // step, before the EndBlock.
+    let withdrawal_steps = &builder.block.block_steps.withdrawals;
+    assert_eq!(withdrawal_steps.len(), MOCK_WITHDRAWALS.len());
+    let balance_prevs = [
+        builder.sdb.get_account(&MOCK_ACCOUNTS[0]).1.balance - MOCK_WITHDRAWALS[0].amount_wei(),
+        Word::zero(),
+    ];
+    for (((log_id, step), withdrawal), balance_prev) in withdrawal_steps
+        .iter()
+        .enumerate()
+        .zip(MOCK_WITHDRAWALS.iter())
+        .zip(balance_prevs)
+    {
+        assert_eq!(step.log_id, log_id);
+        assert_eq!(step.bus_mapping_instance.len(), 1);
+        let operation = &builder.block.container.account[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &AccountOp::new(
+                    withdrawal.address,
+                    AccountField::Balance,
+                    balance_prev + withdrawal.amount_wei(),
+                    balance_prev,
+                )
+            )
+        );
+    }
+    assert_eq!(
+        builder
+            .sdb
+            .get_account(&MOCK_WITHDRAWALS[1].address)
+            .1
+            .balance,
+        MOCK_WITHDRAWALS[1].amount_wei()
+    );
+    assert_eq!(
+        builder.block.block_steps.end_block_last.log_id,
+        MOCK_WITHDRAWALS.len()
+    );
+}
+
+#[test]
+fn test_block_rewards_in_end_block() {
+    let uncles = vec![
+        geth_types::BlockHeader {
+            number: 0xcafd,
+            coinbase: *ADDR_A,
+            ..Default::default()
+        },
+        geth_types::BlockHeader {
+            number: 0xcafc,
+            coinbase: *ADDR_B,
+            ..Default::default()
+        },
+    ];
+    let block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(bytecode! { STOP }),
+        tx_from_1_to_0,
+        |block, _tx| {
+            block
+                .number(0xcafeu64)
+                .hardfork(Hardfork::London)
+                .uncle_headers(uncles.clone())
+        },
+    )
+    .unwrap()
+    .into();
+
+    let access_set = get_state_accesses(&block.eth_block, &block.geth_traces).unwrap();
+    for uncle in uncles.iter() {
+        assert!(access_set.state.contains_key(&uncle.coinbase));
+    }
+
+    let mut builder = crate::mock::BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            hardfork: Hardfork::London,
+            block_reward: BlockReward::Ethash,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    // The BlockReward step credits the reward of the coinbase, which includes
+    // the nephew rewards, and then the uncle rewards.
+    let block_reward = Word::from(2_000_000_000_000_000_000u64);
+    let coinbase_balance = builder.sdb.get_account(&MOCK_COINBASE).1.balance;
+    let rewards = [
+        (
+            *MOCK_COINBASE,
+            coinbase_balance,
+            block_reward + block_reward / 32 * 2,
+        ),
+        (*ADDR_A, block_reward * 7 / 8, block_reward * 7 / 8),
+        (*ADDR_B, block_reward * 6 / 8, block_reward * 6 / 8),
+    ];
+    let block_reward_step = &builder.block.block_steps.block_reward;
+    assert_eq!(block_reward_step.bus_mapping_instance.len(), rewards.len());
+    for (op_ref, (address, balance, reward)) in
+        block_reward_step.bus_mapping_instance.iter().zip(rewards)
+    {
+        let operation = &builder.block.container.account[op_ref.as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::WRITE,
+                &AccountOp::new(address, AccountField::Balance, balance, balance - reward)
+            )
+        );
+    }
+}
+
+#[test]
+fn test_no_block_rewards_in_end_block() {
+    let block: GethData = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(bytecode! { STOP }),
+        tx_from_1_to_0,
+        |block, _tx| block.hardfork(Hardfork::London),
+    )
+    .unwrap()
+    .into();
+
+    // The proof of authority networks don't reward the signers of the blocks,
+    // so the BlockReward step has no ops.
+    let mut builder = crate::mock::BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            hardfork: Hardfork::London,
+            block_reward: BlockReward::NoReward,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+
+    assert!(builder
+        .block
+        .block_steps
+        .block_reward
+        .bus_mapping_instance
+        .is_empty());
+    assert!(builder
+        .block
+        .container
+        .account
+        .iter()
+        .all(|operation| { builder.block.block_steps.block_reward.rwc.0 > operation.rwc().0 }));
+}
@@ -27,6 +27,8 @@ pub enum Error {
     AddressNotFound(Address),
     /// Code not found in the CodeDB
     CodeNotFound(H256),
+    /// Proof of an account or of its storage not matching the state root
+    InvalidStateProof(Address),
     /// Unable to figure out error at a [`GethExecStep`]
     UnexpectedExecStepError(&'static str, Box<GethExecStep>),
     /// Invalid [`eth_types::GethExecTrace`] due to an invalid/unexpected value
@@ -74,7 +76,7 @@ pub enum OogError {
     /// expansion gas cost
     DynamicMemoryExpansion,
     /// Out of Gas for CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY,
-    /// which copy a specified chunk of memory
+    /// MCOPY, which copy a specified chunk of memory
     MemoryCopy,
     /// Out of Gas for BALANCE, EXTCODESIZE, EXTCODEHASH, which possibly touch
     /// an extra account
@@ -140,7 +142,7 @@ pub enum ExecError {
     StackUnderflow,
     /// Out of Gas
     OutOfGas(OogError),
-    /// For SSTORE, LOG0, LOG1, LOG2, LOG3, LOG4, CREATE, CALL, CREATE2,
+    /// For SSTORE, TSTORE, LOG0, LOG1, LOG2, LOG3, LOG4, CREATE, CALL, CREATE2,
     /// SELFDESTRUCT
     WriteProtection,
     /// For CALL, CALLCODE, DELEGATECALL, STATICCALL, CREATE, CREATE2
@@ -162,6 +164,9 @@ pub enum ExecError {
     MaxCodeSizeExceeded,
     /// For CREATE, CREATE2
     NonceUintOverflow(NonceUintOverflowError),
+    /// For CREATE, CREATE2, when the init code is larger than the limit of
+    /// EIP-3860
+    MaxInitCodeSizeExceeded,
 }
 
 // TODO: Move to impl block.
@@ -178,7 +183,8 @@ pub(crate) fn get_step_reported_error(op: &OpcodeId, error: &str) -> ExecError {
             OpcodeId::CALLDATACOPY
             | OpcodeId::CODECOPY
             | OpcodeId::EXTCODECOPY
-            | OpcodeId::RETURNDATACOPY => OogError::MemoryCopy,
+            | OpcodeId::RETURNDATACOPY
+            | OpcodeId::MCOPY => OogError::MemoryCopy,
             OpcodeId::BALANCE | OpcodeId::EXTCODESIZE | OpcodeId::EXTCODEHASH => {
                 OogError::AccountAccess
             }
@@ -1,14 +1,16 @@
 use super::TxExecSteps;
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, ExecState, ExecStep},
-    operation::{AccountField, AccountOp, CallContextField, TxReceiptField, TxRefundOp, RW},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+    },
+    operation::{
+        AccountField, AccountOp, CallContextField, RWCounter, StorageOp, TxAccountFlagField,
+        TxAccountFlagOp, TxReceiptField, TxRefundOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{
-    evm_types::{GasCost, MAX_REFUND_QUOTIENT_OF_GAS_USED},
-    evm_unimplemented, ToWord, Word,
-};
+use eth_types::{evm_types::GasCost, Address, ToWord, Word, H256};
 use ethers_core::utils::get_contract_address;
 
 #[derive(Clone, Copy, Debug)]
@@ -22,6 +24,7 @@ impl TxExecSteps for BeginEndTx {
         match execution_step {
             ExecState::BeginTx => gen_begin_tx_steps(state),
             ExecState::EndTx => gen_end_tx_steps(state),
+            ExecState::DestructAccount => gen_destruct_account_steps(state),
             _ => {
                 unreachable!()
             }
@@ -59,8 +62,24 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         nonce_prev.into(),
     )?;
 
-    // Add caller, callee and coinbase (for EIP-3651) to access list.
-    for address in [call.caller_address, call.address, state.block.coinbase] {
+    // Add caller and callee to access list.
+    for address in [call.caller_address, call.address] {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(address);
+        state.tx_accesslist_account_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            address,
+            true,
+            is_warm_prev,
+        )?;
+    }
+
+    // Add the addresses and then the storage keys of the tx access list
+    // (EIP-2930) to access list, which are copied from the tx table.
+    let (addresses, storage_keys) = state.tx.tx.access_list_entries();
+    let rw_counter_start = state.block_ctx.rwc;
+    let mut access_list = Vec::with_capacity(addresses.len());
+    for address in addresses {
         let is_warm_prev = !state.sdb.add_account_to_access_list(address);
         state.tx_accesslist_account_write(
             &mut exec_step,
@@ -69,11 +88,54 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
             true,
             is_warm_prev,
         )?;
+        access_list.push((address, Word::zero(), is_warm_prev));
+    }
+    push_access_list_copy(
+        state,
+        &mut exec_step,
+        CopyDataType::AccessListAddresses,
+        rw_counter_start,
+        access_list,
+    );
+    let rw_counter_start = state.block_ctx.rwc;
+    let mut access_list = Vec::with_capacity(storage_keys.len());
+    for (address, key) in storage_keys {
+        let is_warm_prev = !state.sdb.add_account_storage_to_access_list((address, key));
+        state.tx_accesslist_account_storage_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            address,
+            key,
+            true,
+            is_warm_prev,
+        )?;
+        access_list.push((address, key, is_warm_prev));
+    }
+    push_access_list_copy(
+        state,
+        &mut exec_step,
+        CopyDataType::AccessListStorageKeys,
+        rw_counter_start,
+        access_list,
+    );
+
+    let hardfork = state.block.circuits_params.hardfork;
+
+    // Add coinbase (for EIP-3651) to access list.
+    if hardfork.is_coinbase_warm() {
+        let is_warm_prev = !state.sdb.add_account_to_access_list(state.block.coinbase);
+        state.tx_accesslist_account_write(
+            &mut exec_step,
+            state.tx_ctx.id(),
+            state.block.coinbase,
+            true,
+            is_warm_prev,
+        )?;
     }
 
     let init_code_gas_cost = if state.tx.is_create() {
         // Calculate gas cost of init code for EIP-3860.
-        (state.tx.tx.call_data.len() as u64 + 31) / 32 * eth_types::evm_types::INIT_CODE_WORD_GAS
+        (state.tx.tx.call_data.len() as u64 + 31) / 32 * hardfork.init_code_word_gas()
     } else {
         0
     };
@@ -83,6 +145,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     } else {
         GasCost::TX
     } + state.tx.tx.call_data_gas_cost()
+        + state.tx.tx.access_list_gas_cost()
         + init_code_gas_cost;
     exec_step.gas_cost = intrinsic_gas_cost;
 
@@ -97,7 +160,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     } else {
         (Word::zero(), true)
     };
-    if !state.is_precompiled(&call.address) && !call.is_create() {
+    if !call.is_create() {
         state.account_read(
             &mut exec_step,
             call.address,
@@ -106,7 +169,9 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         );
     }
 
-    // Transfer with fee
+    // Transfer with fee, which includes the fee of the blob gas (EIP-4844)
+    let fee =
+        state.tx.tx.gas_price * state.tx.gas() + state.block.blob_base_fee * state.tx.tx.blob_gas();
     state.transfer_with_fee(
         &mut exec_step,
         call.caller_address,
@@ -114,7 +179,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         callee_exists,
         call.is_create(),
         call.value,
-        Some(state.tx.tx.gas_price * state.tx.gas()),
+        Some(fee),
     )?;
 
     // In case of contract creation we wish to verify the correctness of the
@@ -135,7 +200,7 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
         });
     }
 
-    // There are 4 branches from here.
+    // There are 3 branches from here.
     match (
         call.is_create(),
         state.is_precompiled(&call.address),
@@ -152,6 +217,16 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
                     value_prev: 0.into(),
                 },
             )?;
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccountFlagOp {
+                    tx_id: state.tx_ctx.id(),
+                    address: call.address,
+                    field: TxAccountFlagField::Created,
+                    value: true,
+                    value_prev: false,
+                },
+            )?;
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -183,18 +258,19 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
             }
             Ok(exec_step)
         }
-        // 2. Call to precompiled.
-        (_, true, _) => {
-            evm_unimplemented!("Call to precompiled is left unimplemented");
-            Ok(exec_step)
-        }
-        (_, _, is_empty_code_hash) => {
-            // 3. Call to account with empty code.
-            if is_empty_code_hash {
+        (_, is_precompile, is_empty_code_hash) => {
+            // 2. Call to account with empty code.
+            if is_empty_code_hash && !is_precompile {
                 return Ok(exec_step);
             }
 
-            // 4. Call to account with non-empty code.
+            // 3. Call to precompiled, which is executed in the next step, or
+            // call to account with non-empty code.
+            if is_precompile && !callee_exists {
+                // The precompile step runs with the callee code hash read
+                // above, which is 0 when the account doesn't exist.
+                state.call_mut()?.code_hash = H256::zero();
+            }
             for (field, value) in [
                 (CallContextField::Depth, call.depth.into()),
                 (
@@ -227,6 +303,37 @@ fn gen_begin_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Erro
     }
 }
 
+/// Push the copy event of the entries of the tx access list added to the
+/// access list, which are copied from the RLC of the entries in the tx table.
+fn push_access_list_copy(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    dst_type: CopyDataType,
+    rw_counter_start: RWCounter,
+    access_list: Vec<(Address, Word, bool)>,
+) {
+    if access_list.is_empty() {
+        return;
+    }
+    let tx_id = NumberOrHash::Number(state.tx_ctx.id());
+    state.push_copy(
+        exec_step,
+        CopyEvent {
+            src_addr: 0,
+            src_addr_end: access_list.len() as u64,
+            src_type: CopyDataType::RlcAcc,
+            src_id: tx_id.clone(),
+            dst_addr: 0,
+            dst_type,
+            dst_id: tx_id,
+            log_id: None,
+            rw_counter_start,
+            bytes: vec![],
+            access_list,
+        },
+    );
+}
+
 fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
     let mut exec_step = state.new_end_tx_step();
     let call = state.tx.calls()[0].clone();
@@ -255,8 +362,12 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         },
     );
 
-    let effective_refund =
-        refund.min((state.tx.gas() - exec_step.gas_left) / MAX_REFUND_QUOTIENT_OF_GAS_USED as u64);
+    let max_refund_quotient = state
+        .block
+        .circuits_params
+        .hardfork
+        .max_refund_quotient_of_gas_used();
+    let effective_refund = refund.min((state.tx.gas() - exec_step.gas_left) / max_refund_quotient);
     let (found, caller_account) = state.sdb.get_account(&call.caller_address);
     if !found {
         return Err(Error::AccountNotFound(call.caller_address));
@@ -322,7 +433,71 @@ fn gen_end_tx_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error>
         state.block_ctx.cumulative_gas_used,
     )?;
 
-    if !state.tx_ctx.is_last_tx() {
+    // The id of the next tx is written by the last DestructAccount step
+    // instead when accounts were self destructed.
+    if !state.tx_ctx.is_last_tx() && state.sdb.destructed_accounts().is_empty() {
+        state.call_context_write(
+            &mut exec_step,
+            state.block_ctx.rwc.0 + 1,
+            CallContextField::TxId,
+            (state.tx_ctx.id() + 1).into(),
+        );
+    }
+
+    Ok(exec_step)
+}
+
+/// Delete the account with the smallest address among the ones self
+/// destructed in the tx.
+fn gen_destruct_account_steps(state: &mut CircuitInputStateRef) -> Result<ExecStep, Error> {
+    let mut exec_step = state.new_destruct_account_step();
+    let call = state.tx.calls()[0].clone();
+    let destructed_accounts = state.sdb.destructed_accounts();
+    let address = destructed_accounts[0];
+
+    state.call_context_read(
+        &mut exec_step,
+        call.call_id,
+        CallContextField::TxId,
+        state.tx_ctx.id().into(),
+    );
+
+    state.push_op(
+        &mut exec_step,
+        RW::WRITE,
+        TxAccountFlagOp {
+            tx_id: state.tx_ctx.id(),
+            address,
+            field: TxAccountFlagField::Destructed,
+            value: false,
+            value_prev: true,
+        },
+    );
+    state.sdb.set_account_destructed(address, false);
+
+    // Clear the code hash first, so that the account isn't seen as an empty
+    // one while its balance and nonce are cleared.
+    let (_, account) = state.sdb.get_account(&address);
+    let code_hash_prev = if account.is_empty() {
+        Word::zero()
+    } else {
+        account.code_hash.to_word()
+    };
+    for (field, value_prev) in [
+        (AccountField::CodeHash, code_hash_prev),
+        (AccountField::Balance, account.balance),
+        (AccountField::Nonce, account.nonce.into()),
+    ] {
+        state.account_write(&mut exec_step, address, field, Word::zero(), value_prev)?;
+    }
+    state.push_op(
+        &mut exec_step,
+        RW::WRITE,
+        StorageOp::new_clear(address, state.tx_ctx.id()),
+    );
+    state.sdb.destruct_account(&address);
+
+    if !state.tx_ctx.is_last_tx() && destructed_accounts.len() == 1 {
         state.call_context_write(
             &mut exec_step,
             state.block_ctx.rwc.0 + 1,
@@ -0,0 +1,138 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::CallContextField,
+    Error,
+};
+use eth_types::{GethExecStep, Word};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::BLOBHASH`](crate::evm::OpcodeId::BLOBHASH)
+/// `OpcodeId`.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Blobhash;
+
+impl Opcode for Blobhash {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        // CallContext read of the TxId
+        state.call_context_read(
+            &mut exec_step,
+            state.call()?.call_id,
+            CallContextField::TxId,
+            Word::from(state.tx_ctx.id()),
+        );
+
+        // Stack read of the blob index
+        let index = geth_step.stack.last()?;
+        let stack_position = geth_step.stack.last_filled();
+        state.stack_read(&mut exec_step, stack_position, index)?;
+
+        // Stack write of the versioned hash of the blob, which is zero when
+        // the index is out of the blobs of the tx
+        let versioned_hash = geth_steps[1].stack.last()?;
+        state.stack_write(&mut exec_step, stack_position, versioned_hash)?;
+
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod blobhash_tests {
+    use crate::{
+        circuit_input_builder::{CircuitsParams, ExecState},
+        evm::OpcodeId,
+        mock::BlockData,
+        operation::{CallContextField, CallContextOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, StackAddress},
+        geth_types::GethData,
+        ToWord, Word,
+    };
+    use mock::{
+        test_ctx::{helpers::*, TestContext},
+        MOCK_BLOB_VERSIONED_HASHES,
+    };
+    use pretty_assertions::assert_eq;
+
+    fn test_ok(index: u64, expected: Word) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        // Get the execution steps from the external tracer
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            blob_tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64).hardfork(Hardfork::Cancun),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::BLOBHASH))
+            .unwrap();
+
+        let call_id = builder.block.txs()[0].calls()[0].call_id;
+        let operation =
+            &builder.block.container.call_context[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(
+            (operation.rw(), operation.op()),
+            (
+                RW::READ,
+                &CallContextOp {
+                    call_id,
+                    field: CallContextField::TxId,
+                    value: Word::one(),
+                }
+            )
+        );
+
+        assert_eq!(
+            [1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|operation| (operation.rw(), operation.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1023), Word::from(index))
+                ),
+                (RW::WRITE, &StackOp::new(1, StackAddress::from(1023), expected))
+            ]
+        );
+    }
+
+    #[test]
+    fn blobhash_opcode_impl() {
+        test_ok(1, MOCK_BLOB_VERSIONED_HASHES[1].to_word());
+    }
+
+    #[test]
+    fn blobhash_opcode_impl_out_of_range() {
+        test_ok(2, Word::zero());
+    }
+}
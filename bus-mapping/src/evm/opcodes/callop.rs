@@ -1,19 +1,18 @@
-use super::Opcode;
+use super::{precompiles, Opcode};
 use crate::{
     circuit_input_builder::{CallKind, CircuitInputStateRef, CodeSource, ExecStep},
     operation::{AccountField, CallContextField, TxAccessListAccountOp},
-    precompile::{execute_precompiled, is_precompiled},
+    precompile::{is_precompiled, PrecompileCalls},
     state_db::CodeDB,
     Error,
 };
 use eth_types::{
     evm_types::{
         gas_utils::{eip150_gas, memory_expansion_gas_cost},
-        GasCost,
+        GasCost, GAS_STIPEND_CALL_WITH_VALUE,
     },
-    GethExecStep, ToWord, Word,
+    GethExecStep, ToWord, Word, H256,
 };
-use std::cmp::min;
 
 /// Placeholder structure used to implement [`Opcode`] trait over it
 /// corresponding to the `OpcodeId::CALL`, `OpcodeId::CALLCODE`,
@@ -171,7 +170,7 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
 
         let code_address = call.code_address();
         let is_precompile = code_address
-            .map(|ref addr| is_precompiled(addr))
+            .map(|ref addr| is_precompiled(addr, state.block.circuits_params.hardfork))
             .unwrap_or(false);
         // TODO: What about transfer for CALLCODE?
         // Transfer value only for CALL opcode, is_precheck_ok = true.
@@ -202,7 +201,8 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         let has_value = !call.value.is_zero() && !call.is_delegatecall();
         let memory_expansion_gas_cost =
             memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
-        let gas_cost = if is_warm {
+        // Precompiled contracts are always warm (EIP-2929).
+        let gas_cost = if is_warm || is_precompile {
             GasCost::WARM_ACCESS
         } else {
             GasCost::COLD_ACCOUNT_ACCESS
@@ -219,63 +219,11 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
         let gas_specified = geth_step.stack.last()?;
         let callee_gas_left = eip150_gas(geth_step.gas - gas_cost, gas_specified);
 
-        // There are 4 branches from here.
+        // There are 3 branches from here.
         // add failure case for insufficient balance or error depth in the future.
         match (!is_precheck_ok, is_precompile, is_empty_code_hash) {
-            // 1. Call to precompiled.
-            (false, true, _) => {
-                assert!(call.is_success, "call to precompile should not fail");
-                let caller_ctx = state.caller_ctx_mut()?;
-                let code_address = code_address.unwrap();
-                let (result, contract_gas_cost) = execute_precompiled(
-                    &code_address,
-                    if args_length != 0 {
-                        &caller_ctx.memory.0[args_offset..args_offset + args_length]
-                    } else {
-                        &[]
-                    },
-                    callee_gas_left,
-                );
-                log::trace!(
-                    "precompile return data len {} gas {}",
-                    result.len(),
-                    contract_gas_cost
-                );
-                caller_ctx.return_data = result.clone();
-                let length = min(result.len(), ret_length);
-                if length != 0 {
-                    caller_ctx.memory.extend_at_least(ret_offset + length);
-                }
-                caller_ctx.memory.0[ret_offset..ret_offset + length]
-                    .copy_from_slice(&result[..length]);
-                for (field, value) in [
-                    (CallContextField::LastCalleeId, call.call_id.into()),
-                    (CallContextField::LastCalleeReturnDataOffset, 0.into()),
-                    (
-                        CallContextField::LastCalleeReturnDataLength,
-                        result.len().into(),
-                    ),
-                ] {
-                    state.call_context_write(&mut exec_step, current_call.call_id, field, value);
-                }
-
-                log::warn!("missing circuit part of precompile");
-                state.handle_return(&mut exec_step, geth_steps, false)?;
-
-                let real_cost = geth_steps[0].gas - geth_steps[1].gas;
-                if real_cost != exec_step.gas_cost {
-                    log::warn!(
-                        "precompile gas fixed from {} to {}, step {:?}",
-                        exec_step.gas_cost,
-                        real_cost,
-                        geth_steps[0]
-                    );
-                }
-                exec_step.gas_cost = real_cost;
-                Ok(vec![exec_step])
-            }
-            // 2. Call to account with empty code.
-            (false, _, true) => {
+            // 1. Call to account with empty code.
+            (false, false, true) => {
                 for (field, value) in [
                     (CallContextField::LastCalleeId, 0.into()),
                     (CallContextField::LastCalleeReturnDataOffset, 0.into()),
@@ -286,8 +234,8 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                 state.handle_return(&mut exec_step, geth_steps, false)?;
                 Ok(vec![exec_step])
             }
-            // 3. Call to account with non-empty code.
-            (false, _, false) => {
+            // 2. Call to precompiled or to account with non-empty code.
+            (false, _, _) => {
                 for (field, value) in [
                     (CallContextField::ProgramCounter, (geth_step.pc + 1).into()),
                     (
@@ -340,15 +288,40 @@ impl<const N_ARGS: usize> Opcode for CallOpcode<N_ARGS> {
                     (CallContextField::LastCalleeReturnDataLength, 0.into()),
                     (CallContextField::IsRoot, 0.into()),
                     (CallContextField::IsCreate, 0.into()),
-                    (CallContextField::CodeHash, call.code_hash.to_word()),
+                    (CallContextField::CodeHash, callee_code_hash_word),
                 ] {
                     state.call_context_write(&mut exec_step, call.call_id, field, value);
                 }
 
+                if is_precompile {
+                    let precompile = PrecompileCalls::try_from(callee_address)?;
+                    // The precompile step runs with the callee code hash read
+                    // above, which is 0 when the account doesn't exist.
+                    if !callee_exists {
+                        state.call_mut()?.code_hash = H256::zero();
+                    }
+                    let stipend = if has_value {
+                        GAS_STIPEND_CALL_WITH_VALUE
+                    } else {
+                        0
+                    };
+                    // The CALL step is pushed before generating the precompile
+                    // step, so that the reversible writes of a failed call are
+                    // reverted into it.
+                    state.tx.steps_mut().push(exec_step);
+                    let precompile_step = precompiles::gen_associated_ops(
+                        state,
+                        geth_steps,
+                        precompile,
+                        callee_gas_left + stipend,
+                    )?;
+                    return Ok(vec![precompile_step]);
+                }
+
                 Ok(vec![exec_step])
             }
 
-            // 4. insufficient balance or error depth cases.
+            // 3. insufficient balance or error depth cases.
             (true, _, _) => {
                 for (field, value) in [
                     (CallContextField::LastCalleeId, 0.into()),
@@ -376,7 +349,6 @@ mod tests {
         TestContext,
     };
 
-    // move this to circuit after circuit part is complete
     #[test]
     fn test_precompiled_call() {
         struct PrecompileCall {
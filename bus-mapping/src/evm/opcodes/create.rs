@@ -1,38 +1,51 @@
 use crate::{
-    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
     evm::Opcode,
-    operation::{AccountField, AccountOp, CallContextField, TxAccessListAccountOp},
+    operation::{
+        AccountField, AccountOp, CallContextField, MemoryOp, TxAccessListAccountOp,
+        TxAccountFlagField, TxAccountFlagOp, RW,
+    },
     state_db::CodeDB,
     Error,
 };
-use eth_types::{evm_types::gas_utils::memory_expansion_gas_cost, GethExecStep, ToWord, Word};
+use eth_types::{Bytecode, GethExecStep, ToBigEndian, ToWord, Word, H160, H256};
+use ethers_core::utils::{keccak256, rlp};
 
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the `OpcodeId::CREATE` and `OpcodeId::CREATE2`.
+/// Both the successful case and the failures that happen before entering the
+/// initialization call (depth, insufficient balance, nonce overflow and
+/// address collision) are handled here.
 #[derive(Debug, Copy, Clone)]
-pub struct DummyCreate<const IS_CREATE2: bool>;
+pub struct Create<const IS_CREATE2: bool>;
 
-impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
+impl<const IS_CREATE2: bool> Opcode for Create<IS_CREATE2> {
     fn gen_associated_ops(
         state: &mut CircuitInputStateRef,
         geth_steps: &[GethExecStep],
     ) -> Result<Vec<ExecStep>, Error> {
-        // TODO: replace dummy create here
         let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
 
         // Get low Uint64 of offset to generate copy steps. Since offset could
         // be Uint64 overflow if length is zero.
         let offset = geth_step.stack.nth_last(1)?.low_u64() as usize;
         let length = geth_step.stack.nth_last(2)?.as_usize();
 
-        let curr_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
         if length != 0 {
             state
                 .call_ctx_mut()?
                 .memory
                 .extend_at_least(offset + length);
         }
-        let next_memory_word_size = (state.call_ctx()?.memory.len() as u64) / 32;
+        let next_memory_word_size = state.call_ctx()?.memory.word_size() as u64;
 
-        let mut exec_step = state.new_step(geth_step)?;
+        // The rw_counter at the beginning of this step is used as the call_id
+        // of the initialization call, so parse it before any rw operation.
+        let callee = state.parse_call(geth_step)?;
+        let address = callee.address;
 
         let n_pop = if IS_CREATE2 { 4 } else { 3 };
         for i in 0..n_pop {
@@ -43,18 +56,10 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
             )?;
         }
 
-        let address = if IS_CREATE2 {
-            state.create2_address(&geth_steps[0])?
-        } else {
-            state.create_address()?
-        };
-
-        // TODO: rename this to initialization call?
-        let call = state.parse_call(geth_step)?;
         state.stack_write(
             &mut exec_step,
             geth_step.stack.nth_last_filled(n_pop - 1),
-            if call.is_success {
+            if callee.is_success {
                 address.to_word()
             } else {
                 Word::zero()
@@ -62,123 +67,452 @@ impl<const IS_CREATE2: bool> Opcode for DummyCreate<IS_CREATE2> {
         )?;
 
         let tx_id = state.tx_ctx.id();
-        let current_call = state.call()?.clone();
-
-        // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
-        // > When a CREATE or CREATE2 opcode is called,
-        // > immediately (i.e. before checks are done to determine
-        // > whether or not the address is unclaimed)
-        // > add the address being created to accessed_addresses,
-        // > but gas costs of CREATE and CREATE2 are unchanged
-        let is_warm = state.sdb.check_account_in_access_list(&address);
-        state.push_op_reversible(
-            &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id: state.tx_ctx.id(),
-                address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
+        let caller = state.call()?.clone();
+        for (field, value) in [
+            (CallContextField::TxId, tx_id.into()),
+            (CallContextField::Depth, caller.depth.into()),
+            (CallContextField::CalleeAddress, caller.address.to_word()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                caller.rw_counter_end_of_reversion.into(),
+            ),
+            (
+                CallContextField::IsPersistent,
+                caller.is_persistent.to_word(),
+            ),
+        ] {
+            state.call_context_read(&mut exec_step, caller.call_id, field, value);
+        }
 
-        // Increase caller's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.caller_address);
-        state.push_op_reversible(
+        let (found, caller_account) = state.sdb.get_account(&caller.address);
+        if !found {
+            return Err(Error::AccountNotFound(caller.address));
+        }
+        let caller_balance = caller_account.balance;
+        let caller_nonce = caller_account.nonce;
+        state.account_read(
             &mut exec_step,
-            AccountOp {
-                address: call.caller_address,
-                field: AccountField::Nonce,
-                value: (nonce_prev + 1).into(),
-                value_prev: nonce_prev.into(),
-            },
-        )?;
-
-        // Add callee into access list
-        let is_warm = state.sdb.check_account_in_access_list(&call.address);
-        state.push_op_reversible(
+            caller.address,
+            AccountField::Balance,
+            caller_balance,
+        );
+        state.account_read(
             &mut exec_step,
-            TxAccessListAccountOp {
-                tx_id,
-                address: call.address,
-                is_warm: true,
-                is_warm_prev: is_warm,
-            },
-        )?;
+            caller.address,
+            AccountField::Nonce,
+            caller_nonce.into(),
+        );
 
-        state.push_call(call.clone());
+        // Check if an error of ErrDepth, ErrInsufficientBalance or
+        // ErrNonceUintOverflow occurred.  In these cases the initialization
+        // call is not entered, and neither the caller's nonce nor the access
+        // list is updated.
+        let is_precheck_ok =
+            caller.depth < 1025 && caller_balance >= callee.value && caller_nonce < u64::MAX;
 
-        state.transfer(
-            &mut exec_step,
-            call.caller_address,
-            call.address,
-            true,
-            true,
-            call.value,
-        )?;
+        // An address collision happens when the account to be created already
+        // has a non-zero nonce or non-empty code (EIP-684).
+        let (callee_exists, callee_code_hash, callee_nonce) = {
+            let (_, callee_account) = state.sdb.get_account(&address);
+            (
+                !callee_account.is_empty(),
+                callee_account.code_hash,
+                callee_account.nonce,
+            )
+        };
+        let is_address_collision =
+            callee_nonce != 0 || callee_code_hash != CodeDB::empty_code_hash();
 
-        // Increase callee's nonce
-        let nonce_prev = state.sdb.get_nonce(&call.address);
-        debug_assert!(nonce_prev == 0);
-        state.push_op_reversible(
-            &mut exec_step,
-            AccountOp {
-                address: call.address,
-                field: AccountField::Nonce,
-                value: 1.into(),
-                value_prev: 0.into(),
-            },
-        )?;
+        let init_code = state
+            .call_ctx()?
+            .memory
+            .read_chunk(offset.into(), length.into());
+        let is_entering = is_precheck_ok && !is_address_collision && length > 0;
 
-        let memory_expansion_gas_cost =
-            memory_expansion_gas_cost(curr_memory_word_size, next_memory_word_size);
+        if is_precheck_ok {
+            // Increase caller's nonce
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: caller.address,
+                    field: AccountField::Nonce,
+                    value: (caller_nonce + 1).into(),
+                    value_prev: caller_nonce.into(),
+                },
+            )?;
 
-        // EIP-150: all but one 64th of the caller's gas is sent to the callee.
-        let caller_gas_left = (geth_step.gas - geth_step.gas_cost - memory_expansion_gas_cost) / 64;
+            // Quote from [EIP-2929](https://eips.ethereum.org/EIPS/eip-2929)
+            // > When a CREATE or CREATE2 opcode is called,
+            // > immediately (i.e. before checks are done to determine
+            // > whether or not the address is unclaimed)
+            // > add the address being created to accessed_addresses,
+            // > but gas costs of CREATE and CREATE2 are unchanged
+            // Note that geth only does so after the depth, balance and nonce
+            // checks pass.
+            let is_warm = state.sdb.check_account_in_access_list(&address);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccessListAccountOp {
+                    tx_id,
+                    address,
+                    is_warm: true,
+                    is_warm_prev: is_warm,
+                },
+            )?;
 
-        for (field, value) in [
-            (CallContextField::ProgramCounter, (geth_step.pc + 1).into()),
-            (
-                CallContextField::StackPointer,
-                geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
-            ),
-            (CallContextField::GasLeft, caller_gas_left.into()),
-            (CallContextField::MemorySize, next_memory_word_size.into()),
-            (
-                CallContextField::ReversibleWriteCounter,
-                // +3 is because we do some transfers after pushing the call. can be just push the
-                // call later?
-                (exec_step.reversible_write_counter + 3).into(),
-            ),
-        ] {
-            state.call_context_write(&mut exec_step, current_call.call_id, field, value);
+            // Feed the keccak input of the contract address derivation to the
+            // Keccak circuit, so that the CREATE/CREATE2 gadget can verify the
+            // address with a lookup to the Keccak table.
+            state
+                .block
+                .sha3_inputs
+                .push(create_address_preimage::<IS_CREATE2>(
+                    caller.address,
+                    caller_nonce,
+                    geth_step.stack.nth_last(3).unwrap_or_default(),
+                    &init_code,
+                ));
         }
 
-        for (field, value) in [
-            (CallContextField::CallerId, current_call.call_id.into()),
-            (CallContextField::IsSuccess, call.is_success.to_word()),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-            (CallContextField::TxId, state.tx_ctx.id().into()),
-            (
-                CallContextField::CallerAddress,
-                current_call.address.to_word(),
-            ),
-            (CallContextField::CalleeAddress, call.address.to_word()),
-            (
-                CallContextField::RwCounterEndOfReversion,
-                call.rw_counter_end_of_reversion.to_word(),
-            ),
-            (CallContextField::IsPersistent, call.is_persistent.to_word()),
-        ] {
-            state.call_context_write(&mut exec_step, call.call_id, field, value);
+        // Switch to the initialization call's context.
+        let caller_reversible_write_counter = state.call_ctx()?.reversible_write_counter;
+        state.push_call(callee.clone());
+
+        if is_precheck_ok {
+            for (field, value) in [
+                (
+                    CallContextField::RwCounterEndOfReversion,
+                    callee.rw_counter_end_of_reversion.to_word(),
+                ),
+                (
+                    CallContextField::IsPersistent,
+                    callee.is_persistent.to_word(),
+                ),
+            ] {
+                state.call_context_write(&mut exec_step, callee.call_id, field, value);
+            }
+
+            state.account_read(
+                &mut exec_step,
+                callee.address,
+                AccountField::CodeHash,
+                if callee_exists {
+                    callee_code_hash.to_word()
+                } else {
+                    Word::zero()
+                },
+            );
+            state.account_read(
+                &mut exec_step,
+                callee.address,
+                AccountField::Nonce,
+                callee_nonce.into(),
+            );
+
+            if length > 0 {
+                handle_copy(
+                    state,
+                    &mut exec_step,
+                    caller.call_id,
+                    offset,
+                    &init_code,
+                    callee.code_hash,
+                )?;
+            }
+
+            if !is_address_collision {
+                state.transfer(
+                    &mut exec_step,
+                    caller.address,
+                    callee.address,
+                    callee_exists,
+                    true,
+                    callee.value,
+                )?;
+
+                // EIP-161: the nonce of a created account starts at 1.
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: callee.address,
+                        field: AccountField::Nonce,
+                        value: 1.into(),
+                        value_prev: 0.into(),
+                    },
+                )?;
+                // An account created earlier in the tx would make the address
+                // collide, so it can't have been flagged yet.
+                debug_assert!(!state.sdb.is_account_created(&callee.address));
+                state.push_op_reversible(
+                    &mut exec_step,
+                    TxAccountFlagOp {
+                        tx_id,
+                        address: callee.address,
+                        field: TxAccountFlagField::Created,
+                        value: true,
+                        value_prev: false,
+                    },
+                )?;
+            }
         }
 
-        if call.code_hash == CodeDB::empty_code_hash() {
-            // 1. Create with empty initcode.
-            state.handle_return(&mut exec_step, geth_steps, false)?;
+        if is_entering {
+            // EIP-150: all but one 64th of the caller's gas is sent to the
+            // initialization call.
+            let caller_gas_left = (geth_step.gas - geth_step.gas_cost) / 64;
+
+            for (field, value) in [
+                (CallContextField::ProgramCounter, (geth_step.pc + 1).into()),
+                (
+                    CallContextField::StackPointer,
+                    geth_step.stack.nth_last_filled(n_pop - 1).0.into(),
+                ),
+                (CallContextField::GasLeft, caller_gas_left.into()),
+                (CallContextField::MemorySize, next_memory_word_size.into()),
+                (
+                    CallContextField::ReversibleWriteCounter,
+                    caller_reversible_write_counter.into(),
+                ),
+            ] {
+                state.call_context_write(&mut exec_step, caller.call_id, field, value);
+            }
+
+            for (field, value) in [
+                (CallContextField::CallerId, caller.call_id.into()),
+                (CallContextField::IsSuccess, callee.is_success.to_word()),
+                (CallContextField::TxId, tx_id.into()),
+                (
+                    CallContextField::CallerAddress,
+                    callee.caller_address.to_word(),
+                ),
+                (CallContextField::CalleeAddress, callee.address.to_word()),
+                (CallContextField::Depth, callee.depth.into()),
+                (CallContextField::CallDataOffset, 0.into()),
+                (CallContextField::CallDataLength, 0.into()),
+                (CallContextField::Value, callee.value),
+                (CallContextField::IsStatic, false.to_word()),
+                (CallContextField::LastCalleeId, 0.into()),
+                (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                (CallContextField::LastCalleeReturnDataLength, 0.into()),
+                (CallContextField::IsRoot, false.to_word()),
+                (CallContextField::IsCreate, true.to_word()),
+                (CallContextField::CodeHash, callee.code_hash.to_word()),
+            ] {
+                state.call_context_write(&mut exec_step, callee.call_id, field, value);
+            }
+
             Ok(vec![exec_step])
         } else {
-            // 2. Create with non-empty initcode.
+            // The initialization call is either not entered (failed precheck
+            // or address collision), or finishes immediately because the init
+            // code is empty.
+            for (field, value) in [
+                (CallContextField::LastCalleeId, 0.into()),
+                (CallContextField::LastCalleeReturnDataOffset, 0.into()),
+                (CallContextField::LastCalleeReturnDataLength, 0.into()),
+            ] {
+                state.call_context_write(&mut exec_step, caller.call_id, field, value);
+            }
+            state.handle_return(&mut exec_step, geth_steps, false)?;
+
+            // Gas forwarded to the initialization call is returned to the
+            // caller, except for address collision which consumes all of it.
+            if let Some(next_step) = geth_steps.get(1) {
+                exec_step.gas_cost = geth_step.gas - next_step.gas;
+            }
             Ok(vec![exec_step])
         }
     }
 }
+
+/// Return the keccak preimage of the contract address derivation, which is
+/// `RLP([caller_address, caller_nonce])` for CREATE and
+/// `0xff ++ caller_address ++ salt ++ keccak256(init_code)` for CREATE2.
+fn create_address_preimage<const IS_CREATE2: bool>(
+    caller_address: H160,
+    caller_nonce: u64,
+    salt: Word,
+    init_code: &[u8],
+) -> Vec<u8> {
+    if IS_CREATE2 {
+        let mut preimage = Vec::with_capacity(1 + 20 + 32 + 32);
+        preimage.push(0xff);
+        preimage.extend_from_slice(caller_address.as_bytes());
+        preimage.extend_from_slice(&salt.to_be_bytes());
+        preimage.extend_from_slice(&keccak256(init_code));
+        preimage
+    } else {
+        let mut stream = rlp::RlpStream::new();
+        stream.begin_list(2);
+        stream.append(&caller_address);
+        stream.append(&caller_nonce);
+        stream.out().to_vec()
+    }
+}
+
+/// Push the memory reads and the copy event that move the init code from the
+/// caller's memory into the bytecode of the initialization call.
+fn handle_copy(
+    state: &mut CircuitInputStateRef,
+    step: &mut ExecStep,
+    caller_id: usize,
+    offset: usize,
+    init_code: &[u8],
+    code_hash: H256,
+) -> Result<(), Error> {
+    debug_assert_eq!(CodeDB::hash(init_code), code_hash);
+    let bytes: Vec<_> = Bytecode::from(init_code.to_vec())
+        .code
+        .iter()
+        .map(|element| (element.value, element.is_code))
+        .collect();
+
+    let rw_counter_start = state.block_ctx.rwc;
+    for (i, (byte, _)) in bytes.iter().enumerate() {
+        state.push_op(
+            step,
+            RW::READ,
+            MemoryOp::new(caller_id, (offset + i).into(), *byte),
+        );
+    }
+
+    state.push_copy(
+        step,
+        CopyEvent {
+            rw_counter_start,
+            src_type: CopyDataType::Memory,
+            src_id: NumberOrHash::Number(caller_id),
+            src_addr: offset.try_into().unwrap(),
+            src_addr_end: (offset + init_code.len()).try_into().unwrap(),
+            dst_type: CopyDataType::Bytecode,
+            dst_id: NumberOrHash::Hash(code_hash),
+            dst_addr: 0,
+            log_id: None,
+            bytes,
+            access_list: vec![],
+        },
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        circuit_input_builder::{CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{AccountField, AccountOp, RW},
+        state_db::CodeDB,
+    };
+    use eth_types::{
+        bytecode, evm_types::OpcodeId, geth_types::GethData, word, ToBigEndian, ToWord, Word,
+    };
+    use ethers_core::utils::{get_contract_address, get_create2_address};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext, MOCK_DEPLOYED_CONTRACT_BYTECODE,
+    };
+
+    fn build_block(is_create2: bool) -> crate::circuit_input_builder::CircuitInputBuilder {
+        let mut code = bytecode! {
+            PUSH21(*MOCK_DEPLOYED_CONTRACT_BYTECODE)
+            PUSH1(0)
+            MSTORE
+        };
+        if is_create2 {
+            code.push(32, word!("0x1234"));
+        }
+        code.append(&bytecode! {
+            PUSH1(0x15)
+            PUSH1(0xB)
+            PUSH1(0)
+        });
+        code.write_op(if is_create2 {
+            OpcodeId::CREATE2
+        } else {
+            OpcodeId::CREATE
+        });
+        code.write_op(OpcodeId::STOP);
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+        builder
+    }
+
+    #[test]
+    fn test_create_address_and_init_code_copy() {
+        for is_create2 in [false, true] {
+            let builder = build_block(is_create2);
+            let tx = &builder.block.txs()[0];
+            let opcode = if is_create2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            };
+            let step = tx
+                .steps()
+                .iter()
+                .find(|step| step.exec_state == ExecState::Op(opcode))
+                .unwrap();
+
+            let caller = tx.calls()[0].address;
+            let init_code = MOCK_DEPLOYED_CONTRACT_BYTECODE.to_be_bytes()[11..].to_vec();
+            let expected_address = if is_create2 {
+                get_create2_address(
+                    caller,
+                    word!("0x1234").to_be_bytes().to_vec(),
+                    init_code.clone(),
+                )
+            } else {
+                get_contract_address(caller, 0)
+            };
+
+            // The address is pushed onto the caller's stack.
+            let n_pop = if is_create2 { 4 } else { 3 };
+            let stack_write = &builder.block.container.stack[step.rw_index(n_pop).as_usize()];
+            assert_eq!(stack_write.rw(), RW::WRITE);
+            assert_eq!(stack_write.op().value(), &expected_address.to_word());
+
+            // The caller's nonce is increased.
+            assert!(builder
+                .block
+                .container
+                .account
+                .iter()
+                .any(|op| op.rw() == RW::WRITE
+                    && op.op()
+                        == &AccountOp {
+                            address: caller,
+                            field: AccountField::Nonce,
+                            value: Word::from(1),
+                            value_prev: Word::zero(),
+                        }));
+
+            // The init code is copied from memory into the bytecode table.
+            let copy_event = builder
+                .block
+                .copy_events
+                .iter()
+                .find(|event| event.dst_type == CopyDataType::Bytecode)
+                .unwrap();
+            assert_eq!(copy_event.src_type, CopyDataType::Memory);
+            assert_eq!(copy_event.src_addr, 0xB);
+            assert_eq!(copy_event.bytes.len(), 0x15);
+            assert_eq!(
+                copy_event.dst_id,
+                NumberOrHash::Hash(CodeDB::hash(&init_code))
+            );
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    error::ExecError,
+    evm::{Opcode, OpcodeId},
+    Error,
+};
+use eth_types::GethExecStep;
+
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct ErrorMaxInitCodeSize;
+
+// ErrorMaxInitCodeSize deals with a CREATE or CREATE2 whose init code is
+// larger than the limit of EIP-3860. It reads the value, the offset and the
+// length of the init code from the stack before restoring the call context.
+impl Opcode for ErrorMaxInitCodeSize {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+        let next_step = geth_steps.get(1);
+        exec_step.error = state.get_step_err(geth_step, next_step)?;
+        assert_eq!(exec_step.error, Some(ExecError::MaxInitCodeSizeExceeded));
+        assert!([OpcodeId::CREATE, OpcodeId::CREATE2].contains(&geth_step.op));
+
+        for i in 0..3 {
+            state.stack_read(
+                &mut exec_step,
+                geth_step.stack.nth_last_filled(i),
+                geth_step.stack.nth_last(i)?,
+            )?;
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, true)?;
+        Ok(vec![exec_step])
+    }
+}
@@ -0,0 +1,249 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecStep, NumberOrHash,
+    },
+    Error,
+};
+use eth_types::GethExecStep;
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the [`OpcodeId::MCOPY`](crate::evm::OpcodeId::MCOPY)
+/// `OpcodeId`.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Mcopy;
+
+impl Opcode for Mcopy {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let dst_offset = geth_step.stack.nth_last(0)?;
+        let src_offset = geth_step.stack.nth_last(1)?;
+        let length = geth_step.stack.nth_last(2)?;
+
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(0),
+            dst_offset,
+        )?;
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.nth_last_filled(1),
+            src_offset,
+        )?;
+        state.stack_read(&mut exec_step, geth_step.stack.nth_last_filled(2), length)?;
+
+        // Both offsets are within range if length is non-zero, otherwise the
+        // memory expansion would have run out of gas.
+        let length = length.as_u64();
+        if length != 0 {
+            let copy_event = gen_copy_event(
+                state,
+                &mut exec_step,
+                src_offset.as_u64(),
+                dst_offset.as_u64(),
+                length,
+            )?;
+            state.push_copy(&mut exec_step, copy_event);
+        }
+
+        Ok(vec![exec_step])
+    }
+}
+
+fn gen_copy_event(
+    state: &mut CircuitInputStateRef,
+    exec_step: &mut ExecStep,
+    src_addr: u64,
+    dst_addr: u64,
+    length: u64,
+) -> Result<CopyEvent, Error> {
+    let rw_counter_start = state.block_ctx.rwc;
+
+    // The source and destination ranges may overlap, so every byte is read
+    // before any of them is written.
+    let bytes = state
+        .call_ctx()?
+        .memory
+        .read_chunk((src_addr as usize).into(), (length as usize).into());
+    for (idx, byte) in bytes.iter().enumerate() {
+        state.memory_read(exec_step, (src_addr as usize + idx).into(), *byte)?;
+    }
+    for (idx, byte) in bytes.iter().enumerate() {
+        state.memory_write(exec_step, (dst_addr as usize + idx).into(), *byte)?;
+    }
+
+    // reconstruction
+    let memory = &mut state.call_ctx_mut()?.memory;
+    memory.extend_at_least((src_addr.max(dst_addr) + length) as usize);
+    memory.0[dst_addr as usize..(dst_addr + length) as usize].copy_from_slice(&bytes);
+
+    let call_id = state.call()?.call_id;
+    Ok(CopyEvent {
+        src_type: CopyDataType::Memory,
+        src_id: NumberOrHash::Number(call_id),
+        src_addr,
+        src_addr_end: src_addr + length,
+        dst_type: CopyDataType::Memory,
+        dst_id: NumberOrHash::Number(call_id),
+        dst_addr,
+        log_id: None,
+        rw_counter_start,
+        bytes: bytes.into_iter().map(|byte| (byte, false)).collect(),
+        access_list: vec![],
+    })
+}
+
+#[cfg(test)]
+mod mcopy_tests {
+    use crate::{
+        circuit_input_builder::{CircuitsParams, CopyDataType, ExecState, NumberOrHash},
+        mock::BlockData,
+        operation::{MemoryOp, StackOp, RW},
+    };
+    use eth_types::{
+        bytecode,
+        evm_types::{Hardfork, MemoryAddress, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Word,
+    };
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn mcopy_opcode_impl() {
+        // Overlapping ranges with the destination after the source
+        test_ok(0x01, 0x00, 0x04);
+        // Overlapping ranges with the destination before the source
+        test_ok(0x00, 0x01, 0x04);
+        // Disjoint ranges expanding the memory
+        test_ok(0x40, 0x10, 0x20);
+    }
+
+    fn test_ok(dst_offset: usize, src_offset: usize, size: usize) {
+        // Memory bytes 0..32 hold 1..=32 before the copy.
+        let memory = (1..=32u8).collect::<Vec<_>>();
+        let code = bytecode! {
+            PUSH32(Word::from_big_endian(&memory))
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(size)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        };
+
+        let block: GethData = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.number(0xcafeu64).hardfork(Hardfork::Cancun),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        let step = builder.block.txs()[0]
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::MCOPY))
+            .unwrap();
+
+        let expected_call_id = builder.block.txs()[0].calls()[step.call_index].call_id;
+
+        assert_eq!(
+            [0, 1, 2]
+                .map(|idx| &builder.block.container.stack[step.bus_mapping_instance[idx].as_usize()])
+                .map(|op| (op.rw(), op.op())),
+            [
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1021), Word::from(dst_offset)),
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1022), Word::from(src_offset)),
+                ),
+                (
+                    RW::READ,
+                    &StackOp::new(1, StackAddress::from(1023), Word::from(size)),
+                ),
+            ]
+        );
+
+        // The source bytes as they were before the copy.
+        let bytes = (src_offset..src_offset + size)
+            .map(|addr| memory.get(addr).copied().unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        // RW table memory reads of the whole source range, then writes of the
+        // whole destination range, following the 32 writes of MSTORE.
+        assert_eq!(
+            (32..32 + 2 * size)
+                .map(|idx| &builder.block.container.memory[idx])
+                .map(|op| (op.rw(), op.op().clone()))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+            bytes
+                .iter()
+                .enumerate()
+                .map(|(idx, byte)| {
+                    (
+                        RW::READ,
+                        MemoryOp::new(1, MemoryAddress::from(src_offset + idx), *byte),
+                    )
+                })
+                .chain(bytes.iter().enumerate().map(|(idx, byte)| {
+                    (
+                        RW::WRITE,
+                        MemoryOp::new(1, MemoryAddress::from(dst_offset + idx), *byte),
+                    )
+                }))
+                .collect::<Vec<(RW, MemoryOp)>>(),
+        );
+
+        let copy_events = builder.block.copy_events.clone();
+        assert_eq!(copy_events.len(), 1);
+        assert_eq!(
+            copy_events[0].src_id,
+            NumberOrHash::Number(expected_call_id)
+        );
+        assert_eq!(copy_events[0].src_addr as usize, src_offset);
+        assert_eq!(copy_events[0].src_addr_end as usize, src_offset + size);
+        assert_eq!(copy_events[0].src_type, CopyDataType::Memory);
+        assert_eq!(
+            copy_events[0].dst_id,
+            NumberOrHash::Number(expected_call_id)
+        );
+        assert_eq!(copy_events[0].dst_addr as usize, dst_offset);
+        assert_eq!(copy_events[0].dst_type, CopyDataType::Memory);
+        assert!(copy_events[0].log_id.is_none());
+        assert_eq!(
+            copy_events[0].bytes,
+            bytes
+                .into_iter()
+                .map(|byte| (byte, false))
+                .collect::<Vec<_>>()
+        );
+        assert!(copy_events[0].is_memory_copy());
+        assert_eq!(copy_events[0].rw_counter_delta() as usize, 2 * size);
+    }
+}
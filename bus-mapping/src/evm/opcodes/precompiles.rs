@@ -0,0 +1,259 @@
+use super::TxExecSteps;
+use crate::{
+    circuit_input_builder::{
+        CircuitInputStateRef, CopyDataType, CopyEvent, ExecState, ExecStep, NumberOrHash,
+    },
+    operation::{CallContextField, MemoryOp, RW},
+    precompile::{execute_precompiled, PrecompileCalls, PrecompileEvent},
+    Error,
+};
+use eth_types::{GethExecStep, ToWord};
+use std::{cmp::min, iter};
+
+/// Placeholder structure used to implement [`TxExecSteps`] for the root call
+/// of a transaction sent to a precompiled contract, which is executed right
+/// after the BeginTx step.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PrecompileTx;
+
+impl TxExecSteps for PrecompileTx {
+    fn gen_associated_steps(
+        state: &mut CircuitInputStateRef,
+        execution_step: ExecState,
+    ) -> Result<ExecStep, Error> {
+        let ExecState::Precompile(precompile) = execution_step else {
+            unreachable!()
+        };
+        let begin_tx_step = state
+            .tx
+            .steps()
+            .last()
+            .expect("steps should have at least one BeginTx step");
+        let gas_left = begin_tx_step.gas_left - begin_tx_step.gas_cost;
+        gen_associated_ops(state, &[], precompile, gas_left)
+    }
+}
+
+/// Generate the [`ExecStep`] of a call to a precompiled contract.  The callee
+/// call context must have already been pushed by the caller, which is either a
+/// *CALL opcode or the BeginTx step of a transaction sent to a precompile.
+/// `geth_steps` starts at the *CALL step, and is empty for the root call.
+/// `gas_left` is the gas passed to the precompile, including the stipend.
+///
+/// The step reads the callee call context, accumulates the input into an RLC
+/// that is checked by the gadget of the precompile, and on success copies the
+/// output into the callee memory (so that it can be read as return data) and
+/// into the return area of the caller memory, before returning to the caller.
+pub(crate) fn gen_associated_ops(
+    state: &mut CircuitInputStateRef,
+    geth_steps: &[GethExecStep],
+    precompile: PrecompileCalls,
+    gas_left: u64,
+) -> Result<ExecStep, Error> {
+    let call = state.call()?.clone();
+    let call_ctx = state.call_ctx()?;
+    let input = call_ctx.call_data.clone();
+
+    let result = execute_precompiled(
+        &precompile.into(),
+        &input,
+        state.block.circuits_params.hardfork,
+    );
+    if call.is_success != (result.is_success && result.gas_cost <= gas_left) {
+        log::warn!(
+            "precompile {:?} result mismatch, geth: {}, precompile: {:?} with gas_left {}",
+            precompile,
+            call.is_success,
+            result,
+            gas_left
+        );
+    }
+    let output = if call.is_success {
+        result.output.clone()
+    } else {
+        vec![]
+    };
+
+    let mut exec_step = ExecStep {
+        exec_state: ExecState::Precompile(precompile),
+        gas_left,
+        // A failed precompile call consumes all the gas passed to it.
+        gas_cost: if call.is_success {
+            result.gas_cost
+        } else {
+            gas_left
+        },
+        call_index: call_ctx.index,
+        rwc: state.block_ctx.rwc,
+        reversible_write_counter: call_ctx.reversible_write_counter,
+        log_id: state.tx_ctx.log_id,
+        ..Default::default()
+    };
+
+    for (field, value) in [
+        (CallContextField::IsSuccess, call.is_success.to_word()),
+        (CallContextField::TxId, state.tx_ctx.id().into()),
+        (CallContextField::CallerId, call.caller_id.into()),
+        (
+            CallContextField::CallDataOffset,
+            call.call_data_offset.into(),
+        ),
+        (
+            CallContextField::CallDataLength,
+            call.call_data_length.into(),
+        ),
+        (
+            CallContextField::ReturnDataOffset,
+            call.return_data_offset.into(),
+        ),
+        (
+            CallContextField::ReturnDataLength,
+            call.return_data_length.into(),
+        ),
+    ] {
+        state.call_context_read(&mut exec_step, call.call_id, field, value);
+    }
+
+    // Copy the input from the tx calldata or the caller memory into the
+    // precompile table.  The precompiles with a fixed size input, and MODEXP,
+    // copy it again right-padded with zeros or truncated to that size, where
+    // the padding isn't read from memory.
+    if !input.is_empty() {
+        let padded_input = precompile.padded_input_len(&input).map(|len| {
+            let mut padded_input = input.clone();
+            padded_input.resize(len, 0);
+            padded_input
+        });
+        for bytes in iter::once(&input).chain(padded_input.as_ref()) {
+            let rw_counter_start = state.block_ctx.rwc;
+            let (src_type, src_id) = if call.is_root {
+                (
+                    CopyDataType::TxCalldata,
+                    NumberOrHash::Number(state.tx_ctx.id()),
+                )
+            } else {
+                for (idx, byte) in bytes.iter().take(input.len()).enumerate() {
+                    state.push_op(
+                        &mut exec_step,
+                        RW::READ,
+                        MemoryOp::new(
+                            call.caller_id,
+                            (call.call_data_offset as usize + idx).into(),
+                            *byte,
+                        ),
+                    );
+                }
+                (CopyDataType::Memory, NumberOrHash::Number(call.caller_id))
+            };
+            state.push_copy(
+                &mut exec_step,
+                CopyEvent {
+                    src_addr: call.call_data_offset,
+                    src_addr_end: call.call_data_offset + call.call_data_length,
+                    src_type,
+                    src_id,
+                    dst_addr: 0,
+                    dst_type: CopyDataType::RlcAcc,
+                    dst_id: NumberOrHash::Number(call.call_id),
+                    log_id: None,
+                    rw_counter_start,
+                    bytes: bytes.iter().map(|byte| (*byte, false)).collect(),
+                    access_list: vec![],
+                },
+            );
+        }
+    }
+
+    if !call.is_root && !output.is_empty() {
+        // Write the output into the callee memory, where it's read from as
+        // return data.
+        let rw_counter_start = state.block_ctx.rwc;
+        for (idx, byte) in output.iter().enumerate() {
+            state.memory_write(&mut exec_step, idx.into(), *byte)?;
+        }
+        let call_ctx = state.call_ctx_mut()?;
+        call_ctx.memory.extend_at_least(output.len());
+        call_ctx.memory.0[..output.len()].copy_from_slice(&output);
+        state.push_copy(
+            &mut exec_step,
+            CopyEvent {
+                src_addr: 0,
+                src_addr_end: output.len() as u64,
+                src_type: CopyDataType::RlcAcc,
+                src_id: NumberOrHash::Number(call.call_id),
+                dst_addr: 0,
+                dst_type: CopyDataType::Memory,
+                dst_id: NumberOrHash::Number(call.call_id),
+                log_id: None,
+                rw_counter_start,
+                bytes: output.iter().map(|byte| (*byte, false)).collect(),
+                access_list: vec![],
+            },
+        );
+
+        // Copy the output into the return area of the caller memory.
+        let length = min(output.len(), call.return_data_length as usize);
+        if length > 0 {
+            let rw_counter_start = state.block_ctx.rwc;
+            for (idx, byte) in output[..length].iter().enumerate() {
+                state.push_op(
+                    &mut exec_step,
+                    RW::READ,
+                    MemoryOp::new(call.call_id, idx.into(), *byte),
+                );
+                state.push_op(
+                    &mut exec_step,
+                    RW::WRITE,
+                    MemoryOp::new(
+                        call.caller_id,
+                        (call.return_data_offset as usize + idx).into(),
+                        *byte,
+                    ),
+                );
+            }
+            let return_data_offset = call.return_data_offset as usize;
+            let caller_ctx = state.caller_ctx_mut()?;
+            caller_ctx
+                .memory
+                .extend_at_least(return_data_offset + length);
+            caller_ctx.memory.0[return_data_offset..return_data_offset + length]
+                .copy_from_slice(&output[..length]);
+            state.push_copy(
+                &mut exec_step,
+                CopyEvent {
+                    src_addr: 0,
+                    src_addr_end: output.len() as u64,
+                    src_type: CopyDataType::Memory,
+                    src_id: NumberOrHash::Number(call.call_id),
+                    dst_addr: call.return_data_offset,
+                    dst_type: CopyDataType::Memory,
+                    dst_id: NumberOrHash::Number(call.caller_id),
+                    log_id: None,
+                    rw_counter_start,
+                    bytes: output[..length].iter().map(|byte| (*byte, false)).collect(),
+                    access_list: vec![],
+                },
+            );
+        }
+    }
+
+    if !call.is_root {
+        state.caller_ctx_mut()?.return_data = output;
+    }
+
+    state.block.add_precompile_event(PrecompileEvent {
+        kind: precompile,
+        input,
+        output: result.output,
+        gas_cost: result.gas_cost,
+        is_success: result.is_success,
+    });
+
+    state.handle_return(
+        &mut exec_step,
+        geth_steps,
+        !call.is_root || !call.is_success,
+    )?;
+
+    Ok(exec_step)
+}
@@ -190,6 +190,7 @@ fn handle_copy(
             dst_addr: destination.offset.try_into().unwrap(),
             log_id: None,
             bytes,
+            access_list: vec![],
         },
     );
 
@@ -232,6 +233,7 @@ fn handle_create(
             dst_addr: 0,
             log_id: None,
             bytes,
+            access_list: vec![],
         },
     );
 
@@ -0,0 +1,457 @@
+use super::Opcode;
+use crate::{
+    circuit_input_builder::{CircuitInputStateRef, ExecStep},
+    operation::{
+        AccountField, AccountOp, CallContextField, TxAccessListAccountOp, TxAccountFlagField,
+        TxAccountFlagOp, TxRefundOp, RW,
+    },
+    state_db::CodeDB,
+    Error,
+};
+use eth_types::{GethExecStep, ToAddress, ToWord, Word, H256};
+
+/// Placeholder structure used to implement [`Opcode`] trait over it
+/// corresponding to the
+/// [`OpcodeId::SELFDESTRUCT`](crate::evm::OpcodeId::SELFDESTRUCT) `OpcodeId`.
+/// The whole balance of the current account is moved to the beneficiary, and
+/// the account is marked to be destructed at the end of the transaction,
+/// which since EIP-6780 only happens to accounts created in the same
+/// transaction.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct SelfDestruct;
+
+impl Opcode for SelfDestruct {
+    fn gen_associated_ops(
+        state: &mut CircuitInputStateRef,
+        geth_steps: &[GethExecStep],
+    ) -> Result<Vec<ExecStep>, Error> {
+        let geth_step = &geth_steps[0];
+        let mut exec_step = state.new_step(geth_step)?;
+
+        let call = state.call()?.clone();
+        let sender = call.address;
+
+        // Read beneficiary address from stack.
+        let beneficiary_word = geth_step.stack.last()?;
+        let beneficiary = beneficiary_word.to_address();
+        state.stack_read(
+            &mut exec_step,
+            geth_step.stack.last_filled(),
+            beneficiary_word,
+        )?;
+
+        for (field, value) in [
+            (CallContextField::TxId, state.tx_ctx.id().into()),
+            (CallContextField::IsStatic, (call.is_static as u64).into()),
+            (
+                CallContextField::RwCounterEndOfReversion,
+                call.rw_counter_end_of_reversion.into(),
+            ),
+            (CallContextField::IsPersistent, call.is_persistent.to_word()),
+            (CallContextField::CalleeAddress, sender.to_word()),
+            (CallContextField::IsSuccess, 1.into()),
+        ] {
+            state.call_context_read(&mut exec_step, call.call_id, field, value);
+        }
+
+        // Update transaction access list for beneficiary address.
+        let is_warm = state.sdb.check_account_in_access_list(&beneficiary);
+        state.push_op_reversible(
+            &mut exec_step,
+            TxAccessListAccountOp {
+                tx_id: state.tx_ctx.id(),
+                address: beneficiary,
+                is_warm: true,
+                is_warm_prev: is_warm,
+            },
+        )?;
+
+        let (_, beneficiary_account) = state.sdb.get_account(&beneficiary);
+        let beneficiary_exists = !beneficiary_account.is_empty();
+        let beneficiary_code_hash = if beneficiary_exists {
+            beneficiary_account.code_hash
+        } else {
+            H256::zero()
+        };
+        state.account_read(
+            &mut exec_step,
+            beneficiary,
+            AccountField::CodeHash,
+            beneficiary_code_hash.to_word(),
+        );
+
+        let (found, sender_account) = state.sdb.get_account(&sender);
+        if !found {
+            return Err(Error::AccountNotFound(sender));
+        }
+        let value = sender_account.balance;
+        state.account_read(&mut exec_step, sender, AccountField::Balance, value);
+
+        let tx_id = state.tx_ctx.id();
+        let is_created = state.sdb.is_account_created(&sender);
+        state.push_op(
+            &mut exec_step,
+            RW::READ,
+            TxAccountFlagOp {
+                tx_id,
+                address: sender,
+                field: TxAccountFlagField::Created,
+                value: is_created,
+                value_prev: is_created,
+            },
+        );
+
+        if !value.is_zero() {
+            // If beneficiary doesn't exist, create it
+            if !beneficiary_exists {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: beneficiary,
+                        field: AccountField::CodeHash,
+                        value: CodeDB::empty_code_hash().to_word(),
+                        value_prev: Word::zero(),
+                    },
+                )?;
+            }
+
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: sender,
+                    field: AccountField::Balance,
+                    value: Word::zero(),
+                    value_prev: value,
+                },
+            )?;
+            let beneficiary_balance_prev = state.sdb.get_account(&beneficiary).1.balance;
+            state.push_op_reversible(
+                &mut exec_step,
+                AccountOp {
+                    address: beneficiary,
+                    field: AccountField::Balance,
+                    value: beneficiary_balance_prev + value,
+                    value_prev: beneficiary_balance_prev,
+                },
+            )?;
+        }
+
+        let hardfork = state.block.circuits_params.hardfork;
+        let is_destructed = !hardfork.has_eip6780() || is_created;
+        if is_destructed {
+            // The balance is burnt when the beneficiary is the current account
+            // itself.
+            if !value.is_zero() && beneficiary == sender {
+                state.push_op_reversible(
+                    &mut exec_step,
+                    AccountOp {
+                        address: sender,
+                        field: AccountField::Balance,
+                        value: Word::zero(),
+                        value_prev: value,
+                    },
+                )?;
+            }
+
+            let is_destructed_prev = state.sdb.is_account_destructed(&sender);
+            state.push_op_reversible(
+                &mut exec_step,
+                TxAccountFlagOp {
+                    tx_id,
+                    address: sender,
+                    field: TxAccountFlagField::Destructed,
+                    value: true,
+                    value_prev: is_destructed_prev,
+                },
+            )?;
+
+            // Only the first SELFDESTRUCT of the account is refunded.
+            let selfdestruct_refund = hardfork.selfdestruct_refund();
+            if selfdestruct_refund != 0 && !is_destructed_prev {
+                let refund_prev = state.sdb.refund();
+                state.push_op_reversible(
+                    &mut exec_step,
+                    TxRefundOp {
+                        tx_id,
+                        value: refund_prev + selfdestruct_refund,
+                        value_prev: refund_prev,
+                    },
+                )?;
+            }
+        }
+
+        state.handle_return(&mut exec_step, geth_steps, !call.is_root)?;
+        Ok(vec![exec_step])
+    }
+}
+
+#[cfg(test)]
+mod selfdestruct_tests {
+    use super::*;
+    use crate::{
+        circuit_input_builder::{CircuitsParams, ExecState},
+        mock::BlockData,
+        operation::{StackOp, Target, RW},
+    };
+    use eth_types::{
+        address, bytecode,
+        evm_types::{Hardfork, OpcodeId, StackAddress},
+        geth_types::GethData,
+        Address, Word,
+    };
+    use mock::TestContext;
+    use pretty_assertions::assert_eq;
+
+    const CONTRACT: Address = Address::repeat_byte(0x10);
+    const EOA: Address = Address::repeat_byte(0x20);
+
+    #[test]
+    fn test_selfdestruct_to_existing_beneficiary() {
+        test_ok(EOA, Hardfork::Shanghai);
+    }
+
+    #[test]
+    fn test_selfdestruct_to_non_existing_beneficiary() {
+        test_ok(
+            address!("0xaabbccddee000000000000000000000000000000"),
+            Hardfork::Shanghai,
+        );
+    }
+
+    #[test]
+    fn test_selfdestruct_to_itself() {
+        test_ok(CONTRACT, Hardfork::Shanghai);
+    }
+
+    #[test]
+    fn test_selfdestruct_refund_berlin() {
+        test_ok(EOA, Hardfork::Berlin);
+    }
+
+    #[test]
+    fn test_selfdestruct_not_created_cancun() {
+        test_ok(EOA, Hardfork::Cancun);
+    }
+
+    #[test]
+    fn test_selfdestruct_to_itself_not_created_cancun() {
+        test_ok(CONTRACT, Hardfork::Cancun);
+    }
+
+    fn test_ok(beneficiary: Address, hardfork: Hardfork) {
+        let code = bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        };
+        let contract_balance = Word::from(800u64);
+        let eoa_balance = Word::from(1u64 << 20);
+
+        // Get the execution steps from the external tracer.
+        let block: GethData = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(CONTRACT)
+                    .balance(contract_balance)
+                    .code(code.clone());
+                accs[1].address(EOA).balance(eoa_balance);
+                accs[2]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0].to(accs[0].address).from(accs[2].address);
+            },
+            |block, _tx| block.number(0xcafeu64).hardfork(hardfork),
+        )
+        .unwrap()
+        .into();
+
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                hardfork,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
+        builder
+            .handle_block(&block.eth_block, &block.geth_traces)
+            .unwrap();
+
+        // Since EIP-6780 only an account created in the same transaction is
+        // destructed, which happens once the transaction ends.
+        let is_destructed = !hardfork.has_eip6780();
+        let contract = builder.sdb.get_account(&CONTRACT).1;
+        assert_eq!(contract.is_empty(), is_destructed);
+        if !is_destructed {
+            assert_eq!(
+                contract.balance,
+                if beneficiary == CONTRACT {
+                    contract_balance
+                } else {
+                    Word::zero()
+                }
+            );
+        }
+
+        let transaction = &builder.block.txs()[0];
+        let call_id = transaction.calls()[0].call_id;
+        let step = transaction
+            .steps()
+            .iter()
+            .find(|step| step.exec_state == ExecState::Op(OpcodeId::SELFDESTRUCT))
+            .unwrap();
+        assert_eq!(
+            transaction
+                .steps()
+                .iter()
+                .filter(|step| step.exec_state == ExecState::DestructAccount)
+                .count(),
+            is_destructed as usize
+        );
+        let container = &builder.block.container;
+
+        let operation = &container.stack[step.bus_mapping_instance[0].as_usize()];
+        assert_eq!(operation.rw(), RW::READ);
+        assert_eq!(
+            operation.op(),
+            &StackOp {
+                call_id,
+                address: StackAddress::from(1023u32),
+                value: beneficiary.to_word(),
+            }
+        );
+
+        let account_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::Account)
+            .map(|op_ref| {
+                let op = &container.account[op_ref.as_usize()];
+                (op.rw(), op.op().clone())
+            })
+            .collect::<Vec<_>>();
+
+        let (beneficiary_code_hash, beneficiary_balance_prev) = if beneficiary == CONTRACT {
+            (CodeDB::hash(&code.to_vec()).to_word(), Word::zero())
+        } else if beneficiary == EOA {
+            (CodeDB::empty_code_hash().to_word(), eoa_balance)
+        } else {
+            (Word::zero(), Word::zero())
+        };
+        let mut expected = vec![
+            (
+                RW::READ,
+                AccountOp::new(
+                    beneficiary,
+                    AccountField::CodeHash,
+                    beneficiary_code_hash,
+                    beneficiary_code_hash,
+                ),
+            ),
+            (
+                RW::READ,
+                AccountOp::new(
+                    CONTRACT,
+                    AccountField::Balance,
+                    contract_balance,
+                    contract_balance,
+                ),
+            ),
+        ];
+        if beneficiary_code_hash.is_zero() {
+            expected.push((
+                RW::WRITE,
+                AccountOp::new(
+                    beneficiary,
+                    AccountField::CodeHash,
+                    CodeDB::empty_code_hash().to_word(),
+                    Word::zero(),
+                ),
+            ));
+        }
+        expected.extend([
+            (
+                RW::WRITE,
+                AccountOp::new(
+                    CONTRACT,
+                    AccountField::Balance,
+                    Word::zero(),
+                    contract_balance,
+                ),
+            ),
+            (
+                RW::WRITE,
+                AccountOp::new(
+                    beneficiary,
+                    AccountField::Balance,
+                    beneficiary_balance_prev + contract_balance,
+                    beneficiary_balance_prev,
+                ),
+            ),
+        ]);
+        if is_destructed && beneficiary == CONTRACT {
+            // The balance is burnt.
+            expected.push((
+                RW::WRITE,
+                AccountOp::new(
+                    CONTRACT,
+                    AccountField::Balance,
+                    Word::zero(),
+                    contract_balance,
+                ),
+            ));
+        }
+        assert_eq!(account_ops, expected);
+
+        let tx_account_flag_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::TxAccountFlag)
+            .map(|op_ref| {
+                let op = &container.tx_account_flag[op_ref.as_usize()];
+                (op.rw(), op.op().clone())
+            })
+            .collect::<Vec<_>>();
+        let mut expected = vec![(
+            RW::READ,
+            TxAccountFlagOp {
+                tx_id: 1,
+                address: CONTRACT,
+                field: TxAccountFlagField::Created,
+                value: false,
+                value_prev: false,
+            },
+        )];
+        if is_destructed {
+            expected.push((
+                RW::WRITE,
+                TxAccountFlagOp {
+                    tx_id: 1,
+                    address: CONTRACT,
+                    field: TxAccountFlagField::Destructed,
+                    value: true,
+                    value_prev: false,
+                },
+            ));
+        }
+        assert_eq!(tx_account_flag_ops, expected);
+
+        // EIP-3529 removed the refund of SELFDESTRUCT.
+        let tx_refund_ops = step
+            .bus_mapping_instance
+            .iter()
+            .filter(|op_ref| op_ref.target() == Target::TxRefund)
+            .map(|op_ref| container.tx_refund[op_ref.as_usize()].op().value)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tx_refund_ops,
+            if hardfork < Hardfork::London {
+                vec![24000]
+            } else {
+                vec![]
+            }
+        );
+    }
+}
@@ -46,20 +46,20 @@ impl<const N_POP: usize, const N_PUSH: usize> Opcode for StackOnlyOpcode<N_POP,
 #[cfg(test)]
 mod stackonlyop_tests {
     use crate::{
-        circuit_input_builder::ExecState,
+        circuit_input_builder::{CircuitsParams, ExecState},
         mock::BlockData,
         operation::{StackOp, RW},
     };
     use eth_types::{
         bytecode,
-        evm_types::{OpcodeId, StackAddress},
+        evm_types::{Hardfork, OpcodeId, StackAddress},
         geth_types::GethData,
-        word, Bytecode, Word,
+        word, Bytecode, ToWord, Word,
     };
     use itertools::Itertools;
     use mock::{
         test_ctx::{helpers::*, TestContext},
-        MOCK_BASEFEE, MOCK_DIFFICULTY, MOCK_GASLIMIT,
+        MOCK_BASEFEE, MOCK_GASLIMIT, MOCK_MIX_HASH,
     };
     use pretty_assertions::assert_eq;
     use std::ops::{BitOr, BitXor};
@@ -69,18 +69,41 @@ mod stackonlyop_tests {
         code: Bytecode,
         pops: Vec<StackOp>,
         pushes: Vec<StackOp>,
+    ) {
+        stack_only_opcode_impl_with_hardfork::<N_POP, N_PUSH>(
+            opcode,
+            code,
+            pops,
+            pushes,
+            Hardfork::default(),
+        )
+    }
+
+    fn stack_only_opcode_impl_with_hardfork<const N_POP: usize, const N_PUSH: usize>(
+        opcode: OpcodeId,
+        code: Bytecode,
+        pops: Vec<StackOp>,
+        pushes: Vec<StackOp>,
+        hardfork: Hardfork,
     ) {
         // Get the execution steps from the external tracer
         let block: GethData = TestContext::<2, 1>::new(
             None,
             account_0_code_account_1_no_code(code),
             tx_from_1_to_0,
-            |block, _tx| block.number(0xcafeu64),
+            |block, _tx| block.number(0xcafeu64).hardfork(hardfork),
         )
         .unwrap()
         .into();
 
-        let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+        let mut builder = BlockData::new_from_geth_data_with_params(
+            block.clone(),
+            CircuitsParams {
+                hardfork,
+                ..Default::default()
+            },
+        )
+        .new_circuit_input_builder();
         builder
             .handle_block(&block.eth_block, &block.geth_traces)
             .unwrap();
@@ -367,15 +390,16 @@ mod stackonlyop_tests {
     }
 
     #[test]
-    fn difficulty_opcode_impl() {
-        stack_only_opcode_impl::<0, 1>(
+    fn prevrandao_opcode_impl() {
+        stack_only_opcode_impl_with_hardfork::<0, 1>(
             OpcodeId::DIFFICULTY,
             bytecode! {
                 DIFFICULTY
                 STOP
             },
             vec![],
-            vec![StackOp::new(1, StackAddress(1023), *MOCK_DIFFICULTY)],
+            vec![StackOp::new(1, StackAddress(1023), MOCK_MIX_HASH.to_word())],
+            Hardfork::Paris,
         );
     }
 
@@ -404,4 +428,19 @@ mod stackonlyop_tests {
             vec![StackOp::new(1, StackAddress(1023), *MOCK_BASEFEE)],
         );
     }
+
+    #[test]
+    fn push0_opcode_impl() {
+        stack_only_opcode_impl_with_hardfork::<0, 1>(
+            OpcodeId::PUSH0,
+            bytecode! {
+                PUSH1(0x80u64)
+                PUSH0
+                STOP
+            },
+            vec![],
+            vec![StackOp::new(1, StackAddress(1022), Word::zero())],
+            Hardfork::Shanghai,
+        );
+    }
 }
@@ -24,6 +24,8 @@ impl fmt::Debug for OperationRef {
                 Target::CallContext => "CallContext",
                 Target::TxReceipt => "TxReceipt",
                 Target::TxLog => "TxLog",
+                Target::TransientStorage => "TransientStorage",
+                Target::TxAccountFlag => "TxAccountFlag",
             },
             self.1
         ))
@@ -4,7 +4,11 @@ use crate::{
     circuit_input_builder::{get_state_accesses, Block, CircuitInputBuilder, CircuitsParams},
     state_db::{self, CodeDB, StateDB},
 };
-use eth_types::{geth_types::GethData, Word};
+use eth_types::{
+    geth_types::GethData,
+    state_trie::{StateAccount, StateTrie},
+    Word,
+};
 
 /// BlockData is a type that contains all the information from a block required
 /// to build the circuit inputs.
@@ -14,6 +18,8 @@ pub struct BlockData {
     pub sdb: StateDB,
     /// CodeDB
     pub code_db: CodeDB,
+    /// State trie of the accounts
+    pub state_trie: StateTrie,
     /// chain id
     pub chain_id: Word,
     /// history hashes contains most recent 256 block hashes in history, where
@@ -37,7 +43,7 @@ impl BlockData {
             Block::new(
                 self.chain_id,
                 self.history_hashes.clone(),
-                Word::default(),
+                self.state_trie.clone(),
                 &self.eth_block,
                 self.circuits_params,
             )
@@ -59,14 +65,38 @@ impl BlockData {
             sdb.set_account(addr, state_db::Account::zero());
         }
 
+        let mut state_trie = StateTrie::default();
         for account in geth_data.accounts {
             code_db.insert(account.code.to_vec());
-            sdb.set_account(&account.address, state_db::Account::from(account.clone()));
+            let address = account.address;
+            let account = state_db::Account::from(account);
+            for (key, value) in &account.storage {
+                state_trie
+                    .set_storage(&address, key, *value)
+                    .expect("storage update");
+            }
+            let storage_root = state_trie
+                .account(&address)
+                .expect("state account")
+                .storage_root;
+            state_trie
+                .set_account(
+                    &address,
+                    StateAccount {
+                        nonce: account.nonce,
+                        balance: account.balance,
+                        storage_root,
+                        code_hash: account.code_hash,
+                    },
+                )
+                .expect("account update");
+            sdb.set_account(&address, account);
         }
 
         Self {
             sdb,
             code_db,
+            state_trie,
             chain_id: geth_data.chain_id,
             history_hashes: geth_data.history_hashes,
             eth_block: geth_data.eth_block,
@@ -115,6 +115,10 @@ pub enum Target {
     TxReceipt,
     /// Means the target of the operation is the TxLog.
     TxLog,
+    /// Means the target of the operation is the TransientStorage.
+    TransientStorage,
+    /// Means the target of the operation is the TxAccountFlag.
+    TxAccountFlag,
 }
 
 impl_expr!(Target);
@@ -135,6 +139,8 @@ impl Target {
                 | Target::TxRefund
                 | Target::Account
                 | Target::Storage
+                | Target::TransientStorage
+                | Target::TxAccountFlag
         )
     }
 }
@@ -321,14 +327,24 @@ pub struct StorageOp {
     pub tx_id: usize,
     /// Storage Value before the transaction
     pub committed_value: Word,
+    /// Whether the operation clears the whole storage of the account, which
+    /// is deleted at the end of the transaction.  The key, the committed and
+    /// previous values are then 0, and the value is 1.
+    pub is_clear: bool,
 }
 
 impl fmt::Debug for StorageOp {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str("StorageOp { ")?;
         f.write_fmt(format_args!(
-            "tx_id: {:?}, addr: {:?}, key: {:?}, committed_val: 0x{:x}, val_prev: 0x{:x}, val: 0x{:x}",
-            self.tx_id, self.address, self.key, self.committed_value, self.value_prev, self.value
+            "tx_id: {:?}, addr: {:?}, key: {:?}, committed_val: 0x{:x}, val_prev: 0x{:x}, val: 0x{:x}, is_clear: {:?}",
+            self.tx_id,
+            self.address,
+            self.key,
+            self.committed_value,
+            self.value_prev,
+            self.value,
+            self.is_clear
         ))?;
         f.write_str(" }")
     }
@@ -351,6 +367,21 @@ impl StorageOp {
             value_prev,
             tx_id,
             committed_value,
+            is_clear: false,
+        }
+    }
+
+    /// Create a new instance of a `StorageOp` which clears the storage of the
+    /// account deleted at the end of the transaction.
+    pub fn new_clear(address: Address, tx_id: usize) -> StorageOp {
+        StorageOp {
+            address,
+            key: Word::zero(),
+            value: Word::one(),
+            value_prev: Word::zero(),
+            tx_id,
+            committed_value: Word::zero(),
+            is_clear: true,
         }
     }
 
@@ -400,7 +431,88 @@ impl PartialOrd for StorageOp {
 
 impl Ord for StorageOp {
     fn cmp(&self, other: &Self) -> Ordering {
-        (&self.address, &self.key).cmp(&(&other.address, &other.key))
+        (&self.address, &self.is_clear, &self.key).cmp(&(
+            &other.address,
+            &other.is_clear,
+            &other.key,
+        ))
+    }
+}
+
+/// Represents a [`READ`](RW::READ)/[`WRITE`](RW::WRITE) into the transient
+/// storage implied by a `TLOAD` or `TSTORE` step of the
+/// [`ExecStep`](crate::circuit_input_builder::ExecStep).  The transient storage
+/// only lives during a transaction, so it's keyed by the transaction id.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TransientStorageOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Transient Storage Key
+    pub key: Word,
+    /// Transient Storage Value after the operation
+    pub value: Word,
+    /// Transient Storage Value before the operation
+    pub value_prev: Word,
+}
+
+impl fmt::Debug for TransientStorageOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TransientStorageOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, key: {:?}, val_prev: 0x{:x}, val: 0x{:x}",
+            self.tx_id, self.address, self.key, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl TransientStorageOp {
+    /// Create a new instance of a `TransientStorageOp` from it's components.
+    pub const fn new(
+        tx_id: usize,
+        address: Address,
+        key: Word,
+        value: Word,
+        value_prev: Word,
+    ) -> TransientStorageOp {
+        TransientStorageOp {
+            tx_id,
+            address,
+            key,
+            value,
+            value_prev,
+        }
+    }
+
+    /// Returns the [`Target`] (operation type) of this operation.
+    pub const fn target(&self) -> Target {
+        Target::TransientStorage
+    }
+}
+
+impl Op for TransientStorageOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TransientStorage(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
+impl PartialOrd for TransientStorageOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TransientStorageOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address, &self.key).cmp(&(&other.tx_id, &other.address, &other.key))
     }
 }
 
@@ -506,6 +618,68 @@ impl Op for TxAccessListAccountStorageOp {
     }
 }
 
+/// Represents a flag of an account which lives during a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TxAccountFlagField {
+    /// The account was created by the transaction or one of its `CREATE*`
+    /// steps, which is only tracked since EIP-6780.
+    Created,
+    /// The account was self destructed and is deleted at the end of the
+    /// transaction.
+    Destructed,
+}
+
+/// Represents a change in a flag of an account implied by a `BeginTx`,
+/// `CREATE*`, `SELFDESTRUCT` or `DestructAccount` step.
+#[derive(Clone, PartialEq, Eq)]
+pub struct TxAccountFlagOp {
+    /// Transaction ID: Transaction index in the block starting at 1.
+    pub tx_id: usize,
+    /// Account Address
+    pub address: Address,
+    /// Flag of the account
+    pub field: TxAccountFlagField,
+    /// Value of the flag after the operation
+    pub value: bool,
+    /// Value of the flag before the operation
+    pub value_prev: bool,
+}
+
+impl fmt::Debug for TxAccountFlagOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("TxAccountFlagOp { ")?;
+        f.write_fmt(format_args!(
+            "tx_id: {:?}, addr: {:?}, field: {:?}, val_prev: {:?}, val: {:?}",
+            self.tx_id, self.address, self.field, self.value_prev, self.value
+        ))?;
+        f.write_str(" }")
+    }
+}
+
+impl PartialOrd for TxAccountFlagOp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TxAccountFlagOp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.tx_id, &self.address, &self.field).cmp(&(&other.tx_id, &other.address, &other.field))
+    }
+}
+
+impl Op for TxAccountFlagOp {
+    fn into_enum(self) -> OpEnum {
+        OpEnum::TxAccountFlag(self)
+    }
+
+    fn reverse(&self) -> Self {
+        let mut rev = self.clone();
+        swap(&mut rev.value, &mut rev.value_prev);
+        rev
+    }
+}
+
 /// Represents a change in the Transaction Refund AccessList implied by an
 /// `SSTORE`, `STOP`, `RETURN` or `REVERT` step of the
 /// [`ExecStep`](crate::circuit_input_builder::ExecStep).
@@ -939,10 +1113,14 @@ pub enum OpEnum {
     Memory(MemoryOp),
     /// Storage
     Storage(StorageOp),
+    /// TransientStorage
+    TransientStorage(TransientStorageOp),
     /// TxAccessListAccount
     TxAccessListAccount(TxAccessListAccountOp),
     /// TxAccessListAccountStorage
     TxAccessListAccountStorage(TxAccessListAccountStorageOp),
+    /// TxAccountFlag
+    TxAccountFlag(TxAccountFlagOp),
     /// TxRefund
     TxRefund(TxRefundOp),
     /// Account
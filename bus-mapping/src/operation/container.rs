@@ -1,7 +1,7 @@
 use super::{
     AccountOp, CallContextOp, MemoryOp, Op, OpEnum, Operation, RWCounter, StackOp, StartOp,
-    StorageOp, Target, TxAccessListAccountOp, TxAccessListAccountStorageOp, TxLogOp, TxReceiptOp,
-    TxRefundOp, RW,
+    StorageOp, Target, TransientStorageOp, TxAccessListAccountOp, TxAccessListAccountStorageOp,
+    TxAccountFlagOp, TxLogOp, TxReceiptOp, TxRefundOp, RW,
 };
 use crate::exec_trace::OperationRef;
 use itertools::Itertools;
@@ -28,10 +28,14 @@ pub struct OperationContainer {
     pub stack: Vec<Operation<StackOp>>,
     /// Operations of StorageOp
     pub storage: Vec<Operation<StorageOp>>,
+    /// Operations of TransientStorageOp
+    pub transient_storage: Vec<Operation<TransientStorageOp>>,
     /// Operations of TxAccessListAccountOp
     pub tx_access_list_account: Vec<Operation<TxAccessListAccountOp>>,
     /// Operations of TxAccessListAccountStorageOp
     pub tx_access_list_account_storage: Vec<Operation<TxAccessListAccountStorageOp>>,
+    /// Operations of TxAccountFlagOp
+    pub tx_account_flag: Vec<Operation<TxAccountFlagOp>>,
     /// Operations of TxRefundOp
     pub tx_refund: Vec<Operation<TxRefundOp>>,
     /// Operations of AccountOp
@@ -60,8 +64,10 @@ impl OperationContainer {
             memory: Vec::new(),
             stack: Vec::new(),
             storage: Vec::new(),
+            transient_storage: Vec::new(),
             tx_access_list_account: Vec::new(),
             tx_access_list_account_storage: Vec::new(),
+            tx_account_flag: Vec::new(),
             tx_refund: Vec::new(),
             account: Vec::new(),
             call_context: Vec::new(),
@@ -110,6 +116,14 @@ impl OperationContainer {
                 });
                 OperationRef::from((Target::Storage, self.storage.len() - 1))
             }
+            OpEnum::TransientStorage(op) => {
+                self.transient_storage.push(if reversible {
+                    Operation::new_reversible(rwc, rw, op)
+                } else {
+                    Operation::new(rwc, rw, op)
+                });
+                OperationRef::from((Target::TransientStorage, self.transient_storage.len() - 1))
+            }
             OpEnum::TxAccessListAccount(op) => {
                 self.tx_access_list_account.push(if reversible {
                     Operation::new_reversible(rwc, rw, op)
@@ -132,6 +146,14 @@ impl OperationContainer {
                     self.tx_access_list_account_storage.len() - 1,
                 ))
             }
+            OpEnum::TxAccountFlag(op) => {
+                self.tx_account_flag.push(if reversible {
+                    Operation::new_reversible(rwc, rw, op)
+                } else {
+                    Operation::new(rwc, rw, op)
+                });
+                OperationRef::from((Target::TxAccountFlag, self.tx_account_flag.len() - 1))
+            }
             OpEnum::TxRefund(op) => {
                 self.tx_refund.push(if reversible {
                     Operation::new_reversible(rwc, rw, op)
@@ -1,23 +1,770 @@
 //! precompile helpers
 
-use eth_types::Address;
+use eth_types::{
+    evm_types::Hardfork,
+    sign_types::{recover_pk, SignData},
+    Address, ToBigEndian, ToLittleEndian, Word, U512,
+};
+use halo2_proofs::halo2curves::{
+    group::ff::{FromUniformBytes, PrimeField},
+    secp256k1,
+};
 use revm_precompile::{Precompile, Precompiles};
+use strum_macros::EnumIter;
 
-/// Check if address is a precompiled or not.
-pub fn is_precompiled(address: &Address) -> bool {
-    Precompiles::berlin()
+/// Precompiled contracts of the `hardfork`.  The set is the same from Berlin
+/// to Shanghai, and the point evaluation precompile added by Cancun (EIP-4844)
+/// is not supported.
+pub fn precompiles(hardfork: Hardfork) -> &'static Precompiles {
+    match hardfork {
+        Hardfork::Berlin | Hardfork::London | Hardfork::Shanghai | Hardfork::Cancun => {
+            Precompiles::berlin()
+        }
+    }
+}
+
+/// Check if address is a precompiled or not under the rules of the `hardfork`.
+pub fn is_precompiled(address: &Address, hardfork: Hardfork) -> bool {
+    precompiles(hardfork)
         .get(address.as_fixed_bytes())
         .is_some()
 }
 
-pub(crate) fn execute_precompiled(address: &Address, input: &[u8], gas: u64) -> (Vec<u8>, u64) {
-    let Some(Precompile::Standard(precompile_fn)) = Precompiles::berlin()
+/// Number of precompiled contracts supported by the EVM.
+pub const PRECOMPILE_COUNT: usize = 9;
+
+/// Addresses of the precompiled contracts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, EnumIter)]
+pub enum PrecompileCalls {
+    /// Elliptic Curve Recovery
+    ECRecover = 0x01,
+    /// SHA2-256 hash function
+    Sha256 = 0x02,
+    /// Ripemd-160 hash function
+    Ripemd160 = 0x03,
+    /// Identity function
+    Identity = 0x04,
+    /// Modular exponentiation
+    Modexp = 0x05,
+    /// Point addition on alt_bn128
+    Bn128Add = 0x06,
+    /// Point scalar multiplication on alt_bn128
+    Bn128Mul = 0x07,
+    /// Bilinear function on groups on alt_bn128
+    Bn128Pairing = 0x08,
+    /// Compression function F used in the BLAKE2 cryptographic hashing
+    /// algorithm
+    Blake2F = 0x09,
+}
+
+impl From<PrecompileCalls> for Address {
+    fn from(value: PrecompileCalls) -> Self {
+        let mut addr = [0u8; 20];
+        addr[19] = value as u8;
+        Self(addr)
+    }
+}
+
+impl From<PrecompileCalls> for u64 {
+    fn from(value: PrecompileCalls) -> Self {
+        value as u64
+    }
+}
+
+impl TryFrom<Address> for PrecompileCalls {
+    type Error = crate::Error;
+
+    fn try_from(address: Address) -> Result<Self, Self::Error> {
+        if address.0[0..19] != [0u8; 19] {
+            return Err(crate::Error::InternalError("not a precompile address"));
+        }
+        Ok(match address.0[19] {
+            0x01 => Self::ECRecover,
+            0x02 => Self::Sha256,
+            0x03 => Self::Ripemd160,
+            0x04 => Self::Identity,
+            0x05 => Self::Modexp,
+            0x06 => Self::Bn128Add,
+            0x07 => Self::Bn128Mul,
+            0x08 => Self::Bn128Pairing,
+            0x09 => Self::Blake2F,
+            _ => return Err(crate::Error::InternalError("not a precompile address")),
+        })
+    }
+}
+
+impl PrecompileCalls {
+    /// Length of the input read by the precompiles with a fixed size input,
+    /// and by MODEXP from the lengths at the start of its input, which is
+    /// right-padded with zeros or truncated to it.
+    pub fn padded_input_len(&self, input: &[u8]) -> Option<usize> {
+        match self {
+            Self::ECRecover | Self::Bn128Add => Some(128),
+            Self::Bn128Mul => Some(96),
+            Self::Modexp => Some(ModExpAuxData::new(input, &[]).padded_input_len()),
+            _ => None,
+        }
+    }
+}
+
+/// Result of executing a precompiled contract off-circuit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrecompileResult {
+    /// Output bytes, empty when the call fails.
+    pub output: Vec<u8>,
+    /// Gas required by the precompile for the given input.  It is 0 when the
+    /// input is invalid, in which case all the gas passed to the call is
+    /// consumed.
+    pub gas_cost: u64,
+    /// Whether the input is valid for the precompile.  A call with a valid
+    /// input can still fail when it runs out of gas.
+    pub is_success: bool,
+}
+
+/// Execute the precompile at `address` with `input`.  The precompile is run
+/// with unlimited gas so that its gas cost is always known, the caller is
+/// responsible for checking it against the gas available to the call.
+pub(crate) fn execute_precompiled(
+    address: &Address,
+    input: &[u8],
+    hardfork: Hardfork,
+) -> PrecompileResult {
+    let Some(Precompile::Standard(precompile_fn)) = precompiles(hardfork)
         .get(address.as_fixed_bytes())  else {
         panic!("calling non-exist precompiled contract address")
     };
 
-    match precompile_fn(input, gas) {
-        Ok((gas_cost, return_value)) => (return_value, gas_cost),
-        Err(_) => (vec![], gas),
+    match precompile_fn(input, u64::MAX) {
+        Ok((gas_cost, output)) => PrecompileResult {
+            output,
+            gas_cost,
+            is_success: true,
+        },
+        Err(_) => PrecompileResult::default(),
+    }
+}
+
+/// Event that records a single call to a precompiled contract, from which the
+/// step of the call is assigned.
+#[derive(Clone, Debug)]
+pub struct PrecompileEvent {
+    /// Precompile that was called.
+    pub kind: PrecompileCalls,
+    /// Input bytes.
+    pub input: Vec<u8>,
+    /// Output bytes, empty when the call fails.
+    pub output: Vec<u8>,
+    /// Gas required by the precompile for this input.
+    pub gas_cost: u64,
+    /// Whether the input is valid for the precompile.
+    pub is_success: bool,
+}
+
+/// Auxiliary data of a call to the ecRecover precompile, parsed from its input
+/// right-padded with zeros to 128 bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcrecoverAuxData {
+    /// Keccak hash of the signed message.
+    pub msg_hash: Word,
+    /// v-component of the signature.
+    pub sig_v: Word,
+    /// r-component of the signature.
+    pub sig_r: Word,
+    /// s-component of the signature.
+    pub sig_s: Word,
+    /// Recovered address, which is zero when the recovery fails.
+    pub recovered_addr: Address,
+}
+
+impl EcrecoverAuxData {
+    /// Create the auxiliary data from the input and the output of the call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(128, 0);
+        let recovered_addr = if output.len() == 32 {
+            Address::from_slice(&output[12..])
+        } else {
+            Address::zero()
+        };
+
+        Self {
+            msg_hash: Word::from_big_endian(&resized_input[0x00..0x20]),
+            sig_v: Word::from_big_endian(&resized_input[0x20..0x40]),
+            sig_r: Word::from_big_endian(&resized_input[0x40..0x60]),
+            sig_s: Word::from_big_endian(&resized_input[0x60..0x80]),
+            recovered_addr,
+        }
+    }
+
+    /// Message hash reduced modulo the secp256k1 order, which is the scalar
+    /// that is signed.
+    pub fn msg_hash_reduced(&self) -> Word {
+        Word::from_little_endian(&self.msg_hash_scalar().to_repr())
+    }
+
+    fn msg_hash_scalar(&self) -> secp256k1::Fq {
+        let mut msg_hash_le = [0u8; 64];
+        msg_hash_le[..32].copy_from_slice(&self.msg_hash.to_le_bytes());
+        secp256k1::Fq::from_uniform_bytes(&msg_hash_le)
+    }
+
+    /// Recovery id of the signature, or `None` if `v` is neither 27 nor 28.
+    pub fn recovery_id(&self) -> Option<u8> {
+        if self.sig_v == Word::from(27) || self.sig_v == Word::from(28) {
+            Some(self.sig_v.as_u64() as u8 - 27)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `v` is 27 or 28, and `r` and `s` are non-zero secp256k1
+    /// scalars, without which no address can be recovered.
+    pub fn is_well_formed(&self) -> bool {
+        self.recovery_id().is_some()
+            && [self.sig_r, self.sig_s].iter().all(|word| {
+                !word.is_zero()
+                    && bool::from(secp256k1::Fq::from_repr(word.to_le_bytes()).is_some())
+            })
+    }
+
+    /// Signature data to be verified by the SignVerify chip, or `None` if no
+    /// address was recovered.
+    pub fn sign_data(&self) -> Option<SignData> {
+        if self.recovered_addr.is_zero() {
+            return None;
+        }
+        let pk = recover_pk(
+            self.recovery_id()?,
+            &self.sig_r,
+            &self.sig_s,
+            &self.msg_hash.to_be_bytes(),
+        )
+        .ok()?;
+        let sig_r = Option::from(secp256k1::Fq::from_repr(self.sig_r.to_le_bytes()))?;
+        let sig_s = Option::from(secp256k1::Fq::from_repr(self.sig_s.to_le_bytes()))?;
+        Some(SignData {
+            signature: (sig_r, sig_s),
+            pk,
+            msg_hash: self.msg_hash_scalar(),
+        })
+    }
+}
+
+/// Maximum size in bytes of the base, the exponent and the modulus of the calls
+/// to the MODEXP precompile supported by the ModExp circuit.
+pub const MODEXP_SIZE_LIMIT: usize = 32;
+
+/// Auxiliary data of a call to the MODEXP precompile, parsed from its input
+/// right-padded with zeros.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModExpAuxData {
+    /// Length of the base in bytes.
+    pub base_len: Word,
+    /// Length of the exponent in bytes.
+    pub exp_len: Word,
+    /// Length of the modulus in bytes.
+    pub mod_len: Word,
+    /// Base, 0 when the call is not supported.
+    pub base: Word,
+    /// Exponent, 0 when the call is not supported.
+    pub exponent: Word,
+    /// Modulus, 0 when the call is not supported.
+    pub modulus: Word,
+    /// Output of the call, 0 when the call is not supported.
+    pub output: Word,
+}
+
+impl ModExpAuxData {
+    /// Create the auxiliary data from the input and the output of the call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        if resized_input.len() < 96 {
+            resized_input.resize(96, 0);
+        }
+        let [base_len, exp_len, mod_len] =
+            [0, 1, 2].map(|idx| Word::from_big_endian(&resized_input[idx * 32..(idx + 1) * 32]));
+        let mut aux_data = Self {
+            base_len,
+            exp_len,
+            mod_len,
+            ..Default::default()
+        };
+
+        if aux_data.is_supported() {
+            let [base_len, exp_len, mod_len] =
+                [base_len, exp_len, mod_len].map(|len| len.as_usize());
+            let mut data = resized_input[96..].to_vec();
+            data.resize(base_len + exp_len + mod_len, 0);
+            aux_data.base = Word::from_big_endian(&data[..base_len]);
+            aux_data.exponent = Word::from_big_endian(&data[base_len..base_len + exp_len]);
+            aux_data.modulus = Word::from_big_endian(&data[base_len + exp_len..]);
+            aux_data.output = Word::from_big_endian(output);
+        }
+
+        aux_data
+    }
+
+    /// Length of the lengths, the base, the exponent and the modulus, where
+    /// the last three are taken as empty when the call is not supported.
+    pub fn padded_input_len(&self) -> usize {
+        if self.is_supported() {
+            96 + [self.base_len, self.exp_len, self.mod_len]
+                .iter()
+                .map(|len| len.as_usize())
+                .sum::<usize>()
+        } else {
+            96
+        }
+    }
+
+    /// Whether the base, the exponent and the modulus fit in
+    /// [`MODEXP_SIZE_LIMIT`] bytes.
+    pub fn is_supported(&self) -> bool {
+        [self.base_len, self.exp_len, self.mod_len]
+            .iter()
+            .all(|len| *len <= Word::from(MODEXP_SIZE_LIMIT))
+    }
+}
+
+/// Intermediary modular multiplication step of a [`ModExpEvent`], representing
+/// `a * b == q * modulus + r`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModExpStep {
+    /// First multiplicand.
+    pub a: Word,
+    /// Second multiplicand.
+    pub b: Word,
+    /// Quotient of the product by the modulus.
+    pub q: Word,
+    /// Remainder of the product by the modulus.
+    pub r: Word,
+}
+
+/// Event representing a modular exponentiation `base ^ exponent == result (mod
+/// modulus)` with a non-zero modulus.
+#[derive(Clone, Debug)]
+pub struct ModExpEvent {
+    /// Base of the exponentiation.
+    pub base: Word,
+    /// Exponent of the exponentiation.
+    pub exponent: Word,
+    /// Modulus of the exponentiation.
+    pub modulus: Word,
+    /// Exponentiation result.
+    pub result: Word,
+    /// Intermediate modular multiplications, a squaring followed by a
+    /// multiplication by the base (or by 1) for each of the 256 bits of the
+    /// exponent, from the most significant one.
+    pub steps: Vec<ModExpStep>,
+}
+
+impl ModExpEvent {
+    /// Compute the modular exponentiation by squaring.
+    pub fn new(base: Word, exponent: Word, modulus: Word) -> Self {
+        debug_assert!(!modulus.is_zero(), "modulus should not be zero");
+        let mul_mod = |a: Word, b: Word| {
+            let (q, r) = a.full_mul(b).div_mod(U512::from(modulus));
+            ModExpStep {
+                a,
+                b,
+                q: Word::try_from(q).expect("quotient should fit in 256 bits"),
+                r: Word::try_from(r).expect("remainder should fit in 256 bits"),
+            }
+        };
+
+        let mut result = Word::one();
+        let mut steps = Vec::with_capacity(512);
+        for idx in (0..256).rev() {
+            let square = mul_mod(result, result);
+            let multiplier = if exponent.bit(idx) { base } else { Word::one() };
+            let multiply = mul_mod(square.r, multiplier);
+            result = multiply.r;
+            steps.extend([square, multiply]);
+        }
+
+        Self {
+            base,
+            exponent,
+            modulus,
+            result,
+            steps,
+        }
+    }
+}
+
+impl Default for ModExpEvent {
+    fn default() -> Self {
+        Self::new(2.into(), 2.into(), 3.into())
+    }
+}
+
+/// Auxiliary data of a call to the ecAdd precompile, parsed from its input
+/// right-padded with zeros to 128 bytes.  The point at infinity is encoded as
+/// (0, 0).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcAddAuxData {
+    /// x-coordinate of the first point.
+    pub p_x: Word,
+    /// y-coordinate of the first point.
+    pub p_y: Word,
+    /// x-coordinate of the second point.
+    pub q_x: Word,
+    /// y-coordinate of the second point.
+    pub q_y: Word,
+    /// x-coordinate of the sum of the points.
+    pub r_x: Word,
+    /// y-coordinate of the sum of the points.
+    pub r_y: Word,
+    /// Whether both points are valid, otherwise the call fails and the sum
+    /// is (0, 0).
+    pub is_valid: bool,
+}
+
+impl EcAddAuxData {
+    /// Create the auxiliary data from the input and the output of the call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(128, 0);
+        let mut resized_output = output.to_vec();
+        resized_output.resize(64, 0);
+
+        Self {
+            p_x: Word::from_big_endian(&resized_input[0x00..0x20]),
+            p_y: Word::from_big_endian(&resized_input[0x20..0x40]),
+            q_x: Word::from_big_endian(&resized_input[0x40..0x60]),
+            q_y: Word::from_big_endian(&resized_input[0x60..0x80]),
+            r_x: Word::from_big_endian(&resized_output[0x00..0x20]),
+            r_y: Word::from_big_endian(&resized_output[0x20..0x40]),
+            // Only the calls with an invalid input have an empty output.
+            is_valid: !output.is_empty(),
+        }
+    }
+}
+
+/// Auxiliary data of a call to the ecMul precompile, parsed from its input
+/// right-padded with zeros to 96 bytes.  The point at infinity is encoded as
+/// (0, 0).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcMulAuxData {
+    /// x-coordinate of the point.
+    pub p_x: Word,
+    /// y-coordinate of the point.
+    pub p_y: Word,
+    /// Scalar, which can be greater than the order of the group.
+    pub scalar: Word,
+    /// x-coordinate of the product of the point by the scalar.
+    pub r_x: Word,
+    /// y-coordinate of the product of the point by the scalar.
+    pub r_y: Word,
+    /// Whether the point is valid, otherwise the call fails and the product
+    /// is (0, 0).
+    pub is_valid: bool,
+}
+
+impl EcMulAuxData {
+    /// Create the auxiliary data from the input and the output of the call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        let mut resized_input = input.to_vec();
+        resized_input.resize(96, 0);
+        let mut resized_output = output.to_vec();
+        resized_output.resize(64, 0);
+
+        Self {
+            p_x: Word::from_big_endian(&resized_input[0x00..0x20]),
+            p_y: Word::from_big_endian(&resized_input[0x20..0x40]),
+            scalar: Word::from_big_endian(&resized_input[0x40..0x60]),
+            r_x: Word::from_big_endian(&resized_output[0x00..0x20]),
+            r_y: Word::from_big_endian(&resized_output[0x20..0x40]),
+            is_valid: !output.is_empty(),
+        }
+    }
+}
+
+/// Size in bytes of a pair of points in the input of the ecPairing precompile.
+pub const EC_PAIRING_PAIR_SIZE: usize = 192;
+
+/// Maximum number of pairs of points of the calls to the ecPairing precompile
+/// supported by the ECC circuit, which fails to synthesize a block with a
+/// call of more pairs.
+pub const EC_PAIRING_PAIRS_LIMIT: usize = 4;
+
+/// Pair of points in the input of a call to the ecPairing precompile.  The
+/// coordinates of the G2 point are elements of Fq2, encoded as `[imaginary,
+/// real]`, and the points at infinity are encoded with zero coordinates.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingPair {
+    /// x-coordinate of the G1 point.
+    pub g1_x: Word,
+    /// y-coordinate of the G1 point.
+    pub g1_y: Word,
+    /// x-coordinate of the G2 point.
+    pub g2_x: [Word; 2],
+    /// y-coordinate of the G2 point.
+    pub g2_y: [Word; 2],
+}
+
+impl EcPairingPair {
+    /// Create the pair from its 192 bytes in the input.
+    pub fn new(bytes: &[u8]) -> Self {
+        debug_assert_eq!(bytes.len(), EC_PAIRING_PAIR_SIZE);
+        let [g1_x, g1_y, g2_x_im, g2_x_re, g2_y_im, g2_y_re] =
+            [0, 1, 2, 3, 4, 5].map(|idx| Word::from_big_endian(&bytes[idx * 32..(idx + 1) * 32]));
+
+        Self {
+            g1_x,
+            g1_y,
+            g2_x: [g2_x_im, g2_x_re],
+            g2_y: [g2_y_im, g2_y_re],
+        }
+    }
+
+    /// Words of the pair in the order of the input.
+    pub fn words(&self) -> [Word; 6] {
+        [
+            self.g1_x,
+            self.g1_y,
+            self.g2_x[0],
+            self.g2_x[1],
+            self.g2_y[0],
+            self.g2_y[1],
+        ]
+    }
+
+    /// Bytes of the pair in the input.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.words()
+            .iter()
+            .flat_map(|word| word.to_be_bytes())
+            .collect()
+    }
+}
+
+/// Auxiliary data of a call to the ecPairing precompile, whose input length is
+/// a multiple of [`EC_PAIRING_PAIR_SIZE`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EcPairingAuxData {
+    /// Pairs of points of the input.
+    pub pairs: Vec<EcPairingPair>,
+    /// Whether the product of the pairings of the pairs is 1.
+    pub output: bool,
+    /// Whether all the points are valid, otherwise the call fails and the
+    /// output is false.
+    pub is_valid: bool,
+}
+
+impl EcPairingAuxData {
+    /// Create the auxiliary data from the input and the output of the call.
+    pub fn new(input: &[u8], output: &[u8]) -> Self {
+        debug_assert_eq!(input.len() % EC_PAIRING_PAIR_SIZE, 0);
+        Self {
+            pairs: input
+                .chunks(EC_PAIRING_PAIR_SIZE)
+                .map(EcPairingPair::new)
+                .collect(),
+            output: output.last() == Some(&1),
+            is_valid: !output.is_empty(),
+        }
+    }
+
+    /// Input of the call.
+    pub fn input(&self) -> Vec<u8> {
+        self.pairs.iter().flat_map(|pair| pair.to_bytes()).collect()
+    }
+
+    /// Whether the number of pairs is within [`EC_PAIRING_PAIRS_LIMIT`].
+    pub fn is_supported(&self) -> bool {
+        self.pairs.len() <= EC_PAIRING_PAIRS_LIMIT
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use eth_types::{address, sign_types::SECP256K1_Q, word};
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn precompile_address_roundtrip() {
+        assert_eq!(PrecompileCalls::iter().count(), PRECOMPILE_COUNT);
+        for precompile in PrecompileCalls::iter() {
+            let address = Address::from(precompile);
+            assert!(is_precompiled(&address, Hardfork::default()));
+            assert_eq!(PrecompileCalls::try_from(address).unwrap(), precompile);
+        }
+        assert!(PrecompileCalls::try_from(Address::zero()).is_err());
+    }
+
+    #[test]
+    fn ecrecover_aux_data() {
+        let mut input = hex::decode(
+            "18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c\
+             000000000000000000000000000000000000000000000000000000000000001c\
+             73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75f\
+             eeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549",
+        )
+        .unwrap();
+        let result = execute_precompiled(
+            &PrecompileCalls::ECRecover.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcrecoverAuxData::new(&input, &result.output);
+        assert_eq!(aux_data.sig_v, Word::from(28));
+        assert_eq!(aux_data.recovery_id(), Some(1));
+        assert_eq!(
+            aux_data.recovered_addr,
+            address!("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b")
+        );
+        assert!(aux_data.is_well_formed());
+        assert!(aux_data.sign_data().is_some());
+        assert_eq!(aux_data.msg_hash_reduced(), aux_data.msg_hash);
+
+        // A message hash not less than the secp256k1 order is reduced.
+        let msg_hash = Word::from_little_endian(&SECP256K1_Q.to_bytes_le());
+        let aux_data = EcrecoverAuxData {
+            msg_hash: msg_hash + Word::from(7),
+            ..aux_data
+        };
+        assert_eq!(aux_data.msg_hash_reduced(), Word::from(7));
+
+        // An invalid v recovers no address.
+        input[63] = 29;
+        let result = execute_precompiled(
+            &PrecompileCalls::ECRecover.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcrecoverAuxData::new(&input, &result.output);
+        assert!(result.output.is_empty());
+        assert_eq!(aux_data.recovery_id(), None);
+        assert_eq!(aux_data.recovered_addr, Address::zero());
+        assert!(!aux_data.is_well_formed());
+        assert!(aux_data.sign_data().is_none());
+    }
+
+    #[test]
+    fn ec_aux_data() {
+        let g1 = "0000000000000000000000000000000000000000000000000000000000000001\
+                  0000000000000000000000000000000000000000000000000000000000000002";
+        let g1_double = [
+            word!("0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"),
+            word!("0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"),
+        ];
+
+        // G1 + G1, where the input is the first point followed by the second.
+        let input = hex::decode(g1.repeat(2)).unwrap();
+        let result = execute_precompiled(
+            &PrecompileCalls::Bn128Add.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcAddAuxData::new(&input, &result.output);
+        assert_eq!([aux_data.p_x, aux_data.p_y], [Word::from(1), Word::from(2)]);
+        assert_eq!([aux_data.r_x, aux_data.r_y], g1_double);
+        assert!(aux_data.is_valid);
+
+        // The second point is not on the curve.
+        let mut input = input;
+        input[127] = 0x03;
+        let result = execute_precompiled(
+            &PrecompileCalls::Bn128Add.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcAddAuxData::new(&input, &result.output);
+        assert!(!aux_data.is_valid);
+        assert_eq!([aux_data.r_x, aux_data.r_y], [Word::zero(); 2]);
+
+        // 2 * G1
+        let mut input = hex::decode(g1).unwrap();
+        input.extend(Word::from(2).to_be_bytes());
+        let result = execute_precompiled(
+            &PrecompileCalls::Bn128Mul.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcMulAuxData::new(&input, &result.output);
+        assert_eq!(aux_data.scalar, Word::from(2));
+        assert_eq!([aux_data.r_x, aux_data.r_y], g1_double);
+
+        // The scalar is right-padded with zeros.
+        input.pop();
+        let aux_data = EcMulAuxData::new(&input, &[]);
+        assert_eq!(aux_data.scalar, Word::zero());
+        input[94] = 0x01;
+        let aux_data = EcMulAuxData::new(&input, &[]);
+        assert_eq!(aux_data.scalar, Word::from(0x100));
+
+        // e(G1, G2) * e(-G1, G2) == 1
+        let g2 = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+                  1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+                  090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+                  12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+        let g1_neg = "0000000000000000000000000000000000000000000000000000000000000001\
+                      30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+        let input = hex::decode([g1, g2, g1_neg, g2].concat()).unwrap();
+        let result = execute_precompiled(
+            &PrecompileCalls::Bn128Pairing.into(),
+            &input,
+            Hardfork::default(),
+        );
+        let aux_data = EcPairingAuxData::new(&input, &result.output);
+        assert!(aux_data.output);
+        assert!(aux_data.is_valid);
+        assert!(aux_data.is_supported());
+        assert_eq!(aux_data.pairs.len(), 2);
+        assert_eq!(aux_data.pairs[0].g1_y, Word::from(2));
+        assert_eq!(
+            aux_data.pairs[1].g2_x[0],
+            word!("0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2")
+        );
+        assert_eq!(aux_data.input(), input);
+
+        // e(G1, G2) != 1
+        let input = hex::decode([g1, g2].concat()).unwrap();
+        let result = execute_precompiled(
+            &PrecompileCalls::Bn128Pairing.into(),
+            &input,
+            Hardfork::default(),
+        );
+        assert!(!EcPairingAuxData::new(&input, &result.output).output);
+    }
+
+    #[test]
+    fn modexp_aux_data() {
+        // 3 ^ 0x1234 mod 0x10001, with extra input bytes that are ignored.
+        let mut input = vec![0u8; 96];
+        input[31] = 1;
+        input[63] = 2;
+        input[95] = 3;
+        input.extend([0x03, 0x12, 0x34, 0x01, 0x00, 0x01, 0xff]);
+        let result =
+            execute_precompiled(&PrecompileCalls::Modexp.into(), &input, Hardfork::default());
+        let aux_data = ModExpAuxData::new(&input, &result.output);
+        assert!(aux_data.is_supported());
+        assert_eq!(aux_data.padded_input_len(), 102);
+        assert_eq!(aux_data.base, Word::from(3));
+        assert_eq!(aux_data.exponent, Word::from(0x1234));
+        assert_eq!(aux_data.modulus, Word::from(0x10001));
+
+        let event = ModExpEvent::new(aux_data.base, aux_data.exponent, aux_data.modulus);
+        assert_eq!(event.steps.len(), 512);
+        assert_eq!(event.result, aux_data.output);
+
+        // The modulus is truncated by the end of the input.
+        input.truncate(100);
+        let result =
+            execute_precompiled(&PrecompileCalls::Modexp.into(), &input, Hardfork::default());
+        let aux_data = ModExpAuxData::new(&input, &result.output);
+        assert_eq!(aux_data.modulus, Word::from(0x10000));
+        let event = ModExpEvent::new(aux_data.base, aux_data.exponent, aux_data.modulus);
+        assert_eq!(event.result, aux_data.output);
+
+        // A length over 32 bytes is not supported.
+        input[95] = 33;
+        let aux_data = ModExpAuxData::new(&input, &[]);
+        assert!(!aux_data.is_supported());
+        assert_eq!(aux_data.padded_input_len(), 96);
+        assert_eq!(aux_data.modulus, Word::zero());
     }
 }
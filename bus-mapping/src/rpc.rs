@@ -101,6 +101,22 @@ impl<P: JsonRpcClient> GethClient<P> {
             .map_err(|e| Error::JSONRpcError(e.into()))
     }
 
+    /// Calls `eth_getUncleByBlockHashAndIndex` via JSON-RPC returning the
+    /// uncle at `index` of the block with hash `hash` as a [`Block`] without
+    /// transactions.
+    pub async fn get_uncle_by_block_hash_and_index(
+        &self,
+        hash: Hash,
+        index: U64,
+    ) -> Result<Block<Hash>, Error> {
+        let hash = serialize(&hash);
+        let index = serialize(&index);
+        self.0
+            .request("eth_getUncleByBlockHashAndIndex", [hash, index])
+            .await
+            .map_err(|e| Error::JSONRpcError(e.into()))
+    }
+
     /// Calls `debug_traceBlockByHash` via JSON-RPC returning a
     /// [`Vec<GethExecTrace>`] with each GethTrace corresponding to 1
     /// transaction of the block.
@@ -109,8 +109,14 @@ pub struct StateDB {
     // state before current transaction, to calculate gas cost for some opcodes like sstore.
     // So both dirty storage and committed storage are needed.
     dirty_storage: HashMap<(Address, Word), Word>,
-    // Accounts that have been through `SELFDESTRUCT` under the situation that `is_persistent` is
-    // `true`. These accounts will be reset once `commit_tx` is called.
+    // `transient_storage` contains the EIP-1153 storage, which is discarded when
+    // current transaction finishes.
+    transient_storage: HashMap<(Address, Word), Word>,
+    // Accounts that have been created in current transaction, which are the
+    // only ones that `SELFDESTRUCT` deletes since EIP-6780.
+    created_account: HashSet<Address>,
+    // Accounts that have been through `SELFDESTRUCT` in current transaction,
+    // which are deleted by the `DestructAccount` steps at its end.
     destructed_account: HashSet<Address>,
     refund: u64,
 }
@@ -196,6 +202,21 @@ impl StateDB {
         self.dirty_storage.insert((*addr, *key), *value);
     }
 
+    /// Get a reference to the transient storage value at `addr` and `key`.
+    /// Returns false and a zero [`Word`] when the `key` wasn't written in
+    /// current tx.
+    pub fn get_transient_storage(&self, addr: &Address, key: &Word) -> (bool, &Word) {
+        match self.transient_storage.get(&(*addr, *key)) {
+            Some(v) => (true, v),
+            None => (false, &VALUE_ZERO),
+        }
+    }
+
+    /// Set transient storage value at `addr` and `key`.
+    pub fn set_transient_storage(&mut self, addr: &Address, key: &Word, value: &Word) {
+        self.transient_storage.insert((*addr, *key), *value);
+    }
+
     /// Get nonce of account with `addr`.
     pub fn get_nonce(&self, addr: &Address) -> u64 {
         let (_, account) = self.get_account(addr);
@@ -244,9 +265,48 @@ impl StateDB {
         debug_assert!(exist);
     }
 
-    /// Set account as self destructed.
-    pub fn destruct_account(&mut self, addr: Address) {
-        self.destructed_account.insert(addr);
+    /// Check whether `addr` was created in current transaction.
+    pub fn is_account_created(&self, addr: &Address) -> bool {
+        self.created_account.contains(addr)
+    }
+
+    /// Set whether `addr` was created in current transaction.
+    pub fn set_account_created(&mut self, addr: Address, is_created: bool) {
+        if is_created {
+            self.created_account.insert(addr);
+        } else {
+            self.created_account.remove(&addr);
+        }
+    }
+
+    /// Check whether `addr` was self destructed in current transaction.
+    pub fn is_account_destructed(&self, addr: &Address) -> bool {
+        self.destructed_account.contains(addr)
+    }
+
+    /// Set whether `addr` was self destructed in current transaction.
+    pub fn set_account_destructed(&mut self, addr: Address, is_destructed: bool) {
+        if is_destructed {
+            self.destructed_account.insert(addr);
+        } else {
+            self.destructed_account.remove(&addr);
+        }
+    }
+
+    /// Return the accounts self destructed in current transaction sorted by
+    /// address.
+    pub fn destructed_accounts(&self) -> Vec<Address> {
+        let mut addrs: Vec<_> = self.destructed_account.iter().copied().collect();
+        addrs.sort();
+        addrs
+    }
+
+    /// Delete the account at `addr` along with the writes to its storage in
+    /// current transaction.
+    pub fn destruct_account(&mut self, addr: &Address) {
+        self.dirty_storage
+            .retain(|(dirty_addr, _), _| dirty_addr != addr);
+        self.state.insert(*addr, ACCOUNT_ZERO.clone());
     }
 
     /// Retrieve refund.
@@ -259,7 +319,8 @@ impl StateDB {
         self.refund = value;
     }
 
-    /// Clear access list and refund, and commit dirty storage.
+    /// Clear access list, refund, transient storage and created accounts, and
+    /// commit dirty storage.
     /// It should be invoked before processing
     /// with new transaction with the same [`StateDB`].
     pub fn commit_tx(&mut self) {
@@ -270,10 +331,9 @@ impl StateDB {
             *ptr = value;
         }
         self.dirty_storage = HashMap::new();
-        for addr in self.destructed_account.clone() {
-            let (_, account) = self.get_account_mut(&addr);
-            *account = ACCOUNT_ZERO.clone();
-        }
+        self.transient_storage = HashMap::new();
+        self.created_account = HashSet::new();
+        debug_assert!(self.destructed_account.is_empty());
         self.refund = 0;
     }
 }
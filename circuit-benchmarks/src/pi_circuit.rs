@@ -2,7 +2,7 @@
 #[cfg(test)]
 mod tests {
     use ark_std::{end_timer, start_timer};
-    use eth_types::Word;
+    use eth_types::{geth_types::Receipt, Word};
     use halo2_proofs::{
         arithmetic::Field,
         halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -126,9 +126,14 @@ mod tests {
         public_data.chain_id = Word::from(chain_id);
 
         let n_tx = max_txs;
-        for _ in 0..n_tx {
+        for i in 0..n_tx {
             let eth_tx = eth_types::Transaction::from(mock::CORRECT_MOCK_TXS[0].clone());
             public_data.transactions.push(eth_tx);
+            public_data.receipts.push(Receipt {
+                status: 1,
+                cumulative_gas_used: 21000 * (i as u64 + 1),
+                ..Default::default()
+            });
         }
         public_data
     }
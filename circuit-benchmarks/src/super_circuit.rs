@@ -4,7 +4,12 @@
 mod tests {
     use ark_std::{end_timer, start_timer};
     use bus_mapping::circuit_input_builder::CircuitsParams;
-    use eth_types::{address, bytecode, geth_types::GethData, Word};
+    use eth_types::{
+        address, bytecode,
+        evm_types::{BlockReward, Hardfork},
+        geth_types::GethData,
+        Word,
+    };
     use ethers_signers::{LocalWallet, Signer};
     use halo2_proofs::{
         halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -88,6 +93,15 @@ mod tests {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            hardfork: Hardfork::default(),
+            block_reward: BlockReward::default(),
         };
         let (_, circuit, instance, _) =
             SuperCircuit::build(block, circuits_params, Fr::from(0x100)).unwrap();
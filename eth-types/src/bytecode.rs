@@ -96,7 +96,7 @@ impl Bytecode {
 
     /// Push
     pub fn push<T: ToWord>(&mut self, n: u8, value: T) -> &mut Self {
-        debug_assert!((1..=32).contains(&n), "invalid push");
+        debug_assert!((0..=32).contains(&n), "invalid push");
         let value = value.to_word();
 
         // Write the op code
@@ -218,7 +218,8 @@ impl FromStr for OpcodeWithData {
     #[allow(clippy::manual_range_contains)]
     fn from_str(op: &str) -> Result<Self, Self::Err> {
         let err = || Error::InvalidAsmError(op.to_string());
-        if let Some(push) = op.strip_prefix("PUSH") {
+        // `PUSH0` has no immediate data and is parsed as a plain opcode
+        if let Some(push) = op.strip_prefix("PUSH").filter(|push| *push != "0") {
             let n_value: Vec<_> = push.splitn(3, ['(', ')']).collect();
             let n = n_value[0].parse::<u8>().map_err(|_| err())?;
             if n < 1 || n > 32 {
@@ -255,7 +256,7 @@ impl<'a> Iterator for BytecodeIterator<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         self.0.next().map(|byte| {
             let op = OpcodeId::from(byte.value);
-            if op.is_push() {
+            if op.is_push_with_data() {
                 let n = op.data_len();
                 let mut value = vec![0u8; n];
                 for value_byte in value.iter_mut() {
@@ -277,7 +278,7 @@ impl From<Vec<u8>> for Bytecode {
         while let Some(byte) = input_iter.next() {
             let op = OpcodeId::from(*byte);
             code.write_op(op);
-            if op.is_push() {
+            if op.is_push_with_data() {
                 let n = op.postfix().expect("opcode with postfix");
                 for _ in 0..n {
                     match input_iter.next() {
@@ -315,14 +316,14 @@ macro_rules! bytecode_internal {
     ($code:ident, ) => {};
     // PUSHX op codes
     ($code:ident, $x:ident ($v:expr) $($rest:tt)*) => {{
-        debug_assert!($crate::evm_types::OpcodeId::$x.is_push(), "invalid push");
+        debug_assert!($crate::evm_types::OpcodeId::$x.is_push_with_data(), "invalid push");
         let n = $crate::evm_types::OpcodeId::$x.postfix().expect("opcode with postfix");
         $code.push(n, $v);
         $crate::bytecode_internal!($code, $($rest)*);
     }};
     // Default opcode without any inputs
     ($code:ident, $x:ident $($rest:tt)*) => {{
-        debug_assert!(!$crate::evm_types::OpcodeId::$x.is_push(), "invalid push");
+        debug_assert!(!$crate::evm_types::OpcodeId::$x.is_push_with_data(), "invalid push");
         $code.write_op($crate::evm_types::OpcodeId::$x);
         $crate::bytecode_internal!($code, $($rest)*);
     }};
@@ -543,11 +544,13 @@ mod tests {
             PUSH24(0x321)
             PUSH32(0x432)
             MUL
+            PUSH0
             CALLVALUE
             CALLER
             POP
             POP
             POP
+            POP
             STOP
         };
         assert_eq!(Bytecode::try_from(code.to_vec()).unwrap(), code);
@@ -559,6 +562,8 @@ mod tests {
             PUSH1(5)
             PUSH2(0xa)
             MUL
+            PUSH0
+            ADD
             STOP
         };
         let mut code2 = Bytecode::default();
@@ -1,5 +1,6 @@
 //! Error module for the eth-types crate
 
+use crate::H256;
 use core::fmt::{Display, Formatter, Result as FmtResult};
 use std::error::Error as StdError;
 
@@ -33,6 +34,10 @@ pub enum Error {
     WordToMemAddr,
     /// Signature parsing error.
     Signature(libsecp256k1::Error),
+    /// Error while trying to access a trie node which isn't known.
+    TrieNodeNotFound(H256),
+    /// Error when a trie node is invalid or not supported.
+    InvalidTrieNode(Vec<u8>),
 }
 
 impl From<libsecp256k1::Error> for Error {
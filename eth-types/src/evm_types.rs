@@ -3,24 +3,30 @@
 // use serde::{Deserialize, Serialize};
 // use std::fmt;
 
+pub mod block_reward;
 pub mod gas_utils;
+pub mod hardfork;
 pub mod memory;
 pub mod opcode_ids;
 pub mod stack;
 pub mod storage;
 
+pub use block_reward::BlockReward;
+pub use hardfork::Hardfork;
 pub use memory::{Memory, MemoryAddress};
 pub use opcode_ids::OpcodeId;
 pub use stack::{Stack, StackAddress};
 pub use storage::Storage;
 
-/// Once per word of the init code when creating a contract.
-pub const INIT_CODE_WORD_GAS: u64 = 2;
-/// Quotient for max refund of gas used
-pub const MAX_REFUND_QUOTIENT_OF_GAS_USED: usize = 5;
 /// Gas stipend when CALL or CALLCODE is attached with value.
 pub const GAS_STIPEND_CALL_WITH_VALUE: u64 = 2300;
 
+/// Maximum number of blobs a transaction can carry (EIP-4844).
+pub const MAX_BLOBS_PER_TX: usize = 6;
+
+/// Maximum size of the init code of a contract creation (EIP-3860).
+pub const MAX_INIT_CODE_SIZE: u64 = 49152;
+
 /// Defines the gas consumption.
 pub struct GasCost;
 
@@ -64,13 +70,16 @@ impl GasCost {
     pub const SSTORE_SET: u64 = 20000;
     /// Constant cost for a storage reset
     pub const SSTORE_RESET: u64 = 2900;
-    /// Constant cost for a storage clear. EIP-3529 changed it to 4800 from
-    /// 15000.
-    pub const SSTORE_CLEARS_SCHEDULE: u64 = 4800;
     /// Constant cost for a non-creation transaction
     pub const TX: u64 = 21000;
     /// Constant cost for a creation transaction
     pub const CREATION_TX: u64 = 53000;
+    /// Constant cost per address in the access list of a transaction
+    /// (EIP-2930)
+    pub const ACCESS_LIST_PER_ADDRESS: u64 = 2400;
+    /// Constant cost per storage key in the access list of a transaction
+    /// (EIP-2930)
+    pub const ACCESS_LIST_PER_STORAGE_KEY: u64 = 1900;
     /// Constant cost for calling with non-zero value
     pub const CALL_WITH_VALUE: u64 = 9000;
     /// Constant cost for turning empty account into non-empty account
@@ -86,4 +95,6 @@ impl GasCost {
     /// Times ceil exponent byte size for the EXP instruction, EIP-158 changed
     /// it from 10 to 50.
     pub const EXP_BYTE_TIMES: u64 = 50;
+    /// Blob gas consumed by every blob of a transaction (EIP-4844)
+    pub const PER_BLOB: u64 = 131072;
 }
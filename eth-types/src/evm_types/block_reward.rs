@@ -0,0 +1,78 @@
+//! Rewards credited at the end of a block to the miners of the block and of
+//! its uncles.
+
+use super::Hardfork;
+use crate::Word;
+use serde::{Deserialize, Serialize};
+
+/// Reward of the miner of a block under Ethash since Constantinople
+/// (EIP-1234), which is 2 ETH.
+pub const ETHASH_BLOCK_REWARD: u64 = 2_000_000_000_000_000_000;
+
+/// Rewards that the consensus of the network credits at the end of a block.
+/// The miner of the block gets the block reward plus 1/32 of it per included
+/// uncle, and the miner of an uncle gets 1/8 of the block reward per block
+/// left from the uncle depth to 8.  There are no rewards from the merge on.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BlockReward {
+    /// The Ethash rewards of the mainnet
+    #[default]
+    Ethash,
+    /// A fixed block reward, for the proof of work networks with their own
+    /// issuance
+    Fixed(Word),
+    /// No rewards, as in the Clique proof of authority networks
+    NoReward,
+}
+
+impl BlockReward {
+    /// Reward of the miner of a block under the rules of the `hardfork`,
+    /// without the rewards for the uncles.
+    pub fn block_reward(&self, hardfork: Hardfork) -> Word {
+        if hardfork >= Hardfork::Paris {
+            return Word::zero();
+        }
+        match self {
+            Self::Ethash => Word::from(ETHASH_BLOCK_REWARD),
+            Self::Fixed(reward) => *reward,
+            Self::NoReward => Word::zero(),
+        }
+    }
+
+    /// Reward of the miner of a block for each uncle it includes.
+    pub fn nephew_reward(&self, hardfork: Hardfork) -> Word {
+        self.block_reward(hardfork) / 32
+    }
+
+    /// Reward of the miner of the uncle `uncle_number` included in the block
+    /// `number`.
+    pub fn uncle_reward(&self, hardfork: Hardfork, number: u64, uncle_number: u64) -> Word {
+        self.block_reward(hardfork) * (uncle_number + 8 - number) / 8
+    }
+}
+
+#[cfg(test)]
+mod block_reward_tests {
+    use super::*;
+
+    #[test]
+    fn ethash_rewards() {
+        let reward = BlockReward::Ethash;
+        assert_eq!(
+            reward.block_reward(Hardfork::London),
+            Word::from(ETHASH_BLOCK_REWARD)
+        );
+        assert_eq!(
+            reward.nephew_reward(Hardfork::London),
+            Word::from(62_500_000_000_000_000u64)
+        );
+        assert_eq!(
+            reward.uncle_reward(Hardfork::London, 100, 99),
+            Word::from(1_750_000_000_000_000_000u64)
+        );
+        assert!(reward.block_reward(Hardfork::Paris).is_zero());
+        assert!(BlockReward::NoReward
+            .block_reward(Hardfork::Berlin)
+            .is_zero());
+    }
+}
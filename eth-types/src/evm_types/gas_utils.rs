@@ -47,3 +47,26 @@ pub fn eip150_gas(gas_left: u64, gas_specified: Word) -> u64 {
 
     capped_gas
 }
+
+/// Calculate the blob base fee of a block from its excess blob gas, as
+/// specified by EIP-4844.
+pub fn blob_base_fee(excess_blob_gas: u64) -> Word {
+    const MIN_BLOB_BASE_FEE: u64 = 1;
+    const BLOB_BASE_FEE_UPDATE_FRACTION: u64 = 3338477;
+
+    // Approximate `factor * e ** (numerator / denominator)` with its Taylor
+    // expansion.
+    let factor = Word::from(MIN_BLOB_BASE_FEE);
+    let numerator = Word::from(excess_blob_gas);
+    let denominator = Word::from(BLOB_BASE_FEE_UPDATE_FRACTION);
+    let mut output = Word::zero();
+    let mut numerator_accum = factor * denominator;
+    let mut i = Word::one();
+    while !numerator_accum.is_zero() {
+        output += numerator_accum;
+        numerator_accum = numerator_accum * numerator / (denominator * i);
+        i += Word::one();
+    }
+
+    output / denominator
+}
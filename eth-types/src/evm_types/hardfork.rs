@@ -0,0 +1,107 @@
+//! Hardforks of the Ethereum mainnet and the execution rules they select.
+
+use super::MAX_INIT_CODE_SIZE;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
+
+/// Hardfork whose execution rules a block follows. Only the forks from Berlin
+/// on are supported, the variants are ordered chronologically.  London is the
+/// default, the later forks are opted in explicitly.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Eq,
+    PartialEq,
+    Ord,
+    PartialOrd,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumIter,
+)]
+pub enum Hardfork {
+    /// Berlin (EIP-2929 and EIP-2930)
+    Berlin,
+    /// London (EIP-1559, EIP-3198, EIP-3529 and EIP-3541)
+    #[default]
+    London,
+    /// Paris, the merge (EIP-3675 and EIP-4399)
+    Paris,
+    /// Shanghai (EIP-3651, EIP-3855, EIP-3860 and EIP-4895)
+    Shanghai,
+    /// Cancun (EIP-1153, EIP-4844, EIP-5656, EIP-6780 and EIP-7516), without
+    /// the beacon block root of EIP-4788
+    Cancun,
+}
+
+impl Hardfork {
+    /// Quotient of the gas used that bounds the refund at the end of a
+    /// transaction. EIP-3529 changed it to 5 from 2.
+    pub fn max_refund_quotient_of_gas_used(&self) -> u64 {
+        if *self >= Self::London {
+            5
+        } else {
+            2
+        }
+    }
+
+    /// Refund for clearing a storage slot. EIP-3529 changed it to 4800 from
+    /// 15000.
+    pub fn sstore_clears_schedule(&self) -> u64 {
+        if *self >= Self::London {
+            4800
+        } else {
+            15000
+        }
+    }
+
+    /// Refund for the first SELFDESTRUCT of an account in a transaction.
+    /// EIP-3529 removed it.
+    pub fn selfdestruct_refund(&self) -> u64 {
+        if *self >= Self::London {
+            0
+        } else {
+            24000
+        }
+    }
+
+    /// Returns whether SELFDESTRUCT only deletes the account when it was
+    /// created in the same transaction, which was introduced by EIP-6780.
+    pub fn has_eip6780(&self) -> bool {
+        *self >= Self::Cancun
+    }
+
+    /// Gas charged once per word of the init code when creating a contract,
+    /// which was introduced by EIP-3860.
+    pub fn init_code_word_gas(&self) -> u64 {
+        if *self >= Self::Shanghai {
+            2
+        } else {
+            0
+        }
+    }
+
+    /// Maximum size of the init code when creating a contract, which was
+    /// introduced by EIP-3860.  The init code is unbounded before Shanghai.
+    pub fn max_init_code_size(&self) -> Option<u64> {
+        if *self >= Self::Shanghai {
+            Some(MAX_INIT_CODE_SIZE)
+        } else {
+            None
+        }
+    }
+
+    /// Returns whether the coinbase is warm at the start of a transaction,
+    /// which was introduced by EIP-3651.
+    pub fn is_coinbase_warm(&self) -> bool {
+        *self >= Self::Shanghai
+    }
+
+    /// Returns whether the DIFFICULTY opcode returns the prevRandao of the
+    /// block instead of its difficulty, which was introduced by EIP-4399.
+    pub fn has_prevrandao(&self) -> bool {
+        *self >= Self::Paris
+    }
+}
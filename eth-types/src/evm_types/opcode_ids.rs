@@ -1,5 +1,8 @@
 //! Doc this
-use crate::{error::Error, evm_types::GasCost};
+use crate::{
+    error::Error,
+    evm_types::{GasCost, Hardfork},
+};
 use core::fmt::Debug;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -95,6 +98,8 @@ pub enum OpcodeId {
     JUMPDEST,
 
     // PUSHn
+    /// `PUSH0`
+    PUSH0,
     /// `PUSH1`
     PUSH1,
     /// `PUSH2`
@@ -279,12 +284,22 @@ pub enum OpcodeId {
     SELFBALANCE,
     /// `BASEFEE`
     BASEFEE,
+    /// `BLOBHASH`
+    BLOBHASH,
+    /// `BLOBBASEFEE`
+    BLOBBASEFEE,
     /// `SLOAD`
     SLOAD,
     /// `SSTORE`
     SSTORE,
     /// `GAS`
     GAS,
+    /// `TLOAD`
+    TLOAD,
+    /// `TSTORE`
+    TSTORE,
+    /// `MCOPY`
+    MCOPY,
 
     // LOGn
     /// `LOG0`
@@ -315,8 +330,14 @@ pub enum OpcodeId {
 }
 
 impl OpcodeId {
-    /// Returns `true` if the `OpcodeId` is a `PUSHn`.
+    /// Returns `true` if the `OpcodeId` is a `PUSHn`, including `PUSH0`.
     pub fn is_push(&self) -> bool {
+        self.as_u8() >= Self::PUSH0.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
+    }
+
+    /// Returns `true` if the `OpcodeId` is a `PUSHn` followed by immediate
+    /// data, which excludes `PUSH0`.
+    pub fn is_push_with_data(&self) -> bool {
         self.as_u8() >= Self::PUSH1.as_u8() && self.as_u8() <= Self::PUSH32.as_u8()
     }
 
@@ -402,6 +423,7 @@ impl OpcodeId {
             OpcodeId::PC => 0x58u8,
             OpcodeId::MSIZE => 0x59u8,
             OpcodeId::JUMPDEST => 0x5bu8,
+            OpcodeId::PUSH0 => 0x5fu8,
             OpcodeId::PUSH1 => 0x60u8,
             OpcodeId::PUSH2 => 0x61u8,
             OpcodeId::PUSH3 => 0x62u8,
@@ -490,9 +512,14 @@ impl OpcodeId {
             OpcodeId::CHAINID => 0x46u8,
             OpcodeId::SELFBALANCE => 0x47u8,
             OpcodeId::BASEFEE => 0x48u8,
+            OpcodeId::BLOBHASH => 0x49u8,
+            OpcodeId::BLOBBASEFEE => 0x4au8,
             OpcodeId::SLOAD => 0x54u8,
             OpcodeId::SSTORE => 0x55u8,
             OpcodeId::GAS => 0x5au8,
+            OpcodeId::TLOAD => 0x5cu8,
+            OpcodeId::TSTORE => 0x5du8,
+            OpcodeId::MCOPY => 0x5eu8,
             OpcodeId::LOG0 => 0xa0u8,
             OpcodeId::LOG1 => 0xa1u8,
             OpcodeId::LOG2 => 0xa2u8,
@@ -568,18 +595,24 @@ impl OpcodeId {
             OpcodeId::CHAINID => GasCost::QUICK,
             OpcodeId::SELFBALANCE => GasCost::FAST,
             OpcodeId::BASEFEE => GasCost::QUICK,
+            OpcodeId::BLOBHASH => GasCost::FASTEST,
+            OpcodeId::BLOBBASEFEE => GasCost::QUICK,
             OpcodeId::POP => GasCost::QUICK,
             OpcodeId::MLOAD => GasCost::FASTEST,
             OpcodeId::MSTORE => GasCost::FASTEST,
             OpcodeId::MSTORE8 => GasCost::FASTEST,
             OpcodeId::SLOAD => GasCost::ZERO,
             OpcodeId::SSTORE => GasCost::ZERO,
+            OpcodeId::TLOAD => GasCost::WARM_ACCESS,
+            OpcodeId::TSTORE => GasCost::WARM_ACCESS,
+            OpcodeId::MCOPY => GasCost::FASTEST,
             OpcodeId::JUMP => GasCost::MID,
             OpcodeId::JUMPI => GasCost::SLOW,
             OpcodeId::PC => GasCost::QUICK,
             OpcodeId::MSIZE => GasCost::QUICK,
             OpcodeId::GAS => GasCost::QUICK,
             OpcodeId::JUMPDEST => GasCost::ONE,
+            OpcodeId::PUSH0 => GasCost::QUICK,
             OpcodeId::PUSH1 => GasCost::FASTEST,
             OpcodeId::PUSH2 => GasCost::FASTEST,
             OpcodeId::PUSH3 => GasCost::FASTEST,
@@ -722,18 +755,24 @@ impl OpcodeId {
             OpcodeId::CHAINID => (1, 1024),
             OpcodeId::SELFBALANCE => (1, 1024),
             OpcodeId::BASEFEE => (1, 1024),
+            OpcodeId::BLOBHASH => (0, 1023),
+            OpcodeId::BLOBBASEFEE => (1, 1024),
             OpcodeId::POP => (0, 1023),
             OpcodeId::MLOAD => (0, 1023),
             OpcodeId::MSTORE => (0, 1022),
             OpcodeId::MSTORE8 => (0, 1022),
             OpcodeId::SLOAD => (0, 1023),
             OpcodeId::SSTORE => (0, 1022),
+            OpcodeId::TLOAD => (0, 1023),
+            OpcodeId::TSTORE => (0, 1022),
+            OpcodeId::MCOPY => (0, 1021),
             OpcodeId::JUMP => (0, 1023),
             OpcodeId::JUMPI => (0, 1022),
             OpcodeId::PC => (1, 1024),
             OpcodeId::MSIZE => (1, 1024),
             OpcodeId::GAS => (1, 1024),
             OpcodeId::JUMPDEST => (0, 1024),
+            OpcodeId::PUSH0 => (1, 1024),
             OpcodeId::PUSH1 => (1, 1024),
             OpcodeId::PUSH2 => (1, 1024),
             OpcodeId::PUSH3 => (1, 1024),
@@ -835,13 +874,14 @@ impl OpcodeId {
                 | OpcodeId::RETURNDATACOPY
                 | OpcodeId::CODECOPY
                 | OpcodeId::EXTCODECOPY
+                | OpcodeId::MCOPY
         )
     }
 
     /// Returns PUSHn opcode from parameter n.
     pub fn push_n(n: u8) -> Result<Self, Error> {
-        if (1..=32).contains(&n) {
-            Ok(OpcodeId::from(OpcodeId::PUSH1.as_u8() + n - 1))
+        if (0..=32).contains(&n) {
+            Ok(OpcodeId::from(OpcodeId::PUSH0.as_u8() + n))
         } else {
             Err(Error::InvalidOpConversion)
         }
@@ -850,7 +890,7 @@ impl OpcodeId {
     /// If operation has postfix returns it, otherwise None.
     pub fn postfix(&self) -> Option<u8> {
         if self.is_push() {
-            Some(self.as_u8() - OpcodeId::PUSH1.as_u8() + 1)
+            Some(self.as_u8() - OpcodeId::PUSH0.as_u8())
         } else if self.is_dup() {
             Some(self.as_u8() - OpcodeId::DUP1.as_u8() + 1)
         } else if self.is_swap() {
@@ -866,26 +906,43 @@ impl OpcodeId {
     /// push opcodes.
     pub fn data_len(&self) -> usize {
         if self.is_push() {
-            (self.as_u8() - OpcodeId::PUSH1.as_u8() + 1) as usize
+            (self.as_u8() - OpcodeId::PUSH0.as_u8()) as usize
         } else {
             0
         }
     }
 
-    /// Returns the all valid opcodes.
-    pub fn valid_opcodes() -> Vec<Self> {
+    /// Returns true if the `OpcodeId` is defined by the rules of the
+    /// `hardfork`.
+    pub fn is_enabled(&self, hardfork: Hardfork) -> bool {
+        match self {
+            OpcodeId::BASEFEE => hardfork >= Hardfork::London,
+            OpcodeId::PUSH0 => hardfork >= Hardfork::Shanghai,
+            OpcodeId::TLOAD
+            | OpcodeId::TSTORE
+            | OpcodeId::MCOPY
+            | OpcodeId::BLOBHASH
+            | OpcodeId::BLOBBASEFEE => hardfork >= Hardfork::Cancun,
+            OpcodeId::INVALID(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Returns the all valid opcodes under the rules of the `hardfork`.
+    pub fn valid_opcodes(hardfork: Hardfork) -> Vec<Self> {
         (u8::MIN..=u8::MAX).fold(vec![], |mut acc, val| {
-            if !matches!(val.into(), Self::INVALID(_)) {
-                acc.push(val.into());
+            let opcode = Self::from(val);
+            if opcode.is_enabled(hardfork) {
+                acc.push(opcode);
             }
             acc
         })
     }
 
-    /// Returns the all invalid opcodes.
-    pub fn invalid_opcodes() -> Vec<Self> {
+    /// Returns the all invalid opcodes under the rules of the `hardfork`.
+    pub fn invalid_opcodes(hardfork: Hardfork) -> Vec<Self> {
         (u8::MIN..=u8::MAX).fold(vec![], |mut acc, val| {
-            if matches!(val.into(), Self::INVALID(_)) {
+            if !Self::from(val).is_enabled(hardfork) {
                 acc.push(Self::INVALID(val));
             }
             acc
@@ -936,6 +993,7 @@ impl From<u8> for OpcodeId {
             0x58u8 => OpcodeId::PC,
             0x59u8 => OpcodeId::MSIZE,
             0x5bu8 => OpcodeId::JUMPDEST,
+            0x5fu8 => OpcodeId::PUSH0,
             0x60u8 => OpcodeId::PUSH1,
             0x61u8 => OpcodeId::PUSH2,
             0x62u8 => OpcodeId::PUSH3,
@@ -1024,9 +1082,14 @@ impl From<u8> for OpcodeId {
             0x46u8 => OpcodeId::CHAINID,
             0x47u8 => OpcodeId::SELFBALANCE,
             0x48u8 => OpcodeId::BASEFEE,
+            0x49u8 => OpcodeId::BLOBHASH,
+            0x4au8 => OpcodeId::BLOBBASEFEE,
             0x54u8 => OpcodeId::SLOAD,
             0x55u8 => OpcodeId::SSTORE,
             0x5au8 => OpcodeId::GAS,
+            0x5cu8 => OpcodeId::TLOAD,
+            0x5du8 => OpcodeId::TSTORE,
+            0x5eu8 => OpcodeId::MCOPY,
             0xa0u8 => OpcodeId::LOG0,
             0xa1u8 => OpcodeId::LOG1,
             0xa2u8 => OpcodeId::LOG2,
@@ -1089,6 +1152,7 @@ impl FromStr for OpcodeId {
             "PC" => OpcodeId::PC,
             "MSIZE" => OpcodeId::MSIZE,
             "JUMPDEST" => OpcodeId::JUMPDEST,
+            "PUSH0" => OpcodeId::PUSH0,
             "PUSH1" => OpcodeId::PUSH1,
             "PUSH2" => OpcodeId::PUSH2,
             "PUSH3" => OpcodeId::PUSH3,
@@ -1156,7 +1220,6 @@ impl FromStr for OpcodeId {
             "RETURN" => OpcodeId::RETURN,
             "REVERT" => OpcodeId::REVERT,
             "INVALID" => OpcodeId::INVALID(0xfe),
-            "PUSH0" => OpcodeId::INVALID(0x5f),
             "SHA3" | "KECCAK256" => OpcodeId::SHA3,
             "ADDRESS" => OpcodeId::ADDRESS,
             "BALANCE" => OpcodeId::BALANCE,
@@ -1179,6 +1242,9 @@ impl FromStr for OpcodeId {
             "SLOAD" => OpcodeId::SLOAD,
             "SSTORE" => OpcodeId::SSTORE,
             "GAS" => OpcodeId::GAS,
+            "TLOAD" => OpcodeId::TLOAD,
+            "TSTORE" => OpcodeId::TSTORE,
+            "MCOPY" => OpcodeId::MCOPY,
             "LOG0" => OpcodeId::LOG0,
             "LOG1" => OpcodeId::LOG1,
             "LOG2" => OpcodeId::LOG2,
@@ -1193,6 +1259,8 @@ impl FromStr for OpcodeId {
             "SELFDESTRUCT" => OpcodeId::SELFDESTRUCT,
             "CHAINID" => OpcodeId::CHAINID,
             "BASEFEE" => OpcodeId::BASEFEE,
+            "BLOBHASH" => OpcodeId::BLOBHASH,
+            "BLOBBASEFEE" => OpcodeId::BLOBBASEFEE,
             _ => {
                 // Parse an invalid opcode value as reported by geth
                 lazy_static! {
@@ -1234,20 +1302,18 @@ mod opcode_ids_tests {
 
     #[test]
     fn push_n() {
+        assert!(matches!(OpcodeId::push_n(0), Ok(OpcodeId::PUSH0)));
         assert!(matches!(OpcodeId::push_n(1), Ok(OpcodeId::PUSH1)));
         assert!(matches!(OpcodeId::push_n(10), Ok(OpcodeId::PUSH10)));
         assert!(matches!(
             OpcodeId::push_n(100),
             Err(Error::InvalidOpConversion)
         ));
-        assert!(matches!(
-            OpcodeId::push_n(0),
-            Err(Error::InvalidOpConversion)
-        ));
     }
 
     #[test]
     fn postfix() {
+        assert_eq!(OpcodeId::PUSH0.postfix(), Some(0));
         assert_eq!(OpcodeId::PUSH1.postfix(), Some(1));
         assert_eq!(OpcodeId::PUSH10.postfix(), Some(10));
         assert_eq!(OpcodeId::LOG2.postfix(), Some(2));
@@ -1256,9 +1322,27 @@ mod opcode_ids_tests {
 
     #[test]
     fn data_len() {
+        assert_eq!(OpcodeId::PUSH0.data_len(), 0);
         assert_eq!(OpcodeId::PUSH1.data_len(), 1);
         assert_eq!(OpcodeId::PUSH10.data_len(), 10);
         assert_eq!(OpcodeId::LOG2.data_len(), 0);
         assert_eq!(OpcodeId::CALLCODE.data_len(), 0);
     }
+
+    #[test]
+    fn is_enabled() {
+        assert!(!OpcodeId::BASEFEE.is_enabled(Hardfork::Berlin));
+        assert!(OpcodeId::BASEFEE.is_enabled(Hardfork::London));
+        assert!(!OpcodeId::PUSH0.is_enabled(Hardfork::London));
+        assert!(OpcodeId::PUSH0.is_enabled(Hardfork::Shanghai));
+        assert!(!OpcodeId::TSTORE.is_enabled(Hardfork::Shanghai));
+        assert!(OpcodeId::TSTORE.is_enabled(Hardfork::Cancun));
+        assert!(!OpcodeId::MCOPY.is_enabled(Hardfork::Shanghai));
+        assert!(OpcodeId::MCOPY.is_enabled(Hardfork::Cancun));
+        assert!(!OpcodeId::BLOBHASH.is_enabled(Hardfork::Shanghai));
+        assert!(OpcodeId::BLOBBASEFEE.is_enabled(Hardfork::Cancun));
+        assert!(!OpcodeId::INVALID(0xfe).is_enabled(Hardfork::Shanghai));
+        assert!(OpcodeId::invalid_opcodes(Hardfork::Berlin).contains(&OpcodeId::INVALID(0x48)));
+        assert!(!OpcodeId::valid_opcodes(Hardfork::Berlin).contains(&OpcodeId::BASEFEE));
+    }
 }
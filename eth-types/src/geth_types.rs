@@ -1,22 +1,24 @@
 //! Types needed for generating Ethereum traces
 
 use crate::{
+    evm_types::{gas_utils::blob_base_fee, GasCost},
     keccak256,
     sign_types::{biguint_to_32bytes_le, ct_option_ok_or, recover_pk, SignData, SECP256K1_Q},
+    trie::ordered_trie_root,
     AccessList, Address, Block, Bytes, Error, GethExecTrace, Hash, ToBigEndian, ToLittleEndian,
-    ToWord, Word, U64,
+    ToWord, Word, H64, U64,
 };
 use ethers_core::{
-    types::{transaction::response, NameOrAddress, TransactionRequest},
-    utils::get_contract_address,
+    types::{transaction::response, Bloom, NameOrAddress, OtherFields, TransactionRequest},
+    utils::{get_contract_address, rlp},
 };
 use ethers_signers::{LocalWallet, Signer};
 use halo2_proofs::halo2curves::{group::ff::PrimeField, secp256k1};
 use num::Integer;
 use num_bigint::BigUint;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 use serde_with::serde_as;
-use std::collections::HashMap;
+use std::{collections::HashMap, iter};
 
 /// Definition of all of the data related to an account.
 #[serde_as]
@@ -70,44 +72,136 @@ pub struct BlockConstants {
     pub number: U64,
     /// difficulty
     pub difficulty: Word,
+    /// mix hash, which is the prevRandao of the block from the merge on
+    /// (EIP-4399)
+    pub mix_hash: Hash,
     /// gas limit
     pub gas_limit: Word,
     /// base fee
     pub base_fee: Word,
+    /// excess blob gas (EIP-4844)
+    /// U64 type is required to serialize into proper hex with 0x prefix
+    pub excess_blob_gas: U64,
+    /// blob base fee (EIP-4844)
+    pub blob_base_fee: Word,
+}
+
+/// Return the excess blob gas of a block (EIP-4844), which is zero for the
+/// blocks before Cancun that don't have this field.
+pub fn excess_blob_gas<TX>(block: &Block<TX>) -> u64 {
+    block
+        .other
+        .get_deserialized::<U64>("excessBlobGas")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+        .as_u64()
+}
+
+/// Return the withdrawals of a block (EIP-4895), which are empty for the
+/// blocks before Shanghai that don't have this field.
+pub fn withdrawals<TX>(block: &Block<TX>) -> Vec<Withdrawal> {
+    block
+        .other
+        .get_deserialized::<Vec<Withdrawal>>("withdrawals")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Return the headers of the uncles of a block, which are not part of the
+/// block returned by the RPC and are added as the `uncleHeaders` other field
+/// by [`set_uncles`].
+pub fn uncles<TX>(block: &Block<TX>) -> Vec<BlockHeader> {
+    block
+        .other
+        .get_deserialized::<Vec<BlockHeader>>("uncleHeaders")
+        .and_then(Result::ok)
+        .unwrap_or_default()
+}
+
+/// Add the headers of the uncles of a block as its `uncleHeaders` other field.
+pub fn set_uncles<TX>(block: &mut Block<TX>, uncles: &[BlockHeader]) {
+    block
+        .other
+        .insert("uncleHeaders".to_string(), serde_json::json!(uncles));
+}
+
+/// Return the uncles of a block, the withdrawals (EIP-4895) of a Shanghai
+/// block and the excess blob gas (EIP-4844) of a Cancun block as the other
+/// fields of the ethers block, which doesn't have these fields.
+pub fn block_fields(
+    uncles: &[BlockHeader],
+    withdrawals: Option<&[Withdrawal]>,
+    excess_blob_gas: Option<u64>,
+) -> OtherFields {
+    let mut fields = serde_json::Map::new();
+    if !uncles.is_empty() {
+        fields.insert("uncleHeaders".to_string(), serde_json::json!(uncles));
+    }
+    if let Some(withdrawals) = withdrawals {
+        fields.insert("withdrawals".to_string(), serde_json::json!(withdrawals));
+    }
+    if let Some(excess_blob_gas) = excess_blob_gas {
+        fields.insert(
+            "excessBlobGas".to_string(),
+            serde_json::json!(U64::from(excess_blob_gas)),
+        );
+    }
+    serde_json::from_value(serde_json::Value::Object(fields))
+        .expect("uncles, withdrawals and excess blob gas are valid other fields")
+}
+
+/// Return the fields of a blob transaction (EIP-4844) as the other fields of
+/// the ethers transaction, which doesn't have these fields.
+pub fn blob_tx_fields(max_fee_per_blob_gas: Word, blob_versioned_hashes: &[Hash]) -> OtherFields {
+    serde_json::from_value(serde_json::json!({
+        "maxFeePerBlobGas": max_fee_per_blob_gas,
+        "blobVersionedHashes": blob_versioned_hashes,
+    }))
+    .expect("blob fields are valid other fields")
 }
 
 impl<TX> TryFrom<&Block<TX>> for BlockConstants {
     type Error = Error;
 
     fn try_from(block: &Block<TX>) -> Result<Self, Self::Error> {
+        let excess_blob_gas = excess_blob_gas(block);
         Ok(Self {
             coinbase: block.author.ok_or(Error::IncompleteBlock)?,
             timestamp: block.timestamp,
             number: block.number.ok_or(Error::IncompleteBlock)?,
             difficulty: block.difficulty,
+            mix_hash: block.mix_hash.unwrap_or_default(),
             gas_limit: block.gas_limit,
             base_fee: block.base_fee_per_gas.ok_or(Error::IncompleteBlock)?,
+            excess_blob_gas: excess_blob_gas.into(),
+            blob_base_fee: blob_base_fee(excess_blob_gas),
         })
     }
 }
 
 impl BlockConstants {
     /// Generates a new `BlockConstants` instance from it's fields.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         coinbase: Address,
         timestamp: Word,
         number: U64,
         difficulty: Word,
+        mix_hash: Hash,
         gas_limit: Word,
         base_fee: Word,
+        excess_blob_gas: U64,
     ) -> BlockConstants {
         BlockConstants {
             coinbase,
             timestamp,
             number,
             difficulty,
+            mix_hash,
             gas_limit,
             base_fee,
+            excess_blob_gas,
+            blob_base_fee: blob_base_fee(excess_blob_gas.as_u64()),
         }
     }
 }
@@ -115,6 +209,9 @@ impl BlockConstants {
 /// Definition of all of the constants related to an Ethereum transaction.
 #[derive(Debug, Default, Clone, Serialize)]
 pub struct Transaction {
+    /// Transaction type: 0 for legacy, 1 for EIP-2930, 2 for EIP-1559 and 3
+    /// for EIP-4844 transactions
+    pub transaction_type: u64,
     /// Sender address
     pub from: Address,
     /// Recipient address (None for contract creation)
@@ -128,11 +225,13 @@ pub struct Transaction {
     pub gas_limit: U64,
     /// Transfered value
     pub value: Word,
-    /// Gas Price
+    /// Gas Price, which is the effective gas price for EIP-1559 transactions
     pub gas_price: Word,
-    /// Gas fee cap
+    /// Gas fee cap (max fee per gas), which is the gas price for legacy
+    /// transactions
     pub gas_fee_cap: Word,
-    /// Gas tip cap
+    /// Gas tip cap (max priority fee per gas), which is the gas price for
+    /// legacy transactions
     pub gas_tip_cap: Word,
     /// The compiled code of a contract OR the first 4 bytes of the hash of the
     /// invoked method signature and encoded parameters. For details see
@@ -140,8 +239,13 @@ pub struct Transaction {
     pub call_data: Bytes,
     /// Access list
     pub access_list: Option<AccessList>,
+    /// Max fee per blob gas of blob transactions (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// Versioned hashes of the blobs of blob transactions (EIP-4844)
+    pub blob_versioned_hashes: Vec<Hash>,
 
-    /// "v" value of the transaction signature
+    /// "v" value of the transaction signature, which is the parity of the y
+    /// coordinate of the signature point for typed transactions
     pub v: u64,
     /// "r" value of the transaction signature
     pub r: Word,
@@ -152,19 +256,21 @@ pub struct Transaction {
 impl From<&Transaction> for crate::Transaction {
     fn from(tx: &Transaction) -> crate::Transaction {
         crate::Transaction {
+            transaction_type: Some(tx.transaction_type.into()),
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.to_word(),
             gas: tx.gas_limit.to_word(),
             value: tx.value,
             gas_price: Some(tx.gas_price),
-            max_priority_fee_per_gas: Some(tx.gas_fee_cap),
-            max_fee_per_gas: Some(tx.gas_tip_cap),
+            max_priority_fee_per_gas: Some(tx.gas_tip_cap),
+            max_fee_per_gas: Some(tx.gas_fee_cap),
             input: tx.call_data.clone(),
             access_list: tx.access_list.clone(),
             v: tx.v.into(),
             r: tx.r,
             s: tx.s,
+            other: tx.blob_fields(),
             ..Default::default()
         }
     }
@@ -172,17 +278,40 @@ impl From<&Transaction> for crate::Transaction {
 
 impl From<&crate::Transaction> for Transaction {
     fn from(tx: &crate::Transaction) -> Transaction {
+        let transaction_type = tx.transaction_type.unwrap_or_default().as_u64();
+        let gas_price = tx.gas_price.unwrap_or_default();
+        // Only EIP-1559 and blob transactions (types 2 and 3) have the fee caps,
+        // which are the gas price for the other transaction types.
+        let (gas_fee_cap, gas_tip_cap) = if transaction_type >= 2 {
+            (
+                tx.max_fee_per_gas.unwrap_or_default(),
+                tx.max_priority_fee_per_gas.unwrap_or_default(),
+            )
+        } else {
+            (gas_price, gas_price)
+        };
         Transaction {
+            transaction_type,
             from: tx.from,
             to: tx.to,
             nonce: tx.nonce.as_u64().into(),
             gas_limit: tx.gas.as_u64().into(),
             value: tx.value,
-            gas_price: tx.gas_price.unwrap_or_default(),
-            gas_fee_cap: tx.max_priority_fee_per_gas.unwrap_or_default(),
-            gas_tip_cap: tx.max_fee_per_gas.unwrap_or_default(),
+            gas_price,
+            gas_fee_cap,
+            gas_tip_cap,
             call_data: tx.input.clone(),
             access_list: tx.access_list.clone(),
+            max_fee_per_blob_gas: tx
+                .other
+                .get_deserialized("maxFeePerBlobGas")
+                .and_then(Result::ok)
+                .unwrap_or_default(),
+            blob_versioned_hashes: tx
+                .other
+                .get_deserialized("blobVersionedHashes")
+                .and_then(Result::ok)
+                .unwrap_or_default(),
             v: tx.v.as_u64(),
             r: tx.r,
             s: tx.s,
@@ -218,14 +347,23 @@ impl Transaction {
             secp256k1::Fq::from_repr(sig_s_le),
             Error::Signature(libsecp256k1::Error::InvalidSignature),
         )?;
-        // msg = rlp([nonce, gasPrice, gas, to, value, data, sig_v, r, s])
-        let req: TransactionRequest = self.into();
-        let msg = req.chain_id(chain_id).rlp();
+        let (msg, v) = if self.transaction_type == 0 {
+            // msg = rlp([nonce, gasPrice, gas, to, value, data, chain_id, 0, 0])
+            let req: TransactionRequest = self.into();
+            let v = self
+                .v
+                .checked_sub(35 + chain_id * 2)
+                .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))?;
+            (req.chain_id(chain_id).rlp().to_vec(), v)
+        } else {
+            // msg = type || rlp([chain_id, nonce, ..., data, access_list])
+            (self.rlp_unsigned(chain_id), self.v)
+        };
+        if v > 1 {
+            return Err(Error::Signature(libsecp256k1::Error::InvalidSignature));
+        }
+        let v = v as u8;
         let msg_hash: [u8; 32] = keccak256(&msg);
-        let v = self
-            .v
-            .checked_sub(35 + chain_id * 2)
-            .ok_or(Error::Signature(libsecp256k1::Error::InvalidSignature))? as u8;
         let pk = recover_pk(v, &self.r, &self.s, &msg_hash)?;
         // msg_hash = msg_hash % q
         let msg_hash = BigUint::from_bytes_be(msg_hash.as_slice());
@@ -242,6 +380,86 @@ impl Transaction {
         })
     }
 
+    /// Return the transaction used to pad the tx circuit up to its maximum
+    /// number of transactions: an unsigned legacy transaction with an EIP-155
+    /// `v` for the chain.
+    pub fn padding(chain_id: u64) -> Self {
+        Self {
+            v: chain_id * 2 + 35,
+            ..Default::default()
+        }
+    }
+
+    /// Append the fields of the transaction that are covered by its signature
+    /// to the RLP stream, following the payload of its type.
+    fn rlp_append_unsigned_fields(&self, stream: &mut rlp::RlpStream, chain_id: u64) {
+        if self.transaction_type != 0 {
+            stream.append(&chain_id);
+        }
+        stream.append(&self.nonce);
+        if self.transaction_type >= 2 {
+            stream.append(&self.gas_tip_cap);
+            stream.append(&self.gas_fee_cap);
+        } else {
+            stream.append(&self.gas_price);
+        }
+        stream.append(&self.gas_limit);
+        match self.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&self.value);
+        stream.append(&self.call_data.to_vec());
+        if self.transaction_type != 0 {
+            stream.append(&self.access_list.clone().unwrap_or_default());
+        }
+        if self.transaction_type == 3 {
+            stream.append(&self.max_fee_per_blob_gas);
+            stream.append_list::<Hash, _>(&self.blob_versioned_hashes);
+        }
+    }
+
+    /// Return the number of fields in the RLP list of the signed transaction
+    fn rlp_signed_fields_len(&self) -> usize {
+        match self.transaction_type {
+            0 => 9,
+            1 => 11,
+            2 => 12,
+            _ => 14,
+        }
+    }
+
+    /// Return the signing message of a typed transaction (EIP-2718), which is
+    /// the type followed by the RLP encoding of its unsigned fields
+    pub fn rlp_unsigned(&self, chain_id: u64) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new();
+        stream.begin_list(self.rlp_signed_fields_len() - 3);
+        self.rlp_append_unsigned_fields(&mut stream, chain_id);
+        [vec![self.transaction_type as u8], stream.out().to_vec()].concat()
+    }
+
+    /// Return the encoding of the signed transaction, which is the RLP
+    /// encoding of its fields prefixed by its type for typed transactions
+    /// (EIP-2718)
+    pub fn rlp_signed(&self, chain_id: u64) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new();
+        stream.begin_list(self.rlp_signed_fields_len());
+        self.rlp_append_unsigned_fields(&mut stream, chain_id);
+        stream.append(&self.v);
+        stream.append(&self.r);
+        stream.append(&self.s);
+        if self.transaction_type == 0 {
+            stream.out().to_vec()
+        } else {
+            [vec![self.transaction_type as u8], stream.out().to_vec()].concat()
+        }
+    }
+
+    /// Return the hash of the signed transaction
+    pub fn hash(&self, chain_id: u64) -> Hash {
+        Hash::from(keccak256(&self.rlp_signed(chain_id)))
+    }
+
     /// Compute call data gas cost from call data
     pub fn call_data_gas_cost(&self) -> u64 {
         self.call_data
@@ -249,6 +467,74 @@ impl Transaction {
             .fold(0, |acc, byte| acc + if *byte == 0 { 4 } else { 16 })
     }
 
+    /// Return the number of addresses and the number of storage keys in the
+    /// access list (EIP-2930)
+    pub fn access_list_len(&self) -> (u64, u64) {
+        self.access_list.as_ref().map_or((0, 0), |access_list| {
+            access_list
+                .0
+                .iter()
+                .fold((0, 0), |(addresses, keys), item| {
+                    (addresses + 1, keys + item.storage_keys.len() as u64)
+                })
+        })
+    }
+
+    /// Return the addresses of the access list (EIP-2930), and then its
+    /// storage keys with the address of their item, in the order in which
+    /// they are added to the access list of the transaction
+    pub fn access_list_entries(&self) -> (Vec<Address>, Vec<(Address, Word)>) {
+        let items = self
+            .access_list
+            .as_ref()
+            .map_or(&[][..], |access_list| &access_list.0);
+        let addresses = items.iter().map(|item| item.address).collect();
+        let storage_keys = items
+            .iter()
+            .flat_map(|item| {
+                item.storage_keys
+                    .iter()
+                    .map(|key| (item.address, key.to_word()))
+            })
+            .collect();
+        (addresses, storage_keys)
+    }
+
+    /// Compute access list gas cost (EIP-2930)
+    pub fn access_list_gas_cost(&self) -> u64 {
+        let (addresses, storage_keys) = self.access_list_len();
+        addresses * GasCost::ACCESS_LIST_PER_ADDRESS
+            + storage_keys * GasCost::ACCESS_LIST_PER_STORAGE_KEY
+    }
+
+    /// Return the RLP encoding of the access list (EIP-2930), which is the
+    /// encoding of the empty list when there is no access list
+    pub fn access_list_rlp(&self) -> Vec<u8> {
+        rlp::encode(&self.access_list.clone().unwrap_or_default()).to_vec()
+    }
+
+    /// Return the blob gas consumed by the blobs of the transaction (EIP-4844)
+    pub fn blob_gas(&self) -> u64 {
+        self.blob_versioned_hashes.len() as u64 * GasCost::PER_BLOB
+    }
+
+    /// Return the RLP encoding of the list of blob versioned hashes
+    /// (EIP-4844), which is the encoding of the empty list for the transactions
+    /// without blobs
+    pub fn blob_versioned_hashes_rlp(&self) -> Vec<u8> {
+        rlp::encode_list::<Hash, _>(&self.blob_versioned_hashes).to_vec()
+    }
+
+    /// Return the fields of blob transactions (EIP-4844) that are not part of
+    /// the ethers transaction, which are empty for the other transaction types
+    fn blob_fields(&self) -> OtherFields {
+        if self.transaction_type == 3 {
+            blob_tx_fields(self.max_fee_per_blob_gas, &self.blob_versioned_hashes)
+        } else {
+            OtherFields::default()
+        }
+    }
+
     /// Get the "to" address. If `to` is None then zero adddress
     pub fn to_or_zero(&self) -> Address {
         self.to.unwrap_or_default()
@@ -283,13 +569,255 @@ impl Transaction {
             r: self.r,
             s: self.s,
             v: U64::from(self.v),
+            transaction_type: Some(self.transaction_type.into()),
             block_number: Some(block_number),
             chain_id: Some(chain_id),
+            other: self.blob_fields(),
             ..response::Transaction::default()
         }
     }
 }
 
+/// Log emitted by a transaction
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Log {
+    /// Address of the contract that emitted the log
+    pub address: Address,
+    /// Topics of the log
+    pub topics: Vec<Hash>,
+    /// Data of the log
+    pub data: Bytes,
+}
+
+impl Log {
+    /// Return the inputs of the bloom filter of the log: its address followed
+    /// by its topics.
+    pub fn bloom_inputs(&self) -> impl Iterator<Item = &[u8]> {
+        iter::once(self.address.as_bytes()).chain(self.topics.iter().map(|topic| topic.as_bytes()))
+    }
+
+    /// Set the bits of the bloom filter of the address and the topics of the
+    /// log.
+    pub fn accrue_bloom(&self, bloom: &mut [u8; 256]) {
+        for input in self.bloom_inputs() {
+            let hash = keccak256(input);
+            // Each of the first three pairs of bytes of the hash selects one
+            // of the 2048 bits, counted from the end.
+            for pair in hash[..6].chunks(2) {
+                let bit = (pair[0] as usize * 256 + pair[1] as usize) % 2048;
+                bloom[255 - bit / 8] |= 1 << (bit % 8);
+            }
+        }
+    }
+}
+
+impl rlp::Encodable for Log {
+    fn rlp_append(&self, s: &mut rlp::RlpStream) {
+        s.begin_list(3);
+        s.append(&self.address);
+        s.append_list::<Hash, _>(&self.topics);
+        s.append(&self.data.to_vec());
+    }
+}
+
+/// Definition of all of the data related to the receipt of a transaction.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    /// Type of the transaction
+    pub transaction_type: u64,
+    /// Status of the transaction: 1 for success and 0 for failure
+    pub status: u64,
+    /// Gas used by the transactions of the block up to this one
+    pub cumulative_gas_used: u64,
+    /// Logs emitted by the transaction
+    pub logs: Vec<Log>,
+}
+
+impl Receipt {
+    /// Return the bloom filter of the logs.
+    pub fn logs_bloom(&self) -> [u8; 256] {
+        let mut bloom = [0; 256];
+        for log in &self.logs {
+            log.accrue_bloom(&mut bloom);
+        }
+        bloom
+    }
+
+    /// Return the bloom filter of the logs of the receipts of a block.
+    pub fn block_logs_bloom(receipts: &[Self]) -> Bloom {
+        let mut bloom = [0; 256];
+        for log in receipts.iter().flat_map(|receipt| &receipt.logs) {
+            log.accrue_bloom(&mut bloom);
+        }
+        Bloom::from(bloom)
+    }
+
+    /// Return the encoding of the receipt: the RLP list of its fields,
+    /// prefixed by the type for typed transactions (EIP-2718).
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream.append(&self.status);
+        stream.append(&self.cumulative_gas_used);
+        stream.append(&self.logs_bloom().to_vec());
+        stream.append_list::<Log, _>(&self.logs);
+        if self.transaction_type == 0 {
+            stream.out().to_vec()
+        } else {
+            [vec![self.transaction_type as u8], stream.out().to_vec()].concat()
+        }
+    }
+}
+
+/// Maximum number of withdrawals of a block (EIP-4895)
+pub const MAX_WITHDRAWALS_PER_BLOCK: usize = 16;
+
+/// Withdrawal of a validator from the beacon chain (EIP-4895), which
+/// increases the balance of its address at the end of the block.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    /// Index of the withdrawal, which increases monotonically across blocks
+    pub index: U64,
+    /// Index of the validator
+    pub validator_index: U64,
+    /// Recipient of the withdrawal
+    pub address: Address,
+    /// Amount of the withdrawal, in Gwei
+    pub amount: U64,
+}
+
+impl Withdrawal {
+    /// Return the amount of the withdrawal in Wei.
+    pub fn amount_wei(&self) -> Word {
+        Word::from(self.amount.as_u64()) * Word::exp10(9)
+    }
+
+    /// Return the encoding of the withdrawal: the RLP list of its fields.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream.append(&self.index);
+        stream.append(&self.validator_index);
+        stream.append(&self.address);
+        stream.append(&self.amount);
+        stream.out().to_vec()
+    }
+
+    /// Return the root of the ordered trie of the encodings of the
+    /// withdrawals of a block.
+    pub fn withdrawals_root(withdrawals: &[Self]) -> Hash {
+        ordered_trie_root(&withdrawals.iter().map(Self::rlp).collect::<Vec<_>>())
+    }
+}
+
+/// Maximum number of uncles of a block under Ethash.
+pub const MAX_UNCLES: usize = 2;
+
+/// Header of a block after London, whose hash is the keccak of its RLP
+/// encoding.  It is also the type of the headers of the uncles of a block.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeader {
+    /// Hash of the parent block
+    pub parent_hash: Hash,
+    /// Hash of the list of uncles
+    pub uncles_hash: Hash,
+    /// Beneficiary of the fees of the block
+    pub coinbase: Address,
+    /// State root after the block
+    pub state_root: Hash,
+    /// Root of the trie of the transactions
+    pub transactions_root: Hash,
+    /// Root of the trie of the receipts
+    pub receipts_root: Hash,
+    /// Bloom filter of the logs of the transactions
+    pub logs_bloom: Bloom,
+    /// Difficulty
+    pub difficulty: Word,
+    /// Block number
+    pub number: u64,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Gas used by the transactions
+    pub gas_used: u64,
+    /// Timestamp
+    pub timestamp: u64,
+    /// Extra data, of up to 32 bytes
+    pub extra_data: Bytes,
+    /// Mix hash
+    pub mix_hash: Hash,
+    /// Nonce
+    pub nonce: H64,
+    /// Base fee (EIP-1559)
+    pub base_fee: Word,
+}
+
+impl<TX> From<&Block<TX>> for BlockHeader {
+    fn from(block: &Block<TX>) -> Self {
+        Self {
+            parent_hash: block.parent_hash,
+            uncles_hash: block.uncles_hash,
+            coinbase: block.author.unwrap_or_default(),
+            state_root: block.state_root,
+            transactions_root: block.transactions_root,
+            receipts_root: block.receipts_root,
+            logs_bloom: block.logs_bloom.unwrap_or_default(),
+            difficulty: block.difficulty,
+            number: block.number.unwrap_or_default().low_u64(),
+            gas_limit: block.gas_limit.low_u64(),
+            gas_used: block.gas_used.low_u64(),
+            timestamp: block.timestamp.low_u64(),
+            extra_data: block.extra_data.clone(),
+            mix_hash: block.mix_hash.unwrap_or_default(),
+            nonce: block.nonce.unwrap_or_default(),
+            base_fee: block.base_fee_per_gas.unwrap_or_default(),
+        }
+    }
+}
+
+impl BlockHeader {
+    /// Return the RLP encoding of the header: the list of its fields.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(16);
+        stream.append(&self.parent_hash);
+        stream.append(&self.uncles_hash);
+        stream.append(&self.coinbase);
+        stream.append(&self.state_root);
+        stream.append(&self.transactions_root);
+        stream.append(&self.receipts_root);
+        stream.append(&self.logs_bloom.as_bytes().to_vec());
+        stream.append(&self.difficulty);
+        stream.append(&self.number);
+        stream.append(&self.gas_limit);
+        stream.append(&self.gas_used);
+        stream.append(&self.timestamp);
+        stream.append(&self.extra_data.to_vec());
+        stream.append(&self.mix_hash);
+        stream.append(&self.nonce);
+        stream.append(&self.base_fee);
+        stream.out().to_vec()
+    }
+
+    /// Return the hash of the block.
+    pub fn hash(&self) -> Hash {
+        Hash::from(keccak256(&self.rlp()))
+    }
+
+    /// Return the RLP encoding of the list of the headers of the uncles of a
+    /// block.
+    pub fn uncles_rlp(uncles: &[Self]) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(uncles.len());
+        for uncle in uncles {
+            stream.append_raw(&uncle.rlp(), 1);
+        }
+        stream.out().to_vec()
+    }
+
+    /// Return the uncles hash of a block: the keccak of the encoding of the
+    /// headers of its uncles.
+    pub fn uncles_hash(uncles: &[Self]) -> Hash {
+        Hash::from(keccak256(&Self::uncles_rlp(uncles)))
+    }
+}
+
 /// GethData is a type that contains all the information of a Ethereum block
 #[derive(Debug, Clone)]
 pub struct GethData {
@@ -0,0 +1,529 @@
+//! Secure Merkle Patricia Tries of the state, whose keys are the hashes of the
+//! addresses of the accounts and of the keys of their storage slots.  The
+//! tries are known through the nodes of some of their paths, such as the ones
+//! of the proofs of the accessed state, and every update returns the paths to
+//! its key before and after it.
+
+use crate::{
+    keccak256,
+    trie::{rlp_string, TrieNode},
+    Address, Error, ToBigEndian, Word, H256,
+};
+use ethers_core::utils::rlp;
+use std::collections::HashMap;
+
+/// Root of the empty trie, the hash of the encoding of the empty string.
+pub const EMPTY_TRIE_ROOT: H256 = H256([
+    0x56, 0xe8, 0x1f, 0x17, 0x1b, 0xcc, 0x55, 0xa6, 0xff, 0x83, 0x45, 0xe6, 0x92, 0xc0, 0xf8, 0x6e,
+    0x5b, 0x48, 0xe0, 0x1b, 0x99, 0x6c, 0xad, 0xc0, 0x01, 0x62, 0x2f, 0xb5, 0xe3, 0x63, 0xb4, 0x21,
+]);
+
+/// Account of the state trie.  The addresses without account have the
+/// default one, with a zero code hash, which is never stored in the trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateAccount {
+    /// Nonce of the account
+    pub nonce: u64,
+    /// Balance of the account
+    pub balance: Word,
+    /// Root of the storage trie of the account
+    pub storage_root: H256,
+    /// Hash of the code of the account, zero if the account doesn't exist
+    pub code_hash: H256,
+}
+
+impl Default for StateAccount {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: Word::zero(),
+            storage_root: EMPTY_TRIE_ROOT,
+            code_hash: H256::zero(),
+        }
+    }
+}
+
+impl StateAccount {
+    /// Return the RLP encoding of the account, which is the value of its leaf.
+    pub fn rlp(&self) -> Vec<u8> {
+        let mut stream = rlp::RlpStream::new_list(4);
+        stream
+            .append(&self.nonce)
+            .append(&self.balance)
+            .append(&self.storage_root)
+            .append(&self.code_hash);
+        stream.out().to_vec()
+    }
+
+    /// Decode an account from the value of its leaf.
+    pub fn from_rlp(bytes: &[u8]) -> Result<Self, Error> {
+        let list = rlp::Rlp::new(bytes);
+        let decode = || -> Result<Self, rlp::DecoderError> {
+            if list.item_count()? != 4 {
+                return Err(rlp::DecoderError::RlpIncorrectListLen);
+            }
+            Ok(Self {
+                nonce: list.val_at(0)?,
+                balance: list.val_at(1)?,
+                storage_root: list.val_at(2)?,
+                code_hash: list.val_at(3)?,
+            })
+        };
+        decode().map_err(|_| Error::InvalidTrieNode(bytes.to_vec()))
+    }
+}
+
+/// Update of the value at a key of a trie, with the nodes of the paths to the
+/// key from the roots before and after it.  A path ends at the leaf of the
+/// key, or at the node which proves its absence: a branch node without child
+/// at the next nibble of the key, or a leaf or extension node whose key
+/// diverges from it.  The path in the empty trie has no nodes.  The removal of
+/// the next to last child of a branch node merges the branch node with the
+/// remaining child, whose node is off the paths and is given with them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrieUpdate {
+    /// Key of the trie
+    pub key: H256,
+    /// Root of the trie before the update
+    pub old_root: H256,
+    /// Root of the trie after the update
+    pub new_root: H256,
+    /// Path to the key in the trie before the update
+    pub old_path: Vec<TrieNode>,
+    /// Path to the key in the trie after the update
+    pub new_path: Vec<TrieNode>,
+    /// Node of the child merged with its parent branch node by the update
+    pub merged: Option<TrieNode>,
+}
+
+/// Return the key of the account of the address in the state trie.
+pub fn account_key(address: &Address) -> H256 {
+    H256(keccak256(address.as_bytes()))
+}
+
+/// Return the key of the storage slot in the storage trie of its account.
+pub fn storage_key(key: &Word) -> H256 {
+    H256(keccak256(key.to_be_bytes()))
+}
+
+/// Return the nibbles of a key of a trie.
+pub fn key_nibbles(key: &H256) -> Vec<u8> {
+    key.as_bytes()
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+/// State trie, and the storage tries of its accounts, known through some of
+/// their nodes.  All the nodes are referenced by their hash, as the nodes
+/// shorter than 32 bytes, which are embedded in their parent, are only found
+/// at depths which aren't reached by hashed keys in practice and aren't
+/// supported.  Updates which remove the next to last child of a branch node
+/// need the node of the remaining child, which must be known.
+#[derive(Clone, Debug)]
+pub struct StateTrie {
+    root: H256,
+    nodes: HashMap<H256, TrieNode>,
+}
+
+impl Default for StateTrie {
+    fn default() -> Self {
+        Self::new(EMPTY_TRIE_ROOT)
+    }
+}
+
+impl StateTrie {
+    /// Return the state trie with the root, whose nodes are added with
+    /// [`StateTrie::add_nodes`].
+    pub fn new(root: H256) -> Self {
+        Self {
+            root,
+            nodes: HashMap::new(),
+        }
+    }
+
+    /// Add the nodes of the state trie or of storage tries with the RLP
+    /// encodings, such as the nodes of the proofs of `eth_getProof`.
+    pub fn add_nodes<'a>(
+        &mut self,
+        nodes: impl IntoIterator<Item = &'a [u8]>,
+    ) -> Result<(), Error> {
+        for rlp in nodes {
+            let node =
+                TrieNode::from_rlp(rlp).ok_or_else(|| Error::InvalidTrieNode(rlp.to_vec()))?;
+            self.nodes.insert(H256(keccak256(rlp)), node);
+        }
+        Ok(())
+    }
+
+    /// Return the root of the state trie.
+    pub fn root(&self) -> H256 {
+        self.root
+    }
+
+    /// Return the known nodes of the tries, including the nodes of all the
+    /// tries before and after the updates.
+    pub fn nodes(&self) -> impl Iterator<Item = &TrieNode> {
+        self.nodes.values()
+    }
+
+    /// Return the account of the address.
+    pub fn account(&self, address: &Address) -> Result<StateAccount, Error> {
+        let (_, value) = self.path(self.root, &key_nibbles(&account_key(address)))?;
+        value.map_or(Ok(StateAccount::default()), |value| {
+            StateAccount::from_rlp(&value)
+        })
+    }
+
+    /// Return the value of the storage slot of the account of the address.
+    pub fn storage(&self, address: &Address, key: &Word) -> Result<Word, Error> {
+        let root = self.account(address)?.storage_root;
+        let (_, value) = self.path(root, &key_nibbles(&storage_key(key)))?;
+        value.map_or(Ok(Word::zero()), |value| {
+            rlp::decode(&value).map_err(|_| Error::InvalidTrieNode(value))
+        })
+    }
+
+    /// Set the account of the address, and return the update of the state
+    /// trie.
+    pub fn set_account(
+        &mut self,
+        address: &Address,
+        account: StateAccount,
+    ) -> Result<TrieUpdate, Error> {
+        let value = (account != StateAccount::default()).then(|| account.rlp());
+        let update = self.update(self.root, account_key(address), value)?;
+        self.root = update.new_root;
+        Ok(update)
+    }
+
+    /// Set the value of the storage slot of the account of the address, and
+    /// return the updates of its storage trie and of the state trie.
+    pub fn set_storage(
+        &mut self,
+        address: &Address,
+        key: &Word,
+        value: Word,
+    ) -> Result<(TrieUpdate, TrieUpdate), Error> {
+        let mut account = self.account(address)?;
+        let value = (!value.is_zero()).then(|| rlp::encode(&value).to_vec());
+        let storage_update = self.update(account.storage_root, storage_key(key), value)?;
+        account.storage_root = storage_update.new_root;
+        let account_update = self.set_account(address, account)?;
+        Ok((storage_update, account_update))
+    }
+
+    /// Clear the storage of the account of the address, and return the update
+    /// of the state trie.
+    pub fn clear_storage(&mut self, address: &Address) -> Result<TrieUpdate, Error> {
+        let mut account = self.account(address)?;
+        account.storage_root = EMPTY_TRIE_ROOT;
+        self.set_account(address, account)
+    }
+
+    /// Set the value at the key of the trie with the root, and return the
+    /// update.
+    fn update(
+        &mut self,
+        root: H256,
+        key: H256,
+        value: Option<Vec<u8>>,
+    ) -> Result<TrieUpdate, Error> {
+        let nibbles = key_nibbles(&key);
+        let (old_path, old_value) = self.path(root, &nibbles)?;
+        let merged = match (old_value, &value) {
+            (Some(_), None) => self.merged(&old_path)?,
+            _ => None,
+        };
+        let reference = self.set(&root_reference(root), &nibbles, value.as_deref())?;
+        let new_root = match reference.as_slice() {
+            [0xa0, hash @ ..] => H256::from_slice(hash),
+            _ => EMPTY_TRIE_ROOT,
+        };
+        let (new_path, _) = self.path(new_root, &nibbles)?;
+        Ok(TrieUpdate {
+            key,
+            old_root: root,
+            new_root,
+            old_path,
+            new_path,
+            merged,
+        })
+    }
+
+    /// Return the node of the other child of the parent branch node of the
+    /// leaf ending the path, if it's the only one.
+    fn merged(&self, path: &[TrieNode]) -> Result<Option<TrieNode>, Error> {
+        let (leaf, parent) = match path {
+            [.., TrieNode::Branch(children), leaf] => (leaf.reference(), children),
+            _ => return Ok(None),
+        };
+        let others: Vec<_> = parent
+            .iter()
+            .filter(|child| child[..] != [0x80] && **child != leaf)
+            .collect();
+        match others[..] {
+            [other] => self.node(other),
+            _ => Ok(None),
+        }
+    }
+
+    /// Return the nodes of the path to the key with the nibbles in the trie
+    /// with the root, and the value at the key.
+    fn path(&self, root: H256, nibbles: &[u8]) -> Result<(Vec<TrieNode>, Option<Vec<u8>>), Error> {
+        let mut path = Vec::new();
+        let mut reference = root_reference(root);
+        let mut depth = 0;
+        let value = loop {
+            let node = match self.node(&reference)? {
+                Some(node) => node,
+                None => break None,
+            };
+            path.push(node.clone());
+            match node {
+                TrieNode::Branch(children) => {
+                    reference = children[nibbles[depth] as usize].clone();
+                    depth += 1;
+                }
+                TrieNode::Extension(shared, child) => {
+                    if !nibbles[depth..].starts_with(&shared) {
+                        break None;
+                    }
+                    reference = child;
+                    depth += shared.len();
+                }
+                TrieNode::Leaf(rest, value) => {
+                    break (nibbles[depth..] == rest[..]).then_some(value)
+                }
+            }
+        };
+        Ok((path, value))
+    }
+
+    /// Return the node with the reference, or `None` for empty children.
+    fn node(&self, reference: &[u8]) -> Result<Option<TrieNode>, Error> {
+        match reference {
+            [0x80] => Ok(None),
+            [0xa0, hash @ ..] => {
+                let hash = H256::from_slice(hash);
+                let node = self.nodes.get(&hash).ok_or(Error::TrieNodeNotFound(hash))?;
+                Ok(Some(node.clone()))
+            }
+            _ => Err(Error::InvalidTrieNode(reference.to_vec())),
+        }
+    }
+
+    /// Add the node, and return its reference.
+    fn insert(&mut self, node: TrieNode) -> Result<Vec<u8>, Error> {
+        let rlp = node.rlp();
+        if rlp.len() < 32 {
+            return Err(Error::InvalidTrieNode(rlp));
+        }
+        let hash = keccak256(&rlp);
+        self.nodes.insert(H256(hash), node);
+        Ok(rlp_string(&hash))
+    }
+
+    /// Set the value at the remaining nibbles of the key in the subtrie of the
+    /// node with the reference, and return the reference to the new node.
+    fn set(
+        &mut self,
+        reference: &[u8],
+        nibbles: &[u8],
+        value: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Error> {
+        let node = match (self.node(reference)?, value) {
+            (Some(node), _) => node,
+            (None, Some(value)) => {
+                return self.insert(TrieNode::Leaf(nibbles.to_vec(), value.to_vec()))
+            }
+            (None, None) => return Ok(reference.to_vec()),
+        };
+        match node {
+            TrieNode::Leaf(rest, _) if rest == nibbles => match value {
+                Some(value) => self.insert(TrieNode::Leaf(rest, value.to_vec())),
+                None => Ok(vec![0x80]),
+            },
+            TrieNode::Extension(shared, child) if nibbles.starts_with(&shared) => {
+                let child = self.set(&child, &nibbles[shared.len()..], value)?;
+                self.prepend(&shared, child)
+            }
+            TrieNode::Branch(mut children) => {
+                let nibble = nibbles[0] as usize;
+                children[nibble] = self.set(&children[nibble], &nibbles[1..], value)?;
+                let remaining: Vec<_> = (0..16).filter(|&i| children[i] != [0x80]).collect();
+                // A branch node left with a single child is merged with it.
+                if let [nibble] = remaining[..] {
+                    let child = children[nibble].clone();
+                    self.prepend(&[nibble as u8], child)
+                } else {
+                    self.insert(TrieNode::Branch(children))
+                }
+            }
+            node => match value {
+                Some(value) => self.split(node, nibbles, value),
+                None => Ok(reference.to_vec()),
+            },
+        }
+    }
+
+    /// Return the reference to the node with the nibbles prepended to the path
+    /// of the node with the reference, which are merged into its key unless
+    /// it's a branch node.
+    fn prepend(&mut self, nibbles: &[u8], reference: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if nibbles.is_empty() {
+            return Ok(reference);
+        }
+        match self.node(&reference)? {
+            Some(TrieNode::Leaf(rest, value)) => {
+                self.insert(TrieNode::Leaf([nibbles, &rest].concat(), value))
+            }
+            Some(TrieNode::Extension(shared, child)) => {
+                self.insert(TrieNode::Extension([nibbles, &shared].concat(), child))
+            }
+            Some(TrieNode::Branch(_)) => {
+                self.insert(TrieNode::Extension(nibbles.to_vec(), reference))
+            }
+            None => Ok(reference),
+        }
+    }
+
+    /// Return the reference to the node replacing a leaf or extension node
+    /// whose key diverges from the remaining nibbles of the key, where the
+    /// value is added.
+    fn split(&mut self, node: TrieNode, nibbles: &[u8], value: &[u8]) -> Result<Vec<u8>, Error> {
+        let key = match &node {
+            TrieNode::Leaf(key, _) | TrieNode::Extension(key, _) => key.clone(),
+            TrieNode::Branch(_) => unreachable!("branch nodes don't diverge from keys"),
+        };
+        let shared = key.iter().zip(nibbles).take_while(|(a, b)| a == b).count();
+        let rest = key[shared + 1..].to_vec();
+        let sibling = match node {
+            TrieNode::Extension(_, child) if rest.is_empty() => child,
+            TrieNode::Extension(_, child) => self.insert(TrieNode::Extension(rest, child))?,
+            TrieNode::Leaf(_, value) => self.insert(TrieNode::Leaf(rest, value))?,
+            TrieNode::Branch(_) => unreachable!(),
+        };
+        let leaf = self.insert(TrieNode::Leaf(
+            nibbles[shared + 1..].to_vec(),
+            value.to_vec(),
+        ))?;
+        let mut children: [Vec<u8>; 16] = std::array::from_fn(|_| vec![0x80]);
+        children[key[shared] as usize] = sibling;
+        children[nibbles[shared] as usize] = leaf;
+        let branch = self.insert(TrieNode::Branch(children))?;
+        self.prepend(&nibbles[..shared], branch)
+    }
+}
+
+/// Return the reference to the root of a trie.
+fn root_reference(root: H256) -> Vec<u8> {
+    if root == EMPTY_TRIE_ROOT {
+        vec![0x80]
+    } else {
+        rlp_string(root.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(nonce: u64) -> StateAccount {
+        StateAccount {
+            nonce,
+            balance: Word::from(nonce * 1000),
+            code_hash: H256(keccak256([])),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_trie_root() {
+        assert_eq!(EMPTY_TRIE_ROOT, H256(keccak256([0x80])));
+    }
+
+    #[test]
+    fn account_encoding() {
+        let account = account(3);
+        assert_eq!(StateAccount::from_rlp(&account.rlp()).unwrap(), account);
+    }
+
+    #[test]
+    fn insert_and_delete() {
+        let addresses: Vec<Address> = (1..=40u64).map(Address::from_low_u64_be).collect();
+        let mut trie = StateTrie::default();
+        let mut roots = vec![trie.root()];
+        for (i, address) in addresses.iter().enumerate() {
+            let update = trie.set_account(address, account(i as u64 + 1)).unwrap();
+            assert_eq!(update.old_root, roots[i]);
+            assert_eq!(update.new_root, trie.root());
+            roots.push(trie.root());
+        }
+        for (i, address) in addresses.iter().enumerate() {
+            assert_eq!(trie.account(address).unwrap(), account(i as u64 + 1));
+        }
+        // Removing the accounts in the reverse order gives back the same roots.
+        for (i, address) in addresses.iter().enumerate().rev() {
+            let update = trie.set_account(address, StateAccount::default()).unwrap();
+            assert_eq!(update.new_root, roots[i]);
+            assert_eq!(trie.account(address).unwrap(), StateAccount::default());
+        }
+        assert_eq!(trie.root(), EMPTY_TRIE_ROOT);
+    }
+
+    #[test]
+    fn partial_trie() {
+        let addresses: Vec<Address> = (1..=20u64).map(Address::from_low_u64_be).collect();
+        let mut trie = StateTrie::default();
+        for (i, address) in addresses.iter().enumerate() {
+            trie.set_account(address, account(i as u64 + 1)).unwrap();
+        }
+        let update = trie.set_account(&addresses[3], account(100)).unwrap();
+
+        // The trie known through the path to the account gives the same
+        // update.
+        let mut partial = StateTrie::new(update.old_root);
+        let rlps: Vec<_> = update.old_path.iter().map(|node| node.rlp()).collect();
+        partial
+            .add_nodes(rlps.iter().map(|rlp| rlp.as_slice()))
+            .unwrap();
+        assert_eq!(partial.account(&addresses[3]).unwrap(), account(4));
+        assert_eq!(
+            partial.set_account(&addresses[3], account(100)).unwrap(),
+            update
+        );
+        assert!(matches!(
+            partial.account(&addresses[5]),
+            Err(Error::TrieNodeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn storage_updates() {
+        let address = Address::from_low_u64_be(1);
+        let mut trie = StateTrie::default();
+        trie.set_account(&address, account(1)).unwrap();
+        let state_root = trie.root();
+        for key in 0..10u64 {
+            let (storage, state) = trie
+                .set_storage(&address, &Word::from(key), Word::from(key + 1))
+                .unwrap();
+            assert_eq!(
+                trie.account(&address).unwrap().storage_root,
+                storage.new_root
+            );
+            assert_eq!(state.new_root, trie.root());
+        }
+        for key in 0..10u64 {
+            assert_eq!(
+                trie.storage(&address, &Word::from(key)).unwrap(),
+                Word::from(key + 1)
+            );
+            trie.set_storage(&address, &Word::from(key), Word::zero())
+                .unwrap();
+        }
+        assert_eq!(trie.account(&address).unwrap(), account(1));
+        assert_eq!(trie.root(), state_root);
+    }
+}
@@ -0,0 +1,276 @@
+//! Ordered Merkle Patricia Tries, whose keys are the RLP encodings of the
+//! indexes of their values, as used for the transactions and receipts roots of
+//! the block header.
+
+use crate::{keccak256, H256};
+use ethers_core::utils::rlp;
+use std::array;
+
+/// Node of a Merkle Patricia Trie.  The children of branch and extension nodes
+/// are given by their references: the RLP encoding of their hash, or their own
+/// encoding when it's shorter than 32 bytes, and `0x80` for empty children.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrieNode {
+    /// Branch node with the references to its children.  The value of the
+    /// branch nodes of ordered tries is always empty, as no key is a prefix of
+    /// another one.
+    Branch([Vec<u8>; 16]),
+    /// Extension node with its shared nibbles and the reference to its child
+    Extension(Vec<u8>, Vec<u8>),
+    /// Leaf node with the remaining nibbles of its key and its value
+    Leaf(Vec<u8>, Vec<u8>),
+}
+
+impl TrieNode {
+    /// Return the RLP encodings of the items of the node.
+    pub fn items(&self) -> Vec<Vec<u8>> {
+        match self {
+            Self::Branch(children) => children
+                .iter()
+                .cloned()
+                .chain(std::iter::once(vec![0x80]))
+                .collect(),
+            Self::Extension(nibbles, child) => {
+                vec![rlp_string(&hex_prefix(nibbles, false)), child.clone()]
+            }
+            Self::Leaf(nibbles, value) => {
+                vec![rlp_string(&hex_prefix(nibbles, true)), rlp_string(value)]
+            }
+        }
+    }
+
+    /// Return the RLP encoding of the node.
+    pub fn rlp(&self) -> Vec<u8> {
+        let items = self.items();
+        let mut stream = rlp::RlpStream::new_list(items.len());
+        for item in items {
+            stream.append_raw(&item, 1);
+        }
+        stream.out().to_vec()
+    }
+
+    /// Return the hash of the node.
+    pub fn hash(&self) -> H256 {
+        H256(keccak256(&self.rlp()))
+    }
+
+    /// Return the reference to the node from its parent.
+    pub fn reference(&self) -> Vec<u8> {
+        let rlp = self.rlp();
+        if rlp.len() < 32 {
+            rlp
+        } else {
+            rlp_string(&keccak256(&rlp))
+        }
+    }
+
+    /// Decode a node from its RLP encoding.  Return `None` if the bytes
+    /// aren't the canonical encoding of a node, or encode a branch node with a
+    /// value, which can't be found in tries whose keys have the same length.
+    pub fn from_rlp(bytes: &[u8]) -> Option<Self> {
+        let list = rlp::Rlp::new(bytes);
+        let node = match list.item_count().ok()? {
+            17 => {
+                if !list.at(16).ok()?.data().ok()?.is_empty() {
+                    return None;
+                }
+                let mut children = Vec::with_capacity(16);
+                for index in 0..16 {
+                    children.push(list.at(index).ok()?.as_raw().to_vec());
+                }
+                Self::Branch(children.try_into().ok()?)
+            }
+            2 => {
+                let (nibbles, is_leaf) = hex_prefix_decode(list.at(0).ok()?.data().ok()?)?;
+                let item = list.at(1).ok()?;
+                if is_leaf {
+                    Self::Leaf(nibbles, item.data().ok()?.to_vec())
+                } else {
+                    Self::Extension(nibbles, item.as_raw().to_vec())
+                }
+            }
+            _ => return None,
+        };
+        (node.rlp() == bytes).then_some(node)
+    }
+}
+
+/// Return the key of the value at `index` in an ordered trie: the nibbles of
+/// the RLP encoding of the index.
+pub fn ordered_trie_key(index: usize) -> Vec<u8> {
+    rlp::encode(&(index as u64))
+        .iter()
+        .flat_map(|byte| [byte >> 4, byte & 0xf])
+        .collect()
+}
+
+/// Return the nodes of the ordered trie of the values with their paths (the
+/// nibbles of the keys leading to them), in depth-first order from the root.
+/// The trie of no values has no nodes.
+pub fn ordered_trie_nodes(values: &[Vec<u8>]) -> Vec<(Vec<u8>, TrieNode)> {
+    let entries: Vec<(Vec<u8>, &[u8])> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| (ordered_trie_key(index), value.as_slice()))
+        .collect();
+    let mut nodes = Vec::new();
+    if !entries.is_empty() {
+        insert_nodes(&entries, &[], &mut nodes);
+    }
+    nodes
+}
+
+/// Return the root of the ordered trie of the values.
+pub fn ordered_trie_root(values: &[Vec<u8>]) -> H256 {
+    ordered_trie_nodes(values)
+        .first()
+        .map_or_else(|| H256(keccak256(&[0x80])), |(_, root)| root.hash())
+}
+
+/// Push the node at `path` of the (key, value) entries whose keys start with
+/// it, followed by its descendants, and return its reference.
+fn insert_nodes(
+    entries: &[(Vec<u8>, &[u8])],
+    path: &[u8],
+    nodes: &mut Vec<(Vec<u8>, TrieNode)>,
+) -> Vec<u8> {
+    let depth = path.len();
+    let index = nodes.len();
+    // Replaced below once the references of the children are known
+    nodes.push((path.to_vec(), TrieNode::Leaf(vec![], vec![])));
+
+    let node = if let [(key, value)] = entries {
+        TrieNode::Leaf(key[depth..].to_vec(), value.to_vec())
+    } else {
+        let first = &entries[0].0;
+        let shared = (depth..first.len())
+            .take_while(|&i| entries.iter().all(|(key, _)| key.get(i) == first.get(i)))
+            .count();
+        if shared > 0 {
+            let child = insert_nodes(entries, &first[..depth + shared], nodes);
+            TrieNode::Extension(first[depth..depth + shared].to_vec(), child)
+        } else {
+            TrieNode::Branch(array::from_fn(|nibble| {
+                let group: Vec<_> = entries
+                    .iter()
+                    .filter(|(key, _)| key[depth] as usize == nibble)
+                    .cloned()
+                    .collect();
+                if group.is_empty() {
+                    vec![0x80]
+                } else {
+                    let child_path = [path, &[nibble as u8]].concat();
+                    insert_nodes(&group, &child_path, nodes)
+                }
+            }))
+        }
+    };
+
+    let reference = node.reference();
+    nodes[index].1 = node;
+    reference
+}
+
+/// Hex-prefix encoding of the nibbles, with the flag of leaf nodes.
+pub(crate) fn hex_prefix(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let is_odd = nibbles.len() % 2 == 1;
+    let flag = 2 * is_leaf as u8 + is_odd as u8;
+    let (first, rest) = if is_odd {
+        (nibbles[0], &nibbles[1..])
+    } else {
+        (0, nibbles)
+    };
+    std::iter::once(flag << 4 | first)
+        .chain(rest.chunks(2).map(|pair| pair[0] << 4 | pair[1]))
+        .collect()
+}
+
+/// Decode the nibbles and the flag of leaf nodes from their hex-prefix
+/// encoding.
+fn hex_prefix_decode(bytes: &[u8]) -> Option<(Vec<u8>, bool)> {
+    let (&first, rest) = bytes.split_first()?;
+    let flag = first >> 4;
+    if flag > 3 || (flag & 1 == 0 && first & 0xf != 0) {
+        return None;
+    }
+    let nibbles = (flag & 1 == 1)
+        .then_some(first & 0xf)
+        .into_iter()
+        .chain(rest.iter().flat_map(|byte| [byte >> 4, byte & 0xf]))
+        .collect();
+    Some((nibbles, flag & 2 == 2))
+}
+
+pub(crate) fn rlp_string(bytes: &[u8]) -> Vec<u8> {
+    rlp::encode(&bytes.to_vec()).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_trie_root() {
+        let root = hex::decode("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421")
+            .unwrap();
+        assert_eq!(ordered_trie_root(&[]), H256::from_slice(&root));
+    }
+
+    #[test]
+    fn single_value_trie() {
+        let value = vec![0xab; 40];
+        let nodes = ordered_trie_nodes(&[value.clone()]);
+        // The key of the only value is 0x80, whose hex-prefix encoding as a
+        // leaf is 0x20 0x80.
+        let leaf = [vec![0xec, 0x82, 0x20, 0x80, 0xa8], value].concat();
+        assert_eq!(
+            nodes,
+            vec![(vec![], TrieNode::Leaf(vec![8, 0], vec![0xab; 40]))]
+        );
+        assert_eq!(nodes[0].1.rlp(), leaf);
+        assert_eq!(ordered_trie_root(&[vec![0xab; 40]]), H256(keccak256(&leaf)));
+    }
+
+    #[test]
+    fn ordered_trie_structure() {
+        let values: Vec<Vec<u8>> = (0..20u8).map(|i| vec![i; 40]).collect();
+        let nodes = ordered_trie_nodes(&values);
+        // The keys 0x01..0x13 and 0x80 branch on their first nibble, and the
+        // keys 0x10..0x13 share the nibble 1.
+        assert!(matches!(nodes[0], (ref path, TrieNode::Branch(_)) if path.is_empty()));
+        let leaves = nodes
+            .iter()
+            .filter(|(_, node)| matches!(node, TrieNode::Leaf(..)))
+            .count();
+        assert_eq!(leaves, values.len());
+        for (path, node) in &nodes {
+            if let TrieNode::Leaf(nibbles, value) = node {
+                let index = value[0] as usize;
+                assert_eq!(
+                    [path.clone(), nibbles.clone()].concat(),
+                    ordered_trie_key(index)
+                );
+            }
+        }
+        // Every node but the root is referenced by the hash of its encoding.
+        for (_, node) in &nodes[1..] {
+            let reference = node.reference();
+            assert!(nodes
+                .iter()
+                .any(|(_, parent)| parent.items().contains(&reference)));
+        }
+    }
+
+    #[test]
+    fn decode_nodes() {
+        let values: Vec<Vec<u8>> = (0..20u8).map(|i| vec![i; 40]).collect();
+        for (_, node) in ordered_trie_nodes(&values) {
+            assert_eq!(TrieNode::from_rlp(&node.rlp()), Some(node));
+        }
+        let leaf = TrieNode::Leaf(vec![1, 2, 3], vec![0xab; 40]);
+        let mut rlp = leaf.rlp();
+        assert_eq!(TrieNode::from_rlp(&rlp), Some(leaf));
+        rlp.push(0);
+        assert_eq!(TrieNode::from_rlp(&rlp), None);
+    }
+}
@@ -0,0 +1,461 @@
+//! The RlpItem chip decodes a stream of RLP items with one byte per row.
+//!
+//! The rows of an item are its header rows (the RLP prefix, followed by the
+//! bytes of the length in the long form) and then its content rows.  Lists,
+//! and the other items whose payload is made of the items that follow them,
+//! are only made of header rows, and raw items are only made of content rows.
+//! The content of an item is accumulated in `value_acc` with a multiplier
+//! given by the circuit, which sets how the items follow each other.
+
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+
+use crate::{
+    bool_check,
+    util::{not, Expr},
+};
+
+/// Expressions of the current row setting how the item is decoded.
+#[derive(Clone, Debug)]
+pub struct RlpItemFlags<F> {
+    /// Whether the item is a list, whose prefix is offset by 0x40.
+    pub is_list: Expression<F>,
+    /// Whether the item is only a header, whose payload is the items that
+    /// follow it.
+    pub is_header_only: Expression<F>,
+    /// Whether the item is a raw byte string without header.
+    pub is_raw: Expression<F>,
+    /// Multiplier of `value_acc` in the content rows.
+    pub multiplier: Expression<F>,
+}
+
+/// Config of the RlpItem chip.
+#[derive(Clone, Copy, Debug)]
+pub struct RlpItemConfig {
+    /// Whether the row is a header row.
+    pub is_header: Column<Advice>,
+    /// Whether the header is in the long form.
+    pub is_long: Column<Advice>,
+    /// Length of the content of the item, or of the payload of the items
+    /// that are only a header.
+    pub length: Column<Advice>,
+    /// Index of the byte in the content of the item, or number of bytes of
+    /// the length that follow in header rows.
+    pub index: Column<Advice>,
+    /// Whether the row is the first one of the item.
+    pub is_item_first: Column<Advice>,
+    /// Whether the row is the last one of the item.
+    pub is_item_last: Column<Advice>,
+    /// Value of the length accumulated in header rows, and value of the item
+    /// accumulated in content rows.
+    pub value_acc: Column<Advice>,
+}
+
+impl RlpItemConfig {
+    /// Returns an expression that is 1 on the first byte of the content of an
+    /// item, at the start of the item or after its header, and 0 otherwise.
+    pub fn is_content_first<F: Field>(&self, meta: &mut VirtualCells<'_, F>) -> Expression<F> {
+        let is_item_first = meta.query_advice(self.is_item_first, Rotation::cur());
+        not::expr(meta.query_advice(self.is_header, Rotation::cur()))
+            * (is_item_first.clone()
+                + not::expr(is_item_first) * meta.query_advice(self.is_header, Rotation::prev()))
+    }
+}
+
+/// Witness of the RlpItem chip in a row.
+#[derive(Clone, Copy, Debug)]
+pub struct RlpItemRow<F> {
+    /// Whether the row is a header row.
+    pub is_header: bool,
+    /// Whether the header is in the long form.
+    pub is_long: bool,
+    /// Length of the content of the item, or of the payload of the items
+    /// that are only a header.
+    pub length: usize,
+    /// Index of the byte in the content of the item, or number of bytes of
+    /// the length that follow in header rows.
+    pub index: usize,
+    /// Whether the row is the first one of the item.
+    pub is_item_first: bool,
+    /// Whether the row is the last one of the item.
+    pub is_item_last: bool,
+    /// Value of the length accumulated in header rows, and value of the item
+    /// accumulated in content rows.
+    pub value_acc: Value<F>,
+}
+
+impl<F: Field> RlpItemRow<F> {
+    /// Witness of the rows which are not part of an item.
+    pub fn padding() -> Self {
+        Self {
+            is_header: false,
+            is_long: false,
+            length: 0,
+            index: 0,
+            is_item_first: false,
+            is_item_last: false,
+            value_acc: Value::known(F::ZERO),
+        }
+    }
+
+    /// Witness of the rows of the bytes of an item, which is only a header
+    /// when the length of its payload is given, and has no header when it's
+    /// raw.  The content is accumulated with `multiplier`.
+    pub fn item_rows(
+        bytes: &[u8],
+        payload_len: Option<usize>,
+        is_raw: bool,
+        multiplier: Value<F>,
+    ) -> Vec<Self> {
+        // Number of header rows: the whole header of the items that are only a
+        // header, or the prefix and the length bytes of a string.
+        let header_len = if payload_len.is_some() {
+            bytes.len()
+        } else if is_raw || bytes[0] < 0x80 {
+            0
+        } else if bytes[0] <= 0xb7 {
+            1
+        } else {
+            1 + (bytes[0] - 0xb7) as usize
+        };
+        let length = payload_len.unwrap_or(bytes.len() - header_len);
+
+        let mut value_acc = Value::known(F::ZERO);
+        bytes
+            .iter()
+            .enumerate()
+            .map(|(idx, &byte)| {
+                let is_header = idx < header_len;
+                let byte = Value::known(F::from(byte as u64));
+                value_acc = if is_header {
+                    if idx == 0 {
+                        Value::known(F::ZERO)
+                    } else {
+                        value_acc * Value::known(F::from(256)) + byte
+                    }
+                } else if idx == header_len {
+                    byte
+                } else {
+                    value_acc * multiplier + byte
+                };
+                Self {
+                    is_header,
+                    is_long: is_header && header_len > 1,
+                    length,
+                    index: if is_header {
+                        header_len - 1 - idx
+                    } else {
+                        idx - header_len
+                    },
+                    is_item_first: idx == 0,
+                    is_item_last: idx == bytes.len() - 1,
+                    value_acc,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Chip that decodes the RLP items of a stream of bytes.
+#[derive(Clone, Debug)]
+pub struct RlpItemChip {
+    config: RlpItemConfig,
+}
+
+impl RlpItemChip {
+    /// Configures the RlpItem chip, decoding the items of the bytes in `byte`
+    /// with up to `max_length_bytes` bytes of length, which is at most 3.
+    /// The bytes must be range checked by the circuit, and `u8_table` is used
+    /// to range check the lengths.
+    pub fn configure<F: Field>(
+        meta: &mut ConstraintSystem<F>,
+        q_enable: impl Fn(&mut VirtualCells<'_, F>) -> Expression<F>,
+        byte: Column<Advice>,
+        flags: impl Fn(&mut VirtualCells<'_, F>) -> RlpItemFlags<F>,
+        max_length_bytes: usize,
+        u8_table: TableColumn,
+    ) -> RlpItemConfig {
+        assert!((1..=3).contains(&max_length_bytes));
+        let [is_header, is_long, length, index, is_item_first, is_item_last] =
+            [(); 6].map(|_| meta.advice_column());
+        let value_acc = meta.advice_column_in(SecondPhase);
+        let config = RlpItemConfig {
+            is_header,
+            is_long,
+            length,
+            index,
+            is_item_first,
+            is_item_last,
+            value_acc,
+        };
+
+        meta.create_gate("rlp item", |meta| {
+            let q_enable = q_enable(meta);
+            let is_content_first = config.is_content_first(meta);
+            let RlpItemFlags {
+                is_list,
+                is_header_only,
+                is_raw,
+                multiplier,
+            } = flags(meta);
+            let length_next = meta.query_advice(length, Rotation::next());
+            let [is_header_prev, is_long_prev, index_prev, value_acc_prev] =
+                [is_header, is_long, index, value_acc]
+                    .map(|column| meta.query_advice(column, Rotation::prev()));
+            let [is_header, is_long, is_item_first, is_item_last, length, index, value_acc] = [
+                is_header,
+                is_long,
+                is_item_first,
+                is_item_last,
+                length,
+                index,
+                value_acc,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let byte = meta.query_advice(byte, Rotation::cur());
+
+            let is_header_first = is_header.clone() * is_item_first.clone();
+            let is_content_next = not::expr(is_header.clone()) * not::expr(is_item_first.clone());
+            // The RLP prefix is 0x80 + length for short strings, and 0xb7 +
+            // the number of bytes of the length for long strings, offset by
+            // 0x40 for lists.
+            let prefix_base = 0x80.expr() + 0x40.expr() * is_list;
+            // Words are accumulated as the RLC of their little-endian bytes,
+            // and other values as the RLC of their bytes or as big-endian
+            // integers, depending on the multiplier.
+            let value_acc_next = value_acc_prev.clone() * multiplier + byte.clone();
+            let length_bytes_in_set = (1..=max_length_bytes)
+                .fold(1.expr(), |acc, len| acc * (index.clone() - len.expr()));
+
+            let constraints = [
+                ("is_header is boolean", bool_check(is_header.clone())),
+                ("is_long is boolean", bool_check(is_long.clone())),
+                (
+                    "is_item_first is boolean",
+                    bool_check(is_item_first.clone()),
+                ),
+                ("is_item_last is boolean", bool_check(is_item_last.clone())),
+                (
+                    "the length is the same one in the whole item",
+                    not::expr(is_item_last.clone()) * (length_next - length.clone()),
+                ),
+                (
+                    "value_acc starts at 0 in the prefix",
+                    is_header_first.clone() * value_acc.clone(),
+                ),
+                (
+                    "no length bytes follow a short prefix",
+                    is_header_first.clone() * not::expr(is_long.clone()) * index.clone(),
+                ),
+                (
+                    "the short prefix has the length",
+                    is_header_first.clone()
+                        * not::expr(is_long.clone())
+                        * (byte.clone() - prefix_base.clone() - length.clone()),
+                ),
+                (
+                    "the long prefix has the number of length bytes",
+                    is_header_first.clone()
+                        * is_long.clone()
+                        * (byte.clone() - prefix_base - 55.expr() - index.clone()),
+                ),
+                (
+                    "the length bytes that follow a long prefix are in range",
+                    is_header_first * is_long.clone() * length_bytes_in_set,
+                ),
+                (
+                    "length bytes follow the prefix",
+                    is_header.clone()
+                        * not::expr(is_item_first.clone())
+                        * not::expr(is_header_prev.clone()),
+                ),
+                (
+                    "length bytes follow a long prefix",
+                    is_header.clone()
+                        * not::expr(is_item_first.clone())
+                        * not::expr(is_long.clone()),
+                ),
+                (
+                    "index counts down the length bytes",
+                    is_header.clone()
+                        * not::expr(is_item_first.clone())
+                        * (index.clone() - index_prev.clone() + 1.expr()),
+                ),
+                (
+                    "value_acc accumulates the length",
+                    is_header.clone()
+                        * not::expr(is_item_first.clone())
+                        * (value_acc.clone() - value_acc_prev.clone() * 256.expr() - byte.clone()),
+                ),
+                (
+                    "raw items have no header",
+                    is_raw.clone() * is_header.clone(),
+                ),
+                (
+                    "the headers without content are only headers",
+                    is_header_only.clone() * not::expr(is_header.clone()),
+                ),
+                (
+                    "a string without content is empty",
+                    is_header.clone()
+                        * is_item_last.clone()
+                        * not::expr(is_header_only.clone())
+                        * length.clone(),
+                ),
+                (
+                    "an empty string has a short prefix",
+                    is_header.clone()
+                        * is_item_last.clone()
+                        * not::expr(is_header_only.clone())
+                        * is_long.clone(),
+                ),
+                (
+                    "the header ends after its length",
+                    is_header_only.clone() * is_item_last.clone() * index.clone(),
+                ),
+                (
+                    "the long header has the length of the payload",
+                    is_header_only
+                        * is_item_last.clone()
+                        * is_long
+                        * (length.clone() - value_acc.clone()),
+                ),
+                (
+                    "index starts at 0 in the content",
+                    is_content_first.clone() * index.clone(),
+                ),
+                (
+                    "value_acc starts at the first byte of the content",
+                    is_content_first * (value_acc.clone() - byte),
+                ),
+                (
+                    "the content follows all the length bytes",
+                    is_content_next.clone() * is_header_prev.clone() * index_prev.clone(),
+                ),
+                (
+                    "the content has the length of the long header",
+                    is_content_next.clone()
+                        * is_header_prev.clone()
+                        * is_long_prev
+                        * (length.clone() - value_acc_prev),
+                ),
+                (
+                    "index increases by 1",
+                    is_content_next.clone()
+                        * not::expr(is_header_prev.clone())
+                        * (index.clone() - index_prev - 1.expr()),
+                ),
+                (
+                    "value_acc accumulates the content",
+                    is_content_next * not::expr(is_header_prev) * (value_acc - value_acc_next),
+                ),
+                (
+                    "the content ends at its length",
+                    not::expr(is_header.clone())
+                        * is_item_last
+                        * (index - length.clone() + 1.expr()),
+                ),
+                (
+                    "a string without header is a single byte",
+                    not::expr(is_header) * is_item_first * not::expr(is_raw) * (length - 1.expr()),
+                ),
+            ];
+            constraints
+                .into_iter()
+                .map(move |(name, constraint)| (name, q_enable.clone() * constraint))
+        });
+
+        meta.lookup("rlp item short length range", |meta| {
+            // Short strings and lists have up to 55 bytes.
+            let enable = q_enable(meta)
+                * meta.query_advice(is_header, Rotation::cur())
+                * meta.query_advice(is_item_first, Rotation::cur())
+                * not::expr(meta.query_advice(is_long, Rotation::cur()));
+            let length = meta.query_advice(length, Rotation::cur());
+            vec![(enable * (55.expr() - length), u8_table)]
+        });
+        meta.lookup("rlp item long length range", |meta| {
+            // The length of long strings and lists has no leading zeros, and
+            // it's more than 55 when it has a single byte, whose index is 0.
+            let enable = q_enable(meta)
+                * meta.query_advice(is_header, Rotation::cur())
+                * not::expr(meta.query_advice(is_item_first, Rotation::cur()))
+                * meta.query_advice(is_item_first, Rotation::prev());
+            let index = meta.query_advice(index, Rotation::cur());
+            let min = 1.expr()
+                + (index.clone() - 1.expr())
+                    * (index - 2.expr())
+                    * Expression::Constant(F::from(55) * F::from(2).invert().unwrap());
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - min), u8_table)]
+        });
+        meta.lookup("rlp item single byte range", |meta| {
+            // A single byte without header is less than 0x80.
+            let enable = q_enable(meta)
+                * not::expr(meta.query_advice(is_header, Rotation::cur()))
+                * meta.query_advice(is_item_first, Rotation::cur())
+                * not::expr(flags(meta).is_raw);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * byte * 2.expr(), u8_table)]
+        });
+        meta.lookup("rlp item short string range", |meta| {
+            // A single byte with header is at least 0x80.
+            let enable = q_enable(meta)
+                * not::expr(meta.query_advice(is_header, Rotation::cur()))
+                * not::expr(meta.query_advice(is_item_first, Rotation::cur()))
+                * meta.query_advice(is_header, Rotation::prev())
+                * meta.query_advice(is_item_last, Rotation::cur());
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - 0x80.expr()), u8_table)]
+        });
+
+        config
+    }
+
+    /// Constructs a RlpItem chip given a config.
+    pub fn construct(config: RlpItemConfig) -> Self {
+        Self { config }
+    }
+
+    /// Assigns the witness of the chip in a row.
+    pub fn assign<F: Field>(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &RlpItemRow<F>,
+    ) -> Result<(), Error> {
+        let config = &self.config;
+        for (name, column, value) in [
+            ("is_header", config.is_header, row.is_header as u64),
+            ("is_long", config.is_long, row.is_long as u64),
+            ("length", config.length, row.length as u64),
+            ("index", config.index, row.index as u64),
+            (
+                "is_item_first",
+                config.is_item_first,
+                row.is_item_first as u64,
+            ),
+            ("is_item_last", config.is_item_last, row.is_item_last as u64),
+        ] {
+            region.assign_advice(
+                || format!("rlp item {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        region.assign_advice(
+            || "rlp item value_acc",
+            config.value_acc,
+            offset,
+            || row.value_acc,
+        )?;
+        Ok(())
+    }
+}
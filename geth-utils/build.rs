@@ -15,7 +15,7 @@ fn main() {
         // The error type is private so have to check the error string
         if format!("{}", e).starts_with("Failed to find tool.") {
             fail(
-                " Failed to find Go. Please install Go 1.16 or later \
+                " Failed to find Go. Please install Go 1.20 or later \
                 following the instructions at https://golang.org/doc/install.
                 On linux it is also likely available as a package."
                     .to_string(),
@@ -82,12 +82,14 @@ func FormatLogs(logs []logger.StructLog) []StructLogRes {
 }
 
 type Block struct {
-	Coinbase   common.Address `json:"coinbase"`
-	Timestamp  *hexutil.Big   `json:"timestamp"`
-	Number     *hexutil.Big   `json:"number"`
-	Difficulty *hexutil.Big   `json:"difficulty"`
-	GasLimit   *hexutil.Big   `json:"gas_limit"`
-	BaseFee    *hexutil.Big   `json:"base_fee"`
+	Coinbase    common.Address `json:"coinbase"`
+	Timestamp   *hexutil.Big   `json:"timestamp"`
+	Number      *hexutil.Big   `json:"number"`
+	Difficulty  *hexutil.Big   `json:"difficulty"`
+	MixHash     common.Hash    `json:"mix_hash"`
+	GasLimit    *hexutil.Big   `json:"gas_limit"`
+	BaseFee     *hexutil.Big   `json:"base_fee"`
+	BlobBaseFee *hexutil.Big   `json:"blob_base_fee"`
 }
 
 type Account struct {
@@ -111,6 +113,8 @@ type Transaction struct {
 		Address     common.Address `json:"address"`
 		StorageKeys []common.Hash  `json:"storage_keys"`
 	} `json:"access_list"`
+	BlobGasFeeCap *hexutil.Big  `json:"max_fee_per_blob_gas"`
+	BlobHashes    []common.Hash `json:"blob_versioned_hashes"`
 }
 
 type TraceConfig struct {
@@ -122,6 +126,7 @@ type TraceConfig struct {
 	Accounts      map[common.Address]Account `json:"accounts"`
 	Transactions  []Transaction              `json:"transactions"`
 	LoggerConfig  *logger.Config             `json:"logger_config"`
+	Hardfork      string                     `json:"hardfork"`
 }
 
 func newUint64(val uint64) *uint64 { return &val }
@@ -148,12 +153,29 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		TerminalTotalDifficultyPassed: true,
 	}
 
+	// Disable the forks following the one the config selects
+	isMerge := true
+	switch config.Hardfork {
+	case "Berlin":
+		chainConfig.LondonBlock = nil
+		chainConfig.ShanghaiTime = nil
+		isMerge = false
+	case "London":
+		chainConfig.ShanghaiTime = nil
+		isMerge = false
+	case "Paris":
+		chainConfig.ShanghaiTime = nil
+	case "Cancun":
+		chainConfig.CancunTime = newUint64(0)
+	}
+
 	var txsGasLimit uint64
 	blockGasLimit := toBigInt(config.Block.GasLimit).Uint64()
 	messages := make([]core.Message, len(config.Transactions))
 	for i, tx := range config.Transactions {
-		// If gas price is specified directly, the tx is treated as legacy type.
-		if tx.GasPrice != nil {
+		// If gas fee cap is not specified, the tx is treated as legacy type,
+		// otherwise gas price is the effective gas price of the EIP-1559 tx.
+		if tx.GasFeeCap == nil || tx.GasFeeCap.ToInt().Sign() == 0 {
 			tx.GasFeeCap = tx.GasPrice
 			tx.GasTipCap = tx.GasPrice
 		}
@@ -174,6 +196,8 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 			GasTipCap: toBigInt(tx.GasTipCap),
 			Data: tx.CallData,
 			AccessList: txAccessList,
+			BlobGasFeeCap: toBigInt(tx.BlobGasFeeCap),
+			BlobHashes: tx.BlobHashes,
 			SkipAccountChecks: false,
 		}
 
@@ -183,8 +207,11 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		return nil, fmt.Errorf("txs total gas: %d Exceeds block gas limit: %d", txsGasLimit, blockGasLimit)
 	}
 
-	// For opcode PREVRANDAO
-	randao := common.BigToHash(toBigInt(config.Block.Difficulty)) // TODO: fix
+	// For opcode PREVRANDAO, which replaces DIFFICULTY from the merge on
+	var random *common.Hash
+	if isMerge {
+		random = &config.Block.MixHash
+	}
 
 	blockCtx := vm.BlockContext{
 		CanTransfer: core.CanTransfer,
@@ -201,8 +228,9 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 		BlockNumber: toBigInt(config.Block.Number),
 		Time:        toBigInt(config.Block.Timestamp).Uint64(),
 		Difficulty:  toBigInt(config.Block.Difficulty),
-		Random:      &randao,
+		Random:      random,
 		BaseFee:     toBigInt(config.Block.BaseFee),
+		BlobBaseFee: toBigInt(config.Block.BlobBaseFee),
 		GasLimit:    blockGasLimit,
 	}
 
@@ -224,7 +252,7 @@ func Trace(config TraceConfig) ([]*ExecutionResult, error) {
 	executionResults := make([]*ExecutionResult, len(config.Transactions))
 	for i, message := range messages {
 		tracer := logger.NewStructLogger(config.LoggerConfig)
-		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Debug: true, Tracer: tracer, NoBaseFee: true})
+		evm := vm.NewEVM(blockCtx, core.NewEVMTxContext(&message), stateDB, &chainConfig, vm.Config{Tracer: tracer, NoBaseFee: true})
 
 		result, err := core.ApplyMessage(evm, &message, new(core.GasPool).AddGas(message.GasLimit))
 		if err != nil {
@@ -3,7 +3,10 @@ use bus_mapping::{
     circuit_input_builder::{BuilderClient, CircuitInputBuilder, CircuitsParams},
     mock::BlockData,
 };
-use eth_types::geth_types::GethData;
+use eth_types::{
+    evm_types::{BlockReward, Hardfork},
+    geth_types::GethData,
+};
 use halo2_proofs::{
     dev::{CellValue, MockProver},
     halo2curves::bn256::{Bn256, Fr, G1Affine},
@@ -59,7 +62,10 @@ const MAX_EVM_ROWS: usize = 10000;
 /// MAX_EXP_STEPS
 const MAX_EXP_STEPS: usize = 1000;
 
-const MAX_KECCAK_ROWS: usize = 15000;
+/// MAX_KECCAK_ROWS, which fits the nodes of the MPT proofs of the state
+/// updates: a branch node of up to 532 bytes takes 4 chunks of 300 rows, and
+/// every update hashes the nodes of the paths before and after it.
+const MAX_KECCAK_ROWS: usize = 50000;
 
 const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_rws: MAX_RWS,
@@ -70,6 +76,17 @@ const CIRCUITS_PARAMS: CircuitsParams = CircuitsParams {
     max_evm_rows: MAX_EVM_ROWS,
     max_exp_steps: MAX_EXP_STEPS,
     max_keccak_rows: MAX_KECCAK_ROWS,
+    max_ecrecover_calls: 0,
+    max_sha256_rows: 0,
+    max_modexp_calls: 0,
+    max_ec_add_calls: 0,
+    max_ec_mul_calls: 0,
+    max_ec_pairing_calls: 0,
+    max_mpt_rows: 0,
+    // The geth dev chain follows the Shanghai rules
+    hardfork: Hardfork::Shanghai,
+    // The geth dev chain doesn't credit block rewards
+    block_reward: BlockReward::NoReward,
 };
 
 const EVM_CIRCUIT_DEGREE: u32 = 18;
@@ -3,6 +3,7 @@
 use bus_mapping::circuit_input_builder::{
     build_state_code_db, get_state_accesses, BuilderClient, CircuitsParams,
 };
+use eth_types::evm_types::{BlockReward, Hardfork};
 use integration_tests::{get_client, log_init, GenDataOutput};
 use lazy_static::lazy_static;
 use log::trace;
@@ -24,6 +25,17 @@ async fn test_circuit_input_builder_block(block_num: u64) {
             max_evm_rows: 0,
             max_exp_steps: 1000,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            // The geth dev chain follows the Shanghai rules
+            hardfork: Hardfork::Shanghai,
+            // The geth dev chain doesn't credit block rewards
+            block_reward: BlockReward::NoReward,
         },
     )
     .await
@@ -41,7 +53,8 @@ async fn test_circuit_input_builder_block(block_num: u64) {
     let (proofs, codes) = cli.get_state(block_num, access_set).await.unwrap();
 
     // 4. Build a partial StateDB from step 3
-    let (state_db, code_db) = build_state_code_db(proofs, codes);
+    let (state_db, code_db, state_trie) =
+        build_state_code_db(proofs, codes, prev_state_root).unwrap();
     trace!("StateDB: {:#?}", state_db);
 
     // 5. For each step in TxExecTraces, gen the associated ops and state
@@ -53,7 +66,7 @@ async fn test_circuit_input_builder_block(block_num: u64) {
             &eth_block,
             &geth_trace,
             history_hashes,
-            prev_state_root,
+            state_trie,
         )
         .unwrap();
 
@@ -1,7 +1,13 @@
 //! Mock Block definition and builder related methods.
 
-use crate::{MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY, MOCK_GASLIMIT};
-use eth_types::{Address, Block, Bytes, Hash, Transaction, Word, H64, U64};
+use crate::{
+    MockTransaction, MOCK_BASEFEE, MOCK_CHAIN_ID, MOCK_DIFFICULTY, MOCK_GASLIMIT, MOCK_MIX_HASH,
+};
+use eth_types::{
+    evm_types::Hardfork,
+    geth_types::{block_fields, BlockHeader, Withdrawal},
+    Address, Block, Bytes, Hash, Transaction, Word, H64, U64,
+};
 use ethers_core::types::{Bloom, OtherFields};
 
 #[derive(Clone, Debug)]
@@ -27,14 +33,20 @@ pub struct MockBlock {
     total_difficulty: Word,
     seal_fields: Vec<Bytes>,
     uncles: Vec<Hash>,
+    uncle_headers: Vec<BlockHeader>,
     pub(crate) transactions: Vec<MockTransaction>,
     size: Word,
     mix_hash: Hash,
     nonce: H64,
+    withdrawals: Vec<Withdrawal>,
+    excess_blob_gas: u64,
     // This field is handled here as we assume that all block txs have the same ChainId.
     // Also, the field is stored in the block_table since we don't have a chain_config
     // structure/table.
     pub(crate) chain_id: Word,
+    // This field is handled here as it is not part of the block header, it only
+    // selects the rules the block txs are traced with.
+    pub(crate) hardfork: Hardfork,
 }
 
 impl Default for MockBlock {
@@ -58,17 +70,22 @@ impl Default for MockBlock {
             total_difficulty: Word::zero(),
             seal_fields: Vec::new(),
             uncles: Vec::new(),
+            uncle_headers: Vec::new(),
             transactions: Vec::new(),
             size: Word::zero(),
-            mix_hash: Hash::zero(),
+            mix_hash: *MOCK_MIX_HASH,
             nonce: H64::zero(),
+            withdrawals: Vec::new(),
+            excess_blob_gas: 0,
             chain_id: *MOCK_CHAIN_ID,
+            hardfork: Hardfork::default(),
         }
     }
 }
 
 impl From<MockBlock> for Block<Transaction> {
     fn from(mut mock: MockBlock) -> Self {
+        let other = mock.other_fields();
         Block {
             hash: mock.hash.or_else(|| Some(Hash::default())),
             parent_hash: mock.parent_hash,
@@ -90,19 +107,29 @@ impl From<MockBlock> for Block<Transaction> {
             transactions: mock
                 .transactions
                 .iter_mut()
-                .map(|mock_tx| (mock_tx.chain_id(mock.chain_id).to_owned()).into())
+                .map(|mock_tx| {
+                    // The gas price of an EIP-1559 or blob transaction in a
+                    // block is the effective gas price.
+                    if mock_tx.transaction_type >= U64::from(2) {
+                        mock_tx.gas_price = mock_tx
+                            .max_fee_per_gas
+                            .min(mock.base_fee_per_gas + mock_tx.max_priority_fee_per_gas);
+                    }
+                    (mock_tx.chain_id(mock.chain_id).to_owned()).into()
+                })
                 .collect::<Vec<Transaction>>(),
             size: Some(mock.size),
             mix_hash: Some(mock.mix_hash),
             nonce: Some(mock.nonce),
             base_fee_per_gas: Some(mock.base_fee_per_gas),
-            other: OtherFields::default(),
+            other,
         }
     }
 }
 
 impl From<MockBlock> for Block<()> {
     fn from(mock: MockBlock) -> Self {
+        let other = mock.other_fields();
         Block {
             hash: mock.hash.or_else(|| Some(Hash::default())),
             parent_hash: mock.parent_hash,
@@ -126,7 +153,7 @@ impl From<MockBlock> for Block<()> {
             mix_hash: Some(mock.mix_hash),
             nonce: Some(mock.nonce),
             base_fee_per_gas: Some(mock.base_fee_per_gas),
-            other: OtherFields::default(),
+            other,
         }
     }
 }
@@ -268,12 +295,47 @@ impl MockBlock {
         self
     }
 
+    /// Set uncle_headers field for the MockBlock.
+    pub fn uncle_headers(&mut self, uncle_headers: Vec<BlockHeader>) -> &mut Self {
+        self.uncle_headers = uncle_headers;
+        self
+    }
+
+    /// Set withdrawals field for the MockBlock.
+    pub fn withdrawals(&mut self, withdrawals: Vec<Withdrawal>) -> &mut Self {
+        self.withdrawals = withdrawals;
+        self
+    }
+
+    /// Set excess_blob_gas field for the MockBlock.
+    pub fn excess_blob_gas(&mut self, excess_blob_gas: u64) -> &mut Self {
+        self.excess_blob_gas = excess_blob_gas;
+        self
+    }
+
     /// Set chain_id field for the MockBlock.
     pub fn chain_id(&mut self, chain_id: Word) -> &mut Self {
         self.chain_id = chain_id;
         self
     }
 
+    /// Set hardfork field for the MockBlock.
+    pub fn hardfork(&mut self, hardfork: Hardfork) -> &mut Self {
+        self.hardfork = hardfork;
+        self
+    }
+
+    /// Return the fields of the block that ethers doesn't know about, which
+    /// are the headers of the uncles, the withdrawals (EIP-4895) of Shanghai
+    /// blocks and the excess blob gas (EIP-4844) of Cancun blocks.
+    fn other_fields(&self) -> OtherFields {
+        block_fields(
+            &self.uncle_headers,
+            (self.hardfork >= Hardfork::Shanghai).then_some(self.withdrawals.as_slice()),
+            (self.hardfork >= Hardfork::Cancun).then_some(self.excess_blob_gas),
+        )
+    }
+
     /// Finalizes the current MockBlock under construction returning a new
     /// instance to it.
     pub fn build(&mut self) -> Self {
@@ -1,6 +1,9 @@
 //! Mock types and functions to generate GethData used for tests
 
-use eth_types::{address, bytecode, bytecode::Bytecode, word, Address, Bytes, Word};
+use eth_types::{
+    address, bytecode, bytecode::Bytecode, geth_types::Withdrawal, word, Address, Bytes, Hash,
+    ToBigEndian, Word,
+};
 use ethers_signers::LocalWallet;
 use lazy_static::lazy_static;
 use rand::SeedableRng;
@@ -31,6 +34,8 @@ lazy_static! {
     pub static ref MOCK_CHAIN_ID: Word = Word::from(1338u64);
     /// Mock DIFFICULTY value
     pub static ref MOCK_DIFFICULTY: Word = Word::from(0x200000u64);
+    /// Mock mix hash value, which is the PREVRANDAO value from the merge on
+    pub static ref MOCK_MIX_HASH: Hash = Hash::repeat_byte(0x5a);
     /// Mock accounts loaded with ETH to use for test cases.
     pub static ref MOCK_ACCOUNTS: Vec<Address> = vec![
         address!("0x000000000000000000000000000000000cafe111"),
@@ -39,6 +44,28 @@ lazy_static! {
         address!("0x000000000000000000000000000000000cafe444"),
         address!("0x000000000000000000000000000000000cafe555"),
     ];
+    /// Mock versioned hashes of blobs (EIP-4844), whose first byte is the
+    /// version of the KZG commitment.
+    pub static ref MOCK_BLOB_VERSIONED_HASHES: Vec<Hash> = vec![
+        Hash::from(word!("0x01000000000000000000000000000000000000000000000000000000000b10b1").to_be_bytes()),
+        Hash::from(word!("0x01000000000000000000000000000000000000000000000000000000000b10b2").to_be_bytes()),
+    ];
+    /// Mock withdrawals (EIP-4895): one to the first of the
+    /// [`static@MOCK_ACCOUNTS`] and one to an account that doesn't exist.
+    pub static ref MOCK_WITHDRAWALS: Vec<Withdrawal> = vec![
+        Withdrawal {
+            index: 0x1000u64.into(),
+            validator_index: 0x10u64.into(),
+            address: MOCK_ACCOUNTS[0],
+            amount: 1_000_000_000u64.into(),
+        },
+        Withdrawal {
+            index: 0x1001u64.into(),
+            validator_index: 0x11u64.into(),
+            address: address!("0x00000000000000000000000000000000000dead1"),
+            amount: 0x1234u64.into(),
+        },
+    ];
     /// Mock EVM codes to use for test cases.
     pub static ref MOCK_CODES: Vec<Bytes> = vec![
         Bytes::from([0x60, 0x10, 0x00]), // PUSH1(0x10), STOP
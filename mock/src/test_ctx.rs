@@ -2,6 +2,7 @@
 
 use crate::{eth, MockAccount, MockBlock, MockTransaction};
 use eth_types::{
+    evm_types::Hardfork,
     geth_types::{Account, BlockConstants, GethData},
     Block, Bytecode, Error, GethExecTrace, Transaction, Word,
 };
@@ -157,6 +158,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
         func_block(&mut block, transactions).build();
 
         let chain_id = block.chain_id;
+        let hardfork = block.hardfork;
         let block = Block::<Transaction>::from(block);
         let accounts: [Account; NACC] = accounts
             .iter()
@@ -172,6 +174,7 @@ impl<const NACC: usize, const NTX: usize> TestContext<NACC, NTX> {
             accounts.to_vec(),
             history_hashes.clone(),
             logger_config,
+            hardfork,
         )?;
 
         Ok(Self {
@@ -232,6 +235,7 @@ pub fn gen_geth_traces(
     accounts: Vec<Account>,
     history_hashes: Option<Vec<Word>>,
     logger_config: LoggerConfig,
+    hardfork: Hardfork,
 ) -> Result<Vec<GethExecTrace>, Error> {
     let trace_config = TraceConfig {
         chain_id,
@@ -247,6 +251,7 @@ pub fn gen_geth_traces(
             .map(eth_types::geth_types::Transaction::from)
             .collect(),
         logger_config,
+        hardfork,
     };
     let traces = trace(&trace_config)?;
     Ok(traces)
@@ -256,7 +261,7 @@ pub fn gen_geth_traces(
 /// builder pattern used to construct [`TestContext`]s.
 pub mod helpers {
     use super::*;
-    use crate::MOCK_ACCOUNTS;
+    use crate::{gwei, MOCK_ACCOUNTS, MOCK_BLOB_VERSIONED_HASHES};
 
     /// Generate a simple setup which adds balance to two default accounts from
     /// [`static@MOCK_ACCOUNTS`]:
@@ -278,4 +283,18 @@ pub mod helpers {
     pub fn tx_from_1_to_0(mut txs: Vec<&mut MockTransaction>, accs: [MockAccount; 2]) {
         txs[0].from(accs[1].address).to(accs[0].address);
     }
+
+    /// Generate a single blob transaction (EIP-4844) from the second account
+    /// of the list to the first one, which carries the blobs of
+    /// [`static@MOCK_BLOB_VERSIONED_HASHES`].
+    pub fn blob_tx_from_1_to_0(mut txs: Vec<&mut MockTransaction>, accs: [MockAccount; 2]) {
+        txs[0]
+            .from(accs[1].address)
+            .to(accs[0].address)
+            .transaction_type(3)
+            .max_fee_per_gas(gwei(2))
+            .max_priority_fee_per_gas(gwei(1))
+            .max_fee_per_blob_gas(gwei(1))
+            .blob_versioned_hashes(MOCK_BLOB_VERSIONED_HASHES.clone());
+    }
 }
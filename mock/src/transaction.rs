@@ -2,12 +2,18 @@
 
 use super::{MOCK_ACCOUNTS, MOCK_CHAIN_ID, MOCK_GASPRICE};
 use eth_types::{
-    geth_types::Transaction as GethTransaction, word, AccessList, Address, Bytes, Hash,
-    Transaction, Word, U64,
+    geth_types::{blob_tx_fields, Transaction as GethTransaction},
+    keccak256, word, AccessList, Address, Bytes, Hash, Transaction, Word, U64,
 };
 use ethers_core::{
     rand::{CryptoRng, RngCore},
-    types::{OtherFields, TransactionRequest},
+    types::{
+        transaction::{
+            eip1559::Eip1559TransactionRequest, eip2718::TypedTransaction,
+            eip2930::Eip2930TransactionRequest,
+        },
+        OtherFields, TransactionRequest,
+    },
 };
 use ethers_signers::{LocalWallet, Signer};
 use lazy_static::lazy_static;
@@ -136,6 +142,8 @@ pub struct MockTransaction {
     pub access_list: AccessList,
     pub max_priority_fee_per_gas: Word,
     pub max_fee_per_gas: Word,
+    pub max_fee_per_blob_gas: Word,
+    pub blob_versioned_hashes: Vec<Hash>,
     pub chain_id: Word,
 }
 
@@ -160,6 +168,8 @@ impl Default for MockTransaction {
             access_list: AccessList::default(),
             max_priority_fee_per_gas: Word::zero(),
             max_fee_per_gas: Word::zero(),
+            max_fee_per_blob_gas: Word::zero(),
+            blob_versioned_hashes: Vec::new(),
             chain_id: *MOCK_CHAIN_ID,
         }
     }
@@ -167,6 +177,13 @@ impl Default for MockTransaction {
 
 impl From<MockTransaction> for Transaction {
     fn from(mock: MockTransaction) -> Self {
+        // The blob transactions (EIP-4844) keep their blob fields with the
+        // fields ethers doesn't know about.
+        let other = if mock.transaction_type == U64::from(3) {
+            blob_tx_fields(mock.max_fee_per_blob_gas, &mock.blob_versioned_hashes)
+        } else {
+            OtherFields::default()
+        };
         Transaction {
             hash: mock.hash.unwrap_or_default(),
             nonce: mock.nonce.into(),
@@ -187,7 +204,7 @@ impl From<MockTransaction> for Transaction {
             max_priority_fee_per_gas: Some(mock.max_priority_fee_per_gas),
             max_fee_per_gas: Some(mock.max_fee_per_gas),
             chain_id: Some(mock.chain_id),
-            other: OtherFields::default(),
+            other,
         }
     }
 }
@@ -298,6 +315,18 @@ impl MockTransaction {
         self
     }
 
+    /// Set max_fee_per_blob_gas field for the MockTransaction.
+    pub fn max_fee_per_blob_gas(&mut self, max_fee_per_blob_gas: Word) -> &mut Self {
+        self.max_fee_per_blob_gas = max_fee_per_blob_gas;
+        self
+    }
+
+    /// Set blob_versioned_hashes field for the MockTransaction.
+    pub fn blob_versioned_hashes(&mut self, blob_versioned_hashes: Vec<Hash>) -> &mut Self {
+        self.blob_versioned_hashes = blob_versioned_hashes;
+        self
+    }
+
     /// Set chain_id field for the MockTransaction.
     pub(crate) fn chain_id(&mut self, chain_id: Word) -> &mut Self {
         self.chain_id = chain_id;
@@ -320,14 +349,47 @@ impl MockTransaction {
         match (self.v, self.r, self.s) {
             (None, None, None) => {
                 // Compute sig params and set them in case we have a wallet as `from` attr.
-                if self.from.is_wallet() && self.hash.is_none() {
+                if self.from.is_wallet() && self.hash.is_none() && self.transaction_type == 3.into()
+                {
+                    // ethers has no request for blob transactions (EIP-4844),
+                    // so their signing message is built from the geth
+                    // transaction.
+                    let geth_tx = GethTransaction::from(self.to_owned());
+                    let msg = geth_tx.rlp_unsigned(self.chain_id.low_u64());
+                    let sig = self.from.as_wallet().sign_hash(keccak256(msg).into());
+                    self.sig_data((sig.v - 27, sig.r, sig.s));
+                } else if self.from.is_wallet() && self.hash.is_none() {
+                    let chain_id = self.chain_id.low_u64();
+                    let tx: TypedTransaction = match self.transaction_type.as_u64() {
+                        1 => Eip2930TransactionRequest::new(tx, self.access_list.clone()).into(),
+                        2 => Eip1559TransactionRequest::new()
+                            .from(self.from.address())
+                            .to(self.to.clone().unwrap_or_default().address())
+                            .nonce(self.nonce)
+                            .value(self.value)
+                            .data(self.input.clone())
+                            .gas(self.gas)
+                            .max_priority_fee_per_gas(self.max_priority_fee_per_gas)
+                            .max_fee_per_gas(self.max_fee_per_gas)
+                            .access_list(self.access_list.clone())
+                            .chain_id(chain_id)
+                            .into(),
+                        _ => tx.into(),
+                    };
                     let sig = self
                         .from
                         .as_wallet()
-                        .with_chain_id(self.chain_id.low_u64())
-                        .sign_transaction_sync(&tx.into());
+                        .with_chain_id(chain_id)
+                        .sign_transaction_sync(&tx);
+                    // The `v` of typed transactions is the parity of the y
+                    // coordinate instead of the EIP-155 value.
+                    let v = if self.transaction_type.is_zero() {
+                        sig.v
+                    } else {
+                        sig.v - 35 - chain_id * 2
+                    };
                     // Set sig parameters
-                    self.sig_data((sig.v, sig.r, sig.s));
+                    self.sig_data((v, sig.r, sig.s));
                 }
             }
             (Some(_), Some(_), Some(_)) => (),
@@ -337,7 +399,14 @@ impl MockTransaction {
         // Compute tx hash in case is not already set
         if self.hash.is_none() {
             let tmp_tx = Transaction::from(self.to_owned());
-            self.hash(tmp_tx.hash());
+            // The ethers hash doesn't follow the encoding of blob
+            // transactions.
+            let hash = if self.transaction_type == 3.into() {
+                GethTransaction::from(&tmp_tx).hash(self.chain_id.low_u64())
+            } else {
+                tmp_tx.hash()
+            };
+            self.hash(hash);
         }
 
         self.to_owned()
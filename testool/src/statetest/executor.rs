@@ -1,10 +1,10 @@
 use super::{AccountMatch, StateTest, StateTestResult};
-use crate::config::TestSuite;
+use crate::{config::TestSuite, utils::TEST_FORK};
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
     mock::BlockData,
 };
-use eth_types::{geth_types, Address, Bytes, GethExecTrace, U256, U64};
+use eth_types::{evm_types::BlockReward, geth_types, Address, Bytes, GethExecTrace, U256, U64};
 use ethers_core::{
     k256::ecdsa::SigningKey,
     types::{transaction::eip2718::TypedTransaction, TransactionRequest},
@@ -132,26 +132,33 @@ fn into_traceconfig(st: StateTest) -> (String, TraceConfig, StateTestResult) {
                 timestamp: U256::from(st.env.current_timestamp),
                 number: U64::from(st.env.current_number),
                 difficulty: st.env.current_difficulty,
+                mix_hash: st.env.current_random,
                 gas_limit: U256::from(st.env.current_gas_limit),
                 base_fee: U256::one(),
+                excess_blob_gas: U64::zero(),
+                blob_base_fee: U256::one(),
             },
 
             transactions: vec![geth_types::Transaction {
+                transaction_type: 0,
                 from: st.from,
                 to: st.to,
                 nonce: U64::from(st.nonce),
                 value: st.value,
                 gas_limit: U64::from(st.gas_limit),
                 gas_price: st.gas_price,
-                gas_fee_cap: U256::zero(),
-                gas_tip_cap: U256::zero(),
+                gas_fee_cap: st.gas_price,
+                gas_tip_cap: st.gas_price,
                 call_data: st.data,
                 access_list: None,
+                max_fee_per_blob_gas: U256::zero(),
+                blob_versioned_hashes: Vec::new(),
                 v: sig.v,
                 r: sig.r,
                 s: sig.s,
             }],
             accounts: st.pre,
+            hardfork: TEST_FORK.hardfork(),
             ..Default::default()
         },
         st.result,
@@ -223,6 +230,7 @@ pub fn run_test(
         timestamp: trace_config.block_constants.timestamp,
         number: Some(U64::from(trace_config.block_constants.number.as_u64())),
         difficulty: trace_config.block_constants.difficulty,
+        mix_hash: Some(trace_config.block_constants.mix_hash),
         gas_limit: trace_config.block_constants.gas_limit,
         base_fee_per_gas: Some(trace_config.block_constants.base_fee),
         transactions,
@@ -257,6 +265,16 @@ pub fn run_test(
             max_evm_rows: 0,
             max_exp_steps: 5000,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            hardfork: TEST_FORK.hardfork(),
+            // The state tests don't credit block rewards
+            block_reward: BlockReward::NoReward,
         };
         let block_data = BlockData::new_from_geth_data_with_params(geth_data, circuits_params);
 
@@ -282,6 +300,16 @@ pub fn run_test(
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            hardfork: TEST_FORK.hardfork(),
+            // The state tests don't credit block rewards
+            block_reward: BlockReward::NoReward,
         };
         let (k, circuit, instance, _builder) =
             SuperCircuit::<Fr>::build(geth_data, circuits_params, Fr::from(0x100)).unwrap();
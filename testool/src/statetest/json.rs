@@ -14,6 +14,7 @@ use std::collections::HashMap;
 struct TestEnv {
     current_coinbase: String,
     current_difficulty: String,
+    current_random: Option<String>,
     current_gas_limit: String,
     current_number: String,
     current_timestamp: String,
@@ -193,6 +194,12 @@ impl<'a> JsonStateTestBuilder<'a> {
         Ok(Env {
             current_coinbase: parse::parse_address(&env.current_coinbase)?,
             current_difficulty: parse::parse_u256(&env.current_difficulty)?,
+            current_random: env
+                .current_random
+                .as_deref()
+                .map(parse::parse_hash)
+                .transpose()?
+                .unwrap_or_default(),
             current_gas_limit: parse::parse_u64(&env.current_gas_limit)?,
             current_number: parse::parse_u64(&env.current_number)?,
             current_timestamp: parse::parse_u64(&env.current_timestamp)?,
@@ -372,6 +379,7 @@ mod test {
             env: Env {
                 current_coinbase: Address::from_str("0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba")?,
                 current_difficulty: U256::from(131072u64),
+                current_random: H256::zero(),
                 current_gas_limit: 0xFF112233445566,
                 current_number: 1,
                 current_timestamp: 1000,
@@ -7,6 +7,7 @@ use std::{collections::HashMap, str::FromStr};
 pub struct Env {
     pub current_coinbase: Address,
     pub current_difficulty: U256,
+    pub current_random: H256,
     pub current_gas_limit: u64,
     pub current_number: u64,
     pub current_timestamp: u64,
@@ -94,6 +95,7 @@ impl std::fmt::Display for StateTest {
             "difficulty",
             format!("{}", self.env.current_difficulty)
         ]);
+        table.add_row(row!["random", format!("{:?}", self.env.current_random)]);
         table.add_row(row!["number", format!("{}", self.env.current_number)]);
         table.add_row(row!["timestamp", format!("{}", self.env.current_timestamp)]);
         table.add_row(row!["prev_hash", format!("{:?}", self.env.previous_hash)]);
@@ -263,6 +265,7 @@ impl StateTest {
             env: Env {
                 current_coinbase: *mock::MOCK_COINBASE,
                 current_difficulty: U256::default(),
+                current_random: H256::default(),
                 current_gas_limit: 16000000,
                 current_number: 1,
                 current_timestamp: 1,
@@ -205,6 +205,11 @@ impl<'a> YamlStateTestBuilder<'a> {
         Ok(Env {
             current_coinbase: Self::parse_address(&yaml["currentCoinbase"])?,
             current_difficulty: Self::parse_u256(&yaml["currentDifficulty"])?,
+            current_random: yaml["currentRandom"]
+                .as_str()
+                .map(parse::parse_hash)
+                .transpose()?
+                .unwrap_or_default(),
             current_gas_limit: Self::parse_u64(&yaml["currentGasLimit"])?,
             current_number: Self::parse_u64(&yaml["currentNumber"])?,
             current_timestamp: Self::parse_u64(&yaml["currentTimestamp"])?,
@@ -601,6 +606,7 @@ arith:
             env: Env {
                 current_coinbase: address!("0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba"),
                 current_difficulty: U256::from(0x20000u64),
+                current_random: H256::zero(),
                 current_number: 1,
                 current_timestamp: 1000,
                 current_gas_limit: 100000000,
@@ -1,7 +1,7 @@
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use eth_types::{bytecode::OpcodeWithData, Bytecode, GethExecTrace, U256};
+use eth_types::{bytecode::OpcodeWithData, evm_types::Hardfork, Bytecode, GethExecTrace, U256};
 use log::{error, info};
 use prettytable::Table;
 use std::process::{Command, Stdio};
@@ -70,6 +70,18 @@ impl MainnetFork {
 
         Ok(in_network)
     }
+
+    /// Returns the hardfork whose rules the fork follows.  The forks before
+    /// Berlin are not supported and follow the Berlin rules.
+    pub fn hardfork(&self) -> Hardfork {
+        if *self >= MainnetFork::Merge {
+            Hardfork::Paris
+        } else if *self >= MainnetFork::London {
+            Hardfork::London
+        } else {
+            Hardfork::Berlin
+        }
+    }
 }
 
 pub fn print_trace(trace: GethExecTrace) -> Result<()> {
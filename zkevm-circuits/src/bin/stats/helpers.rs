@@ -8,7 +8,12 @@ use cli_table::{
     format::{Justify, Separator},
     print_stdout, Table, WithTitle,
 };
-use eth_types::{bytecode, evm_types::OpcodeId, geth_types::GethData, Address, Bytecode, ToWord};
+use eth_types::{
+    bytecode,
+    evm_types::{Hardfork, OpcodeId},
+    geth_types::GethData,
+    Address, Bytecode, ToWord,
+};
 use mock::{eth, test_ctx::TestContext, MOCK_ACCOUNTS};
 use strum::IntoEnumIterator;
 use zkevm_circuits::evm_circuit::step::ExecutionState;
@@ -144,7 +149,7 @@ pub(crate) fn print_circuit_stats_by_states(
         if !fn_filter(state) {
             continue;
         }
-        for responsible_op in state.responsible_opcodes() {
+        for responsible_op in state.responsible_opcodes(Hardfork::default()) {
             let opcode = responsible_op.opcode();
             let mut code = bytecode! {
                 PUSH2(0x00)
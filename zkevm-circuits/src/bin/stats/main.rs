@@ -29,16 +29,7 @@ fn main() {
 /// Prints the stats of EVM circuit per execution state.
 fn evm_states_stats() {
     print_circuit_stats_by_states(
-        |state| {
-            // TODO: Enable CREATE/CREATE2 once they are supported
-            !matches!(
-                state,
-                ExecutionState::ErrorInvalidOpcode
-                    | ExecutionState::CREATE
-                    | ExecutionState::CREATE2
-                    | ExecutionState::SELFDESTRUCT
-            )
-        },
+        |state| !matches!(state, ExecutionState::ErrorInvalidOpcode),
         |opcode| match opcode {
             OpcodeId::RETURNDATACOPY => {
                 bytecode! {
@@ -67,16 +58,7 @@ fn evm_states_stats() {
 /// Prints the stats of State circuit per execution state.
 fn state_states_stats() {
     print_circuit_stats_by_states(
-        |state| {
-            // TODO: Enable CREATE/CREATE2 once they are supported
-            !matches!(
-                state,
-                ExecutionState::ErrorInvalidOpcode
-                    | ExecutionState::CREATE
-                    | ExecutionState::CREATE2
-                    | ExecutionState::SELFDESTRUCT
-            )
-        },
+        |state| !matches!(state, ExecutionState::ErrorInvalidOpcode),
         bytecode_prefix_op_big_rws,
         |block, _, step_index| {
             let step = &block.txs[0].steps()[step_index];
@@ -107,7 +89,7 @@ fn copy_states_stats() {
             block
                 .copy_events
                 .iter()
-                .map(|c| c.bytes.len() * 2)
+                .map(|c| c.copy_length() * 2)
                 .sum::<usize>()
         },
     );
@@ -180,6 +162,22 @@ fn get_exec_steps_occupancy() {
         keccak_table,
         LOOKUP_CONFIG[6].1,
         exp_table,
-        LOOKUP_CONFIG[7].1
+        LOOKUP_CONFIG[7].1,
+        ecrecover_table,
+        LOOKUP_CONFIG[8].1,
+        sha256_table,
+        LOOKUP_CONFIG[9].1,
+        modexp_table,
+        LOOKUP_CONFIG[10].1,
+        ec_add_table,
+        LOOKUP_CONFIG[11].1,
+        ec_mul_table,
+        LOOKUP_CONFIG[12].1,
+        ec_pairing_table,
+        LOOKUP_CONFIG[13].1,
+        withdrawal_table,
+        LOOKUP_CONFIG[14].1,
+        uncle_table,
+        LOOKUP_CONFIG[15].1
     );
 }
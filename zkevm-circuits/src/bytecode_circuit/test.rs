@@ -77,7 +77,7 @@ fn bytecode_unrolling() {
         }
     }
     // Now add the different push ops
-    for n in 1..=32 {
+    for n in 0..=32 {
         let data_byte = OpcodeId::PUSH32.as_u8();
         bytecode.push(
             n,
@@ -88,7 +88,7 @@ fn bytecode_unrolling() {
             tag: Fr::from(BytecodeFieldTag::Byte as u64),
             index: Fr::from(rows.len() as u64),
             is_code: Fr::from(true as u64),
-            value: Fr::from(OpcodeId::PUSH1.as_u64() + ((n - 1) as u64)),
+            value: Fr::from(OpcodeId::PUSH0.as_u64() + (n as u64)),
         });
         for _ in 0..n {
             rows.push(BytecodeRow {
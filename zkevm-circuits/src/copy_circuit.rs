@@ -18,8 +18,9 @@ use eth_types::{Field, Word};
 
 use gadgets::{
     binary_number::BinaryNumberChip,
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
     less_than::{LtChip, LtConfig, LtInstruction},
-    util::{and, not, or, Expr},
+    util::{and, not, or, select, Expr},
 };
 use halo2_proofs::{
     circuit::{Layouter, Region, Value},
@@ -57,6 +58,16 @@ pub struct CopyCircuitConfig<F> {
     /// In case of a bytecode tag, this denotes whether or not the copied byte
     /// is an opcode or push data byte.
     pub is_code: Column<Advice>,
+    /// Whether the row belongs to a copy from memory to memory within the
+    /// same call, whose bytes are all read before any of them is written.
+    pub is_memory_copy: Column<Advice>,
+    /// In case of an access list storage keys tag, this denotes the RLC of
+    /// the storage key added to the access list along with the address in
+    /// `value`.
+    pub storage_key: Column<Advice>,
+    /// In case of an access list tag, this denotes whether or not the entry
+    /// was already in the access list.
+    pub is_warm_prev: Column<Advice>,
     /// Whether the row is enabled or not.
     pub q_enable: Column<Fixed>,
     /// The Copy Table contains the columns that are exposed via the lookup
@@ -66,6 +77,9 @@ pub struct CopyCircuitConfig<F> {
     /// Since `src_addr` and `src_addr_end` are u64, 8 bytes are sufficient for
     /// the Lt chip.
     pub addr_lt_addr_end: LtConfig<F, 8>,
+    /// IsZero chip to check whether the read and write rows of a step have
+    /// the same id.
+    pub is_id_unchanged: IsZeroConfig<F>,
     // External tables
     /// TxTable
     pub tx_table: TxTable,
@@ -113,6 +127,9 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
         let value_acc_rlc = meta.advice_column_in(SecondPhase);
         let is_code = meta.advice_column();
         let is_pad = meta.advice_column();
+        let is_memory_copy = meta.advice_column();
+        let storage_key = meta.advice_column_in(SecondPhase);
+        let is_warm_prev = meta.advice_column();
         let is_first = copy_table.is_first;
         let id = copy_table.id;
         let addr = copy_table.addr;
@@ -135,6 +152,12 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             |meta| meta.query_advice(addr, Rotation::cur()),
             |meta| meta.query_advice(src_addr_end, Rotation::cur()),
         );
+        let is_id_unchanged = IsZeroChip::configure(
+            meta,
+            |meta| meta.query_selector(q_step),
+            |meta| meta.query_advice(id, Rotation::cur()) - meta.query_advice(id, Rotation::next()),
+            meta.advice_column_in(SecondPhase),
+        );
 
         meta.create_gate("verify row", |meta| {
             let mut cb = BaseConstraintBuilder::default();
@@ -198,17 +221,28 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 or::expr([
                     tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
                     tag.value_equals(CopyDataType::TxLog, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta),
                 ]),
                 not::expr(meta.query_advice(is_pad, Rotation::cur())),
             ]);
+            let is_memory_copy = meta.query_advice(is_memory_copy, Rotation::cur());
             cb.condition(
-                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                and::expr([
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    not::expr(is_memory_copy.clone()),
+                ]),
                 |cb| {
                     cb.require_equal(
                         "rows[0].rw_counter + rw_diff == rows[1].rw_counter",
                         meta.query_advice(rw_counter, Rotation::cur()) + rw_diff.clone(),
                         meta.query_advice(rw_counter, Rotation::next()),
                     );
+                },
+            );
+            cb.condition(
+                not::expr(meta.query_advice(is_last, Rotation::cur())),
+                |cb| {
                     cb.require_equal(
                         "rows[0].rwc_inc_left - rw_diff == rows[1].rwc_inc_left",
                         meta.query_advice(rwc_inc_left, Rotation::cur()) - rw_diff.clone(),
@@ -228,12 +262,42 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     rw_diff,
                 );
             });
+            // A memory copy reads all the bytes first and then writes them, so
+            // the reads and the writes each take consecutive rw counters.
+            cb.condition(
+                and::expr([
+                    is_memory_copy.clone(),
+                    meta.query_advice(is_first, Rotation::cur()),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[0].rw_counter + bytes_left == rows[1].rw_counter for first memory copy step",
+                        meta.query_advice(rw_counter, Rotation::cur())
+                            + meta.query_advice(bytes_left, Rotation::cur()),
+                        meta.query_advice(rw_counter, Rotation::next()),
+                    );
+                },
+            );
+            cb.condition(
+                and::expr([
+                    is_memory_copy,
+                    not::expr(meta.query_advice(is_last, Rotation::cur())),
+                    not::expr(meta.query_advice(is_last, Rotation::next())),
+                ]),
+                |cb| {
+                    cb.require_equal(
+                        "rows[0].rw_counter + 1 == rows[2].rw_counter for memory copy",
+                        meta.query_advice(rw_counter, Rotation::cur()) + 1.expr(),
+                        meta.query_advice(rw_counter, Rotation(2)),
+                    );
+                },
+            );
 
             cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
         });
 
         meta.create_gate(
-            "Last Step (check value accumulator) Memory => Bytecode or RlcAcc",
+            "Last Step (check value accumulator) Memory => Bytecode or from/to RlcAcc",
             |meta: &mut halo2_proofs::plonk::VirtualCells<F>| {
                 let mut cb = BaseConstraintBuilder::default();
 
@@ -253,7 +317,8 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     and::expr([
                         tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
                         tag.value_equals(CopyDataType::Bytecode, Rotation::next())(meta),
-                    ]) + tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(meta),
+                    ]) + tag.value_equals(CopyDataType::RlcAcc, Rotation::cur())(meta)
+                        + tag.value_equals(CopyDataType::RlcAcc, Rotation::next())(meta),
                 ]))
             },
         );
@@ -281,10 +346,19 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     );
                 },
             );
+            // The storage keys added to the access list are accumulated right
+            // after the address of their item.
+            let is_storage_keys =
+                tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::next())(meta);
             cb.condition(meta.query_advice(is_first, Rotation::cur()), |cb| {
                 cb.require_equal(
                     "value == value_acc_rlc at every first copy event",
-                    meta.query_advice(value, Rotation::cur()),
+                    select::expr(
+                        is_storage_keys.clone(),
+                        meta.query_advice(value, Rotation::cur()) * challenges.keccak_input()
+                            + meta.query_advice(storage_key, Rotation::cur()),
+                        meta.query_advice(value, Rotation::cur()),
+                    ),
                     meta.query_advice(value_acc_rlc, Rotation::cur()),
                 );
             });
@@ -293,6 +367,11 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 meta.query_advice(value, Rotation::cur()),
                 meta.query_advice(value, Rotation::next()),
             );
+            cb.require_equal(
+                "write storage_key == read storage_key",
+                meta.query_advice(storage_key, Rotation::cur()),
+                meta.query_advice(storage_key, Rotation::next()),
+            );
             cb.require_equal(
                 "value_acc_rlc is same for read-write rows",
                 meta.query_advice(value_acc_rlc, Rotation::cur()),
@@ -304,12 +383,18 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                     not::expr(meta.query_advice(is_pad, Rotation::cur())),
                 ]),
                 |cb| {
+                    let value_acc_rlc_next = meta.query_advice(value_acc_rlc, Rotation::cur())
+                        * challenges.keccak_input()
+                        + meta.query_advice(value, Rotation(2));
                     cb.require_equal(
                         "value_acc_rlc(2) == value_acc_rlc(0) * r + value(2)",
                         meta.query_advice(value_acc_rlc, Rotation(2)),
-                        meta.query_advice(value_acc_rlc, Rotation::cur())
-                            * challenges.keccak_input()
-                            + meta.query_advice(value, Rotation(2)),
+                        select::expr(
+                            is_storage_keys,
+                            value_acc_rlc_next.clone() * challenges.keccak_input()
+                                + meta.query_advice(storage_key, Rotation(2)),
+                            value_acc_rlc_next,
+                        ),
                     );
                 },
             );
@@ -329,6 +414,20 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
                 "is_pad == 0 for write row",
                 meta.query_advice(is_pad, Rotation::next()),
             );
+            cb.require_equal(
+                "is_memory_copy == (tag == Memory && tag_next == Memory && id == id_next)",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                and::expr([
+                    tag.value_equals(CopyDataType::Memory, Rotation::cur())(meta),
+                    tag.value_equals(CopyDataType::Memory, Rotation::next())(meta),
+                    is_id_unchanged.expr(),
+                ]),
+            );
+            cb.require_equal(
+                "is_memory_copy is same for read-write rows",
+                meta.query_advice(is_memory_copy, Rotation::cur()),
+                meta.query_advice(is_memory_copy, Rotation::next()),
+            );
 
             cb.gate(and::expr([meta.query_selector(q_step)]))
         });
@@ -378,6 +477,50 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             .collect()
         });
 
+        meta.lookup_any("TxAccessListAccount lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * tag.value_equals(CopyDataType::AccessListAddresses, Rotation::cur())(meta);
+            vec![
+                meta.query_advice(rw_counter, Rotation::cur()),
+                1.expr(),
+                Target::TxAccessListAccount.expr(),
+                meta.query_advice(id, Rotation::cur()), // tx_id
+                meta.query_advice(value, Rotation::cur()), // address
+                0.expr(),
+                0.expr(),
+                1.expr(),
+                meta.query_advice(is_warm_prev, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+            ]
+            .into_iter()
+            .zip(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
+        meta.lookup_any("TxAccessListAccountStorage lookup", |meta| {
+            let cond = meta.query_fixed(q_enable, Rotation::cur())
+                * tag.value_equals(CopyDataType::AccessListStorageKeys, Rotation::cur())(meta);
+            vec![
+                meta.query_advice(rw_counter, Rotation::cur()),
+                1.expr(),
+                Target::TxAccessListAccountStorage.expr(),
+                meta.query_advice(id, Rotation::cur()), // tx_id
+                meta.query_advice(value, Rotation::cur()), // address
+                0.expr(),
+                meta.query_advice(storage_key, Rotation::cur()),
+                1.expr(),
+                meta.query_advice(is_warm_prev, Rotation::cur()),
+                0.expr(),
+                0.expr(),
+            ]
+            .into_iter()
+            .zip(rw_table.table_exprs(meta).into_iter())
+            .map(|(arg, table)| (cond.clone() * arg, table))
+            .collect()
+        });
+
         meta.lookup_any("Bytecode lookup", |meta| {
             let cond = meta.query_fixed(q_enable, Rotation::cur())
                 * tag.value_equals(CopyDataType::Bytecode, Rotation::cur())(meta)
@@ -418,8 +561,12 @@ impl<F: Field> SubCircuitConfig<F> for CopyCircuitConfig<F> {
             value_acc_rlc,
             is_pad,
             is_code,
+            is_memory_copy,
+            storage_key,
+            is_warm_prev,
             q_enable,
             addr_lt_addr_end,
+            is_id_unchanged,
             copy_table,
             tx_table,
             rw_table,
@@ -436,14 +583,12 @@ impl<F: Field> CopyCircuitConfig<F> {
         offset: &mut usize,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        is_id_unchanged_chip: &IsZeroChip<F>,
         challenges: Challenges<Value<F>>,
         copy_event: &CopyEvent,
     ) -> Result<(), Error> {
-        for (step_idx, (tag, table_row, circuit_row)) in
-            CopyTable::assignments(copy_event, challenges)
-                .iter()
-                .enumerate()
-        {
+        let assignments = CopyTable::assignments(copy_event, challenges);
+        for (step_idx, (tag, table_row, circuit_row)) in assignments.iter().enumerate() {
             let is_read = step_idx % 2 == 0;
 
             // Copy table assignments
@@ -476,13 +621,15 @@ impl<F: Field> CopyCircuitConfig<F> {
                 || Value::known(F::ONE),
             )?;
 
-            // is_last, value, is_pad, is_code
+            // is_last, value, is_pad, is_code, storage_key, is_warm_prev
             for (column, &(value, label)) in [
                 self.is_last,
                 self.value,
                 self.value_acc_rlc,
                 self.is_pad,
                 self.is_code,
+                self.storage_key,
+                self.is_warm_prev,
             ]
             .iter()
             .zip_eq(circuit_row)
@@ -495,9 +642,27 @@ impl<F: Field> CopyCircuitConfig<F> {
                 )?;
             }
 
+            // is_memory_copy
+            region.assign_advice(
+                || format!("is_memory_copy at row: {}", *offset),
+                self.is_memory_copy,
+                *offset,
+                || Value::known(F::from(copy_event.is_memory_copy() as u64)),
+            )?;
+
             // tag
             tag_chip.assign(region, *offset, tag)?;
 
+            // is_id_unchanged chip
+            if is_read {
+                let (_, write_table_row, _) = &assignments[step_idx + 1];
+                is_id_unchanged_chip.assign(
+                    region,
+                    *offset,
+                    table_row[1].0 - write_table_row[1].0,
+                )?;
+            }
+
             // lt chip
             if is_read {
                 lt_chip.assign(
@@ -522,7 +687,10 @@ impl<F: Field> CopyCircuitConfig<F> {
         max_copy_rows: usize,
         challenges: Challenges<Value<F>>,
     ) -> Result<(), Error> {
-        let copy_rows_needed = copy_events.iter().map(|c| c.bytes.len() * 2).sum::<usize>();
+        let copy_rows_needed = copy_events
+            .iter()
+            .map(|c| c.copy_length() * 2)
+            .sum::<usize>();
 
         // The `+ 2` is used to take into account the two extra empty copy rows needed
         // to satisfy the query at `Rotation(2)` performed inside of the
@@ -532,6 +700,7 @@ impl<F: Field> CopyCircuitConfig<F> {
 
         let tag_chip = BinaryNumberChip::construct(self.copy_table.tag);
         let lt_chip = LtChip::construct(self.addr_lt_addr_end);
+        let is_id_unchanged_chip = IsZeroChip::construct(self.is_id_unchanged.clone());
 
         lt_chip.load(layouter)?;
 
@@ -542,6 +711,9 @@ impl<F: Field> CopyCircuitConfig<F> {
                 region.name_column(|| "value", self.value);
                 region.name_column(|| "is_code", self.is_code);
                 region.name_column(|| "is_pad", self.is_pad);
+                region.name_column(|| "is_memory_copy", self.is_memory_copy);
+                region.name_column(|| "storage_key", self.storage_key);
+                region.name_column(|| "is_warm_prev", self.is_warm_prev);
 
                 let mut offset = 0;
                 for copy_event in copy_events.iter() {
@@ -550,17 +722,33 @@ impl<F: Field> CopyCircuitConfig<F> {
                         &mut offset,
                         &tag_chip,
                         &lt_chip,
+                        &is_id_unchanged_chip,
                         challenges,
                         copy_event,
                     )?;
                 }
 
                 for _ in 0..max_copy_rows - copy_rows_needed - 2 {
-                    self.assign_padding_row(&mut region, &mut offset, false, &tag_chip, &lt_chip)?;
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        false,
+                        &tag_chip,
+                        &lt_chip,
+                        &is_id_unchanged_chip,
+                    )?;
                 }
 
-                self.assign_padding_row(&mut region, &mut offset, true, &tag_chip, &lt_chip)?;
-                self.assign_padding_row(&mut region, &mut offset, true, &tag_chip, &lt_chip)?;
+                for _ in 0..2 {
+                    self.assign_padding_row(
+                        &mut region,
+                        &mut offset,
+                        true,
+                        &tag_chip,
+                        &lt_chip,
+                        &is_id_unchanged_chip,
+                    )?;
+                }
 
                 Ok(())
             },
@@ -574,6 +762,7 @@ impl<F: Field> CopyCircuitConfig<F> {
         is_last_two: bool,
         tag_chip: &BinaryNumberChip<F, CopyDataType, 3>,
         lt_chip: &LtChip<F, 8>,
+        is_id_unchanged_chip: &IsZeroChip<F>,
     ) -> Result<(), Error> {
         if !is_last_two {
             // q_enable
@@ -666,6 +855,27 @@ impl<F: Field> CopyCircuitConfig<F> {
             *offset,
             || Value::known(F::ZERO),
         )?;
+        // is_memory_copy
+        region.assign_advice(
+            || format!("assign is_memory_copy {}", *offset),
+            self.is_memory_copy,
+            *offset,
+            || Value::known(F::ZERO),
+        )?;
+        // storage_key
+        region.assign_advice(
+            || format!("assign storage_key {}", *offset),
+            self.storage_key,
+            *offset,
+            || Value::known(F::ZERO),
+        )?;
+        // is_warm_prev
+        region.assign_advice(
+            || format!("assign is_warm_prev {}", *offset),
+            self.is_warm_prev,
+            *offset,
+            || Value::known(F::ZERO),
+        )?;
         // rw_counter
         region.assign_advice(
             || format!("assign rw_counter {}", *offset),
@@ -684,6 +894,8 @@ impl<F: Field> CopyCircuitConfig<F> {
         tag_chip.assign(region, *offset, &CopyDataType::Padding)?;
         // Assign LT gadget
         lt_chip.assign(region, *offset, F::ZERO, F::ONE)?;
+        // Assign IsZero gadget
+        is_id_unchanged_chip.assign(region, *offset, Value::known(F::ZERO))?;
 
         *offset += 1;
 
@@ -787,7 +999,7 @@ impl<F: Field> SubCircuit<F> for CopyCircuit<F> {
             block
                 .copy_events
                 .iter()
-                .map(|c| c.bytes.len() * 2)
+                .map(|c| c.copy_length() * 2)
                 .sum::<usize>()
                 + 2,
             block.circuits_params.max_copy_rows,
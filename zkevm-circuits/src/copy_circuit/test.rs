@@ -9,7 +9,10 @@ use bus_mapping::{
     evm::Sha3CodeGen,
     mock::BlockData,
 };
-use eth_types::{bytecode, geth_types::GethData, ToWord, Word};
+use eth_types::{
+    bytecode, evm_types::Hardfork, geth_types::GethData, AccessList, AccessListItem, ToWord, Word,
+    H256,
+};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
@@ -189,6 +192,77 @@ fn gen_tx_log_data() -> CircuitInputBuilder {
     builder
 }
 
+fn gen_mcopy_data() -> CircuitInputBuilder {
+    // The destination overlaps the source, so all the bytes are read before
+    // any of them is written.
+    let code = bytecode! {
+        PUSH32(Word::MAX)   // value
+        PUSH1(0)            // offset
+        MSTORE
+        PUSH1(0x30)         // length
+        PUSH1(0x00)         // src_offset
+        PUSH1(0x10)         // dst_offset
+        MCOPY
+        STOP
+    };
+    let test_ctx = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(code),
+        |mut txs, accs| {
+            txs[0].to(accs[0].address).from(accs[1].address);
+        },
+        |block, _tx| block.number(0xcafeu64).hardfork(Hardfork::Cancun),
+    )
+    .unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data_with_params(
+        block.clone(),
+        CircuitsParams {
+            hardfork: Hardfork::Cancun,
+            ..Default::default()
+        },
+    )
+    .new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
+fn gen_access_list_data() -> CircuitInputBuilder {
+    // The callee and the duplicated storage key are already warm when added
+    // to the access list.
+    let access_list = AccessList(vec![
+        AccessListItem {
+            address: MOCK_ACCOUNTS[0],
+            storage_keys: vec![H256::zero()],
+        },
+        AccessListItem {
+            address: MOCK_ACCOUNTS[2],
+            storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(1)],
+        },
+    ]);
+    let test_ctx = TestContext::<2, 1>::new(
+        None,
+        account_0_code_account_1_no_code(bytecode! { STOP }),
+        |mut txs, accs| {
+            txs[0]
+                .to(accs[0].address)
+                .from(accs[1].address)
+                .transaction_type(1)
+                .access_list(access_list);
+        },
+        |block, _tx| block.number(0xcafeu64),
+    )
+    .unwrap();
+    let block: GethData = test_ctx.into();
+    let mut builder = BlockData::new_from_geth_data(block.clone()).new_circuit_input_builder();
+    builder
+        .handle_block(&block.eth_block, &block.geth_traces)
+        .unwrap();
+    builder
+}
+
 #[test]
 fn copy_circuit_valid_calldatacopy() {
     let builder = gen_calldatacopy_data();
@@ -224,6 +298,20 @@ fn copy_circuit_valid_tx_log() {
     assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
 }
 
+#[test]
+fn copy_circuit_valid_mcopy() {
+    let builder = gen_mcopy_data();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
+#[test]
+fn copy_circuit_valid_access_list() {
+    let builder = gen_access_list_data();
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+    assert_eq!(test_copy_circuit_from_block(10, block), Ok(()));
+}
+
 #[test]
 fn copy_circuit_invalid_calldatacopy() {
     let mut builder = gen_calldatacopy_data();
@@ -304,6 +392,37 @@ fn copy_circuit_invalid_tx_log() {
     );
 }
 
+#[test]
+fn copy_circuit_invalid_mcopy() {
+    let mut builder = gen_mcopy_data();
+
+    // modify first byte of first copy event
+    builder.block.copy_events[0].bytes[0].0 =
+        builder.block.copy_events[0].bytes[0].0.wrapping_add(1);
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["Memory lookup", "Memory lookup"],
+    );
+}
+
+#[test]
+fn copy_circuit_invalid_access_list() {
+    let mut builder = gen_access_list_data();
+
+    // modify is_warm_prev of the first address of the access list
+    builder.block.copy_events[0].access_list[0].2 = !builder.block.copy_events[0].access_list[0].2;
+
+    let block = block_convert::<Fr>(&builder.block, &builder.code_db).unwrap();
+
+    assert_error_matches(
+        test_copy_circuit_from_block(10, block),
+        vec!["TxAccessListAccount lookup"],
+    );
+}
+
 #[test]
 fn variadic_size_check() {
     let builder = gen_tx_log_data();
@@ -0,0 +1,1026 @@
+//! The ECC circuit implementation, which verifies the calls to the ecAdd, ecMul
+//! and ecPairing precompiles over the BN254 curve with the halo2wrong ECC and
+//! integer chips.
+//!
+//! Whether the inputs of the calls are valid and their results are computed in
+//! the circuit from the inputs, and copied into the rows of the `EcAddTable`,
+//! `EcMulTable` and `EcPairingTable`, which are padded with disabled rows up to
+//! the max number of calls.  A point is valid when its coordinates are lower
+//! than the modulus of the field, and it is on the curve or the point at
+//! infinity, and G2 points must also be in the subgroup of order r.  The
+//! operations take the generator in place of the point at infinity and of the
+//! invalid points, and the results of the calls with an invalid input are
+//! (0, 0) or false.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+mod pairing;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EccCircuit as TestEccCircuit;
+
+use self::pairing::{AssignedFq2, AssignedG2, AssignedPairingInput, PairingChip};
+use crate::{
+    table::{EcAddTable, EcMulTable, EcPairingTable, LookupTable},
+    tx_circuit::sign_verify::{
+        integer_to_bytes_le, SignVerifyConfig, BIT_LEN_LIMB, NUMBER_OF_LIMBS,
+    },
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::{
+    EcAddAuxData, EcMulAuxData, EcPairingAuxData, EcPairingPair, EC_PAIRING_PAIRS_LIMIT,
+};
+use ecc::{maingate, AssignedPoint, EccConfig, GeneralEccChip};
+use eth_types::{Field, ToLittleEndian, Word};
+use gadgets::util::split_u256;
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine, G1, G2},
+        ff::{Field as _, PrimeField},
+        group::{Curve, Group},
+    },
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+};
+use integer::{
+    rns::{Integer, Rns},
+    AssignedInteger, IntegerChip, IntegerInstructions, Range,
+};
+use itertools::Itertools;
+use log::error;
+use maingate::{
+    AssignedCondition, AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+    RangeConfig, RangeInstructions, RegionCtx, Term,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use std::{iter, marker::PhantomData, rc::Rc};
+
+type EccChip<F> = GeneralEccChip<G1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type FqChip<F> = IntegerChip<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedFq<F> = AssignedInteger<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+type AssignedG1<F> = AssignedPoint<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>;
+
+/// Config for EccCircuit
+#[derive(Clone, Debug)]
+pub struct EccCircuitConfig<F: Field> {
+    main_gate_config: MainGateConfig,
+    range_config: RangeConfig,
+    /// Selector of the rows of the RLC of the inputs of the ecPairing calls
+    q_rlc: Selector,
+    rlc: Column<Advice>,
+    _marker: PhantomData<F>,
+    // External tables
+    ec_add_table: EcAddTable,
+    ec_mul_table: EcMulTable,
+    ec_pairing_table: EcPairingTable,
+}
+
+/// Circuit configuration arguments
+pub struct EccCircuitConfigArgs<F: Field> {
+    /// EcAddTable
+    pub ec_add_table: EcAddTable,
+    /// EcMulTable
+    pub ec_mul_table: EcMulTable,
+    /// EcPairingTable
+    pub ec_pairing_table: EcPairingTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EccCircuitConfig<F> {
+    type ConfigArgs = EccCircuitConfigArgs<F>;
+
+    /// Return a new EccCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ec_add_table,
+            ec_mul_table,
+            ec_pairing_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let (rns_base, rns_scalar) = EccChip::<F>::rns();
+        let main_gate_config = MainGate::<F>::configure(meta);
+        let range_config = RangeChip::<F>::configure(
+            meta,
+            &main_gate_config,
+            vec![BIT_LEN_LIMB / NUMBER_OF_LIMBS, 8],
+            [rns_base.overflow_lengths(), rns_scalar.overflow_lengths()].concat(),
+        );
+
+        let q_rlc = meta.selector();
+        let rlc = meta.advice_column_in(SecondPhase);
+        meta.enable_equality(rlc);
+        SignVerifyConfig::configure_rlc(
+            meta,
+            "ec_pairing_input_rlc",
+            main_gate_config.clone(),
+            q_rlc,
+            rlc,
+            challenges.keccak_input(),
+        );
+
+        // The table rows are copied from the results computed by the chips.
+        for column in iter::empty()
+            .chain(<EcAddTable as LookupTable<F>>::advice_columns(
+                &ec_add_table,
+            ))
+            .chain(<EcMulTable as LookupTable<F>>::advice_columns(
+                &ec_mul_table,
+            ))
+            .chain(<EcPairingTable as LookupTable<F>>::advice_columns(
+                &ec_pairing_table,
+            ))
+        {
+            meta.enable_equality(column);
+        }
+
+        Self {
+            main_gate_config,
+            range_config,
+            q_rlc,
+            rlc,
+            _marker: PhantomData,
+            ec_add_table,
+            ec_mul_table,
+            ec_pairing_table,
+        }
+    }
+}
+
+impl<F: Field> EccCircuitConfig<F> {
+    /// Load the RangeChip table.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let range_chip = RangeChip::<F>::new(self.range_config.clone());
+        range_chip.load_table(layouter)
+    }
+
+    fn ecc_chip_config(&self) -> EccConfig {
+        EccConfig::new(self.range_config.clone(), self.main_gate_config.clone())
+    }
+}
+
+/// Element of a prime field assigned from a word, along with its little-endian
+/// bytes and their 128-bit lo and hi parts.
+struct AssignedWord<W: PrimeField, F: Field> {
+    integer: AssignedInteger<W, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    bytes_le: [AssignedValue<F>; 32],
+    lo_hi: [AssignedValue<F>; 2],
+}
+
+/// Word of the input of a call assigned as an element of a prime field, which
+/// is the word reduced modulo the modulus of the field.
+struct AssignedInputWord<W: PrimeField, F: Field> {
+    reduced: AssignedWord<W, F>,
+    /// Little-endian bytes of the word as given in the input.
+    bytes_le: Vec<AssignedValue<F>>,
+    /// 128-bit lo and hi parts of the word as given in the input.
+    lo_hi: [AssignedValue<F>; 2],
+    /// Whether the word is lower than the modulus.
+    is_in_field: AssignedCondition<F>,
+}
+
+/// G1 point of the input of a call, where the point at infinity is (0, 0).
+struct AssignedG1Input<F: Field> {
+    x: AssignedInputWord<Fq, F>,
+    y: AssignedInputWord<Fq, F>,
+    is_infinity: AssignedCondition<F>,
+    is_valid: AssignedCondition<F>,
+    /// The point, or the generator when it's the point at infinity or it's
+    /// invalid, which is asserted to be on the curve.
+    point: AssignedG1<F>,
+}
+
+impl<F: Field> AssignedG1Input<F> {
+    /// The point as given in the input, when it's valid.
+    fn raw(&self) -> AssignedG1<F> {
+        AssignedPoint::new(
+            self.x.reduced.integer.clone(),
+            self.y.reduced.integer.clone(),
+        )
+    }
+}
+
+struct ChipsRef<'a, F: Field> {
+    main_gate: &'a MainGate<F>,
+    range_chip: &'a RangeChip<F>,
+    ecc_chip: &'a EccChip<F>,
+    fq_rns: Rc<Rns<Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+    fr_rns: Rc<Rns<Fr, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+}
+
+impl<'a, F: Field> ChipsRef<'a, F> {
+    fn fq_chip(&self) -> &FqChip<F> {
+        self.ecc_chip.base_field_chip()
+    }
+
+    /// Compose the 128-bit lo and hi parts of little-endian bytes.
+    fn compose_lo_hi(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        bytes_le: &[AssignedValue<F>; 32],
+    ) -> Result<[AssignedValue<F>; 2], Error> {
+        let powers_of_256 = iter::successors(Some(F::ONE), |coeff| Some(F::from(256) * coeff))
+            .take(16)
+            .collect_vec();
+        let mut lo_hi = Vec::with_capacity(2);
+        for bytes in bytes_le.chunks(16) {
+            let terms = bytes
+                .iter()
+                .zip(powers_of_256.iter())
+                .map(|(byte, coeff)| Term::Assigned(byte, *coeff))
+                .collect_vec();
+            lo_hi.push(self.main_gate.compose(ctx, &terms, F::ZERO)?);
+        }
+        Ok(lo_hi.try_into().unwrap())
+    }
+
+    /// Return the word of a reduced integer, which is asserted to be in
+    /// canonical form.
+    fn integer_to_word<W: PrimeField>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chip: &IntegerChip<W, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        integer: AssignedInteger<W, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ) -> Result<AssignedWord<W, F>, Error> {
+        chip.assert_in_field(ctx, &integer)?;
+        let bytes_le = integer_to_bytes_le(ctx, self.range_chip, &integer)?;
+        let lo_hi = self.compose_lo_hi(ctx, &bytes_le)?;
+        Ok(AssignedWord {
+            integer,
+            bytes_le,
+            lo_hi,
+        })
+    }
+
+    /// Assign a word of the input of a call reduced modulo the modulus of W,
+    /// as `word = reduced + quotient * modulus`, where the quotient and the
+    /// carry from the lo to the hi parts are lower than 256.
+    fn assign_input_word<W: PrimeField>(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chip: &IntegerChip<W, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        rns: &Rc<Rns<W, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>>,
+        word: Word,
+    ) -> Result<AssignedInputWord<W, F>, Error> {
+        let main_gate = self.main_gate;
+        let modulus = Word::from_str_radix(&W::MODULUS[2..], 16).unwrap();
+        let (reduced, quotient) = (word % modulus, word / modulus);
+        let (word_lo, word_hi) = split_u256(&word);
+        let (modulus_lo, modulus_hi) = split_u256(&modulus);
+        let (reduced_lo, _) = split_u256(&reduced);
+        let carry = (reduced_lo + quotient * modulus_lo - word_lo) >> 128;
+
+        let mut bytes_le = Vec::with_capacity(32);
+        let mut lo_hi = Vec::with_capacity(2);
+        for value in [word_lo, word_hi] {
+            let value = Value::known(F::from_u128(value.as_u128()));
+            let (value, bytes) = self.range_chip.decompose(ctx, value, 8, 128)?;
+            bytes_le.extend(bytes);
+            lo_hi.push(value);
+        }
+        let quotient =
+            self.range_chip
+                .decompose(ctx, Value::known(F::from(quotient.as_u64())), 8, 8)?;
+        let carry = self
+            .range_chip
+            .decompose(ctx, Value::known(F::from(carry.as_u64())), 8, 8)?;
+        let reduced = W::from_repr(reduced.to_le_bytes()).unwrap();
+        let reduced = Integer::from_fe(reduced, Rc::clone(rns));
+        let reduced = chip.assign_integer(ctx, Value::known(reduced).into(), Range::Remainder)?;
+        let reduced = self.integer_to_word(ctx, chip, reduced)?;
+        let [modulus_lo, modulus_hi] =
+            [modulus_lo, modulus_hi].map(|value| F::from_u128(value.as_u128()));
+        let two_to_128 = F::from_u128(1 << 64) * F::from_u128(1 << 64);
+        for terms in [
+            [
+                Term::Assigned(&reduced.lo_hi[0], F::ONE),
+                Term::Assigned(&quotient.0, modulus_lo),
+                Term::Assigned(&carry.0, -two_to_128),
+                Term::Assigned(&lo_hi[0], -F::ONE),
+            ],
+            [
+                Term::Assigned(&reduced.lo_hi[1], F::ONE),
+                Term::Assigned(&quotient.0, modulus_hi),
+                Term::Assigned(&carry.0, F::ONE),
+                Term::Assigned(&lo_hi[1], -F::ONE),
+            ],
+        ] {
+            let value = main_gate.compose(ctx, &terms, F::ZERO)?;
+            main_gate.assert_zero(ctx, &value)?;
+        }
+        let is_in_field = main_gate.is_zero(ctx, &quotient.0)?;
+
+        Ok(AssignedInputWord {
+            reduced,
+            bytes_le,
+            lo_hi: lo_hi.try_into().unwrap(),
+            is_in_field,
+        })
+    }
+
+    /// Assign a coordinate of a point of the input of a call.
+    fn assign_fq(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        word: Word,
+    ) -> Result<AssignedInputWord<Fq, F>, Error> {
+        self.assign_input_word(ctx, self.fq_chip(), &self.fq_rns, word)
+    }
+
+    /// Return the words of the coordinates of a point.
+    fn point_to_words(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedG1<F>,
+    ) -> Result<[AssignedWord<Fq, F>; 2], Error> {
+        let x = self.fq_chip().reduce(ctx, point.x())?;
+        let y = self.fq_chip().reduce(ctx, point.y())?;
+        Ok([
+            self.integer_to_word(ctx, self.fq_chip(), x)?,
+            self.integer_to_word(ctx, self.fq_chip(), y)?,
+        ])
+    }
+
+    /// Return whether all the words, given by their lo and hi parts, are zero.
+    fn is_zero_words(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        words: &[&[AssignedValue<F>; 2]],
+    ) -> Result<AssignedCondition<F>, Error> {
+        let terms = words
+            .iter()
+            .flat_map(|lo_hi| lo_hi.iter())
+            .map(|value| Term::Assigned(value, F::ONE))
+            .collect_vec();
+        let sum = self.main_gate.compose(ctx, &terms, F::ZERO)?;
+        self.main_gate.is_zero(ctx, &sum)
+    }
+
+    /// Return whether two elements of Fq in canonical form are equal.
+    fn is_equal_fq(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq<F>,
+        b: &AssignedFq<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let mut is_equal = self.main_gate.assign_constant(ctx, F::ONE)?;
+        for (a, b) in a.limbs().iter().zip(b.limbs().iter()) {
+            let is_limb_equal = self.main_gate.is_equal(ctx, a.as_ref(), b.as_ref())?;
+            is_equal = self.main_gate.and(ctx, &is_equal, &is_limb_equal)?;
+        }
+        Ok(is_equal)
+    }
+
+    /// Return whether an element of Fq is equal to another, after reducing
+    /// them to their canonical form.
+    fn is_equal_fq_reduced(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq<F>,
+        b: &AssignedFq<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let a = self.fq_chip().reduce(ctx, a)?;
+        self.fq_chip().assert_in_field(ctx, &a)?;
+        let b = self.fq_chip().reduce(ctx, b)?;
+        self.fq_chip().assert_in_field(ctx, &b)?;
+        self.is_equal_fq(ctx, &a, &b)
+    }
+
+    /// Return whether a point is on the curve y^2 = x^3 + 3.
+    fn is_on_curve(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedG1<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let fq_chip = self.fq_chip();
+        let b = fq_chip.assign_constant(ctx, <G1Affine as CurveAffine>::b())?;
+        let y_square = fq_chip.square(ctx, point.y())?;
+        let x_square = fq_chip.square(ctx, point.x())?;
+        let x_cube = fq_chip.mul(ctx, &x_square, point.x())?;
+        let x_cube_plus_b = fq_chip.add(ctx, &x_cube, &b)?;
+        self.is_equal_fq_reduced(ctx, &y_square, &x_cube_plus_b)
+    }
+
+    /// Return the point at infinity as (0, 0), which is not on the curve.
+    fn infinity(&self, ctx: &mut RegionCtx<'_, F>) -> Result<AssignedG1<F>, Error> {
+        let zero = self.fq_chip().assign_constant(ctx, Fq::ZERO)?;
+        Ok(AssignedPoint::new(zero.clone(), zero))
+    }
+
+    /// Assign a G1 point of the input of a call.
+    fn assign_g1(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        x: Word,
+        y: Word,
+    ) -> Result<AssignedG1Input<F>, Error> {
+        let main_gate = self.main_gate;
+        let x = self.assign_fq(ctx, x)?;
+        let y = self.assign_fq(ctx, y)?;
+        let is_infinity = self.is_zero_words(ctx, &[&x.lo_hi, &y.lo_hi])?;
+        let raw = AssignedPoint::new(x.reduced.integer.clone(), y.reduced.integer.clone());
+        let is_on_curve = self.is_on_curve(ctx, &raw)?;
+        let is_in_field = main_gate.and(ctx, &x.is_in_field, &y.is_in_field)?;
+        let is_on_curve_or_infinity = main_gate.or(ctx, &is_on_curve, &is_infinity)?;
+        let is_valid = main_gate.and(ctx, &is_in_field, &is_on_curve_or_infinity)?;
+
+        let generator = self
+            .ecc_chip
+            .assign_constant(ctx, G1::generator().to_affine())?;
+        let is_generator = main_gate.or(ctx, &is_infinity, &main_gate.not(ctx, &is_valid)?)?;
+        let point = self.ecc_chip.select(ctx, &is_generator, &generator, &raw)?;
+        self.ecc_chip.assert_is_on_curve(ctx, &point)?;
+        Ok(AssignedG1Input {
+            x,
+            y,
+            is_infinity,
+            is_valid,
+            point,
+        })
+    }
+}
+
+/// ECC Circuit for verifying the calls to the ecAdd, ecMul and ecPairing
+/// precompiles
+#[derive(Clone, Debug)]
+pub struct EccCircuit<F: Field> {
+    /// Max number of supported calls to the ecAdd precompile
+    pub max_add_calls: usize,
+    /// Max number of supported calls to the ecMul precompile
+    pub max_mul_calls: usize,
+    /// Max number of supported calls to the ecPairing precompile
+    pub max_pairing_calls: usize,
+    /// Calls to the ecAdd precompile
+    pub add_events: Vec<EcAddAuxData>,
+    /// Calls to the ecMul precompile
+    pub mul_events: Vec<EcMulAuxData>,
+    /// Calls to the ecPairing precompile
+    pub pairing_events: Vec<EcPairingAuxData>,
+    /// Aux generator for EccChip
+    pub aux_generator: G1Affine,
+    /// Window size for EccChip
+    pub window_size: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> Default for EccCircuit<F> {
+    fn default() -> Self {
+        Self::new(0, 0, 0, Vec::new(), Vec::new(), Vec::new())
+    }
+}
+
+impl<F: Field> EccCircuit<F> {
+    /// Return a new EccCircuit
+    pub fn new(
+        max_add_calls: usize,
+        max_mul_calls: usize,
+        max_pairing_calls: usize,
+        add_events: Vec<EcAddAuxData>,
+        mul_events: Vec<EcMulAuxData>,
+        pairing_events: Vec<EcPairingAuxData>,
+    ) -> Self {
+        // TODO: Investigate if it is safe to use a random point as aux generator that
+        // is choosen by the prover, as done in the SignVerifyChip.
+        // https://github.com/privacy-scaling-explorations/halo2wrong/issues/53
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let aux_generator = <G1Affine as CurveAffine>::CurveExt::random(&mut rng).to_affine();
+        Self {
+            max_add_calls,
+            max_mul_calls,
+            max_pairing_calls,
+            add_events,
+            mul_events,
+            pairing_events,
+            aux_generator,
+            window_size: 4,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(
+        num_add_calls: usize,
+        num_mul_calls: usize,
+        num_pairing_calls: usize,
+    ) -> usize {
+        // The value rows_range_chip_table is the one of the SignVerifyChip, and
+        // rows_ec_add, rows_ec_mul and rows_ec_pairing are upper estimates from
+        // the number of integer operations of each call.
+        // TODO: Measure them from log debugs while running the tests with
+        // `RUST_LOG=debug`, as done for the SignVerifyChip.
+        let rows_range_chip_table = 295188;
+        let rows_ecc_chip_aux = 226;
+        let rows_ec_add = 1_500;
+        let rows_ec_mul = 60_000;
+        let rows_ec_pairing = 3_000_000;
+        std::cmp::max(
+            rows_range_chip_table,
+            rows_ecc_chip_aux
+                + rows_ec_add * num_add_calls
+                + rows_ec_mul * num_mul_calls
+                + rows_ec_pairing * num_pairing_calls,
+        )
+    }
+
+    /// Events padded up to the max number of calls, along with whether they
+    /// are enabled.
+    fn padded_events<T: Clone>(events: &[T], max_calls: usize, padding: T) -> Vec<(bool, T)> {
+        events
+            .iter()
+            .cloned()
+            .map(|event| (true, event))
+            .chain(iter::repeat((false, padding)))
+            .take(max_calls)
+            .collect()
+    }
+
+    /// Rows of a table for the padded events, after an all-zero row.
+    fn table_rows<T, const N: usize>(
+        events: &[(bool, T)],
+        assignments: impl Fn(&T) -> [Value<F>; N],
+    ) -> Vec<[Value<F>; N]> {
+        iter::once([Value::known(F::ZERO); N])
+            .chain(events.iter().map(|(is_enabled, event)| {
+                let mut row = assignments(event);
+                row[0] = Value::known(F::from(*is_enabled as u64));
+                row
+            }))
+            .collect()
+    }
+
+    /// Assign the rows of a table, which are copied from the cells computed
+    /// by the chips.
+    fn assign_table<const N: usize>(
+        layouter: &mut impl Layouter<F>,
+        name: &'static str,
+        columns: &[Column<Advice>],
+        rows: &[[Value<F>; N]],
+        assigned_rows: &[[AssignedValue<F>; N]],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || name,
+            |mut region| {
+                for (offset, (row, assigned_row)) in rows.iter().zip_eq(assigned_rows).enumerate() {
+                    for ((&column, value), assigned) in
+                        columns.iter().zip_eq(row).zip_eq(assigned_row)
+                    {
+                        let cell = region.assign_advice(
+                            || format!("{} row {}", name, offset),
+                            column,
+                            offset,
+                            || *value,
+                        )?;
+                        region.constrain_equal(cell.cell(), assigned.cell())?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assign a row of the ecAdd table, without `is_enabled`.
+    fn assign_ec_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        event: &EcAddAuxData,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let ecc_chip = chips.ecc_chip;
+        let p = chips.assign_g1(ctx, event.p_x, event.p_y)?;
+        let q = chips.assign_g1(ctx, event.q_x, event.q_y)?;
+        let infinity = chips.infinity(ctx)?;
+
+        // The incomplete addition of the points requires distinct
+        // x-coordinates, so the doubled point is added instead when they're
+        // equal, and then the result is selected.
+        let doubled = ecc_chip.double(ctx, &p.point)?;
+        let is_x_equal = chips.is_equal_fq(ctx, p.point.x(), q.point.x())?;
+        let is_y_equal = chips.is_equal_fq(ctx, p.point.y(), q.point.y())?;
+        let addend = ecc_chip.select(ctx, &is_x_equal, &doubled, &q.point)?;
+        let sum = ecc_chip.add(ctx, &p.point, &addend)?;
+        let doubled_or_infinity = ecc_chip.select(ctx, &is_y_equal, &doubled, &infinity)?;
+        let r = ecc_chip.select(ctx, &is_x_equal, &doubled_or_infinity, &sum)?;
+        let r = ecc_chip.select(ctx, &q.is_infinity, &p.raw(), &r)?;
+        let r = ecc_chip.select(ctx, &p.is_infinity, &q.raw(), &r)?;
+        let is_valid = chips.main_gate.and(ctx, &p.is_valid, &q.is_valid)?;
+        let r = ecc_chip.select(ctx, &is_valid, &r, &infinity)?;
+        let [r_x, r_y] = chips.point_to_words(ctx, &r)?;
+
+        Ok([
+            p.x.lo_hi,
+            p.y.lo_hi,
+            q.x.lo_hi,
+            q.y.lo_hi,
+            [is_valid],
+            r_x.lo_hi,
+            r_y.lo_hi,
+        ]
+        .concat())
+    }
+
+    /// Assign a row of the ecMul table, without `is_enabled`.
+    fn assign_ec_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        event: &EcMulAuxData,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let ChipsRef {
+            main_gate,
+            ecc_chip,
+            ..
+        } = chips;
+        let scalar_chip = ecc_chip.scalar_field_chip();
+        let p = chips.assign_g1(ctx, event.p_x, event.p_y)?;
+        let scalar = chips.assign_input_word(ctx, scalar_chip, &chips.fr_rns, event.scalar)?;
+        let reduced = scalar.reduced;
+
+        // The scalar multiplication requires a non-zero scalar, so 1 is used
+        // instead of 0, and then the result is selected.
+        let is_scalar_zero = chips.is_zero_words(ctx, &[&reduced.lo_hi])?;
+        let one = scalar_chip.assign_constant(ctx, Fr::ONE)?;
+        let scalar = scalar_chip.select(ctx, &one, &reduced.integer, &is_scalar_zero)?;
+        let product = ecc_chip.mul(ctx, &p.point, &scalar, self.window_size)?;
+        let is_infinity = main_gate.or(ctx, &p.is_infinity, &is_scalar_zero)?;
+        let is_infinity = main_gate.or(ctx, &is_infinity, &main_gate.not(ctx, &p.is_valid)?)?;
+        let infinity = chips.infinity(ctx)?;
+        let r = ecc_chip.select(ctx, &is_infinity, &infinity, &product)?;
+        let [r_x, r_y] = chips.point_to_words(ctx, &r)?;
+
+        Ok([
+            p.x.lo_hi,
+            p.y.lo_hi,
+            scalar.lo_hi,
+            [p.is_valid],
+            r_x.lo_hi,
+            r_y.lo_hi,
+        ]
+        .concat())
+    }
+
+    /// Assign a row of the ecPairing table, without `is_enabled`.
+    fn assign_ec_pairing(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        event: &EcPairingAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedValue<F>>, Error> {
+        let pairing_chip = PairingChip::new(ctx, chips.fq_chip(), chips.main_gate)?;
+        let main_gate = chips.main_gate;
+        let g2_generator = G2::generator().to_affine();
+        let g2_generator = AssignedG2 {
+            x: pairing_chip.fq2_constant(ctx, g2_generator.x.c0, g2_generator.x.c1)?,
+            y: pairing_chip.fq2_constant(ctx, g2_generator.y.c0, g2_generator.y.c1)?,
+        };
+
+        // The input is padded at the front with pairs of points at infinity,
+        // which are skipped and keep the RLC of the input.
+        let num_padding_pairs = EC_PAIRING_PAIRS_LIMIT - event.pairs.len();
+        let mut pairs = Vec::with_capacity(EC_PAIRING_PAIRS_LIMIT);
+        let mut input_bytes = Vec::new();
+        let mut is_valid = main_gate.assign_constant(ctx, F::ONE)?;
+        for pair in iter::repeat(EcPairingPair::default())
+            .take(num_padding_pairs)
+            .chain(event.pairs.iter().cloned())
+        {
+            let g1 = chips.assign_g1(ctx, pair.g1_x, pair.g1_y)?;
+            // [x_im, x_re, y_im, y_re]
+            let mut g2_words = Vec::with_capacity(4);
+            for word in [pair.g2_x[0], pair.g2_x[1], pair.g2_y[0], pair.g2_y[1]] {
+                g2_words.push(chips.assign_fq(ctx, word)?);
+            }
+            let g2_is_infinity =
+                chips.is_zero_words(ctx, &g2_words.iter().map(|word| &word.lo_hi).collect_vec())?;
+            let g2 = AssignedG2 {
+                x: AssignedFq2 {
+                    c0: g2_words[1].reduced.integer.clone(),
+                    c1: g2_words[0].reduced.integer.clone(),
+                },
+                y: AssignedFq2 {
+                    c0: g2_words[3].reduced.integer.clone(),
+                    c1: g2_words[2].reduced.integer.clone(),
+                },
+            };
+            let g2_is_on_curve = pairing_chip.g2_is_on_curve(ctx, &g2)?;
+            let g2_is_on_curve_or_infinity = main_gate.or(ctx, &g2_is_on_curve, &g2_is_infinity)?;
+            let g2 = AssignedG2 {
+                x: pairing_chip.fq2_select(ctx, &g2.x, &g2_generator.x, &g2_is_on_curve)?,
+                y: pairing_chip.fq2_select(ctx, &g2.y, &g2_generator.y, &g2_is_on_curve)?,
+            };
+            // The point at infinity and the points not in the twist were
+            // replaced by the generator, so the subgroup check holds for them.
+            let g2_is_in_subgroup = pairing_chip.g2_is_in_subgroup(ctx, &g2)?;
+            let g2 = AssignedG2 {
+                x: pairing_chip.fq2_select(ctx, &g2.x, &g2_generator.x, &g2_is_in_subgroup)?,
+                y: pairing_chip.fq2_select(ctx, &g2.y, &g2_generator.y, &g2_is_in_subgroup)?,
+            };
+            let mut is_pair_valid =
+                main_gate.and(ctx, &g1.is_valid, &g2_is_on_curve_or_infinity)?;
+            is_pair_valid = main_gate.and(ctx, &is_pair_valid, &g2_is_in_subgroup)?;
+            for word in g2_words.iter() {
+                is_pair_valid = main_gate.and(ctx, &is_pair_valid, &word.is_in_field)?;
+            }
+            is_valid = main_gate.and(ctx, &is_valid, &is_pair_valid)?;
+            let skip = main_gate.or(ctx, &g1.is_infinity, &g2_is_infinity)?;
+            let skip = main_gate.or(ctx, &skip, &main_gate.not(ctx, &is_pair_valid)?)?;
+
+            // The values of the bytes are passed explicitly, as the ones of
+            // the assigned cells are unknown in the second phase.
+            for (word, assigned) in pair
+                .words()
+                .iter()
+                .zip([&g1.x, &g1.y].into_iter().chain(g2_words.iter()))
+            {
+                input_bytes.extend(
+                    assigned
+                        .bytes_le
+                        .iter()
+                        .rev()
+                        .zip(word.to_be_bytes())
+                        .map(|(byte, value)| (byte.clone(), Value::known(F::from(value as u64)))),
+                );
+            }
+            pairs.push(AssignedPairingInput {
+                g1: g1.point,
+                g2,
+                skip,
+            });
+        }
+
+        let input_rlc =
+            self.assign_rlc(config, ctx, chips, input_bytes, challenges.keccak_input())?;
+        let result = pairing_chip.multi_pairing(ctx, &pairs)?;
+        let is_one = pairing_chip.fq12_is_one(ctx, &result)?;
+        let output = main_gate.and(ctx, &is_valid, &is_one)?;
+
+        Ok(vec![input_rlc, is_valid, output])
+    }
+
+    /// Assign the RLC of big-endian bytes, which are copied into the rows of
+    /// the RLC gate.
+    fn assign_rlc(
+        &self,
+        config: &EccCircuitConfig<F>,
+        ctx: &mut RegionCtx<'_, F>,
+        chips: &ChipsRef<F>,
+        inputs_be: Vec<(AssignedValue<F>, Value<F>)>,
+        challenge: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let zero = chips.main_gate.assign_constant(ctx, F::ZERO)?;
+        let columns = config.main_gate_config.advices();
+        let num_padding = (columns.len() - inputs_be.len() % columns.len()) % columns.len();
+        let inputs_be = iter::repeat((zero.clone(), Value::known(F::ZERO)))
+            .take(num_padding)
+            .chain(inputs_be)
+            .collect_vec();
+
+        let mut rlc = Value::known(F::ZERO);
+        for (chunk_idx, chunk) in inputs_be.chunks_exact(columns.len()).enumerate() {
+            ctx.enable(config.q_rlc)?;
+            let assigned_rlc = ctx.assign_advice(|| "ec_pairing_input_rlc", config.rlc, rlc)?;
+            for ((idx, column), (cell, value)) in
+                (chunk_idx * chunk.len()..).zip(columns).zip(chunk)
+            {
+                let copied =
+                    ctx.assign_advice(|| format!("ec_pairing_input_byte[{idx}]"), column, *value)?;
+                ctx.constrain_equal(cell.cell(), copied.cell())?;
+            }
+            if chunk_idx == 0 {
+                ctx.constrain_equal(zero.cell(), assigned_rlc.cell())?;
+            }
+            rlc = iter::once(rlc)
+                .chain(chunk.iter().map(|(_, value)| *value))
+                .fold(Value::known(F::ZERO), |acc, input| acc * challenge + input);
+            ctx.next();
+        }
+
+        let assigned_rlc = ctx.assign_advice(|| "ec_pairing_input_rlc", config.rlc, rlc)?;
+        ctx.next();
+
+        Ok(assigned_rlc)
+    }
+
+    /// Assign the rows computed by the chips for the padded events, after an
+    /// all-zero row.
+    fn assign_rows<T, R, const N: usize>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        name: &'static str,
+        main_gate: &MainGate<F>,
+        events: &[(bool, T)],
+        mut assign_row: R,
+    ) -> Result<Vec<[AssignedValue<F>; N]>, Error>
+    where
+        R: FnMut(&mut RegionCtx<'_, F>, &T) -> Result<Vec<AssignedValue<F>>, Error>,
+    {
+        layouter.assign_region(
+            || name,
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                let zero = main_gate.assign_constant(&mut ctx, F::ZERO)?;
+                let mut rows = vec![[(); N].map(|_| zero.clone())];
+                for (is_enabled, event) in events {
+                    let is_enabled =
+                        main_gate.assign_constant(&mut ctx, F::from(*is_enabled as u64))?;
+                    let row = iter::once(is_enabled)
+                        .chain(assign_row(&mut ctx, event)?)
+                        .collect_vec();
+                    rows.push(row.try_into().unwrap());
+                }
+                log::debug!("{}: {} rows", name, ctx.offset());
+                Ok(rows)
+            },
+        )
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EccCircuit<F> {
+    type Config = EccCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_calls = |max_calls: usize, num_events: usize| {
+            if max_calls == 0 {
+                num_events
+            } else {
+                max_calls
+            }
+        };
+        let params = &block.circuits_params;
+        Self::new(
+            max_calls(params.max_ec_add_calls, block.ec_add_events.len()),
+            max_calls(params.max_ec_mul_calls, block.ec_mul_events.len()),
+            max_calls(params.max_ec_pairing_calls, block.ec_pairing_events.len()),
+            block.ec_add_events.clone(),
+            block.ec_mul_events.clone(),
+            block.ec_pairing_events.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        let params = &block.circuits_params;
+        (
+            Self::min_num_rows(
+                block.ec_add_events.len(),
+                block.ec_mul_events.len(),
+                block.ec_pairing_events.len(),
+            ),
+            Self::min_num_rows(
+                params.max_ec_add_calls.max(block.ec_add_events.len()),
+                params.max_ec_mul_calls.max(block.ec_mul_events.len()),
+                params
+                    .max_ec_pairing_calls
+                    .max(block.ec_pairing_events.len()),
+            ),
+        )
+    }
+
+    /// Make the assignments to the EccCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        for (name, num_events, max_calls) in [
+            ("ec add", self.add_events.len(), self.max_add_calls),
+            ("ec mul", self.mul_events.len(), self.max_mul_calls),
+            (
+                "ec pairing",
+                self.pairing_events.len(),
+                self.max_pairing_calls,
+            ),
+        ] {
+            if num_events > max_calls {
+                error!(
+                    "{} events.len() = {} > max_calls = {}",
+                    name, num_events, max_calls
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+        if let Some(event) = self
+            .pairing_events
+            .iter()
+            .find(|event| !event.is_supported())
+        {
+            error!(
+                "ec pairing pairs.len() = {} > EC_PAIRING_PAIRS_LIMIT = {}",
+                event.pairs.len(),
+                EC_PAIRING_PAIRS_LIMIT
+            );
+            return Err(Error::Synthesis);
+        }
+
+        config.load_aux_tables(layouter)?;
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let mut ecc_chip = EccChip::<F>::new(config.ecc_chip_config());
+        layouter.assign_region(
+            || "ecc chip aux",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                ecc_chip.assign_aux_generator(&mut ctx, Value::known(self.aux_generator))?;
+                ecc_chip.assign_aux(&mut ctx, self.window_size, 1)?;
+                log::debug!("ecc chip aux: {} rows", ctx.offset());
+                Ok(())
+            },
+        )?;
+        let (fq_rns, fr_rns) = EccChip::<F>::rns();
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            fq_rns: Rc::new(fq_rns),
+            fr_rns: Rc::new(fr_rns),
+        };
+
+        let add_events = Self::padded_events(
+            &self.add_events,
+            self.max_add_calls,
+            EcAddAuxData {
+                is_valid: true,
+                ..Default::default()
+            },
+        );
+        let assigned_rows =
+            self.assign_rows(layouter, "ec add", &main_gate, &add_events, |ctx, event| {
+                self.assign_ec_add(ctx, &chips, event)
+            })?;
+        Self::assign_table(
+            layouter,
+            "ec add table",
+            &<EcAddTable as LookupTable<F>>::advice_columns(&config.ec_add_table),
+            &Self::table_rows(&add_events, |event| EcAddTable::assignments(event)[0]),
+            &assigned_rows,
+        )?;
+
+        let mul_events = Self::padded_events(
+            &self.mul_events,
+            self.max_mul_calls,
+            EcMulAuxData {
+                is_valid: true,
+                ..Default::default()
+            },
+        );
+        let assigned_rows =
+            self.assign_rows(layouter, "ec mul", &main_gate, &mul_events, |ctx, event| {
+                self.assign_ec_mul(ctx, &chips, event)
+            })?;
+        Self::assign_table(
+            layouter,
+            "ec mul table",
+            &<EcMulTable as LookupTable<F>>::advice_columns(&config.ec_mul_table),
+            &Self::table_rows(&mul_events, |event| EcMulTable::assignments(event)[0]),
+            &assigned_rows,
+        )?;
+
+        // The padding calls have an empty input, whose output is true.
+        let pairing_events = Self::padded_events(
+            &self.pairing_events,
+            self.max_pairing_calls,
+            EcPairingAuxData {
+                pairs: Vec::new(),
+                output: true,
+                is_valid: true,
+            },
+        );
+        let assigned_rows = self.assign_rows(
+            layouter,
+            "ec pairing",
+            &main_gate,
+            &pairing_events,
+            |ctx, event| self.assign_ec_pairing(config, ctx, &chips, event, challenges),
+        )?;
+        Self::assign_table(
+            layouter,
+            "ec pairing table",
+            &<EcPairingTable as LookupTable<F>>::advice_columns(&config.ec_pairing_table),
+            &Self::table_rows(&pairing_events, |event| {
+                EcPairingTable::assignments(event, challenges)[0]
+            }),
+            &assigned_rows,
+        )
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
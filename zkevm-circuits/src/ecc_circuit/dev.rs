@@ -0,0 +1,53 @@
+pub use super::EccCircuit;
+
+use crate::{
+    ecc_circuit::{EccCircuitConfig, EccCircuitConfigArgs},
+    table::{EcAddTable, EcMulTable, EcPairingTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+};
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+impl<F: Field> Circuit<F> for EccCircuit<F> {
+    type Config = (EccCircuitConfig<F>, Challenges);
+    type FloorPlanner = SimpleFloorPlanner;
+    type Params = ();
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let ec_add_table = EcAddTable::construct(meta);
+        let ec_mul_table = EcMulTable::construct(meta);
+        let ec_pairing_table = EcPairingTable::construct(meta);
+        let challenges = Challenges::construct(meta);
+
+        let config = {
+            let challenges = challenges.exprs(meta);
+            EccCircuitConfig::new(
+                meta,
+                EccCircuitConfigArgs {
+                    ec_add_table,
+                    ec_mul_table,
+                    ec_pairing_table,
+                    challenges,
+                },
+            )
+        };
+
+        (config, challenges)
+    }
+
+    fn synthesize(
+        &self,
+        (config, challenges): Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let challenges = challenges.values(&mut layouter);
+        self.synthesize_sub(&config, &challenges, &mut layouter)
+    }
+}
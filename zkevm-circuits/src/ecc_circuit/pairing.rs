@@ -0,0 +1,994 @@
+//! BN254 optimal ate pairing on top of the IntegerChip, with the towers of
+//! extension fields used by halo2curves:
+//!
+//! - Fq2 = Fq[u] / (u^2 + 1)
+//! - Fq6 = Fq2[v] / (v^3 - ξ), where ξ = 9 + u
+//! - Fq12 = Fq6[w] / (w^2 - v)
+//!
+//! The G2 points are in the D-type twist E': y^2 = x^3 + 3 / ξ over Fq2 and
+//! all of them are in affine coordinates.
+
+use super::{AssignedFq, AssignedG1, FqChip};
+use crate::tx_circuit::sign_verify::BIT_LEN_LIMB;
+use eth_types::{Field, ToLittleEndian, Word};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::{
+        bn256::{Fq, G2Affine},
+        ff::{Field as _, PrimeField},
+    },
+    plonk::Error,
+};
+use integer::IntegerInstructions;
+use maingate::{AssignedCondition, MainGate, MainGateInstructions, RegionCtx, Term};
+
+/// Non-adjacent form of 6x + 2, where x is the BN parameter, from the least
+/// significant digit.
+const ATE_LOOP_COUNT: [i8; 66] = [
+    0, 0, 0, 1, 0, 1, 0, -1, 0, 0, -1, 0, 0, 0, 1, 0, 0, -1, 0, -1, 0, 0, 0, 1, 0, -1, 0, 0, 0, 0,
+    -1, 0, 0, 1, 0, -1, 0, 0, 1, 0, 0, 0, 0, 0, -1, 0, 0, -1, 0, 1, 0, -1, 0, 0, 0, -1, 0, -1, 0,
+    0, 0, 1, 0, -1, 0, 1,
+];
+
+/// BN parameter x.
+const BN_X: u64 = 4965661367192848881;
+
+/// Scalar 6x^2 of the endomorphism ψ on the G2 points of order r, where ψ is
+/// the untwist-Frobenius-twist endomorphism.
+const G2_PSI_SCALAR: u128 = 6 * (BN_X as u128) * (BN_X as u128);
+
+/// Coefficients ξ^(i * (p^k - 1) / 6) of the k-th power of the Frobenius map
+/// for the coefficients of w^i, for k = 1, 2, 3 and i = 1, ..., 5.  They are in
+/// Fq for k = 2.
+const FROBENIUS_COEFFS: [[[&str; 2]; 5]; 3] = [
+    [
+        [
+            "1284b71c2865a7dfe8b99fdd76e68b605c521e08292f2176d60b35dadcc9e470",
+            "246996f3b4fae7e6a6327cfe12150b8e747992778eeec7e5ca5cf05f80f362ac",
+        ],
+        [
+            "2fb347984f7911f74c0bec3cf559b143b78cc310c2c3330c99e39557176f553d",
+            "16c9e55061ebae204ba4cc8bd75a079432ae2a1d0b7c9dce1665d51c640fcba2",
+        ],
+        [
+            "063cf305489af5dcdc5ec698b6e2f9b9dbaae0eda9c95998dc54014671a0135a",
+            "07c03cbcac41049a0704b5a7ec796f2b21807dc98fa25bd282d37f632623b0e3",
+        ],
+        [
+            "05b54f5e64eea80180f3c0b75a181e84d33365f7be94ec72848a1f55921ea762",
+            "2c145edbe7fd8aee9f3a80b03b0b1c923685d2ea1bdec763c13b4711cd2b8126",
+        ],
+        [
+            "0183c1e74f798649e93a3661a4353ff4425c459b55aa1bd32ea2c810eab7692f",
+            "12acf2ca76fd0675a27fb246c7729f7db080cb99678e2ac024c6b8ee6e0c2c4b",
+        ],
+    ],
+    [
+        [
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd49",
+            "0",
+        ],
+        [
+            "30644e72e131a0295e6dd9e7e0acccb0c28f069fbb966e3de4bd44e5607cfd48",
+            "0",
+        ],
+        [
+            "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd46",
+            "0",
+        ],
+        ["59e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe", "0"],
+        ["59e26bcea0d48bacd4f263f1acdb5c4f5763473177ffffff", "0"],
+    ],
+    [
+        [
+            "19dc81cfcc82e4bbefe9608cd0acaa90894cb38dbe55d24ae86f7d391ed4a67f",
+            "00abf8b60be77d7306cbeee33576139d7f03a5e397d439ec7694aa2bf4c0c101",
+        ],
+        [
+            "0856e078b755ef0abaff1c77959f25ac805ffd3d5d6942d37b746ee87bdcfb6d",
+            "04f1de41b3d1766fa9f30e6dec26094f0fdf31bf98ff2631380cab2baaa586de",
+        ],
+        [
+            "2a275b6d9896aa4cdbf17f1dca9e5ea3bbd689a3bea870f45fcc8ad066dce9ed",
+            "28a411b634f09b8fb14b900e9507e9327600ecc7d8cf6ebab94d0cb3b2594c64",
+        ],
+        [
+            "0bc58c6611c08dab19bee0f7b5b2444ee633094575b06bcb0e1a92bc3ccbf066",
+            "23d5e999e1910a12feb0f6ef0cd21d04a44a9e08737f96e55fe3ed9d730c239f",
+        ],
+        [
+            "13c49044952c0905711699fa3b4d3f692ed68098967c84a5ebde847076261b43",
+            "16db366a59b1dd0b9fb1b2282a48633d3e2ddaea200280211f25041384282499",
+        ],
+    ],
+];
+
+fn fq_from_hex(hex: &str) -> Fq {
+    let word = Word::from_str_radix(hex, 16).expect("valid hex");
+    Fq::from_repr(word.to_le_bytes()).unwrap()
+}
+
+/// Element of Fq2 as c0 + c1 * u.
+#[derive(Clone, Debug)]
+pub(super) struct AssignedFq2<F: Field> {
+    pub(super) c0: AssignedFq<F>,
+    pub(super) c1: AssignedFq<F>,
+}
+
+/// Element of Fq6 as c0 + c1 * v + c2 * v^2.
+#[derive(Clone, Debug)]
+struct AssignedFq6<F: Field> {
+    c0: AssignedFq2<F>,
+    c1: AssignedFq2<F>,
+    c2: AssignedFq2<F>,
+}
+
+/// Element of Fq12 as c0 + c1 * w.
+#[derive(Clone, Debug)]
+pub(super) struct AssignedFq12<F: Field> {
+    c0: AssignedFq6<F>,
+    c1: AssignedFq6<F>,
+}
+
+impl<F: Field> AssignedFq12<F> {
+    /// Coefficients of w^0, ..., w^5.
+    fn coeffs(&self) -> [&AssignedFq2<F>; 6] {
+        [
+            &self.c0.c0,
+            &self.c1.c0,
+            &self.c0.c1,
+            &self.c1.c1,
+            &self.c0.c2,
+            &self.c1.c2,
+        ]
+    }
+
+    fn from_coeffs([w0, w1, w2, w3, w4, w5]: [AssignedFq2<F>; 6]) -> Self {
+        Self {
+            c0: AssignedFq6 {
+                c0: w0,
+                c1: w2,
+                c2: w4,
+            },
+            c1: AssignedFq6 {
+                c0: w1,
+                c1: w3,
+                c2: w5,
+            },
+        }
+    }
+}
+
+/// G2 point in affine coordinates.
+#[derive(Clone, Debug)]
+pub(super) struct AssignedG2<F: Field> {
+    pub(super) x: AssignedFq2<F>,
+    pub(super) y: AssignedFq2<F>,
+}
+
+/// Pair of points of a pairing, which contributes with 1 to the product of
+/// the pairings when `skip` is true.
+#[derive(Clone, Debug)]
+pub(super) struct AssignedPairingInput<F: Field> {
+    pub(super) g1: AssignedG1<F>,
+    pub(super) g2: AssignedG2<F>,
+    pub(super) skip: AssignedCondition<F>,
+}
+
+/// Chip computing the product of the pairings of a list of pairs of points.
+pub(super) struct PairingChip<'a, F: Field> {
+    fq_chip: &'a FqChip<F>,
+    main_gate: &'a MainGate<F>,
+    fq_zero: AssignedFq<F>,
+    fq_one: AssignedFq<F>,
+}
+
+impl<'a, F: Field> PairingChip<'a, F> {
+    pub(super) fn new(
+        ctx: &mut RegionCtx<'_, F>,
+        fq_chip: &'a FqChip<F>,
+        main_gate: &'a MainGate<F>,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            fq_chip,
+            main_gate,
+            fq_zero: fq_chip.assign_constant(ctx, Fq::ZERO)?,
+            fq_one: fq_chip.assign_constant(ctx, Fq::ONE)?,
+        })
+    }
+
+    // Fq2
+
+    pub(super) fn fq2_constant(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        c0: Fq,
+        c1: Fq,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.assign_constant(ctx, c0)?,
+            c1: self.fq_chip.assign_constant(ctx, c1)?,
+        })
+    }
+
+    fn fq2_zero(&self) -> AssignedFq2<F> {
+        AssignedFq2 {
+            c0: self.fq_zero.clone(),
+            c1: self.fq_zero.clone(),
+        }
+    }
+
+    fn fq2_one(&self) -> AssignedFq2<F> {
+        AssignedFq2 {
+            c0: self.fq_one.clone(),
+            c1: self.fq_zero.clone(),
+        }
+    }
+
+    fn fq2_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.add(ctx, &a.c0, &b.c0)?,
+            c1: self.fq_chip.add(ctx, &a.c1, &b.c1)?,
+        })
+    }
+
+    fn fq2_sub(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.sub(ctx, &a.c0, &b.c0)?,
+            c1: self.fq_chip.sub(ctx, &a.c1, &b.c1)?,
+        })
+    }
+
+    fn fq2_neg(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.neg(ctx, &a.c0)?,
+            c1: self.fq_chip.neg(ctx, &a.c1)?,
+        })
+    }
+
+    fn fq2_conjugate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: a.c0.clone(),
+            c1: self.fq_chip.neg(ctx, &a.c1)?,
+        })
+    }
+
+    fn fq2_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        // Karatsuba: (a0 + a1 u)(b0 + b1 u) = a0 b0 - a1 b1 + ((a0 + a1)(b0 + b1)
+        // - a0 b0 - a1 b1) u
+        let fq_chip = self.fq_chip;
+        let t0 = fq_chip.mul(ctx, &a.c0, &b.c0)?;
+        let t1 = fq_chip.mul(ctx, &a.c1, &b.c1)?;
+        let a_sum = fq_chip.add(ctx, &a.c0, &a.c1)?;
+        let b_sum = fq_chip.add(ctx, &b.c0, &b.c1)?;
+        let t2 = fq_chip.mul(ctx, &a_sum, &b_sum)?;
+        let t2 = fq_chip.sub(ctx, &t2, &t0)?;
+        Ok(AssignedFq2 {
+            c0: fq_chip.sub(ctx, &t0, &t1)?,
+            c1: fq_chip.sub(ctx, &t2, &t1)?,
+        })
+    }
+
+    fn fq2_square(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        // (a0 + a1 u)^2 = (a0 + a1)(a0 - a1) + 2 a0 a1 u
+        let fq_chip = self.fq_chip;
+        let sum = fq_chip.add(ctx, &a.c0, &a.c1)?;
+        let diff = fq_chip.sub(ctx, &a.c0, &a.c1)?;
+        let product = fq_chip.mul(ctx, &a.c0, &a.c1)?;
+        Ok(AssignedFq2 {
+            c0: fq_chip.mul(ctx, &sum, &diff)?,
+            c1: fq_chip.add(ctx, &product, &product)?,
+        })
+    }
+
+    fn fq2_mul_by_fq(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.mul(ctx, &a.c0, b)?,
+            c1: self.fq_chip.mul(ctx, &a.c1, b)?,
+        })
+    }
+
+    fn fq_mul_by_nine(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq<F>,
+    ) -> Result<AssignedFq<F>, Error> {
+        let fq_chip = self.fq_chip;
+        let double = fq_chip.add(ctx, a, a)?;
+        let quadruple = fq_chip.add(ctx, &double, &double)?;
+        let octuple = fq_chip.add(ctx, &quadruple, &quadruple)?;
+        fq_chip.add(ctx, &octuple, a)
+    }
+
+    /// Multiply by ξ = 9 + u.
+    fn fq2_mul_by_nonresidue(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        // (a0 + a1 u)(9 + u) = 9 a0 - a1 + (a0 + 9 a1) u
+        let a0_nine_times = self.fq_mul_by_nine(ctx, &a.c0)?;
+        let a1_nine_times = self.fq_mul_by_nine(ctx, &a.c1)?;
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.sub(ctx, &a0_nine_times, &a.c1)?,
+            c1: self.fq_chip.add(ctx, &a.c0, &a1_nine_times)?,
+        })
+    }
+
+    fn fq2_invert(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        // (a0 + a1 u)^-1 = (a0 - a1 u) / (a0^2 + a1^2)
+        let fq_chip = self.fq_chip;
+        let a0_square = fq_chip.square(ctx, &a.c0)?;
+        let a1_square = fq_chip.square(ctx, &a.c1)?;
+        let norm = fq_chip.add(ctx, &a0_square, &a1_square)?;
+        let norm_inverse = fq_chip.invert_incomplete(ctx, &norm)?;
+        let c1 = fq_chip.mul(ctx, &a.c1, &norm_inverse)?;
+        Ok(AssignedFq2 {
+            c0: fq_chip.mul(ctx, &a.c0, &norm_inverse)?,
+            c1: fq_chip.neg(ctx, &c1)?,
+        })
+    }
+
+    /// Return whether two elements are equal, after reducing their
+    /// coefficients to their canonical form.
+    fn fq2_is_equal(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let main_gate = self.main_gate;
+        let mut is_equal = main_gate.assign_constant(ctx, F::ONE)?;
+        for (a, b) in [(&a.c0, &b.c0), (&a.c1, &b.c1)] {
+            let a = self.fq_chip.reduce(ctx, a)?;
+            self.fq_chip.assert_in_field(ctx, &a)?;
+            let b = self.fq_chip.reduce(ctx, b)?;
+            self.fq_chip.assert_in_field(ctx, &b)?;
+            for (a, b) in a.limbs().iter().zip(b.limbs().iter()) {
+                let is_limb_equal = main_gate.is_equal(ctx, a.as_ref(), b.as_ref())?;
+                is_equal = main_gate.and(ctx, &is_equal, &is_limb_equal)?;
+            }
+        }
+        Ok(is_equal)
+    }
+
+    pub(super) fn fq2_select(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq2<F>,
+        b: &AssignedFq2<F>,
+        cond: &AssignedCondition<F>,
+    ) -> Result<AssignedFq2<F>, Error> {
+        Ok(AssignedFq2 {
+            c0: self.fq_chip.select(ctx, &a.c0, &b.c0, cond)?,
+            c1: self.fq_chip.select(ctx, &a.c1, &b.c1, cond)?,
+        })
+    }
+
+    // Fq6
+
+    fn fq6_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_add(ctx, &a.c0, &b.c0)?,
+            c1: self.fq2_add(ctx, &a.c1, &b.c1)?,
+            c2: self.fq2_add(ctx, &a.c2, &b.c2)?,
+        })
+    }
+
+    fn fq6_sub(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_sub(ctx, &a.c0, &b.c0)?,
+            c1: self.fq2_sub(ctx, &a.c1, &b.c1)?,
+            c2: self.fq2_sub(ctx, &a.c2, &b.c2)?,
+        })
+    }
+
+    fn fq6_neg(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_neg(ctx, &a.c0)?,
+            c1: self.fq2_neg(ctx, &a.c1)?,
+            c2: self.fq2_neg(ctx, &a.c2)?,
+        })
+    }
+
+    fn fq6_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+        b: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        // Karatsuba
+        let v0 = self.fq2_mul(ctx, &a.c0, &b.c0)?;
+        let v1 = self.fq2_mul(ctx, &a.c1, &b.c1)?;
+        let v2 = self.fq2_mul(ctx, &a.c2, &b.c2)?;
+
+        // c0 = v0 + ξ((a1 + a2)(b1 + b2) - v1 - v2)
+        let a_sum = self.fq2_add(ctx, &a.c1, &a.c2)?;
+        let b_sum = self.fq2_add(ctx, &b.c1, &b.c2)?;
+        let t = self.fq2_mul(ctx, &a_sum, &b_sum)?;
+        let t = self.fq2_sub(ctx, &t, &v1)?;
+        let t = self.fq2_sub(ctx, &t, &v2)?;
+        let t = self.fq2_mul_by_nonresidue(ctx, &t)?;
+        let c0 = self.fq2_add(ctx, &v0, &t)?;
+
+        // c1 = (a0 + a1)(b0 + b1) - v0 - v1 + ξ v2
+        let a_sum = self.fq2_add(ctx, &a.c0, &a.c1)?;
+        let b_sum = self.fq2_add(ctx, &b.c0, &b.c1)?;
+        let t = self.fq2_mul(ctx, &a_sum, &b_sum)?;
+        let t = self.fq2_sub(ctx, &t, &v0)?;
+        let t = self.fq2_sub(ctx, &t, &v1)?;
+        let v2_nonresidue = self.fq2_mul_by_nonresidue(ctx, &v2)?;
+        let c1 = self.fq2_add(ctx, &t, &v2_nonresidue)?;
+
+        // c2 = (a0 + a2)(b0 + b2) - v0 + v1 - v2
+        let a_sum = self.fq2_add(ctx, &a.c0, &a.c2)?;
+        let b_sum = self.fq2_add(ctx, &b.c0, &b.c2)?;
+        let t = self.fq2_mul(ctx, &a_sum, &b_sum)?;
+        let t = self.fq2_sub(ctx, &t, &v0)?;
+        let t = self.fq2_add(ctx, &t, &v1)?;
+        let c2 = self.fq2_sub(ctx, &t, &v2)?;
+
+        Ok(AssignedFq6 { c0, c1, c2 })
+    }
+
+    /// Multiply by v.
+    fn fq6_mul_by_nonresidue(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        Ok(AssignedFq6 {
+            c0: self.fq2_mul_by_nonresidue(ctx, &a.c2)?,
+            c1: a.c0.clone(),
+            c2: a.c1.clone(),
+        })
+    }
+
+    fn fq6_invert(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq6<F>,
+    ) -> Result<AssignedFq6<F>, Error> {
+        // t0 = a0^2 - ξ a1 a2
+        let a0_square = self.fq2_square(ctx, &a.c0)?;
+        let a1_a2 = self.fq2_mul(ctx, &a.c1, &a.c2)?;
+        let a1_a2 = self.fq2_mul_by_nonresidue(ctx, &a1_a2)?;
+        let t0 = self.fq2_sub(ctx, &a0_square, &a1_a2)?;
+        // t1 = ξ a2^2 - a0 a1
+        let a2_square = self.fq2_square(ctx, &a.c2)?;
+        let a2_square = self.fq2_mul_by_nonresidue(ctx, &a2_square)?;
+        let a0_a1 = self.fq2_mul(ctx, &a.c0, &a.c1)?;
+        let t1 = self.fq2_sub(ctx, &a2_square, &a0_a1)?;
+        // t2 = a1^2 - a0 a2
+        let a1_square = self.fq2_square(ctx, &a.c1)?;
+        let a0_a2 = self.fq2_mul(ctx, &a.c0, &a.c2)?;
+        let t2 = self.fq2_sub(ctx, &a1_square, &a0_a2)?;
+        // t = a0 t0 + ξ(a2 t1 + a1 t2)
+        let a2_t1 = self.fq2_mul(ctx, &a.c2, &t1)?;
+        let a1_t2 = self.fq2_mul(ctx, &a.c1, &t2)?;
+        let t = self.fq2_add(ctx, &a2_t1, &a1_t2)?;
+        let t = self.fq2_mul_by_nonresidue(ctx, &t)?;
+        let a0_t0 = self.fq2_mul(ctx, &a.c0, &t0)?;
+        let t = self.fq2_add(ctx, &a0_t0, &t)?;
+        let t_inverse = self.fq2_invert(ctx, &t)?;
+
+        Ok(AssignedFq6 {
+            c0: self.fq2_mul(ctx, &t0, &t_inverse)?,
+            c1: self.fq2_mul(ctx, &t1, &t_inverse)?,
+            c2: self.fq2_mul(ctx, &t2, &t_inverse)?,
+        })
+    }
+
+    // Fq12
+
+    fn fq12_one(&self) -> AssignedFq12<F> {
+        AssignedFq12::from_coeffs([
+            self.fq2_one(),
+            self.fq2_zero(),
+            self.fq2_zero(),
+            self.fq2_zero(),
+            self.fq2_zero(),
+            self.fq2_zero(),
+        ])
+    }
+
+    fn fq12_mul(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        b: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // c0 = a0 b0 + v a1 b1, c1 = (a0 + a1)(b0 + b1) - a0 b0 - a1 b1
+        let t0 = self.fq6_mul(ctx, &a.c0, &b.c0)?;
+        let t1 = self.fq6_mul(ctx, &a.c1, &b.c1)?;
+        let t1_nonresidue = self.fq6_mul_by_nonresidue(ctx, &t1)?;
+        let c0 = self.fq6_add(ctx, &t0, &t1_nonresidue)?;
+        let a_sum = self.fq6_add(ctx, &a.c0, &a.c1)?;
+        let b_sum = self.fq6_add(ctx, &b.c0, &b.c1)?;
+        let t = self.fq6_mul(ctx, &a_sum, &b_sum)?;
+        let t = self.fq6_sub(ctx, &t, &t0)?;
+        let c1 = self.fq6_sub(ctx, &t, &t1)?;
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    fn fq12_square(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // c0 = (a0 + a1)(a0 + v a1) - t - v t, c1 = 2 t, where t = a0 a1
+        let t = self.fq6_mul(ctx, &a.c0, &a.c1)?;
+        let t_nonresidue = self.fq6_mul_by_nonresidue(ctx, &t)?;
+        let a_sum = self.fq6_add(ctx, &a.c0, &a.c1)?;
+        let a1_nonresidue = self.fq6_mul_by_nonresidue(ctx, &a.c1)?;
+        let a_sum_nonresidue = self.fq6_add(ctx, &a.c0, &a1_nonresidue)?;
+        let c0 = self.fq6_mul(ctx, &a_sum, &a_sum_nonresidue)?;
+        let c0 = self.fq6_sub(ctx, &c0, &t)?;
+        let c0 = self.fq6_sub(ctx, &c0, &t_nonresidue)?;
+        let c1 = self.fq6_add(ctx, &t, &t)?;
+        Ok(AssignedFq12 { c0, c1 })
+    }
+
+    fn fq12_conjugate(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        Ok(AssignedFq12 {
+            c0: a.c0.clone(),
+            c1: self.fq6_neg(ctx, &a.c1)?,
+        })
+    }
+
+    fn fq12_invert(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // (a0 + a1 w)^-1 = (a0 - a1 w) / (a0^2 - v a1^2)
+        let a0_square = self.fq6_mul(ctx, &a.c0, &a.c0)?;
+        let a1_square = self.fq6_mul(ctx, &a.c1, &a.c1)?;
+        let a1_square = self.fq6_mul_by_nonresidue(ctx, &a1_square)?;
+        let t = self.fq6_sub(ctx, &a0_square, &a1_square)?;
+        let t_inverse = self.fq6_invert(ctx, &t)?;
+        let c1 = self.fq6_mul(ctx, &a.c1, &t_inverse)?;
+        Ok(AssignedFq12 {
+            c0: self.fq6_mul(ctx, &a.c0, &t_inverse)?,
+            c1: self.fq6_neg(ctx, &c1)?,
+        })
+    }
+
+    /// Raise to the power p^k, for k = 1, 2, 3.
+    fn fq12_frobenius_map(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+        power: usize,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let [w0, coeffs @ ..] = a.coeffs();
+        let mut mapped = Vec::with_capacity(6);
+        mapped.push(if power % 2 == 1 {
+            self.fq2_conjugate(ctx, w0)?
+        } else {
+            w0.clone()
+        });
+        for (coeff, [c0, c1]) in coeffs.into_iter().zip(FROBENIUS_COEFFS[power - 1]) {
+            mapped.push(if power % 2 == 1 {
+                let coeff = self.fq2_conjugate(ctx, coeff)?;
+                let frobenius_coeff = self.fq2_constant(ctx, fq_from_hex(c0), fq_from_hex(c1))?;
+                self.fq2_mul(ctx, &coeff, &frobenius_coeff)?
+            } else {
+                let frobenius_coeff = self.fq_chip.assign_constant(ctx, fq_from_hex(c0))?;
+                self.fq2_mul_by_fq(ctx, coeff, &frobenius_coeff)?
+            });
+        }
+        Ok(AssignedFq12::from_coeffs(mapped.try_into().unwrap()))
+    }
+
+    /// Raise to the power of the BN parameter x.
+    fn fq12_pow_by_x(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let mut result = a.clone();
+        for i in (0..63 - BN_X.leading_zeros()).rev() {
+            result = self.fq12_square(ctx, &result)?;
+            if (BN_X >> i) & 1 == 1 {
+                result = self.fq12_mul(ctx, &result, a)?;
+            }
+        }
+        Ok(result)
+    }
+
+    /// Raise to the power of -x, for an element of the cyclotomic subgroup.
+    fn fq12_pow_by_neg_x(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let result = self.fq12_pow_by_x(ctx, a)?;
+        self.fq12_conjugate(ctx, &result)
+    }
+
+    /// Return whether the element is 1, with its coefficients reduced to
+    /// their canonical limbs.
+    pub(super) fn fq12_is_one(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        a: &AssignedFq12<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let main_gate = self.main_gate;
+        let limb_coeff = F::from_u128(1 << BIT_LEN_LIMB);
+        let mut is_one = main_gate.assign_constant(ctx, F::ONE)?;
+        let coeffs = a
+            .coeffs()
+            .into_iter()
+            .flat_map(|coeff| [&coeff.c0, &coeff.c1]);
+        for (idx, coeff) in coeffs.enumerate() {
+            let coeff = self.fq_chip.reduce(ctx, coeff)?;
+            self.fq_chip.assert_in_field(ctx, &coeff)?;
+            let limbs = coeff.limbs();
+            let expected = F::from((idx == 0) as u64);
+            for (limb_lo, limb_hi, constant) in [
+                (&limbs[0], &limbs[1], -expected),
+                (&limbs[2], &limbs[3], F::ZERO),
+            ] {
+                let value = main_gate.compose(
+                    ctx,
+                    &[
+                        Term::Assigned(limb_lo.as_ref(), F::ONE),
+                        Term::Assigned(limb_hi.as_ref(), limb_coeff),
+                    ],
+                    constant,
+                )?;
+                let is_zero = main_gate.is_zero(ctx, &value)?;
+                is_one = main_gate.and(ctx, &is_one, &is_zero)?;
+            }
+        }
+        Ok(is_one)
+    }
+
+    // G2
+
+    /// Return whether the point is in the twist, without checking that it is
+    /// in the subgroup of order r.
+    pub(super) fn g2_is_on_curve(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        point: &AssignedG2<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let b = <G2Affine as CurveAffine>::b();
+        let b = self.fq2_constant(ctx, b.c0, b.c1)?;
+        let y_square = self.fq2_square(ctx, &point.y)?;
+        let x_square = self.fq2_square(ctx, &point.x)?;
+        let x_cube = self.fq2_mul(ctx, &x_square, &point.x)?;
+        let x_cube_plus_b = self.fq2_add(ctx, &x_cube, &b)?;
+        self.fq2_is_equal(ctx, &y_square, &x_cube_plus_b)
+    }
+
+    /// Return P + Q with the incomplete addition, which requires xP != xQ.
+    fn g2_add(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedG2<F>,
+        q: &AssignedG2<F>,
+    ) -> Result<AssignedG2<F>, Error> {
+        // lambda = (yQ - yP) / (xQ - xP)
+        let numerator = self.fq2_sub(ctx, &q.y, &p.y)?;
+        let denominator = self.fq2_sub(ctx, &q.x, &p.x)?;
+        let denominator = self.fq2_invert(ctx, &denominator)?;
+        let lambda = self.fq2_mul(ctx, &numerator, &denominator)?;
+        self.g2_third_point(ctx, p, &lambda, &q.x)
+    }
+
+    /// Return 2P, which requires yP != 0.
+    fn g2_double(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedG2<F>,
+    ) -> Result<AssignedG2<F>, Error> {
+        // lambda = 3 xP^2 / 2 yP
+        let x_square = self.fq2_square(ctx, &p.x)?;
+        let numerator = self.fq2_add(ctx, &x_square, &x_square)?;
+        let numerator = self.fq2_add(ctx, &numerator, &x_square)?;
+        let denominator = self.fq2_add(ctx, &p.y, &p.y)?;
+        let denominator = self.fq2_invert(ctx, &denominator)?;
+        let lambda = self.fq2_mul(ctx, &numerator, &denominator)?;
+        self.g2_third_point(ctx, p, &lambda, &p.x)
+    }
+
+    /// Return the third point of the line with slope lambda through P,
+    /// negated.  `x` is the x-coordinate of the other point of the line.
+    fn g2_third_point(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedG2<F>,
+        lambda: &AssignedFq2<F>,
+        x: &AssignedFq2<F>,
+    ) -> Result<AssignedG2<F>, Error> {
+        // x3 = lambda^2 - xP - x, y3 = lambda (xP - x3) - yP
+        let x3 = self.fq2_square(ctx, lambda)?;
+        let x3 = self.fq2_sub(ctx, &x3, &p.x)?;
+        let x3 = self.fq2_sub(ctx, &x3, x)?;
+        let y3 = self.fq2_sub(ctx, &p.x, &x3)?;
+        let y3 = self.fq2_mul(ctx, lambda, &y3)?;
+        let y3 = self.fq2_sub(ctx, &y3, &p.y)?;
+        Ok(AssignedG2 { x: x3, y: y3 })
+    }
+
+    /// Return ψ(P) = (conj(xP) ξ^((p - 1) / 3), conj(yP) ξ^((p - 1) / 2)),
+    /// which is π(P) for the Frobenius endomorphism π of the twist.
+    fn g2_psi(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedG2<F>,
+    ) -> Result<AssignedG2<F>, Error> {
+        let [[c0, c1], [c2, c3]] = [FROBENIUS_COEFFS[0][1], FROBENIUS_COEFFS[0][2]];
+        let x_coeff = self.fq2_constant(ctx, fq_from_hex(c0), fq_from_hex(c1))?;
+        let y_coeff = self.fq2_constant(ctx, fq_from_hex(c2), fq_from_hex(c3))?;
+        let x = self.fq2_conjugate(ctx, &p.x)?;
+        let y = self.fq2_conjugate(ctx, &p.y)?;
+        Ok(AssignedG2 {
+            x: self.fq2_mul(ctx, &x, &x_coeff)?,
+            y: self.fq2_mul(ctx, &y, &y_coeff)?,
+        })
+    }
+
+    /// Return whether the point of the twist is in the subgroup of order r,
+    /// which holds iff ψ(P) = [6x^2] P.  The scalar multiplication is done by
+    /// double-and-add with the incomplete formulas, none of whose exceptional
+    /// cases is reached for the points of the twist, as the cofactor of the
+    /// twist is odd and its small prime factor 10069 doesn't divide any of the
+    /// intermediate scalars t, 2t or 2t + 1.
+    pub(super) fn g2_is_in_subgroup(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        p: &AssignedG2<F>,
+    ) -> Result<AssignedCondition<F>, Error> {
+        let mut product = p.clone();
+        for i in (0..127 - G2_PSI_SCALAR.leading_zeros()).rev() {
+            product = self.g2_double(ctx, &product)?;
+            if (G2_PSI_SCALAR >> i) & 1 == 1 {
+                product = self.g2_add(ctx, &product, p)?;
+            }
+        }
+        let psi = self.g2_psi(ctx, p)?;
+        let is_x_equal = self.fq2_is_equal(ctx, &psi.x, &product.x)?;
+        let is_y_equal = self.fq2_is_equal(ctx, &psi.y, &product.y)?;
+        self.main_gate.and(ctx, &is_x_equal, &is_y_equal)
+    }
+
+    // Miller loop
+
+    /// Evaluate at P the line with slope lambda through T, as the sparse
+    /// element yP - lambda xP w + (lambda xT - yT) w^3, or 1 when the pair is
+    /// skipped.
+    fn line(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        lambda: &AssignedFq2<F>,
+        t: &AssignedG2<F>,
+        pair: &AssignedPairingInput<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let w0 = AssignedFq2 {
+            c0: self
+                .fq_chip
+                .select(ctx, &self.fq_one, pair.g1.y(), &pair.skip)?,
+            c1: self.fq_zero.clone(),
+        };
+        let w1 = self.fq2_mul_by_fq(ctx, lambda, pair.g1.x())?;
+        let w1 = self.fq2_neg(ctx, &w1)?;
+        let w1 = self.fq2_select(ctx, &self.fq2_zero(), &w1, &pair.skip)?;
+        let w3 = self.fq2_mul(ctx, lambda, &t.x)?;
+        let w3 = self.fq2_sub(ctx, &w3, &t.y)?;
+        let w3 = self.fq2_select(ctx, &self.fq2_zero(), &w3, &pair.skip)?;
+
+        Ok(AssignedFq12::from_coeffs([
+            w0,
+            w1,
+            self.fq2_zero(),
+            w3,
+            self.fq2_zero(),
+            self.fq2_zero(),
+        ]))
+    }
+
+    /// Multiply f by the line with slope lambda through T evaluated at P, and
+    /// replace T by the third point of the line, negated.  `x` is the
+    /// x-coordinate of the other point of the line.
+    fn line_step(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        f: &AssignedFq12<F>,
+        t: &mut AssignedG2<F>,
+        lambda: &AssignedFq2<F>,
+        x: &AssignedFq2<F>,
+        pair: &AssignedPairingInput<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        let line = self.line(ctx, lambda, t, pair)?;
+        let f = self.fq12_mul(ctx, f, &line)?;
+        *t = self.g2_third_point(ctx, t, lambda, x)?;
+
+        Ok(f)
+    }
+
+    fn doubling_step(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        f: &AssignedFq12<F>,
+        t: &mut AssignedG2<F>,
+        pair: &AssignedPairingInput<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // lambda = 3 xT^2 / 2 yT
+        let x_square = self.fq2_square(ctx, &t.x)?;
+        let numerator = self.fq2_add(ctx, &x_square, &x_square)?;
+        let numerator = self.fq2_add(ctx, &numerator, &x_square)?;
+        let denominator = self.fq2_add(ctx, &t.y, &t.y)?;
+        let denominator = self.fq2_invert(ctx, &denominator)?;
+        let lambda = self.fq2_mul(ctx, &numerator, &denominator)?;
+        let x = t.x.clone();
+        self.line_step(ctx, f, t, &lambda, &x, pair)
+    }
+
+    fn addition_step(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        f: &AssignedFq12<F>,
+        t: &mut AssignedG2<F>,
+        q: &AssignedG2<F>,
+        pair: &AssignedPairingInput<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // lambda = (yQ - yT) / (xQ - xT)
+        let numerator = self.fq2_sub(ctx, &q.y, &t.y)?;
+        let denominator = self.fq2_sub(ctx, &q.x, &t.x)?;
+        let denominator = self.fq2_invert(ctx, &denominator)?;
+        let lambda = self.fq2_mul(ctx, &numerator, &denominator)?;
+        self.line_step(ctx, f, t, &lambda, &q.x, pair)
+    }
+
+    fn multi_miller_loop(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        pairs: &[AssignedPairingInput<F>],
+    ) -> Result<AssignedFq12<F>, Error> {
+        let mut neg_qs = Vec::with_capacity(pairs.len());
+        for pair in pairs {
+            neg_qs.push(AssignedG2 {
+                x: pair.g2.x.clone(),
+                y: self.fq2_neg(ctx, &pair.g2.y)?,
+            });
+        }
+        let mut ts = pairs.iter().map(|pair| pair.g2.clone()).collect::<Vec<_>>();
+
+        let mut f = self.fq12_one();
+        for i in (0..ATE_LOOP_COUNT.len() - 1).rev() {
+            f = self.fq12_square(ctx, &f)?;
+            for ((pair, neg_q), t) in pairs.iter().zip(&neg_qs).zip(ts.iter_mut()) {
+                f = self.doubling_step(ctx, &f, t, pair)?;
+                match ATE_LOOP_COUNT[i] {
+                    1 => f = self.addition_step(ctx, &f, t, &pair.g2, pair)?,
+                    -1 => f = self.addition_step(ctx, &f, t, neg_q, pair)?,
+                    _ => {}
+                }
+            }
+        }
+
+        // Lines through T and π(Q), and through T + π(Q) and -π^2(Q), where π
+        // is the Frobenius endomorphism of the twist.
+        for (pair, t) in pairs.iter().zip(ts.iter_mut()) {
+            let q1 = self.g2_psi(ctx, &pair.g2)?;
+            f = self.addition_step(ctx, &f, t, &q1, pair)?;
+
+            // The coefficient of the y-coordinate of π^2(Q) is -1.
+            let x_coeff = self
+                .fq_chip
+                .assign_constant(ctx, fq_from_hex(FROBENIUS_COEFFS[1][1][0]))?;
+            let neg_q2 = AssignedG2 {
+                x: self.fq2_mul_by_fq(ctx, &pair.g2.x, &x_coeff)?,
+                y: pair.g2.y.clone(),
+            };
+            f = self.addition_step(ctx, &f, t, &neg_q2, pair)?;
+        }
+
+        Ok(f)
+    }
+
+    fn final_exponentiation(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        f: &AssignedFq12<F>,
+    ) -> Result<AssignedFq12<F>, Error> {
+        // Easy part: f^((p^6 - 1)(p^2 + 1))
+        let f_inverse = self.fq12_invert(ctx, f)?;
+        let r = self.fq12_conjugate(ctx, f)?;
+        let r = self.fq12_mul(ctx, &r, &f_inverse)?;
+        let r_frobenius = self.fq12_frobenius_map(ctx, &r, 2)?;
+        let r = self.fq12_mul(ctx, &r_frobenius, &r)?;
+
+        // Hard part: r^((p^4 - p^2 + 1) / r), following "Faster hashing to
+        // G2" by Fuentes-Castañeda, Knapp and Rodríguez-Henríquez.
+        let y0 = self.fq12_pow_by_neg_x(ctx, &r)?;
+        let y1 = self.fq12_square(ctx, &y0)?;
+        let y2 = self.fq12_square(ctx, &y1)?;
+        let y3 = self.fq12_mul(ctx, &y2, &y1)?;
+        let y4 = self.fq12_pow_by_neg_x(ctx, &y3)?;
+        let y5 = self.fq12_square(ctx, &y4)?;
+        let y6 = self.fq12_pow_by_neg_x(ctx, &y5)?;
+        let y3 = self.fq12_conjugate(ctx, &y3)?;
+        let y6 = self.fq12_conjugate(ctx, &y6)?;
+        let y7 = self.fq12_mul(ctx, &y6, &y4)?;
+        let y8 = self.fq12_mul(ctx, &y7, &y3)?;
+        let y9 = self.fq12_mul(ctx, &y8, &y1)?;
+        let y10 = self.fq12_mul(ctx, &y8, &y4)?;
+        let y11 = self.fq12_mul(ctx, &y10, &r)?;
+        let y12 = self.fq12_frobenius_map(ctx, &y9, 1)?;
+        let y13 = self.fq12_mul(ctx, &y12, &y11)?;
+        let y8 = self.fq12_frobenius_map(ctx, &y8, 2)?;
+        let y14 = self.fq12_mul(ctx, &y8, &y13)?;
+        let r = self.fq12_conjugate(ctx, &r)?;
+        let y15 = self.fq12_mul(ctx, &r, &y9)?;
+        let y15 = self.fq12_frobenius_map(ctx, &y15, 3)?;
+        self.fq12_mul(ctx, &y15, &y14)
+    }
+
+    /// Return the product of the pairings of the pairs, in Fq12.
+    pub(super) fn multi_pairing(
+        &self,
+        ctx: &mut RegionCtx<'_, F>,
+        pairs: &[AssignedPairingInput<F>],
+    ) -> Result<AssignedFq12<F>, Error> {
+        let f = self.multi_miller_loop(ctx, pairs)?;
+        self.final_exponentiation(ctx, &f)
+    }
+}
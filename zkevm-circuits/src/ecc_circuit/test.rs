@@ -0,0 +1,258 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{word, Word};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn ecc_circuit_unusable_rows() {
+    assert_eq!(
+        EccCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EccCircuit::<Fr>>(()),
+    )
+}
+
+fn run(circuit: EccCircuit<Fr>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        EccCircuit::<Fr>::unusable_rows()
+            + EccCircuit::<Fr>::min_num_rows(
+                circuit.max_add_calls,
+                circuit.max_mul_calls,
+                circuit.max_pairing_calls,
+            ),
+    );
+    // EccChip -> MainGate instance column
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+const INFINITY: [Word; 2] = [Word::zero(); 2];
+
+fn g1() -> [Word; 2] {
+    [Word::from(1), Word::from(2)]
+}
+
+fn g1_neg() -> [Word; 2] {
+    [
+        Word::from(1),
+        word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45"),
+    ]
+}
+
+fn g1_double() -> [Word; 2] {
+    [
+        word!("0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3"),
+        word!("0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"),
+    ]
+}
+
+fn g1_triple() -> [Word; 2] {
+    [
+        word!("0x0769bf9ac56bea3ff40232bcb1b6bd159315d84715b8e679f2d355961915abf0"),
+        word!("0x2ab799bee0489429554fdb7c8d086475319e63b40b9c5b57cdf1ff3dd9fe2261"),
+    ]
+}
+
+/// Point not on the curve
+fn g1_off_curve() -> [Word; 2] {
+    [Word::from(1), Word::from(3)]
+}
+
+/// Generator with its x-coordinate not lower than the modulus
+fn g1_out_of_field() -> [Word; 2] {
+    [
+        word!("0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd48"),
+        Word::from(2),
+    ]
+}
+
+fn ec_add([p_x, p_y]: [Word; 2], [q_x, q_y]: [Word; 2], [r_x, r_y]: [Word; 2]) -> EcAddAuxData {
+    EcAddAuxData {
+        p_x,
+        p_y,
+        q_x,
+        q_y,
+        is_valid: true,
+        r_x,
+        r_y,
+    }
+}
+
+fn ec_add_invalid(p: [Word; 2], q: [Word; 2]) -> EcAddAuxData {
+    EcAddAuxData {
+        is_valid: false,
+        ..ec_add(p, q, INFINITY)
+    }
+}
+
+fn ec_mul([p_x, p_y]: [Word; 2], scalar: Word, [r_x, r_y]: [Word; 2]) -> EcMulAuxData {
+    EcMulAuxData {
+        p_x,
+        p_y,
+        scalar,
+        is_valid: true,
+        r_x,
+        r_y,
+    }
+}
+
+fn ec_mul_invalid(p: [Word; 2], scalar: Word) -> EcMulAuxData {
+    EcMulAuxData {
+        is_valid: false,
+        ..ec_mul(p, scalar, INFINITY)
+    }
+}
+
+fn ec_pairing(points: &[([Word; 2], bool)], output: bool) -> EcPairingAuxData {
+    // G2 generator, with the coordinates encoded as [imaginary, real]
+    let g2_x = [
+        word!("0x198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"),
+        word!("0x1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"),
+    ];
+    let g2_y = [
+        word!("0x090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"),
+        word!("0x12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"),
+    ];
+    let pairs = points
+        .iter()
+        .map(|&([g1_x, g1_y], is_g2_infinity)| EcPairingPair {
+            g1_x,
+            g1_y,
+            g2_x: if is_g2_infinity {
+                [Word::zero(); 2]
+            } else {
+                g2_x
+            },
+            g2_y: if is_g2_infinity {
+                [Word::zero(); 2]
+            } else {
+                g2_y
+            },
+        })
+        .collect();
+    EcPairingAuxData {
+        pairs,
+        output,
+        is_valid: true,
+    }
+}
+
+#[test]
+fn ecc_circuit_ec_add() {
+    let events = vec![
+        ec_add(g1(), g1(), g1_double()),
+        ec_add(g1(), g1_double(), g1_triple()),
+        ec_add(g1_double(), g1(), g1_triple()),
+        ec_add(g1(), g1_neg(), INFINITY),
+        ec_add(INFINITY, g1(), g1()),
+        ec_add(g1_neg(), INFINITY, g1_neg()),
+        ec_add(INFINITY, INFINITY, INFINITY),
+        ec_add_invalid(g1_off_curve(), g1()),
+        ec_add_invalid(INFINITY, g1_off_curve()),
+        ec_add_invalid(g1(), g1_out_of_field()),
+    ];
+    let circuit = EccCircuit::new(events.len() + 1, 0, 0, events, vec![], vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn ecc_circuit_ec_mul() {
+    let modulus = word!("0x30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+    let events = vec![
+        ec_mul(g1(), Word::from(2), g1_double()),
+        ec_mul(g1_neg(), modulus - Word::from(3), g1_triple()),
+        ec_mul(g1(), modulus + Word::from(2), g1_double()),
+        ec_mul(
+            g1(),
+            Word::MAX,
+            [
+                word!("0x2f588cffe99db877a4434b598ab28f81e0522910ea52b45f0adaa772b2d5d352"),
+                word!("0x12f42fa8fd34fb1b33d8c6a718b6590198389b26fc9d8808d971f8b009777a97"),
+            ],
+        ),
+        ec_mul(g1(), Word::zero(), INFINITY),
+        ec_mul(g1(), modulus, INFINITY),
+        ec_mul(INFINITY, Word::from(5), INFINITY),
+        ec_mul_invalid(g1_off_curve(), Word::from(2)),
+        ec_mul_invalid(g1_out_of_field(), Word::zero()),
+    ];
+    let circuit = EccCircuit::new(0, events.len() + 1, 0, vec![], events, vec![]);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[test]
+fn ecc_circuit_wrong_result() {
+    let add_events = vec![ec_add(g1(), g1(), g1())];
+    let mul_events = vec![ec_mul(g1(), Word::from(3), g1_double())];
+    let circuit = EccCircuit::new(1, 1, 0, add_events, mul_events, vec![]);
+    assert!(run(circuit).is_err());
+}
+
+#[test]
+fn ecc_circuit_wrong_is_valid() {
+    let add_events = vec![
+        ec_add(g1_out_of_field(), INFINITY, g1()),
+        EcAddAuxData {
+            is_valid: true,
+            ..ec_add_invalid(g1_off_curve(), g1())
+        },
+    ];
+    let mul_events = vec![ec_mul(g1_off_curve(), Word::zero(), INFINITY)];
+    for (add_events, mul_events) in [(add_events, vec![]), (vec![], mul_events)] {
+        let circuit = EccCircuit::new(2, 1, 0, add_events, mul_events, vec![]);
+        assert!(run(circuit).is_err());
+    }
+}
+
+#[ignore = "Due to high memory requirement"]
+#[test]
+fn ecc_circuit_ec_pairing() {
+    let events = vec![
+        // e(G1, G2) * e(-G1, G2) == 1, with pairs of points at infinity
+        ec_pairing(
+            &[
+                (g1(), false),
+                (INFINITY, false),
+                (g1_neg(), false),
+                (g1(), true),
+            ],
+            true,
+        ),
+        // e(G1, G2) != 1
+        ec_pairing(&[(g1(), false)], false),
+        // G1 point not on the curve
+        EcPairingAuxData {
+            is_valid: false,
+            ..ec_pairing(&[(g1(), false), (g1_off_curve(), false)], false)
+        },
+        // G2 point in the twist but not in the subgroup of order r
+        EcPairingAuxData {
+            pairs: vec![EcPairingPair {
+                g1_x: Word::from(1),
+                g1_y: Word::from(2),
+                g2_x: [Word::zero(), Word::from(1)],
+                g2_y: [
+                    word!("0x0d1271953ed9ea0836846e70a1934187998c7f790cb4d7511b7f8da82de048a4"),
+                    word!("0x2869111d5381f072f8e2728fdb825a51aadd70e52c9830e9ab4b871c0531f1bb"),
+                ],
+            }],
+            output: false,
+            is_valid: false,
+        },
+    ];
+    let circuit = EccCircuit::new(0, 0, events.len(), vec![], vec![], events);
+    assert_eq!(run(circuit), Ok(()));
+}
+
+#[ignore = "Due to high memory requirement"]
+#[test]
+fn ecc_circuit_ec_pairing_wrong_output() {
+    let events = vec![ec_pairing(&[(g1(), false), (g1_neg(), false)], false)];
+    let circuit = EccCircuit::new(0, 0, events.len(), vec![], vec![], events);
+    assert!(run(circuit).is_err());
+}
@@ -0,0 +1,594 @@
+//! The ecRecover circuit implementation, which verifies the calls to the
+//! ecRecover precompile with a well formed signature with the SignVerifyChip.
+//! A call recovers no address only when `r` is not the x coordinate of any
+//! point of the curve, or when the public key recovered from the signature is
+//! the point at infinity.  The message hash of the calls is reduced modulo the
+//! secp256k1 order, as it is signed.
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::EcRecoverCircuit as TestEcRecoverCircuit;
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{EcRecoverTable, KeccakTable, LookupTable},
+    tx_circuit::sign_verify::{
+        AssignedInfinitySignature, AssignedSignatureVerify, InfinitySignature, SignVerifyChip,
+        SignVerifyConfig,
+    },
+    util::{Challenges, Expr, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::EcrecoverAuxData;
+use eth_types::{sign_types::SignData, Field, ToLittleEndian};
+use gadgets::util::not;
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    circuit::{AssignedCell, Layouter, Value},
+    halo2curves::{
+        group::ff::{Field as _, PrimeField},
+        secp256k1::{self, Secp256k1Affine},
+    },
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
+    poly::Rotation,
+};
+use itertools::Itertools;
+use log::error;
+use std::marker::PhantomData;
+
+/// Config for EcRecoverCircuit
+#[derive(Clone, Debug)]
+pub struct EcRecoverCircuitConfig<F: Field> {
+    q_enable: Selector,
+    /// Whether an address was recovered, in which case the signature is
+    /// verified by the SignVerifyChip.
+    is_valid: Column<Advice>,
+    /// Outputs of the SignVerifyChip, copied into the rows of the
+    /// EcRecoverTable.
+    sign_verify_msg_hash_rlc: Column<Advice>,
+    sign_verify_sig_r_rlc: Column<Advice>,
+    sign_verify_sig_s_rlc: Column<Advice>,
+    sign_verify_r_y_parity: Column<Advice>,
+    /// RLC of the x coordinate proved to be off the curve by the
+    /// SignVerifyChip, copied into the rows where no address is recovered.
+    off_curve_sig_r_rlc: Column<Advice>,
+    /// Whether the point at infinity is recovered, in which case the
+    /// signature is proved to recover it by the SignVerifyChip.
+    is_infinity: Column<Advice>,
+    /// Outputs of the proof that the point at infinity is recovered, copied
+    /// into the rows of the EcRecoverTable.
+    infinity_msg_hash_rlc: Column<Advice>,
+    infinity_sig_r_rlc: Column<Advice>,
+    infinity_sig_s_rlc: Column<Advice>,
+    infinity_r_y_parity: Column<Advice>,
+    sign_verify: SignVerifyConfig,
+    _marker: PhantomData<F>,
+    // External tables
+    ecrecover_table: EcRecoverTable,
+    keccak_table: KeccakTable,
+}
+
+/// Circuit configuration arguments
+pub struct EcRecoverCircuitConfigArgs<F: Field> {
+    /// EcRecoverTable
+    pub ecrecover_table: EcRecoverTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for EcRecoverCircuitConfig<F> {
+    type ConfigArgs = EcRecoverCircuitConfigArgs<F>;
+
+    /// Return a new EcRecoverCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            ecrecover_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.selector();
+        let is_valid = meta.advice_column();
+        let sign_verify_msg_hash_rlc = meta.advice_column_in(SecondPhase);
+        let sign_verify_sig_r_rlc = meta.advice_column_in(SecondPhase);
+        let sign_verify_sig_s_rlc = meta.advice_column_in(SecondPhase);
+        let sign_verify_r_y_parity = meta.advice_column();
+        let off_curve_sig_r_rlc = meta.advice_column_in(SecondPhase);
+        let is_infinity = meta.advice_column();
+        let infinity_msg_hash_rlc = meta.advice_column_in(SecondPhase);
+        let infinity_sig_r_rlc = meta.advice_column_in(SecondPhase);
+        let infinity_sig_s_rlc = meta.advice_column_in(SecondPhase);
+        let infinity_r_y_parity = meta.advice_column();
+        for column in [
+            sign_verify_msg_hash_rlc,
+            sign_verify_sig_r_rlc,
+            sign_verify_sig_s_rlc,
+            sign_verify_r_y_parity,
+            off_curve_sig_r_rlc,
+            infinity_msg_hash_rlc,
+            infinity_sig_r_rlc,
+            infinity_sig_s_rlc,
+            infinity_r_y_parity,
+            ecrecover_table.recovered_addr,
+        ] {
+            meta.enable_equality(column);
+        }
+
+        let sign_verify = SignVerifyConfig::new(meta, keccak_table.clone(), challenges);
+
+        meta.create_gate("ecrecover table row", |meta| {
+            let mut cb = BaseConstraintBuilder::default();
+
+            let is_enabled = meta.query_advice(ecrecover_table.is_enabled, Rotation::cur());
+            let is_valid = meta.query_advice(is_valid, Rotation::cur());
+            let is_infinity = meta.query_advice(is_infinity, Rotation::cur());
+            let recovered_addr = meta.query_advice(ecrecover_table.recovered_addr, Rotation::cur());
+            let sig_v_rlc = meta.query_advice(ecrecover_table.sig_v_rlc, Rotation::cur());
+
+            cb.require_boolean("is_enabled is boolean", is_enabled.clone());
+            cb.require_boolean("is_valid is boolean", is_valid.clone());
+            cb.require_boolean("is_infinity is boolean", is_infinity.clone());
+            cb.condition(not::expr(is_enabled.clone()), |cb| {
+                cb.require_zero("is_valid is 0 when the row is disabled", is_valid.clone());
+                cb.require_zero(
+                    "is_infinity is 0 when the row is disabled",
+                    is_infinity.clone(),
+                );
+            });
+            cb.require_zero(
+                "an address and the point at infinity are not both recovered",
+                is_valid.clone() * is_infinity.clone(),
+            );
+            cb.condition(not::expr(is_valid.clone()), |cb| {
+                cb.require_zero(
+                    "recovered_addr is 0 when the signature is not verified",
+                    recovered_addr,
+                );
+            });
+
+            // No address is recovered only when r is off the curve or the
+            // point at infinity is recovered.
+            cb.condition(
+                is_enabled * not::expr(is_valid.clone()) * not::expr(is_infinity.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "sig_r_rlc is the x coordinate off the curve",
+                        meta.query_advice(ecrecover_table.sig_r_rlc, Rotation::cur()),
+                        meta.query_advice(off_curve_sig_r_rlc, Rotation::cur()),
+                    );
+                },
+            );
+
+            // The signature of the row matches the one verified by the
+            // SignVerifyChip when an address is recovered, or the one proved
+            // to recover the point at infinity.
+            for (condition, [msg_hash_rlc, sig_r_rlc, sig_s_rlc, r_y_parity]) in [
+                (
+                    is_valid,
+                    [
+                        sign_verify_msg_hash_rlc,
+                        sign_verify_sig_r_rlc,
+                        sign_verify_sig_s_rlc,
+                        sign_verify_r_y_parity,
+                    ],
+                ),
+                (
+                    is_infinity,
+                    [
+                        infinity_msg_hash_rlc,
+                        infinity_sig_r_rlc,
+                        infinity_sig_s_rlc,
+                        infinity_r_y_parity,
+                    ],
+                ),
+            ] {
+                cb.condition(condition, |cb| {
+                    // The RLC of a word lower than 256 is the word itself, and
+                    // the recovery id is the parity of the y coordinate of the
+                    // point R of the signature.
+                    cb.require_equal(
+                        "sig_v == 27 + r_y_parity",
+                        sig_v_rlc.clone(),
+                        27.expr() + meta.query_advice(r_y_parity, Rotation::cur()),
+                    );
+                    for (name, column, proof_column) in [
+                        (
+                            "msg_hash_rlc matches the proof",
+                            ecrecover_table.msg_hash_rlc,
+                            msg_hash_rlc,
+                        ),
+                        (
+                            "sig_r_rlc matches the proof",
+                            ecrecover_table.sig_r_rlc,
+                            sig_r_rlc,
+                        ),
+                        (
+                            "sig_s_rlc matches the proof",
+                            ecrecover_table.sig_s_rlc,
+                            sig_s_rlc,
+                        ),
+                    ] {
+                        cb.require_equal(
+                            name,
+                            meta.query_advice(column, Rotation::cur()),
+                            meta.query_advice(proof_column, Rotation::cur()),
+                        );
+                    }
+                });
+            }
+
+            cb.gate(meta.query_selector(q_enable))
+        });
+
+        Self {
+            q_enable,
+            is_valid,
+            sign_verify_msg_hash_rlc,
+            sign_verify_sig_r_rlc,
+            sign_verify_sig_s_rlc,
+            sign_verify_r_y_parity,
+            off_curve_sig_r_rlc,
+            is_infinity,
+            infinity_msg_hash_rlc,
+            infinity_sig_r_rlc,
+            infinity_sig_s_rlc,
+            infinity_r_y_parity,
+            sign_verify,
+            ecrecover_table,
+            keccak_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> EcRecoverCircuitConfig<F> {
+    /// Load ECDSA RangeChip table.
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.sign_verify.load_range(layouter)
+    }
+}
+
+/// EcRecover Circuit for verifying the calls to the ecRecover precompile
+#[derive(Clone, Default, Debug)]
+pub struct EcRecoverCircuit<F: Field> {
+    /// Max number of supported calls to the ecRecover precompile
+    pub max_ecrecover_calls: usize,
+    /// Calls to the ecRecover precompile
+    pub events: Vec<EcrecoverAuxData>,
+    /// SignVerify chip
+    pub sign_verify: SignVerifyChip<F>,
+}
+
+impl<F: Field> EcRecoverCircuit<F> {
+    /// Return a new EcRecoverCircuit
+    pub fn new(max_ecrecover_calls: usize, events: Vec<EcrecoverAuxData>) -> Self {
+        EcRecoverCircuit::<F> {
+            max_ecrecover_calls,
+            events,
+            sign_verify: SignVerifyChip::new(max_ecrecover_calls),
+        }
+    }
+
+    /// Return the minimum number of rows required to prove an input of a
+    /// particular size.
+    pub fn min_num_rows(num_calls: usize) -> usize {
+        // Upper bounds of the rows of the proof that an x coordinate is off
+        // the curve, and of the proof that the point at infinity is recovered,
+        // which multiplies a single point where the signature verification
+        // multiplies two, along with the rows of its ecc chip aux.
+        let rows_off_curve_verify = 512;
+        let rows_infinity_verify = 104471;
+        let rows_infinity_aux = 226;
+        std::cmp::max(
+            num_calls + 1,
+            SignVerifyChip::<F>::min_num_rows(num_calls)
+                + rows_infinity_aux
+                + (rows_off_curve_verify + rows_infinity_verify) * num_calls,
+        )
+    }
+
+    /// Calls to the ecRecover precompile with a well formed signature, with
+    /// the ones that recover an address first, along with their signature
+    /// data, then the ones that recover the point at infinity, along with
+    /// their signature, and last the ones with `r` off the curve.
+    fn sorted_events(
+        &self,
+    ) -> (
+        Vec<&EcrecoverAuxData>,
+        Vec<SignData>,
+        Vec<InfinitySignature>,
+    ) {
+        let mut valid = Vec::new();
+        let mut infinity = Vec::new();
+        let mut off_curve = Vec::new();
+        for event in self.events.iter().filter(|event| event.is_well_formed()) {
+            if let Some(sign_data) = event.sign_data() {
+                valid.push((event, sign_data));
+            } else if let Some(signature) = infinity_signature(event) {
+                infinity.push((event, signature));
+            } else {
+                off_curve.push(event);
+            }
+        }
+        let (valid_events, sign_datas): (Vec<_>, Vec<_>) = valid.into_iter().unzip();
+        let (infinity_events, infinity_signatures): (Vec<_>, Vec<_>) = infinity.into_iter().unzip();
+        let events = valid_events
+            .into_iter()
+            .chain(infinity_events)
+            .chain(off_curve)
+            .collect();
+        (events, sign_datas, infinity_signatures)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn assign_ecrecover_table(
+        &self,
+        config: &EcRecoverCircuitConfig<F>,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+        events: &[&EcrecoverAuxData],
+        num_valid: usize,
+        num_infinity: usize,
+        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+        assigned_infinity_signatures: Vec<AssignedInfinitySignature<F>>,
+        assigned_off_curve_xs_rlc: Vec<AssignedCell<F, F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecrecover table",
+            |mut region| {
+                let table_columns =
+                    <EcRecoverTable as LookupTable<F>>::advice_columns(&config.ecrecover_table);
+                let columns = table_columns.iter().chain([
+                    &config.is_valid,
+                    &config.sign_verify_msg_hash_rlc,
+                    &config.sign_verify_sig_r_rlc,
+                    &config.sign_verify_sig_s_rlc,
+                    &config.sign_verify_r_y_parity,
+                    &config.off_curve_sig_r_rlc,
+                    &config.is_infinity,
+                    &config.infinity_msg_hash_rlc,
+                    &config.infinity_sig_r_rlc,
+                    &config.infinity_sig_s_rlc,
+                    &config.infinity_r_y_parity,
+                ]);
+
+                // Empty entry
+                for column in columns {
+                    region.assign_advice(
+                        || "ecrecover table all-zero row",
+                        *column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                for (i, ((assigned_sig_verif, assigned_infinity), assigned_off_curve_x_rlc)) in
+                    assigned_sig_verifs
+                        .iter()
+                        .zip_eq(&assigned_infinity_signatures)
+                        .zip_eq(&assigned_off_curve_xs_rlc)
+                        .enumerate()
+                {
+                    let offset = i + 1;
+                    config.q_enable.enable(&mut region, offset)?;
+
+                    let row = events
+                        .get(i)
+                        .map(|event| EcRecoverTable::assignments(event, challenges)[0])
+                        .unwrap_or([Value::known(F::ZERO); 6]);
+                    let mut assigned_row = Vec::with_capacity(row.len());
+                    for (&column, value) in table_columns.iter().zip_eq(row) {
+                        assigned_row.push(region.assign_advice(
+                            || format!("ecrecover table row {}", offset),
+                            column,
+                            offset,
+                            || value,
+                        )?);
+                    }
+                    region.assign_advice(
+                        || format!("ecrecover is_valid {}", offset),
+                        config.is_valid,
+                        offset,
+                        || Value::known(F::from((i < num_valid) as u64)),
+                    )?;
+                    region.assign_advice(
+                        || format!("ecrecover is_infinity {}", offset),
+                        config.is_infinity,
+                        offset,
+                        || {
+                            Value::known(F::from(
+                                (num_valid..num_valid + num_infinity).contains(&i) as u64,
+                            ))
+                        },
+                    )?;
+
+                    // Copy constraints using fixed offsets between the ecrecover
+                    // table rows and the SignVerifyChip
+                    region.constrain_equal(
+                        assigned_row[5].cell(),
+                        assigned_sig_verif.address.cell(),
+                    )?;
+                    for (name, column, assigned) in [
+                        (
+                            "sign_verify_msg_hash_rlc",
+                            config.sign_verify_msg_hash_rlc,
+                            &assigned_sig_verif.msg_hash_rlc,
+                        ),
+                        (
+                            "sign_verify_sig_r_rlc",
+                            config.sign_verify_sig_r_rlc,
+                            &assigned_sig_verif.sig_r_rlc,
+                        ),
+                        (
+                            "sign_verify_sig_s_rlc",
+                            config.sign_verify_sig_s_rlc,
+                            &assigned_sig_verif.sig_s_rlc,
+                        ),
+                        (
+                            "sign_verify_r_y_parity",
+                            config.sign_verify_r_y_parity,
+                            &assigned_sig_verif.r_y_parity,
+                        ),
+                        (
+                            "off_curve_sig_r_rlc",
+                            config.off_curve_sig_r_rlc,
+                            assigned_off_curve_x_rlc,
+                        ),
+                        (
+                            "infinity_msg_hash_rlc",
+                            config.infinity_msg_hash_rlc,
+                            &assigned_infinity.msg_hash_rlc,
+                        ),
+                        (
+                            "infinity_sig_r_rlc",
+                            config.infinity_sig_r_rlc,
+                            &assigned_infinity.sig_r_rlc,
+                        ),
+                        (
+                            "infinity_sig_s_rlc",
+                            config.infinity_sig_s_rlc,
+                            &assigned_infinity.sig_s_rlc,
+                        ),
+                        (
+                            "infinity_r_y_parity",
+                            config.infinity_r_y_parity,
+                            &assigned_infinity.r_y_parity,
+                        ),
+                    ] {
+                        assigned.copy_advice(|| name, &mut region, column, offset)?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> SubCircuit<F> for EcRecoverCircuit<F> {
+    type Config = EcRecoverCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_ecrecover_calls = if block.circuits_params.max_ecrecover_calls == 0 {
+            block.ecrecover_events.len()
+        } else {
+            block.circuits_params.max_ecrecover_calls
+        };
+        Self::new(max_ecrecover_calls, block.ecrecover_events.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(block.ecrecover_events.len()),
+            Self::min_num_rows(
+                block
+                    .circuits_params
+                    .max_ecrecover_calls
+                    .max(block.ecrecover_events.len()),
+            ),
+        )
+    }
+
+    /// Make the assignments to the EcRecoverCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_ecrecover_calls {
+            error!(
+                "ecrecover events.len() = {} > max_ecrecover_calls = {}",
+                self.events.len(),
+                self.max_ecrecover_calls
+            );
+            return Err(Error::Synthesis);
+        }
+        let (events, sign_datas, infinity_signatures) = self.sorted_events();
+        let num_recovered = sign_datas.len() + infinity_signatures.len();
+
+        config.load_aux_tables(layouter)?;
+        let assigned_sig_verifs =
+            self.sign_verify
+                .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
+        // The calls that recover the point at infinity are proved to do so,
+        // and the r of the rest of the calls that recover no address are
+        // proved to be off the curve.
+        let infinity_signatures: Vec<_> = (0..events.len())
+            .map(|i| {
+                i.checked_sub(sign_datas.len())
+                    .and_then(|idx| infinity_signatures.get(idx).copied())
+            })
+            .collect();
+        let assigned_infinity_signatures = self.sign_verify.assign_infinity(
+            &config.sign_verify,
+            layouter,
+            &infinity_signatures,
+            challenges,
+        )?;
+        let off_curve_xs: Vec<_> = events
+            .iter()
+            .enumerate()
+            .map(|(i, event)| {
+                (i >= num_recovered)
+                    .then(|| Option::from(secp256k1::Fp::from_repr(event.sig_r.to_le_bytes())))
+                    .flatten()
+            })
+            .collect();
+        let assigned_off_curve_xs_rlc = self.sign_verify.assign_off_curve(
+            &config.sign_verify,
+            layouter,
+            &off_curve_xs,
+            challenges,
+        )?;
+        self.assign_ecrecover_table(
+            config,
+            challenges,
+            layouter,
+            &events,
+            sign_datas.len(),
+            num_recovered - sign_datas.len(),
+            assigned_sig_verifs,
+            assigned_infinity_signatures,
+            assigned_off_curve_xs_rlc,
+        )
+    }
+
+    fn instance(&self) -> Vec<Vec<F>> {
+        // The maingate expects an instance column, but we don't use it, so we return an
+        // "empty" instance column
+        vec![vec![]]
+    }
+}
+
+/// Signature of a call that recovers no address with `r` on the curve, from
+/// which the point at infinity is recovered, along with its point R, or `None`
+/// if `r` is off the curve.
+fn infinity_signature(event: &EcrecoverAuxData) -> Option<InfinitySignature> {
+    let x = Option::<secp256k1::Fp>::from(secp256k1::Fp::from_repr(event.sig_r.to_le_bytes()))?;
+    let y = Option::<secp256k1::Fp>::from((x.square() * x + secp256k1::Fp::from(7)).sqrt())?;
+    let y = if y.to_bytes()[0] & 1 == event.recovery_id()? {
+        y
+    } else {
+        -y
+    };
+    Some(InfinitySignature {
+        msg_hash: Option::from(secp256k1::Fq::from_repr(
+            event.msg_hash_reduced().to_le_bytes(),
+        ))?,
+        sig_s: Option::from(secp256k1::Fq::from_repr(event.sig_s.to_le_bytes()))?,
+        point_r: Option::from(Secp256k1Affine::from_xy(x, y))?,
+    })
+}
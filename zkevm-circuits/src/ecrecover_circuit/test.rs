@@ -0,0 +1,168 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{
+    address, keccak256,
+    sign_types::{pk_bytes_le, pk_bytes_swap_endianness, recover_pk, SECP256K1_Q},
+    word, Address, Word,
+};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn ecrecover_circuit_unusable_rows() {
+    assert_eq!(
+        EcRecoverCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, EcRecoverCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(
+    events: Vec<EcrecoverAuxData>,
+    max_ecrecover_calls: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        EcRecoverCircuit::<Fr>::unusable_rows()
+            + EcRecoverCircuit::<Fr>::min_num_rows(max_ecrecover_calls),
+    );
+    // SignVerifyChip -> ECDSAChip -> MainGate instance column
+    let circuit = EcRecoverCircuit::<F>::new(max_ecrecover_calls, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![vec![]]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+fn valid_event() -> EcrecoverAuxData {
+    EcrecoverAuxData {
+        msg_hash: word!("0x18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c"),
+        sig_v: Word::from(28),
+        sig_r: word!("0x73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75f"),
+        sig_s: word!("0xeeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549"),
+        recovered_addr: address!("0xa94f5374fce5edbc8e2a8697c15331677e6ebf0b"),
+    }
+}
+
+fn off_curve_event() -> EcrecoverAuxData {
+    // The smallest r that is not the x coordinate of any point of the curve
+    let sig_r = (1u64..)
+        .find(|&x| {
+            let x = secp256k1::Fp::from(x);
+            bool::from((x.square() * x + secp256k1::Fp::from(7)).sqrt().is_none())
+        })
+        .unwrap();
+    EcrecoverAuxData {
+        sig_r: Word::from(sig_r),
+        recovered_addr: Address::zero(),
+        ..valid_event()
+    }
+}
+
+fn infinity_event() -> EcrecoverAuxData {
+    // R = G, so that Q = r^-1 * (s * R - msg_hash * G) = 0 when s == msg_hash.
+    EcrecoverAuxData {
+        msg_hash: Word::from(1),
+        sig_v: Word::from(27),
+        sig_r: word!("0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+        sig_s: Word::from(1),
+        recovered_addr: Address::zero(),
+    }
+}
+
+fn secp256k1_n() -> Word {
+    Word::from_little_endian(&SECP256K1_Q.to_bytes_le())
+}
+
+#[test]
+fn ecrecover_circuit_valid_signature() {
+    assert_eq!(run::<Fr>(vec![valid_event()], 1), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_invalid_inputs() {
+    let invalid_v = EcrecoverAuxData {
+        sig_v: Word::from(29),
+        recovered_addr: Address::zero(),
+        ..valid_event()
+    };
+    let invalid_s = EcrecoverAuxData {
+        sig_s: Word::MAX,
+        recovered_addr: Address::zero(),
+        ..valid_event()
+    };
+    assert_eq!(
+        run::<Fr>(vec![invalid_v, valid_event(), invalid_s], 4),
+        Ok(())
+    );
+}
+
+#[test]
+fn ecrecover_circuit_wrong_address() {
+    let event = EcrecoverAuxData {
+        recovered_addr: address!("0x00000000000000000000000000000000000000ff"),
+        ..valid_event()
+    };
+    assert!(run::<Fr>(vec![event], 1).is_err());
+}
+
+#[test]
+fn ecrecover_circuit_off_curve_r() {
+    assert_eq!(run::<Fr>(vec![off_curve_event(), valid_event()], 2), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_missing_address() {
+    let event = EcrecoverAuxData {
+        recovered_addr: Address::zero(),
+        ..valid_event()
+    };
+    assert!(run::<Fr>(vec![event], 1).is_err());
+}
+
+#[test]
+fn ecrecover_circuit_msg_hash_overflow() {
+    let event = valid_event();
+    let msg_hash = secp256k1_n() + Word::from(0x1234);
+    let pk = recover_pk(1, &event.sig_r, &event.sig_s, &msg_hash.to_be_bytes()).unwrap();
+    let pk_hash = keccak256(pk_bytes_swap_endianness(&pk_bytes_le(&pk)));
+    let event = EcrecoverAuxData {
+        msg_hash,
+        recovered_addr: Address::from_slice(&pk_hash[12..]),
+        ..event
+    };
+    assert_eq!(run::<Fr>(vec![event], 1), Ok(()));
+}
+
+#[test]
+fn ecrecover_circuit_point_at_infinity() {
+    // The message hash n + 1 is reduced to 1.
+    let overflow_event = EcrecoverAuxData {
+        msg_hash: secp256k1_n() + Word::from(1),
+        ..infinity_event()
+    };
+    assert_eq!(
+        run::<Fr>(
+            vec![
+                infinity_event(),
+                off_curve_event(),
+                valid_event(),
+                overflow_event
+            ],
+            4
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn ecrecover_circuit_wrong_infinity() {
+    // The point at infinity is not recovered with a different message hash.
+    let event = EcrecoverAuxData {
+        msg_hash: Word::from(2),
+        ..infinity_event()
+    };
+    assert!(run::<Fr>(vec![event], 1).is_err());
+}
@@ -22,12 +22,17 @@ pub use crate::witness;
 use crate::{
     evm_circuit::param::{MAX_STEP_HEIGHT, STEP_STATE_HEIGHT},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, LookupTable, RwTable, TxTable,
+        BlockTable, BytecodeTable, CopyTable, EcAddTable, EcMulTable, EcPairingTable,
+        EcRecoverTable, ExpTable, KeccakTable, LookupTable, ModExpTable, RwTable, Sha256Table,
+        TxTable, UncleTable, WithdrawalTable,
     },
     util::{Challenges, SubCircuit, SubCircuitConfig},
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{
+    evm_types::{BlockReward, Hardfork},
+    Field,
+};
 use execution::ExecutionConfig;
 use itertools::Itertools;
 use strum::IntoEnumIterator;
@@ -48,6 +53,14 @@ pub struct EvmCircuitConfig<F> {
     copy_table: CopyTable,
     keccak_table: KeccakTable,
     exp_table: ExpTable,
+    ecrecover_table: EcRecoverTable,
+    sha256_table: Sha256Table,
+    modexp_table: ModExpTable,
+    ec_add_table: EcAddTable,
+    ec_mul_table: EcMulTable,
+    ec_pairing_table: EcPairingTable,
+    withdrawal_table: WithdrawalTable,
+    uncle_table: UncleTable,
 }
 
 /// Circuit configuration arguments
@@ -68,6 +81,22 @@ pub struct EvmCircuitConfigArgs<F: Field> {
     pub keccak_table: KeccakTable,
     /// ExpTable
     pub exp_table: ExpTable,
+    /// EcRecoverTable
+    pub ecrecover_table: EcRecoverTable,
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+    /// EcAddTable
+    pub ec_add_table: EcAddTable,
+    /// EcMulTable
+    pub ec_mul_table: EcMulTable,
+    /// EcPairingTable
+    pub ec_pairing_table: EcPairingTable,
+    /// WithdrawalTable
+    pub withdrawal_table: WithdrawalTable,
+    /// UncleTable
+    pub uncle_table: UncleTable,
 }
 
 impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
@@ -85,6 +114,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            ecrecover_table,
+            sha256_table,
+            modexp_table,
+            ec_add_table,
+            ec_mul_table,
+            ec_pairing_table,
+            withdrawal_table,
+            uncle_table,
         }: Self::ConfigArgs,
     ) -> Self {
         let fixed_table = [(); 4].map(|_| meta.fixed_column());
@@ -101,6 +138,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             &copy_table,
             &keccak_table,
             &exp_table,
+            &ecrecover_table,
+            &sha256_table,
+            &modexp_table,
+            &ec_add_table,
+            &ec_mul_table,
+            &ec_pairing_table,
+            &withdrawal_table,
+            &uncle_table,
         ));
 
         meta.annotate_lookup_any_column(byte_table[0], || "byte_range");
@@ -114,6 +159,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
         copy_table.annotate_columns(meta);
         keccak_table.annotate_columns(meta);
         exp_table.annotate_columns(meta);
+        ecrecover_table.annotate_columns(meta);
+        sha256_table.annotate_columns(meta);
+        modexp_table.annotate_columns(meta);
+        ec_add_table.annotate_columns(meta);
+        ec_mul_table.annotate_columns(meta);
+        ec_pairing_table.annotate_columns(meta);
+        withdrawal_table.annotate_columns(meta);
+        uncle_table.annotate_columns(meta);
 
         Self {
             fixed_table,
@@ -126,6 +179,14 @@ impl<F: Field> SubCircuitConfig<F> for EvmCircuitConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            ecrecover_table,
+            sha256_table,
+            modexp_table,
+            ec_add_table,
+            ec_mul_table,
+            ec_pairing_table,
+            withdrawal_table,
+            uncle_table,
         }
     }
 }
@@ -136,12 +197,18 @@ impl<F: Field> EvmCircuitConfig<F> {
         &self,
         layouter: &mut impl Layouter<F>,
         fixed_table_tags: Vec<FixedTableTag>,
+        hardfork: Hardfork,
+        block_reward: BlockReward,
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "fixed table",
             |mut region| {
                 for (offset, row) in std::iter::once([F::ZERO; 4])
-                    .chain(fixed_table_tags.iter().flat_map(|tag| tag.build()))
+                    .chain(
+                        fixed_table_tags
+                            .iter()
+                            .flat_map(|tag| tag.build(hardfork, block_reward)),
+                    )
                     .enumerate()
                 {
                     for (column, value) in self.fixed_table.iter().zip_eq(row) {
@@ -225,6 +292,13 @@ impl<F: Field> EvmCircuit<F> {
                 num_rows += step.execution_state().get_step_height();
             }
         }
+        for step in block
+            .withdrawal_steps
+            .iter()
+            .chain(std::iter::once(&block.block_reward_step))
+        {
+            num_rows += step.execution_state().get_step_height();
+        }
 
         // It must have one row for EndBlock and at least one unused one
         num_rows + 2
@@ -249,7 +323,13 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
         let num_rows_required_for_execution_steps: usize = Self::get_num_rows_required(block);
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(block)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| {
+                tag.build::<F>(
+                    block.circuits_params.hardfork,
+                    block.circuits_params.block_reward,
+                )
+                .count()
+            })
             .sum();
         (
             std::cmp::max(
@@ -269,7 +349,12 @@ impl<F: Field> SubCircuit<F> for EvmCircuit<F> {
     ) -> Result<(), Error> {
         let block = self.block.as_ref().unwrap();
 
-        config.load_fixed_table(layouter, self.fixed_table_tags.clone())?;
+        config.load_fixed_table(
+            layouter,
+            self.fixed_table_tags.clone(),
+            block.circuits_params.hardfork,
+            block.circuits_params.block_reward,
+        )?;
         config.load_byte_table(layouter)?;
         config.execution.assign_block(layouter, block, challenges)
     }
@@ -375,6 +460,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let keccak_table = KeccakTable::construct(meta);
         let exp_table = ExpTable::construct(meta);
+        let ecrecover_table = EcRecoverTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let ec_add_table = EcAddTable::construct(meta);
+        let ec_mul_table = EcMulTable::construct(meta);
+        let ec_pairing_table = EcPairingTable::construct(meta);
+        let withdrawal_table = WithdrawalTable::construct(meta);
+        let uncle_table = UncleTable::construct(meta);
         let challenges = Challenges::construct(meta);
         let challenges_expr = challenges.exprs(meta);
 
@@ -390,6 +483,14 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
                     copy_table,
                     keccak_table,
                     exp_table,
+                    ecrecover_table,
+                    sha256_table,
+                    modexp_table,
+                    ec_add_table,
+                    ec_mul_table,
+                    ec_pairing_table,
+                    withdrawal_table,
+                    uncle_table,
                 },
             ),
             challenges,
@@ -431,6 +532,28 @@ impl<F: Field> Circuit<F> for EvmCircuit<F> {
             .keccak_table
             .dev_load(&mut layouter, &block.sha3_inputs, &challenges)?;
         config.exp_table.load(&mut layouter, block)?;
+        config
+            .ecrecover_table
+            .dev_load(&mut layouter, &block.ecrecover_events, &challenges)?;
+        config
+            .sha256_table
+            .dev_load(&mut layouter, &block.sha256_inputs, &challenges)?;
+        config
+            .modexp_table
+            .dev_load(&mut layouter, &block.modexp_events)?;
+        config
+            .ec_add_table
+            .dev_load(&mut layouter, &block.ec_add_events)?;
+        config
+            .ec_mul_table
+            .dev_load(&mut layouter, &block.ec_mul_events)?;
+        config
+            .ec_pairing_table
+            .dev_load(&mut layouter, &block.ec_pairing_events, &challenges)?;
+        config
+            .withdrawal_table
+            .load(&mut layouter, &block.withdrawals)?;
+        config.uncle_table.load(&mut layouter, &block.uncles)?;
 
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
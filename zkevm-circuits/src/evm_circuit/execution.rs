@@ -1,8 +1,10 @@
 use super::{
     param::{
-        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
-        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, N_BYTE_LOOKUPS, N_COPY_COLUMNS,
-        N_PHASE1_COLUMNS, RW_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
+        BLOCK_TABLE_LOOKUPS, BYTECODE_TABLE_LOOKUPS, COPY_TABLE_LOOKUPS, ECRECOVER_TABLE_LOOKUPS,
+        EC_ADD_TABLE_LOOKUPS, EC_MUL_TABLE_LOOKUPS, EC_PAIRING_TABLE_LOOKUPS, EXP_TABLE_LOOKUPS,
+        FIXED_TABLE_LOOKUPS, KECCAK_TABLE_LOOKUPS, MODEXP_TABLE_LOOKUPS, N_BYTE_LOOKUPS,
+        N_COPY_COLUMNS, N_PHASE1_COLUMNS, RW_TABLE_LOOKUPS, SHA256_TABLE_LOOKUPS, TX_TABLE_LOOKUPS,
+        UNCLE_TABLE_LOOKUPS, WITHDRAWAL_TABLE_LOOKUPS,
     },
     step::HasExecutionState,
     util::{instrumentation::Instrument, CachedRegion, CellManager, StoredExpression},
@@ -45,7 +47,10 @@ mod address;
 mod balance;
 mod begin_tx;
 mod bitwise;
+mod blobbasefee;
+mod blobhash;
 mod block_ctx;
+mod block_reward;
 mod blockhash;
 mod byte;
 mod calldatacopy;
@@ -58,12 +63,15 @@ mod chainid;
 mod codecopy;
 mod codesize;
 mod comparator;
+mod create;
+mod destruct_account;
 mod dummy;
 mod dup;
 mod end_block;
 mod end_tx;
 mod error_invalid_jump;
 mod error_invalid_opcode;
+mod error_max_init_code_size;
 mod error_oog_call;
 mod error_oog_constant;
 mod error_oog_exp;
@@ -85,6 +93,7 @@ mod jump;
 mod jumpdest;
 mod jumpi;
 mod logs;
+mod mcopy;
 mod memory;
 mod msize;
 mod mul_div_mod;
@@ -94,13 +103,16 @@ mod opcode_not;
 mod origin;
 mod pc;
 mod pop;
+mod precompiles;
 mod push;
+mod push0;
 mod return_revert;
 mod returndatacopy;
 mod returndatasize;
 mod sar;
 mod sdiv_smod;
 mod selfbalance;
+mod selfdestruct;
 mod sha3;
 mod shl_shr;
 mod signed_comparator;
@@ -109,6 +121,9 @@ mod sload;
 mod sstore;
 mod stop;
 mod swap;
+mod tload;
+mod tstore;
+mod withdrawal;
 
 use self::sha3::Sha3Gadget;
 use add_sub::AddSubGadget;
@@ -117,7 +132,10 @@ use address::AddressGadget;
 use balance::BalanceGadget;
 use begin_tx::BeginTxGadget;
 use bitwise::BitwiseGadget;
+use blobbasefee::BlobBaseFeeGadget;
+use blobhash::BlobHashGadget;
 use block_ctx::{BlockCtxU160Gadget, BlockCtxU256Gadget, BlockCtxU64Gadget};
+use block_reward::BlockRewardGadget;
 use blockhash::BlockHashGadget;
 use byte::ByteGadget;
 use calldatacopy::CallDataCopyGadget;
@@ -130,12 +148,15 @@ use chainid::ChainIdGadget;
 use codecopy::CodeCopyGadget;
 use codesize::CodesizeGadget;
 use comparator::ComparatorGadget;
+use create::CreateGadget;
+use destruct_account::DestructAccountGadget;
 use dummy::DummyGadget;
 use dup::DupGadget;
 use end_block::EndBlockGadget;
 use end_tx::EndTxGadget;
 use error_invalid_jump::ErrorInvalidJumpGadget;
 use error_invalid_opcode::ErrorInvalidOpcodeGadget;
+use error_max_init_code_size::ErrorMaxInitCodeSizeGadget;
 use error_oog_call::ErrorOOGCallGadget;
 use error_oog_constant::ErrorOOGConstantGadget;
 use error_oog_exp::ErrorOOGExpGadget;
@@ -156,6 +177,7 @@ use jump::JumpGadget;
 use jumpdest::JumpdestGadget;
 use jumpi::JumpiGadget;
 use logs::LogGadget;
+use mcopy::MCopyGadget;
 use memory::MemoryGadget;
 use msize::MsizeGadget;
 use mul_div_mod::MulDivModGadget;
@@ -164,13 +186,16 @@ use opcode_not::NotGadget;
 use origin::OriginGadget;
 use pc::PcGadget;
 use pop::PopGadget;
+use precompiles::PrecompileGadget;
 use push::PushGadget;
+use push0::Push0Gadget;
 use return_revert::ReturnRevertGadget;
 use returndatacopy::ReturnDataCopyGadget;
 use returndatasize::ReturnDataSizeGadget;
 use sar::SarGadget;
 use sdiv_smod::SignedDivModGadget;
 use selfbalance::SelfbalanceGadget;
+use selfdestruct::SelfDestructGadget;
 use shl_shr::ShlShrGadget;
 use signed_comparator::SignedComparatorGadget;
 use signextend::SignextendGadget;
@@ -178,6 +203,9 @@ use sload::SloadGadget;
 use sstore::SstoreGadget;
 use stop::StopGadget;
 use swap::SwapGadget;
+use tload::TloadGadget;
+use tstore::TstoreGadget;
+use withdrawal::WithdrawalGadget;
 
 pub(crate) trait ExecutionGadget<F: Field> {
     const NAME: &'static str;
@@ -221,8 +249,11 @@ pub struct ExecutionConfig<F> {
     instrument: Instrument,
     // internal state gadgets
     begin_tx_gadget: Box<BeginTxGadget<F>>,
+    block_reward_gadget: Box<BlockRewardGadget<F>>,
+    destruct_account_gadget: Box<DestructAccountGadget<F>>,
     end_block_gadget: Box<EndBlockGadget<F>>,
     end_tx_gadget: Box<EndTxGadget<F>>,
+    withdrawal_gadget: Box<WithdrawalGadget<F>>,
     // opcode gadgets
     add_sub_gadget: Box<AddSubGadget<F>>,
     addmod_gadget: Box<AddModGadget<F>>,
@@ -252,6 +283,7 @@ pub struct ExecutionConfig<F> {
     jumpdest_gadget: Box<JumpdestGadget<F>>,
     jumpi_gadget: Box<JumpiGadget<F>>,
     log_gadget: Box<LogGadget<F>>,
+    mcopy_gadget: Box<MCopyGadget<F>>,
     memory_gadget: Box<MemoryGadget<F>>,
     msize_gadget: Box<MsizeGadget<F>>,
     mul_div_mod_gadget: Box<MulDivModGadget<F>>,
@@ -261,6 +293,7 @@ pub struct ExecutionConfig<F> {
     pc_gadget: Box<PcGadget<F>>,
     pop_gadget: Box<PopGadget<F>>,
     push_gadget: Box<PushGadget<F>>,
+    push0_gadget: Box<Push0Gadget<F>>,
     return_revert_gadget: Box<ReturnRevertGadget<F>>,
     sar_gadget: Box<SarGadget<F>>,
     sdiv_smod_gadget: Box<SignedDivModGadget<F>>,
@@ -269,19 +302,36 @@ pub struct ExecutionConfig<F> {
     shl_shr_gadget: Box<ShlShrGadget<F>>,
     returndatasize_gadget: Box<ReturnDataSizeGadget<F>>,
     returndatacopy_gadget: Box<ReturnDataCopyGadget<F>>,
-    create_gadget: Box<DummyGadget<F, 3, 1, { ExecutionState::CREATE }>>,
-    create2_gadget: Box<DummyGadget<F, 4, 1, { ExecutionState::CREATE2 }>>,
-    selfdestruct_gadget: Box<DummyGadget<F, 1, 0, { ExecutionState::SELFDESTRUCT }>>,
+    create_gadget: Box<CreateGadget<F, false, { ExecutionState::CREATE }>>,
+    create2_gadget: Box<CreateGadget<F, true, { ExecutionState::CREATE2 }>>,
+    selfdestruct_gadget: Box<SelfDestructGadget<F>>,
     signed_comparator_gadget: Box<SignedComparatorGadget<F>>,
     signextend_gadget: Box<SignextendGadget<F>>,
     sload_gadget: Box<SloadGadget<F>>,
     sstore_gadget: Box<SstoreGadget<F>>,
     stop_gadget: Box<StopGadget<F>>,
     swap_gadget: Box<SwapGadget<F>>,
+    tload_gadget: Box<TloadGadget<F>>,
+    tstore_gadget: Box<TstoreGadget<F>>,
     blockhash_gadget: Box<BlockHashGadget<F>>,
     block_ctx_u64_gadget: Box<BlockCtxU64Gadget<F>>,
     block_ctx_u160_gadget: Box<BlockCtxU160Gadget<F>>,
     block_ctx_u256_gadget: Box<BlockCtxU256Gadget<F>>,
+    blobhash_gadget: Box<BlobHashGadget<F>>,
+    blob_base_fee_gadget: Box<BlobBaseFeeGadget<F>>,
+    // precompile gadgets
+    precompile_ecrecover_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileEcRecover }>>,
+    precompile_sha256_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileSha256 }>>,
+    precompile_ripemd160_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileRipemd160 }>>,
+    precompile_identity_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileIdentity }>>,
+    precompile_big_mod_exp_gadget:
+        Box<PrecompileGadget<F, { ExecutionState::PrecompileBigModExp }>>,
+    precompile_bn256_add_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileBn256Add }>>,
+    precompile_bn256_scalar_mul_gadget:
+        Box<PrecompileGadget<F, { ExecutionState::PrecompileBn256ScalarMul }>>,
+    precompile_bn256_pairing_gadget:
+        Box<PrecompileGadget<F, { ExecutionState::PrecompileBn256Pairing }>>,
+    precompile_blake2f_gadget: Box<PrecompileGadget<F, { ExecutionState::PrecompileBlake2f }>>,
     // error gadgets
     error_oog_call: Box<ErrorOOGCallGadget<F>>,
     error_oog_constant: Box<ErrorOOGConstantGadget<F>>,
@@ -310,6 +360,7 @@ pub struct ExecutionConfig<F> {
         Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorContractAddressCollision }>>,
     error_invalid_creation_code:
         Box<DummyGadget<F, 0, 0, { ExecutionState::ErrorInvalidCreationCode }>>,
+    error_max_init_code_size: Box<ErrorMaxInitCodeSizeGadget<F>>,
     error_return_data_out_of_bound: Box<ErrorReturnDataOutOfBoundGadget<F>>,
 }
 
@@ -328,6 +379,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        ecrecover_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ec_add_table: &dyn LookupTable<F>,
+        ec_mul_table: &dyn LookupTable<F>,
+        ec_pairing_table: &dyn LookupTable<F>,
+        withdrawal_table: &dyn LookupTable<F>,
+        uncle_table: &dyn LookupTable<F>,
     ) -> Self {
         let mut instrument = Instrument::default();
         let q_usable = meta.complex_selector();
@@ -368,11 +427,15 @@ impl<F: Field> ExecutionConfig<F> {
 
             // NEW: Enabled, this will break hand crafted tests, maybe we can remove them?
             let first_step_check = {
-                let begin_tx_end_block_selector = step_curr
-                    .execution_state_selector([ExecutionState::BeginTx, ExecutionState::EndBlock]);
+                let begin_tx_withdrawal_block_reward_selector =
+                    step_curr.execution_state_selector([
+                        ExecutionState::BeginTx,
+                        ExecutionState::Withdrawal,
+                        ExecutionState::BlockReward,
+                    ]);
                 iter::once((
-                    "First step should be BeginTx or EndBlock",
-                    q_step_first * (1.expr() - begin_tx_end_block_selector),
+                    "First step should be BeginTx, Withdrawal or BlockReward",
+                    q_step_first * (1.expr() - begin_tx_withdrawal_block_reward_selector),
                 ))
             };
 
@@ -488,8 +551,11 @@ impl<F: Field> ExecutionConfig<F> {
             advices,
             // internal states
             begin_tx_gadget: configure_gadget!(),
+            block_reward_gadget: configure_gadget!(),
+            destruct_account_gadget: configure_gadget!(),
             end_block_gadget: configure_gadget!(),
             end_tx_gadget: configure_gadget!(),
+            withdrawal_gadget: configure_gadget!(),
             // opcode gadgets
             add_sub_gadget: configure_gadget!(),
             addmod_gadget: configure_gadget!(),
@@ -515,6 +581,7 @@ impl<F: Field> ExecutionConfig<F> {
             jumpdest_gadget: configure_gadget!(),
             jumpi_gadget: configure_gadget!(),
             log_gadget: configure_gadget!(),
+            mcopy_gadget: configure_gadget!(),
             memory_gadget: configure_gadget!(),
             msize_gadget: configure_gadget!(),
             mul_div_mod_gadget: configure_gadget!(),
@@ -524,6 +591,7 @@ impl<F: Field> ExecutionConfig<F> {
             pc_gadget: configure_gadget!(),
             pop_gadget: configure_gadget!(),
             push_gadget: configure_gadget!(),
+            push0_gadget: configure_gadget!(),
             return_revert_gadget: configure_gadget!(),
             sdiv_smod_gadget: configure_gadget!(),
             selfbalance_gadget: configure_gadget!(),
@@ -546,9 +614,13 @@ impl<F: Field> ExecutionConfig<F> {
             sstore_gadget: configure_gadget!(),
             stop_gadget: configure_gadget!(),
             swap_gadget: configure_gadget!(),
+            tload_gadget: configure_gadget!(),
+            tstore_gadget: configure_gadget!(),
             block_ctx_u64_gadget: configure_gadget!(),
             block_ctx_u160_gadget: configure_gadget!(),
             block_ctx_u256_gadget: configure_gadget!(),
+            blobhash_gadget: configure_gadget!(),
+            blob_base_fee_gadget: configure_gadget!(),
             // error gadgets
             error_oog_constant: configure_gadget!(),
             error_oog_static_memory_gadget: configure_gadget!(),
@@ -571,7 +643,18 @@ impl<F: Field> ExecutionConfig<F> {
             error_depth: configure_gadget!(),
             error_contract_address_collision: configure_gadget!(),
             error_invalid_creation_code: configure_gadget!(),
+            error_max_init_code_size: configure_gadget!(),
             error_return_data_out_of_bound: configure_gadget!(),
+            // precompile gadgets
+            precompile_ecrecover_gadget: configure_gadget!(),
+            precompile_sha256_gadget: configure_gadget!(),
+            precompile_ripemd160_gadget: configure_gadget!(),
+            precompile_identity_gadget: configure_gadget!(),
+            precompile_big_mod_exp_gadget: configure_gadget!(),
+            precompile_bn256_add_gadget: configure_gadget!(),
+            precompile_bn256_scalar_mul_gadget: configure_gadget!(),
+            precompile_bn256_pairing_gadget: configure_gadget!(),
+            precompile_blake2f_gadget: configure_gadget!(),
             // step and presets
             step: step_curr,
             height_map,
@@ -590,6 +673,14 @@ impl<F: Field> ExecutionConfig<F> {
             copy_table,
             keccak_table,
             exp_table,
+            ecrecover_table,
+            sha256_table,
+            modexp_table,
+            ec_add_table,
+            ec_mul_table,
+            ec_pairing_table,
+            withdrawal_table,
+            uncle_table,
             &challenges,
             &cell_manager,
         );
@@ -744,9 +835,36 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "EndTx can only transit to BeginTx or EndBlock",
+                            "EndTx can only transit to BeginTx, DestructAccount, Withdrawal or \
+                             BlockReward",
                             ExecutionState::EndTx,
-                            vec![ExecutionState::BeginTx, ExecutionState::EndBlock],
+                            vec![
+                                ExecutionState::BeginTx,
+                                ExecutionState::DestructAccount,
+                                ExecutionState::Withdrawal,
+                                ExecutionState::BlockReward,
+                            ],
+                        ),
+                        (
+                            "DestructAccount can only transit to BeginTx, DestructAccount, \
+                             Withdrawal or BlockReward",
+                            ExecutionState::DestructAccount,
+                            vec![
+                                ExecutionState::BeginTx,
+                                ExecutionState::DestructAccount,
+                                ExecutionState::Withdrawal,
+                                ExecutionState::BlockReward,
+                            ],
+                        ),
+                        (
+                            "Withdrawal can only transit to Withdrawal or BlockReward",
+                            ExecutionState::Withdrawal,
+                            vec![ExecutionState::Withdrawal, ExecutionState::BlockReward],
+                        ),
+                        (
+                            "BlockReward can only transit to EndBlock",
+                            ExecutionState::BlockReward,
+                            vec![ExecutionState::EndBlock],
                         ),
                         (
                             "EndBlock can only transit to EndBlock",
@@ -760,9 +878,14 @@ impl<F: Field> ExecutionConfig<F> {
                 .chain(
                     IntoIterator::into_iter([
                         (
-                            "Only EndTx can transit to BeginTx",
+                            "Only EndTx or DestructAccount can transit to BeginTx",
                             ExecutionState::BeginTx,
-                            vec![ExecutionState::EndTx],
+                            vec![ExecutionState::EndTx, ExecutionState::DestructAccount],
+                        ),
+                        (
+                            "Only EndTx or DestructAccount can transit to DestructAccount",
+                            ExecutionState::DestructAccount,
+                            vec![ExecutionState::EndTx, ExecutionState::DestructAccount],
                         ),
                         (
                             "Only ExecutionState which halts or BeginTx can transit to EndTx",
@@ -773,9 +896,27 @@ impl<F: Field> ExecutionConfig<F> {
                                 .collect(),
                         ),
                         (
-                            "Only EndTx or EndBlock can transit to EndBlock",
+                            "Only EndTx, DestructAccount or Withdrawal can transit to Withdrawal",
+                            ExecutionState::Withdrawal,
+                            vec![
+                                ExecutionState::EndTx,
+                                ExecutionState::DestructAccount,
+                                ExecutionState::Withdrawal,
+                            ],
+                        ),
+                        (
+                            "Only EndTx, DestructAccount or Withdrawal can transit to BlockReward",
+                            ExecutionState::BlockReward,
+                            vec![
+                                ExecutionState::EndTx,
+                                ExecutionState::DestructAccount,
+                                ExecutionState::Withdrawal,
+                            ],
+                        ),
+                        (
+                            "Only BlockReward or EndBlock can transit to EndBlock",
                             ExecutionState::EndBlock,
-                            vec![ExecutionState::EndTx, ExecutionState::EndBlock],
+                            vec![ExecutionState::BlockReward, ExecutionState::EndBlock],
                         ),
                     ])
                     .filter(move |(_, _, from)| !from.contains(&execution_state))
@@ -806,6 +947,14 @@ impl<F: Field> ExecutionConfig<F> {
         copy_table: &dyn LookupTable<F>,
         keccak_table: &dyn LookupTable<F>,
         exp_table: &dyn LookupTable<F>,
+        ecrecover_table: &dyn LookupTable<F>,
+        sha256_table: &dyn LookupTable<F>,
+        modexp_table: &dyn LookupTable<F>,
+        ec_add_table: &dyn LookupTable<F>,
+        ec_mul_table: &dyn LookupTable<F>,
+        ec_pairing_table: &dyn LookupTable<F>,
+        withdrawal_table: &dyn LookupTable<F>,
+        uncle_table: &dyn LookupTable<F>,
         challenges: &Challenges<Expression<F>>,
         cell_manager: &CellManager<F>,
     ) {
@@ -822,6 +971,14 @@ impl<F: Field> ExecutionConfig<F> {
                         Table::Copy => copy_table,
                         Table::Keccak => keccak_table,
                         Table::Exp => exp_table,
+                        Table::EcRecover => ecrecover_table,
+                        Table::Sha256 => sha256_table,
+                        Table::ModExp => modexp_table,
+                        Table::EcAdd => ec_add_table,
+                        Table::EcMul => ec_mul_table,
+                        Table::EcPairing => ec_pairing_table,
+                        Table::Withdrawal => withdrawal_table,
+                        Table::Uncle => uncle_table,
                     }
                     .table_exprs(meta);
                     vec![(
@@ -915,6 +1072,17 @@ impl<F: Field> ExecutionConfig<F> {
                             .iter()
                             .map(move |step| (tx, &tx.calls[step.call_index], step))
                     })
+                    .chain(
+                        block
+                            .withdrawal_steps
+                            .iter()
+                            .map(|step| (&dummy_tx, &last_call, step)),
+                    )
+                    .chain(std::iter::once((
+                        &dummy_tx,
+                        &last_call,
+                        &block.block_reward_step,
+                    )))
                     .chain(std::iter::once((&dummy_tx, &last_call, end_block_not_last)))
                     .peekable();
 
@@ -1035,6 +1203,14 @@ impl<F: Field> ExecutionConfig<F> {
             ("EVM_lookup_copy", COPY_TABLE_LOOKUPS),
             ("EVM_lookup_keccak", KECCAK_TABLE_LOOKUPS),
             ("EVM_lookup_exp", EXP_TABLE_LOOKUPS),
+            ("EVM_lookup_ecrecover", ECRECOVER_TABLE_LOOKUPS),
+            ("EVM_lookup_sha256", SHA256_TABLE_LOOKUPS),
+            ("EVM_lookup_modexp", MODEXP_TABLE_LOOKUPS),
+            ("EVM_lookup_ec_add", EC_ADD_TABLE_LOOKUPS),
+            ("EVM_lookup_ec_mul", EC_MUL_TABLE_LOOKUPS),
+            ("EVM_lookup_ec_pairing", EC_PAIRING_TABLE_LOOKUPS),
+            ("EVM_lookup_withdrawal", WITHDRAWAL_TABLE_LOOKUPS),
+            ("EVM_lookup_uncle", UNCLE_TABLE_LOOKUPS),
             ("EVM_adv_phase2", N_PHASE2_COLUMNS),
             ("EVM_copy", N_COPY_COLUMNS),
             ("EVM_lookup_byte", N_BYTE_LOOKUPS),
@@ -1170,7 +1346,10 @@ impl<F: Field> ExecutionConfig<F> {
             // internal states
             ExecutionState::BeginTx => assign_exec_step!(self.begin_tx_gadget),
             ExecutionState::EndTx => assign_exec_step!(self.end_tx_gadget),
+            ExecutionState::DestructAccount => assign_exec_step!(self.destruct_account_gadget),
             ExecutionState::EndBlock => assign_exec_step!(self.end_block_gadget),
+            ExecutionState::Withdrawal => assign_exec_step!(self.withdrawal_gadget),
+            ExecutionState::BlockReward => assign_exec_step!(self.block_reward_gadget),
             // opcode
             ExecutionState::ADD_SUB => assign_exec_step!(self.add_sub_gadget),
             ExecutionState::ADDMOD => assign_exec_step!(self.addmod_gadget),
@@ -1199,6 +1378,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::JUMPDEST => assign_exec_step!(self.jumpdest_gadget),
             ExecutionState::JUMPI => assign_exec_step!(self.jumpi_gadget),
             ExecutionState::LOG => assign_exec_step!(self.log_gadget),
+            ExecutionState::MCOPY => assign_exec_step!(self.mcopy_gadget),
             ExecutionState::MEMORY => assign_exec_step!(self.memory_gadget),
             ExecutionState::MSIZE => assign_exec_step!(self.msize_gadget),
             ExecutionState::MUL_DIV_MOD => assign_exec_step!(self.mul_div_mod_gadget),
@@ -1208,6 +1388,7 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::PC => assign_exec_step!(self.pc_gadget),
             ExecutionState::POP => assign_exec_step!(self.pop_gadget),
             ExecutionState::PUSH => assign_exec_step!(self.push_gadget),
+            ExecutionState::PUSH0 => assign_exec_step!(self.push0_gadget),
             ExecutionState::RETURN_REVERT => assign_exec_step!(self.return_revert_gadget),
             ExecutionState::RETURNDATASIZE => assign_exec_step!(self.returndatasize_gadget),
             ExecutionState::RETURNDATACOPY => assign_exec_step!(self.returndatacopy_gadget),
@@ -1218,13 +1399,13 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::BLOCKCTXU160 => assign_exec_step!(self.block_ctx_u160_gadget),
             ExecutionState::BLOCKCTXU256 => assign_exec_step!(self.block_ctx_u256_gadget),
             ExecutionState::BLOCKHASH => assign_exec_step!(self.blockhash_gadget),
+            ExecutionState::BLOBHASH => assign_exec_step!(self.blobhash_gadget),
+            ExecutionState::BLOBBASEFEE => assign_exec_step!(self.blob_base_fee_gadget),
             ExecutionState::SELFBALANCE => assign_exec_step!(self.selfbalance_gadget),
-            // dummy gadgets
             ExecutionState::EXTCODECOPY => assign_exec_step!(self.extcodecopy_gadget),
             ExecutionState::CREATE => assign_exec_step!(self.create_gadget),
             ExecutionState::CREATE2 => assign_exec_step!(self.create2_gadget),
             ExecutionState::SELFDESTRUCT => assign_exec_step!(self.selfdestruct_gadget),
-            // end of dummy gadgets
             ExecutionState::SHA3 => assign_exec_step!(self.sha3_gadget),
             ExecutionState::SHL_SHR => assign_exec_step!(self.shl_shr_gadget),
             ExecutionState::SIGNEXTEND => assign_exec_step!(self.signextend_gadget),
@@ -1232,6 +1413,8 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::SSTORE => assign_exec_step!(self.sstore_gadget),
             ExecutionState::STOP => assign_exec_step!(self.stop_gadget),
             ExecutionState::SWAP => assign_exec_step!(self.swap_gadget),
+            ExecutionState::TLOAD => assign_exec_step!(self.tload_gadget),
+            ExecutionState::TSTORE => assign_exec_step!(self.tstore_gadget),
             // dummy errors
             ExecutionState::ErrorOutOfGasStaticMemoryExpansion => {
                 assign_exec_step!(self.error_oog_static_memory_gadget)
@@ -1292,9 +1475,40 @@ impl<F: Field> ExecutionConfig<F> {
             ExecutionState::ErrorInvalidCreationCode => {
                 assign_exec_step!(self.error_invalid_creation_code)
             }
+            ExecutionState::ErrorMaxInitCodeSizeExceeded => {
+                assign_exec_step!(self.error_max_init_code_size)
+            }
             ExecutionState::ErrorReturnDataOutOfBound => {
                 assign_exec_step!(self.error_return_data_out_of_bound)
             }
+            // precompile calls
+            ExecutionState::PrecompileEcRecover => {
+                assign_exec_step!(self.precompile_ecrecover_gadget)
+            }
+            ExecutionState::PrecompileSha256 => {
+                assign_exec_step!(self.precompile_sha256_gadget)
+            }
+            ExecutionState::PrecompileRipemd160 => {
+                assign_exec_step!(self.precompile_ripemd160_gadget)
+            }
+            ExecutionState::PrecompileIdentity => {
+                assign_exec_step!(self.precompile_identity_gadget)
+            }
+            ExecutionState::PrecompileBigModExp => {
+                assign_exec_step!(self.precompile_big_mod_exp_gadget)
+            }
+            ExecutionState::PrecompileBn256Add => {
+                assign_exec_step!(self.precompile_bn256_add_gadget)
+            }
+            ExecutionState::PrecompileBn256ScalarMul => {
+                assign_exec_step!(self.precompile_bn256_scalar_mul_gadget)
+            }
+            ExecutionState::PrecompileBn256Pairing => {
+                assign_exec_step!(self.precompile_bn256_pairing_gadget)
+            }
+            ExecutionState::PrecompileBlake2f => {
+                assign_exec_step!(self.precompile_blake2f_gadget)
+            }
 
             unimpl_state => evm_unimplemented!("unimplemented ExecutionState: {:?}", unimpl_state),
         }
@@ -3,27 +3,32 @@ use crate::{
         execution::ExecutionGadget,
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_U64, N_BYTES_WORD},
         step::ExecutionState,
+        table::HardforkParam,
         util::{
             and,
-            common_gadget::TransferWithGasFeeGadget,
+            common_gadget::{PrecompileAddressGadget, TransferWithGasFeeGadget},
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
                 Transition::{Delta, To},
             },
-            is_precompiled,
             math_gadget::{
-                ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget,
-                MulWordByU64Gadget, RangeCheckGadget,
+                AddWordsGadget, ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget,
+                IsZeroGadget, LtWordGadget, MulWordByU64Gadget, RangeCheckGadget,
             },
             not, or, select, CachedRegion, Cell, StepRws, Word,
         },
-        witness::{Block, Call, ExecStep, Transaction},
+        witness::{
+            access_list_addresses_rlc, access_list_storage_keys_rlc, Block, Call, ExecStep,
+            Transaction,
+        },
     },
     table::{
-        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxFieldTag as TxContextFieldTag,
+        AccountFieldTag, BlockContextFieldTag, CallContextFieldTag, TxAccountFlagFieldTag,
+        TxFieldTag as TxContextFieldTag,
     },
     util::Expr,
 };
+use bus_mapping::circuit_input_builder::CopyDataType;
 use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar};
 use ethers_core::utils::{get_contract_address, keccak256};
 use gadgets::util::expr_from_bytes;
@@ -35,7 +40,21 @@ pub(crate) struct BeginTxGadget<F> {
     tx_nonce: Cell<F>,
     tx_gas: Cell<F>,
     tx_gas_price: Word<F>,
+    tx_max_fee_per_gas: Word<F>,
+    tx_max_priority_fee_per_gas: Word<F>,
+    base_fee: Word<F>,
+    add_base_fee_by_max_priority_fee: AddWordsGadget<F, 2, true>,
+    is_max_fee_lt_base_fee_plus_max_priority_fee: LtWordGadget<F>,
+    is_max_fee_lt_base_fee: LtWordGadget<F>,
+    is_max_fee_lt_max_priority_fee: LtWordGadget<F>,
     mul_gas_fee_by_gas: MulWordByU64Gadget<F>,
+    // EIP-4844 (Blob transactions)
+    tx_max_fee_per_blob_gas: Word<F>,
+    tx_blob_versioned_hashes_len: Cell<F>,
+    blob_base_fee: Word<F>,
+    is_max_fee_per_blob_gas_lt_blob_base_fee: LtWordGadget<F>,
+    mul_blob_fee_by_blob_gas: MulWordByU64Gadget<F>,
+    add_gas_fee_by_blob_fee: AddWordsGadget<F, 2, true>,
     tx_caller_address: Cell<F>,
     tx_caller_address_is_zero: IsZeroGadget<F>,
     tx_callee_address: Cell<F>,
@@ -44,6 +63,12 @@ pub(crate) struct BeginTxGadget<F> {
     tx_value: Word<F>,
     tx_call_data_length: Cell<F>,
     tx_call_data_gas_cost: Cell<F>,
+    tx_access_list_addresses_len: Cell<F>,
+    tx_access_list_storage_keys_len: Cell<F>,
+    tx_access_list_addresses_rlc: Cell<F>,
+    tx_access_list_storage_keys_rlc: Cell<F>,
+    is_access_list_addresses_empty: IsZeroGadget<F>,
+    is_access_list_storage_keys_empty: IsZeroGadget<F>,
     tx_call_data_word_length: ConstantDivisionGadget<F, N_BYTES_U64>,
     reversion_info: ReversionInfo<F>,
     sufficient_gas_left: RangeCheckGadget<F, N_BYTES_GAS>,
@@ -53,8 +78,11 @@ pub(crate) struct BeginTxGadget<F> {
     caller_nonce_hash_bytes: [Cell<F>; N_BYTES_WORD],
     create: ContractCreateGadget<F, false>,
     callee_not_exists: IsZeroGadget<F>,
+    is_precompile: PrecompileAddressGadget<F>,
     is_caller_callee_equal: Cell<F>,
+    init_code_word_gas: Cell<F>,
     // EIP-3651 (Warm COINBASE)
+    warm_coinbase: Cell<F>,
     coinbase: Cell<F>,
     // Caller, callee and a list addresses are added to the access list before
     // coinbase, and may be duplicate.
@@ -86,7 +114,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             reversion_info.is_persistent(),
         ); // rwc_delta += 1
 
-        let [tx_nonce, tx_gas, tx_caller_address, tx_callee_address, tx_is_create, tx_call_data_length, tx_call_data_gas_cost] =
+        let [tx_nonce, tx_gas, tx_caller_address, tx_callee_address, tx_is_create, tx_call_data_length, tx_call_data_gas_cost, tx_access_list_addresses_len, tx_access_list_storage_keys_len, tx_blob_versioned_hashes_len] =
             [
                 TxContextFieldTag::Nonce,
                 TxContextFieldTag::Gas,
@@ -95,6 +123,9 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 TxContextFieldTag::IsCreate,
                 TxContextFieldTag::CallDataLength,
                 TxContextFieldTag::CallDataGasCost,
+                TxContextFieldTag::AccessListAddressesLen,
+                TxContextFieldTag::AccessListStorageKeysLen,
+                TxContextFieldTag::BlobVersionedHashesLen,
             ]
             .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
         let tx_caller_address_is_zero = IsZeroGadget::construct(cb, tx_caller_address.expr());
@@ -103,7 +134,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_caller_address_is_zero.expr(),
             false.expr(),
         );
-        let [tx_gas_price, tx_value] = [TxContextFieldTag::GasPrice, TxContextFieldTag::Value]
+        let [tx_gas_price, tx_max_fee_per_gas, tx_max_priority_fee_per_gas, tx_max_fee_per_blob_gas, tx_value] =
+            [
+                TxContextFieldTag::GasPrice,
+                TxContextFieldTag::MaxFeePerGas,
+                TxContextFieldTag::MaxPriorityFeePerGas,
+                TxContextFieldTag::MaxFeePerBlobGas,
+                TxContextFieldTag::Value,
+            ]
             .map(|field_tag| cb.tx_context_as_word(tx_id.expr(), field_tag, None));
 
         let call_callee_address = cb.query_cell();
@@ -130,30 +168,95 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
-        // TODO: Implement EIP 1559 (currently it only supports legacy
-        // transaction format)
+        // Calculate the effective gas price for EIP-1559, which is
+        // min(max_fee_per_gas, base_fee + max_priority_fee_per_gas).  The fee
+        // caps of the other transaction types are their gas price.
+        let base_fee = cb.query_word_rlc();
+        cb.block_lookup(BlockContextFieldTag::BaseFee.expr(), None, base_fee.expr());
+        let add_base_fee_by_max_priority_fee = AddWordsGadget::construct(
+            cb,
+            [base_fee.clone(), tx_max_priority_fee_per_gas.clone()],
+            cb.query_word_rlc(),
+        );
+        let is_max_fee_lt_base_fee_plus_max_priority_fee = LtWordGadget::construct(
+            cb,
+            &tx_max_fee_per_gas,
+            add_base_fee_by_max_priority_fee.sum(),
+        );
+        cb.require_equal(
+            "gas_price == min(max_fee_per_gas, base_fee + max_priority_fee_per_gas)",
+            tx_gas_price.expr(),
+            select::expr(
+                is_max_fee_lt_base_fee_plus_max_priority_fee.expr(),
+                tx_max_fee_per_gas.expr(),
+                add_base_fee_by_max_priority_fee.sum().expr(),
+            ),
+        );
+        let is_max_fee_lt_base_fee = LtWordGadget::construct(cb, &tx_max_fee_per_gas, &base_fee);
+        cb.require_zero("max_fee_per_gas >= base_fee", is_max_fee_lt_base_fee.expr());
+        let is_max_fee_lt_max_priority_fee =
+            LtWordGadget::construct(cb, &tx_max_fee_per_gas, &tx_max_priority_fee_per_gas);
+        cb.require_zero(
+            "max_fee_per_gas >= max_priority_fee_per_gas",
+            is_max_fee_lt_max_priority_fee.expr(),
+        );
+
         // Calculate transaction gas fee
         let mul_gas_fee_by_gas =
             MulWordByU64Gadget::construct(cb, tx_gas_price.clone(), tx_gas.expr());
 
+        // Calculate the blob fee for EIP-4844, which is charged for the blob
+        // gas of the tx at the blob base fee of the block, and which is added
+        // to the gas fee.  Txs without blobs have no blob gas.
+        let blob_base_fee = cb.query_word_rlc();
+        cb.block_lookup(
+            BlockContextFieldTag::BlobBaseFee.expr(),
+            None,
+            blob_base_fee.expr(),
+        );
+        let is_max_fee_per_blob_gas_lt_blob_base_fee =
+            LtWordGadget::construct(cb, &tx_max_fee_per_blob_gas, &blob_base_fee);
+        cb.require_zero(
+            "max_fee_per_blob_gas >= blob_base_fee for txs with blobs",
+            tx_blob_versioned_hashes_len.expr() * is_max_fee_per_blob_gas_lt_blob_base_fee.expr(),
+        );
+        let mul_blob_fee_by_blob_gas = MulWordByU64Gadget::construct(
+            cb,
+            blob_base_fee.clone(),
+            tx_blob_versioned_hashes_len.expr() * GasCost::PER_BLOB.expr(),
+        );
+        let add_gas_fee_by_blob_fee = AddWordsGadget::construct(
+            cb,
+            [
+                mul_gas_fee_by_gas.product().clone(),
+                mul_blob_fee_by_blob_gas.product().clone(),
+            ],
+            cb.query_word_rlc(),
+        );
+
         let tx_call_data_word_length =
             ConstantDivisionGadget::construct(cb, tx_call_data_length.expr() + 31.expr(), 32);
 
         // Calculate gas cost of init code for EIP-3860.
+        let init_code_word_gas = cb.hardfork_param(HardforkParam::InitCodeWordGas);
         let init_code_gas_cost = select::expr(
             tx_is_create.expr(),
-            tx_call_data_word_length.quotient().expr()
-                * eth_types::evm_types::INIT_CODE_WORD_GAS.expr(),
+            tx_call_data_word_length.quotient().expr() * init_code_word_gas.expr(),
             0.expr(),
         );
 
-        // TODO: Take gas cost of access list (EIP 2930) into consideration.
+        // Calculate gas cost of access list for EIP-2930.
+        let access_list_gas_cost = tx_access_list_addresses_len.expr()
+            * GasCost::ACCESS_LIST_PER_ADDRESS.expr()
+            + tx_access_list_storage_keys_len.expr() * GasCost::ACCESS_LIST_PER_STORAGE_KEY.expr();
+
         // Use intrinsic gas
         let intrinsic_gas_cost = select::expr(
             tx_is_create.expr(),
             GasCost::CREATION_TX.expr(),
             GasCost::TX.expr(),
         ) + tx_call_data_gas_cost.expr()
+            + access_list_gas_cost
             + init_code_gas_cost;
 
         // Check gas_left is sufficient
@@ -179,17 +282,74 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         ); // rwc_delta += 1
 
-        // Query coinbase address.
+        // Add the addresses and then the storage keys of the tx access list
+        // (EIP-2930) to access list, which are copied from their RLCs in the
+        // tx table.
+        let tx_access_list_addresses_rlc = cb.query_cell_phase2();
+        let tx_access_list_storage_keys_rlc = cb.query_cell_phase2();
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::AccessListAddressesRLC,
+            None,
+            tx_access_list_addresses_rlc.expr(),
+        );
+        cb.tx_context_lookup(
+            tx_id.expr(),
+            TxContextFieldTag::AccessListStorageKeysRLC,
+            None,
+            tx_access_list_storage_keys_rlc.expr(),
+        );
+        let is_access_list_addresses_empty =
+            IsZeroGadget::construct(cb, tx_access_list_addresses_len.expr());
+        let is_access_list_storage_keys_empty =
+            IsZeroGadget::construct(cb, tx_access_list_storage_keys_len.expr());
+        for (dst_tag, len, rlc, is_empty) in [
+            (
+                CopyDataType::AccessListAddresses,
+                &tx_access_list_addresses_len,
+                &tx_access_list_addresses_rlc,
+                &is_access_list_addresses_empty,
+            ),
+            (
+                CopyDataType::AccessListStorageKeys,
+                &tx_access_list_storage_keys_len,
+                &tx_access_list_storage_keys_rlc,
+                &is_access_list_storage_keys_empty,
+            ),
+        ] {
+            cb.condition(not::expr(is_empty.expr()), |cb| {
+                cb.copy_table_lookup(
+                    tx_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    tx_id.expr(),
+                    dst_tag.expr(),
+                    0.expr(),
+                    len.expr(),
+                    0.expr(),
+                    len.expr(),
+                    rlc.expr(),
+                    len.expr(),
+                ); // rwc_delta += a
+            });
+        }
+        let tx_access_list_len =
+            tx_access_list_addresses_len.expr() + tx_access_list_storage_keys_len.expr();
+
+        // Query coinbase address, which is added to the access list when the
+        // hardfork warms it.
+        let warm_coinbase = cb.hardfork_param(HardforkParam::WarmCoinbase);
         let coinbase = cb.query_cell();
         let is_coinbase_warm = cb.query_bool();
         cb.block_lookup(BlockContextFieldTag::Coinbase.expr(), None, coinbase.expr());
-        cb.account_access_list_write(
-            tx_id.expr(),
-            coinbase.expr(),
-            1.expr(),
-            is_coinbase_warm.expr(),
-            None,
-        ); // rwc_delta += 1
+        cb.condition(warm_coinbase.expr(), |cb| {
+            cb.account_access_list_write(
+                tx_id.expr(),
+                coinbase.expr(),
+                1.expr(),
+                is_coinbase_warm.expr(),
+                None,
+            ); // rwc_delta += c
+        });
 
         // Read code_hash of callee
         let phase2_code_hash = cb.query_cell_phase2();
@@ -198,10 +358,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         let callee_not_exists = IsZeroGadget::construct(cb, phase2_code_hash.expr());
         // no_callee_code is true when the account exists and has empty
         // code hash, or when the account doesn't exist (which we encode with
-        // code_hash = 0).
-        let no_callee_code = is_empty_code_hash.expr() + callee_not_exists.expr();
+        // code_hash = 0).  Precompiled contracts have no code, so txs to them
+        // are taken out of this case.
+        let is_precompile = PrecompileAddressGadget::construct(cb, tx_callee_address.expr());
+        let no_callee_code =
+            is_empty_code_hash.expr() + callee_not_exists.expr() - is_precompile.expr();
 
-        // TODO: And not precompile
         cb.condition(not::expr(tx_is_create.expr()), |cb| {
             cb.account_read(
                 tx_callee_address.expr(),
@@ -218,7 +380,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             not::expr(callee_not_exists.expr()),
             or::expr([tx_is_create.expr(), callee_not_exists.expr()]),
             tx_value.clone(),
-            mul_gas_fee_by_gas.product().clone(),
+            add_gas_fee_by_blob_fee.sum().clone(),
             &mut reversion_info,
         );
 
@@ -261,6 +423,14 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 0.expr(),
                 Some(&mut reversion_info),
             );
+            cb.tx_account_flag_write(
+                tx_id.expr(),
+                call_callee_address.expr(),
+                TxAccountFlagFieldTag::Created,
+                1.expr(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
             for (field_tag, value) in [
                 (CallContextFieldTag::Depth, 1.expr()),
                 (CallContextFieldTag::CallerAddress, tx_caller_address.expr()),
@@ -289,7 +459,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             }
 
             cb.require_step_state_transition(StepStateTransition {
-                // 21 + a reads and writes:
+                // 22 + a + b + c reads and writes:
                 //   - Write CallContext TxId
                 //   - Write CallContext RwCounterEndOfReversion
                 //   - Write CallContext IsPersistent
@@ -297,9 +467,11 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write Account (Caller) Nonce
                 //   - Write TxAccessListAccount (Caller)
                 //   - Write TxAccessListAccount (Callee)
-                //   - Write TxAccessListAccount (Coinbase) for EIP-3651
-                //   - a TransferWithGasFeeGadget
+                //   - a Write TxAccessListAccount(Storage) for EIP-2930
+                //   - c Write TxAccessListAccount (Coinbase) for EIP-3651
+                //   - b TransferWithGasFeeGadget
                 //   - Write Account (Callee) Nonce (Reversible)
+                //   - Write TxAccountFlag (Callee) Created (Reversible)
                 //   - Write CallContext Depth
                 //   - Write CallContext CallerAddress
                 //   - Write CallContext CalleeAddress
@@ -313,24 +485,28 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 //   - Write CallContext IsRoot
                 //   - Write CallContext IsCreate
                 //   - Write CallContext CodeHash
-                rw_counter: Delta(22.expr() + transfer_with_gas_fee.rw_delta()),
+                rw_counter: Delta(
+                    22.expr()
+                        + tx_access_list_len.clone()
+                        + transfer_with_gas_fee.rw_delta()
+                        + warm_coinbase.expr(),
+                ),
                 call_id: To(call_id.expr()),
                 is_root: To(true.expr()),
                 is_create: To(tx_is_create.expr()),
                 code_hash: To(cb.curr.state.code_hash.expr()),
                 gas_left: To(gas_left.clone()),
-                // There are a + 1 reversible writes:
+                // There are a + 2 reversible writes:
                 //  - a TransferWithGasFeeGadget
                 //  - Callee Account Nonce
-                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 1.expr()),
+                //  - Callee TxAccountFlag Created
+                reversible_write_counter: To(transfer_with_gas_fee.reversible_w_delta() + 2.expr()),
                 log_id: To(0.expr()),
                 ..StepStateTransition::new_context()
             });
         });
 
-        // TODO: 2. Handle call to precompiled contracts.
-
-        // 3. Call to account with empty code.
+        // 2. Call to account with empty code.
         cb.condition(
             and::expr([not::expr(tx_is_create.expr()), no_callee_code.clone()]),
             |cb| {
@@ -346,7 +522,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 );
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 8 reads and writes:
+                    // 8 + a + b + c reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -354,17 +530,24 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - a Write TxAccessListAccount(Storage) for EIP-2930
+                    //   - c Write TxAccessListAccount (Coinbase) for EIP-3651
                     //   - Read Account CodeHash
-                    //   - a TransferWithGasFeeGadget
-                    rw_counter: Delta(9.expr() + transfer_with_gas_fee.rw_delta()),
+                    //   - b TransferWithGasFeeGadget
+                    rw_counter: Delta(
+                        8.expr()
+                            + tx_access_list_len.clone()
+                            + transfer_with_gas_fee.rw_delta()
+                            + warm_coinbase.expr(),
+                    ),
                     call_id: To(call_id.expr()),
                     ..StepStateTransition::any()
                 });
             },
         );
 
-        // 4. Call to account with non-empty code.
+        // 3. Call to precompiled contract, which is executed in the next step,
+        // or call to account with non-empty code.
         cb.condition(
             and::expr([not::expr(tx_is_create.expr()), not::expr(no_callee_code)]),
             |cb| {
@@ -391,7 +574,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                 }
 
                 cb.require_step_state_transition(StepStateTransition {
-                    // 21 reads and writes:
+                    // 21 + a + b + c reads and writes:
                     //   - Write CallContext TxId
                     //   - Write CallContext RwCounterEndOfReversion
                     //   - Write CallContext IsPersistent
@@ -399,9 +582,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write Account Nonce
                     //   - Write TxAccessListAccount (Caller)
                     //   - Write TxAccessListAccount (Callee)
-                    //   - Write TxAccessListAccount (Coinbase) for EIP-3651
+                    //   - a Write TxAccessListAccount(Storage) for EIP-2930
+                    //   - c Write TxAccessListAccount (Coinbase) for EIP-3651
                     //   - Read Account CodeHash
-                    //   - a TransferWithGasFeeGadget
+                    //   - b TransferWithGasFeeGadget
                     //   - Write CallContext Depth
                     //   - Write CallContext CallerAddress
                     //   - Write CallContext CalleeAddress
@@ -415,7 +599,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     //   - Write CallContext IsRoot
                     //   - Write CallContext IsCreate
                     //   - Write CallContext CodeHash
-                    rw_counter: Delta(22.expr() + transfer_with_gas_fee.rw_delta()),
+                    rw_counter: Delta(
+                        21.expr()
+                            + tx_access_list_len
+                            + transfer_with_gas_fee.rw_delta()
+                            + warm_coinbase.expr(),
+                    ),
                     call_id: To(call_id.expr()),
                     is_root: To(true.expr()),
                     is_create: To(tx_is_create.expr()),
@@ -425,6 +614,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
                     log_id: To(0.expr()),
                     ..StepStateTransition::new_context()
                 });
+
+                cb.condition(is_precompile.expr(), |cb| {
+                    cb.require_next_precompile_state(tx_callee_address.expr());
+                });
             },
         );
 
@@ -433,7 +626,20 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_nonce,
             tx_gas,
             tx_gas_price,
+            tx_max_fee_per_gas,
+            tx_max_priority_fee_per_gas,
+            base_fee,
+            add_base_fee_by_max_priority_fee,
+            is_max_fee_lt_base_fee_plus_max_priority_fee,
+            is_max_fee_lt_base_fee,
+            is_max_fee_lt_max_priority_fee,
             mul_gas_fee_by_gas,
+            tx_max_fee_per_blob_gas,
+            tx_blob_versioned_hashes_len,
+            blob_base_fee,
+            is_max_fee_per_blob_gas_lt_blob_base_fee,
+            mul_blob_fee_by_blob_gas,
+            add_gas_fee_by_blob_fee,
             tx_caller_address,
             tx_caller_address_is_zero,
             tx_callee_address,
@@ -442,6 +648,12 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             tx_value,
             tx_call_data_length,
             tx_call_data_gas_cost,
+            tx_access_list_addresses_len,
+            tx_access_list_storage_keys_len,
+            tx_access_list_addresses_rlc,
+            tx_access_list_storage_keys_rlc,
+            is_access_list_addresses_empty,
+            is_access_list_storage_keys_empty,
             tx_call_data_word_length,
             reversion_info,
             sufficient_gas_left,
@@ -451,7 +663,10 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             caller_nonce_hash_bytes,
             create,
             callee_not_exists,
+            is_precompile,
             is_caller_callee_equal,
+            init_code_word_gas,
+            warm_coinbase,
             coinbase,
             is_coinbase_warm,
         }
@@ -467,18 +682,23 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         step: &ExecStep,
     ) -> Result<(), Error> {
         let gas_fee = tx.gas_price * tx.gas;
+        let blob_gas = tx.blob_versioned_hashes.len() as u64 * GasCost::PER_BLOB;
+        let blob_fee = block.context.blob_base_fee * blob_gas;
         let zero = eth_types::Word::zero();
 
         let mut rws = StepRws::new(block, step);
-        rws.offset_add(7);
+        rws.offset_add(
+            7 + (tx.access_list_addresses_len + tx.access_list_storage_keys_len) as usize,
+        );
 
-        let is_coinbase_warm = rws.next().tx_access_list_value_pair().1;
+        let hardfork = block.circuits_params.hardfork;
+        let is_coinbase_warm =
+            hardfork.is_coinbase_warm() && rws.next().tx_access_list_value_pair().1;
         let mut callee_code_hash = zero;
-        if !is_precompiled(&tx.callee_address) && !tx.is_create {
+        if !tx.is_create {
             callee_code_hash = rws.next().account_value_pair().1;
         }
-        let callee_exists =
-            is_precompiled(&tx.callee_address) || (!tx.is_create && !callee_code_hash.is_zero());
+        let callee_exists = !tx.is_create && !callee_code_hash.is_zero();
         let caller_balance_sub_fee_pair = rws.next().account_value_pair();
         let must_create = tx.is_create;
         if (!callee_exists && !tx.value.is_zero()) || must_create {
@@ -499,8 +719,66 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             .assign(region, offset, Value::known(F::from(tx.gas)))?;
         self.tx_gas_price
             .assign(region, offset, Some(tx.gas_price.to_le_bytes()))?;
+        self.tx_max_fee_per_gas
+            .assign(region, offset, Some(tx.max_fee_per_gas.to_le_bytes()))?;
+        self.tx_max_priority_fee_per_gas.assign(
+            region,
+            offset,
+            Some(tx.max_priority_fee_per_gas.to_le_bytes()),
+        )?;
+        let base_fee = block.context.base_fee;
+        self.base_fee
+            .assign(region, offset, Some(base_fee.to_le_bytes()))?;
+        let base_fee_plus_max_priority_fee = base_fee + tx.max_priority_fee_per_gas;
+        self.add_base_fee_by_max_priority_fee.assign(
+            region,
+            offset,
+            [base_fee, tx.max_priority_fee_per_gas],
+            base_fee_plus_max_priority_fee,
+        )?;
+        self.is_max_fee_lt_base_fee_plus_max_priority_fee.assign(
+            region,
+            offset,
+            tx.max_fee_per_gas,
+            base_fee_plus_max_priority_fee,
+        )?;
+        self.is_max_fee_lt_base_fee
+            .assign(region, offset, tx.max_fee_per_gas, base_fee)?;
+        self.is_max_fee_lt_max_priority_fee.assign(
+            region,
+            offset,
+            tx.max_fee_per_gas,
+            tx.max_priority_fee_per_gas,
+        )?;
         self.mul_gas_fee_by_gas
             .assign(region, offset, tx.gas_price, tx.gas, gas_fee)?;
+        self.tx_max_fee_per_blob_gas.assign(
+            region,
+            offset,
+            Some(tx.max_fee_per_blob_gas.to_le_bytes()),
+        )?;
+        self.tx_blob_versioned_hashes_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+        )?;
+        let blob_base_fee = block.context.blob_base_fee;
+        self.blob_base_fee
+            .assign(region, offset, Some(blob_base_fee.to_le_bytes()))?;
+        self.is_max_fee_per_blob_gas_lt_blob_base_fee.assign(
+            region,
+            offset,
+            tx.max_fee_per_blob_gas,
+            blob_base_fee,
+        )?;
+        self.mul_blob_fee_by_blob_gas
+            .assign(region, offset, blob_base_fee, blob_gas, blob_fee)?;
+        self.add_gas_fee_by_blob_fee.assign(
+            region,
+            offset,
+            [gas_fee, blob_fee],
+            gas_fee + blob_fee,
+        )?;
         let caller_address = tx
             .caller_address
             .to_scalar()
@@ -545,6 +823,49 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             offset,
             Value::known(F::from(tx.call_data_gas_cost)),
         )?;
+        self.tx_access_list_addresses_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_addresses_len)),
+        )?;
+        self.tx_access_list_storage_keys_len.assign(
+            region,
+            offset,
+            Value::known(F::from(tx.access_list_storage_keys_len)),
+        )?;
+        self.tx_access_list_addresses_rlc.assign(
+            region,
+            offset,
+            region
+                .challenges()
+                .keccak_input()
+                .map(|challenge| access_list_addresses_rlc(&tx.access_list_addresses, challenge)),
+        )?;
+        self.tx_access_list_storage_keys_rlc.assign(
+            region,
+            offset,
+            region
+                .challenges()
+                .keccak_input()
+                .zip(region.challenges().evm_word())
+                .map(|(keccak_input, evm_word)| {
+                    access_list_storage_keys_rlc(
+                        &tx.access_list_storage_keys,
+                        keccak_input,
+                        evm_word,
+                    )
+                }),
+        )?;
+        self.is_access_list_addresses_empty.assign(
+            region,
+            offset,
+            F::from(tx.access_list_addresses_len),
+        )?;
+        self.is_access_list_storage_keys_empty.assign(
+            region,
+            offset,
+            F::from(tx.access_list_storage_keys_len),
+        )?;
         self.tx_call_data_word_length
             .assign(region, offset, tx.call_data_length as u128 + 31)?;
         self.reversion_info.assign(
@@ -562,7 +883,7 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             caller_balance_sub_value_pair,
             callee_balance_pair,
             tx.value,
-            gas_fee,
+            gas_fee + blob_fee,
         )?;
         self.phase2_code_hash
             .assign(region, offset, region.word_rlc(callee_code_hash))?;
@@ -574,6 +895,8 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
         )?;
         self.callee_not_exists
             .assign_value(region, offset, region.word_rlc(callee_code_hash))?;
+        self.is_precompile
+            .assign(region, offset, tx.callee_address)?;
 
         let untrimmed_contract_addr = {
             let mut stream = ethers_core::utils::rlp::RlpStream::new();
@@ -600,6 +923,16 @@ impl<F: Field> ExecutionGadget<F> for BeginTxGadget<F> {
             None,
         )?;
 
+        self.init_code_word_gas.assign(
+            region,
+            offset,
+            Value::known(F::from(hardfork.init_code_word_gas())),
+        )?;
+        self.warm_coinbase.assign(
+            region,
+            offset,
+            Value::known(F::from(hardfork.is_coinbase_warm() as u64)),
+        )?;
         self.coinbase.assign(
             region,
             offset,
@@ -626,10 +959,16 @@ mod test {
     use std::vec;
 
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use bus_mapping::evm::OpcodeId;
-    use eth_types::{self, bytecode, evm_types::GasCost, word, Bytecode, Word};
+    use bus_mapping::{circuit_input_builder::CircuitsParams, evm::OpcodeId};
+    use eth_types::{
+        self, bytecode,
+        evm_types::{GasCost, Hardfork},
+        word, AccessList, AccessListItem, Bytecode, ToWord, Word, H256,
+    };
 
-    use mock::{eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS};
+    use mock::{
+        eth, gwei, MockTransaction, TestContext, MOCK_ACCOUNTS, MOCK_BLOB_VERSIONED_HASHES,
+    };
 
     fn gas(call_data: &[u8]) -> Word {
         Word::from(
@@ -774,6 +1113,127 @@ mod test {
         }
     }
 
+    #[test]
+    fn begin_tx_access_list() {
+        // Access the pre-warmed storage slot and account, with duplicated
+        // entries and the callee in the access list.
+        let code = bytecode! {
+            PUSH1(0)
+            SLOAD
+            POP
+            PUSH20(MOCK_ACCOUNTS[2].to_word())
+            BALANCE
+            POP
+            STOP
+        };
+        let access_list = AccessList(vec![
+            AccessListItem {
+                address: MOCK_ACCOUNTS[0],
+                storage_keys: vec![H256::zero()],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(1)],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![],
+            },
+        ]);
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_ACCOUNTS[0])
+                    .balance(eth(10))
+                    .code(code);
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .gas_price(gwei(2))
+                    .gas(Word::from(0x10000))
+                    .transaction_type(1)
+                    .access_list(access_list);
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn begin_tx_eip1559() {
+        // The effective gas price is capped by max_fee_per_gas in the first
+        // case and by base_fee + max_priority_fee_per_gas in the second.
+        for (max_fee_per_gas, max_priority_fee_per_gas) in [(gwei(3), gwei(2)), (gwei(5), gwei(1))]
+        {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                },
+                |mut txs, _accs| {
+                    txs[0]
+                        .from(MOCK_ACCOUNTS[1])
+                        .to(MOCK_ACCOUNTS[0])
+                        .transaction_type(2)
+                        .max_fee_per_gas(max_fee_per_gas)
+                        .max_priority_fee_per_gas(max_priority_fee_per_gas)
+                        .gas(Word::from(0x10000));
+                },
+                |block, _tx| block.number(0xcafeu64).base_fee_per_gas(gwei(2)),
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        }
+    }
+
+    #[test]
+    fn begin_tx_blob_tx() {
+        // The blob fee is charged at the blob base fee of the block, which is
+        // more than the minimum one with this excess blob gas.
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0].address(MOCK_ACCOUNTS[0]).balance(eth(10));
+                accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+            },
+            |mut txs, _accs| {
+                txs[0]
+                    .from(MOCK_ACCOUNTS[1])
+                    .to(MOCK_ACCOUNTS[0])
+                    .transaction_type(3)
+                    .max_fee_per_gas(gwei(3))
+                    .max_priority_fee_per_gas(gwei(1))
+                    .max_fee_per_blob_gas(gwei(1))
+                    .blob_versioned_hashes(MOCK_BLOB_VERSIONED_HASHES.clone())
+                    .gas(Word::from(0x10000));
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .base_fee_per_gas(gwei(2))
+                    .hardfork(Hardfork::Cancun)
+                    .excess_blob_gas(0x1000000)
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
     #[test]
     fn begin_tx_no_code() {
         let ctx = TestContext::<2, 1>::new(
@@ -820,6 +1280,10 @@ mod test {
     }
 
     fn begin_tx_deploy(nonce: u64) {
+        begin_tx_deploy_with_hardfork(nonce, Hardfork::default());
+    }
+
+    fn begin_tx_deploy_with_hardfork(nonce: u64, hardfork: Hardfork) {
         let code = bytecode! {
             // [ADDRESS, STOP]
             PUSH32(word!("3000000000000000000000000000000000000000000000000000000000000000"))
@@ -847,17 +1311,28 @@ mod test {
                     .value(eth(2))
                     .input(code.into());
             },
-            |block, _tx| block.number(0xcafeu64),
+            |block, _tx| block.number(0xcafeu64).hardfork(hardfork),
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork,
+                ..Default::default()
+            })
+            .run();
     }
 
     #[test]
     fn begin_tx_deploy_nonce_zero() {
         begin_tx_deploy(0);
     }
+
+    // The coinbase is warm and the init code words are charged from Shanghai on
+    #[test]
+    fn begin_tx_deploy_shanghai() {
+        begin_tx_deploy_with_hardfork(0, Hardfork::Shanghai);
+    }
     #[test]
     fn begin_tx_deploy_nonce_small_1byte() {
         begin_tx_deploy(1);
@@ -0,0 +1,159 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        util::{
+            common_gadget::{SameContextGadget, WordByteCapGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::Delta,
+            },
+            CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{CallContextFieldTag, TxContextFieldTag},
+    util::Expr,
+};
+use bus_mapping::evm::OpcodeId;
+use eth_types::{evm_types::MAX_BLOBS_PER_TX, Field, ToLittleEndian};
+use gadgets::util::not;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+#[derive(Clone, Debug)]
+pub(crate) struct BlobHashGadget<F> {
+    same_context: SameContextGadget<F>,
+    tx_id: Cell<F>,
+    index: WordByteCapGadget<F, N_BYTES_U64>,
+    blob_versioned_hash: Word<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlobHashGadget<F> {
+    const NAME: &'static str = "BLOBHASH";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BLOBHASH;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        let index = WordByteCapGadget::construct(cb, MAX_BLOBS_PER_TX.expr());
+        cb.stack_pop(index.original_word());
+
+        let blob_versioned_hash = cb.query_word_rlc();
+
+        // The tx table has a BlobVersionedHash row for every possible blob,
+        // which is zero past the blobs of the tx.
+        cb.condition(index.lt_cap(), |cb| {
+            cb.tx_context_lookup(
+                tx_id.expr(),
+                TxContextFieldTag::BlobVersionedHash,
+                Some(index.valid_value()),
+                blob_versioned_hash.expr(),
+            );
+        });
+        cb.condition(not::expr(index.lt_cap()), |cb| {
+            cb.require_zero(
+                "Index out of the blobs of the tx",
+                blob_versioned_hash.expr(),
+            );
+        });
+
+        cb.stack_push(blob_versioned_hash.expr());
+
+        let step_state_transition = StepStateTransition {
+            rw_counter: Delta(3.expr()),
+            program_counter: Delta(1.expr()),
+            gas_left: Delta(-OpcodeId::BLOBHASH.constant_gas_cost().expr()),
+            ..Default::default()
+        };
+
+        let opcode = cb.query_cell();
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+        Self {
+            same_context,
+            tx_id,
+            index,
+            blob_versioned_hash,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+
+        let index = block.get_rws(step, 1).stack_value();
+        self.index
+            .assign(region, offset, index, F::from(MAX_BLOBS_PER_TX as u64))?;
+
+        self.blob_versioned_hash.assign(
+            region,
+            offset,
+            Some(block.get_rws(step, 2).stack_value().to_le_bytes()),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, evm_types::Hardfork, U256};
+    use mock::test_ctx::{helpers::*, TestContext};
+
+    fn test_ok(index: U256) {
+        let code = bytecode! {
+            PUSH32(index)
+            BLOBHASH
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            blob_tx_from_1_to_0,
+            |block, _tx| block.hardfork(Hardfork::Cancun),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn blobhash_gadget_first_blob() {
+        test_ok(0.into());
+    }
+
+    #[test]
+    fn blobhash_gadget_last_blob() {
+        test_ok(1.into());
+    }
+
+    #[test]
+    fn blobhash_gadget_no_blob() {
+        test_ok(5.into());
+    }
+
+    #[test]
+    fn blobhash_gadget_out_of_cap() {
+        test_ok(6.into());
+        test_ok(U256::MAX);
+    }
+}
@@ -3,10 +3,11 @@ use crate::{
         execution::ExecutionGadget,
         param::{N_BYTES_ACCOUNT_ADDRESS, N_BYTES_U64, N_BYTES_WORD},
         step::ExecutionState,
+        table::HardforkParam,
         util::{
             common_gadget::SameContextGadget,
             constraint_builder::{EVMConstraintBuilder, StepStateTransition, Transition::Delta},
-            from_bytes, CachedRegion, RandomLinearCombination,
+            from_bytes, CachedRegion, Cell, RandomLinearCombination,
         },
         witness::{Block, Call, ExecStep, Transaction},
     },
@@ -15,7 +16,10 @@ use crate::{
 };
 use bus_mapping::evm::OpcodeId;
 use eth_types::{Field, ToLittleEndian};
-use halo2_proofs::plonk::Error;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
 
 #[derive(Clone, Debug)]
 pub(crate) struct BlockCtxGadget<F, const N_BYTES: usize> {
@@ -23,8 +27,17 @@ pub(crate) struct BlockCtxGadget<F, const N_BYTES: usize> {
     value: RandomLinearCombination<F, N_BYTES>,
 }
 
+/// Returns the tag of the block table field of the opcode, as the fields
+/// follow the order of the opcodes.
+fn opcode_tag<F: Field>(opcode: Expression<F>) -> Expression<F> {
+    BlockContextFieldTag::Coinbase.expr() + (opcode - OpcodeId::COINBASE.as_u64().expr())
+}
+
 impl<F: Field, const N_BYTES: usize> BlockCtxGadget<F, N_BYTES> {
-    fn construct(cb: &mut EVMConstraintBuilder<F>) -> Self {
+    fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        tag: impl FnOnce(Expression<F>) -> Expression<F>,
+    ) -> Self {
         let value = cb.query_word_rlc();
 
         // Push the const generic parameter N_BYTES value to the stack
@@ -32,11 +45,10 @@ impl<F: Field, const N_BYTES: usize> BlockCtxGadget<F, N_BYTES> {
 
         // Get op's FieldTag
         let opcode = cb.query_cell();
-        let blockctx_tag = BlockContextFieldTag::Coinbase.expr()
-            + (opcode.expr() - OpcodeId::COINBASE.as_u64().expr());
+        let blockctx_tag = tag(opcode.expr());
 
         // Lookup block table with block context ops
-        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/BASEFEE
+        // TIMESTAMP/NUMBER/GASLIMIT, COINBASE and DIFFICULTY/PREVRANDAO/BASEFEE
         let value_expr = if N_BYTES == N_BYTES_WORD {
             value.expr()
         } else {
@@ -72,7 +84,7 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU64Gadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKCTXU64;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let value_u64 = BlockCtxGadget::construct(cb);
+        let value_u64 = BlockCtxGadget::construct(cb, opcode_tag);
 
         Self { value_u64 }
     }
@@ -113,7 +125,7 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU160Gadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKCTXU160;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let value_u160 = BlockCtxGadget::construct(cb);
+        let value_u160 = BlockCtxGadget::construct(cb, opcode_tag);
 
         Self { value_u160 }
     }
@@ -150,6 +162,7 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU160Gadget<F> {
 #[derive(Clone, Debug)]
 pub(crate) struct BlockCtxU256Gadget<F> {
     value_u256: BlockCtxGadget<F, N_BYTES_WORD>,
+    is_prevrandao: Cell<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
@@ -158,9 +171,29 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
     const EXECUTION_STATE: ExecutionState = ExecutionState::BLOCKCTXU256;
 
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
-        let value_u256 = BlockCtxGadget::construct(cb);
+        // From the merge on, DIFFICULTY returns the prevRandao of the block
+        // (EIP-4399) instead of its difficulty.
+        let is_prevrandao = cb.hardfork_param(HardforkParam::PrevRandao);
+        let value_u256 = BlockCtxGadget::construct(cb, |opcode| {
+            // DIFFICULTY and BASEFEE are the only opcodes of this gadget, so
+            // is_difficulty is 1 for DIFFICULTY and 0 for BASEFEE.
+            let is_difficulty = (OpcodeId::BASEFEE.as_u64().expr() - opcode.clone())
+                * Expression::Constant(
+                    F::from(OpcodeId::BASEFEE.as_u64() - OpcodeId::DIFFICULTY.as_u64())
+                        .invert()
+                        .unwrap(),
+                );
+            opcode_tag(opcode)
+                + is_difficulty
+                    * is_prevrandao.expr()
+                    * (BlockContextFieldTag::PrevRandao.expr()
+                        - BlockContextFieldTag::Difficulty.expr())
+        });
 
-        Self { value_u256 }
+        Self {
+            value_u256,
+            is_prevrandao,
+        }
     }
 
     fn assign_exec_step(
@@ -181,6 +214,13 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
         self.value_u256
             .value
             .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.is_prevrandao.assign(
+            region,
+            offset,
+            Value::known(F::from(
+                block.circuits_params.hardfork.has_prevrandao() as u64
+            )),
+        )?;
 
         Ok(())
     }
@@ -189,8 +229,9 @@ impl<F: Field> ExecutionGadget<F> for BlockCtxU256Gadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
-    use eth_types::bytecode;
-    use mock::TestContext;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, evm_types::Hardfork};
+    use mock::test_ctx::{helpers::*, TestContext};
 
     fn test_ok(bytecode: bytecode::Bytecode) {
         CircuitTestBuilder::new_from_test_ctx(
@@ -230,4 +271,28 @@ mod test {
         };
         test_ok(bytecode);
     }
+
+    #[test]
+    fn blockcxt_u256_gadget_after_merge_test() {
+        let bytecode = bytecode! {
+            DIFFICULTY
+            POP
+            BASEFEE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(Hardfork::Paris),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Paris,
+                ..Default::default()
+            })
+            .run();
+    }
 }
@@ -0,0 +1,396 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        table::HardforkParam,
+        util::{
+            common_gadget::UpdateBalanceGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            from_bytes,
+            math_gadget::IsZeroGadget,
+            not, sum, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::BlockContextFieldTag,
+    util::Expr,
+};
+use eth_types::{
+    geth_types::{BlockHeader, MAX_UNCLES},
+    Field, ToLittleEndian, ToScalar, U256,
+};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for the virtual step which follows the withdrawals of the block, and
+/// credits the block reward plus the nephew reward for each uncle to the
+/// coinbase, and the uncle reward to the miner of each uncle.  There are no
+/// rewards from Paris on, nor for the networks without rewards, in which case
+/// the step does no rw.
+#[derive(Clone, Debug)]
+pub(crate) struct BlockRewardGadget<F> {
+    block_reward: Cell<F>,
+    block_reward_is_zero: IsZeroGadget<F>,
+    nephew_reward: Cell<F>,
+    number: Cell<F>,
+    coinbase: Cell<F>,
+    coinbase_reward: Word<F>,
+    coinbase_balance: UpdateBalanceGadget<F, 2, true>,
+    uncles: [UncleRewardGadget<F>; MAX_UNCLES],
+}
+
+/// Gadget for the reward of the miner of the uncle at a row of the uncle
+/// table, where a padding row has a zero number.
+#[derive(Clone, Debug)]
+struct UncleRewardGadget<F> {
+    number: Cell<F>,
+    miner: Cell<F>,
+    is_padding: IsZeroGadget<F>,
+    reward: Word<F>,
+    miner_balance: UpdateBalanceGadget<F, 2, true>,
+}
+
+impl<F: Field> ExecutionGadget<F> for BlockRewardGadget<F> {
+    const NAME: &'static str = "BlockReward";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::BlockReward;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // A block without txs nor withdrawals starts with its block reward.
+        cb.step_first(|cb| {
+            cb.require_zero("log_id is initialized to be 0", cb.curr.state.log_id.expr());
+            cb.require_zero(
+                "call_id is initialized to be 0",
+                cb.curr.state.call_id.expr(),
+            );
+        });
+
+        let block_reward = cb.hardfork_param(HardforkParam::BlockReward);
+        let block_reward_is_zero = IsZeroGadget::construct(cb, block_reward.expr());
+        let nephew_reward = cb.hardfork_param(HardforkParam::NephewReward);
+
+        let number = cb.query_cell();
+        cb.block_lookup(BlockContextFieldTag::Number.expr(), None, number.expr());
+        let coinbase = cb.query_cell();
+        cb.block_lookup(BlockContextFieldTag::Coinbase.expr(), None, coinbase.expr());
+
+        let mut id = 0u64;
+        let uncles = [(); MAX_UNCLES].map(|()| {
+            id += 1;
+            UncleRewardGadget::construct(
+                cb,
+                id.expr(),
+                number.expr(),
+                not::expr(block_reward_is_zero.expr()),
+            )
+        });
+        let n_uncles = sum::expr(
+            uncles
+                .iter()
+                .map(|uncle| not::expr(uncle.is_padding.expr())),
+        );
+
+        // The rewards are below 2^128, as the block reward is a u64.
+        let coinbase_reward = cb.query_word_rlc();
+        let coinbase_balance = cb.condition(not::expr(block_reward_is_zero.expr()), |cb| {
+            cb.require_equal(
+                "coinbase_reward == block_reward + nephew_reward * n_uncles",
+                from_bytes::expr(&coinbase_reward.cells[..16]),
+                block_reward.expr() + nephew_reward.expr() * n_uncles.clone(),
+            );
+            cb.require_zero(
+                "coinbase_reward < 2^128",
+                from_bytes::expr(&coinbase_reward.cells[16..]),
+            );
+            UpdateBalanceGadget::construct(cb, coinbase.expr(), vec![coinbase_reward.clone()], None)
+        });
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(not::expr(block_reward_is_zero.expr()) * (1.expr() + n_uncles)),
+            // We propagate call_id so that EndBlock can get the last tx_id
+            // in order to count processed txs.
+            call_id: Same,
+            // We propagate log_id so that EndBlock can count the withdrawals.
+            log_id: Same,
+            ..StepStateTransition::any()
+        });
+
+        Self {
+            block_reward,
+            block_reward_is_zero,
+            nephew_reward,
+            number,
+            coinbase,
+            coinbase_reward,
+            coinbase_balance,
+            uncles,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let hardfork = block.circuits_params.hardfork;
+        let block_reward = block.circuits_params.block_reward;
+        let reward = F::from(HardforkParam::BlockReward.value(hardfork, block_reward));
+        self.block_reward
+            .assign(region, offset, Value::known(reward))?;
+        self.block_reward_is_zero.assign(region, offset, reward)?;
+        self.nephew_reward.assign(
+            region,
+            offset,
+            Value::known(F::from(
+                HardforkParam::NephewReward.value(hardfork, block_reward),
+            )),
+        )?;
+
+        let number = block.context.number.as_u64();
+        self.number
+            .assign(region, offset, Value::known(F::from(number)))?;
+        self.coinbase.assign(
+            region,
+            offset,
+            Value::known(
+                block
+                    .context
+                    .coinbase
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+
+        let has_rewards = !block_reward.block_reward(hardfork).is_zero();
+        let (balance, balance_prev) = if has_rewards {
+            block.get_rws(step, 0).account_value_pair()
+        } else {
+            Default::default()
+        };
+        let coinbase_reward = balance - balance_prev;
+        self.coinbase_reward
+            .assign(region, offset, Some(coinbase_reward.to_le_bytes()))?;
+        self.coinbase_balance.assign(
+            region,
+            offset,
+            balance_prev,
+            vec![coinbase_reward],
+            balance,
+        )?;
+
+        for (idx, gadget) in self.uncles.iter().enumerate() {
+            let uncle = block.uncles.get(idx).cloned().unwrap_or_default();
+            let (balance, balance_prev) = if has_rewards && idx < block.uncles.len() {
+                block.get_rws(step, 1 + idx).account_value_pair()
+            } else {
+                Default::default()
+            };
+            gadget.assign(region, offset, &uncle, balance, balance_prev)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<F: Field> UncleRewardGadget<F> {
+    fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        id: Expression<F>,
+        block_number: Expression<F>,
+        has_rewards: Expression<F>,
+    ) -> Self {
+        let number = cb.query_cell();
+        let miner = cb.query_cell();
+        cb.uncle_table_lookup(id, number.expr(), miner.expr());
+        let is_padding = IsZeroGadget::construct(cb, number.expr());
+
+        let reward = cb.query_word_rlc();
+        let miner_balance = cb.condition(has_rewards * not::expr(is_padding.expr()), |cb| {
+            // The lookup also bounds the depth of the uncle to 1..=7.
+            let uncle_reward = cb.uncle_reward(number.expr() + 8.expr() - block_number);
+            cb.require_equal(
+                "reward == uncle_reward",
+                from_bytes::expr(&reward.cells[..16]),
+                uncle_reward.expr(),
+            );
+            cb.require_zero("reward < 2^128", from_bytes::expr(&reward.cells[16..]));
+            UpdateBalanceGadget::construct(cb, miner.expr(), vec![reward.clone()], None)
+        });
+
+        Self {
+            number,
+            miner,
+            is_padding,
+            reward,
+            miner_balance,
+        }
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        uncle: &BlockHeader,
+        balance: U256,
+        balance_prev: U256,
+    ) -> Result<(), Error> {
+        let number = F::from(uncle.number);
+        self.number.assign(region, offset, Value::known(number))?;
+        self.miner.assign(
+            region,
+            offset,
+            Value::known(
+                uncle
+                    .coinbase
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+        self.is_padding.assign(region, offset, number)?;
+
+        let reward = balance - balance_prev;
+        self.reward
+            .assign(region, offset, Some(reward.to_le_bytes()))?;
+        self.miner_balance
+            .assign(region, offset, balance_prev, vec![reward], balance)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{BlockReward, Hardfork},
+        geth_types::BlockHeader,
+        Address, Word,
+    };
+    use mock::{test_ctx::helpers::*, TestContext, MOCK_WITHDRAWALS};
+
+    fn test_block_reward(hardfork: Hardfork, block_reward: BlockReward, uncles: Vec<BlockHeader>) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .hardfork(hardfork)
+                    .uncle_headers(uncles.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork,
+                block_reward,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    fn uncle(number: u64, miner: u8) -> BlockHeader {
+        BlockHeader {
+            number,
+            coinbase: Address::repeat_byte(miner),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn block_reward_without_uncles() {
+        test_block_reward(Hardfork::London, BlockReward::Ethash, vec![]);
+    }
+
+    #[test]
+    fn block_reward_with_uncles() {
+        test_block_reward(
+            Hardfork::London,
+            BlockReward::Ethash,
+            vec![uncle(0xcafd, 0xaa), uncle(0xcaf8, 0xbb)],
+        );
+    }
+
+    #[test]
+    fn block_reward_uncles_with_same_miner() {
+        test_block_reward(
+            Hardfork::Berlin,
+            BlockReward::Fixed(Word::from(5_000_000_000_000_000_000u64)),
+            vec![uncle(0xcafc, 0xaa), uncle(0xcafd, 0xaa)],
+        );
+    }
+
+    #[test]
+    fn block_reward_no_reward() {
+        test_block_reward(
+            Hardfork::London,
+            BlockReward::NoReward,
+            vec![uncle(0xcafd, 0xaa)],
+        );
+    }
+
+    #[test]
+    fn block_reward_after_paris() {
+        test_block_reward(Hardfork::Shanghai, BlockReward::Ethash, vec![]);
+    }
+
+    #[test]
+    fn block_reward_without_txs() {
+        let ctx = TestContext::<0, 0>::new(
+            None,
+            |_| {},
+            |_, _| {},
+            |block, _| {
+                block
+                    .number(0xcafeu64)
+                    .hardfork(Hardfork::London)
+                    .author(address!("0x00000000000000000000000000000000000000cc"))
+                    .uncle_headers(vec![uncle(0xcafd, 0xaa)])
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::London,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn block_reward_after_withdrawals() {
+        let ctx = TestContext::<0, 0>::new(
+            None,
+            |_| {},
+            |_, _| {},
+            |block, _| {
+                block
+                    .number(0xcafeu64)
+                    .hardfork(Hardfork::Shanghai)
+                    .withdrawals(MOCK_WITHDRAWALS.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+}
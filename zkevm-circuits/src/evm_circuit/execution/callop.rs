@@ -4,7 +4,7 @@ use crate::evm_circuit::{
     step::ExecutionState,
     util::{
         and,
-        common_gadget::{CommonCallGadget, TransferGadget},
+        common_gadget::{CommonCallGadget, PrecompileAddressGadget, TransferGadget},
         constraint_builder::{
             ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
             Transition::{Delta, To},
@@ -20,7 +20,9 @@ use crate::{
     util::Expr,
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::{evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToLittleEndian, ToScalar, U256};
+use eth_types::{
+    evm_types::GAS_STIPEND_CALL_WITH_VALUE, Field, ToAddress, ToLittleEndian, ToScalar, U256,
+};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget for call related opcodes. It supports `OpcodeId::CALL`,
@@ -53,6 +55,7 @@ pub(crate) struct CallOpGadget<F> {
     is_depth_ok: LtGadget<F, N_BYTES_U64>,
     one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
     capped_callee_gas_left: MinMaxGadget<F, N_BYTES_GAS>,
+    is_precompile: PrecompileAddressGadget<F>,
 }
 
 impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
@@ -114,6 +117,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             call_gadget.callee_address_expr(),
         );
 
+        // The code is executed from the precompiled contract at the callee
+        // address popped from the stack.
+        let is_precompile =
+            PrecompileAddressGadget::construct(cb, call_gadget.callee_address_expr());
+
         // Add callee to access list
         let is_warm = cb.query_bool();
         let is_warm_prev = cb.query_bool();
@@ -180,7 +188,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                 cb,
                 caller_address.expr(),
                 callee_address.expr(),
-                not::expr(call_gadget.callee_not_exists.expr()),
+                or::expr([
+                    not::expr(call_gadget.callee_not_exists.expr()),
+                    is_precompile.expr(),
+                ]),
+                0.expr(),
                 call_gadget.value.clone(),
                 &mut callee_reversion_info,
             )
@@ -198,13 +210,19 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
 
         // no_callee_code is true when the account exists and has empty
         // code hash, or when the account doesn't exist (which we encode with
-        // code_hash = 0).
-        let no_callee_code =
-            call_gadget.is_empty_code_hash.expr() + call_gadget.callee_not_exists.expr();
+        // code_hash = 0).  Precompiled contracts have no code, so calls to them
+        // are taken out of this case.
+        let no_callee_code = call_gadget.is_empty_code_hash.expr()
+            + call_gadget.callee_not_exists.expr()
+            - is_precompile.expr();
 
         // Sum up and verify gas cost.
         // Only CALL opcode could invoke transfer to make empty account into non-empty.
-        let gas_cost = call_gadget.gas_cost_expr(is_warm_prev.expr(), is_call.expr());
+        // Precompiled contracts are always warm (EIP-2929).
+        let gas_cost = call_gadget.gas_cost_expr(
+            or::expr([is_warm_prev.expr(), is_precompile.expr()]),
+            is_call.expr(),
+        );
         // Apply EIP 150
         let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
         let one_64th_gas = ConstantDivisionGadget::construct(cb, gas_available.clone(), 64);
@@ -217,8 +235,6 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             all_but_one_64th_gas,
         );
 
-        // TODO: Handle precompiled
-
         let stack_pointer_delta =
             select::expr(is_call.expr() + is_callcode.expr(), 6.expr(), 5.expr());
         let memory_expansion = call_gadget.memory_expansion.clone();
@@ -402,6 +418,11 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
                     reversible_write_counter: To(transfer_rwc_delta),
                     ..StepStateTransition::new_context()
                 });
+
+                // A precompiled contract is executed in the next step.
+                cb.condition(is_precompile.expr(), |cb| {
+                    cb.require_next_precompile_state(call_gadget.callee_address_expr());
+                });
             },
         );
 
@@ -428,6 +449,7 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             is_depth_ok,
             one_64th_gas,
             capped_callee_gas_left,
+            is_precompile,
         }
     }
 
@@ -588,10 +610,14 @@ impl<F: Field> ExecutionGadget<F> for CallOpGadget<F> {
             )?;
         }
 
+        let is_precompile =
+            self.is_precompile
+                .assign(region, offset, callee_address.to_address())?;
+
         let has_value = !value.is_zero() && !is_delegatecall;
         let gas_cost = self.call.cal_gas_cost_for_assignment(
             memory_expansion_gas_cost,
-            is_warm_prev,
+            is_warm_prev || is_precompile,
             is_call,
             has_value,
             !callee_exists,
@@ -0,0 +1,884 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{
+            N_BYTES_ACCOUNT_ADDRESS, N_BYTES_GAS, N_BYTES_MEMORY_WORD_SIZE, N_BYTES_U64,
+            N_BYTES_WORD,
+        },
+        step::ExecutionState,
+        table::HardforkParam,
+        util::{
+            and,
+            common_gadget::TransferGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            math_gadget::{
+                ConstantDivisionGadget, ContractCreateGadget, IsEqualGadget, IsZeroGadget,
+                LtGadget, LtWordGadget,
+            },
+            memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget, MemoryWordSizeGadget},
+            not, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag, TxAccountFlagFieldTag},
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId, state_db::CodeDB};
+use eth_types::{evm_types::GasCost, Field, ToBigEndian, ToLittleEndian, ToWord, U256};
+use ethers_core::utils::{keccak256, rlp};
+use gadgets::util::expr_from_bytes;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CREATE and CREATE2 opcodes.  It handles the successful case,
+/// the failures that happen before the initialization call is entered
+/// (depth, insufficient balance and nonce overflow), and the contract address
+/// collision, which happens when the account to be created already has a
+/// non-zero nonce or non-empty code (EIP-684).  An init code larger than the
+/// limit of EIP-3860 is left to `ErrorMaxInitCodeSizeGadget`.
+#[derive(Clone, Debug)]
+pub(crate) struct CreateGadget<F, const IS_CREATE2: bool, const S: ExecutionState> {
+    opcode: Cell<F>,
+    tx_id: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    depth: Cell<F>,
+    is_success: Cell<F>,
+    value: Word<F>,
+
+    init_code: MemoryAddressGadget<F>,
+    init_code_rlc: Cell<F>,
+    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    init_code_word_size: MemoryWordSizeGadget<F>,
+    init_code_word_gas: Cell<F>,
+    max_init_code_size: Cell<F>,
+    is_init_code_too_large: LtGadget<F, N_BYTES_U64>,
+
+    create: ContractCreateGadget<F, IS_CREATE2>,
+    keccak_output: [Cell<F>; N_BYTES_WORD],
+
+    caller_balance: Word<F>,
+    is_depth_ok: LtGadget<F, N_BYTES_U64>,
+    is_insufficient_balance: LtWordGadget<F>,
+    is_nonce_in_range: LtGadget<F, N_BYTES_U64>,
+
+    is_warm_prev: Cell<F>,
+    callee_reversion_info: ReversionInfo<F>,
+    callee_code_hash: Cell<F>,
+    callee_not_exists: IsZeroGadget<F>,
+    callee_is_empty_code_hash: IsEqualGadget<F>,
+    callee_nonce: Cell<F>,
+    callee_nonce_is_zero: IsZeroGadget<F>,
+    transfer: TransferGadget<F>,
+
+    one_64th_gas: ConstantDivisionGadget<F, N_BYTES_GAS>,
+}
+
+impl<F: Field, const IS_CREATE2: bool, const S: ExecutionState> ExecutionGadget<F>
+    for CreateGadget<F, IS_CREATE2, S>
+{
+    const NAME: &'static str = "CREATE";
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+        cb.require_equal(
+            "Opcode is CREATE or CREATE2",
+            opcode.expr(),
+            if IS_CREATE2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            }
+            .expr(),
+        );
+
+        // Use rw_counter of the step which triggers next call as its call_id.
+        let callee_call_id = cb.curr.state.rw_counter.clone();
+
+        let value = cb.query_word_rlc();
+        let init_code_memory_offset = cb.query_cell_phase2();
+        let init_code_length = cb.query_word_rlc();
+        let create = ContractCreateGadget::construct(cb);
+
+        cb.stack_pop(value.expr());
+        cb.stack_pop(init_code_memory_offset.expr());
+        cb.stack_pop(init_code_length.expr());
+        if IS_CREATE2 {
+            let salt = create.salt_word_rlc(cb);
+            cb.stack_pop(salt);
+        }
+        let init_code =
+            MemoryAddressGadget::construct(cb, init_code_memory_offset, init_code_length);
+
+        // The lower 20 bytes of the keccak output are the address of the new
+        // contract, which is pushed onto the stack in the successful case.
+        let keccak_output = array_init::array_init(|_| cb.query_byte());
+        let callee_address = expr_from_bytes(&keccak_output[..N_BYTES_ACCOUNT_ADDRESS]);
+        let callee_address_rlc = cb.word_rlc::<N_BYTES_ACCOUNT_ADDRESS>(
+            keccak_output[..N_BYTES_ACCOUNT_ADDRESS]
+                .iter()
+                .map(Expr::expr)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap(),
+        );
+        let is_success = cb.query_bool();
+        cb.stack_push(is_success.expr() * callee_address_rlc);
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+        let depth = cb.call_context(None, CallContextFieldTag::Depth);
+        cb.call_context_lookup(
+            false.expr(),
+            None,
+            CallContextFieldTag::CalleeAddress,
+            create.caller_address(),
+        );
+        let mut reversion_info = cb.reversion_info_read(None);
+
+        let caller_balance = cb.query_word_rlc();
+        cb.account_read(
+            create.caller_address(),
+            AccountFieldTag::Balance,
+            caller_balance.expr(),
+        );
+        cb.account_read(
+            create.caller_address(),
+            AccountFieldTag::Nonce,
+            create.caller_nonce(),
+        );
+
+        // depth < 1025, value <= caller_balance and caller_nonce < u64::MAX
+        let is_depth_ok = LtGadget::construct(cb, depth.expr(), 1025.expr());
+        let is_insufficient_balance = LtWordGadget::construct(cb, &caller_balance, &value);
+        let is_nonce_in_range = LtGadget::construct(cb, create.caller_nonce(), u64::MAX.expr());
+        let is_precheck_ok = and::expr([
+            is_depth_ok.expr(),
+            not::expr(is_insufficient_balance.expr()),
+            is_nonce_in_range.expr(),
+        ]);
+        cb.condition(not::expr(is_precheck_ok.expr()), |cb| {
+            cb.require_zero(
+                "is_success is false when the precheck fails",
+                is_success.expr(),
+            );
+        });
+
+        let is_warm_prev = cb.query_bool();
+        let init_code_rlc = cb.query_cell_phase2();
+        let callee_code_hash = cb.query_cell_phase2();
+        let callee_not_exists = IsZeroGadget::construct(cb, callee_code_hash.expr());
+        let empty_code_hash_rlc = cb.empty_code_hash_rlc();
+        let callee_is_empty_code_hash =
+            IsEqualGadget::construct(cb, callee_code_hash.expr(), empty_code_hash_rlc.expr());
+        let callee_nonce = cb.query_cell();
+        let callee_nonce_is_zero = IsZeroGadget::construct(cb, callee_nonce.expr());
+        let is_address_collision = not::expr(
+            (callee_not_exists.expr() + callee_is_empty_code_hash.expr())
+                * callee_nonce_is_zero.expr(),
+        );
+        let code_hash_rlc = create.code_hash_word_rlc(cb);
+
+        let (callee_reversion_info, transfer) =
+            cb.condition(is_precheck_ok.expr(), |cb| {
+                // Increase caller's nonce and add the new contract address
+                // into access list.
+                cb.account_write(
+                    create.caller_address(),
+                    AccountFieldTag::Nonce,
+                    create.caller_nonce() + 1.expr(),
+                    create.caller_nonce(),
+                    Some(&mut reversion_info),
+                );
+                cb.account_access_list_write(
+                    tx_id.expr(),
+                    callee_address.expr(),
+                    1.expr(),
+                    is_warm_prev.expr(),
+                    Some(&mut reversion_info),
+                );
+
+                // Verify the contract address derivation.
+                let keccak_output_rlc = cb.word_rlc::<N_BYTES_WORD>(
+                    keccak_output
+                        .iter()
+                        .map(Expr::expr)
+                        .collect::<Vec<_>>()
+                        .try_into()
+                        .unwrap(),
+                );
+                let keccak_input_rlc = create.input_rlc(cb);
+                cb.keccak_table_lookup(
+                    keccak_input_rlc,
+                    create.input_length(),
+                    keccak_output_rlc,
+                );
+
+                // Propagate rw_counter_end_of_reversion and is_persistent
+                let mut callee_reversion_info =
+                    cb.reversion_info_write(Some(callee_call_id.expr()));
+                cb.require_equal(
+                    "callee_is_persistent == is_persistent ⋅ is_success",
+                    callee_reversion_info.is_persistent(),
+                    reversion_info.is_persistent() * is_success.expr(),
+                );
+                cb.condition(is_success.expr() * (1.expr() - reversion_info.is_persistent()), |cb| {
+                    cb.require_equal(
+                        "callee_rw_counter_end_of_reversion == rw_counter_end_of_reversion - (reversible_write_counter + 2)",
+                        callee_reversion_info.rw_counter_end_of_reversion(),
+                        reversion_info.rw_counter_of_reversion(1.expr()),
+                    );
+                });
+
+                cb.account_read(
+                    callee_address.expr(),
+                    AccountFieldTag::CodeHash,
+                    callee_code_hash.expr(),
+                );
+                cb.account_read(
+                    callee_address.expr(),
+                    AccountFieldTag::Nonce,
+                    callee_nonce.expr(),
+                );
+
+                // Copy the init code from caller's memory into the bytecode
+                // table. We don't need to place any additional constraints on
+                // the code hash because the copy circuit enforces that it is
+                // the hash of the bytes in the copy lookup.
+                cb.condition(init_code.has_length(), |cb| {
+                    cb.copy_table_lookup(
+                        cb.curr.state.call_id.expr(),
+                        CopyDataType::Memory.expr(),
+                        code_hash_rlc.expr(),
+                        CopyDataType::Bytecode.expr(),
+                        init_code.offset(),
+                        init_code.address(),
+                        0.expr(),
+                        init_code.length(),
+                        init_code_rlc.expr(),
+                        init_code.length(),
+                    );
+                });
+                cb.condition(not::expr(init_code.has_length()), |cb| {
+                    cb.require_equal(
+                        "code hash of empty init code is EMPTY_CODE_HASH",
+                        code_hash_rlc.expr(),
+                        empty_code_hash_rlc.expr(),
+                    );
+                });
+
+                cb.condition(is_address_collision.expr(), |cb| {
+                    cb.require_zero(
+                        "is_success is false when the address collides",
+                        is_success.expr(),
+                    );
+                });
+
+                let transfer = cb.condition(not::expr(is_address_collision.expr()), |cb| {
+                    // Empty init code always succeeds.
+                    cb.condition(not::expr(init_code.has_length()), |cb| {
+                        cb.require_equal(
+                            "is_success is true for empty init code",
+                            is_success.expr(),
+                            1.expr(),
+                        );
+                    });
+
+                    let transfer = TransferGadget::construct(
+                        cb,
+                        create.caller_address(),
+                        callee_address.expr(),
+                        not::expr(callee_not_exists.expr()),
+                        1.expr(),
+                        value.clone(),
+                        &mut callee_reversion_info,
+                    );
+
+                    // EIP-161: the nonce of a created account starts at 1.
+                    cb.account_write(
+                        callee_address.expr(),
+                        AccountFieldTag::Nonce,
+                        1.expr(),
+                        0.expr(),
+                        Some(&mut callee_reversion_info),
+                    );
+                    // Flag the account as created in the tx for SELFDESTRUCT
+                    // (EIP-6780).
+                    cb.tx_account_flag_write(
+                        tx_id.expr(),
+                        callee_address.expr(),
+                        TxAccountFlagFieldTag::Created,
+                        1.expr(),
+                        0.expr(),
+                        Some(&mut callee_reversion_info),
+                    );
+
+                    transfer
+                });
+
+                (callee_reversion_info, transfer)
+            });
+
+        // Sum up and verify gas cost.
+        let memory_expansion = MemoryExpansionGadget::construct(cb, [init_code.address()]);
+        let init_code_word_size = MemoryWordSizeGadget::construct(cb, init_code.length());
+        let init_code_word_gas = cb.hardfork_param(HardforkParam::InitCodeWordGas);
+        let max_init_code_size = cb.hardfork_param(HardforkParam::MaxInitCodeSize);
+        let is_init_code_too_large =
+            LtGadget::construct(cb, max_init_code_size.expr(), init_code.length());
+        cb.require_zero(
+            "init code size <= max init code size (EIP-3860)",
+            is_init_code_too_large.expr(),
+        );
+        let keccak_gas_cost = if IS_CREATE2 {
+            GasCost::COPY_SHA3.expr() * init_code_word_size.expr()
+        } else {
+            0.expr()
+        };
+        let gas_cost = GasCost::CREATE.expr()
+            + memory_expansion.gas_cost()
+            + init_code_word_gas.expr() * init_code_word_size.expr()
+            + keccak_gas_cost;
+        // Apply EIP 150
+        let gas_available = cb.curr.state.gas_left.expr() - gas_cost.clone();
+        let one_64th_gas = ConstantDivisionGadget::construct(cb, gas_available.clone(), 64);
+        let callee_gas_left = gas_available - one_64th_gas.quotient();
+
+        let stack_pointer_delta = if IS_CREATE2 { 3u64 } else { 2u64 };
+        let is_entering = and::expr([
+            is_precheck_ok.expr(),
+            not::expr(is_address_collision.expr()),
+            init_code.has_length(),
+        ]);
+
+        // Enter the initialization call.
+        cb.condition(is_entering.expr(), |cb| {
+            // Save caller's call state
+            for (field_tag, value) in [
+                (
+                    CallContextFieldTag::ProgramCounter,
+                    cb.curr.state.program_counter.expr() + 1.expr(),
+                ),
+                (
+                    CallContextFieldTag::StackPointer,
+                    cb.curr.state.stack_pointer.expr() + stack_pointer_delta.expr(),
+                ),
+                (CallContextFieldTag::GasLeft, one_64th_gas.quotient()),
+                (
+                    CallContextFieldTag::MemorySize,
+                    memory_expansion.next_memory_word_size(),
+                ),
+                (
+                    CallContextFieldTag::ReversibleWriteCounter,
+                    cb.curr.state.reversible_write_counter.expr() + 2.expr(),
+                ),
+            ] {
+                cb.call_context_lookup(true.expr(), None, field_tag, value);
+            }
+
+            // Setup next call's context.
+            for (field_tag, value) in [
+                (CallContextFieldTag::CallerId, cb.curr.state.call_id.expr()),
+                (CallContextFieldTag::IsSuccess, is_success.expr()),
+                (CallContextFieldTag::TxId, tx_id.expr()),
+                (CallContextFieldTag::CallerAddress, create.caller_address()),
+                (CallContextFieldTag::CalleeAddress, callee_address.expr()),
+                (CallContextFieldTag::Depth, depth.expr() + 1.expr()),
+                (CallContextFieldTag::CallDataOffset, 0.expr()),
+                (CallContextFieldTag::CallDataLength, 0.expr()),
+                (CallContextFieldTag::Value, value.expr()),
+                (CallContextFieldTag::IsStatic, 0.expr()),
+                (CallContextFieldTag::LastCalleeId, 0.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataOffset, 0.expr()),
+                (CallContextFieldTag::LastCalleeReturnDataLength, 0.expr()),
+                (CallContextFieldTag::IsRoot, 0.expr()),
+                (CallContextFieldTag::IsCreate, 1.expr()),
+                (CallContextFieldTag::CodeHash, code_hash_rlc.expr()),
+            ] {
+                cb.call_context_lookup(true.expr(), Some(callee_call_id.expr()), field_tag, value);
+            }
+
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(cb.rw_counter_offset()),
+                call_id: To(callee_call_id.expr()),
+                is_root: To(false.expr()),
+                is_create: To(true.expr()),
+                code_hash: To(code_hash_rlc.expr()),
+                gas_left: To(callee_gas_left.clone()),
+                // `transfer`, the nonce update and the Created flag of the new
+                // contract are reversible writes of the initialization call.
+                reversible_write_counter: To(transfer.reversible_w_delta() + 2.expr()),
+                ..StepStateTransition::new_context()
+            });
+        });
+
+        // The initialization call is not entered, either because the precheck
+        // fails, the address collides, or the init code is empty.
+        cb.condition(not::expr(is_entering), |cb| {
+            for field_tag in [
+                CallContextFieldTag::LastCalleeId,
+                CallContextFieldTag::LastCalleeReturnDataOffset,
+                CallContextFieldTag::LastCalleeReturnDataLength,
+            ] {
+                cb.call_context_lookup(true.expr(), None, field_tag, 0.expr());
+            }
+
+            // The gas sent to the initialization call is consumed when the
+            // address collides, and returned otherwise.
+            let is_precheck_ok_and_collision = is_precheck_ok.expr() * is_address_collision.expr();
+            cb.require_step_state_transition(StepStateTransition {
+                rw_counter: Delta(cb.rw_counter_offset()),
+                program_counter: Delta(1.expr()),
+                stack_pointer: Delta(stack_pointer_delta.expr()),
+                gas_left: Delta(
+                    -gas_cost.clone() - is_precheck_ok_and_collision * callee_gas_left.clone(),
+                ),
+                memory_word_size: To(memory_expansion.next_memory_word_size()),
+                reversible_write_counter: Delta(
+                    is_precheck_ok.expr()
+                        * (2.expr()
+                            + not::expr(is_address_collision.expr())
+                                * (transfer.reversible_w_delta() + 2.expr())),
+                ),
+                ..StepStateTransition::default()
+            });
+        });
+
+        Self {
+            opcode,
+            tx_id,
+            reversion_info,
+            depth,
+            is_success,
+            value,
+            init_code,
+            init_code_rlc,
+            memory_expansion,
+            init_code_word_size,
+            init_code_word_gas,
+            max_init_code_size,
+            is_init_code_too_large,
+            create,
+            keccak_output,
+            caller_balance,
+            is_depth_ok,
+            is_insufficient_balance,
+            is_nonce_in_range,
+            is_warm_prev,
+            callee_reversion_info,
+            callee_code_hash,
+            callee_not_exists,
+            callee_is_empty_code_hash,
+            callee_nonce,
+            callee_nonce_is_zero,
+            transfer,
+            one_64th_gas,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let n_pop = if IS_CREATE2 { 4 } else { 3 };
+        let [value, init_code_start, init_code_length] =
+            [0, 1, 2].map(|i| block.get_rws(step, i).stack_value());
+        let salt = if IS_CREATE2 {
+            block.get_rws(step, 3).stack_value()
+        } else {
+            U256::zero()
+        };
+        let is_success = !block.get_rws(step, n_pop).stack_value().is_zero();
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.is_success
+            .assign(region, offset, Value::known(F::from(is_success as u64)))?;
+
+        let init_code_address =
+            self.init_code
+                .assign(region, offset, init_code_start, init_code_length)?;
+        let (_, memory_expansion_gas_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [init_code_address],
+        )?;
+        let init_code_word_size =
+            self.init_code_word_size
+                .assign(region, offset, init_code_length.as_u64())?;
+        let init_code_word_gas = block.circuits_params.hardfork.init_code_word_gas();
+        self.init_code_word_gas.assign(
+            region,
+            offset,
+            Value::known(F::from(init_code_word_gas)),
+        )?;
+        let max_init_code_size = HardforkParam::MaxInitCodeSize.value(
+            block.circuits_params.hardfork,
+            block.circuits_params.block_reward,
+        );
+        self.max_init_code_size.assign(
+            region,
+            offset,
+            Value::known(F::from(max_init_code_size)),
+        )?;
+        self.is_init_code_too_large.assign(
+            region,
+            offset,
+            F::from(max_init_code_size),
+            F::from(init_code_length.as_u64()),
+        )?;
+
+        let [tx_id, depth] =
+            [n_pop + 1, n_pop + 2].map(|i| block.get_rws(step, i).call_context_value());
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx_id.low_u64())))?;
+        self.depth
+            .assign(region, offset, Value::known(F::from(depth.low_u64())))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+
+        let caller_balance = block.get_rws(step, n_pop + 6).account_value_pair().0;
+        let caller_nonce = block
+            .get_rws(step, n_pop + 7)
+            .account_value_pair()
+            .0
+            .low_u64();
+        self.caller_balance
+            .assign(region, offset, Some(caller_balance.to_le_bytes()))?;
+        self.is_depth_ok
+            .assign(region, offset, F::from(depth.low_u64()), F::from(1025))?;
+        self.is_insufficient_balance
+            .assign(region, offset, caller_balance, value)?;
+        self.is_nonce_in_range
+            .assign(region, offset, F::from(caller_nonce), F::from(u64::MAX))?;
+        let is_precheck_ok =
+            depth.low_u64() < 1025 && caller_balance >= value && caller_nonce < u64::MAX;
+
+        let init_code = if !is_precheck_ok || init_code_length.is_zero() {
+            vec![]
+        } else {
+            (0..init_code_length.as_usize())
+                .map(|i| block.get_rws(step, n_pop + 14 + i).memory_value())
+                .collect()
+        };
+        let init_code_hash = if is_precheck_ok {
+            CodeDB::hash(&init_code)
+        } else {
+            CodeDB::empty_code_hash()
+        };
+        self.init_code_rlc.assign(
+            region,
+            offset,
+            region.keccak_rlc(&init_code.iter().rev().cloned().collect::<Vec<u8>>()),
+        )?;
+        self.create.assign(
+            region,
+            offset,
+            call.address,
+            caller_nonce,
+            Some(init_code_hash.to_word()),
+            Some(salt),
+        )?;
+
+        let keccak_input = if IS_CREATE2 {
+            let mut keccak_input = vec![0xff];
+            keccak_input.extend_from_slice(call.address.as_bytes());
+            keccak_input.extend_from_slice(&salt.to_be_bytes());
+            keccak_input.extend_from_slice(init_code_hash.as_bytes());
+            keccak_input
+        } else {
+            let mut stream = rlp::RlpStream::new();
+            stream.begin_list(2);
+            stream.append(&call.address);
+            stream.append(&caller_nonce);
+            stream.out().to_vec()
+        };
+        let keccak_output = keccak256(&keccak_input);
+        for (cell, byte) in self.keccak_output.iter().rev().zip(keccak_output.iter()) {
+            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+        }
+
+        let mut callee_code_hash = U256::zero();
+        let mut callee_nonce = U256::zero();
+        let mut is_address_collision = false;
+        if is_precheck_ok {
+            let (_, is_warm_prev) = block.get_rws(step, n_pop + 9).tx_access_list_value_pair();
+            self.is_warm_prev
+                .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+
+            let [callee_rw_counter_end_of_reversion, callee_is_persistent] =
+                [10, 11].map(|i| block.get_rws(step, n_pop + i).call_context_value());
+            self.callee_reversion_info.assign(
+                region,
+                offset,
+                callee_rw_counter_end_of_reversion.low_u64() as usize,
+                callee_is_persistent.low_u64() != 0,
+            )?;
+
+            callee_code_hash = block.get_rws(step, n_pop + 12).account_value_pair().0;
+            callee_nonce = block.get_rws(step, n_pop + 13).account_value_pair().0;
+            is_address_collision = !callee_nonce.is_zero()
+                || (!callee_code_hash.is_zero()
+                    && callee_code_hash != CodeDB::empty_code_hash().to_word());
+        }
+        self.callee_code_hash
+            .assign(region, offset, region.word_rlc(callee_code_hash))?;
+        self.callee_not_exists
+            .assign_value(region, offset, region.word_rlc(callee_code_hash))?;
+        self.callee_is_empty_code_hash.assign_value(
+            region,
+            offset,
+            region.word_rlc(callee_code_hash),
+            region.empty_code_hash_rlc(),
+        )?;
+        self.callee_nonce.assign(
+            region,
+            offset,
+            Value::known(F::from(callee_nonce.low_u64())),
+        )?;
+        self.callee_nonce_is_zero
+            .assign(region, offset, F::from(callee_nonce.low_u64()))?;
+
+        let [caller_balance_pair, callee_balance_pair] =
+            if is_precheck_ok && !is_address_collision && !value.is_zero() {
+                // The balance updates follow the init code and the creation of
+                // the callee account, which is skipped when it already exists.
+                let rw_offset =
+                    n_pop + 14 + init_code.len() + usize::from(callee_code_hash.is_zero());
+                [rw_offset, rw_offset + 1].map(|i| block.get_rws(step, i).account_value_pair())
+            } else {
+                [(U256::zero(), U256::zero()), (U256::zero(), U256::zero())]
+            };
+        self.transfer.assign(
+            region,
+            offset,
+            caller_balance_pair,
+            callee_balance_pair,
+            value,
+        )?;
+
+        let gas_cost = GasCost::CREATE
+            + memory_expansion_gas_cost
+            + init_code_word_size
+                * (init_code_word_gas + if IS_CREATE2 { GasCost::COPY_SHA3 } else { 0 });
+        self.one_64th_gas
+            .assign(region, offset, (step.gas_left - gas_cost).into())?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{
+        address, bytecode, evm_types::OpcodeId, geth_types::Account, Address, Bytecode, Word, U64,
+    };
+    use ethers_core::utils::get_contract_address;
+    use itertools::Itertools;
+    use mock::{eth, TestContext};
+
+    const CALLER_ADDRESS: Address = Address::repeat_byte(0x34);
+
+    fn initialization_bytecode(is_success: bool) -> Bytecode {
+        let memory_bytes = [0x60; 10];
+        let memory_address = 0;
+        let memory_value = Word::from_big_endian(&memory_bytes);
+        let mut code = bytecode! {
+            PUSH10(memory_value)
+            PUSH1(memory_address)
+            MSTORE
+            PUSH2(5)
+            PUSH2(32u64 - u64::try_from(memory_bytes.len()).unwrap())
+        };
+        code.write_op(if is_success {
+            OpcodeId::RETURN
+        } else {
+            OpcodeId::REVERT
+        });
+        code
+    }
+
+    fn creator_bytecode(
+        initialization_bytecode: Bytecode,
+        value: Word,
+        is_create2: bool,
+        repeat: usize,
+    ) -> Bytecode {
+        let initialization_bytes = initialization_bytecode.code();
+        let mut code = bytecode! {
+            PUSH32(Word::from_big_endian(&initialization_bytes))
+            PUSH1(0)
+            MSTORE
+        };
+        for _ in 0..repeat {
+            if is_create2 {
+                code.append(&bytecode! {PUSH1(45)}); // salt
+            }
+            code.append(&bytecode! {
+                PUSH1(initialization_bytes.len()) // length
+                PUSH1(32 - initialization_bytes.len()) // offset
+                PUSH32(value) // value
+            });
+            code.write_op(if is_create2 {
+                OpcodeId::CREATE2
+            } else {
+                OpcodeId::CREATE
+            });
+            code.write_op(OpcodeId::POP);
+        }
+        code.write_op(OpcodeId::STOP);
+        code
+    }
+
+    fn test_context(caller: Account) -> TestContext<2, 1> {
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(100000u64.into());
+            },
+            |block, _| block,
+        )
+        .unwrap()
+    }
+
+    /// Test context where the address created by the first CREATE of the
+    /// caller already holds the `callee` account.
+    fn test_context_with_callee(caller: Account, callee: Account) -> TestContext<3, 1> {
+        TestContext::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x000000000000000000000000000000000000cafe"))
+                    .balance(eth(10));
+                accs[1].account(&caller);
+                accs[2].account(&Account {
+                    address: get_contract_address(caller.address, caller.nonce),
+                    ..callee
+                });
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(100000u64.into());
+            },
+            |block, _| block,
+        )
+        .unwrap()
+    }
+
+    fn creator_account(code: Bytecode, balance: Word, nonce: u64) -> Account {
+        Account {
+            address: CALLER_ADDRESS,
+            balance,
+            nonce: U64::from(nonce),
+            code: code.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_create() {
+        for ((is_success, is_create2), value) in [true, false]
+            .iter()
+            .cartesian_product(&[true, false])
+            .cartesian_product(&[Word::zero(), Word::from(1000)])
+        {
+            let init_code = initialization_bytecode(*is_success);
+            let caller = creator_account(
+                creator_bytecode(init_code, *value, *is_create2, 1),
+                eth(10),
+                1,
+            );
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create_empty_init_code() {
+        for is_create2 in [true, false] {
+            let caller = creator_account(
+                creator_bytecode(Bytecode::default(), Word::from(1000), is_create2, 1),
+                eth(10),
+                1,
+            );
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create_insufficient_balance() {
+        for is_create2 in [true, false] {
+            let caller = creator_account(
+                creator_bytecode(initialization_bytecode(true), eth(20), is_create2, 1),
+                eth(10),
+                1,
+            );
+            CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+        }
+    }
+
+    #[test]
+    fn test_create2_address_collision() {
+        let caller = creator_account(
+            creator_bytecode(initialization_bytecode(true), Word::zero(), true, 2),
+            eth(10),
+            1,
+        );
+        CircuitTestBuilder::new_from_test_ctx(test_context(caller)).run();
+    }
+
+    #[test]
+    fn test_create_prefunded_address() {
+        let caller = creator_account(
+            creator_bytecode(initialization_bytecode(true), Word::from(1000), false, 1),
+            eth(10),
+            1,
+        );
+        let callee = Account {
+            balance: eth(1),
+            ..Default::default()
+        };
+        CircuitTestBuilder::new_from_test_ctx(test_context_with_callee(caller, callee)).run();
+    }
+
+    #[test]
+    fn test_create_nonce_only_address_collision() {
+        let caller = creator_account(
+            creator_bytecode(initialization_bytecode(true), Word::zero(), false, 1),
+            eth(10),
+            1,
+        );
+        let callee = Account {
+            nonce: U64::one(),
+            ..Default::default()
+        };
+        CircuitTestBuilder::new_from_test_ctx(test_context_with_callee(caller, callee)).run();
+    }
+}
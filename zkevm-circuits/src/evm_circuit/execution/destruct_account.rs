@@ -0,0 +1,292 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            constraint_builder::{
+                EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same, To},
+            },
+            CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag, TxAccountFlagFieldTag},
+    util::Expr,
+};
+use eth_types::{Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the virtual step which follows the EndTx once for every account
+/// self destructed in the tx.  The Destructed flag of the account is reset and
+/// its code hash, balance, nonce and storage are cleared.  The state circuit requires
+/// every Destructed flag to be reset at its last access, so no destruction can
+/// be skipped.
+#[derive(Clone, Debug)]
+pub(crate) struct DestructAccountGadget<F> {
+    tx_id: Cell<F>,
+    address: Cell<F>,
+    code_hash_prev: Cell<F>,
+    balance_prev: Cell<F>,
+    nonce_prev: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for DestructAccountGadget<F> {
+    const NAME: &'static str = "DestructAccount";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::DestructAccount;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        let address = cb.query_cell();
+        cb.tx_account_flag_write(
+            tx_id.expr(),
+            address.expr(),
+            TxAccountFlagFieldTag::Destructed,
+            0.expr(),
+            1.expr(),
+            None,
+        );
+
+        // The code hash is cleared first, so that the account doesn't look
+        // empty while its balance and nonce are cleared.
+        let code_hash_prev = cb.query_cell_phase2();
+        let balance_prev = cb.query_cell_phase2();
+        let nonce_prev = cb.query_cell();
+        for (field_tag, value_prev) in [
+            (AccountFieldTag::CodeHash, code_hash_prev.expr()),
+            (AccountFieldTag::Balance, balance_prev.expr()),
+            (AccountFieldTag::Nonce, nonce_prev.expr()),
+        ] {
+            cb.account_write(address.expr(), field_tag, 0.expr(), value_prev, None);
+        }
+        cb.account_storage_clear(address.expr(), tx_id.expr());
+
+        cb.condition(
+            cb.next.execution_state_selector([ExecutionState::BeginTx]),
+            |cb| {
+                cb.call_context_lookup(
+                    true.expr(),
+                    Some(cb.next.state.rw_counter.expr()),
+                    CallContextFieldTag::TxId,
+                    tx_id.expr() + 1.expr(),
+                );
+
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(7.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::DestructAccount]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(6.expr()),
+                    call_id: Same,
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        cb.condition(
+            cb.next.execution_state_selector([
+                ExecutionState::Withdrawal,
+                ExecutionState::BlockReward,
+            ]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(6.expr()),
+                    call_id: Same,
+                    // The withdrawals count the applied ones in log_id.
+                    log_id: To(0.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        Self {
+            tx_id,
+            address,
+            code_hash_prev,
+            balance_prev,
+            nonce_prev,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+
+        let address = block
+            .get_rws(step, 1)
+            .address()
+            .expect("TxAccountFlag has an address");
+        self.address.assign(
+            region,
+            offset,
+            Value::known(
+                address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+
+        let [code_hash_prev, balance_prev, nonce_prev] =
+            [2, 3, 4].map(|index| block.get_rws(step, index).account_value_pair().1);
+        self.code_hash_prev
+            .assign(region, offset, region.word_rlc(code_hash_prev))?;
+        self.balance_prev
+            .assign(region, offset, region.word_rlc(balance_prev))?;
+        self.nonce_prev.assign(
+            region,
+            offset,
+            Value::known(
+                nonce_prev
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use eth_types::{address, bytecode, Address, Bytecode, ToWord, Word};
+    use mock::TestContext;
+
+    const CALLER: Address = Address::repeat_byte(0x10);
+    const CONTRACT_A: Address = Address::repeat_byte(0x20);
+    const CONTRACT_B: Address = Address::repeat_byte(0x21);
+    const BENEFICIARY: Address = Address::repeat_byte(0x30);
+
+    fn selfdestruct_code() -> Bytecode {
+        bytecode! {
+            PUSH20(BENEFICIARY.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    #[test]
+    fn destruct_account_between_txs() {
+        let ctx = TestContext::<4, 2>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1].address(CONTRACT_A).code(selfdestruct_code());
+                accs[2]
+                    .address(CONTRACT_B)
+                    .balance(Word::from(800u64))
+                    .code(selfdestruct_code());
+                accs[3].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+                txs[1]
+                    .from(accs[0].address)
+                    .to(accs[2].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn destruct_account_with_storage() {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT_A)
+                    .balance(Word::from(800u64))
+                    .code(selfdestruct_code())
+                    .storage(
+                        vec![(Word::one(), Word::from(5)), (Word::from(2), Word::from(6))]
+                            .into_iter(),
+                    );
+                accs[2].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+
+    #[test]
+    fn destruct_account_multiple_in_tx() {
+        let mut caller_code = Bytecode::default();
+        for contract in [CONTRACT_B, CONTRACT_A, CONTRACT_B] {
+            caller_code.append(&bytecode! {
+                PUSH1(0) // retLength
+                PUSH1(0) // retOffset
+                PUSH1(0) // argsLength
+                PUSH1(0) // argsOffset
+                PUSH1(0) // value
+                PUSH20(contract.to_word())
+                GAS
+                CALL
+                POP
+            });
+        }
+        let ctx = TestContext::<5, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1].address(CALLER).code(caller_code);
+                accs[2]
+                    .address(CONTRACT_A)
+                    .balance(Word::from(800u64))
+                    .code(selfdestruct_code());
+                accs[3]
+                    .address(CONTRACT_B)
+                    .balance(Word::from(900u64))
+                    .code(selfdestruct_code());
+                accs[4].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(200000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
@@ -14,7 +14,7 @@ use crate::{
     table::{CallContextFieldTag, TxContextFieldTag},
     util::Expr,
 };
-use eth_types::Field;
+use eth_types::{Field, ToScalar};
 use halo2_proofs::{circuit::Value, plonk::Error};
 
 #[derive(Clone, Debug)]
@@ -24,6 +24,12 @@ pub(crate) struct EndBlockGadget<F> {
     is_empty_block: IsZeroGadget<F>,
     max_rws: Cell<F>,
     max_txs: Cell<F>,
+    total_withdrawals_is_zero: IsZeroGadget<F>,
+    // Fields of the last withdrawal
+    withdrawal_index: Cell<F>,
+    withdrawal_validator_index: Cell<F>,
+    withdrawal_address: Cell<F>,
+    withdrawal_amount: Cell<F>,
 }
 
 const EMPTY_BLOCK_N_RWS: u64 = 0;
@@ -38,13 +44,19 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         let max_rws = cb.query_copy_cell();
         let total_txs = cb.query_cell();
         let total_txs_is_max_txs = IsEqualGadget::construct(cb, total_txs.expr(), max_txs.expr());
-        // Note that rw_counter starts at 1
-        let is_empty_block =
-            IsZeroGadget::construct(cb, cb.curr.state.rw_counter.clone().expr() - 1.expr());
-        // If the block is empty, we do 0 rw_table lookups
+        // The Withdrawal steps before the EndBlock count the withdrawals in
+        // log_id, which the BlockReward step propagates.
+        let total_withdrawals = cb.curr.state.log_id.expr();
+        let total_withdrawals_is_zero = IsZeroGadget::construct(cb, total_withdrawals.clone());
+        // The call_id is the one of the last tx, which is the non-zero
+        // rw_counter of its BeginTx, and it is zero without txs.
+        let is_empty_block = IsZeroGadget::construct(cb, cb.curr.state.call_id.expr());
+        // The steps before the EndBlock have done rw_counter - 1 rw_table
+        // lookups, as rw_counter starts at 1.
+        // If the block is empty, we do 0 more rw_table lookups
         // If the block is not empty, we will do 1 call_context lookup
-        let total_rws = not::expr(is_empty_block.expr())
-            * (cb.curr.state.rw_counter.clone().expr() - 1.expr() + 1.expr());
+        let total_rws =
+            cb.curr.state.rw_counter.clone().expr() - 1.expr() + not::expr(is_empty_block.expr());
 
         // 1. Constraint total_rws and total_txs witness values depending on the empty
         // block case.
@@ -77,7 +89,25 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             // meaningful txs in the tx_table is total_tx.
         });
 
-        // 3. Verify rw_counter counts to the same number of meaningful rows in
+        // 3. Verify that total_withdrawals is the id of the last withdrawal,
+        // as the ids of the rows of the withdrawal table go from 1 to the
+        // number of withdrawals of the block.  Each Withdrawal step has
+        // looked up its row and increased the balance of its address.
+        let withdrawal_index = cb.query_cell();
+        let withdrawal_validator_index = cb.query_cell();
+        let withdrawal_address = cb.query_cell();
+        let withdrawal_amount = cb.query_cell();
+        cb.condition(not::expr(total_withdrawals_is_zero.expr()), |cb| {
+            cb.withdrawal_table_lookup(
+                total_withdrawals.clone(),
+                withdrawal_index.expr(),
+                withdrawal_validator_index.expr(),
+                withdrawal_address.expr(),
+                withdrawal_amount.expr(),
+            );
+        });
+
+        // 4. Verify rw_counter counts to the same number of meaningful rows in
         // rw_table to ensure there is no malicious insertion.
         // Verify that there are at most total_rws meaningful entries in the rw_table
         cb.rw_table_start_lookup(1.expr());
@@ -88,15 +118,12 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         // We conclude that the number of meaningful entries in the rw_table
         // is total_rws.
 
-        // cb.step_last(|cb| {
-        //     // TODO: Handle reward to coinbase.  Depends on spec:
-        //     // https://github.com/privacy-scaling-explorations/zkevm-specs/issues/290
-        // });
         cb.not_step_last(|cb| {
-            // Propagate rw_counter and call_id all the way down.
+            // Propagate rw_counter, call_id and log_id all the way down.
             cb.require_step_state_transition(StepStateTransition {
                 rw_counter: Same,
                 call_id: Same,
+                log_id: Same,
                 ..StepStateTransition::any()
             });
         });
@@ -107,6 +134,11 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
             total_txs,
             total_txs_is_max_txs,
             is_empty_block,
+            total_withdrawals_is_zero,
+            withdrawal_index,
+            withdrawal_validator_index,
+            withdrawal_address,
+            withdrawal_amount,
         }
     }
 
@@ -116,11 +148,16 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         offset: usize,
         block: &Block<F>,
         _: &Transaction,
-        _: &Call,
+        call: &Call,
         step: &ExecStep,
     ) -> Result<(), Error> {
+        self.total_withdrawals_is_zero.assign(
+            region,
+            offset,
+            F::from(block.withdrawals.len() as u64),
+        )?;
         self.is_empty_block
-            .assign(region, offset, F::from(u64::from(step.rwc) - 1))?;
+            .assign(region, offset, F::from(call.call_id as u64))?;
         let max_rws = F::from(block.circuits_params.max_rws as u64);
         let max_rws_assigned = self.max_rws.assign(region, offset, Value::known(max_rws))?;
 
@@ -131,6 +168,23 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
         self.total_txs_is_max_txs
             .assign(region, offset, total_txs, max_txs)?;
         let max_txs_assigned = self.max_txs.assign(region, offset, Value::known(max_txs))?;
+
+        let withdrawal = block.withdrawals.last().cloned().unwrap_or_default();
+        for (cell, value) in [
+            (&self.withdrawal_index, F::from(withdrawal.index.as_u64())),
+            (
+                &self.withdrawal_validator_index,
+                F::from(withdrawal.validator_index.as_u64()),
+            ),
+            (
+                &self.withdrawal_address,
+                withdrawal.address.to_scalar().unwrap(),
+            ),
+            (&self.withdrawal_amount, F::from(withdrawal.amount.as_u64())),
+        ] {
+            cell.assign(region, offset, Value::known(value))?;
+        }
+
         // When rw_indices is not empty, we're at the last row (at a fixed offset),
         // where we need to access the max_rws and max_txs constant.
         if !step.rw_indices_len() == 0 {
@@ -144,10 +198,14 @@ impl<F: Field> ExecutionGadget<F> for EndBlockGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        address, bytecode,
+        evm_types::{BlockReward, Hardfork},
+        geth_types::BlockHeader,
+    };
 
-    use eth_types::bytecode;
-
-    use mock::TestContext;
+    use mock::{test_ctx::helpers::*, TestContext, MOCK_WITHDRAWALS};
 
     fn test_circuit(evm_circuit_pad_to: usize) {
         let bytecode = bytecode! {
@@ -178,4 +236,85 @@ mod test {
     fn end_block_padding() {
         test_circuit(50);
     }
+
+    #[test]
+    fn end_block_withdrawals() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |block, _tx| {
+                block
+                    .hardfork(Hardfork::Shanghai)
+                    .withdrawals(MOCK_WITHDRAWALS.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn end_block_withdrawals_without_txs() {
+        let ctx = TestContext::<0, 0>::new(
+            None,
+            |_| {},
+            |_, _| {},
+            |block, _| {
+                block
+                    .hardfork(Hardfork::Shanghai)
+                    .withdrawals(MOCK_WITHDRAWALS.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    fn test_block_reward(block_reward: BlockReward) {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode! { STOP }),
+            tx_from_1_to_0,
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .hardfork(Hardfork::London)
+                    .uncle_headers(vec![BlockHeader {
+                        number: 0xcafd,
+                        coinbase: address!("0x00000000000000000000000000000000000000aa"),
+                        ..Default::default()
+                    }])
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::London,
+                block_reward,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn end_block_block_reward() {
+        test_block_reward(BlockReward::Ethash);
+    }
+
+    #[test]
+    fn end_block_no_block_reward() {
+        test_block_reward(BlockReward::NoReward);
+    }
 }
@@ -3,15 +3,16 @@ use crate::{
         execution::ExecutionGadget,
         param::N_BYTES_GAS,
         step::ExecutionState,
+        table::HardforkParam,
         util::{
             common_gadget::UpdateBalanceGadget,
             constraint_builder::{
                 ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
-                Transition::{Delta, Same},
+                Transition::{Delta, Same, To},
             },
             math_gadget::{
-                AddWordsGadget, ConstantDivisionGadget, IsEqualGadget, MinMaxGadget,
-                MulWordByU64Gadget,
+                AddWordsGadget, IsEqualGadget, LtGadget, MinMaxGadget, MulWordByU64Gadget,
+                RangeCheckGadget,
             },
             CachedRegion, Cell,
         },
@@ -21,7 +22,7 @@ use crate::{
     util::Expr,
 };
 use bus_mapping::operation::Target;
-use eth_types::{evm_types::MAX_REFUND_QUOTIENT_OF_GAS_USED, Field, ToScalar};
+use eth_types::{Field, ToScalar};
 use halo2_proofs::{circuit::Value, plonk::Error};
 use strum::EnumCount;
 
@@ -29,7 +30,11 @@ use strum::EnumCount;
 pub(crate) struct EndTxGadget<F> {
     tx_id: Cell<F>,
     tx_gas: Cell<F>,
-    max_refund: ConstantDivisionGadget<F, N_BYTES_GAS>,
+    max_refund_quotient: Cell<F>,
+    max_refund: Cell<F>,
+    max_refund_remainder: Cell<F>,
+    max_refund_range_check: RangeCheckGadget<F, N_BYTES_GAS>,
+    is_max_refund_remainder_lt_quotient: LtGadget<F, 1>,
     refund: Cell<F>,
     effective_refund: MinMaxGadget<F, N_BYTES_GAS>,
     mul_gas_price_by_refund: MulWordByU64Gadget<F>,
@@ -58,16 +63,30 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
                 .map(|field_tag| cb.tx_context(tx_id.expr(), field_tag, None));
         let tx_gas_price = cb.tx_context_as_word(tx_id.expr(), TxContextFieldTag::GasPrice, None);
 
-        // Calculate effective gas to refund
+        // Calculate effective gas to refund, which is bounded by the gas used
+        // divided by the max refund quotient of the hardfork.
         let gas_used = tx_gas.expr() - cb.curr.state.gas_left.expr();
-        let max_refund = ConstantDivisionGadget::construct(
-            cb,
-            gas_used.clone(),
-            MAX_REFUND_QUOTIENT_OF_GAS_USED as u64,
+        let max_refund_quotient = cb.hardfork_param(HardforkParam::MaxRefundQuotientOfGasUsed);
+        let max_refund = cb.query_cell();
+        let max_refund_remainder = cb.query_cell();
+        // The quotient is at most 5, and the remainder is smaller.
+        cb.range_lookup(max_refund_remainder.expr(), 5);
+        let is_max_refund_remainder_lt_quotient =
+            LtGadget::construct(cb, max_refund_remainder.expr(), max_refund_quotient.expr());
+        cb.require_equal(
+            "max_refund_remainder < max_refund_quotient",
+            is_max_refund_remainder_lt_quotient.expr(),
+            1.expr(),
+        );
+        let max_refund_range_check = RangeCheckGadget::construct(cb, max_refund.expr());
+        cb.require_equal(
+            "gas_used - max_refund_remainder == max_refund ⋅ max_refund_quotient",
+            gas_used.clone() - max_refund_remainder.expr(),
+            max_refund.expr() * max_refund_quotient.expr(),
         );
         let refund = cb.query_cell();
         cb.tx_refund_read(tx_id.expr(), refund.expr());
-        let effective_refund = MinMaxGadget::construct(cb, max_refund.quotient(), refund.expr());
+        let effective_refund = MinMaxGadget::construct(cb, max_refund.expr(), refund.expr());
 
         // Add effective_refund * tx_gas_price back to caller's balance
         let mul_gas_price_by_refund = MulWordByU64Gadget::construct(
@@ -161,13 +180,32 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         );
 
         cb.condition(
-            cb.next.execution_state_selector([ExecutionState::EndBlock]),
+            cb.next.execution_state_selector([
+                ExecutionState::Withdrawal,
+                ExecutionState::BlockReward,
+            ]),
             |cb| {
                 cb.require_step_state_transition(StepStateTransition {
                     rw_counter: Delta(9.expr() - is_first_tx.expr()),
                     // We propagate call_id so that EndBlock can get the last tx_id
                     // in order to count processed txs.
                     call_id: Same,
+                    // The withdrawals count the applied ones in log_id.
+                    log_id: To(0.expr()),
+                    ..StepStateTransition::any()
+                });
+            },
+        );
+
+        cb.condition(
+            cb.next
+                .execution_state_selector([ExecutionState::DestructAccount]),
+            |cb| {
+                cb.require_step_state_transition(StepStateTransition {
+                    rw_counter: Delta(9.expr() - is_first_tx.expr()),
+                    // We propagate call_id so that DestructAccount can get the
+                    // tx_id of the self destructed accounts.
+                    call_id: Same,
                     ..StepStateTransition::any()
                 });
             },
@@ -176,7 +214,11 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
         Self {
             tx_id,
             tx_gas,
+            max_refund_quotient,
             max_refund,
+            max_refund_remainder,
+            max_refund_range_check,
+            is_max_refund_remainder_lt_quotient,
             refund,
             effective_refund,
             mul_gas_price_by_refund,
@@ -210,16 +252,37 @@ impl<F: Field> ExecutionGadget<F> for EndTxGadget<F> {
             .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
         self.tx_gas
             .assign(region, offset, Value::known(F::from(tx.gas)))?;
-        let (max_refund, _) = self.max_refund.assign(region, offset, gas_used as u128)?;
-        self.refund
-            .assign(region, offset, Value::known(F::from(refund)))?;
-        self.effective_refund.assign(
+        let max_refund_quotient = block
+            .circuits_params
+            .hardfork
+            .max_refund_quotient_of_gas_used();
+        let max_refund = gas_used / max_refund_quotient;
+        let max_refund_remainder = gas_used % max_refund_quotient;
+        self.max_refund_quotient.assign(
+            region,
+            offset,
+            Value::known(F::from(max_refund_quotient)),
+        )?;
+        self.max_refund
+            .assign(region, offset, Value::known(F::from(max_refund)))?;
+        self.max_refund_remainder.assign(
             region,
             offset,
-            F::from(max_refund as u64),
-            F::from(refund),
+            Value::known(F::from(max_refund_remainder)),
         )?;
-        let effective_refund = refund.min(max_refund as u64);
+        self.max_refund_range_check
+            .assign(region, offset, F::from(max_refund))?;
+        self.is_max_refund_remainder_lt_quotient.assign(
+            region,
+            offset,
+            F::from(max_refund_remainder),
+            F::from(max_refund_quotient),
+        )?;
+        self.refund
+            .assign(region, offset, Value::known(F::from(refund)))?;
+        self.effective_refund
+            .assign(region, offset, F::from(max_refund), F::from(refund))?;
+        let effective_refund = refund.min(max_refund);
         let gas_fee_refund = tx.gas_price * (effective_refund + step.gas_left);
         self.mul_gas_price_by_refund.assign(
             region,
@@ -68,9 +68,14 @@ impl<F: Field> ExecutionGadget<F> for ErrorInvalidOpcodeGadget<F> {
 #[cfg(test)]
 mod test {
     use crate::{evm_circuit::test::rand_bytes, test_util::CircuitTestBuilder};
-    use eth_types::{bytecode::Bytecode, Word};
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, bytecode::Bytecode, evm_types::Hardfork, Word};
     use lazy_static::lazy_static;
-    use mock::{generate_mock_call_bytecode, MockCallBytecodeParams, TestContext};
+    use mock::{
+        generate_mock_call_bytecode,
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        MockCallBytecodeParams, TestContext,
+    };
 
     lazy_static! {
         static ref TESTING_INVALID_CODES: [Vec<u8>; 6] = [
@@ -80,7 +85,7 @@ mod test {
             vec![0xa5],
             vec![0xf6],
             vec![0xfe],
-            // Multiple invalid opcodes
+            // Multiple invalid opcodes, PUSH0 is only defined from Shanghai on
             vec![0x5c, 0x5e, 0x5f],
         ];
     }
@@ -99,16 +104,49 @@ mod test {
         }
     }
 
+    #[test]
+    fn invalid_opcode_not_enabled_by_hardfork() {
+        // BASEFEE is only defined from London on.
+        let code = bytecode! {
+            BASEFEE
+            STOP
+        };
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(Hardfork::Berlin),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Berlin,
+                ..Default::default()
+            })
+            .run();
+    }
+
     fn test_root_ok(invalid_code: &[u8]) {
         let mut code = Bytecode::default();
         invalid_code.iter().for_each(|b| {
             code.write(*b, true);
         });
 
-        CircuitTestBuilder::new_from_test_ctx(
-            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(Hardfork::London),
         )
-        .run();
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::London,
+                ..Default::default()
+            })
+            .run();
     }
 
     fn test_internal_ok(call_data_offset: usize, call_data_length: usize, invalid_code: &[u8]) {
@@ -141,10 +179,15 @@ mod test {
             |mut txs, accs| {
                 txs[0].to(accs[1].address).from(accs[2].address);
             },
-            |block, _tx| block,
+            |block, _tx| block.hardfork(Hardfork::London),
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::London,
+                ..Default::default()
+            })
+            .run();
     }
 }
@@ -0,0 +1,212 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::N_BYTES_U64,
+        step::ExecutionState,
+        table::HardforkParam,
+        util::{
+            common_gadget::CommonErrorGadget,
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            math_gadget::LtGadget,
+            memory_gadget::MemoryAddressGadget,
+            CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{evm_types::OpcodeId, Field, ToLittleEndian};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for CREATE and CREATE2 when the init code is larger than the limit
+/// of EIP-3860, which halts the call with an exception.  The limit is looked
+/// up from the hardfork, before Shanghai it can't be exceeded.
+#[derive(Clone, Debug)]
+pub(crate) struct ErrorMaxInitCodeSizeGadget<F> {
+    opcode: Cell<F>,
+    value: Word<F>,
+    init_code: MemoryAddressGadget<F>,
+    max_init_code_size: Cell<F>,
+    is_init_code_too_large: LtGadget<F, N_BYTES_U64>,
+    common_error_gadget: CommonErrorGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for ErrorMaxInitCodeSizeGadget<F> {
+    const NAME: &'static str = "ErrorMaxInitCodeSizeExceeded";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::ErrorMaxInitCodeSizeExceeded;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.require_in_set(
+            "ErrorMaxInitCodeSizeExceeded only happens in [CREATE, CREATE2]",
+            opcode.expr(),
+            vec![OpcodeId::CREATE.expr(), OpcodeId::CREATE2.expr()],
+        );
+
+        let value = cb.query_word_rlc();
+        let init_code_memory_offset = cb.query_cell_phase2();
+        let init_code_length = cb.query_word_rlc();
+
+        // Pop value, init code offset and init code length from stack
+        cb.stack_pop(value.expr());
+        cb.stack_pop(init_code_memory_offset.expr());
+        cb.stack_pop(init_code_length.expr());
+        let init_code =
+            MemoryAddressGadget::construct(cb, init_code_memory_offset, init_code_length);
+
+        // The init code is larger than the limit of the hardfork
+        let max_init_code_size = cb.hardfork_param(HardforkParam::MaxInitCodeSize);
+        let is_init_code_too_large =
+            LtGadget::construct(cb, max_init_code_size.expr(), init_code.length());
+        cb.require_equal(
+            "init code size > max init code size (EIP-3860)",
+            is_init_code_too_large.expr(),
+            1.expr(),
+        );
+
+        let common_error_gadget = CommonErrorGadget::construct(cb, opcode.expr(), 5.expr());
+
+        Self {
+            opcode,
+            value,
+            init_code,
+            max_init_code_size,
+            is_init_code_too_large,
+            common_error_gadget,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let [value, init_code_start, init_code_length] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.init_code
+            .assign(region, offset, init_code_start, init_code_length)?;
+
+        let max_init_code_size = HardforkParam::MaxInitCodeSize.value(
+            block.circuits_params.hardfork,
+            block.circuits_params.block_reward,
+        );
+        self.max_init_code_size.assign(
+            region,
+            offset,
+            Value::known(F::from(max_init_code_size)),
+        )?;
+        self.is_init_code_too_large.assign(
+            region,
+            offset,
+            F::from(max_init_code_size),
+            F::from(init_code_length.as_u64()),
+        )?;
+
+        self.common_error_gadget
+            .assign(region, offset, block, call, step, 5)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{
+        bytecode,
+        bytecode::Bytecode,
+        evm_types::{Hardfork, MAX_INIT_CODE_SIZE},
+        geth_types::Account,
+        Address, ToWord, Word,
+    };
+    use mock::{
+        eth,
+        test_ctx::{helpers::*, TestContext},
+        MOCK_ACCOUNTS,
+    };
+
+    fn create_code(is_create2: bool) -> Bytecode {
+        let mut code = Bytecode::default();
+        if is_create2 {
+            code.op_create2(0, 0, MAX_INIT_CODE_SIZE + 1, 0);
+        } else {
+            code.op_create(0, 0, MAX_INIT_CODE_SIZE + 1);
+        }
+        code
+    }
+
+    fn run_test<const NACC: usize>(ctx: TestContext<NACC, 1>) {
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn error_max_init_code_size_root() {
+        for is_create2 in [false, true] {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                account_0_code_account_1_no_code(create_code(is_create2)),
+                tx_from_1_to_0,
+                |block, _tx| block.hardfork(Hardfork::Shanghai),
+            )
+            .unwrap();
+
+            run_test(ctx);
+        }
+    }
+
+    #[test]
+    fn error_max_init_code_size_internal() {
+        for is_create2 in [false, true] {
+            let callee = Account {
+                address: Address::repeat_byte(0xff),
+                code: create_code(is_create2).to_vec().into(),
+                ..Default::default()
+            };
+            let caller_code = bytecode! {
+                PUSH1(0) // ret_size
+                PUSH1(0) // ret_offset
+                PUSH1(0) // args_size
+                PUSH1(0) // args_offset
+                PUSH1(0) // value
+                PUSH32(callee.address.to_word())
+                PUSH32(Word::from(100_000)) // gas
+                CALL
+                STOP
+            };
+            let ctx = TestContext::<3, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(MOCK_ACCOUNTS[0])
+                        .balance(eth(10))
+                        .code(caller_code);
+                    accs[1].address(MOCK_ACCOUNTS[1]).balance(eth(10));
+                    accs[2].account(&callee);
+                },
+                |mut txs, accs| {
+                    txs[0].from(accs[1].address).to(accs[0].address);
+                },
+                |block, _tx| block.hardfork(Hardfork::Shanghai),
+            )
+            .unwrap();
+
+            run_test(ctx);
+        }
+    }
+}
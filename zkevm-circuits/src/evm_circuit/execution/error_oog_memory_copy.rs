@@ -24,7 +24,8 @@ use halo2_proofs::{circuit::Value, plonk::Error};
 
 /// Gadget to implement the corresponding out of gas errors for
 /// [`OpcodeId::CALLDATACOPY`], [`OpcodeId::CODECOPY`],
-/// [`OpcodeId::EXTCODECOPY`] and [`OpcodeId::RETURNDATACOPY`].
+/// [`OpcodeId::EXTCODECOPY`], [`OpcodeId::RETURNDATACOPY`] and
+/// [`OpcodeId::MCOPY`].
 #[derive(Clone, Debug)]
 pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     opcode: Cell<F>,
@@ -35,12 +36,16 @@ pub(crate) struct ErrorOOGMemoryCopyGadget<F> {
     external_address: Word<F>,
     /// Source offset
     src_offset: Word<F>,
+    /// Source offset and size to copy for `MCOPY`, which also expands the
+    /// memory to the source range
+    src_memory_addr: MemoryAddressGadget<F>,
     /// Destination offset and size to copy
     dst_memory_addr: MemoryAddressGadget<F>,
-    memory_expansion: MemoryExpansionGadget<F, 1, N_BYTES_MEMORY_WORD_SIZE>,
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
     memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
     insufficient_gas: LtGadget<F, N_BYTES_GAS>,
     is_extcodecopy: IsZeroGadget<F>,
+    is_mcopy: IsZeroGadget<F>,
     common_error_gadget: CommonErrorGadget<F>,
 }
 
@@ -52,17 +57,19 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
         let opcode = cb.query_cell();
         cb.require_in_set(
-            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY or RETURNDATACOPY",
+            "ErrorOutOfGasMemoryCopy opcode must be CALLDATACOPY, CODECOPY, EXTCODECOPY, RETURNDATACOPY or MCOPY",
             opcode.expr(),
             vec![
                 OpcodeId::CALLDATACOPY.expr(),
                 OpcodeId::CODECOPY.expr(),
                 OpcodeId::EXTCODECOPY.expr(),
                 OpcodeId::RETURNDATACOPY.expr(),
+                OpcodeId::MCOPY.expr(),
             ],
         );
 
         let dst_offset = cb.query_cell_phase2();
+        let src_memory_offset = cb.query_cell_phase2();
         let src_offset = cb.query_word_rlc();
         let copy_size = cb.query_word_rlc();
         let external_address = cb.query_word_rlc();
@@ -71,6 +78,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
 
         let is_extcodecopy =
             IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::EXTCODECOPY.expr());
+        let is_mcopy = IsZeroGadget::construct(cb, opcode.expr() - OpcodeId::MCOPY.expr());
 
         cb.condition(is_extcodecopy.expr(), |cb| {
             cb.call_context_lookup(false.expr(), None, CallContextFieldTag::TxId, tx_id.expr());
@@ -90,8 +98,25 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
         cb.stack_pop(src_offset.expr());
         cb.stack_pop(copy_size.expr());
 
+        // MCOPY reads the source from memory, so the source offset is a memory
+        // address as well.
+        cb.condition(is_mcopy.expr(), |cb| {
+            cb.require_equal(
+                "MCOPY source memory offset is the source offset",
+                src_memory_offset.expr(),
+                src_offset.expr(),
+            );
+        });
+        let src_memory_addr =
+            MemoryAddressGadget::construct(cb, src_memory_offset, copy_size.clone());
         let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_offset, copy_size);
-        let memory_expansion = MemoryExpansionGadget::construct(cb, [dst_memory_addr.address()]);
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [
+                dst_memory_addr.address(),
+                is_mcopy.expr() * src_memory_addr.address(),
+            ],
+        );
         let memory_copier_gas = MemoryCopierGasGadget::construct(
             cb,
             dst_memory_addr.length(),
@@ -137,11 +162,13 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             tx_id,
             external_address,
             src_offset,
+            src_memory_addr,
             dst_memory_addr,
             memory_expansion,
             memory_copier_gas,
             insufficient_gas,
             is_extcodecopy,
+            is_mcopy,
             common_error_gadget,
         }
     }
@@ -157,6 +184,7 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
     ) -> Result<(), Error> {
         let opcode = step.opcode().unwrap();
         let is_extcodecopy = opcode == OpcodeId::EXTCODECOPY;
+        let is_mcopy = opcode == OpcodeId::MCOPY;
 
         log::debug!(
             "ErrorOutOfGasMemoryCopy: opcode = {}, gas_left = {}, gas_cost = {}",
@@ -188,12 +216,21 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             .assign(region, offset, Some(external_address.to_le_bytes()))?;
         self.src_offset
             .assign(region, offset, Some(src_offset.to_le_bytes()))?;
+        let src_memory_addr = self.src_memory_addr.assign(
+            region,
+            offset,
+            if is_mcopy { src_offset } else { U256::zero() },
+            copy_size,
+        )?;
         let memory_addr = self
             .dst_memory_addr
             .assign(region, offset, dst_offset, copy_size)?;
-        let (_, memory_expansion_cost) =
-            self.memory_expansion
-                .assign(region, offset, step.memory_word_size(), [memory_addr])?;
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [memory_addr, if is_mcopy { src_memory_addr } else { 0 }],
+        )?;
         let memory_copier_gas = self.memory_copier_gas.assign(
             region,
             offset,
@@ -220,6 +257,11 @@ impl<F: Field> ExecutionGadget<F> for ErrorOOGMemoryCopyGadget<F> {
             offset,
             F::from(opcode.as_u64()) - F::from(OpcodeId::EXTCODECOPY.as_u64()),
         )?;
+        self.is_mcopy.assign(
+            region,
+            offset,
+            F::from(opcode.as_u64()) - F::from(OpcodeId::MCOPY.as_u64()),
+        )?;
         self.common_error_gadget.assign(
             region,
             offset,
@@ -242,8 +284,11 @@ mod tests {
         evm_circuit::test::{rand_bytes, rand_word},
         test_util::CircuitTestBuilder,
     };
+    use bus_mapping::circuit_input_builder::CircuitsParams;
     use eth_types::{
-        bytecode, evm_types::gas_utils::memory_copier_gas_cost, Bytecode, ToWord, U256,
+        bytecode,
+        evm_types::{gas_utils::memory_copier_gas_cost, Hardfork},
+        Bytecode, ToWord, U256,
     };
     use itertools::Itertools;
     use mock::{
@@ -272,6 +317,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_oog_memory_copy_for_mcopy() {
+        for (src_offset, (dst_offset, copy_size)) in [0x00, 0x40, 0x3000]
+            .iter()
+            .cartesian_product(TESTING_DST_OFFSET_COPY_SIZE_PAIRS.iter())
+        {
+            let testing_data = TestingData::new_for_mcopy(*src_offset, *dst_offset, *copy_size);
+
+            test_root(&testing_data);
+            test_internal(&testing_data);
+        }
+    }
+
     #[test]
     fn test_oog_memory_copy_for_extcodecopy() {
         for (is_warm, (dst_offset, copy_size)) in [false, true]
@@ -288,6 +346,7 @@ mod tests {
     struct TestingData {
         bytecode: Bytecode,
         gas_cost: u64,
+        hardfork: Hardfork,
     }
 
     impl TestingData {
@@ -305,7 +364,38 @@ mod tests {
                 + opcode.constant_gas_cost()
                 + memory_copier_gas_cost(0, memory_word_size, copy_size);
 
-            Self { bytecode, gas_cost }
+            Self {
+                bytecode,
+                gas_cost,
+                hardfork: Hardfork::default(),
+            }
+        }
+
+        pub fn new_for_mcopy(src_offset: u64, dst_offset: u64, copy_size: u64) -> Self {
+            let bytecode = bytecode! {
+                PUSH32(copy_size)
+                PUSH32(src_offset)
+                PUSH32(dst_offset)
+                MCOPY
+            };
+
+            // MCOPY expands the memory to cover both the source and the
+            // destination.
+            let memory_word_size = if copy_size == 0 {
+                0
+            } else {
+                (src_offset.max(dst_offset) + copy_size + 31) / 32
+            };
+
+            let gas_cost = OpcodeId::PUSH32.constant_gas_cost() * 3
+                + OpcodeId::MCOPY.constant_gas_cost()
+                + memory_copier_gas_cost(0, memory_word_size, copy_size);
+
+            Self {
+                bytecode,
+                gas_cost,
+                hardfork: Hardfork::Cancun,
+            }
         }
 
         pub fn new_for_extcodecopy(is_warm: bool, dst_offset: u64, copy_size: u64) -> Self {
@@ -339,7 +429,11 @@ mod tests {
                     + memory_copier_gas_cost(memory_word_size, memory_word_size, copy_size);
             }
 
-            Self { bytecode, gas_cost }
+            Self {
+                bytecode,
+                gas_cost,
+                hardfork: Hardfork::default(),
+            }
         }
     }
 
@@ -354,11 +448,16 @@ mod tests {
                     .to(accs[0].address)
                     .gas((GasCost::TX + testing_data.gas_cost - 1).into());
             },
-            |block, _tx| block.number(0xcafe_u64),
+            |block, _tx| block.number(0xcafe_u64).hardfork(testing_data.hardfork),
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: testing_data.hardfork,
+                ..Default::default()
+            })
+            .run();
     }
 
     fn test_internal(testing_data: &TestingData) {
@@ -397,10 +496,15 @@ mod tests {
             |mut txs, accs| {
                 txs[0].from(accs[2].address).to(accs[1].address);
             },
-            |block, _tx| block,
+            |block, _tx| block.hardfork(testing_data.hardfork),
         )
         .unwrap();
 
-        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: testing_data.hardfork,
+                ..Default::default()
+            })
+            .run();
     }
 }
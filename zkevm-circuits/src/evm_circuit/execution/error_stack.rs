@@ -74,11 +74,11 @@ mod test {
     use crate::test_util::CircuitTestBuilder;
     use bus_mapping::{circuit_input_builder::CircuitsParams, evm::OpcodeId};
     use eth_types::{
-        self, address, bytecode, bytecode::Bytecode, geth_types::Account, Address, ToWord, Word,
-        U64,
+        self, address, bytecode, bytecode::Bytecode, evm_types::Hardfork, geth_types::Account,
+        Address, ToWord, Word, U64,
     };
 
-    use mock::TestContext;
+    use mock::{test_ctx::helpers::*, TestContext};
 
     fn test_stack_underflow(value: Word) {
         let bytecode = bytecode! {
@@ -102,10 +102,11 @@ mod test {
 
     #[test]
     fn stack_overflow_simple() {
-        test_stack_overflow(OpcodeId::PUSH1, &[123]);
+        test_stack_overflow(OpcodeId::PUSH1, &[123], Hardfork::default());
         test_stack_overflow(
             OpcodeId::PUSH10,
             &[2, 10, 2, 5, 22, 100, 124, 210, 156, 120],
+            Hardfork::default(),
         );
         test_stack_overflow(
             OpcodeId::PUSH20,
@@ -113,10 +114,16 @@ mod test {
                 2, 10, 2, 5, 22, 100, 124, 210, 156, 120, 10, 28, 37, 87, 211, 255, 212, 60, 76,
                 119,
             ],
+            Hardfork::default(),
         );
     }
 
-    fn test_stack_overflow(opcode: OpcodeId, bytes: &[u8]) {
+    #[test]
+    fn stack_overflow_push0() {
+        test_stack_overflow(OpcodeId::PUSH0, &[], Hardfork::Shanghai);
+    }
+
+    fn test_stack_overflow(opcode: OpcodeId, bytes: &[u8], hardfork: Hardfork) {
         assert!(bytes.len() == opcode.data_len());
         let mut bytecode = bytecode! {
             .write_op(opcode)
@@ -134,14 +141,21 @@ mod test {
         // append final stop op code
         bytecode.op_stop();
 
-        CircuitTestBuilder::new_from_test_ctx(
-            TestContext::<2, 1>::simple_ctx_with_bytecode(bytecode).unwrap(),
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(bytecode),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(hardfork),
         )
-        .params(CircuitsParams {
-            max_rws: 2048,
-            ..Default::default()
-        })
-        .run();
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                max_rws: 2048,
+                hardfork,
+                ..Default::default()
+            })
+            .run();
     }
 
     #[derive(Clone, Copy, Debug, Default)]
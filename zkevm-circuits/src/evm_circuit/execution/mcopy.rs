@@ -0,0 +1,238 @@
+use bus_mapping::{circuit_input_builder::CopyDataType, evm::OpcodeId};
+use eth_types::{evm_types::GasCost, Field, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+use crate::{
+    evm_circuit::{
+        param::N_BYTES_MEMORY_WORD_SIZE,
+        step::ExecutionState,
+        util::{
+            common_gadget::SameContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition, Transition,
+            },
+            memory_gadget::{MemoryAddressGadget, MemoryCopierGasGadget, MemoryExpansionGadget},
+            not, CachedRegion, Cell,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+
+use super::ExecutionGadget;
+
+#[derive(Clone, Debug)]
+pub(crate) struct MCopyGadget<F> {
+    same_context: SameContextGadget<F>,
+    /// The memory address in the current context from where we read.
+    src_memory_addr: MemoryAddressGadget<F>,
+    /// The memory address in the current context to which we write. It
+    /// shares the size of the source memory address.
+    dst_memory_addr: MemoryAddressGadget<F>,
+    /// Opcode MCOPY has a dynamic gas cost:
+    /// gas_code = static_gas * minimum_word_size + memory_expansion_cost
+    /// where the memory is expanded to cover both the source and the
+    /// destination.
+    memory_expansion: MemoryExpansionGadget<F, 2, N_BYTES_MEMORY_WORD_SIZE>,
+    /// Opcode MCOPY needs to copy memory bytes into memory. We account for
+    /// the copying costs using the memory copier gas gadget.
+    memory_copier_gas: MemoryCopierGasGadget<F, { GasCost::COPY }>,
+    /// RW inverse counter from the copy table at the start of related copy
+    /// steps.
+    copy_rwc_inc: Cell<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for MCopyGadget<F> {
+    const NAME: &'static str = "MCOPY";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::MCOPY;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+
+        let dst_memory_offset = cb.query_cell_phase2();
+        let src_memory_offset = cb.query_cell_phase2();
+        let size = cb.query_word_rlc();
+
+        // Pop items from stack.
+        cb.stack_pop(dst_memory_offset.expr());
+        cb.stack_pop(src_memory_offset.expr());
+        cb.stack_pop(size.expr());
+
+        // Construct the memory addresses in the source and the destination,
+        // both in the memory of the current context.
+        let src_memory_addr = MemoryAddressGadget::construct(cb, src_memory_offset, size.clone());
+        let dst_memory_addr = MemoryAddressGadget::construct(cb, dst_memory_offset, size);
+
+        // Calculate the next memory size and the gas cost for this memory
+        // access. This also accounts for the dynamic gas required to copy bytes to
+        // memory.
+        let memory_expansion = MemoryExpansionGadget::construct(
+            cb,
+            [src_memory_addr.address(), dst_memory_addr.address()],
+        );
+        let memory_copier_gas = MemoryCopierGasGadget::construct(
+            cb,
+            dst_memory_addr.length(),
+            memory_expansion.gas_cost(),
+        );
+
+        let copy_rwc_inc = cb.query_cell();
+        cb.condition(dst_memory_addr.has_length(), |cb| {
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                src_memory_addr.offset(),
+                src_memory_addr.address(),
+                dst_memory_addr.offset(),
+                dst_memory_addr.length(),
+                0.expr(), // for MCOPY, rlc_acc is 0
+                copy_rwc_inc.expr(),
+            );
+        });
+        cb.condition(not::expr(dst_memory_addr.has_length()), |cb| {
+            cb.require_zero(
+                "if no bytes to copy, copy table rwc inc == 0",
+                copy_rwc_inc.expr(),
+            );
+        });
+
+        // Expected state transition.
+        let step_state_transition = StepStateTransition {
+            rw_counter: Transition::Delta(cb.rw_counter_offset()),
+            program_counter: Transition::Delta(1.expr()),
+            stack_pointer: Transition::Delta(3.expr()),
+            memory_word_size: Transition::To(memory_expansion.next_memory_word_size()),
+            gas_left: Transition::Delta(
+                -OpcodeId::MCOPY.constant_gas_cost().expr() - memory_copier_gas.gas_cost(),
+            ),
+            ..Default::default()
+        };
+        let same_context = SameContextGadget::construct(cb, opcode, step_state_transition);
+
+        Self {
+            same_context,
+            src_memory_addr,
+            dst_memory_addr,
+            memory_expansion,
+            memory_copier_gas,
+            copy_rwc_inc,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        self.same_context.assign_exec_step(region, offset, step)?;
+
+        let [dest_offset, src_offset, size] =
+            [0, 1, 2].map(|index| block.get_rws(step, index).stack_value());
+
+        // assign the source and destination memory offsets.
+        let src_memory_address = self
+            .src_memory_addr
+            .assign(region, offset, src_offset, size)?;
+        let dst_memory_address = self
+            .dst_memory_addr
+            .assign(region, offset, dest_offset, size)?;
+
+        // assign to gadgets handling memory expansion cost and copying cost.
+        let (_, memory_expansion_cost) = self.memory_expansion.assign(
+            region,
+            offset,
+            step.memory_word_size(),
+            [src_memory_address, dst_memory_address],
+        )?;
+        self.memory_copier_gas
+            .assign(region, offset, size.as_u64(), memory_expansion_cost)?;
+        // rw_counter increases by `size` reads and `size` writes.
+        self.copy_rwc_inc.assign(
+            region,
+            offset,
+            Value::known(
+                (size + size)
+                    .to_scalar()
+                    .expect("unexpected U256 -> Scalar conversion failure"),
+            ),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, evm_types::Hardfork, Word};
+    use mock::{
+        test_ctx::helpers::{account_0_code_account_1_no_code, tx_from_1_to_0},
+        TestContext,
+    };
+
+    fn test_ok(dst_offset: Word, src_offset: Word, size: usize) {
+        let code = bytecode! {
+            PUSH32(Word::MAX)
+            PUSH1(0x00)
+            MSTORE
+            PUSH32(Word::from(0x0102030405060708u64))
+            PUSH1(0x20)
+            MSTORE
+            PUSH32(size)
+            PUSH32(src_offset)
+            PUSH32(dst_offset)
+            MCOPY
+            STOP
+        };
+
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            account_0_code_account_1_no_code(code),
+            tx_from_1_to_0,
+            |block, _tx| block.hardfork(Hardfork::Cancun),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Cancun,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn mcopy_empty() {
+        test_ok(Word::from(0x20), Word::from(0x00), 0x0);
+        test_ok(Word::MAX, Word::MAX, 0x0);
+    }
+
+    #[test]
+    fn mcopy_non_overlapping() {
+        test_ok(Word::from(0x40), Word::from(0x00), 0x20);
+        test_ok(Word::from(0x00), Word::from(0x20), 0x20);
+    }
+
+    #[test]
+    fn mcopy_overlapping() {
+        // destination after the source
+        test_ok(Word::from(0x10), Word::from(0x00), 0x30);
+        // destination before the source
+        test_ok(Word::from(0x00), Word::from(0x10), 0x30);
+        // destination equal to the source
+        test_ok(Word::from(0x08), Word::from(0x08), 0x20);
+    }
+
+    #[test]
+    fn mcopy_expanding_source() {
+        test_ok(Word::from(0x00), Word::from(0x70), 0x40);
+    }
+}
@@ -0,0 +1,871 @@
+mod ec_add;
+mod ec_mul;
+mod ec_pairing;
+mod ecrecover;
+mod identity;
+mod modexp;
+mod sha256;
+
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_GAS, N_BYTES_MEMORY_ADDRESS, STACK_CAPACITY},
+        step::ExecutionState,
+        util::{
+            common_gadget::RestoreContextGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same, To},
+            },
+            from_bytes,
+            math_gadget::{IsZeroGadget, LtGadget, MinMaxGadget},
+            not, rlc, select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::CallContextFieldTag,
+    util::Expr,
+};
+use bus_mapping::{circuit_input_builder::CopyDataType, precompile::PrecompileCalls};
+use ec_add::EcAddGadget;
+use ec_mul::EcMulGadget;
+use ec_pairing::EcPairingGadget;
+use ecrecover::EcrecoverGadget;
+use eth_types::{Field, ToScalar};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+use identity::IdentityGadget;
+use modexp::ModExpGadget;
+use sha256::Sha256Gadget;
+
+/// Gadget for the call to a precompiled contract, which is executed right
+/// after the *CALL opcode or the BeginTx step that called it.  The input is
+/// copied from the caller memory (or the tx calldata for a root call), and the
+/// output, the gas cost and the validity of the input are constrained by the
+/// gadget of the precompile, against the table of its circuit for the ones
+/// that have one.  The input of the precompiles with a fixed size input, and
+/// of MODEXP, is copied again right-padded with zeros or truncated to the
+/// length they read.  On success the output is copied into the callee memory,
+/// from where it can be read as return data, and into the return area of the
+/// caller memory.  RIPEMD-160 and BLAKE2F have no circuit, so their calls
+/// can't be proven.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileGadget<F, const S: ExecutionState> {
+    is_success: Cell<F>,
+    tx_id: Cell<F>,
+    caller_id: Cell<F>,
+    call_data_offset: Cell<F>,
+    call_data_length: Cell<F>,
+    return_data_offset: Cell<F>,
+    return_data_length: Cell<F>,
+
+    input_rlc: Cell<F>,
+    output_rlc: Cell<F>,
+    output_length: Cell<F>,
+    gas_cost: Cell<F>,
+    is_valid: Cell<F>,
+    insufficient_gas: LtGadget<F, N_BYTES_GAS>,
+
+    input_length_is_zero: IsZeroGadget<F>,
+    padded_input: Option<PaddedInput<F>>,
+    output_length_is_zero: IsZeroGadget<F>,
+    copy_length: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+    copy_length_is_zero: IsZeroGadget<F>,
+
+    rw_counter_end_of_reversion: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+
+    ecrecover: Option<EcrecoverGadget<F>>,
+    sha256: Option<Sha256Gadget<F>>,
+    identity: Option<IdentityGadget<F>>,
+    modexp: Option<ModExpGadget<F>>,
+    ec_add: Option<EcAddGadget<F>>,
+    ec_mul: Option<EcMulGadget<F>>,
+    ec_pairing: Option<EcPairingGadget<F>>,
+}
+
+impl<F: Field, const S: ExecutionState> ExecutionGadget<F> for PrecompileGadget<F, S> {
+    const NAME: &'static str = "PRECOMPILE";
+
+    const EXECUTION_STATE: ExecutionState = S;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let address = Self::EXECUTION_STATE
+            .precompile()
+            .expect("execution state should be a precompile call");
+
+        let [is_success, tx_id, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [
+                CallContextFieldTag::IsSuccess,
+                CallContextFieldTag::TxId,
+                CallContextFieldTag::CallerId,
+                CallContextFieldTag::CallDataOffset,
+                CallContextFieldTag::CallDataLength,
+                CallContextFieldTag::ReturnDataOffset,
+                CallContextFieldTag::ReturnDataLength,
+            ]
+            .map(|field_tag| cb.call_context(None, field_tag));
+        let is_root = cb.curr.state.is_root.expr();
+
+        let input_rlc = cb.query_cell_phase2();
+        let output_rlc = cb.query_cell_phase2();
+        let output_length = cb.query_cell();
+        let gas_cost = cb.query_cell();
+        let is_valid = cb.query_bool();
+
+        // Copy the input from the tx calldata or the caller memory, and copy it
+        // again right-padded with zeros or truncated for the precompiles with a
+        // fixed size input and MODEXP, where the padding isn't read from memory.
+        let input_length_is_zero = IsZeroGadget::construct(cb, call_data_length.expr());
+        let src_id = select::expr(is_root.expr(), tx_id.expr(), caller_id.expr());
+        let src_tag = select::expr(
+            is_root.expr(),
+            CopyDataType::TxCalldata.expr(),
+            CopyDataType::Memory.expr(),
+        );
+        cb.condition(not::expr(input_length_is_zero.expr()), |cb| {
+            cb.copy_table_lookup(
+                src_id.expr(),
+                src_tag.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                call_data_offset.expr(),
+                call_data_offset.expr() + call_data_length.expr(),
+                0.expr(),
+                call_data_length.expr(),
+                input_rlc.expr(),
+                select::expr(is_root.expr(), 0.expr(), call_data_length.expr()),
+            );
+        });
+        cb.condition(input_length_is_zero.expr(), |cb| {
+            cb.require_zero("input_rlc == 0 for empty input", input_rlc.expr());
+        });
+        let padded_input_rlc = address
+            .padded_input_len(&[])
+            .map(|_| cb.query_cell_phase2());
+        let padded_input_rlc_expr = padded_input_rlc.as_ref().map(|rlc| rlc.expr());
+
+        let ecrecover = (address == PrecompileCalls::ECRecover).then(|| {
+            EcrecoverGadget::construct(
+                cb,
+                padded_input_rlc_expr.clone().unwrap(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let sha256 = (address == PrecompileCalls::Sha256).then(|| {
+            Sha256Gadget::construct(
+                cb,
+                input_rlc.expr(),
+                call_data_length.expr(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let identity = (address == PrecompileCalls::Identity).then(|| {
+            IdentityGadget::construct(
+                cb,
+                input_rlc.expr(),
+                call_data_length.expr(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let modexp = (address == PrecompileCalls::Modexp).then(|| {
+            ModExpGadget::construct(
+                cb,
+                padded_input_rlc_expr.clone().unwrap(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let ec_add = (address == PrecompileCalls::Bn128Add).then(|| {
+            EcAddGadget::construct(
+                cb,
+                padded_input_rlc_expr.clone().unwrap(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let ec_mul = (address == PrecompileCalls::Bn128Mul).then(|| {
+            EcMulGadget::construct(
+                cb,
+                padded_input_rlc_expr.clone().unwrap(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+        let ec_pairing = (address == PrecompileCalls::Bn128Pairing).then(|| {
+            EcPairingGadget::construct(
+                cb,
+                input_rlc.expr(),
+                call_data_length.expr(),
+                output_rlc.expr(),
+                output_length.expr(),
+                gas_cost.expr(),
+                is_valid.expr(),
+            )
+        });
+
+        // RIPEMD-160 and BLAKE2F have no circuit to constrain their output
+        // against, so their calls are rejected rather than left unconstrained.
+        if matches!(
+            address,
+            PrecompileCalls::Ripemd160 | PrecompileCalls::Blake2F
+        ) {
+            cb.require_zero("the precompile has no circuit", 1.expr());
+        }
+
+        // The length of the padded input of MODEXP is given by the lengths of
+        // the base, the exponent and the modulus.
+        let padded_input = padded_input_rlc.map(|rlc| {
+            let padded_length = match &modexp {
+                Some(modexp) => modexp.padded_input_len(),
+                None => address.padded_input_len(&[]).unwrap().expr(),
+            };
+            let num_copied_bytes =
+                MinMaxGadget::construct(cb, call_data_length.expr(), padded_length.clone());
+            cb.condition(not::expr(input_length_is_zero.expr()), |cb| {
+                cb.copy_table_lookup(
+                    src_id.expr(),
+                    src_tag.expr(),
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::RlcAcc.expr(),
+                    call_data_offset.expr(),
+                    call_data_offset.expr() + call_data_length.expr(),
+                    0.expr(),
+                    padded_length,
+                    rlc.expr(),
+                    select::expr(is_root.expr(), 0.expr(), num_copied_bytes.min()),
+                );
+            });
+            cb.condition(input_length_is_zero.expr(), |cb| {
+                cb.require_zero("padded_input_rlc == 0 for empty input", rlc.expr());
+            });
+            PaddedInput {
+                rlc,
+                num_copied_bytes,
+            }
+        });
+
+        // The call succeeds when the input is valid and there is enough gas to
+        // pay for it, otherwise all the gas passed to the call is consumed.
+        let insufficient_gas =
+            LtGadget::construct(cb, cb.curr.state.gas_left.expr(), gas_cost.expr());
+        cb.require_equal(
+            "is_success == is_valid && gas_left >= gas_cost",
+            is_success.expr(),
+            is_valid.expr() * not::expr(insufficient_gas.expr()),
+        );
+        let gas_consumed = select::expr(
+            is_success.expr(),
+            gas_cost.expr(),
+            cb.curr.state.gas_left.expr(),
+        );
+
+        // Copy the output into the callee memory, and then into the return area
+        // of the caller memory.
+        let output_length_is_zero = IsZeroGadget::construct(cb, output_length.expr());
+        let copy_length =
+            MinMaxGadget::construct(cb, return_data_length.expr(), output_length.expr());
+        let copy_length_is_zero = IsZeroGadget::construct(cb, copy_length.min());
+        let is_output_copied =
+            not::expr(is_root.expr()) * is_success.expr() * not::expr(output_length_is_zero.expr());
+        cb.condition(is_output_copied.expr(), |cb| {
+            cb.copy_table_lookup(
+                cb.curr.state.call_id.expr(),
+                CopyDataType::RlcAcc.expr(),
+                cb.curr.state.call_id.expr(),
+                CopyDataType::Memory.expr(),
+                0.expr(),
+                output_length.expr(),
+                0.expr(),
+                output_length.expr(),
+                output_rlc.expr(),
+                output_length.expr(),
+            );
+        });
+        cb.condition(
+            is_output_copied * not::expr(copy_length_is_zero.expr()),
+            |cb| {
+                cb.copy_table_lookup(
+                    cb.curr.state.call_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    caller_id.expr(),
+                    CopyDataType::Memory.expr(),
+                    0.expr(),
+                    output_length.expr(),
+                    return_data_offset.expr(),
+                    copy_length.min(),
+                    0.expr(),
+                    copy_length.min() + copy_length.min(),
+                );
+            },
+        );
+
+        let rw_counter_end_of_reversion = cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.call_context_lookup(false.expr(), None, CallContextFieldTag::IsSuccess, 0.expr());
+            cb.call_context(None, CallContextFieldTag::RwCounterEndOfReversion)
+        });
+
+        cb.condition(is_root.expr(), |cb| {
+            cb.require_next_state(ExecutionState::EndTx);
+            cb.require_step_state_transition(StepStateTransition {
+                call_id: Same,
+                rw_counter: Delta(
+                    cb.rw_counter_offset()
+                        + not::expr(is_success.expr())
+                            * cb.curr.state.reversible_write_counter.expr(),
+                ),
+                program_counter: To(0.expr()),
+                stack_pointer: To(STACK_CAPACITY.expr()),
+                gas_left: Delta(-gas_consumed.expr()),
+                memory_word_size: To(0.expr()),
+                reversible_write_counter: To(0.expr()),
+                ..StepStateTransition::default()
+            });
+        });
+
+        // The gas consumed by the precompile is passed in place of the memory
+        // expansion cost, so that only the gas left is refunded to the caller.
+        let restore_context = cb.condition(not::expr(is_root.expr()), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                is_success.expr(),
+                0.expr(),
+                0.expr(),
+                is_success.expr() * output_length.expr(),
+                gas_consumed,
+                0.expr(),
+            )
+        });
+
+        cb.condition(not::expr(is_success.expr()), |cb| {
+            cb.require_equal(
+                "rw_counter_end_of_reversion = rw_counter_end_of_step + reversible_counter",
+                rw_counter_end_of_reversion.expr(),
+                cb.curr.state.rw_counter.expr() + cb.rw_counter_offset() - 1.expr()
+                    + cb.curr.state.reversible_write_counter.expr(),
+            );
+        });
+
+        Self {
+            is_success,
+            tx_id,
+            caller_id,
+            call_data_offset,
+            call_data_length,
+            return_data_offset,
+            return_data_length,
+            input_rlc,
+            output_rlc,
+            output_length,
+            gas_cost,
+            is_valid,
+            insufficient_gas,
+            input_length_is_zero,
+            padded_input,
+            output_length_is_zero,
+            copy_length,
+            copy_length_is_zero,
+            rw_counter_end_of_reversion,
+            restore_context,
+            ecrecover,
+            sha256,
+            identity,
+            modexp,
+            ec_add,
+            ec_mul,
+            ec_pairing,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let precompile = Self::EXECUTION_STATE
+            .precompile()
+            .expect("execution state should be a precompile call");
+
+        let [is_success, tx_id, caller_id, call_data_offset, call_data_length, return_data_offset, return_data_length] =
+            [0, 1, 2, 3, 4, 5, 6].map(|idx| block.get_rws(step, idx).call_context_value());
+        for (cell, value) in [
+            (&self.is_success, is_success),
+            (&self.tx_id, tx_id),
+            (&self.caller_id, caller_id),
+            (&self.call_data_offset, call_data_offset),
+            (&self.call_data_length, call_data_length),
+            (&self.return_data_offset, return_data_offset),
+            (&self.return_data_length, return_data_length),
+        ] {
+            cell.assign(region, offset, Value::known(value.to_scalar().unwrap()))?;
+        }
+        let mut rw_offset = 7;
+
+        let input_length = call_data_length.as_usize();
+        let input: Vec<u8> = if call.is_root {
+            let call_data_offset = call_data_offset.as_usize();
+            tx.call_data[call_data_offset..call_data_offset + input_length].to_vec()
+        } else {
+            let input = (rw_offset..rw_offset + input_length)
+                .map(|idx| block.get_rws(step, idx).memory_value())
+                .collect();
+            rw_offset += input_length;
+            input
+        };
+        if let Some(padded_input) = &self.padded_input {
+            let padded_length = precompile.padded_input_len(&input).unwrap();
+            let mut padded_bytes = input.clone();
+            padded_bytes.resize(padded_length, 0);
+            let rlc = region
+                .challenges()
+                .keccak_input()
+                .map(|randomness| rlc::value(padded_bytes.iter().rev(), randomness));
+            padded_input.rlc.assign(region, offset, rlc)?;
+            padded_input.num_copied_bytes.assign(
+                region,
+                offset,
+                F::from(input_length as u64),
+                F::from(padded_length as u64),
+            )?;
+            if !call.is_root {
+                rw_offset += input_length.min(padded_length);
+            }
+        }
+        let event = block
+            .precompile_events
+            .iter()
+            .find(|event| event.kind == precompile && event.input == input)
+            .expect("precompile event should exist for the call");
+
+        let [input_rlc, output_rlc] = [&input, &event.output].map(|bytes| {
+            region
+                .challenges()
+                .keccak_input()
+                .map(|randomness| rlc::value(bytes.iter().rev(), randomness))
+        });
+        self.input_rlc.assign(region, offset, input_rlc)?;
+        self.output_rlc.assign(region, offset, output_rlc)?;
+        self.output_length.assign(
+            region,
+            offset,
+            Value::known(F::from(event.output.len() as u64)),
+        )?;
+        self.gas_cost
+            .assign(region, offset, Value::known(F::from(event.gas_cost)))?;
+        self.is_valid.assign(
+            region,
+            offset,
+            Value::known(F::from(event.is_success as u64)),
+        )?;
+        if let Some(ecrecover) = &self.ecrecover {
+            ecrecover.assign(region, offset, &input, &event.output)?;
+        }
+        if let Some(sha256) = &self.sha256 {
+            sha256.assign(region, offset, &input, &event.output)?;
+        }
+        if let Some(identity) = &self.identity {
+            identity.assign(region, offset, &input)?;
+        }
+        if let Some(modexp) = &self.modexp {
+            modexp.assign(region, offset, &input, &event.output)?;
+        }
+        if let Some(ec_add) = &self.ec_add {
+            ec_add.assign(region, offset, &input, &event.output)?;
+        }
+        if let Some(ec_mul) = &self.ec_mul {
+            ec_mul.assign(region, offset, &input, &event.output)?;
+        }
+        if let Some(ec_pairing) = &self.ec_pairing {
+            ec_pairing.assign(region, offset, &input, &event.output)?;
+        }
+        self.insufficient_gas.assign(
+            region,
+            offset,
+            F::from(step.gas_left),
+            F::from(event.gas_cost),
+        )?;
+
+        self.input_length_is_zero
+            .assign(region, offset, F::from(input_length as u64))?;
+        self.output_length_is_zero
+            .assign(region, offset, F::from(event.output.len() as u64))?;
+        let (copy_length, _) = self.copy_length.assign(
+            region,
+            offset,
+            F::from(return_data_length.as_u64()),
+            F::from(event.output.len() as u64),
+        )?;
+        self.copy_length_is_zero
+            .assign(region, offset, copy_length)?;
+
+        if !call.is_root && call.is_success {
+            let copy_length = event.output.len().min(return_data_length.as_usize());
+            rw_offset += event.output.len() + 2 * copy_length;
+        }
+        if !call.is_success {
+            rw_offset += 2;
+        }
+        self.rw_counter_end_of_reversion.assign(
+            region,
+            offset,
+            Value::known(F::from(call.rw_counter_end_of_reversion as u64)),
+        )?;
+        self.restore_context
+            .assign(region, offset, block, call, step, rw_offset)
+    }
+}
+
+/// Copy of the input of a precompile with a fixed size input or of MODEXP,
+/// right-padded with zeros or truncated to the length it reads.
+#[derive(Clone, Debug)]
+struct PaddedInput<F> {
+    rlc: Cell<F>,
+    /// Number of bytes of the input in the copy, the rest are padding.
+    num_copied_bytes: MinMaxGadget<F, N_BYTES_MEMORY_ADDRESS>,
+}
+
+/// Lo and hi 128 bits of a word, as split in the tables of the precompile
+/// circuits.
+fn word_lo_hi<F: Field>(word: &Word<F>) -> [Expression<F>; 2] {
+    [
+        from_bytes::expr(&word.cells[..16]),
+        from_bytes::expr(&word.cells[16..]),
+    ]
+}
+
+/// RLC of the concatenation of words, which is accumulated in reverse order
+/// with the keccak input challenge, so the last byte of the last word comes
+/// first.
+fn words_rlc<F: Field>(words: &[&Word<F>], keccak_input: Expression<F>) -> Expression<F> {
+    let bytes = words
+        .iter()
+        .rev()
+        .flat_map(|word| word.cells.iter().map(|cell| cell.expr()))
+        .collect::<Vec<_>>();
+    rlc::expr(&bytes, keccak_input)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::{evm::OpcodeId, precompile::PrecompileCalls};
+    use eth_types::{bytecode, word, Address, Bytecode, ToBigEndian, ToWord, Word};
+    use mock::TestContext;
+
+    fn call_precompile(
+        precompile: PrecompileCalls,
+        input: &[u8],
+        return_data_length: usize,
+        gas: Word,
+        value: Word,
+    ) -> Bytecode {
+        let mut code = Bytecode::default();
+        for (idx, chunk) in input.chunks(32).enumerate() {
+            let mut word = [0u8; 32];
+            word[..chunk.len()].copy_from_slice(chunk);
+            code.push(32, Word::from_big_endian(&word));
+            code.push(32, Word::from(idx * 32));
+            code.write_op(OpcodeId::MSTORE);
+        }
+        code.append(&bytecode! {
+            PUSH32(Word::from(return_data_length))
+            PUSH1(0x20) // retOffset
+            PUSH32(Word::from(input.len()))
+            PUSH1(0x00) // argsOffset
+            PUSH32(value)
+            PUSH20(Address::from(precompile).to_word())
+            PUSH32(gas)
+            CALL
+            RETURNDATASIZE
+            PUSH1(0x00)
+            PUSH1(0x00)
+            RETURNDATACOPY
+            STOP
+        });
+        code
+    }
+
+    fn test_ok(code: Bytecode) {
+        CircuitTestBuilder::new_from_test_ctx(
+            TestContext::<2, 1>::simple_ctx_with_bytecode(code).unwrap(),
+        )
+        .run();
+    }
+
+    #[test]
+    fn precompile_identity() {
+        test_ok(call_precompile(
+            PrecompileCalls::Identity,
+            &[0xab; 40],
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+    }
+
+    #[test]
+    fn precompile_identity_empty_input() {
+        test_ok(call_precompile(
+            PrecompileCalls::Identity,
+            &[],
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+    }
+
+    #[test]
+    fn precompile_identity_with_value() {
+        test_ok(call_precompile(
+            PrecompileCalls::Identity,
+            &[0x01, 0x02, 0x03],
+            0x40,
+            word!("0xffff"),
+            Word::from(1u64),
+        ));
+    }
+
+    #[test]
+    fn precompile_sha256() {
+        // The gas cost is charged for every word of the input.
+        for input in [&b"abc"[..], &[][..], &[0x5a; 70][..]] {
+            test_ok(call_precompile(
+                PrecompileCalls::Sha256,
+                input,
+                0x10,
+                word!("0xffff"),
+                Word::zero(),
+            ));
+        }
+    }
+
+    fn modexp_input(base: &[u8], exponent: &[u8], modulus: &[u8]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for field in [base, exponent, modulus] {
+            input.extend_from_slice(&Word::from(field.len()).to_be_bytes());
+        }
+        input.extend_from_slice(base);
+        input.extend_from_slice(exponent);
+        input.extend_from_slice(modulus);
+        input
+    }
+
+    #[test]
+    fn precompile_modexp() {
+        let input = modexp_input(&[0x03], &[0x12, 0x34], &[0x01, 0x00, 0x01]);
+        let mut long_input = input.clone();
+        long_input.extend_from_slice(&[0xff; 10]);
+        for input in [
+            input.clone(),
+            modexp_input(&[0xff; 32], &[0xfe; 32], &[0x7f; 32]),
+            // Zero modulus, empty exponent and base greater than the modulus
+            modexp_input(&[0x02], &[0x05], &[0x00, 0x00]),
+            modexp_input(&[0x08, 0x00], &[], &[0x07]),
+            // The input is padded with zeros or truncated to the lengths.
+            input[..100].to_vec(),
+            input[..80].to_vec(),
+            long_input,
+        ] {
+            test_ok(call_precompile(
+                PrecompileCalls::Modexp,
+                &input,
+                0x20,
+                word!("0xffff"),
+                Word::zero(),
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_ecrecover() {
+        let mut input = hex::decode(
+            "18c547e4f7b0f325ad1e56f57e26c745b09a3e503d86e00e5255ff7f715d3d1c\
+             000000000000000000000000000000000000000000000000000000000000001c\
+             73b1693892219d736caba55bdb67216e485557ea6b6af75f37096c9aa6a5a75f\
+             eeb940b1d03b21e36b0e47e79769f095fe2ab855bd91e3a38756b7d75a9c4549",
+        )
+        .unwrap();
+        test_ok(call_precompile(
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+
+        // An invalid v recovers no address and returns an empty output.
+        input[63] = 29;
+        test_ok(call_precompile(
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+
+        // A message hash not less than the secp256k1 order is reduced.
+        input[63] = 28;
+        input[..32].copy_from_slice(
+            &hex::decode("fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0365141")
+                .unwrap(),
+        );
+        test_ok(call_precompile(
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+
+        // R = G and s == msg_hash recover the point at infinity, so no address.
+        let input = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             000000000000000000000000000000000000000000000000000000000000001b\
+             79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+             0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        test_ok(call_precompile(
+            PrecompileCalls::ECRecover,
+            &input,
+            0x20,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+    }
+
+    const G1: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+                      0000000000000000000000000000000000000000000000000000000000000002";
+
+    #[test]
+    fn precompile_ec_add() {
+        let input = hex::decode(G1.repeat(2)).unwrap();
+        // The input is padded with zeros or truncated to 128 bytes.
+        let mut long_input = input.clone();
+        long_input.extend_from_slice(&[0xff; 20]);
+        for input in [&input[..], &input[..100], &long_input[..]] {
+            test_ok(call_precompile(
+                PrecompileCalls::Bn128Add,
+                input,
+                0x40,
+                word!("0xffff"),
+                Word::zero(),
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_ec_mul() {
+        let mut input = hex::decode(G1).unwrap();
+        input.extend_from_slice(&Word::from(2).to_be_bytes());
+        // The input is padded with zeros or truncated to 96 bytes.
+        let mut long_input = input.clone();
+        long_input.extend_from_slice(&[0xff; 54]);
+        for input in [&input[..], &input[..64], &long_input[..]] {
+            test_ok(call_precompile(
+                PrecompileCalls::Bn128Mul,
+                input,
+                0x40,
+                word!("0xffff"),
+                Word::zero(),
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_ec_pairing() {
+        let g2 = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+                  1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+                  090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+                  12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+        let g1_neg = "0000000000000000000000000000000000000000000000000000000000000001\
+                      30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+        // e(G1, G2) * e(-G1, G2) == 1, and the empty input.
+        for input in [[G1, g2, g1_neg, g2].concat(), String::new()] {
+            test_ok(call_precompile(
+                PrecompileCalls::Bn128Pairing,
+                &hex::decode(input).unwrap(),
+                0x20,
+                word!("0xffffff"),
+                Word::zero(),
+            ));
+        }
+    }
+
+    #[test]
+    fn precompile_out_of_gas() {
+        test_ok(call_precompile(
+            PrecompileCalls::Sha256,
+            &[0xff; 100],
+            0x20,
+            Word::from(10u64),
+            Word::zero(),
+        ));
+    }
+
+    #[test]
+    fn precompile_invalid_input() {
+        // The input of the bn256 addition is not a point on the curve.
+        test_ok(call_precompile(
+            PrecompileCalls::Bn128Add,
+            &[0x01; 128],
+            0x40,
+            word!("0xffff"),
+            Word::zero(),
+        ));
+        // The length of the input of the bn256 pairing is not a multiple of
+        // 192 bytes.
+        test_ok(call_precompile(
+            PrecompileCalls::Bn128Pairing,
+            &[0x00; 100],
+            0x20,
+            word!("0xffffff"),
+            Word::zero(),
+        ));
+    }
+
+    #[test]
+    fn precompile_root_call() {
+        let ctx = TestContext::<2, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(Address::from(PrecompileCalls::Identity))
+                    .balance(Word::from(1u64));
+                accs[1]
+                    .address(Address::repeat_byte(0xfe))
+                    .balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[1].address)
+                    .to(accs[0].address)
+                    .input(vec![0x01, 0x02, 0x03].into());
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
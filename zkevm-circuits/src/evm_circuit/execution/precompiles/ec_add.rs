@@ -0,0 +1,96 @@
+use super::{word_lo_hi, words_rlc};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        CachedRegion, Word,
+    },
+    util::Expr,
+};
+use bus_mapping::precompile::EcAddAuxData;
+use eth_types::{Field, ToLittleEndian};
+use halo2_proofs::plonk::{Error, Expression};
+
+/// Gadget for the ecAdd precompile, which parses the input into two points and
+/// looks up whether they are valid and their sum in the ecAdd table.  The
+/// output is the 64 bytes of the sum, and the gas cost follows EIP-1108.
+#[derive(Clone, Debug)]
+pub(crate) struct EcAddGadget<F> {
+    p_x: Word<F>,
+    p_y: Word<F>,
+    q_x: Word<F>,
+    q_y: Word<F>,
+    r_x: Word<F>,
+    r_y: Word<F>,
+}
+
+impl<F: Field> EcAddGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        padded_input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        let [p_x, p_y, q_x, q_y, r_x, r_y] = [(); 6].map(|_| cb.query_word_rlc());
+
+        let keccak_input = cb.challenges().keccak_input();
+        cb.require_equal(
+            "padded_input_rlc == RLC(p_x || p_y || q_x || q_y)",
+            padded_input_rlc,
+            words_rlc(&[&p_x, &p_y, &q_x, &q_y], keccak_input.clone()),
+        );
+
+        cb.ec_add_table_lookup(
+            [word_lo_hi(&p_x), word_lo_hi(&p_y)],
+            [word_lo_hi(&q_x), word_lo_hi(&q_y)],
+            is_valid.expr(),
+            [word_lo_hi(&r_x), word_lo_hi(&r_y)],
+        );
+        cb.condition(is_valid.expr(), |cb| {
+            cb.require_equal(
+                "output_rlc == RLC(r_x || r_y)",
+                output_rlc,
+                words_rlc(&[&r_x, &r_y], keccak_input),
+            );
+            cb.require_equal("output_len == 64", output_len, 64.expr());
+        });
+        cb.require_equal(
+            "gas_cost == 150 for a valid input, 0 otherwise",
+            gas_cost,
+            150.expr() * is_valid,
+        );
+
+        Self {
+            p_x,
+            p_y,
+            q_x,
+            q_y,
+            r_x,
+            r_y,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        let aux_data = EcAddAuxData::new(input, output);
+
+        for (word, value) in [
+            (&self.p_x, aux_data.p_x),
+            (&self.p_y, aux_data.p_y),
+            (&self.q_x, aux_data.q_x),
+            (&self.q_y, aux_data.q_y),
+            (&self.r_x, aux_data.r_x),
+            (&self.r_y, aux_data.r_y),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+
+        Ok(())
+    }
+}
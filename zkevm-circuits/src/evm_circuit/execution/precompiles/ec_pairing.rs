@@ -0,0 +1,123 @@
+use crate::{
+    evm_circuit::{
+        param::N_BYTES_MEMORY_ADDRESS,
+        util::{
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{IsZeroGadget, LtGadget},
+            not, CachedRegion, Cell,
+        },
+    },
+    util::Expr,
+};
+use bus_mapping::precompile::EC_PAIRING_PAIR_SIZE;
+use eth_types::Field;
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Gadget for the ecPairing precompile, which splits the input length into
+/// the number of pairs of points and a remainder.  The call fails when the
+/// remainder is not zero, otherwise whether the points are valid and the output
+/// are looked up in the ecPairing table from the RLC of the input.  The output
+/// is 1 when the product of the pairings is 1 and 0 otherwise, left-padded to
+/// 32 bytes, and the gas cost follows EIP-1108.
+#[derive(Clone, Debug)]
+pub(crate) struct EcPairingGadget<F> {
+    num_pairs: [Cell<F>; N_BYTES_MEMORY_ADDRESS],
+    input_len_rem: Cell<F>,
+    input_len_rem_lt_pair_size: LtGadget<F, 1>,
+    input_len_rem_is_zero: IsZeroGadget<F>,
+    output: Cell<F>,
+}
+
+impl<F: Field> EcPairingGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        // num_pairs = floor(input_len / EC_PAIRING_PAIR_SIZE)
+        let num_pairs = cb.query_bytes();
+        let num_pairs_expr = from_bytes::expr(&num_pairs);
+        let input_len_rem = cb.query_byte();
+        let input_len_rem_lt_pair_size =
+            LtGadget::construct(cb, input_len_rem.expr(), EC_PAIRING_PAIR_SIZE.expr());
+        cb.require_equal(
+            "input_len == num_pairs * EC_PAIRING_PAIR_SIZE + input_len_rem",
+            input_len,
+            num_pairs_expr.clone() * EC_PAIRING_PAIR_SIZE.expr() + input_len_rem.expr(),
+        );
+        cb.require_equal(
+            "input_len_rem < EC_PAIRING_PAIR_SIZE",
+            input_len_rem_lt_pair_size.expr(),
+            1.expr(),
+        );
+        cb.require_equal(
+            "gas_cost == 45000 + 34000 * num_pairs for a valid input, 0 otherwise",
+            gas_cost,
+            is_valid.expr() * (45000.expr() + 34000.expr() * num_pairs_expr),
+        );
+
+        // The output of a successful call is a boolean, whose RLC is itself.
+        let output = cb.query_bool();
+        let input_len_rem_is_zero = IsZeroGadget::construct(cb, input_len_rem.expr());
+        cb.condition(not::expr(input_len_rem_is_zero.expr()), |cb| {
+            cb.require_zero(
+                "is_valid == 0 when input_len is not a multiple of EC_PAIRING_PAIR_SIZE",
+                is_valid.expr(),
+            );
+        });
+        cb.condition(input_len_rem_is_zero.expr(), |cb| {
+            cb.ec_pairing_table_lookup(input_rlc, is_valid.expr(), output.expr());
+        });
+        cb.condition(is_valid, |cb| {
+            cb.require_equal("output_rlc == output", output_rlc, output.expr());
+            cb.require_equal("output_len == 32", output_len, 32.expr());
+        });
+
+        Self {
+            num_pairs,
+            input_len_rem,
+            input_len_rem_lt_pair_size,
+            input_len_rem_is_zero,
+            output,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        let num_pairs = (input.len() / EC_PAIRING_PAIR_SIZE) as u64;
+        let input_len_rem = (input.len() % EC_PAIRING_PAIR_SIZE) as u64;
+        for (cell, byte) in self.num_pairs.iter().zip(num_pairs.to_le_bytes()) {
+            cell.assign(region, offset, Value::known(F::from(byte as u64)))?;
+        }
+        self.input_len_rem
+            .assign(region, offset, Value::known(F::from(input_len_rem)))?;
+        self.input_len_rem_lt_pair_size.assign(
+            region,
+            offset,
+            F::from(input_len_rem),
+            F::from(EC_PAIRING_PAIR_SIZE as u64),
+        )?;
+        self.input_len_rem_is_zero
+            .assign(region, offset, F::from(input_len_rem))?;
+        self.output.assign(
+            region,
+            offset,
+            Value::known(F::from((output.last() == Some(&1)) as u64)),
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{
+    evm_circuit::{
+        param::N_BYTES_ACCOUNT_ADDRESS,
+        util::{
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{AddWordsGadget, IsEqualGadget, IsZeroGadget, LtWordGadget},
+            not, rlc, sum, CachedRegion, RandomLinearCombination, Word,
+        },
+    },
+    util::Expr,
+};
+use bus_mapping::precompile::EcrecoverAuxData;
+use eth_types::{sign_types::SECP256K1_Q, Field, ToLittleEndian};
+use halo2_proofs::plonk::{Error, Expression};
+
+/// Gadget for the ecRecover precompile, which parses the input into the
+/// message hash and the signature, and looks up the recovered address in the
+/// ecRecover table when the signature is well formed, with the message hash
+/// reduced modulo the secp256k1 order.  The output is the recovered address
+/// left-padded to 32 bytes, or empty when no address is recovered.  Any input
+/// is valid, and the gas cost is always 3000.
+#[derive(Clone, Debug)]
+pub(crate) struct EcrecoverGadget<F> {
+    msg_hash_lt_n: LtWordGadget<F>,
+    msg_hash_reduction: AddWordsGadget<F, 2, true>,
+    sig_v: Word<F>,
+    sig_r: Word<F>,
+    sig_s: Word<F>,
+    recovered_addr: RandomLinearCombination<F, N_BYTES_ACCOUNT_ADDRESS>,
+    recovered_addr_is_zero: IsZeroGadget<F>,
+
+    sig_v_hi_is_zero: IsZeroGadget<F>,
+    sig_v_lo_is_27: IsEqualGadget<F>,
+    sig_v_lo_is_28: IsEqualGadget<F>,
+    sig_r_is_zero: IsZeroGadget<F>,
+    sig_r_lt_n: LtWordGadget<F>,
+    sig_s_is_zero: IsZeroGadget<F>,
+    sig_s_lt_n: LtWordGadget<F>,
+}
+
+impl<F: Field> EcrecoverGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        padded_input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        cb.require_equal("gas_cost == 3000", gas_cost, 3000.expr());
+        cb.require_equal("is_valid == 1", is_valid, 1.expr());
+
+        let [msg_hash, sig_v, sig_r, sig_s] = [(); 4].map(|_| cb.query_word_rlc());
+
+        // The signed scalar is the message hash modulo n, which is the hash
+        // minus n when it's not less than n, as it's less than 2n.
+        let msg_hash_lt_n = LtWordGadget::construct_constant(cb, &msg_hash, secp256k1_n());
+        let msg_hash_reduced = cb.query_word_rlc();
+        let msg_hash_overflow = cb.query_word_rlc();
+        for (cell, n_byte) in msg_hash_overflow
+            .cells
+            .iter()
+            .zip(secp256k1_n().to_le_bytes())
+        {
+            cb.require_equal(
+                "msg_hash_overflow == n when msg_hash >= n, 0 otherwise",
+                cell.expr(),
+                n_byte.expr() * not::expr(msg_hash_lt_n.expr()),
+            );
+        }
+        let msg_hash_reduction = AddWordsGadget::construct(
+            cb,
+            [msg_hash_reduced.clone(), msg_hash_overflow],
+            msg_hash.clone(),
+        );
+
+        // The output is accumulated with the keccak input challenge, where the
+        // 12 leading zero bytes of the address don't contribute.
+        let recovered_addr = cb.query_keccak_rlc();
+        let recovered_addr_expr = from_bytes::expr(&recovered_addr.cells);
+        let recovered_addr_is_zero = IsZeroGadget::construct(cb, recovered_addr_expr.clone());
+
+        // The signature is well formed when v is 27 or 28, and r and s are in
+        // the range [1, n) of the secp256k1 scalars.
+        let sig_v_hi_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_v.cells[1..]));
+        let sig_v_lo_is_27 = IsEqualGadget::construct(cb, sig_v.cells[0].expr(), 27.expr());
+        let sig_v_lo_is_28 = IsEqualGadget::construct(cb, sig_v.cells[0].expr(), 28.expr());
+        let sig_r_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_r.cells));
+        let sig_r_lt_n = LtWordGadget::construct_constant(cb, &sig_r, secp256k1_n());
+        let sig_s_is_zero = IsZeroGadget::construct(cb, sum::expr(&sig_s.cells));
+        let sig_s_lt_n = LtWordGadget::construct_constant(cb, &sig_s, secp256k1_n());
+        let is_well_formed = sig_v_hi_is_zero.expr()
+            * (sig_v_lo_is_27.expr() + sig_v_lo_is_28.expr())
+            * not::expr(sig_r_is_zero.expr())
+            * sig_r_lt_n.expr()
+            * not::expr(sig_s_is_zero.expr())
+            * sig_s_lt_n.expr();
+
+        // No address is recovered from a malformed signature, otherwise the
+        // ecRecover circuit proves whether an address is recovered.
+        cb.condition(is_well_formed.clone(), |cb| {
+            cb.ecrecover_table_lookup(
+                msg_hash_reduced.expr(),
+                sig_v.expr(),
+                sig_r.expr(),
+                sig_s.expr(),
+                recovered_addr_expr.clone(),
+            );
+        });
+        cb.condition(not::expr(is_well_formed), |cb| {
+            cb.require_zero(
+                "recovered_addr == 0 when the signature is malformed",
+                recovered_addr_expr,
+            );
+        });
+
+        // The input is accumulated in reverse order, so the last byte of sig_s
+        // comes first.
+        let input_bytes = [&sig_s, &sig_r, &sig_v, &msg_hash]
+            .iter()
+            .flat_map(|word| word.cells.iter().map(|cell| cell.expr()))
+            .collect::<Vec<_>>();
+        let keccak_input = cb.challenges().keccak_input();
+        cb.require_equal(
+            "padded_input_rlc == RLC(msg_hash || sig_v || sig_r || sig_s)",
+            padded_input_rlc,
+            rlc::expr(&input_bytes, keccak_input),
+        );
+
+        cb.require_equal(
+            "output_rlc == RLC(recovered_addr)",
+            output_rlc,
+            recovered_addr.expr(),
+        );
+        cb.require_equal(
+            "output_len == 32 when an address is recovered, 0 otherwise",
+            output_len,
+            32.expr() * not::expr(recovered_addr_is_zero.expr()),
+        );
+
+        Self {
+            msg_hash_lt_n,
+            msg_hash_reduction,
+            sig_v,
+            sig_r,
+            sig_s,
+            recovered_addr,
+            recovered_addr_is_zero,
+            sig_v_hi_is_zero,
+            sig_v_lo_is_27,
+            sig_v_lo_is_28,
+            sig_r_is_zero,
+            sig_r_lt_n,
+            sig_s_is_zero,
+            sig_s_lt_n,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        let aux_data = EcrecoverAuxData::new(input, output);
+
+        let msg_hash_reduced = aux_data.msg_hash_reduced();
+        self.msg_hash_lt_n
+            .assign(region, offset, aux_data.msg_hash, secp256k1_n())?;
+        self.msg_hash_reduction.assign(
+            region,
+            offset,
+            [msg_hash_reduced, aux_data.msg_hash - msg_hash_reduced],
+            aux_data.msg_hash,
+        )?;
+
+        for (word, value) in [
+            (&self.sig_v, aux_data.sig_v),
+            (&self.sig_r, aux_data.sig_r),
+            (&self.sig_s, aux_data.sig_s),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+        let mut recovered_addr = aux_data.recovered_addr.0;
+        recovered_addr.reverse();
+        self.recovered_addr
+            .assign(region, offset, Some(recovered_addr))?;
+        self.recovered_addr_is_zero
+            .assign(region, offset, from_bytes::value(&recovered_addr))?;
+
+        let sig_v_le = aux_data.sig_v.to_le_bytes();
+        self.sig_v_hi_is_zero.assign(
+            region,
+            offset,
+            sig_v_le[1..]
+                .iter()
+                .fold(F::ZERO, |acc, byte| acc + F::from(*byte as u64)),
+        )?;
+        self.sig_v_lo_is_27
+            .assign(region, offset, F::from(sig_v_le[0] as u64), F::from(27))?;
+        self.sig_v_lo_is_28
+            .assign(region, offset, F::from(sig_v_le[0] as u64), F::from(28))?;
+        for (is_zero, lt_n, value) in [
+            (&self.sig_r_is_zero, &self.sig_r_lt_n, aux_data.sig_r),
+            (&self.sig_s_is_zero, &self.sig_s_lt_n, aux_data.sig_s),
+        ] {
+            is_zero.assign(
+                region,
+                offset,
+                value
+                    .to_le_bytes()
+                    .iter()
+                    .fold(F::ZERO, |acc, byte| acc + F::from(*byte as u64)),
+            )?;
+            lt_n.assign(region, offset, value, secp256k1_n())?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Order of the secp256k1 group, which bounds r and s, and modulo which the
+/// message hash is reduced.
+fn secp256k1_n() -> eth_types::Word {
+    eth_types::Word::from_little_endian(&SECP256K1_Q.to_bytes_le())
+}
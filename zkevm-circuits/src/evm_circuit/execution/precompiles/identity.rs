@@ -0,0 +1,53 @@
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        memory_gadget::MemoryWordSizeGadget,
+        CachedRegion,
+    },
+    util::Expr,
+};
+use eth_types::Field;
+use halo2_proofs::plonk::{Error, Expression};
+
+/// Gadget for the identity precompile, whose output is its input.  Any input
+/// is valid, and the gas cost is 15 plus 3 for every word of the input.
+#[derive(Clone, Debug)]
+pub(crate) struct IdentityGadget<F> {
+    input_word_size: MemoryWordSizeGadget<F>,
+}
+
+impl<F: Field> IdentityGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        cb.require_equal("output_rlc == input_rlc", output_rlc, input_rlc);
+        cb.require_equal("output_len == input_len", output_len, input_len.expr());
+
+        let input_word_size = MemoryWordSizeGadget::construct(cb, input_len);
+        cb.require_equal(
+            "gas_cost == 15 + 3 * ceil(input_len / 32)",
+            gas_cost,
+            15.expr() + 3.expr() * input_word_size.expr(),
+        );
+        cb.require_equal("is_valid == 1", is_valid, 1.expr());
+
+        Self { input_word_size }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+    ) -> Result<(), Error> {
+        self.input_word_size
+            .assign(region, offset, input.len() as u64)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,523 @@
+use super::word_lo_hi;
+use crate::{
+    evm_circuit::{
+        table::{FixedTableTag, Lookup},
+        util::{
+            constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+            from_bytes,
+            math_gadget::{ByteSizeGadget, IsZeroGadget, LtGadget, MinMaxGadget},
+            not, rlc, select, sum, CachedRegion, Cell, Word,
+        },
+    },
+    util::Expr,
+};
+use bus_mapping::precompile::{ModExpAuxData, MODEXP_SIZE_LIMIT};
+use eth_types::{Field, ToLittleEndian};
+use halo2_proofs::{
+    circuit::Value,
+    plonk::{Error, Expression},
+};
+
+/// Number of bits of the exponents of the challenge powers, enough for the
+/// lengths of the base, the exponent and the modulus added up.
+const N_BITS_POWER: usize = 7;
+
+/// Gadget for the MODEXP precompile, which parses the input right-padded with
+/// zeros or truncated to the lengths into the lengths, the base, the exponent
+/// and the modulus, and looks up the result of the modular exponentiation in
+/// the ModExp table.  Only a base, an exponent and a modulus of up to
+/// `MODEXP_SIZE_LIMIT` bytes are supported, and the EVM circuit can't prove a
+/// call with longer ones.  The output is the result left-padded to the length
+/// of the modulus, and the gas cost follows EIP-2565.
+#[derive(Clone, Debug)]
+pub(crate) struct ModExpGadget<F> {
+    base_len: Word<F>,
+    exp_len: Word<F>,
+    mod_len: Word<F>,
+    lengths_hi_is_zero: IsZeroGadget<F>,
+    lengths_lt_limit: [LtGadget<F, 1>; 3],
+
+    base: Word<F>,
+    exponent: Word<F>,
+    modulus: Word<F>,
+    output: Word<F>,
+    sizes: [ByteSizeGadget<F>; 3],
+    lengths_lt_sizes: [LtGadget<F, 1>; 3],
+    modulus_is_zero: IsZeroGadget<F>,
+
+    challenge_powers: [ChallengePowerGadget<F>; 3],
+
+    gas: ModExpGasGadget<F>,
+}
+
+impl<F: Field> ModExpGadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        padded_input_rlc: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        let [base_len, exp_len, mod_len] = [(); 3].map(|_| cb.query_word_rlc());
+        let [base, exponent, modulus, output] = [(); 4].map(|_| cb.query_word_rlc());
+
+        // The call is valid for any input, and it's supported when all the
+        // lengths fit in a byte, and are not greater than the limit.
+        cb.require_equal("is_valid == 1", is_valid, 1.expr());
+        let lengths_hi_is_zero = IsZeroGadget::construct(
+            cb,
+            sum::expr(
+                [&base_len, &exp_len, &mod_len]
+                    .iter()
+                    .flat_map(|length| length.cells[1..].iter().map(|cell| cell.expr())),
+            ),
+        );
+        let lengths_lt_limit = [&base_len, &exp_len, &mod_len].map(|length| {
+            LtGadget::construct(cb, length.cells[0].expr(), (MODEXP_SIZE_LIMIT + 1).expr())
+        });
+        cb.require_equal("lengths fit in a byte", lengths_hi_is_zero.expr(), 1.expr());
+        for lt in lengths_lt_limit.iter() {
+            cb.require_equal("length <= MODEXP_SIZE_LIMIT", lt.expr(), 1.expr());
+        }
+        let [base_len_expr, exp_len_expr, mod_len_expr] =
+            [&base_len, &exp_len, &mod_len].map(|length| length.cells[0].expr());
+
+        // The base, the exponent and the modulus fit in their lengths.
+        let sizes = [&base, &exponent, &modulus]
+            .map(|word| ByteSizeGadget::construct(cb, word.cells.clone().map(|cell| cell.expr())));
+        let lengths_lt_sizes = [
+            (base_len_expr.clone(), &sizes[0]),
+            (exp_len_expr.clone(), &sizes[1]),
+            (mod_len_expr.clone(), &sizes[2]),
+        ]
+        .map(|(length, size)| LtGadget::construct(cb, length, size.byte_size()));
+        for lt in lengths_lt_sizes.iter() {
+            cb.require_zero("byte size of the value <= length", lt.expr());
+        }
+
+        // The padded input is accumulated in reverse order, so the RLC of each
+        // field is shifted by the total length of the fields after it.
+        let challenge_powers = [
+            mod_len_expr.clone(),
+            exp_len_expr.clone() + mod_len_expr.clone(),
+            base_len_expr.clone() + exp_len_expr.clone() + mod_len_expr.clone(),
+        ]
+        .map(|exponent| ChallengePowerGadget::construct(cb, exponent));
+        let keccak_input = cb.challenges().keccak_input();
+        let [lengths_rlc, base_rlc, exponent_rlc, modulus_rlc] = [
+            vec![&mod_len, &exp_len, &base_len],
+            vec![&base],
+            vec![&exponent],
+            vec![&modulus],
+        ]
+        .map(|words| {
+            let bytes = words
+                .iter()
+                .flat_map(|word| word.cells.iter().map(|cell| cell.expr()))
+                .collect::<Vec<_>>();
+            rlc::expr(&bytes, keccak_input.clone())
+        });
+        cb.require_equal(
+            "padded_input_rlc == RLC(lengths || base || exponent || modulus)",
+            padded_input_rlc,
+            lengths_rlc * challenge_powers[2].expr()
+                + base_rlc * challenge_powers[1].expr()
+                + exponent_rlc * challenge_powers[0].expr()
+                + modulus_rlc,
+        );
+
+        // The result of a non-zero modulus is looked up in the ModExp table,
+        // and it's 0 otherwise.
+        let modulus_is_zero = IsZeroGadget::construct(cb, sum::expr(&modulus.cells));
+        cb.condition(not::expr(modulus_is_zero.expr()), |cb| {
+            cb.modexp_table_lookup(
+                word_lo_hi(&base),
+                word_lo_hi(&exponent),
+                word_lo_hi(&modulus),
+                word_lo_hi(&output),
+            );
+        });
+        cb.condition(modulus_is_zero.expr(), |cb| {
+            cb.require_zero("output == 0 for a zero modulus", sum::expr(&output.cells));
+        });
+
+        // The output is accumulated in reverse order with the keccak input
+        // challenge, which matches the little-endian bytes of the word, whose
+        // bytes above mod_len are 0 as the result is less than the modulus.
+        let output_bytes = output
+            .cells
+            .iter()
+            .map(|cell| cell.expr())
+            .collect::<Vec<_>>();
+        cb.require_equal(
+            "output_rlc == RLC(output)",
+            output_rlc,
+            rlc::expr(&output_bytes, keccak_input),
+        );
+        cb.require_equal("output_len == mod_len", output_len, mod_len_expr.clone());
+
+        let gas = ModExpGasGadget::construct(cb, base_len_expr, mod_len_expr, &exponent, &sizes[1]);
+        cb.require_equal("gas_cost == EIP-2565 gas cost", gas_cost, gas.expr());
+
+        Self {
+            base_len,
+            exp_len,
+            mod_len,
+            lengths_hi_is_zero,
+            lengths_lt_limit,
+            base,
+            exponent,
+            modulus,
+            output,
+            sizes,
+            lengths_lt_sizes,
+            modulus_is_zero,
+            challenge_powers,
+            gas,
+        }
+    }
+
+    /// Length of the lengths, the base, the exponent and the modulus, to which
+    /// the input is right-padded with zeros or truncated.
+    pub(crate) fn padded_input_len(&self) -> Expression<F> {
+        96.expr()
+            + sum::expr(
+                [&self.base_len, &self.exp_len, &self.mod_len].map(|length| length.cells[0].expr()),
+            )
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        let aux_data = ModExpAuxData::new(input, output);
+
+        for (word, value) in [
+            (&self.base_len, aux_data.base_len),
+            (&self.exp_len, aux_data.exp_len),
+            (&self.mod_len, aux_data.mod_len),
+            (&self.base, aux_data.base),
+            (&self.exponent, aux_data.exponent),
+            (&self.modulus, aux_data.modulus),
+            (&self.output, aux_data.output),
+        ] {
+            word.assign(region, offset, Some(value.to_le_bytes()))?;
+        }
+
+        let lengths = [aux_data.base_len, aux_data.exp_len, aux_data.mod_len];
+        self.lengths_hi_is_zero.assign(
+            region,
+            offset,
+            lengths
+                .iter()
+                .flat_map(|length| length.to_le_bytes()[1..].to_vec())
+                .fold(F::ZERO, |acc, byte| acc + F::from(byte as u64)),
+        )?;
+        for (lt, length) in self.lengths_lt_limit.iter().zip(lengths.iter()) {
+            lt.assign(
+                region,
+                offset,
+                F::from(length.to_le_bytes()[0] as u64),
+                F::from(MODEXP_SIZE_LIMIT as u64 + 1),
+            )?;
+        }
+
+        for ((size, lt), (length, value)) in self
+            .sizes
+            .iter()
+            .zip(self.lengths_lt_sizes.iter())
+            .zip(
+                lengths
+                    .iter()
+                    .zip([aux_data.base, aux_data.exponent, aux_data.modulus]),
+            )
+        {
+            size.assign(region, offset, value)?;
+            lt.assign(
+                region,
+                offset,
+                F::from(length.to_le_bytes()[0] as u64),
+                F::from((value.bits() as u64 + 7) / 8),
+            )?;
+        }
+        self.modulus_is_zero.assign(
+            region,
+            offset,
+            aux_data
+                .modulus
+                .to_le_bytes()
+                .iter()
+                .fold(F::ZERO, |acc, byte| acc + F::from(*byte as u64)),
+        )?;
+
+        // The lengths of an unsupported call are truncated to their first byte,
+        // as the one of the lengths in the constraints.
+        let [base_len, exp_len, mod_len] = lengths.map(|length| length.to_le_bytes()[0] as u64);
+        for (power, exponent) in self.challenge_powers.iter().zip([
+            mod_len,
+            exp_len + mod_len,
+            base_len + exp_len + mod_len,
+        ]) {
+            power.assign(region, offset, exponent)?;
+        }
+
+        self.gas
+            .assign(region, offset, base_len, mod_len, aux_data.exponent)
+    }
+}
+
+/// Gadget for the power `r^exponent` of the keccak input challenge, with an
+/// exponent of up to `N_BITS_POWER` bits.
+#[derive(Clone, Debug)]
+struct ChallengePowerGadget<F> {
+    bits: [Cell<F>; N_BITS_POWER],
+    powers: [Cell<F>; N_BITS_POWER],
+}
+
+impl<F: Field> ChallengePowerGadget<F> {
+    fn construct(cb: &mut EVMConstraintBuilder<F>, exponent: Expression<F>) -> Self {
+        let bits = [(); N_BITS_POWER].map(|_| cb.query_bool());
+        let powers = [(); N_BITS_POWER].map(|_| cb.query_cell_phase2());
+        cb.require_equal(
+            "exponent == from_bits(bits)",
+            exponent,
+            sum::expr(
+                bits.iter()
+                    .enumerate()
+                    .map(|(idx, bit)| bit.expr() * (1u64 << idx).expr()),
+            ),
+        );
+
+        let challenge_powers = cb
+            .challenges()
+            .keccak_powers_of_randomness::<{ 1 << (N_BITS_POWER - 1) }>();
+        let mut power_prev = 1.expr();
+        for (idx, (bit, power)) in bits.iter().zip(powers.iter()).enumerate() {
+            cb.require_equal(
+                "power == power_prev * (bit ? r^(2^idx) : 1)",
+                power.expr(),
+                power_prev
+                    * select::expr(
+                        bit.expr(),
+                        challenge_powers[(1 << idx) - 1].clone(),
+                        1.expr(),
+                    ),
+            );
+            power_prev = power.expr();
+        }
+
+        Self { bits, powers }
+    }
+
+    fn expr(&self) -> Expression<F> {
+        self.powers[N_BITS_POWER - 1].expr()
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        exponent: u64,
+    ) -> Result<(), Error> {
+        let challenge = region.challenges().keccak_input();
+        let mut power = Value::known(F::ONE);
+        for (idx, (bit, power_cell)) in self.bits.iter().zip(self.powers.iter()).enumerate() {
+            let bit_value = (exponent >> idx) & 1;
+            bit.assign(region, offset, Value::known(F::from(bit_value)))?;
+            if bit_value == 1 {
+                power = power * challenge.map(|r| r.pow([1 << idx, 0, 0, 0]));
+            }
+            power_cell.assign(region, offset, power)?;
+        }
+        Ok(())
+    }
+}
+
+/// Gadget for the EIP-2565 gas cost of a call to the MODEXP precompile with an
+/// exponent of up to 32 bytes:
+/// `max(200, ceil(max(base_len, mod_len) / 8)^2 * max(bit_len(exponent) - 1, 1) / 3)`
+#[derive(Clone, Debug)]
+struct ModExpGasGadget<F> {
+    max_length: MinMaxGadget<F, 1>,
+    words: Cell<F>,
+    words_rem: Cell<F>,
+    words_rem_lt_8: LtGadget<F, 1>,
+    exponent_is_zero: IsZeroGadget<F>,
+    msb_log2: Cell<F>,
+    msb_pow: Cell<F>,
+    msb_lt_pow: LtGadget<F, 1>,
+    msb_lt_double_pow: LtGadget<F, 2>,
+    iteration_count: Cell<F>,
+    iteration_count_is_zero: IsZeroGadget<F>,
+    gas_quotient: [Cell<F>; 2],
+    gas_rem: Cell<F>,
+    gas_rem_lt_3: LtGadget<F, 1>,
+    gas: MinMaxGadget<F, 2>,
+}
+
+impl<F: Field> ModExpGasGadget<F> {
+    fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        base_len: Expression<F>,
+        mod_len: Expression<F>,
+        exponent: &Word<F>,
+        exponent_size: &ByteSizeGadget<F>,
+    ) -> Self {
+        // words = ceil(max(base_len, mod_len) / 8)
+        let max_length = MinMaxGadget::construct(cb, base_len, mod_len);
+        let words = cb.query_byte();
+        let words_rem = cb.query_byte();
+        let words_rem_lt_8 = LtGadget::construct(cb, words_rem.expr(), 8.expr());
+        cb.require_equal(
+            "max_length + 7 == words * 8 + words_rem",
+            max_length.max() + 7.expr(),
+            words.expr() * 8.expr() + words_rem.expr(),
+        );
+        cb.require_equal("words_rem < 8", words_rem_lt_8.expr(), 1.expr());
+
+        // iteration_count = bit_len(exponent) - 1, where the bit length of a
+        // non-zero exponent is given by its byte size and the position of the
+        // most significant bit of its most significant byte.
+        let exponent_is_zero = IsZeroGadget::construct(cb, exponent_size.byte_size());
+        let msb =
+            exponent_size.most_significant_byte(&exponent.cells.clone().map(|cell| cell.expr()));
+        let msb_log2 = cb.query_cell();
+        let msb_pow = cb.query_cell();
+        let msb_lt_pow = LtGadget::construct(cb, msb.clone(), msb_pow.expr());
+        let msb_lt_double_pow = LtGadget::construct(cb, msb, 2.expr() * msb_pow.expr());
+        let iteration_count = cb.query_cell();
+        cb.condition(not::expr(exponent_is_zero.expr()), |cb| {
+            cb.add_lookup(
+                "Pow2 lookup of msb_log2",
+                Lookup::Fixed {
+                    tag: FixedTableTag::Pow2.expr(),
+                    values: [msb_log2.expr(), msb_pow.expr(), 0.expr()],
+                },
+            );
+            cb.require_zero("msb >= 2^msb_log2", msb_lt_pow.expr());
+            cb.require_equal("msb < 2^(msb_log2 + 1)", msb_lt_double_pow.expr(), 1.expr());
+            cb.require_equal(
+                "iteration_count == 8 * (byte_size - 1) + msb_log2",
+                iteration_count.expr(),
+                8.expr() * (exponent_size.byte_size() - 1.expr()) + msb_log2.expr(),
+            );
+        });
+        cb.condition(exponent_is_zero.expr(), |cb| {
+            cb.require_zero(
+                "iteration_count == 0 for a zero exponent",
+                iteration_count.expr(),
+            );
+        });
+        let iteration_count_is_zero = IsZeroGadget::construct(cb, iteration_count.expr());
+
+        // gas = max(200, words^2 * max(iteration_count, 1) / 3)
+        let gas_quotient = cb.query_bytes();
+        let gas_rem = cb.query_byte();
+        let gas_rem_lt_3 = LtGadget::construct(cb, gas_rem.expr(), 3.expr());
+        cb.require_equal(
+            "words^2 * max(iteration_count, 1) == gas_quotient * 3 + gas_rem",
+            words.expr() * words.expr() * (iteration_count.expr() + iteration_count_is_zero.expr()),
+            from_bytes::expr(&gas_quotient) * 3.expr() + gas_rem.expr(),
+        );
+        cb.require_equal("gas_rem < 3", gas_rem_lt_3.expr(), 1.expr());
+        let gas = MinMaxGadget::construct(cb, from_bytes::expr(&gas_quotient), 200.expr());
+
+        Self {
+            max_length,
+            words,
+            words_rem,
+            words_rem_lt_8,
+            exponent_is_zero,
+            msb_log2,
+            msb_pow,
+            msb_lt_pow,
+            msb_lt_double_pow,
+            iteration_count,
+            iteration_count_is_zero,
+            gas_quotient,
+            gas_rem,
+            gas_rem_lt_3,
+            gas,
+        }
+    }
+
+    fn expr(&self) -> Expression<F> {
+        self.gas.max()
+    }
+
+    fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        base_len: u64,
+        mod_len: u64,
+        exponent: eth_types::Word,
+    ) -> Result<(), Error> {
+        self.max_length
+            .assign(region, offset, F::from(base_len), F::from(mod_len))?;
+        let max_length = base_len.max(mod_len);
+        let words = (max_length + 7) / 8;
+        let words_rem = (max_length + 7) % 8;
+        self.words
+            .assign(region, offset, Value::known(F::from(words)))?;
+        self.words_rem
+            .assign(region, offset, Value::known(F::from(words_rem)))?;
+        self.words_rem_lt_8
+            .assign(region, offset, F::from(words_rem), F::from(8))?;
+
+        let byte_size = (exponent.bits() as u64 + 7) / 8;
+        self.exponent_is_zero
+            .assign(region, offset, F::from(byte_size))?;
+        let msb = if byte_size > 0 {
+            exponent.to_le_bytes()[byte_size as usize - 1]
+        } else {
+            0
+        };
+        let msb_log2 = if msb > 0 {
+            7 - msb.leading_zeros() as u64
+        } else {
+            0
+        };
+        let msb_pow = 1u64 << msb_log2;
+        self.msb_log2
+            .assign(region, offset, Value::known(F::from(msb_log2)))?;
+        self.msb_pow
+            .assign(region, offset, Value::known(F::from(msb_pow)))?;
+        self.msb_lt_pow
+            .assign(region, offset, F::from(msb as u64), F::from(msb_pow))?;
+        self.msb_lt_double_pow
+            .assign(region, offset, F::from(msb as u64), F::from(2 * msb_pow))?;
+
+        let iteration_count = if exponent.is_zero() {
+            0
+        } else {
+            exponent.bits() as u64 - 1
+        };
+        self.iteration_count
+            .assign(region, offset, Value::known(F::from(iteration_count)))?;
+        self.iteration_count_is_zero
+            .assign(region, offset, F::from(iteration_count))?;
+
+        let product = words * words * iteration_count.max(1);
+        let (gas_quotient, gas_rem) = (product / 3, product % 3);
+        for (cell, byte) in self
+            .gas_quotient
+            .iter()
+            .zip(gas_quotient.to_le_bytes().iter())
+        {
+            cell.assign(region, offset, Value::known(F::from(*byte as u64)))?;
+        }
+        self.gas_rem
+            .assign(region, offset, Value::known(F::from(gas_rem)))?;
+        self.gas_rem_lt_3
+            .assign(region, offset, F::from(gas_rem), F::from(3))?;
+        self.gas
+            .assign(region, offset, F::from(gas_quotient), F::from(200))?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{ConstrainBuilderCommon, EVMConstraintBuilder},
+        memory_gadget::MemoryWordSizeGadget,
+        rlc, CachedRegion, Word,
+    },
+    util::Expr,
+};
+use eth_types::{Field, ToLittleEndian};
+use halo2_proofs::plonk::{Error, Expression};
+
+/// Gadget for the SHA-256 precompile, which looks up the hash of the input in
+/// the SHA-256 table.  The output is always the 32 bytes of the hash, as any
+/// input is valid, and the gas cost is 60 plus 12 for every word of the input.
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Gadget<F> {
+    output: Word<F>,
+    input_word_size: MemoryWordSizeGadget<F>,
+}
+
+impl<F: Field> Sha256Gadget<F> {
+    pub(crate) fn construct(
+        cb: &mut EVMConstraintBuilder<F>,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+        output_len: Expression<F>,
+        gas_cost: Expression<F>,
+        is_valid: Expression<F>,
+    ) -> Self {
+        let output = cb.query_word_rlc();
+        cb.sha256_table_lookup(input_rlc, input_len.expr(), output.expr());
+
+        // The output is accumulated in reverse order with the keccak input
+        // challenge, which matches the little-endian bytes of the word.
+        let output_bytes = output
+            .cells
+            .iter()
+            .map(|cell| cell.expr())
+            .collect::<Vec<_>>();
+        let keccak_input = cb.challenges().keccak_input();
+        cb.require_equal(
+            "output_rlc == RLC(output)",
+            output_rlc,
+            rlc::expr(&output_bytes, keccak_input),
+        );
+        cb.require_equal("output_len == 32", output_len, 32.expr());
+
+        let input_word_size = MemoryWordSizeGadget::construct(cb, input_len);
+        cb.require_equal(
+            "gas_cost == 60 + 12 * ceil(input_len / 32)",
+            gas_cost,
+            60.expr() + 12.expr() * input_word_size.expr(),
+        );
+        cb.require_equal("is_valid == 1", is_valid, 1.expr());
+
+        Self {
+            output,
+            input_word_size,
+        }
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        input: &[u8],
+        output: &[u8],
+    ) -> Result<(), Error> {
+        self.output.assign(
+            region,
+            offset,
+            Some(eth_types::Word::from_big_endian(output).to_le_bytes()),
+        )?;
+        self.input_word_size
+            .assign(region, offset, input.len() as u64)?;
+        Ok(())
+    }
+}
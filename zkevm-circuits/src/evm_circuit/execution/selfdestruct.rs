@@ -0,0 +1,635 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        param::{N_BYTES_ACCOUNT_ADDRESS, STACK_CAPACITY},
+        step::ExecutionState,
+        table::HardforkParam,
+        util::{
+            common_gadget::{RestoreContextGadget, UpdateBalanceGadget},
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, ReversionInfo, StepStateTransition,
+                Transition::{Delta, To},
+            },
+            from_bytes,
+            math_gadget::{IsEqualGadget, IsZeroGadget},
+            not, select, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    table::{AccountFieldTag, CallContextFieldTag, TxAccountFlagFieldTag},
+    util::Expr,
+};
+use bus_mapping::state_db::CodeDB;
+use eth_types::{evm_types::GasCost, Field, ToAddress, ToLittleEndian, ToScalar, ToWord, U256};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for SELFDESTRUCT.  The whole balance of the current account is
+/// moved to the beneficiary (and burnt when the beneficiary is the account
+/// itself and the account is destructed), then the call halts like STOP.  The
+/// account is flagged as destructed, unless EIP-6780 is active and it wasn't
+/// created in the same tx, and is deleted by a DestructAccount step after the
+/// EndTx.
+#[derive(Clone, Debug)]
+pub(crate) struct SelfDestructGadget<F> {
+    opcode: Cell<F>,
+    beneficiary: Word<F>,
+    tx_id: Cell<F>,
+    is_static: Cell<F>,
+    reversion_info: ReversionInfo<F>,
+    address: Cell<F>,
+    is_warm_prev: Cell<F>,
+    beneficiary_code_hash: Cell<F>,
+    beneficiary_not_exists: IsZeroGadget<F>,
+    value: Word<F>,
+    value_is_zero: IsZeroGadget<F>,
+    is_created: Cell<F>,
+    beneficiary_balance: UpdateBalanceGadget<F, 2, true>,
+    only_created: Cell<F>,
+    is_self_beneficiary: IsEqualGadget<F>,
+    is_destructed_prev: Cell<F>,
+    selfdestruct_refund: Cell<F>,
+    selfdestruct_refund_is_zero: IsZeroGadget<F>,
+    tx_refund_prev: Cell<F>,
+    restore_context: RestoreContextGadget<F>,
+}
+
+impl<F: Field> ExecutionGadget<F> for SelfDestructGadget<F> {
+    const NAME: &'static str = "SELFDESTRUCT";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::SELFDESTRUCT;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        let opcode = cb.query_cell();
+        cb.opcode_lookup(opcode.expr(), 1.expr());
+
+        let beneficiary = cb.query_word_rlc();
+        let beneficiary_address = from_bytes::expr(&beneficiary.cells[..N_BYTES_ACCOUNT_ADDRESS]);
+        cb.stack_pop(beneficiary.expr());
+
+        let tx_id = cb.call_context(None, CallContextFieldTag::TxId);
+
+        // constrain not in static call
+        let is_static = cb.call_context(None, CallContextFieldTag::IsStatic);
+        cb.require_zero("is_static is false", is_static.expr());
+
+        let mut reversion_info = cb.reversion_info_read(None);
+        let address = cb.call_context(None, CallContextFieldTag::CalleeAddress);
+
+        // Call ends with SELFDESTRUCT must be successful
+        cb.call_context_lookup(false.expr(), None, CallContextFieldTag::IsSuccess, 1.expr());
+
+        // Add beneficiary to access list
+        let is_warm_prev = cb.query_bool();
+        cb.account_access_list_write(
+            tx_id.expr(),
+            beneficiary_address.expr(),
+            1.expr(),
+            is_warm_prev.expr(),
+            Some(&mut reversion_info),
+        );
+
+        // For non-existing accounts the code_hash must be 0 in the rw_table.
+        let beneficiary_code_hash = cb.query_cell_phase2();
+        cb.account_read(
+            beneficiary_address.expr(),
+            AccountFieldTag::CodeHash,
+            beneficiary_code_hash.expr(),
+        );
+        let beneficiary_not_exists = IsZeroGadget::construct(cb, beneficiary_code_hash.expr());
+
+        let value = cb.query_word_rlc();
+        cb.account_read(address.expr(), AccountFieldTag::Balance, value.expr());
+        let value_is_zero = IsZeroGadget::construct(cb, value.expr());
+        let is_transfer = not::expr(value_is_zero.expr());
+        let is_new_account = is_transfer.expr() * beneficiary_not_exists.expr();
+
+        let is_created = cb.query_bool();
+        cb.tx_account_flag_read(
+            tx_id.expr(),
+            address.expr(),
+            TxAccountFlagFieldTag::Created,
+            is_created.expr(),
+        );
+
+        // If beneficiary doesn't exist, create it
+        cb.condition(is_new_account.expr(), |cb| {
+            cb.account_write(
+                beneficiary_address.expr(),
+                AccountFieldTag::CodeHash,
+                cb.empty_code_hash_rlc(),
+                0.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        // Follow geth which clears the balance of the current account and then
+        // adds it to the beneficiary, which is a no-op when the beneficiary is
+        // the current account itself.
+        let beneficiary_balance = cb.condition(is_transfer.expr(), |cb| {
+            cb.account_write(
+                address.expr(),
+                AccountFieldTag::Balance,
+                0.expr(),
+                value.expr(),
+                Some(&mut reversion_info),
+            );
+            UpdateBalanceGadget::construct(
+                cb,
+                beneficiary_address.expr(),
+                vec![value.clone()],
+                Some(&mut reversion_info),
+            )
+        });
+
+        // Since EIP-6780 only an account created in the same tx is destructed.
+        let only_created = cb.hardfork_param(HardforkParam::SelfdestructOnlyCreated);
+        let is_destructed = select::expr(only_created.expr(), is_created.expr(), 1.expr());
+
+        // The balance is burnt when the destructed account is the beneficiary.
+        let is_self_beneficiary =
+            IsEqualGadget::construct(cb, beneficiary_address.expr(), address.expr());
+        let is_burnt = is_destructed.expr() * is_transfer.expr() * is_self_beneficiary.expr();
+        cb.condition(is_burnt.expr(), |cb| {
+            cb.account_write(
+                address.expr(),
+                AccountFieldTag::Balance,
+                0.expr(),
+                value.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        // Only the first SELFDESTRUCT of an account in the tx is refunded,
+        // which EIP-3529 removed.
+        let is_destructed_prev = cb.query_bool();
+        let selfdestruct_refund = cb.hardfork_param(HardforkParam::SelfdestructRefund);
+        let selfdestruct_refund_is_zero = IsZeroGadget::construct(cb, selfdestruct_refund.expr());
+        let is_refunded = is_destructed.expr()
+            * not::expr(is_destructed_prev.expr())
+            * not::expr(selfdestruct_refund_is_zero.expr());
+        let tx_refund_prev = cb.query_cell();
+        cb.condition(is_destructed.expr(), |cb| {
+            cb.tx_account_flag_write(
+                tx_id.expr(),
+                address.expr(),
+                TxAccountFlagFieldTag::Destructed,
+                1.expr(),
+                is_destructed_prev.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+        cb.condition(is_refunded.expr(), |cb| {
+            cb.tx_refund_write(
+                tx_id.expr(),
+                tx_refund_prev.expr() + selfdestruct_refund.expr(),
+                tx_refund_prev.expr(),
+                Some(&mut reversion_info),
+            );
+        });
+
+        let gas_cost = GasCost::SELFDESTRUCT.expr()
+            + select::expr(
+                is_warm_prev.expr(),
+                0.expr(),
+                GasCost::COLD_ACCOUNT_ACCESS.expr(),
+            )
+            + is_new_account.expr() * GasCost::NEW_ACCOUNT.expr();
+
+        // +1 Write TxAccessListAccount (beneficiary)
+        // +1 Write Account (beneficiary) CodeHash (account creation via code_hash update)
+        // +1 Write Account (current account) Balance
+        // +1 Write Account (beneficiary) Balance
+        // +1 Write Account (current account) Balance when it's burnt
+        // +1 Write TxAccountFlag (current account) Destructed
+        // +1 Write TxRefund
+        let reversible_write_counter_increase = 1.expr()
+            + is_new_account
+            + is_transfer * 2.expr()
+            + is_burnt
+            + is_destructed
+            + is_refunded;
+
+        // When it's a root call
+        cb.condition(cb.curr.state.is_root.expr(), |cb| {
+            cb.require_next_state(ExecutionState::EndTx);
+            cb.require_step_state_transition(StepStateTransition {
+                program_counter: To(0.expr()),
+                stack_pointer: To(STACK_CAPACITY.expr()),
+                rw_counter: Delta(cb.rw_counter_offset()),
+                gas_left: Delta(-gas_cost.clone()),
+                reversible_write_counter: To(0.expr()),
+                memory_word_size: To(0.expr()),
+                ..StepStateTransition::default()
+            });
+        });
+
+        // When it's an internal call
+        let restore_context = cb.condition(not::expr(cb.curr.state.is_root.expr()), |cb| {
+            RestoreContextGadget::construct(
+                cb,
+                true.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+                gas_cost,
+                reversible_write_counter_increase,
+            )
+        });
+
+        Self {
+            opcode,
+            beneficiary,
+            tx_id,
+            is_static,
+            reversion_info,
+            address,
+            is_warm_prev,
+            beneficiary_code_hash,
+            beneficiary_not_exists,
+            value,
+            value_is_zero,
+            is_created,
+            beneficiary_balance,
+            only_created,
+            is_self_beneficiary,
+            is_destructed_prev,
+            selfdestruct_refund,
+            selfdestruct_refund_is_zero,
+            tx_refund_prev,
+            restore_context,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        tx: &Transaction,
+        call: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let opcode = step.opcode().unwrap();
+        self.opcode
+            .assign(region, offset, Value::known(F::from(opcode.as_u64())))?;
+
+        let beneficiary = block.get_rws(step, 0).stack_value();
+        self.beneficiary
+            .assign(region, offset, Some(beneficiary.to_le_bytes()))?;
+
+        self.tx_id
+            .assign(region, offset, Value::known(F::from(tx.id as u64)))?;
+        self.is_static
+            .assign(region, offset, Value::known(F::from(call.is_static as u64)))?;
+        self.reversion_info.assign(
+            region,
+            offset,
+            call.rw_counter_end_of_reversion,
+            call.is_persistent,
+        )?;
+        self.address.assign(
+            region,
+            offset,
+            Value::known(
+                call.address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+        )?;
+
+        let (_, is_warm_prev) = block.get_rws(step, 7).tx_access_list_value_pair();
+        self.is_warm_prev
+            .assign(region, offset, Value::known(F::from(is_warm_prev as u64)))?;
+
+        let beneficiary_code_hash = block.get_rws(step, 8).account_value_pair().0;
+        self.beneficiary_code_hash.assign(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+        self.beneficiary_not_exists.assign_value(
+            region,
+            offset,
+            region.word_rlc(beneficiary_code_hash),
+        )?;
+        let beneficiary_exists = !beneficiary_code_hash.is_zero();
+
+        let value = block.get_rws(step, 9).account_value_pair().0;
+        self.value
+            .assign(region, offset, Some(value.to_le_bytes()))?;
+        self.value_is_zero
+            .assign_value(region, offset, region.word_rlc(value))?;
+
+        let (is_created, _) = block.get_rws(step, 10).tx_account_flag_value_pair();
+        self.is_created
+            .assign(region, offset, Value::known(F::from(is_created as u64)))?;
+
+        let mut rw_offset = 11;
+        if !value.is_zero() {
+            if !beneficiary_exists {
+                debug_assert_eq!(
+                    block.get_rws(step, rw_offset).account_value_pair().0,
+                    CodeDB::empty_code_hash().to_word()
+                );
+                rw_offset += 1;
+            }
+            let (beneficiary_balance, beneficiary_balance_prev) =
+                block.get_rws(step, rw_offset + 1).account_value_pair();
+            self.beneficiary_balance.assign(
+                region,
+                offset,
+                beneficiary_balance_prev,
+                vec![value],
+                beneficiary_balance,
+            )?;
+            rw_offset += 2;
+        } else {
+            self.beneficiary_balance.assign(
+                region,
+                offset,
+                U256::zero(),
+                vec![U256::zero()],
+                U256::zero(),
+            )?;
+        }
+
+        let hardfork = block.circuits_params.hardfork;
+        self.only_created.assign(
+            region,
+            offset,
+            Value::known(F::from(hardfork.has_eip6780() as u64)),
+        )?;
+        let is_self_beneficiary = self.is_self_beneficiary.assign(
+            region,
+            offset,
+            beneficiary
+                .to_address()
+                .to_scalar()
+                .expect("unexpected Address -> Scalar conversion failure"),
+            call.address
+                .to_scalar()
+                .expect("unexpected Address -> Scalar conversion failure"),
+        )?;
+        let selfdestruct_refund = hardfork.selfdestruct_refund();
+        self.selfdestruct_refund.assign(
+            region,
+            offset,
+            Value::known(F::from(selfdestruct_refund)),
+        )?;
+        self.selfdestruct_refund_is_zero
+            .assign(region, offset, F::from(selfdestruct_refund))?;
+
+        let is_destructed = !hardfork.has_eip6780() || is_created;
+        let (mut is_destructed_prev, mut tx_refund_prev) = (false, 0);
+        if is_destructed {
+            if !value.is_zero() && is_self_beneficiary == F::ONE {
+                rw_offset += 1;
+            }
+            is_destructed_prev = block
+                .get_rws(step, rw_offset)
+                .tx_account_flag_value_pair()
+                .1;
+            rw_offset += 1;
+            if selfdestruct_refund != 0 && !is_destructed_prev {
+                tx_refund_prev = block.get_rws(step, rw_offset).tx_refund_value_pair().1;
+                rw_offset += 1;
+            }
+        }
+        self.is_destructed_prev.assign(
+            region,
+            offset,
+            Value::known(F::from(is_destructed_prev as u64)),
+        )?;
+        self.tx_refund_prev
+            .assign(region, offset, Value::known(F::from(tx_refund_prev)))?;
+
+        if !call.is_root {
+            self.restore_context
+                .assign(region, offset, block, call, step, rw_offset)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{address, bytecode, evm_types::Hardfork, Address, Bytecode, ToWord, Word};
+    use itertools::Itertools;
+    use mock::TestContext;
+
+    const CALLER: Address = Address::repeat_byte(0x10);
+    const CONTRACT: Address = Address::repeat_byte(0x20);
+    const EOA: Address = Address::repeat_byte(0x30);
+
+    fn selfdestruct_code(beneficiary: Address) -> Bytecode {
+        bytecode! {
+            PUSH20(beneficiary.to_word())
+            SELFDESTRUCT
+        }
+    }
+
+    fn test_ok(beneficiary: Address, balance: Word, is_root: bool, is_warm: bool) {
+        test_ok_with_hardfork(beneficiary, balance, is_root, is_warm, Hardfork::default());
+    }
+
+    fn test_ok_with_hardfork(
+        beneficiary: Address,
+        balance: Word,
+        is_root: bool,
+        is_warm: bool,
+        hardfork: Hardfork,
+    ) {
+        let caller_code = {
+            let mut code = Bytecode::default();
+            if is_warm {
+                code.append(&bytecode! {
+                    PUSH20(beneficiary.to_word())
+                    BALANCE
+                    POP
+                });
+            }
+            code.append(&bytecode! {
+                PUSH1(0) // retLength
+                PUSH1(0) // retOffset
+                PUSH1(0) // argsLength
+                PUSH1(0) // argsOffset
+                PUSH1(0) // value
+                PUSH20(CONTRACT.to_word())
+                GAS
+                CALL
+                STOP
+            });
+            code
+        };
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CALLER)
+                    .balance(Word::from(1u64 << 20))
+                    .code(caller_code);
+                accs[2]
+                    .address(CONTRACT)
+                    .balance(balance)
+                    .code(selfdestruct_code(beneficiary));
+                accs[3].address(EOA).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(if is_root {
+                        accs[2].address
+                    } else {
+                        accs[1].address
+                    })
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64).hardfork(hardfork),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn selfdestruct_gadget_simple() {
+        let beneficiaries = vec![
+            EOA,
+            CONTRACT,
+            address!("0xaabbccddee000000000000000000000000000000"),
+        ];
+        let balances = vec![Word::zero(), Word::from(800u64)];
+        let is_roots = vec![true, false];
+        for ((beneficiary, balance), is_root) in beneficiaries
+            .into_iter()
+            .cartesian_product(balances)
+            .cartesian_product(is_roots)
+        {
+            test_ok(beneficiary, balance, is_root, false);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_warm_beneficiary() {
+        test_ok(EOA, Word::from(800u64), false, true);
+    }
+
+    #[test]
+    fn selfdestruct_gadget_refund_berlin() {
+        for is_root in [true, false] {
+            test_ok_with_hardfork(EOA, Word::from(800u64), is_root, false, Hardfork::Berlin);
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_not_created_cancun() {
+        // Neither the account is deleted nor the balance is burnt.
+        for (beneficiary, is_root) in [EOA, CONTRACT].into_iter().cartesian_product([true, false]) {
+            test_ok_with_hardfork(
+                beneficiary,
+                Word::from(800u64),
+                is_root,
+                false,
+                Hardfork::Cancun,
+            );
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_created_cancun() {
+        // The initialization code of the creation tx self destructs, so the
+        // account is deleted since it's created in the same tx, and its
+        // balance is burnt when it's the beneficiary itself.
+        let codes = [
+            selfdestruct_code(EOA),
+            bytecode! {
+                ADDRESS
+                SELFDESTRUCT
+            },
+        ];
+        for code in codes {
+            let ctx = TestContext::<2, 1>::new(
+                None,
+                |accs| {
+                    accs[0]
+                        .address(address!("0x0000000000000000000000000000000000cafe01"))
+                        .balance(Word::from(1u64 << 30));
+                    accs[1].address(EOA).balance(Word::from(1u64 << 20));
+                },
+                |mut txs, accs| {
+                    txs[0]
+                        .from(accs[0].address)
+                        .value(Word::from(800u64))
+                        .gas(Word::from(100000))
+                        .input(code.into());
+                },
+                |block, _tx| block.number(0xcafeu64).hardfork(Hardfork::Cancun),
+            )
+            .unwrap();
+
+            CircuitTestBuilder::new_from_test_ctx(ctx)
+                .params(CircuitsParams {
+                    hardfork: Hardfork::Cancun,
+                    ..Default::default()
+                })
+                .run();
+        }
+    }
+
+    #[test]
+    fn selfdestruct_gadget_reverted() {
+        // The call to the self destructing contract succeeds, but the caller
+        // reverts afterwards.
+        let caller_code = bytecode! {
+            PUSH1(0) // retLength
+            PUSH1(0) // retOffset
+            PUSH1(0) // argsLength
+            PUSH1(0) // argsOffset
+            PUSH1(0) // value
+            PUSH20(CONTRACT.to_word())
+            GAS
+            CALL
+            PUSH1(0)
+            PUSH1(0)
+            REVERT
+        };
+        let ctx = TestContext::<4, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(address!("0x0000000000000000000000000000000000cafe01"))
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CALLER)
+                    .balance(Word::from(1u64 << 20))
+                    .code(caller_code);
+                accs[2]
+                    .address(CONTRACT)
+                    .balance(Word::from(800u64))
+                    .code(selfdestruct_code(EOA));
+                accs[3].address(EOA).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| block.number(0xcafeu64),
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx).run();
+    }
+}
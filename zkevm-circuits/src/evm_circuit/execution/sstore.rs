@@ -3,6 +3,7 @@ use crate::{
         execution::ExecutionGadget,
         param::N_BYTES_GAS,
         step::ExecutionState,
+        table::HardforkParam,
         util::{
             common_gadget::{SameContextGadget, SstoreGasGadget},
             constraint_builder::{
@@ -18,7 +19,10 @@ use crate::{
     util::Expr,
 };
 
-use eth_types::{evm_types::GasCost, Field, ToScalar};
+use eth_types::{
+    evm_types::{GasCost, Hardfork},
+    Field, ToScalar,
+};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Error, Expression},
@@ -218,6 +222,7 @@ impl<F: Field> ExecutionGadget<F> for SstoreGadget<F> {
         self.tx_refund.assign(
             region,
             offset,
+            block.circuits_params.hardfork,
             tx_refund,
             tx_refund_prev,
             value,
@@ -235,6 +240,7 @@ pub(crate) struct SstoreTxRefundGadget<F> {
     value: Cell<F>,
     value_prev: Cell<F>,
     original_value: Cell<F>,
+    sstore_clears_schedule: Cell<F>,
     value_prev_is_zero_gadget: IsZeroGadget<F>,
     value_is_zero_gadget: IsZeroGadget<F>,
     original_is_zero_gadget: IsZeroGadget<F>,
@@ -287,16 +293,18 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         let recreate_slot =
             not::expr(prev_eq_value) * not::expr(original_eq_prev) * (value_prev_is_zero);
 
+        let sstore_clears_schedule = cb.hardfork_param(HardforkParam::SstoreClearsSchedule);
         let tx_refund_new = tx_refund_old.expr()
-            + delete_slot * GasCost::SSTORE_CLEARS_SCHEDULE.expr()
+            + delete_slot * sstore_clears_schedule.expr()
             + reset_existing * (GasCost::SSTORE_RESET.expr() - GasCost::WARM_ACCESS.expr())
             + reset_inexistent * (GasCost::SSTORE_SET.expr() - GasCost::WARM_ACCESS.expr())
-            - recreate_slot * (GasCost::SSTORE_CLEARS_SCHEDULE.expr());
+            - recreate_slot * sstore_clears_schedule.expr();
 
         Self {
             value,
             value_prev,
             original_value,
+            sstore_clears_schedule,
             tx_refund_old,
             tx_refund_new,
             value_prev_is_zero_gadget,
@@ -318,12 +326,19 @@ impl<F: Field> SstoreTxRefundGadget<F> {
         &self,
         region: &mut CachedRegion<'_, '_, F>,
         offset: usize,
+        hardfork: Hardfork,
         tx_refund: u64,
         tx_refund_old: u64,
         value: eth_types::Word,
         value_prev: eth_types::Word,
         original_value: eth_types::Word,
     ) -> Result<(), Error> {
+        let sstore_clears_schedule = hardfork.sstore_clears_schedule();
+        self.sstore_clears_schedule.assign(
+            region,
+            offset,
+            Value::known(F::from(sstore_clears_schedule)),
+        )?;
         self.tx_refund_old
             .assign(region, offset, Value::known(F::from(tx_refund_old)))?;
         self.value.assign(region, offset, region.word_rlc(value))?;
@@ -359,7 +374,13 @@ impl<F: Field> SstoreTxRefundGadget<F> {
             region.word_rlc(value_prev),
         )?;
         debug_assert_eq!(
-            calc_expected_tx_refund(tx_refund_old, value, value_prev, original_value),
+            calc_expected_tx_refund(
+                tx_refund_old,
+                value,
+                value_prev,
+                original_value,
+                sstore_clears_schedule
+            ),
             tx_refund
         );
         Ok(())
@@ -371,6 +392,7 @@ fn calc_expected_tx_refund(
     value: eth_types::Word,
     value_prev: eth_types::Word,
     original_value: eth_types::Word,
+    sstore_clears_schedule: u64,
 ) -> u64 {
     // Same clause tags(like "delete slot (2.1.2b)") used as [`makeGasSStoreFunc` in go-ethereum](https://github.com/ethereum/go-ethereum/blob/9fd8825d5a196edde6d8ef81382979875145b346/core/vm/operations_acl.go#L27)
     // Control flow of this function try to follow `makeGasSStoreFunc` for better
@@ -382,11 +404,11 @@ fn calc_expected_tx_refund(
     // they are NOT MUTUALLY EXCLUSIVE.
     // Search "Apply both of the following clauses" in EIP-2200 for more details.
     // There can be five total kinds of refund:
-    // 1. -SSTORE_CLEARS_SCHEDULE
-    // 2. SSTORE_CLEARS_SCHEDULE
+    // 1. -sstore_clears_schedule
+    // 2. sstore_clears_schedule
     // 3. SSTORE_SET - WARM_ACCESS
     // 4. SSTORE_RESET - WARM_ACCESS
-    // 5. -SSTORE_CLEARS_SCHEDULE + SSTORE_RESET - WARM_ACCESS
+    // 5. -sstore_clears_schedule + SSTORE_RESET - WARM_ACCESS
     // The last case can happen if (original_value, prev_value, value) be (v,0,v)
     // where v != 0,
     // then both "clearing slot refund" and "resetting value refund" are non zero.
@@ -397,11 +419,11 @@ fn calc_expected_tx_refund(
         if !original_value.is_zero() {
             if value_prev.is_zero() {
                 // recreate slot (2.2.1.1)
-                tx_refund_new -= GasCost::SSTORE_CLEARS_SCHEDULE
+                tx_refund_new -= sstore_clears_schedule
             }
             if value.is_zero() {
                 // delete slot (2.2.1.2)
-                tx_refund_new += GasCost::SSTORE_CLEARS_SCHEDULE
+                tx_refund_new += sstore_clears_schedule
             }
         }
 
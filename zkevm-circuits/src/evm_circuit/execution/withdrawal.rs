@@ -0,0 +1,214 @@
+use crate::{
+    evm_circuit::{
+        execution::ExecutionGadget,
+        step::ExecutionState,
+        util::{
+            common_gadget::UpdateBalanceGadget,
+            constraint_builder::{
+                ConstrainBuilderCommon, EVMConstraintBuilder, StepStateTransition,
+                Transition::{Delta, Same},
+            },
+            from_bytes, CachedRegion, Cell, Word,
+        },
+        witness::{Block, Call, ExecStep, Transaction},
+    },
+    util::Expr,
+};
+use eth_types::{Field, ToLittleEndian, ToScalar};
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Gadget for the virtual step which follows the last tx once for every
+/// withdrawal (EIP-4895) of the block.  The log_id counts the withdrawals
+/// applied before the step, so the withdrawals are applied in the order of the
+/// withdrawal table, and each one increases the balance of its address by its
+/// amount in Wei.
+#[derive(Clone, Debug)]
+pub(crate) struct WithdrawalGadget<F> {
+    index: Cell<F>,
+    validator_index: Cell<F>,
+    address: Cell<F>,
+    // Amount in Gwei, as in the withdrawal table
+    amount: Cell<F>,
+    amount_wei: Word<F>,
+    increase_balance: UpdateBalanceGadget<F, 2, true>,
+}
+
+impl<F: Field> ExecutionGadget<F> for WithdrawalGadget<F> {
+    const NAME: &'static str = "Withdrawal";
+
+    const EXECUTION_STATE: ExecutionState = ExecutionState::Withdrawal;
+
+    fn configure(cb: &mut EVMConstraintBuilder<F>) -> Self {
+        // A block without txs starts with its first withdrawal, with a zero
+        // call_id as there is no last tx.
+        cb.step_first(|cb| {
+            cb.require_zero("log_id is initialized to be 0", cb.curr.state.log_id.expr());
+            cb.require_zero(
+                "call_id is initialized to be 0",
+                cb.curr.state.call_id.expr(),
+            );
+        });
+
+        let index = cb.query_cell();
+        let validator_index = cb.query_cell();
+        let address = cb.query_cell();
+        let amount = cb.query_cell();
+        cb.withdrawal_table_lookup(
+            cb.curr.state.log_id.expr() + 1.expr(),
+            index.expr(),
+            validator_index.expr(),
+            address.expr(),
+            amount.expr(),
+        );
+
+        // The amount in Wei is below 2^128, as the amount in Gwei is a u64.
+        let amount_wei = cb.query_word_rlc();
+        cb.require_equal(
+            "amount_wei == amount * 10^9",
+            from_bytes::expr(&amount_wei.cells[..16]),
+            amount.expr() * 1_000_000_000u64.expr(),
+        );
+        cb.require_zero(
+            "amount_wei < 2^128",
+            from_bytes::expr(&amount_wei.cells[16..]),
+        );
+        let increase_balance =
+            UpdateBalanceGadget::construct(cb, address.expr(), vec![amount_wei.clone()], None);
+
+        cb.require_step_state_transition(StepStateTransition {
+            rw_counter: Delta(1.expr()),
+            // We propagate call_id so that EndBlock can get the last tx_id
+            // in order to count processed txs.
+            call_id: Same,
+            log_id: Delta(1.expr()),
+            ..StepStateTransition::any()
+        });
+
+        Self {
+            index,
+            validator_index,
+            address,
+            amount,
+            amount_wei,
+            increase_balance,
+        }
+    }
+
+    fn assign_exec_step(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        block: &Block<F>,
+        _: &Transaction,
+        _: &Call,
+        step: &ExecStep,
+    ) -> Result<(), Error> {
+        let withdrawal = &block.withdrawals[step.log_id];
+        for (cell, value) in [
+            (&self.index, F::from(withdrawal.index.as_u64())),
+            (
+                &self.validator_index,
+                F::from(withdrawal.validator_index.as_u64()),
+            ),
+            (
+                &self.address,
+                withdrawal
+                    .address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure"),
+            ),
+            (&self.amount, F::from(withdrawal.amount.as_u64())),
+        ] {
+            cell.assign(region, offset, Value::known(value))?;
+        }
+
+        let amount_wei = withdrawal.amount_wei();
+        self.amount_wei
+            .assign(region, offset, Some(amount_wei.to_le_bytes()))?;
+        let (balance, balance_prev) = block.get_rws(step, 0).account_value_pair();
+        self.increase_balance
+            .assign(region, offset, balance_prev, vec![amount_wei], balance)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::test_util::CircuitTestBuilder;
+    use bus_mapping::circuit_input_builder::CircuitsParams;
+    use eth_types::{bytecode, evm_types::Hardfork, geth_types::Withdrawal, Address, ToWord, Word};
+    use mock::{TestContext, MOCK_WITHDRAWALS};
+
+    const CONTRACT: Address = Address::repeat_byte(0x20);
+    const BENEFICIARY: Address = Address::repeat_byte(0x30);
+
+    #[test]
+    fn withdrawals_to_same_address() {
+        let withdrawals = MOCK_WITHDRAWALS
+            .iter()
+            .map(|withdrawal| Withdrawal {
+                address: BENEFICIARY,
+                ..withdrawal.clone()
+            })
+            .collect::<Vec<_>>();
+        let ctx = TestContext::<0, 0>::new(
+            None,
+            |_| {},
+            |_, _| {},
+            |block, _| {
+                block
+                    .hardfork(Hardfork::Shanghai)
+                    .withdrawals(withdrawals.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+
+    #[test]
+    fn withdrawal_after_destruct_account() {
+        let ctx = TestContext::<3, 1>::new(
+            None,
+            |accs| {
+                accs[0]
+                    .address(MOCK_WITHDRAWALS[1].address)
+                    .balance(Word::from(1u64 << 30));
+                accs[1]
+                    .address(CONTRACT)
+                    .balance(Word::from(800u64))
+                    .code(bytecode! {
+                        PUSH20(BENEFICIARY.to_word())
+                        SELFDESTRUCT
+                    });
+                accs[2].address(BENEFICIARY).balance(Word::from(1u64 << 20));
+            },
+            |mut txs, accs| {
+                txs[0]
+                    .from(accs[0].address)
+                    .to(accs[1].address)
+                    .gas(Word::from(100000));
+            },
+            |block, _tx| {
+                block
+                    .number(0xcafeu64)
+                    .hardfork(Hardfork::Shanghai)
+                    .withdrawals(MOCK_WITHDRAWALS.clone())
+            },
+        )
+        .unwrap();
+
+        CircuitTestBuilder::new_from_test_ctx(ctx)
+            .params(CircuitsParams {
+                hardfork: Hardfork::Shanghai,
+                ..Default::default()
+            })
+            .run();
+    }
+}
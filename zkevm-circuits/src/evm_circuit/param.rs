@@ -35,7 +35,15 @@ pub(crate) const EVM_LOOKUP_COLS: usize = FIXED_TABLE_LOOKUPS
     + BLOCK_TABLE_LOOKUPS
     + COPY_TABLE_LOOKUPS
     + KECCAK_TABLE_LOOKUPS
-    + EXP_TABLE_LOOKUPS;
+    + EXP_TABLE_LOOKUPS
+    + ECRECOVER_TABLE_LOOKUPS
+    + SHA256_TABLE_LOOKUPS
+    + MODEXP_TABLE_LOOKUPS
+    + EC_ADD_TABLE_LOOKUPS
+    + EC_MUL_TABLE_LOOKUPS
+    + EC_PAIRING_TABLE_LOOKUPS
+    + WITHDRAWAL_TABLE_LOOKUPS
+    + UNCLE_TABLE_LOOKUPS;
 
 /// Lookups done per row.
 pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
@@ -47,6 +55,14 @@ pub const LOOKUP_CONFIG: &[(Table, usize)] = &[
     (Table::Copy, COPY_TABLE_LOOKUPS),
     (Table::Keccak, KECCAK_TABLE_LOOKUPS),
     (Table::Exp, EXP_TABLE_LOOKUPS),
+    (Table::EcRecover, ECRECOVER_TABLE_LOOKUPS),
+    (Table::Sha256, SHA256_TABLE_LOOKUPS),
+    (Table::ModExp, MODEXP_TABLE_LOOKUPS),
+    (Table::EcAdd, EC_ADD_TABLE_LOOKUPS),
+    (Table::EcMul, EC_MUL_TABLE_LOOKUPS),
+    (Table::EcPairing, EC_PAIRING_TABLE_LOOKUPS),
+    (Table::Withdrawal, WITHDRAWAL_TABLE_LOOKUPS),
+    (Table::Uncle, UNCLE_TABLE_LOOKUPS),
 ];
 
 /// Fixed Table lookups done in EVMCircuit
@@ -73,6 +89,30 @@ pub const KECCAK_TABLE_LOOKUPS: usize = 1;
 /// Exp Table lookups done in EVMCircuit
 pub const EXP_TABLE_LOOKUPS: usize = 1;
 
+/// EcRecover Table lookups done in EVMCircuit
+pub const ECRECOVER_TABLE_LOOKUPS: usize = 1;
+
+/// Sha256 Table lookups done in EVMCircuit
+pub const SHA256_TABLE_LOOKUPS: usize = 1;
+
+/// ModExp Table lookups done in EVMCircuit
+pub const MODEXP_TABLE_LOOKUPS: usize = 1;
+
+/// EcAdd Table lookups done in EVMCircuit
+pub const EC_ADD_TABLE_LOOKUPS: usize = 1;
+
+/// EcMul Table lookups done in EVMCircuit
+pub const EC_MUL_TABLE_LOOKUPS: usize = 1;
+
+/// EcPairing Table lookups done in EVMCircuit
+pub const EC_PAIRING_TABLE_LOOKUPS: usize = 1;
+
+/// Withdrawal Table lookups done in EVMCircuit
+pub const WITHDRAWAL_TABLE_LOOKUPS: usize = 1;
+
+/// Uncle Table lookups done in EVMCircuit
+pub const UNCLE_TABLE_LOOKUPS: usize = 1;
+
 /// Maximum number of bytes that an integer can fit in field without wrapping
 /// around.
 pub(crate) const MAX_N_BYTES_INTEGER: usize = 31;
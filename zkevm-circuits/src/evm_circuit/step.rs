@@ -11,8 +11,9 @@ use bus_mapping::{
     circuit_input_builder::ExecState,
     error::{DepthError, ExecError, InsufficientBalanceError, NonceUintOverflowError, OogError},
     evm::OpcodeId,
+    precompile::PrecompileCalls,
 };
-use eth_types::{evm_unimplemented, Field, ToWord};
+use eth_types::{evm_types::Hardfork, Field, ToWord};
 use halo2_proofs::{
     circuit::Value,
     plonk::{Advice, Column, ConstraintSystem, Error, Expression},
@@ -27,6 +28,9 @@ pub enum ExecutionState {
     // Internal state
     BeginTx,
     EndTx,
+    DestructAccount,
+    Withdrawal,
+    BlockReward,
     EndBlock,
     // Opcode successful cases
     STOP,
@@ -68,6 +72,8 @@ pub enum ExecutionState {
     BLOCKCTXU256, // DIFFICULTY, BASEFEE
     CHAINID,
     SELFBALANCE,
+    BLOBHASH,
+    BLOBBASEFEE,
     POP,
     MEMORY, // MLOAD, MSTORE, MSTORE8
     SLOAD,
@@ -78,6 +84,10 @@ pub enum ExecutionState {
     MSIZE,
     GAS,
     JUMPDEST,
+    TLOAD,
+    TSTORE,
+    MCOPY,
+    PUSH0,
     PUSH, // PUSH1, PUSH2, ..., PUSH32
     DUP,  // DUP1, DUP2, ..., DUP16
     SWAP, // SWAP1, SWAP2, ..., SWAP16
@@ -87,6 +97,16 @@ pub enum ExecutionState {
     RETURN_REVERT, // RETURN, REVERT
     CREATE2,
     SELFDESTRUCT,
+    // Precompile calls
+    PrecompileEcRecover,
+    PrecompileSha256,
+    PrecompileRipemd160,
+    PrecompileIdentity,
+    PrecompileBigModExp,
+    PrecompileBn256Add,
+    PrecompileBn256ScalarMul,
+    PrecompileBn256Pairing,
+    PrecompileBlake2f,
     // Error cases
     ErrorInvalidOpcode,
     ErrorStack,
@@ -96,6 +116,7 @@ pub enum ExecutionState {
     ErrorContractAddressCollision,
     ErrorInvalidCreationCode,
     ErrorMaxCodeSizeExceeded,
+    ErrorMaxInitCodeSizeExceeded,
     ErrorInvalidJump,
     ErrorReturnDataOutOfBound,
     ErrorOutOfGasConstant,
@@ -153,6 +174,7 @@ impl From<&ExecError> for ExecutionState {
             ExecError::ReturnDataOutOfBounds => ExecutionState::ErrorReturnDataOutOfBound,
             ExecError::CodeStoreOutOfGas => ExecutionState::ErrorOutOfGasCodeStore,
             ExecError::MaxCodeSizeExceeded => ExecutionState::ErrorMaxCodeSizeExceeded,
+            ExecError::MaxInitCodeSizeExceeded => ExecutionState::ErrorMaxInitCodeSizeExceeded,
             ExecError::OutOfGas(oog_error) => match oog_error {
                 OogError::Constant => ExecutionState::ErrorOutOfGasConstant,
                 OogError::StaticMemoryExpansion => {
@@ -185,7 +207,7 @@ impl From<&ExecStep> for ExecutionState {
                 if op.is_dup() {
                     return ExecutionState::DUP;
                 }
-                if op.is_push() {
+                if op.is_push_with_data() {
                     return ExecutionState::PUSH;
                 }
                 if op.is_swap() {
@@ -195,13 +217,6 @@ impl From<&ExecStep> for ExecutionState {
                     return ExecutionState::LOG;
                 }
 
-                macro_rules! dummy {
-                    ($name:expr) => {{
-                        evm_unimplemented!("{:?} is implemented with DummyGadget", $name);
-                        $name
-                    }};
-                }
-
                 match op {
                     OpcodeId::ADD | OpcodeId::SUB => ExecutionState::ADD_SUB,
                     OpcodeId::ADDMOD => ExecutionState::ADDMOD,
@@ -220,6 +235,7 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::NOT => ExecutionState::NOT,
                     OpcodeId::EXP => ExecutionState::EXP,
                     OpcodeId::POP => ExecutionState::POP,
+                    OpcodeId::PUSH0 => ExecutionState::PUSH0,
                     OpcodeId::PUSH32 => ExecutionState::PUSH,
                     OpcodeId::BYTE => ExecutionState::BYTE,
                     OpcodeId::MLOAD => ExecutionState::MEMORY,
@@ -244,10 +260,15 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::GAS => ExecutionState::GAS,
                     OpcodeId::SAR => ExecutionState::SAR,
                     OpcodeId::SELFBALANCE => ExecutionState::SELFBALANCE,
+                    OpcodeId::BLOBHASH => ExecutionState::BLOBHASH,
+                    OpcodeId::BLOBBASEFEE => ExecutionState::BLOBBASEFEE,
                     OpcodeId::SHA3 => ExecutionState::SHA3,
                     OpcodeId::SHL | OpcodeId::SHR => ExecutionState::SHL_SHR,
                     OpcodeId::SLOAD => ExecutionState::SLOAD,
                     OpcodeId::SSTORE => ExecutionState::SSTORE,
+                    OpcodeId::TLOAD => ExecutionState::TLOAD,
+                    OpcodeId::TSTORE => ExecutionState::TSTORE,
+                    OpcodeId::MCOPY => ExecutionState::MCOPY,
                     OpcodeId::CALLDATASIZE => ExecutionState::CALLDATASIZE,
                     OpcodeId::CALLDATACOPY => ExecutionState::CALLDATACOPY,
                     OpcodeId::CHAINID => ExecutionState::CHAINID,
@@ -264,16 +285,35 @@ impl From<&ExecStep> for ExecutionState {
                     OpcodeId::RETURN | OpcodeId::REVERT => ExecutionState::RETURN_REVERT,
                     OpcodeId::RETURNDATASIZE => ExecutionState::RETURNDATASIZE,
                     OpcodeId::RETURNDATACOPY => ExecutionState::RETURNDATACOPY,
-                    // dummy ops
-                    OpcodeId::CREATE => dummy!(ExecutionState::CREATE),
-                    OpcodeId::CREATE2 => dummy!(ExecutionState::CREATE2),
-                    OpcodeId::SELFDESTRUCT => dummy!(ExecutionState::SELFDESTRUCT),
+                    OpcodeId::CREATE => ExecutionState::CREATE,
+                    OpcodeId::CREATE2 => ExecutionState::CREATE2,
+                    OpcodeId::SELFDESTRUCT => ExecutionState::SELFDESTRUCT,
                     _ => unimplemented!("unimplemented opcode {:?}", op),
                 }
             }
             ExecState::BeginTx => ExecutionState::BeginTx,
             ExecState::EndTx => ExecutionState::EndTx,
+            ExecState::DestructAccount => ExecutionState::DestructAccount,
+            ExecState::Withdrawal => ExecutionState::Withdrawal,
+            ExecState::BlockReward => ExecutionState::BlockReward,
             ExecState::EndBlock => ExecutionState::EndBlock,
+            ExecState::Precompile(precompile) => precompile.into(),
+        }
+    }
+}
+
+impl From<PrecompileCalls> for ExecutionState {
+    fn from(precompile: PrecompileCalls) -> Self {
+        match precompile {
+            PrecompileCalls::ECRecover => ExecutionState::PrecompileEcRecover,
+            PrecompileCalls::Sha256 => ExecutionState::PrecompileSha256,
+            PrecompileCalls::Ripemd160 => ExecutionState::PrecompileRipemd160,
+            PrecompileCalls::Identity => ExecutionState::PrecompileIdentity,
+            PrecompileCalls::Modexp => ExecutionState::PrecompileBigModExp,
+            PrecompileCalls::Bn128Add => ExecutionState::PrecompileBn256Add,
+            PrecompileCalls::Bn128Mul => ExecutionState::PrecompileBn256ScalarMul,
+            PrecompileCalls::Bn128Pairing => ExecutionState::PrecompileBn256Pairing,
+            PrecompileCalls::Blake2F => ExecutionState::PrecompileBlake2f,
         }
     }
 }
@@ -305,6 +345,7 @@ impl ExecutionState {
                 | Self::ErrorWriteProtection
                 | Self::ErrorInvalidCreationCode
                 | Self::ErrorMaxCodeSizeExceeded
+                | Self::ErrorMaxInitCodeSizeExceeded
                 | Self::ErrorInvalidJump
                 | Self::ErrorReturnDataOutOfBound
                 | Self::ErrorOutOfGasConstant
@@ -327,11 +368,17 @@ impl ExecutionState {
     pub(crate) fn halts(&self) -> bool {
         matches!(self, Self::STOP | Self::RETURN_REVERT | Self::SELFDESTRUCT)
             || self.halts_in_exception()
+            || self.precompile().is_some()
+    }
+
+    /// Returns the precompile called in this execution state, if any.
+    pub(crate) fn precompile(&self) -> Option<PrecompileCalls> {
+        PrecompileCalls::iter().find(|precompile| Self::from(*precompile) == *self)
     }
 
-    pub fn responsible_opcodes(&self) -> Vec<ResponsibleOp> {
+    pub fn responsible_opcodes(&self, hardfork: Hardfork) -> Vec<ResponsibleOp> {
         if matches!(self, Self::ErrorStack) {
-            return OpcodeId::valid_opcodes()
+            return OpcodeId::valid_opcodes(hardfork)
                 .into_iter()
                 .flat_map(|op| {
                     op.invalid_stack_ptrs()
@@ -381,6 +428,8 @@ impl ExecutionState {
             Self::BLOCKCTXU256 => vec![OpcodeId::DIFFICULTY, OpcodeId::BASEFEE],
             Self::CHAINID => vec![OpcodeId::CHAINID],
             Self::SELFBALANCE => vec![OpcodeId::SELFBALANCE],
+            Self::BLOBHASH => vec![OpcodeId::BLOBHASH],
+            Self::BLOBBASEFEE => vec![OpcodeId::BLOBBASEFEE],
             Self::POP => vec![OpcodeId::POP],
             Self::MEMORY => {
                 vec![OpcodeId::MLOAD, OpcodeId::MSTORE, OpcodeId::MSTORE8]
@@ -393,6 +442,10 @@ impl ExecutionState {
             Self::MSIZE => vec![OpcodeId::MSIZE],
             Self::GAS => vec![OpcodeId::GAS],
             Self::JUMPDEST => vec![OpcodeId::JUMPDEST],
+            Self::TLOAD => vec![OpcodeId::TLOAD],
+            Self::TSTORE => vec![OpcodeId::TSTORE],
+            Self::MCOPY => vec![OpcodeId::MCOPY],
+            Self::PUSH0 => vec![OpcodeId::PUSH0],
             Self::PUSH => vec![
                 OpcodeId::PUSH1,
                 OpcodeId::PUSH2,
@@ -480,10 +533,13 @@ impl ExecutionState {
             Self::RETURN_REVERT => vec![OpcodeId::RETURN, OpcodeId::REVERT],
             Self::CREATE2 => vec![OpcodeId::CREATE2],
             Self::SELFDESTRUCT => vec![OpcodeId::SELFDESTRUCT],
-            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(),
+            Self::ErrorInvalidOpcode => OpcodeId::invalid_opcodes(hardfork),
+            Self::ErrorMaxInitCodeSizeExceeded => vec![OpcodeId::CREATE, OpcodeId::CREATE2],
             _ => vec![],
         }
         .into_iter()
+        // The opcodes not defined by the hardfork are left to ErrorInvalidOpcode
+        .filter(|op| matches!(op, OpcodeId::INVALID(_)) || op.is_enabled(hardfork))
         .map(Into::into)
         .collect()
     }
@@ -3,7 +3,10 @@ use crate::{
     impl_expr,
 };
 use bus_mapping::evm::OpcodeId;
-use eth_types::Field;
+use eth_types::{
+    evm_types::{BlockReward, Hardfork},
+    Field,
+};
 use gadgets::util::Expr;
 use halo2_proofs::plonk::Expression;
 use strum::IntoEnumIterator;
@@ -27,11 +30,17 @@ pub enum FixedTableTag {
     ResponsibleOpcode,
     Pow2,
     ConstantGasCost,
+    HardforkParam,
+    UncleReward,
 }
 impl_expr!(FixedTableTag);
 
 impl FixedTableTag {
-    pub fn build<F: Field>(&self) -> Box<dyn Iterator<Item = [F; 4]>> {
+    pub fn build<F: Field>(
+        &self,
+        hardfork: Hardfork,
+        block_reward: BlockReward,
+    ) -> Box<dyn Iterator<Item = [F; 4]>> {
         let tag = F::from(*self as u64);
         match self {
             Self::Zero => Box::new((0..1).map(move |_| [tag, F::ZERO, F::ZERO, F::ZERO])),
@@ -78,8 +87,10 @@ impl FixedTableTag {
             })),
             Self::ResponsibleOpcode => {
                 Box::new(ExecutionState::iter().flat_map(move |execution_state| {
-                    execution_state.responsible_opcodes().into_iter().map(
-                        move |responsible_opcode| {
+                    execution_state
+                        .responsible_opcodes(hardfork)
+                        .into_iter()
+                        .map(move |responsible_opcode| {
                             let (op, aux) = match responsible_opcode {
                                 ResponsibleOp::Op(op) => (op, F::ZERO),
                                 ResponsibleOp::InvalidStackPtr(op, stack_ptr) => {
@@ -92,8 +103,7 @@ impl FixedTableTag {
                                 F::from(op.as_u64()),
                                 aux,
                             ]
-                        },
-                    )
+                        })
                 }))
             }
             Self::Pow2 => Box::new((0..256).map(move |value| {
@@ -116,6 +126,71 @@ impl FixedTableTag {
                         ]
                     }),
             ),
+            Self::HardforkParam => Box::new(HardforkParam::iter().map(move |param| {
+                [
+                    tag,
+                    F::from(param as u64),
+                    F::from(param.value(hardfork, block_reward)),
+                    F::ZERO,
+                ]
+            })),
+            // The reward of the miner of an uncle at each depth from 1 to 7,
+            // where the depth is 8 plus the number of the uncle minus the
+            // number of the block.
+            Self::UncleReward => Box::new((1..8).map(move |depth| {
+                [
+                    tag,
+                    F::from(depth),
+                    F::from(block_reward.uncle_reward(hardfork, 8, depth).as_u64()),
+                    F::ZERO,
+                ]
+            })),
+        }
+    }
+}
+
+/// Execution rule parameters whose value depends on the hardfork of the block,
+/// and for the rewards also on the consensus of the network.
+#[derive(Clone, Copy, Debug, EnumIter)]
+pub enum HardforkParam {
+    /// Quotient of the gas used bounding the refund (EIP-3529)
+    MaxRefundQuotientOfGasUsed,
+    /// Refund for clearing a storage slot (EIP-3529)
+    SstoreClearsSchedule,
+    /// Gas cost per word of init code (EIP-3860)
+    InitCodeWordGas,
+    /// Maximum size of the init code, unbounded before Shanghai (EIP-3860)
+    MaxInitCodeSize,
+    /// Whether the coinbase is warm at the start of a tx (EIP-3651)
+    WarmCoinbase,
+    /// Whether DIFFICULTY returns the prevRandao of the block (EIP-4399)
+    PrevRandao,
+    /// Refund for the first SELFDESTRUCT of an account in a tx (EIP-3529)
+    SelfdestructRefund,
+    /// Whether SELFDESTRUCT only deletes accounts created in the same tx (EIP-6780)
+    SelfdestructOnlyCreated,
+    /// Reward of the miner of the block, in Wei
+    BlockReward,
+    /// Reward of the miner of the block for each uncle it includes, in Wei
+    NephewReward,
+}
+impl_expr!(HardforkParam);
+
+impl HardforkParam {
+    /// Returns the value of the parameter under the rules of the `hardfork`
+    /// and the `block_reward` of the network.
+    pub fn value(&self, hardfork: Hardfork, block_reward: BlockReward) -> u64 {
+        match self {
+            Self::MaxRefundQuotientOfGasUsed => hardfork.max_refund_quotient_of_gas_used(),
+            Self::SstoreClearsSchedule => hardfork.sstore_clears_schedule(),
+            Self::InitCodeWordGas => hardfork.init_code_word_gas(),
+            Self::MaxInitCodeSize => hardfork.max_init_code_size().unwrap_or(u64::MAX),
+            Self::WarmCoinbase => hardfork.is_coinbase_warm() as u64,
+            Self::PrevRandao => hardfork.has_prevrandao() as u64,
+            Self::SelfdestructRefund => hardfork.selfdestruct_refund(),
+            Self::SelfdestructOnlyCreated => hardfork.has_eip6780() as u64,
+            Self::BlockReward => block_reward.block_reward(hardfork).as_u64(),
+            Self::NephewReward => block_reward.nephew_reward(hardfork).as_u64(),
         }
     }
 }
@@ -130,6 +205,14 @@ pub enum Table {
     Copy,
     Keccak,
     Exp,
+    EcRecover,
+    Sha256,
+    ModExp,
+    EcAdd,
+    EcMul,
+    EcPairing,
+    Withdrawal,
+    Uncle,
 }
 
 #[derive(Clone, Debug)]
@@ -279,6 +362,95 @@ pub(crate) enum Lookup<F> {
         exponent_lo_hi: [Expression<F>; 2],
         exponentiation_lo_hi: [Expression<F>; 2],
     },
+    /// Lookup to ecRecover table.
+    EcRecoverTable {
+        /// Keccak hash of the signed message.
+        msg_hash_rlc: Expression<F>,
+        /// v-component of the signature.
+        sig_v_rlc: Expression<F>,
+        /// r-component of the signature.
+        sig_r_rlc: Expression<F>,
+        /// s-component of the signature.
+        sig_s_rlc: Expression<F>,
+        /// Recovered address, 0 when the recovery fails.
+        recovered_addr: Expression<F>,
+    },
+    /// Lookup to sha256 table.
+    Sha256Table {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Length of the input.
+        input_len: Expression<F>,
+        /// RLC of the sha256 hash of the input.
+        output_rlc: Expression<F>,
+    },
+    /// Lookup to modexp table, with the words split in their lo and hi 128
+    /// bits.
+    ModExpTable {
+        /// Base of the exponentiation.
+        base: [Expression<F>; 2],
+        /// Exponent of the exponentiation.
+        exponent: [Expression<F>; 2],
+        /// Modulus of the exponentiation.
+        modulus: [Expression<F>; 2],
+        /// Result of the exponentiation.
+        output: [Expression<F>; 2],
+    },
+    /// Lookup to ecAdd table, with the coordinates split in their lo and hi
+    /// 128 bits.
+    EcAddTable {
+        /// Coordinates of the first point.
+        p: [[Expression<F>; 2]; 2],
+        /// Coordinates of the second point.
+        q: [[Expression<F>; 2]; 2],
+        /// Whether both points are valid.
+        is_valid: Expression<F>,
+        /// Coordinates of the sum.
+        r: [[Expression<F>; 2]; 2],
+    },
+    /// Lookup to ecMul table, with the coordinates and the scalar split in
+    /// their lo and hi 128 bits.
+    EcMulTable {
+        /// Coordinates of the point.
+        p: [[Expression<F>; 2]; 2],
+        /// Scalar.
+        scalar: [Expression<F>; 2],
+        /// Whether the point is valid.
+        is_valid: Expression<F>,
+        /// Coordinates of the product.
+        r: [[Expression<F>; 2]; 2],
+    },
+    /// Lookup to ecPairing table.
+    EcPairingTable {
+        /// Accumulator to the input.
+        input_rlc: Expression<F>,
+        /// Whether all the points are valid.
+        is_valid: Expression<F>,
+        /// Whether the product of the pairings is 1.
+        output: Expression<F>,
+    },
+    /// Lookup to withdrawal table.
+    WithdrawalTable {
+        /// Position of the withdrawal in the block, starting at 1.
+        id: Expression<F>,
+        /// Index of the withdrawal.
+        index: Expression<F>,
+        /// Index of the validator.
+        validator_index: Expression<F>,
+        /// Recipient of the withdrawal.
+        address: Expression<F>,
+        /// Amount of the withdrawal, in Gwei.
+        amount: Expression<F>,
+    },
+    /// Lookup to uncle table.
+    UncleTable {
+        /// Position of the uncle in the block, starting at 1.
+        id: Expression<F>,
+        /// Number of the uncle.
+        number: Expression<F>,
+        /// Beneficiary of the uncle reward.
+        miner: Expression<F>,
+    },
     /// Conditional lookup enabled by the first element.
     Conditional(Expression<F>, Box<Lookup<F>>),
 }
@@ -298,6 +470,14 @@ impl<F: Field> Lookup<F> {
             Self::CopyTable { .. } => Table::Copy,
             Self::KeccakTable { .. } => Table::Keccak,
             Self::ExpTable { .. } => Table::Exp,
+            Self::EcRecoverTable { .. } => Table::EcRecover,
+            Self::Sha256Table { .. } => Table::Sha256,
+            Self::ModExpTable { .. } => Table::ModExp,
+            Self::EcAddTable { .. } => Table::EcAdd,
+            Self::EcMulTable { .. } => Table::EcMul,
+            Self::EcPairingTable { .. } => Table::EcPairing,
+            Self::WithdrawalTable { .. } => Table::Withdrawal,
+            Self::UncleTable { .. } => Table::Uncle,
             Self::Conditional(_, lookup) => lookup.table(),
         }
     }
@@ -409,6 +589,90 @@ impl<F: Field> Lookup<F> {
                 exponentiation_lo_hi[0].clone(),
                 exponentiation_lo_hi[1].clone(),
             ],
+            Self::EcRecoverTable {
+                msg_hash_rlc,
+                sig_v_rlc,
+                sig_r_rlc,
+                sig_s_rlc,
+                recovered_addr,
+            } => vec![
+                1.expr(), // is_enabled
+                msg_hash_rlc.clone(),
+                sig_v_rlc.clone(),
+                sig_r_rlc.clone(),
+                sig_s_rlc.clone(),
+                recovered_addr.clone(),
+            ],
+            Self::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                input_len.clone(),
+                output_rlc.clone(),
+            ],
+            Self::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                output,
+            } => [
+                vec![1.expr()], // is_enabled
+                base.to_vec(),
+                exponent.to_vec(),
+                modulus.to_vec(),
+                output.to_vec(),
+            ]
+            .concat(),
+            Self::EcAddTable { p, q, is_valid, r } => [
+                vec![1.expr()], // is_enabled
+                p.concat(),
+                q.concat(),
+                vec![is_valid.clone()],
+                r.concat(),
+            ]
+            .concat(),
+            Self::EcMulTable {
+                p,
+                scalar,
+                is_valid,
+                r,
+            } => [
+                vec![1.expr()], // is_enabled
+                p.concat(),
+                scalar.to_vec(),
+                vec![is_valid.clone()],
+                r.concat(),
+            ]
+            .concat(),
+            Self::EcPairingTable {
+                input_rlc,
+                is_valid,
+                output,
+            } => vec![
+                1.expr(), // is_enabled
+                input_rlc.clone(),
+                is_valid.clone(),
+                output.clone(),
+            ],
+            Self::WithdrawalTable {
+                id,
+                index,
+                validator_index,
+                address,
+                amount,
+            } => vec![
+                id.clone(),
+                index.clone(),
+                validator_index.clone(),
+                address.clone(),
+                amount.clone(),
+            ],
+            Self::UncleTable { id, number, miner } => {
+                vec![id.clone(), number.clone(), miner.clone()]
+            }
             Self::Conditional(condition, lookup) => lookup
                 .input_exprs()
                 .into_iter()
@@ -1,6 +1,6 @@
 use super::{
     constraint_builder::ConstrainBuilderCommon,
-    from_bytes,
+    from_bytes, is_precompiled,
     math_gadget::{IsEqualGadget, IsZeroGadget, LtGadget},
     memory_gadget::{MemoryAddressGadget, MemoryExpansionGadget},
     CachedRegion,
@@ -23,7 +23,8 @@ use crate::{
     util::Expr,
     witness::{Block, Call, ExecStep},
 };
-use eth_types::{evm_types::GasCost, Field, ToLittleEndian, ToScalar, U256};
+use bus_mapping::precompile::PRECOMPILE_COUNT;
+use eth_types::{evm_types::GasCost, Address, Field, ToLittleEndian, ToScalar, U256};
 use gadgets::util::{select, sum};
 use halo2_proofs::{
     circuit::Value,
@@ -374,10 +375,8 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
         let value_is_zero = IsZeroGadget::construct(cb, value.expr());
         // If receiver doesn't exist, create it
         cb.condition(
-            or::expr([
-                not::expr(value_is_zero.expr()) * not::expr(receiver_exists.clone()),
-                must_create.clone(),
-            ]),
+            not::expr(receiver_exists.clone())
+                * or::expr([not::expr(value_is_zero.expr()), must_create.clone()]),
             |cb| {
                 cb.account_write(
                     receiver_address.clone(),
@@ -419,10 +418,8 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
         // +1 Write Account (sender) Balance (Not Reversible tx fee)
         1.expr() +
         // +1 Write Account (receiver) CodeHash (account creation via code_hash update)
-        or::expr([
-            not::expr(self.value_is_zero.expr()) * not::expr(self.receiver_exists.clone()),
-            self.must_create.clone()]
-        ) * 1.expr() +
+        not::expr(self.receiver_exists.clone()) *
+            or::expr([not::expr(self.value_is_zero.expr()), self.must_create.clone()]) +
         // +1 Write Account (sender) Balance
         // +1 Write Account (receiver) Balance
         not::expr(self.value_is_zero.expr()) * 2.expr()
@@ -431,10 +428,8 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
     pub(crate) fn reversible_w_delta(&self) -> Expression<F> {
         // NOTE: Write Account (sender) Balance (Not Reversible tx fee)
         // +1 Write Account (receiver) CodeHash (account creation via code_hash update)
-        or::expr([
-            not::expr(self.value_is_zero.expr()) * not::expr(self.receiver_exists.clone()),
-            self.must_create.clone()]
-        ) * 1.expr() +
+        not::expr(self.receiver_exists.clone()) *
+            or::expr([not::expr(self.value_is_zero.expr()), self.must_create.clone()]) +
         // +1 Write Account (sender) Balance
         // +1 Write Account (receiver) Balance
         not::expr(self.value_is_zero.expr()) * 2.expr()
@@ -481,11 +476,15 @@ impl<F: Field> TransferWithGasFeeGadget<F> {
 /// The TransferGadget handles a transfer of value from sender to receiver.  The
 /// transfer is only performed if the value is not zero.  If the transfer is
 /// performed and the receiver account doesn't exist, it will be created by
-/// setting it's code_hash = EMPTY_HASH. This gadget is used in callop.
+/// setting it's code_hash = EMPTY_HASH.  When `must_create` is true the
+/// receiver is always created, which is the case for CREATE/CREATE2. This
+/// gadget is used in callop and create.
 #[derive(Clone, Debug)]
 pub(crate) struct TransferGadget<F> {
     sender: UpdateBalanceGadget<F, 2, false>,
     receiver: UpdateBalanceGadget<F, 2, true>,
+    receiver_exists: Expression<F>,
+    must_create: Expression<F>,
     pub(crate) value_is_zero: IsZeroGadget<F>,
 }
 
@@ -495,13 +494,15 @@ impl<F: Field> TransferGadget<F> {
         sender_address: Expression<F>,
         receiver_address: Expression<F>,
         receiver_exists: Expression<F>,
+        must_create: Expression<F>,
         value: Word<F>,
         reversion_info: &mut ReversionInfo<F>,
     ) -> Self {
         let value_is_zero = IsZeroGadget::construct(cb, value.expr());
         // If receiver doesn't exist, create it
         cb.condition(
-            not::expr(value_is_zero.expr()) * not::expr(receiver_exists),
+            not::expr(receiver_exists.clone())
+                * or::expr([not::expr(value_is_zero.expr()), must_create.clone()]),
             |cb| {
                 cb.account_write(
                     receiver_address.clone(),
@@ -532,10 +533,21 @@ impl<F: Field> TransferGadget<F> {
         Self {
             sender,
             receiver,
+            receiver_exists,
+            must_create,
             value_is_zero,
         }
     }
 
+    pub(crate) fn reversible_w_delta(&self) -> Expression<F> {
+        // +1 Write Account (receiver) CodeHash (account creation via code_hash update)
+        not::expr(self.receiver_exists.clone()) *
+            or::expr([not::expr(self.value_is_zero.expr()), self.must_create.clone()]) +
+        // +1 Write Account (sender) Balance
+        // +1 Write Account (receiver) Balance
+        not::expr(self.value_is_zero.expr()) * 2.expr()
+    }
+
     pub(crate) fn sender(&self) -> &UpdateBalanceGadget<F, 2, false> {
         &self.sender
     }
@@ -791,6 +803,50 @@ impl<F: Field, const IS_SUCCESS_CALL: bool> CommonCallGadget<F, IS_SUCCESS_CALL>
     }
 }
 
+/// Checks whether an address is one of the precompiled contracts, i.e. whether
+/// it's in the range `[1, PRECOMPILE_COUNT]`.
+#[derive(Clone, Debug)]
+pub(crate) struct PrecompileAddressGadget<F> {
+    address_is_zero: IsZeroGadget<F>,
+    address_lt: LtGadget<F, N_BYTES_ACCOUNT_ADDRESS>,
+}
+
+impl<F: Field> PrecompileAddressGadget<F> {
+    pub(crate) fn construct(cb: &mut EVMConstraintBuilder<F>, address: Expression<F>) -> Self {
+        let address_is_zero = IsZeroGadget::construct(cb, address.clone());
+        let address_lt = LtGadget::construct(cb, address, (PRECOMPILE_COUNT as u64 + 1).expr());
+
+        Self {
+            address_is_zero,
+            address_lt,
+        }
+    }
+
+    pub(crate) fn expr(&self) -> Expression<F> {
+        not::expr(self.address_is_zero.expr()) * self.address_lt.expr()
+    }
+
+    pub(crate) fn assign(
+        &self,
+        region: &mut CachedRegion<'_, '_, F>,
+        offset: usize,
+        address: Address,
+    ) -> Result<bool, Error> {
+        let address_scalar = address
+            .to_scalar()
+            .expect("unexpected Address -> Scalar conversion failure");
+        self.address_is_zero
+            .assign(region, offset, address_scalar)?;
+        self.address_lt.assign(
+            region,
+            offset,
+            address_scalar,
+            F::from(PRECOMPILE_COUNT as u64 + 1),
+        )?;
+        Ok(is_precompiled(&address))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub(crate) struct SloadGasGadget<F> {
     is_warm: Expression<F>,
@@ -2,18 +2,18 @@ use crate::{
     evm_circuit::{
         param::STACK_CAPACITY,
         step::{ExecutionState, Step},
-        table::{FixedTableTag, Lookup, RwValues},
+        table::{FixedTableTag, HardforkParam, Lookup, RwValues},
         util::{Cell, RandomLinearCombination, Word},
     },
     table::{
-        AccountFieldTag, BytecodeFieldTag, CallContextFieldTag, TxContextFieldTag, TxLogFieldTag,
-        TxReceiptFieldTag,
+        AccountFieldTag, BytecodeFieldTag, CallContextFieldTag, StorageFieldTag,
+        TxAccountFlagFieldTag, TxContextFieldTag, TxLogFieldTag, TxReceiptFieldTag,
     },
     util::{build_tx_log_expression, Challenges, Expr},
 };
-use bus_mapping::{operation::Target, state_db::EMPTY_CODE_HASH_LE};
+use bus_mapping::{operation::Target, precompile::PrecompileCalls, state_db::EMPTY_CODE_HASH_LE};
 use eth_types::Field;
-use gadgets::util::not;
+use gadgets::util::{not, sum};
 use halo2_proofs::{
     circuit::Value,
     plonk::{
@@ -22,6 +22,8 @@ use halo2_proofs::{
     },
 };
 
+use strum::IntoEnumIterator;
+
 use super::{rlc, CachedRegion, CellType, StoredExpression};
 
 // Max degree allowed in all expressions passing through the ConstraintBuilder.
@@ -470,6 +472,29 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         self.add_constraint("Constrain next execution state not", next_state.expr());
     }
 
+    /// Constrain the next execution state to be the call to the precompiled
+    /// contract at `address`.
+    pub(crate) fn require_next_precompile_state(&mut self, address: Expression<F>) {
+        let next_precompile = self
+            .next
+            .execution_state_selector(PrecompileCalls::iter().map(ExecutionState::from));
+        self.add_constraint(
+            "Constrain next execution state is a precompile call",
+            1.expr() - next_precompile,
+        );
+        let next_address = sum::expr(PrecompileCalls::iter().map(|precompile| {
+            u64::from(precompile).expr()
+                * self
+                    .next
+                    .execution_state_selector([ExecutionState::from(precompile)])
+        }));
+        self.require_equal(
+            "Constrain next precompile call matches the address",
+            next_address,
+            address,
+        );
+    }
+
     pub(crate) fn require_step_state_transition(
         &mut self,
         step_state_transition: StepStateTransition<F>,
@@ -544,6 +569,36 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Hardfork
+
+    /// Returns a cell holding the value of the `param` under the hardfork of
+    /// the block.
+    pub(crate) fn hardfork_param(&mut self, param: HardforkParam) -> Cell<F> {
+        let cell = self.query_cell();
+        self.add_lookup(
+            "hardfork param",
+            Lookup::Fixed {
+                tag: FixedTableTag::HardforkParam.expr(),
+                values: [param.expr(), cell.expr(), 0.expr()],
+            },
+        );
+        cell
+    }
+
+    /// Returns the reward of the miner of an uncle at `depth`, which is 8 plus
+    /// the number of the uncle minus the number of the block.
+    pub(crate) fn uncle_reward(&mut self, depth: Expression<F>) -> Cell<F> {
+        let cell = self.query_cell();
+        self.add_lookup(
+            "uncle reward",
+            Lookup::Fixed {
+                tag: FixedTableTag::UncleReward.expr(),
+                values: [depth, cell.expr(), 0.expr()],
+            },
+        );
+        cell
+    }
+
     // Opcode
 
     pub(crate) fn opcode_lookup(&mut self, opcode: Expression<F>, is_code: Expression<F>) {
@@ -857,6 +912,58 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Tx account flags
+
+    pub(crate) fn tx_account_flag_read(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        field_tag: TxAccountFlagFieldTag,
+        value: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TxAccountFlag read",
+            false.expr(),
+            Target::TxAccountFlag,
+            RwValues::new(
+                tx_id,
+                account_address,
+                field_tag.expr(),
+                0.expr(),
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    pub(crate) fn tx_account_flag_write(
+        &mut self,
+        tx_id: Expression<F>,
+        account_address: Expression<F>,
+        field_tag: TxAccountFlagFieldTag,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TxAccountFlag write",
+            Target::TxAccountFlag,
+            RwValues::new(
+                tx_id,
+                account_address,
+                field_tag.expr(),
+                0.expr(),
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
     // Tx Refund
 
     pub(crate) fn tx_refund_read(&mut self, tx_id: Expression<F>, value: Expression<F>) {
@@ -1006,6 +1113,80 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    pub(crate) fn account_storage_clear(
+        &mut self,
+        account_address: Expression<F>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "AccountStorage clear",
+            true.expr(),
+            Target::Storage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                StorageFieldTag::Clear.expr(),
+                0.expr(),
+                1.expr(),
+                0.expr(),
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    // Transient Storage
+
+    pub(crate) fn transient_storage_read(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        tx_id: Expression<F>,
+    ) {
+        self.rw_lookup(
+            "TransientStorage read",
+            false.expr(),
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value.clone(),
+                value,
+                0.expr(),
+                0.expr(),
+            ),
+        );
+    }
+
+    pub(crate) fn transient_storage_write(
+        &mut self,
+        account_address: Expression<F>,
+        key: Expression<F>,
+        value: Expression<F>,
+        value_prev: Expression<F>,
+        tx_id: Expression<F>,
+        reversion_info: Option<&mut ReversionInfo<F>>,
+    ) {
+        self.reversible_write(
+            "TransientStorage write",
+            Target::TransientStorage,
+            RwValues::new(
+                tx_id,
+                account_address,
+                0.expr(),
+                key,
+                value,
+                value_prev,
+                0.expr(),
+                0.expr(),
+            ),
+            reversion_info,
+        );
+    }
+
     // Call context
 
     pub(crate) fn call_context(
@@ -1306,6 +1487,135 @@ impl<'a, F: Field> EVMConstraintBuilder<'a, F> {
         );
     }
 
+    // Precompile Tables
+
+    pub(crate) fn ecrecover_table_lookup(
+        &mut self,
+        msg_hash_rlc: Expression<F>,
+        sig_v_rlc: Expression<F>,
+        sig_r_rlc: Expression<F>,
+        sig_s_rlc: Expression<F>,
+        recovered_addr: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ecrecover lookup",
+            Lookup::EcRecoverTable {
+                msg_hash_rlc,
+                sig_v_rlc,
+                sig_r_rlc,
+                sig_s_rlc,
+                recovered_addr,
+            },
+        );
+    }
+
+    pub(crate) fn sha256_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        input_len: Expression<F>,
+        output_rlc: Expression<F>,
+    ) {
+        self.add_lookup(
+            "sha256 lookup",
+            Lookup::Sha256Table {
+                input_rlc,
+                input_len,
+                output_rlc,
+            },
+        );
+    }
+
+    pub(crate) fn modexp_table_lookup(
+        &mut self,
+        base: [Expression<F>; 2],
+        exponent: [Expression<F>; 2],
+        modulus: [Expression<F>; 2],
+        output: [Expression<F>; 2],
+    ) {
+        self.add_lookup(
+            "modexp lookup",
+            Lookup::ModExpTable {
+                base,
+                exponent,
+                modulus,
+                output,
+            },
+        );
+    }
+
+    pub(crate) fn ec_add_table_lookup(
+        &mut self,
+        p: [[Expression<F>; 2]; 2],
+        q: [[Expression<F>; 2]; 2],
+        is_valid: Expression<F>,
+        r: [[Expression<F>; 2]; 2],
+    ) {
+        self.add_lookup("ec add lookup", Lookup::EcAddTable { p, q, is_valid, r });
+    }
+
+    pub(crate) fn ec_mul_table_lookup(
+        &mut self,
+        p: [[Expression<F>; 2]; 2],
+        scalar: [Expression<F>; 2],
+        is_valid: Expression<F>,
+        r: [[Expression<F>; 2]; 2],
+    ) {
+        self.add_lookup(
+            "ec mul lookup",
+            Lookup::EcMulTable {
+                p,
+                scalar,
+                is_valid,
+                r,
+            },
+        );
+    }
+
+    pub(crate) fn ec_pairing_table_lookup(
+        &mut self,
+        input_rlc: Expression<F>,
+        is_valid: Expression<F>,
+        output: Expression<F>,
+    ) {
+        self.add_lookup(
+            "ec pairing lookup",
+            Lookup::EcPairingTable {
+                input_rlc,
+                is_valid,
+                output,
+            },
+        );
+    }
+
+    pub(crate) fn withdrawal_table_lookup(
+        &mut self,
+        id: Expression<F>,
+        index: Expression<F>,
+        validator_index: Expression<F>,
+        address: Expression<F>,
+        amount: Expression<F>,
+    ) {
+        self.add_lookup(
+            "withdrawal lookup",
+            Lookup::WithdrawalTable {
+                id,
+                index,
+                validator_index,
+                address,
+                amount,
+            },
+        );
+    }
+
+    pub(crate) fn uncle_table_lookup(
+        &mut self,
+        id: Expression<F>,
+        number: Expression<F>,
+        miner: Expression<F>,
+    ) {
+        self.add_lookup("uncle lookup", Lookup::UncleTable { id, number, miner });
+    }
+
     // Validation
 
     pub(crate) fn validate_degree(&self, degree: usize, name: &'static str) {
@@ -100,6 +100,30 @@ impl Instrument {
                     CellType::Lookup(Table::Exp) => {
                         report.exp_table = data_entry;
                     }
+                    CellType::Lookup(Table::EcRecover) => {
+                        report.ecrecover_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Sha256) => {
+                        report.sha256_table = data_entry;
+                    }
+                    CellType::Lookup(Table::ModExp) => {
+                        report.modexp_table = data_entry;
+                    }
+                    CellType::Lookup(Table::EcAdd) => {
+                        report.ec_add_table = data_entry;
+                    }
+                    CellType::Lookup(Table::EcMul) => {
+                        report.ec_mul_table = data_entry;
+                    }
+                    CellType::Lookup(Table::EcPairing) => {
+                        report.ec_pairing_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Withdrawal) => {
+                        report.withdrawal_table = data_entry;
+                    }
+                    CellType::Lookup(Table::Uncle) => {
+                        report.uncle_table = data_entry;
+                    }
                 }
             }
             report_collection.push(report);
@@ -125,6 +149,14 @@ pub struct ExecStateReport {
     pub copy_table: StateReportRow,
     pub keccak_table: StateReportRow,
     pub exp_table: StateReportRow,
+    pub ecrecover_table: StateReportRow,
+    pub sha256_table: StateReportRow,
+    pub modexp_table: StateReportRow,
+    pub ec_add_table: StateReportRow,
+    pub ec_mul_table: StateReportRow,
+    pub ec_pairing_table: StateReportRow,
+    pub withdrawal_table: StateReportRow,
+    pub uncle_table: StateReportRow,
 }
 
 impl From<ExecutionState> for ExecStateReport {
@@ -109,6 +109,21 @@ impl<F: Field> ByteSizeGadget<F> {
                 .map(|(i, cell)| i.expr() * cell.expr()),
         )
     }
+
+    /// The most significant non-zero byte of the value, or 0 when the value is
+    /// 0.  `values` must be the same bytes the gadget was constructed with.
+    pub(crate) fn most_significant_byte(
+        &self,
+        values: &[Expression<F>; N_BYTES_WORD],
+    ) -> Expression<F> {
+        sum::expr(
+            self.most_significant_nonzero_byte_index
+                .iter()
+                .skip(1)
+                .zip(values.iter())
+                .map(|(index, value)| index.expr() * value.clone()),
+        )
+    }
 }
 
 #[cfg(test)]
@@ -35,6 +35,29 @@ impl<F: Field> LtWordGadget<F> {
         }
     }
 
+    /// Returns `1` when `lhs < rhs`, where `rhs` is a constant word.
+    pub(crate) fn construct_constant(
+        cb: &mut EVMConstraintBuilder<F>,
+        lhs: &util::Word<F>,
+        rhs: Word,
+    ) -> Self {
+        let (rhs_lo, rhs_hi) = split_u256(&rhs);
+        let comparison_hi = ComparisonGadget::construct(
+            cb,
+            from_bytes::expr(&lhs.cells[16..]),
+            Expression::Constant(F::from_u128(rhs_hi.as_u128())),
+        );
+        let lt_lo = LtGadget::construct(
+            cb,
+            from_bytes::expr(&lhs.cells[..16]),
+            Expression::Constant(F::from_u128(rhs_lo.as_u128())),
+        );
+        Self {
+            comparison_hi,
+            lt_lo,
+        }
+    }
+
     pub(crate) fn expr(&self) -> Expression<F> {
         let (hi_lt, hi_eq) = self.comparison_hi.expr();
         hi_lt + hi_eq * self.lt_lo.expr()
@@ -16,7 +16,10 @@ use crate::{
     table::LookupTable,
     util::Challenges,
 };
-use eth_types::{Field, Word, U256};
+use eth_types::{
+    evm_types::{BlockReward, Hardfork},
+    Field, Word, U256,
+};
 pub(crate) use halo2_proofs::circuit::{Layouter, Value};
 use halo2_proofs::{
     circuit::SimpleFloorPlanner,
@@ -231,7 +234,7 @@ impl<F: Field, G: MathGadgetContainer<F>> Circuit<F> for UnitTestMathGadgetBaseC
                                         | FixedTableTag::Range1024
                                 )
                             })
-                            .flat_map(|tag| tag.build()),
+                            .flat_map(|tag| tag.build(Hardfork::default(), BlockReward::default())),
                     )
                     .enumerate()
                 {
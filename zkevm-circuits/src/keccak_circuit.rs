@@ -1003,11 +1003,14 @@ impl<F: Field> SubCircuit<F> for KeccakCircuit<F> {
     /// Return the minimum number of rows required to prove the block
     fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
         let rows_per_chunk = (NUM_ROUNDS + 1) * get_num_rows_per_round();
+        // The padding of the inputs takes at least one byte, so that the
+        // inputs whose length is a multiple of the rate, such as the empty
+        // ones, need one more chunk.
         (
             block
                 .keccak_inputs
                 .iter()
-                .map(|bytes| (bytes.len() as f64 / 136.0).ceil() as usize * rows_per_chunk)
+                .map(|bytes| (bytes.len() / RATE + 1) * rows_per_chunk)
                 .sum(),
             block.circuits_params.max_keccak_rows,
         )
@@ -19,11 +19,16 @@
 
 pub mod bytecode_circuit;
 pub mod copy_circuit;
+pub mod ecc_circuit;
+pub mod ecrecover_circuit;
 pub mod evm_circuit;
 pub mod exp_circuit;
 pub mod keccak_circuit;
+pub mod modexp_circuit;
+pub mod mpt_circuit;
 pub mod pi_circuit;
 pub mod root_circuit;
+pub mod sha256_circuit;
 pub mod state_circuit;
 pub mod super_circuit;
 pub mod table;
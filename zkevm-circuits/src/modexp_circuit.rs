@@ -0,0 +1,651 @@
+//! The ModExp circuit implementation.
+//!
+//! Each modular exponentiation of a call to the MODEXP precompile takes
+//! `NUM_ROWS_PER_EVENT` rows: a squaring and a multiplication (by the base or
+//! by 1) for every bit of the exponent, from the most significant one.  Every
+//! row proves a modular multiplication `a * b == q * modulus + r` over byte
+//! range checked words, where `a` is the `r` of the previous row.  Only the
+//! `r` of the last row is required to be reduced, and it's exposed as the
+//! result in the `ModExpTable`, whose exponent columns are also used to
+//! accumulate the bits of the exponent.
+mod param;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::ModExpCircuit as TestModExpCircuit;
+
+use std::marker::PhantomData;
+
+use self::param::*;
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    table::{LookupTable, ModExpTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use bus_mapping::precompile::{ModExpEvent, ModExpStep};
+use eth_types::{Field, ToLittleEndian, Word};
+use gadgets::util::{
+    expr_from_bytes, not, pow_of_two, select, split_u256, split_u256_limb64, sum, Expr,
+};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+
+/// ModExpCircuitConfig
+#[derive(Clone, Debug)]
+pub struct ModExpCircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    q_square: Column<Fixed>,
+    q_exponent_hi: Column<Fixed>,
+    bit: Column<Advice>,
+    a_limbs: [Column<Advice>; 4],
+    b_limbs: [Column<Advice>; 4],
+    base: [Column<Advice>; 32],
+    modulus: [Column<Advice>; 32],
+    quotient: [Column<Advice>; 32],
+    remainder: [Column<Advice>; 32],
+    carries: [[Column<Advice>; NUM_CARRY_BYTES]; 3],
+    diff: [Column<Advice>; 32],
+    diff_carry: Column<Advice>,
+    u8_table: TableColumn,
+    /// The columns for the EVM circuit to lookup the modular exponentiations
+    pub modexp_table: ModExpTable,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct ModExpCircuitConfigArgs {
+    /// ModExpTable
+    pub modexp_table: ModExpTable,
+}
+
+impl<F: Field> SubCircuitConfig<F> for ModExpCircuitConfig<F> {
+    type ConfigArgs = ModExpCircuitConfigArgs;
+
+    /// Return a new ModExpCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs { modexp_table }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let q_square = meta.fixed_column();
+        let q_exponent_hi = meta.fixed_column();
+        let bit = meta.advice_column();
+        let a_limbs = array_init::array_init(|_| meta.advice_column());
+        let b_limbs = array_init::array_init(|_| meta.advice_column());
+        let base = array_init::array_init(|_| meta.advice_column());
+        let modulus = array_init::array_init(|_| meta.advice_column());
+        let quotient = array_init::array_init(|_| meta.advice_column());
+        let remainder = array_init::array_init(|_| meta.advice_column());
+        let carries = array_init::array_init(|_| array_init::array_init(|_| meta.advice_column()));
+        let diff = array_init::array_init(|_| meta.advice_column());
+        let diff_carry = meta.advice_column();
+        let u8_table = meta.lookup_table_column();
+
+        for &column in base
+            .iter()
+            .chain(modulus.iter())
+            .chain(quotient.iter())
+            .chain(remainder.iter())
+            .chain(carries.iter().flatten())
+            .chain(diff.iter())
+        {
+            meta.lookup("modexp byte range check", |meta| {
+                let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+                vec![(
+                    q_enable * meta.query_advice(column, Rotation::cur()),
+                    u8_table,
+                )]
+            });
+        }
+
+        meta.create_gate("modexp row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_last = meta.query_fixed(q_last, Rotation::cur());
+            let q_square = meta.query_fixed(q_square, Rotation::cur());
+            let q_exponent_hi = meta.query_fixed(q_exponent_hi, Rotation::cur());
+            let bit_prev = meta.query_advice(bit, Rotation::prev());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let a = a_limbs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let b = b_limbs.map(|column| meta.query_advice(column, Rotation::cur()));
+            let base_next = query_bytes(meta, &base, 1);
+            let base = query_bytes(meta, &base, 0);
+            let modulus_next = query_bytes(meta, &modulus, 1);
+            let modulus = query_bytes(meta, &modulus, 0);
+            let quotient = query_bytes(meta, &quotient, 0);
+            let remainder_prev = query_bytes(meta, &remainder, -1);
+            let remainder = query_bytes(meta, &remainder, 0);
+            let carries = carries.map(|columns| {
+                expr_from_bytes(&query_bytes(meta, &columns, 0))
+                    - Expression::Constant(pow_of_two::<F>(CARRY_OFFSET_BITS))
+            });
+            let diff = query_bytes(meta, &diff, 0);
+            let diff_carry = meta.query_advice(diff_carry, Rotation::cur());
+            let is_enabled = meta.query_advice(modexp_table.is_enabled, Rotation::cur());
+            let [base_lo, base_hi, modulus_lo, modulus_hi, output_lo, output_hi] = [
+                modexp_table.base_lo,
+                modexp_table.base_hi,
+                modexp_table.modulus_lo,
+                modexp_table.modulus_hi,
+                modexp_table.output_lo,
+                modexp_table.output_hi,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [exponent_lo, exponent_hi] = [modexp_table.exponent_lo, modexp_table.exponent_hi]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [exponent_lo_prev, exponent_hi_prev] =
+                [modexp_table.exponent_lo, modexp_table.exponent_hi]
+                    .map(|column| meta.query_advice(column, Rotation::prev()));
+
+            cb.require_boolean("is_enabled is boolean", is_enabled.clone());
+            cb.require_zero(
+                "is_enabled is only set on the last row of an exponentiation",
+                is_enabled * not::expr(q_last.clone()),
+            );
+
+            // The words in the table are the ones of the byte columns, with the
+            // result being the remainder of the last multiplication.
+            for (table_lo_hi, bytes) in [
+                ([base_lo, base_hi], &base),
+                ([modulus_lo, modulus_hi], &modulus),
+                ([output_lo, output_hi], &remainder),
+            ] {
+                for (table_value, value) in table_lo_hi.into_iter().zip(lo_hi(bytes)) {
+                    cb.require_equal("table word == word of the bytes", table_value, value);
+                }
+            }
+
+            // The first multiplicand is 1 at the start of the exponentiation
+            // and the previous result otherwise.  The second one is the same
+            // one when squaring, and the base or 1 (depending on the bit of
+            // the exponent) otherwise.
+            let one = [1.expr(), 0.expr(), 0.expr(), 0.expr()];
+            let base_limbs = limbs(&base);
+            let remainder_prev_limbs = limbs(&remainder_prev);
+            for idx in 0..4 {
+                cb.require_equal(
+                    "a == 1 on the first row, r::prev otherwise",
+                    a[idx].clone(),
+                    select::expr(
+                        q_first.clone(),
+                        one[idx].clone(),
+                        remainder_prev_limbs[idx].clone(),
+                    ),
+                );
+                cb.require_equal(
+                    "b == a when squaring, base or 1 depending on the bit otherwise",
+                    b[idx].clone(),
+                    select::expr(
+                        q_square.clone(),
+                        a[idx].clone(),
+                        select::expr(bit.clone(), base_limbs[idx].clone(), one[idx].clone()),
+                    ),
+                );
+            }
+
+            // The bits of the exponent are accumulated from the most
+            // significant one on the squaring rows, first in the hi 128 bits.
+            cb.require_boolean("bit is boolean", bit.clone());
+            cb.require_zero(
+                "bit is the same one on the squaring and the multiplication rows",
+                not::expr(q_square.clone()) * (bit.clone() - bit_prev),
+            );
+            let exponent_lo_prev = not::expr(q_first.clone()) * exponent_lo_prev;
+            let exponent_hi_prev = not::expr(q_first) * exponent_hi_prev;
+            cb.require_equal(
+                "exponent_hi accumulates the bits of the hi 128 bits",
+                exponent_hi,
+                select::expr(
+                    q_square.clone() * q_exponent_hi.clone(),
+                    exponent_hi_prev.clone() * 2.expr() + bit.clone(),
+                    exponent_hi_prev,
+                ),
+            );
+            cb.require_equal(
+                "exponent_lo accumulates the bits of the lo 128 bits",
+                exponent_lo,
+                select::expr(
+                    q_square * not::expr(q_exponent_hi),
+                    exponent_lo_prev.clone() * 2.expr() + bit,
+                    exponent_lo_prev,
+                ),
+            );
+
+            cb.condition(not::expr(q_last.clone()), |cb| {
+                let values = base.iter().chain(modulus.iter());
+                for (value, value_next) in values.zip(base_next.into_iter().chain(modulus_next)) {
+                    cb.require_equal(
+                        "base and modulus are the same ones in the whole exponentiation",
+                        value.clone(),
+                        value_next,
+                    );
+                }
+            });
+
+            // a * b == q * modulus + r, verified by 128-bit chunks whose
+            // carries are range checked with an offset, as they can be
+            // negative.
+            let quotient_limbs = limbs(&quotient);
+            let modulus_limbs = limbs(&modulus);
+            let [remainder_lo, remainder_hi] = lo_hi(&remainder);
+            let mut carry_prev = 0.expr();
+            for idx in 0..4 {
+                let mut value = product_chunk(&a, &b, idx)
+                    - product_chunk(&quotient_limbs, &modulus_limbs, idx)
+                    + carry_prev;
+                if idx == 0 {
+                    value = value - remainder_lo.clone();
+                }
+                if idx == 1 {
+                    value = value - remainder_hi.clone();
+                }
+                if idx < 3 {
+                    cb.require_equal(
+                        "chunk of a * b - q * modulus - r == carry * 2^128",
+                        value,
+                        carries[idx].clone() * Expression::Constant(pow_of_two::<F>(128)),
+                    );
+                    carry_prev = carries[idx].clone();
+                } else {
+                    cb.require_zero("a * b == q * modulus + r", value);
+                }
+            }
+
+            // The result is reduced: r + 1 + diff == modulus
+            cb.condition(q_last, |cb| {
+                let [diff_lo, diff_hi] = lo_hi(&diff);
+                let [modulus_lo, modulus_hi] = lo_hi(&modulus);
+                cb.require_boolean("diff_carry is boolean", diff_carry.clone());
+                cb.require_equal(
+                    "lo 128 bits of r + 1 + diff == modulus",
+                    remainder_lo + 1.expr() + diff_lo,
+                    modulus_lo + diff_carry.clone() * Expression::Constant(pow_of_two::<F>(128)),
+                );
+                cb.require_equal(
+                    "hi 128 bits of r + 1 + diff == modulus",
+                    remainder_hi + diff_hi + diff_carry,
+                    modulus_hi,
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_last,
+            q_square,
+            q_exponent_hi,
+            bit,
+            a_limbs,
+            b_limbs,
+            base,
+            modulus,
+            quotient,
+            remainder,
+            carries,
+            diff,
+            diff_carry,
+            u8_table,
+            modexp_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> ModExpCircuitConfig<F> {
+    /// Load the u8 table used for the byte range checks
+    pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "modexp u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: &[ModExpEvent],
+        max_modexp_calls: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp circuit",
+            |mut region| {
+                self.annotate_circuit(&mut region);
+
+                // All-zero first row, which is queried at Rotation::prev()
+                for column in self.advice_columns() {
+                    region.assign_advice(
+                        || "modexp all-zero row",
+                        column,
+                        0,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+
+                let padding = ModExpEvent::default();
+                for idx in 0..max_modexp_calls {
+                    let (event, is_enabled) = match events.get(idx) {
+                        Some(event) => (event, true),
+                        None => (&padding, false),
+                    };
+                    self.assign_event(
+                        &mut region,
+                        1 + idx * NUM_ROWS_PER_EVENT,
+                        event,
+                        is_enabled,
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_event(
+        &self,
+        region: &mut Region<F>,
+        offset: usize,
+        event: &ModExpEvent,
+        is_enabled: bool,
+    ) -> Result<(), Error> {
+        let (exponent_lo, exponent_hi) = split_u256(&event.exponent);
+        for (idx, step) in event.steps.iter().enumerate() {
+            let offset = offset + idx;
+            let bit_idx = idx / 2;
+            let is_last = idx == NUM_ROWS_PER_EVENT - 1;
+
+            for (name, column, value) in [
+                ("q_enable", self.q_enable, true),
+                ("q_first", self.q_first, idx == 0),
+                ("q_last", self.q_last, is_last),
+                ("q_square", self.q_square, idx % 2 == 0),
+                (
+                    "q_exponent_hi",
+                    self.q_exponent_hi,
+                    bit_idx < NUM_EXPONENT_BITS / 2,
+                ),
+            ] {
+                region.assign_fixed(
+                    || name,
+                    column,
+                    offset,
+                    || Value::known(F::from(value as u64)),
+                )?;
+            }
+
+            // Bits of the exponent accumulated up to the current one
+            let shift = NUM_EXPONENT_BITS - 1 - bit_idx;
+            let (exponent_lo_acc, exponent_hi_acc) = if bit_idx < NUM_EXPONENT_BITS / 2 {
+                (Word::zero(), exponent_hi >> (shift - 128))
+            } else {
+                (exponent_lo >> shift, exponent_hi)
+            };
+            let (remainder_lo, remainder_hi) = split_u256(&step.r);
+            let (base_lo, base_hi) = split_u256(&event.base);
+            let (modulus_lo, modulus_hi) = split_u256(&event.modulus);
+            let diff = if is_last {
+                event.modulus - step.r - Word::one()
+            } else {
+                Word::zero()
+            };
+            let diff_carry =
+                is_last && (remainder_lo + split_u256(&diff).0 + 1) >> 128 != Word::zero();
+
+            let mut advices = vec![
+                ("bit", self.bit, F::from(event.exponent.bit(shift) as u64)),
+                ("diff_carry", self.diff_carry, F::from(diff_carry as u64)),
+                (
+                    "is_enabled",
+                    self.modexp_table.is_enabled,
+                    F::from((is_enabled && is_last) as u64),
+                ),
+            ];
+            for (name, columns, value) in [("a", self.a_limbs, step.a), ("b", self.b_limbs, step.b)]
+            {
+                for (column, limb) in columns.into_iter().zip(split_u256_limb64(&value)) {
+                    advices.push((name, column, F::from(limb.as_u64())));
+                }
+            }
+            for (name, columns, value) in [
+                ("base", &self.base, event.base),
+                ("modulus", &self.modulus, event.modulus),
+                ("quotient", &self.quotient, step.q),
+                ("remainder", &self.remainder, step.r),
+                ("diff", &self.diff, diff),
+            ] {
+                for (&column, byte) in columns.iter().zip(value.to_le_bytes()) {
+                    advices.push((name, column, F::from(byte as u64)));
+                }
+            }
+            for (columns, carry) in self.carries.iter().zip(mul_carries(step, event.modulus)) {
+                for (&column, byte) in columns.iter().zip(carry.to_le_bytes()) {
+                    advices.push(("carry", column, F::from(byte as u64)));
+                }
+            }
+            for (name, column, value) in [
+                ("base_lo", self.modexp_table.base_lo, base_lo),
+                ("base_hi", self.modexp_table.base_hi, base_hi),
+                (
+                    "exponent_lo",
+                    self.modexp_table.exponent_lo,
+                    exponent_lo_acc,
+                ),
+                (
+                    "exponent_hi",
+                    self.modexp_table.exponent_hi,
+                    exponent_hi_acc,
+                ),
+                ("modulus_lo", self.modexp_table.modulus_lo, modulus_lo),
+                ("modulus_hi", self.modexp_table.modulus_hi, modulus_hi),
+                ("output_lo", self.modexp_table.output_lo, remainder_lo),
+                ("output_hi", self.modexp_table.output_hi, remainder_hi),
+            ] {
+                advices.push((name, column, F::from_u128(value.as_u128())));
+            }
+
+            for (name, column, value) in advices {
+                region.assign_advice(
+                    || format!("modexp {} {}", name, offset),
+                    column,
+                    offset,
+                    || Value::known(value),
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn advice_columns(&self) -> Vec<Column<Advice>> {
+        [self.bit, self.diff_carry]
+            .into_iter()
+            .chain(self.a_limbs)
+            .chain(self.b_limbs)
+            .chain(self.base)
+            .chain(self.modulus)
+            .chain(self.quotient)
+            .chain(self.remainder)
+            .chain(self.carries.into_iter().flatten())
+            .chain(self.diff)
+            .chain(<ModExpTable as LookupTable<F>>::advice_columns(
+                &self.modexp_table,
+            ))
+            .collect()
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        self.modexp_table.annotate_columns_in_region(region);
+        region.name_column(|| "MODEXP_q_enable", self.q_enable);
+        region.name_column(|| "MODEXP_q_first", self.q_first);
+        region.name_column(|| "MODEXP_q_last", self.q_last);
+        region.name_column(|| "MODEXP_q_square", self.q_square);
+        region.name_column(|| "MODEXP_q_exponent_hi", self.q_exponent_hi);
+        region.name_column(|| "MODEXP_bit", self.bit);
+        region.name_column(|| "MODEXP_diff_carry", self.diff_carry);
+    }
+}
+
+fn query_bytes<F: Field>(
+    meta: &mut VirtualCells<'_, F>,
+    columns: &[Column<Advice>],
+    rotation: i32,
+) -> Vec<Expression<F>> {
+    columns
+        .iter()
+        .map(|&column| meta.query_advice(column, Rotation(rotation)))
+        .collect()
+}
+
+/// 64-bit limbs of the little-endian bytes of a word
+fn limbs<F: Field>(bytes: &[Expression<F>]) -> Vec<Expression<F>> {
+    bytes
+        .chunks(8)
+        .map(|chunk| expr_from_bytes(chunk))
+        .collect()
+}
+
+/// Lo and hi 128 bits of the little-endian bytes of a word
+fn lo_hi<F: Field>(bytes: &[Expression<F>]) -> [Expression<F>; 2] {
+    [expr_from_bytes(&bytes[..16]), expr_from_bytes(&bytes[16..])]
+}
+
+/// 128-bit chunk `idx` of the product of two words given in 64-bit limbs
+fn product_chunk<F: Field>(x: &[Expression<F>], y: &[Expression<F>], idx: usize) -> Expression<F> {
+    let term = |k: usize| {
+        sum::expr(
+            (0..4)
+                .filter(|&i| k >= i && k - i < 4)
+                .map(|i| x[i].clone() * y[k - i].clone()),
+        )
+    };
+    term(2 * idx) + term(2 * idx + 1) * Expression::Constant(pow_of_two::<F>(64))
+}
+
+/// Carries of the 128-bit chunks of `a * b - q * modulus - r` of a step,
+/// offset by `2^CARRY_OFFSET_BITS`.
+fn mul_carries(step: &ModExpStep, modulus: Word) -> [Word; 3] {
+    let product_chunks = |x: Word, y: Word| {
+        let (x, y) = (split_u256_limb64(&x), split_u256_limb64(&y));
+        let term = |k: usize| {
+            (0..4)
+                .filter(|&i| k >= i && k - i < 4)
+                .fold(Word::zero(), |acc, i| acc + x[i] * y[k - i])
+        };
+        [0, 1, 2, 3].map(|idx| term(2 * idx) + (term(2 * idx + 1) << 64))
+    };
+    let positive = product_chunks(step.a, step.b);
+    let mut negative = product_chunks(step.q, modulus);
+    let (remainder_lo, remainder_hi) = split_u256(&step.r);
+    negative[0] = negative[0] + remainder_lo;
+    negative[1] = negative[1] + remainder_hi;
+
+    let offset = Word::one() << CARRY_OFFSET_BITS;
+    let mut carry = offset;
+    [0, 1, 2].map(|idx| {
+        let value = positive[idx] + carry + (offset << 128) - negative[idx] - offset;
+        carry = value >> 128;
+        carry
+    })
+}
+
+/// ModExp Circuit for verifying the modular exponentiations of the calls to
+/// the MODEXP precompile
+#[derive(Clone, Default, Debug)]
+pub struct ModExpCircuit<F: Field> {
+    /// Max number of supported calls to the MODEXP precompile
+    pub max_modexp_calls: usize,
+    /// Modular exponentiations of the calls to the MODEXP precompile
+    pub events: Vec<ModExpEvent>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> ModExpCircuit<F> {
+    /// Return a new ModExpCircuit
+    pub fn new(max_modexp_calls: usize, events: Vec<ModExpEvent>) -> Self {
+        ModExpCircuit::<F> {
+            max_modexp_calls,
+            events,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the minimum number of rows required to prove a number of calls
+    /// to the MODEXP precompile.
+    pub fn min_num_rows(num_calls: usize) -> usize {
+        1 + num_calls * NUM_ROWS_PER_EVENT
+    }
+}
+
+impl<F: Field> SubCircuit<F> for ModExpCircuit<F> {
+    type Config = ModExpCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        MODEXP_UNUSABLE_ROWS
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_modexp_calls = if block.circuits_params.max_modexp_calls == 0 {
+            block.modexp_events.len()
+        } else {
+            block.circuits_params.max_modexp_calls
+        };
+        Self::new(max_modexp_calls, block.modexp_events.clone())
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            Self::min_num_rows(block.modexp_events.len()),
+            Self::min_num_rows(
+                block
+                    .circuits_params
+                    .max_modexp_calls
+                    .max(block.modexp_events.len()),
+            ),
+        )
+    }
+
+    /// Make the assignments to the ModExpCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        _challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        if self.events.len() > self.max_modexp_calls {
+            error!(
+                "modexp events.len() = {} > max_modexp_calls = {}",
+                self.events.len(),
+                self.max_modexp_calls
+            );
+            return Err(Error::Synthesis);
+        }
+        config.load_aux_tables(layouter)?;
+        config.assign(layouter, &self.events, self.max_modexp_calls)
+    }
+}
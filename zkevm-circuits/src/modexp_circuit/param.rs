@@ -0,0 +1,17 @@
+pub(crate) const MAX_DEGREE: usize = 5;
+
+/// The number of bits of the exponent, which are all processed for every
+/// modular exponentiation.
+pub(crate) const NUM_EXPONENT_BITS: usize = 256;
+/// The number of rows assigned for each modular exponentiation, a squaring
+/// and a multiplication for each bit of the exponent.
+pub(crate) const NUM_ROWS_PER_EVENT: usize = 2 * NUM_EXPONENT_BITS;
+/// The number of bytes of the carries of the 128-bit chunks of the modular
+/// multiplication.
+pub(crate) const NUM_CARRY_BYTES: usize = 9;
+/// The carries can be negative, so they are stored with an offset of
+/// `2^CARRY_OFFSET_BITS`.
+pub(crate) const CARRY_OFFSET_BITS: usize = 71;
+/// No column is queried at more than 3 distinct rotations, so 6 rows are
+/// unusable.
+pub(crate) const MODEXP_UNUSABLE_ROWS: usize = 6;
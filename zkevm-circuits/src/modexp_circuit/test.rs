@@ -0,0 +1,62 @@
+use super::*;
+use crate::util::{log2_ceil, unusable_rows};
+use eth_types::{word, Word};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn modexp_circuit_unusable_rows() {
+    assert_eq!(
+        ModExpCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, ModExpCircuit::<Fr>>(()),
+    )
+}
+
+fn run<F: Field>(
+    events: Vec<ModExpEvent>,
+    max_modexp_calls: usize,
+) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil(
+        ModExpCircuit::<Fr>::unusable_rows() + ModExpCircuit::<Fr>::min_num_rows(max_modexp_calls),
+    );
+    let circuit = ModExpCircuit::<F>::new(max_modexp_calls, events);
+
+    let prover = match MockProver::run(k, &circuit, vec![]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    };
+    prover.verify()
+}
+
+fn test_events() -> Vec<ModExpEvent> {
+    vec![
+        ModExpEvent::new(Word::from(3), Word::from(0x1234), Word::from(0x10001)),
+        // Base greater than the modulus
+        ModExpEvent::new(Word::MAX, Word::from(2), Word::from(7)),
+        ModExpEvent::new(Word::from(5), Word::zero(), Word::from(1)),
+        ModExpEvent::new(
+            word!("0x9c3d3e1b5f7a6c2e8d4f0a1b2c3d4e5f60718293a4b5c6d7e8f9011223344556"),
+            word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2d"),
+            word!("0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"),
+        ),
+    ]
+}
+
+#[test]
+fn modexp_circuit_simple() {
+    assert_eq!(run::<Fr>(test_events(), 4), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_padding() {
+    assert_eq!(run::<Fr>(test_events()[..1].to_vec(), 3), Ok(()));
+}
+
+#[test]
+fn modexp_circuit_wrong_result() {
+    let mut event = ModExpEvent::new(Word::from(3), Word::from(0x1234), Word::from(0x10001));
+    event.steps.last_mut().unwrap().r = Word::zero();
+    assert!(run::<Fr>(vec![event], 1).is_err());
+}
@@ -0,0 +1,290 @@
+//! The MPT circuit proves the updates of the `MptTable` against the state
+//! trie and the storage tries of the accounts, following the paths of their
+//! keys in the tries before and after every update.
+//!
+//! The nodes of the tries touched by the updates are proved against their
+//! hashes with the `KeccakTable`, the keys of the updates are split into the
+//! nibbles of their hashes, and the steps of the proofs of the updates look up
+//! the nodes and the nibbles of the keys at every depth of their paths.
+
+mod keys;
+pub(crate) mod nodes;
+mod proof;
+mod steps;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::MptCircuit as TestMptCircuit;
+
+use crate::{
+    table::{KeccakTable, MptTable},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness::{self, MptProof, MptUpdates},
+};
+use eth_types::{trie::TrieNode, Field, ToWord};
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, Expression, TableColumn},
+};
+use keys::{MptKeysConfig, TrieKey, KEY_ROWS};
+use log::error;
+use nodes::{MptNodesConfig, TrieType};
+use proof::TrieProof;
+use std::{collections::HashSet, iter, marker::PhantomData};
+use steps::MptStepsConfig;
+
+/// Maximum degree of the constraints of the MPT circuit
+const MAX_DEGREE: usize = 9;
+
+/// Config of the MPT circuit
+#[derive(Clone, Debug)]
+pub struct MptCircuitConfig<F> {
+    nodes: MptNodesConfig<F>,
+    keys: MptKeysConfig<F>,
+    steps: MptStepsConfig<F>,
+    // Table of the bytes, with their high and low nibbles
+    byte_table: [TableColumn; 3],
+    /// MptTable
+    pub mpt_table: MptTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+}
+
+/// Circuit configuration arguments
+pub struct MptCircuitConfigArgs<F: Field> {
+    /// MptTable
+    pub mpt_table: MptTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for MptCircuitConfig<F> {
+    type ConfigArgs = MptCircuitConfigArgs<F>;
+
+    /// Return a new MptCircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            mpt_table,
+            keccak_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let byte_table = [(); 3].map(|_| meta.lookup_table_column());
+        let nodes = MptNodesConfig::new(
+            meta,
+            TrieType::State,
+            byte_table,
+            &keccak_table,
+            &challenges,
+        );
+        let keys = MptKeysConfig::new(meta, byte_table, &keccak_table, &challenges);
+        let steps = MptStepsConfig::new(meta, &nodes, &keys, mpt_table, &challenges);
+
+        Self {
+            nodes,
+            keys,
+            steps,
+            byte_table,
+            mpt_table,
+            keccak_table,
+        }
+    }
+}
+
+impl<F: Field> MptCircuitConfig<F> {
+    /// Load the table of the bytes, with their high and low nibbles.
+    fn load_byte_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "mpt byte table",
+            |mut table| {
+                for byte in 0..256 {
+                    for (&column, value) in
+                        self.byte_table.iter().zip([byte, byte >> 4, byte & 0xf])
+                    {
+                        table.assign_cell(
+                            || format!("mpt byte table row {}", byte),
+                            column,
+                            byte as usize,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Proofs of the updates, with the proofs of the updates of the state trie
+/// and of the storage trie
+type UpdateProofs<'a> = Vec<(&'a MptProof, Vec<TrieProof>)>;
+
+/// Return the proofs of the updates.
+fn update_proofs(updates: &MptUpdates) -> Result<UpdateProofs<'_>, Error> {
+    updates
+        .proofs
+        .iter()
+        .map(|proof| {
+            let trie_proofs = iter::once(&proof.account)
+                .chain(&proof.storage)
+                .map(|update| {
+                    TrieProof::new(update).ok_or_else(|| {
+                        error!("mpt update of the key {:?} is not supported", update.key);
+                        Error::Synthesis
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((proof, trie_proofs))
+        })
+        .collect()
+}
+
+/// Return the nodes of the proofs, with the flags of the nodes of storage
+/// tries, without repetitions.
+fn proof_nodes(proofs: &UpdateProofs) -> Vec<(TrieNode, bool)> {
+    let mut hashes = HashSet::new();
+    let mut nodes = Vec::new();
+    for (_, trie_proofs) in proofs {
+        for (segment, trie_proof) in trie_proofs.iter().enumerate() {
+            let is_storage = segment > 0;
+            for side in trie_proof.rows.iter().flat_map(|row| [&row.old, &row.new]) {
+                if let Some(node) = &side.node {
+                    if hashes.insert((node.hash(), is_storage)) {
+                        nodes.push((node.clone(), is_storage));
+                    }
+                }
+            }
+        }
+    }
+    nodes
+}
+
+/// Return the keys of the proofs, without repetitions.
+fn proof_keys(proofs: &UpdateProofs) -> Vec<TrieKey> {
+    let mut keys: Vec<_> = proofs
+        .iter()
+        .flat_map(|(proof, _)| {
+            iter::once(TrieKey {
+                value: proof.update.address().to_word(),
+                is_storage: false,
+            })
+            .chain(proof.update.storage_slot().map(|value| TrieKey {
+                value,
+                is_storage: true,
+            }))
+        })
+        .collect();
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// MPT Circuit for proving the updates of the state trie and of the storage
+/// tries of the accounts
+#[derive(Clone, Default, Debug)]
+pub struct MptCircuit<F: Field> {
+    /// Updates of the tries, with their proofs
+    pub updates: MptUpdates,
+    /// Number of rows of the nodes, the keys and the steps of the proofs
+    pub max_mpt_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MptCircuit<F> {
+    /// Return a new MptCircuit
+    pub fn new(updates: MptUpdates, max_mpt_rows: usize) -> Self {
+        Self {
+            updates,
+            max_mpt_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the number of rows needed by the nodes, the keys and the steps
+    /// of the proofs of the updates.
+    pub fn min_num_rows(updates: &MptUpdates) -> Result<usize, Error> {
+        let proofs = update_proofs(updates)?;
+        let node_rows = proof_nodes(&proofs)
+            .iter()
+            .map(|(node, _)| node.rlp().len())
+            .sum::<usize>();
+        let key_rows = proof_keys(&proofs).len() * KEY_ROWS;
+        let step_rows = proofs
+            .iter()
+            .flat_map(|(_, trie_proofs)| trie_proofs)
+            .map(|trie_proof| trie_proof.rows.len())
+            .sum::<usize>();
+        Ok(node_rows.max(key_rows).max(step_rows))
+    }
+
+    /// Return the inputs of the keccaks of the nodes and of the keys of the
+    /// proofs of the updates.
+    pub fn keccak_inputs(updates: &MptUpdates) -> Result<Vec<Vec<u8>>, Error> {
+        let proofs = update_proofs(updates)?;
+        let nodes = proof_nodes(&proofs).into_iter().map(|(node, _)| node.rlp());
+        let keys = proof_keys(&proofs).into_iter().map(|key| key.preimage().0);
+        Ok(nodes.chain(keys).collect())
+    }
+}
+
+impl<F: Field> SubCircuit<F> for MptCircuit<F> {
+    type Config = MptCircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        // No column queried at more than 3 distinct rotations, so returns 6 as
+        // minimum unusable rows.
+        6
+    }
+
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        let max_mpt_rows = match block.circuits_params.max_mpt_rows {
+            0 => Self::min_num_rows(&block.mpt_updates).unwrap_or_default(),
+            max_mpt_rows => max_mpt_rows,
+        };
+        Self::new(block.mpt_updates.clone(), max_mpt_rows)
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        // The rows are surrounded by two padding rows, next to the table of
+        // the bytes.
+        let rows = Self::min_num_rows(&block.mpt_updates).unwrap_or_default();
+        (
+            (rows + 2).max(256),
+            (block.circuits_params.max_mpt_rows.max(rows) + 2).max(256),
+        )
+    }
+
+    /// Make the assignments to the MptCircuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let proofs = update_proofs(&self.updates)?;
+        config.load_byte_table(layouter)?;
+        config.nodes.load_transition_table(layouter)?;
+        config.nodes.assign(
+            layouter,
+            &proof_nodes(&proofs),
+            self.max_mpt_rows,
+            challenges,
+        )?;
+        config.keys.assign(
+            layouter,
+            &proof_keys(&proofs),
+            self.max_mpt_rows,
+            challenges,
+        )?;
+        config
+            .steps
+            .assign(layouter, &proofs, self.max_mpt_rows, challenges)
+    }
+}
@@ -0,0 +1,500 @@
+//! Keys of the tries used by the proofs, and powers of the keccak input
+//! challenge.
+//!
+//! Every key takes 65 rows, one per depth in the trie from 0 to 64, with the
+//! nibble of the hash of the key at the depth and the RLC of the nibbles
+//! before it.  The bytes of the hash are accumulated from pairs of nibbles,
+//! and the bytes of the address or of the storage slot of the key are
+//! accumulated next to them, so that the keccak of the key can be looked up
+//! in the `KeccakTable`.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{KeccakTable, LookupTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{
+    keccak256,
+    state_trie::{account_key, key_nibbles, storage_key},
+    Field, ToBigEndian, ToLittleEndian, ToScalar, Word,
+};
+use gadgets::util::not;
+use halo2_proofs::{
+    circuit::{Layouter, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::marker::PhantomData;
+
+use super::MAX_DEGREE;
+
+/// Number of rows of a key
+pub(crate) const KEY_ROWS: usize = 65;
+
+/// Key of an account, whose value is its address, or of a storage slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) struct TrieKey {
+    pub(crate) value: Word,
+    pub(crate) is_storage: bool,
+}
+
+impl TrieKey {
+    /// Return the preimage of the hash of the key, and its bytes padded to a
+    /// word.
+    pub(crate) fn preimage(&self) -> (Vec<u8>, [u8; 32]) {
+        let bytes = self.value.to_be_bytes();
+        if self.is_storage {
+            (bytes.to_vec(), bytes)
+        } else {
+            (bytes[12..].to_vec(), bytes)
+        }
+    }
+
+    /// Return the nibbles of the hash of the key.
+    pub(crate) fn nibbles(&self) -> Vec<u8> {
+        let hash = if self.is_storage {
+            storage_key(&self.value)
+        } else {
+            account_key(&eth_types::Address::from_slice(&self.preimage().0))
+        };
+        key_nibbles(&hash)
+    }
+
+    /// Return the value of the key in the `MptTable`: the address, or the
+    /// RLC of the storage slot.
+    pub(crate) fn table_value<F: Field>(&self, challenges: &Challenges<Value<F>>) -> Value<F> {
+        if self.is_storage {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&self.value.to_le_bytes(), challenge))
+        } else {
+            Value::known(self.value.to_scalar().unwrap())
+        }
+    }
+}
+
+/// Config of the keys of the tries and of the powers of the keccak input
+/// challenge
+#[derive(Clone, Debug)]
+pub(crate) struct MptKeysConfig<F> {
+    q_enable: Column<Fixed>,
+    // First and last rows of the keys, at depths 0 and 64
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    // Rows of the bytes of the hash and of the preimage, at odd depths, and
+    // rows of the 12 bytes of zeros which pad the addresses to a word.
+    q_byte: Column<Fixed>,
+    q_pad: Column<Fixed>,
+    depth: Column<Fixed>,
+    is_key: Column<Advice>,
+    is_storage: Column<Advice>,
+    // Address of accounts, or RLC of storage slots
+    key_value: Column<Advice>,
+    nibble: Column<Advice>,
+    // RLC of the nibbles before the depth, and of all of them.
+    prefix: Column<Advice>,
+    key_rlc: Column<Advice>,
+    // RLC of the bytes of the hash, accumulated as a word.
+    hash_acc: Column<Advice>,
+    // Byte of the preimage accumulated as the input of the keccak, as an
+    // integer and as a word.
+    preimage_byte: Column<Advice>,
+    preimage_rlc: Column<Advice>,
+    preimage_int: Column<Advice>,
+    preimage_word: Column<Advice>,
+    // (exponent, power) of the powers of the keccak input challenge, from 0
+    // to 64.
+    q_pow: Column<Fixed>,
+    q_pow_first: Column<Fixed>,
+    pow_exponent: Column<Fixed>,
+    pow_value: Column<Advice>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MptKeysConfig<F> {
+    /// Return a new MptKeysConfig
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        byte_table: [TableColumn; 3],
+        keccak_table: &KeccakTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let [q_enable, q_first, q_last, q_byte, q_pad, depth] =
+            [(); 6].map(|_| meta.fixed_column());
+        let [is_key, is_storage, nibble, preimage_byte, preimage_int] =
+            [(); 5].map(|_| meta.advice_column());
+        let [key_value, prefix, key_rlc, hash_acc, preimage_rlc, preimage_word] =
+            [(); 6].map(|_| meta.advice_column_in(SecondPhase));
+        let [q_pow, q_pow_first, pow_exponent] = [(); 3].map(|_| meta.fixed_column());
+        let pow_value = meta.advice_column_in(SecondPhase);
+
+        meta.create_gate("mpt keys", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [q_first, q_last, q_byte, q_pad] = [q_first, q_last, q_byte, q_pad]
+                .map(|column| meta.query_fixed(column, Rotation::cur()));
+            let key_values = [is_key, is_storage, key_value, key_rlc].map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let [nibble_prev, hash_acc_prev] =
+                [nibble, hash_acc].map(|column| meta.query_advice(column, Rotation::prev()));
+            let [preimage_rlc_prev, preimage_int_prev, preimage_word_prev] =
+                [preimage_rlc, preimage_int, preimage_word]
+                    .map(|column| meta.query_advice(column, Rotation::prev()));
+            let prefix_next = meta.query_advice(prefix, Rotation::next());
+            let [is_key, is_storage, key_value, nibble, prefix, key_rlc] =
+                [is_key, is_storage, key_value, nibble, prefix, key_rlc]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [hash_acc, preimage_byte, preimage_rlc, preimage_int, preimage_word] = [
+                hash_acc,
+                preimage_byte,
+                preimage_rlc,
+                preimage_int,
+                preimage_word,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.require_boolean("is_key is boolean", is_key);
+            cb.require_boolean("is_storage is boolean", is_storage.clone());
+
+            // The bytes of the hash are the pairs of nibbles ending at odd
+            // depths.
+            let hash_byte = nibble_prev * 16.expr() + nibble.clone();
+            let accumulators = [
+                (hash_acc, hash_acc_prev, challenges.evm_word(), hash_byte),
+                (
+                    preimage_rlc,
+                    preimage_rlc_prev,
+                    challenges.keccak_input(),
+                    preimage_byte.clone(),
+                ),
+                (
+                    preimage_int.clone(),
+                    preimage_int_prev,
+                    256.expr(),
+                    preimage_byte.clone(),
+                ),
+                (
+                    preimage_word.clone(),
+                    preimage_word_prev,
+                    challenges.evm_word(),
+                    preimage_byte.clone(),
+                ),
+            ];
+            cb.condition(q_first.clone(), |cb| {
+                cb.require_zero("the prefix starts empty", prefix.clone());
+                for (acc, _, _, _) in accumulators.iter() {
+                    cb.require_zero("the bytes are accumulated from 0", acc.clone());
+                }
+            });
+            cb.condition(not::expr(q_first), |cb| {
+                for (acc, acc_prev, multiplier, byte) in accumulators {
+                    cb.require_equal(
+                        "the bytes are accumulated at odd depths",
+                        acc,
+                        q_byte.clone() * (acc_prev.clone() * multiplier + byte)
+                            + not::expr(q_byte.clone()) * acc_prev,
+                    );
+                }
+            });
+            cb.condition(not::expr(q_last.clone()), |cb| {
+                for (value, value_next) in key_values {
+                    cb.require_equal(
+                        "the values of the key are the same ones in all its rows",
+                        value_next,
+                        value,
+                    );
+                }
+                cb.require_equal(
+                    "the prefix accumulates the nibbles",
+                    prefix_next,
+                    prefix.clone() * challenges.keccak_input() + nibble.clone(),
+                );
+            });
+            cb.condition(q_last, |cb| {
+                cb.require_zero("there is no nibble at depth 64", nibble);
+                cb.require_equal("key_rlc has all the nibbles", key_rlc, prefix);
+                cb.require_equal(
+                    "the value of the key is its address or storage slot",
+                    key_value,
+                    is_storage.clone() * preimage_word
+                        + not::expr(is_storage.clone()) * preimage_int,
+                );
+            });
+            cb.require_zero(
+                "addresses are padded with zeros",
+                q_pad * not::expr(is_storage) * preimage_byte,
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("mpt powers", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let pow_value_next = meta.query_advice(pow_value, Rotation::next());
+            let pow_value = meta.query_advice(pow_value, Rotation::cur());
+            cb.condition(meta.query_fixed(q_pow_first, Rotation::cur()), |cb| {
+                cb.require_equal("the power of 0 is 1", pow_value.clone(), 1.expr());
+            });
+            cb.condition(meta.query_fixed(q_pow, Rotation::next()), |cb| {
+                cb.require_equal(
+                    "the powers are multiplied by the challenge",
+                    pow_value_next,
+                    pow_value * challenges.keccak_input(),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_pow, Rotation::cur()))
+        });
+
+        meta.lookup("mpt keys hash bytes", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_fixed(q_byte, Rotation::cur());
+            let nibble_prev = meta.query_advice(nibble, Rotation::prev());
+            let nibble = meta.query_advice(nibble, Rotation::cur());
+            [
+                nibble_prev.clone() * 16.expr() + nibble.clone(),
+                nibble_prev,
+                nibble,
+            ]
+            .into_iter()
+            .zip(byte_table)
+            .map(|(value, table)| (enable.clone() * value, table))
+            .collect()
+        });
+        meta.lookup("mpt keys preimage bytes", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_fixed(q_byte, Rotation::cur());
+            let byte = meta.query_advice(preimage_byte, Rotation::cur());
+            vec![(enable * byte, byte_table[0])]
+        });
+        meta.lookup_any("mpt keys keccak", |meta| {
+            let enable = meta.query_fixed(q_last, Rotation::cur())
+                * meta.query_advice(is_key, Rotation::cur());
+            let is_storage = meta.query_advice(is_storage, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(preimage_rlc, Rotation::cur()),
+                20.expr() + is_storage * 12.expr(),
+                meta.query_advice(hash_acc, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_last,
+            q_byte,
+            q_pad,
+            depth,
+            is_key,
+            is_storage,
+            key_value,
+            nibble,
+            prefix,
+            key_rlc,
+            hash_acc,
+            preimage_byte,
+            preimage_rlc,
+            preimage_int,
+            preimage_word,
+            q_pow,
+            q_pow_first,
+            pow_exponent,
+            pow_value,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the (key_value, is_storage, depth, nibble, prefix, key_rlc)
+    /// expressions of the rows of the keys, which are zero out of them.
+    pub(crate) fn key_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 6] {
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.is_key, Rotation::cur());
+        [
+            meta.query_advice(self.key_value, Rotation::cur()),
+            meta.query_advice(self.is_storage, Rotation::cur()),
+            meta.query_fixed(self.depth, Rotation::cur()),
+            meta.query_advice(self.nibble, Rotation::cur()),
+            meta.query_advice(self.prefix, Rotation::cur()),
+            meta.query_advice(self.key_rlc, Rotation::cur()),
+        ]
+        .map(|value| enable.clone() * value)
+    }
+
+    /// Return the (exponent, power) expressions of the powers of the keccak
+    /// input challenge, which are zero out of them.
+    pub(crate) fn pow_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 2] {
+        let q_pow = meta.query_fixed(self.q_pow, Rotation::cur());
+        [
+            meta.query_fixed(self.pow_exponent, Rotation::cur()),
+            q_pow * meta.query_advice(self.pow_value, Rotation::cur()),
+        ]
+    }
+
+    /// Assign the keys in `num_rows` rows, and the powers of the keccak input
+    /// challenge.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        keys: &[TrieKey],
+        num_rows: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let num_keys = num_rows / KEY_ROWS;
+        if keys.len() > num_keys {
+            error!(
+                "mpt keys {} exceed the available keys {}",
+                keys.len(),
+                num_keys
+            );
+            return Err(Error::Synthesis);
+        }
+        let zero = Value::known(F::ZERO);
+        let r = challenges.keccak_input();
+        let word_acc = |acc: Value<F>, byte: u8| {
+            challenges
+                .evm_word()
+                .zip(acc)
+                .map(|(challenge, acc)| acc * challenge + F::from(byte as u64))
+        };
+
+        layouter.assign_region(
+            || "mpt keys",
+            |mut region| {
+                for index in 0..num_keys {
+                    let key = keys.get(index);
+                    let (nibbles, preimage, hash, key_value) = match key {
+                        Some(key) => {
+                            let (preimage, padded) = key.preimage();
+                            let hash = keccak256(&preimage);
+                            (key.nibbles(), padded, hash, key.table_value(challenges))
+                        }
+                        None => (vec![0; 64], [0; 32], [0; 32], zero),
+                    };
+                    let key_rlc = r.map(|r| rlc::value(nibbles.iter().rev(), r));
+                    let [mut prefix, mut hash_acc, mut preimage_rlc, mut preimage_word] = [zero; 4];
+                    let mut preimage_int = F::ZERO;
+                    for depth in 0..KEY_ROWS {
+                        let offset = index * KEY_ROWS + depth;
+                        let is_byte = depth % 2 == 1 && depth < 64;
+                        let nibble = nibbles.get(depth).copied().unwrap_or(0);
+                        let preimage_byte = if is_byte && key.is_some() {
+                            preimage[depth / 2]
+                        } else {
+                            0
+                        };
+                        if is_byte {
+                            hash_acc = word_acc(hash_acc, hash[depth / 2]);
+                            preimage_rlc =
+                                preimage_rlc * r + Value::known(F::from(preimage_byte as u64));
+                            preimage_int =
+                                preimage_int * F::from(256) + F::from(preimage_byte as u64);
+                            preimage_word = word_acc(preimage_word, preimage_byte);
+                        }
+                        for (name, column, value) in [
+                            ("q_enable", self.q_enable, 1),
+                            ("q_first", self.q_first, (depth == 0) as u64),
+                            ("q_last", self.q_last, (depth == 64) as u64),
+                            ("q_byte", self.q_byte, is_byte as u64),
+                            ("q_pad", self.q_pad, (is_byte && depth < 24) as u64),
+                            ("depth", self.depth, depth as u64),
+                        ] {
+                            region.assign_fixed(
+                                || format!("mpt keys {}", name),
+                                column,
+                                offset,
+                                || Value::known(F::from(value)),
+                            )?;
+                        }
+                        for (name, column, value) in [
+                            (
+                                "is_key",
+                                self.is_key,
+                                Value::known(F::from(key.is_some() as u64)),
+                            ),
+                            (
+                                "is_storage",
+                                self.is_storage,
+                                Value::known(F::from(
+                                    key.map_or(false, |key| key.is_storage) as u64
+                                )),
+                            ),
+                            ("key_value", self.key_value, key_value),
+                            ("nibble", self.nibble, Value::known(F::from(nibble as u64))),
+                            ("prefix", self.prefix, prefix),
+                            ("key_rlc", self.key_rlc, key_rlc),
+                            ("hash_acc", self.hash_acc, hash_acc),
+                            (
+                                "preimage_byte",
+                                self.preimage_byte,
+                                Value::known(F::from(preimage_byte as u64)),
+                            ),
+                            ("preimage_rlc", self.preimage_rlc, preimage_rlc),
+                            (
+                                "preimage_int",
+                                self.preimage_int,
+                                Value::known(preimage_int),
+                            ),
+                            ("preimage_word", self.preimage_word, preimage_word),
+                        ] {
+                            region.assign_advice(
+                                || format!("mpt keys {}", name),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        prefix = prefix * r + Value::known(F::from(nibble as u64));
+                    }
+                }
+                Ok(())
+            },
+        )?;
+
+        layouter.assign_region(
+            || "mpt powers",
+            |mut region| {
+                let mut power = Value::known(F::ONE);
+                for exponent in 0..KEY_ROWS {
+                    for (name, column, value) in [
+                        ("q_pow", self.q_pow, 1),
+                        ("q_pow_first", self.q_pow_first, (exponent == 0) as u64),
+                        ("pow_exponent", self.pow_exponent, exponent as u64),
+                    ] {
+                        region.assign_fixed(
+                            || format!("mpt powers {}", name),
+                            column,
+                            exponent,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                    region.assign_advice(
+                        || "mpt powers pow_value",
+                        self.pow_value,
+                        exponent,
+                        || power,
+                    )?;
+                    power = power * r;
+                }
+                Ok(())
+            },
+        )
+    }
+}
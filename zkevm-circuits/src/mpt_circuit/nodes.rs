@@ -0,0 +1,1425 @@
+//! Nodes of the state trie and of the storage tries used by the proofs, and
+//! of the ordered tries of the PI circuit.
+//!
+//! The encoding of every node is laid out one byte per row, split into items
+//! of consecutive bytes: the list header, then the key and the child of
+//! extension nodes, the 16 children and the (empty) value of branch nodes, or
+//! the key and the value of leaf nodes.  The value of account leaves is the
+//! header of the string of the encoding of the account, followed by the
+//! header of its list and by its nonce, balance, storage root and code hash,
+//! the value of storage leaves is the header of the string of the encoding of
+//! the word, followed by this encoding, and the value of the leaves of the
+//! ordered tries is a string of bytes.  The keccak of every node is looked up
+//! in the `KeccakTable`, and the decoded node is exposed to the proofs, with a
+//! row for every child of branch nodes.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    impl_expr,
+    table::{KeccakTable, LookupTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{trie::TrieNode, Field, ToLittleEndian, Word};
+use ethers_core::utils::rlp;
+use gadgets::{
+    rlp_item::{RlpItemChip, RlpItemConfig, RlpItemFlags, RlpItemRow},
+    util::{not, sum},
+};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::{iter, marker::PhantomData};
+
+use super::MAX_DEGREE;
+
+/// Tries whose nodes are decoded
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TrieType {
+    /// State trie and storage tries, whose keys are accumulated as the RLC of
+    /// their nibbles
+    State,
+    /// Ordered tries of the PI circuit, whose short keys are accumulated as
+    /// integers
+    Ordered,
+}
+
+/// Type of the nodes of the tries
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NodeType {
+    Branch = 0,
+    Extension,
+    AccountLeaf,
+    StorageLeaf,
+    /// Leaf of an ordered trie
+    ValueLeaf,
+}
+
+impl_expr!(NodeType);
+
+impl NodeType {
+    pub(crate) fn new(node: &TrieNode, is_storage: bool) -> Self {
+        match node {
+            TrieNode::Branch(_) => Self::Branch,
+            TrieNode::Extension(..) => Self::Extension,
+            TrieNode::Leaf(..) if is_storage => Self::StorageLeaf,
+            TrieNode::Leaf(..) => Self::AccountLeaf,
+        }
+    }
+}
+
+/// Tag of the items of the encoding of a node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ItemTag {
+    /// Rows after the nodes
+    Padding = 0,
+    /// Header of the RLP list of the items of the node
+    ListHeader,
+    /// Hex-prefix encoding of the nibbles of an extension or leaf node
+    Key,
+    /// Reference to a child of a branch or extension node
+    Child,
+    /// Header of the value of a leaf node, or empty value of a branch node
+    Value,
+    /// Header of the RLP list of the fields of an account
+    AccountHeader,
+    Nonce,
+    Balance,
+    StorageRoot,
+    CodeHash,
+    /// Encoding of the word of a storage slot
+    StorageValue,
+    /// String of the value of a leaf of an ordered trie
+    Bytes,
+}
+
+impl_expr!(ItemTag);
+
+/// Witness of a row of the encoding of a node
+#[derive(Clone, Debug)]
+struct NodeRow<F> {
+    node_type: NodeType,
+    tag: ItemTag,
+    // Slot of the child item in a branch node
+    nibble: u8,
+    byte: u8,
+    item: RlpItemRow<F>,
+    bytes_rlc: Value<F>,
+    bytes_len: usize,
+    list_end: usize,
+    is_first: bool,
+    is_last: bool,
+    is_padding: bool,
+    hash: Value<F>,
+    is_odd: bool,
+    key_acc: Value<F>,
+    key_len: usize,
+    key_pow: Value<F>,
+    child_cnt: usize,
+    is_ref: bool,
+    children: Value<F>,
+    payload: Value<F>,
+    payload_len: usize,
+    nonce: Value<F>,
+    balance: Value<F>,
+    storage_root: Value<F>,
+    code_hash: Value<F>,
+}
+
+impl<F: Field> NodeRow<F> {
+    fn padding() -> Self {
+        let zero = Value::known(F::ZERO);
+        Self {
+            node_type: NodeType::Branch,
+            tag: ItemTag::Padding,
+            nibble: 0,
+            byte: 0,
+            item: RlpItemRow::padding(),
+            bytes_rlc: zero,
+            bytes_len: 0,
+            list_end: 0,
+            is_first: false,
+            is_last: false,
+            is_padding: true,
+            hash: zero,
+            is_odd: false,
+            key_acc: zero,
+            key_len: 0,
+            key_pow: zero,
+            child_cnt: 0,
+            is_ref: false,
+            children: zero,
+            payload: zero,
+            payload_len: 0,
+            nonce: zero,
+            balance: zero,
+            storage_root: zero,
+            code_hash: zero,
+        }
+    }
+}
+
+/// Config of the nodes of the tries
+#[derive(Clone, Debug)]
+pub(crate) struct MptNodesConfig<F> {
+    trie_type: TrieType,
+    q_enable: Column<Fixed>,
+    // First enabled row
+    q_first: Column<Fixed>,
+    // One-hot flags of the item tag
+    is_list: Column<Advice>,
+    is_key: Column<Advice>,
+    is_child: Column<Advice>,
+    is_value: Column<Advice>,
+    is_account: Column<Advice>,
+    is_nonce: Column<Advice>,
+    is_balance: Column<Advice>,
+    is_storage_root: Column<Advice>,
+    is_code_hash: Column<Advice>,
+    is_storage_value: Column<Advice>,
+    is_bytes: Column<Advice>,
+    is_padding: Column<Advice>,
+    // One-hot flags of the node type
+    is_branch: Column<Advice>,
+    is_ext: Column<Advice>,
+    is_account_leaf: Column<Advice>,
+    is_storage_leaf: Column<Advice>,
+    is_value_leaf: Column<Advice>,
+    nibble: Column<Advice>,
+    byte: Column<Advice>,
+    nibble_hi: Column<Advice>,
+    nibble_lo: Column<Advice>,
+    // Decoding of the RLP items of the nodes
+    item: RlpItemConfig,
+    bytes_rlc: Column<Advice>,
+    bytes_len: Column<Advice>,
+    list_end: Column<Advice>,
+    is_first: Column<Advice>,
+    is_last: Column<Advice>,
+    hash: Column<Advice>,
+    // Whether the key of the node has an odd number of nibbles
+    is_odd: Column<Advice>,
+    // RLC of the decoded nibbles of the key, or their integer in the ordered
+    // tries, their number, and the power of the RLC challenge or of 16 to
+    // their number.
+    key_acc: Column<Advice>,
+    key_len: Column<Advice>,
+    key_pow: Column<Advice>,
+    // Number of non-empty children accumulated in the node
+    child_cnt: Column<Advice>,
+    // 1 on the last row of a non-empty child
+    is_ref: Column<Advice>,
+    // Fingerprint of the children of branch nodes, with their hashes
+    // accumulated as an RLC.
+    children: Column<Advice>,
+    // Child of extension nodes, word of storage leaves or value of the leaves
+    // of the ordered tries, and its length.
+    payload: Column<Advice>,
+    payload_len: Column<Advice>,
+    // Fields of account leaves
+    nonce: Column<Advice>,
+    balance: Column<Advice>,
+    storage_root: Column<Advice>,
+    code_hash: Column<Advice>,
+    // (node_type, tag, nibble, next_tag, next_nibble) of the consecutive
+    // items of the nodes, and (node_type, Padding, 0, tag, nibble) of their
+    // first items.
+    transition_table: [TableColumn; 5],
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MptNodesConfig<F> {
+    /// Return a new MptNodesConfig decoding the nodes of the tries of
+    /// `trie_type`
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        trie_type: TrieType,
+        byte_table: [TableColumn; 3],
+        keccak_table: &KeccakTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let [is_list, is_key, is_child, is_value, is_account, is_nonce] =
+            [(); 6].map(|_| meta.advice_column());
+        let [is_balance, is_storage_root, is_code_hash, is_storage_value, is_bytes, is_padding] =
+            [(); 6].map(|_| meta.advice_column());
+        let [is_branch, is_ext, is_account_leaf, is_storage_leaf, is_value_leaf] =
+            [(); 5].map(|_| meta.advice_column());
+        let [nibble, byte, nibble_hi, nibble_lo] = [(); 4].map(|_| meta.advice_column());
+        let bytes_rlc = meta.advice_column_in(SecondPhase);
+        let [bytes_len, list_end, is_first, is_last] = [(); 4].map(|_| meta.advice_column());
+        let hash = meta.advice_column_in(SecondPhase);
+        let [is_odd, key_len, child_cnt, is_ref, payload_len] =
+            [(); 5].map(|_| meta.advice_column());
+        let [key_acc, key_pow, children, payload, nonce, balance, storage_root, code_hash] =
+            [(); 8].map(|_| meta.advice_column_in(SecondPhase));
+        let key_base = match trie_type {
+            TrieType::State => challenges.keccak_input(),
+            TrieType::Ordered => 16.expr(),
+        };
+        let transition_table = [(); 5].map(|_| meta.lookup_table_column());
+
+        let q_node = |meta: &mut VirtualCells<'_, F>| {
+            meta.query_fixed(q_enable, Rotation::cur())
+                * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+        };
+        let item = RlpItemChip::configure(
+            meta,
+            q_node,
+            byte,
+            |meta| {
+                // Hashes and words are accumulated as the RLC of their
+                // little-endian bytes, nonces as integers, and the other
+                // items as the RLC of their bytes.
+                let [is_list, is_value, is_account, is_nonce] =
+                    [is_list, is_value, is_account, is_nonce]
+                        .map(|column| meta.query_advice(column, Rotation::cur()));
+                let is_word = sum::expr(
+                    [
+                        is_child,
+                        is_balance,
+                        is_storage_root,
+                        is_code_hash,
+                        is_storage_value,
+                    ]
+                    .map(|column| meta.query_advice(column, Rotation::cur())),
+                );
+                // The list headers and the value headers are followed by the
+                // items of their payload.
+                RlpItemFlags {
+                    is_list: is_list.clone() + is_account.clone(),
+                    is_header_only: is_list + is_account + is_value,
+                    is_raw: 0.expr(),
+                    multiplier: is_word.clone() * challenges.evm_word()
+                        + is_nonce.clone() * 256.expr()
+                        + not::expr(is_word + is_nonce) * challenges.keccak_input(),
+                }
+            },
+            3,
+            byte_table[0],
+        );
+        let RlpItemConfig {
+            is_header,
+            length,
+            is_item_first,
+            is_item_last,
+            value_acc,
+            ..
+        } = item;
+
+        let tag_expr = |meta: &mut VirtualCells<'_, F>, rotation: Rotation| {
+            sum::expr(
+                [
+                    is_list,
+                    is_key,
+                    is_child,
+                    is_value,
+                    is_account,
+                    is_nonce,
+                    is_balance,
+                    is_storage_root,
+                    is_code_hash,
+                    is_storage_value,
+                    is_bytes,
+                ]
+                .into_iter()
+                .enumerate()
+                .map(|(i, column)| meta.query_advice(column, rotation) * (i + 1).expr()),
+            )
+        };
+        let node_type_expr = |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(is_ext, Rotation::cur())
+                + meta.query_advice(is_account_leaf, Rotation::cur()) * 2.expr()
+                + meta.query_advice(is_storage_leaf, Rotation::cur()) * 3.expr()
+                + meta.query_advice(is_value_leaf, Rotation::cur()) * 4.expr()
+        };
+        let is_content_first = |meta: &mut VirtualCells<'_, F>| item.is_content_first(meta);
+
+        meta.create_gate("mpt nodes flags", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let tag_flags = [
+                is_list,
+                is_key,
+                is_child,
+                is_value,
+                is_account,
+                is_nonce,
+                is_balance,
+                is_storage_root,
+                is_code_hash,
+                is_storage_value,
+                is_bytes,
+                is_padding,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let node_flags = [
+                is_branch,
+                is_ext,
+                is_account_leaf,
+                is_storage_leaf,
+                is_value_leaf,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            for flag in tag_flags.iter().chain(node_flags.iter()) {
+                cb.require_boolean("flags are boolean", flag.clone());
+            }
+            cb.require_equal("one tag flag is set", sum::expr(tag_flags), 1.expr());
+
+            let is_padding_next = meta.query_advice(is_padding, Rotation::next());
+            let is_padding = meta.query_advice(is_padding, Rotation::cur());
+            cb.require_equal(
+                "one node flag is set out of padding rows",
+                sum::expr(node_flags),
+                not::expr(is_padding.clone()),
+            );
+            let disabled = [
+                is_first,
+                is_last,
+                is_item_first,
+                is_item_last,
+                is_header,
+                is_ref,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            cb.condition(is_padding, |cb| {
+                cb.require_equal("padding is followed by padding", is_padding_next, 1.expr());
+                for value in disabled {
+                    cb.require_zero("padding rows are not part of a node", value);
+                }
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("mpt nodes first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            cb.require_equal(
+                "the first row starts a node unless there are no nodes",
+                meta.query_advice(is_first, Rotation::cur()),
+                not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("mpt nodes node", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_item_last_prev = meta.query_advice(is_item_last, Rotation::prev());
+            let child_cnt_prev = meta.query_advice(child_cnt, Rotation::prev());
+            let [is_first_next, is_padding_next] =
+                [is_first, is_padding].map(|column| meta.query_advice(column, Rotation::next()));
+            let [byte_next, bytes_rlc_next, bytes_len_next] = [byte, bytes_rlc, bytes_len]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let node_values = [
+                is_branch,
+                is_ext,
+                is_account_leaf,
+                is_storage_leaf,
+                is_value_leaf,
+                list_end,
+                hash,
+            ]
+            .map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let [byte, bytes_rlc, bytes_len, list_end, child_cnt] =
+                [byte, bytes_rlc, bytes_len, list_end, child_cnt]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_first, is_item_last, is_first, is_last, is_odd] =
+                [is_item_first, is_item_last, is_first, is_last, is_odd]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_child, is_value, is_code_hash, is_storage_value, is_bytes, is_ref] = [
+                is_child,
+                is_value,
+                is_code_hash,
+                is_storage_value,
+                is_bytes,
+                is_ref,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_branch, is_ext, is_account_leaf, is_storage_leaf, is_value_leaf] = [
+                is_branch,
+                is_ext,
+                is_account_leaf,
+                is_storage_leaf,
+                is_value_leaf,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            for (name, value) in [
+                ("is_first is boolean", is_first.clone()),
+                ("is_last is boolean", is_last.clone()),
+                ("is_odd is boolean", is_odd),
+            ] {
+                cb.require_boolean(name, value);
+            }
+            cb.require_equal(
+                "an item starts with the node or after the end of the previous item",
+                is_item_first,
+                is_first.clone() + not::expr(is_first.clone()) * is_item_last_prev,
+            );
+            cb.require_equal(
+                "a node starts after the end of the previous one unless padding follows",
+                is_first_next,
+                is_last.clone() * not::expr(is_padding_next.clone()),
+            );
+            cb.require_equal(
+                "child_cnt counts the non-empty children",
+                child_cnt,
+                not::expr(is_first.clone()) * child_cnt_prev + is_ref,
+            );
+
+            cb.condition(is_first, |cb| {
+                cb.require_equal("bytes_len starts at 1", bytes_len.clone(), 1.expr());
+                cb.require_equal("bytes_rlc starts at the byte", bytes_rlc.clone(), byte);
+            });
+            cb.condition(is_last.clone(), |cb| {
+                cb.require_equal(
+                    "the node ends at the end of an item",
+                    is_item_last,
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the node ends at the end of the list",
+                    bytes_len.clone(),
+                    list_end,
+                );
+                cb.require_equal(
+                    "the node ends with the last item of its type",
+                    is_branch * is_value
+                        + is_ext * is_child
+                        + is_account_leaf * is_code_hash
+                        + is_storage_leaf * is_storage_value
+                        + is_value_leaf * is_bytes,
+                    1.expr(),
+                );
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                cb.require_zero("the node continues in the next row", is_padding_next);
+                for (value, value_next) in node_values {
+                    cb.require_equal(
+                        "the values of the node are the same ones in all its rows",
+                        value_next,
+                        value,
+                    );
+                }
+                cb.require_equal(
+                    "bytes_len increases by 1",
+                    bytes_len_next,
+                    bytes_len + 1.expr(),
+                );
+                cb.require_equal(
+                    "bytes_rlc accumulates the bytes",
+                    bytes_rlc_next,
+                    bytes_rlc * challenges.keccak_input() + byte_next,
+                );
+            });
+
+            cb.gate(q_node(meta))
+        });
+
+        meta.create_gate("mpt nodes item", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let item_values = [
+                is_list,
+                is_key,
+                is_child,
+                is_value,
+                is_account,
+                is_nonce,
+                is_balance,
+                is_storage_root,
+                is_code_hash,
+                is_storage_value,
+                is_bytes,
+                nibble,
+            ]
+            .map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let is_value_prev = meta.query_advice(is_value, Rotation::prev());
+            let [is_header, is_item_first, is_item_last, length] =
+                [is_header, is_item_first, is_item_last, length]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [bytes_len, list_end] =
+                [bytes_len, list_end].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_list, is_child, is_value, is_account] =
+                [is_list, is_child, is_value, is_account]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_storage_root, is_code_hash, is_storage_value, is_ext] =
+                [is_storage_root, is_code_hash, is_storage_value, is_ext]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.condition(not::expr(is_item_last.clone()), |cb| {
+                for (value, value_next) in item_values {
+                    cb.require_equal(
+                        "the tag and nibble are the same ones in the whole item",
+                        value_next,
+                        value,
+                    );
+                }
+            });
+
+            // The list headers and the value headers are followed by the
+            // items of their payload, up to the end of the node.
+            cb.condition((is_list + is_account + is_value) * is_item_last, |cb| {
+                cb.require_equal(
+                    "the payload ends at the end of the node",
+                    list_end,
+                    bytes_len + length.clone(),
+                );
+            });
+            cb.condition(is_storage_value.clone() * is_item_first, |cb| {
+                // The encoding of the word is a single byte, which is its own
+                // encoding as a string, or a string with a header.
+                cb.require_equal(
+                    "the word has a header if the value has one",
+                    is_header,
+                    is_value_prev,
+                );
+            });
+
+            // Children are referenced by the hash of their encoding, as the
+            // nodes shorter than 32 bytes aren't found in hashed tries.
+            cb.require_zero(
+                "a child is empty or has 32 bytes",
+                is_child.clone() * length.clone() * (length.clone() - 32.expr()),
+            );
+            cb.require_zero(
+                "the child of an extension node isn't empty",
+                is_ext * is_child * (length.clone() - 32.expr()),
+            );
+            cb.require_zero(
+                "the roots and code hashes have 32 bytes",
+                (is_storage_root + is_code_hash) * (length - 32.expr()),
+            );
+
+            cb.gate(q_node(meta))
+        });
+
+        meta.create_gate("mpt nodes fields", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_content_first = is_content_first(meta);
+            let [key_acc_prev, key_len_prev, key_pow_prev] = [key_acc, key_len, key_pow]
+                .map(|column| meta.query_advice(column, Rotation::prev()));
+            let [nibble_hi, nibble_lo, length, value_acc] =
+                [nibble_hi, nibble_lo, length, value_acc]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [key_acc, key_len, key_pow, is_ref] = [key_acc, key_len, key_pow, is_ref]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_header, is_item_last, is_first, is_odd] =
+                [is_header, is_item_last, is_first, is_odd]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_key, is_child, is_nonce, is_balance, is_storage_root, is_code_hash] = [
+                is_key,
+                is_child,
+                is_nonce,
+                is_balance,
+                is_storage_root,
+                is_code_hash,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_storage_value, is_bytes] = [is_storage_value, is_bytes]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_branch, is_ext, is_account_leaf, is_storage_leaf, is_value_leaf] = [
+                is_branch,
+                is_ext,
+                is_account_leaf,
+                is_storage_leaf,
+                is_value_leaf,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let r = challenges.keccak_input();
+            let base = key_base.clone();
+
+            // The first byte of the hex-prefix encoding of a key has the flag
+            // 2 * is_leaf + is_odd in the high nibble, and the first nibble
+            // of odd keys in the low nibble.
+            let is_key_first = is_key.clone() * is_content_first.clone();
+            cb.condition(is_key_first.clone(), |cb| {
+                cb.require_equal(
+                    "the hex-prefix flag has the node type and the parity",
+                    nibble_hi.clone(),
+                    (is_account_leaf + is_storage_leaf + is_value_leaf) * 2.expr() + is_odd.clone(),
+                );
+            });
+            cb.condition(is_key_first.clone() * is_odd.clone(), |cb| {
+                cb.require_equal(
+                    "key_acc accumulates the first nibble of odd keys",
+                    key_acc.clone(),
+                    key_acc_prev.clone() * base.clone() + nibble_lo.clone(),
+                );
+                cb.require_equal(
+                    "key_len counts the first nibble of odd keys",
+                    key_len.clone(),
+                    key_len_prev.clone() + 1.expr(),
+                );
+                cb.require_equal(
+                    "key_pow follows the first nibble of odd keys",
+                    key_pow.clone(),
+                    key_pow_prev.clone() * base.clone(),
+                );
+            });
+            cb.condition(is_key_first * not::expr(is_odd), |cb| {
+                cb.require_zero(
+                    "the first byte of even keys has no nibble",
+                    nibble_lo.clone(),
+                );
+                cb.require_equal(
+                    "key_acc is the same one",
+                    key_acc.clone(),
+                    key_acc_prev.clone(),
+                );
+                cb.require_equal(
+                    "key_len is the same one",
+                    key_len.clone(),
+                    key_len_prev.clone(),
+                );
+                cb.require_equal(
+                    "key_pow is the same one",
+                    key_pow.clone(),
+                    key_pow_prev.clone(),
+                );
+            });
+            let is_key_content = is_key * not::expr(is_header);
+            cb.condition(is_key_content.clone() * not::expr(is_content_first), |cb| {
+                cb.require_equal(
+                    "key_acc accumulates the nibbles of the key",
+                    key_acc.clone(),
+                    (key_acc_prev.clone() * base.clone() + nibble_hi) * base.clone() + nibble_lo,
+                );
+                cb.require_equal(
+                    "key_len counts the nibbles of the key",
+                    key_len.clone(),
+                    key_len_prev.clone() + 2.expr(),
+                );
+                cb.require_equal(
+                    "key_pow follows the nibbles of the key",
+                    key_pow.clone(),
+                    key_pow_prev.clone() * base.clone() * base,
+                );
+            });
+            cb.condition(
+                not::expr(is_first.clone()) * not::expr(is_key_content),
+                |cb| {
+                    cb.require_equal("key_acc is the same one", key_acc.clone(), key_acc_prev);
+                    cb.require_equal("key_len is the same one", key_len.clone(), key_len_prev);
+                    cb.require_equal("key_pow is the same one", key_pow.clone(), key_pow_prev);
+                },
+            );
+            cb.condition(is_first.clone(), |cb| {
+                cb.require_zero("key_acc starts at 0", key_acc);
+                cb.require_zero("key_len starts at 0", key_len);
+                cb.require_equal("key_pow starts at 1", key_pow, 1.expr());
+            });
+
+            cb.require_equal(
+                "is_ref is the end of a non-empty child",
+                is_ref,
+                is_child.clone()
+                    * is_item_last.clone()
+                    * length.clone()
+                    * Expression::Constant(F::from(32).invert().unwrap()),
+            );
+
+            // The fields of the node are set at the end of their items, and
+            // kept in the next rows up to the end of the node.
+            let is_branch_child = is_branch * is_child.clone() * is_item_last.clone();
+            let is_payload =
+                (is_ext * is_child + is_storage_value + is_bytes) * is_item_last.clone();
+            for (column, is_set, value) in [
+                (
+                    children,
+                    is_branch_child,
+                    meta.query_advice(children, Rotation::prev()) * r + value_acc.clone(),
+                ),
+                (payload, is_payload.clone(), value_acc.clone()),
+                (payload_len, is_payload, length),
+                (nonce, is_nonce * is_item_last.clone(), value_acc.clone()),
+                (
+                    balance,
+                    is_balance * is_item_last.clone(),
+                    value_acc.clone(),
+                ),
+                (
+                    storage_root,
+                    is_storage_root * is_item_last.clone(),
+                    value_acc.clone(),
+                ),
+                (
+                    code_hash,
+                    is_code_hash * is_item_last.clone(),
+                    value_acc.clone(),
+                ),
+            ] {
+                let field = meta.query_advice(column, Rotation::cur());
+                let field_prev = meta.query_advice(column, Rotation::prev());
+                cb.condition(is_first.clone(), |cb| {
+                    cb.require_zero("the fields start at 0", field.clone());
+                });
+                cb.condition(not::expr(is_first.clone()), |cb| {
+                    cb.require_equal(
+                        "the field is set at the end of its item",
+                        field,
+                        is_set.clone() * value + not::expr(is_set) * field_prev,
+                    );
+                });
+            }
+
+            cb.gate(q_node(meta))
+        });
+
+        meta.lookup("mpt nodes byte nibbles", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            [byte, nibble_hi, nibble_lo]
+                .into_iter()
+                .zip(byte_table)
+                .map(|(column, table)| {
+                    (
+                        q_enable.clone() * meta.query_advice(column, Rotation::cur()),
+                        table,
+                    )
+                })
+                .collect()
+        });
+        meta.lookup("mpt nodes integer leading byte", |meta| {
+            // Integers have no leading zeros.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * is_content_first(meta)
+                * (meta.query_advice(is_nonce, Rotation::cur())
+                    + meta.query_advice(is_balance, Rotation::cur())
+                    + meta.query_advice(is_storage_value, Rotation::cur()));
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - 1.expr()), byte_table[0])]
+        });
+        meta.lookup("mpt nodes integer length", |meta| {
+            // Nonces have up to 8 bytes, and balances and words up to 32.
+            let [is_nonce, is_balance, is_storage_value] = [is_nonce, is_balance, is_storage_value]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_first, Rotation::cur())
+                * (is_nonce.clone() + is_balance.clone() + is_storage_value.clone());
+            let max_length = is_nonce * 8.expr() + (is_balance + is_storage_value) * 32.expr();
+            let length = meta.query_advice(length, Rotation::cur());
+            vec![(enable * (max_length - length), byte_table[0])]
+        });
+        meta.lookup("mpt nodes branch children", |meta| {
+            // Branch nodes have at least 2 children, as the other ones would
+            // be merged into extension or leaf nodes.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur())
+                * meta.query_advice(is_branch, Rotation::cur());
+            let child_cnt = meta.query_advice(child_cnt, Rotation::cur());
+            vec![(enable * (child_cnt - 2.expr()), byte_table[0])]
+        });
+        meta.lookup("mpt nodes extension key", |meta| {
+            // Extension nodes have at least 1 nibble.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur())
+                * meta.query_advice(is_ext, Rotation::cur());
+            let key_len = meta.query_advice(key_len, Rotation::cur());
+            vec![(enable * (key_len - 1.expr()), byte_table[0])]
+        });
+
+        meta.lookup("mpt nodes item transition", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * not::expr(meta.query_advice(is_last, Rotation::cur()));
+            [
+                node_type_expr(meta),
+                tag_expr(meta, Rotation::cur()),
+                meta.query_advice(nibble, Rotation::cur()),
+                tag_expr(meta, Rotation::next()),
+                meta.query_advice(nibble, Rotation::next()),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+        meta.lookup("mpt nodes first item", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            [
+                node_type_expr(meta),
+                ItemTag::Padding.expr(),
+                0.expr(),
+                tag_expr(meta, Rotation::cur()),
+                meta.query_advice(nibble, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+
+        meta.lookup_any("mpt nodes keccak", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(bytes_rlc, Rotation::cur()),
+                meta.query_advice(bytes_len, Rotation::cur()),
+                meta.query_advice(hash, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        Self {
+            trie_type,
+            q_enable,
+            q_first,
+            is_list,
+            is_key,
+            is_child,
+            is_value,
+            is_account,
+            is_nonce,
+            is_balance,
+            is_storage_root,
+            is_code_hash,
+            is_storage_value,
+            is_bytes,
+            is_padding,
+            is_branch,
+            is_ext,
+            is_account_leaf,
+            is_storage_leaf,
+            is_value_leaf,
+            nibble,
+            byte,
+            nibble_hi,
+            nibble_lo,
+            item,
+            bytes_rlc,
+            bytes_len,
+            list_end,
+            is_first,
+            is_last,
+            hash,
+            is_odd,
+            key_acc,
+            key_len,
+            key_pow,
+            child_cnt,
+            is_ref,
+            children,
+            payload,
+            payload_len,
+            nonce,
+            balance,
+            storage_root,
+            code_hash,
+            transition_table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the (hash, node_type, key_acc, key_len, payload, children,
+    /// nonce, balance, storage_root, code_hash, payload_len, key_pow)
+    /// expressions of the nodes, which are zero out of their last rows.
+    pub(crate) fn node_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 12] {
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.is_last, Rotation::cur());
+        let node_type = meta.query_advice(self.is_ext, Rotation::cur())
+            + meta.query_advice(self.is_account_leaf, Rotation::cur()) * 2.expr()
+            + meta.query_advice(self.is_storage_leaf, Rotation::cur()) * 3.expr()
+            + meta.query_advice(self.is_value_leaf, Rotation::cur()) * 4.expr();
+        [
+            meta.query_advice(self.hash, Rotation::cur()),
+            node_type,
+            meta.query_advice(self.key_acc, Rotation::cur()),
+            meta.query_advice(self.key_len, Rotation::cur()),
+            meta.query_advice(self.payload, Rotation::cur()),
+            meta.query_advice(self.children, Rotation::cur()),
+            meta.query_advice(self.nonce, Rotation::cur()),
+            meta.query_advice(self.balance, Rotation::cur()),
+            meta.query_advice(self.storage_root, Rotation::cur()),
+            meta.query_advice(self.code_hash, Rotation::cur()),
+            meta.query_advice(self.payload_len, Rotation::cur()),
+            meta.query_advice(self.key_pow, Rotation::cur()),
+        ]
+        .map(|value| enable.clone() * value)
+    }
+
+    /// Return the (hash, nibble, child) expressions of the children of the
+    /// branch nodes, which are zero out of the last rows of the children.
+    pub(crate) fn child_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 3] {
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.is_branch, Rotation::cur())
+            * meta.query_advice(self.is_child, Rotation::cur())
+            * meta.query_advice(self.item.is_item_last, Rotation::cur());
+        [
+            meta.query_advice(self.hash, Rotation::cur()),
+            meta.query_advice(self.nibble, Rotation::cur()),
+            meta.query_advice(self.item.value_acc, Rotation::cur()),
+        ]
+        .map(|value| enable.clone() * value)
+    }
+
+    /// Load the table of the transitions between the items.
+    pub(crate) fn load_transition_table(
+        &self,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "mpt nodes transition table",
+            |mut table| {
+                let (branch, ext) = (NodeType::Branch, NodeType::Extension);
+                let (account, storage) = (NodeType::AccountLeaf, NodeType::StorageLeaf);
+                let value_leaf = NodeType::ValueLeaf;
+                let (list, key, child, value) = (
+                    ItemTag::ListHeader,
+                    ItemTag::Key,
+                    ItemTag::Child,
+                    ItemTag::Value,
+                );
+                let transitions = iter::once((branch, ItemTag::Padding, 0, ItemTag::Padding, 0))
+                    .chain(
+                        [branch, ext, account, storage, value_leaf]
+                            .map(|node_type| (node_type, ItemTag::Padding, 0, list, 0)),
+                    )
+                    .chain(iter::once((branch, list, 0, child, 0)))
+                    .chain((0..15).map(|nibble| (branch, child, nibble, child, nibble + 1)))
+                    .chain([
+                        (branch, child, 15, value, 0),
+                        (ext, list, 0, key, 0),
+                        (ext, key, 0, child, 0),
+                        (account, list, 0, key, 0),
+                        (account, key, 0, value, 0),
+                        (account, value, 0, ItemTag::AccountHeader, 0),
+                        (account, ItemTag::AccountHeader, 0, ItemTag::Nonce, 0),
+                        (account, ItemTag::Nonce, 0, ItemTag::Balance, 0),
+                        (account, ItemTag::Balance, 0, ItemTag::StorageRoot, 0),
+                        (account, ItemTag::StorageRoot, 0, ItemTag::CodeHash, 0),
+                        (storage, list, 0, key, 0),
+                        (storage, key, 0, value, 0),
+                        (storage, key, 0, ItemTag::StorageValue, 0),
+                        (storage, value, 0, ItemTag::StorageValue, 0),
+                        (value_leaf, list, 0, key, 0),
+                        (value_leaf, key, 0, ItemTag::Bytes, 0),
+                    ]);
+                for (offset, (node_type, tag, nibble, tag_next, nibble_next)) in
+                    transitions.enumerate()
+                {
+                    for (&column, value) in self.transition_table.iter().zip([
+                        node_type as u64,
+                        tag as u64,
+                        nibble,
+                        tag_next as u64,
+                        nibble_next,
+                    ]) {
+                        table.assign_cell(
+                            || format!("transition table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Return the rows of the nodes, with the flags of the nodes of storage
+    /// tries.
+    fn node_rows(
+        &self,
+        nodes: &[(TrieNode, bool)],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<NodeRow<F>>, Error> {
+        let word_rlc = |bytes: &[u8]| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&Word::from_big_endian(bytes).to_le_bytes(), challenge))
+        };
+        let invalid = |node: &TrieNode| {
+            error!("mpt node {:?} is not supported", node);
+            Error::Synthesis
+        };
+        let key_base = match self.trie_type {
+            TrieType::State => challenges.keccak_input(),
+            TrieType::Ordered => Value::known(F::from(16)),
+        };
+
+        let mut rows = Vec::new();
+        for (node, is_storage) in nodes {
+            let node_type = match (node, self.trie_type) {
+                (TrieNode::Leaf(..), TrieType::Ordered) => NodeType::ValueLeaf,
+                _ => NodeType::new(node, *is_storage),
+            };
+            let items = node.items();
+            // The tags of the items, with their encodings and the length of
+            // the payload of the value headers.
+            let tagged_items: Vec<(ItemTag, u8, Vec<u8>, usize)> = match node {
+                TrieNode::Branch(_) => (0..16)
+                    .map(|nibble| (ItemTag::Child, nibble))
+                    .chain(iter::once((ItemTag::Value, 0)))
+                    .zip(items)
+                    .map(|((tag, nibble), bytes)| (tag, nibble, bytes, 0))
+                    .collect(),
+                TrieNode::Extension(..) => vec![
+                    (ItemTag::Key, 0, items[0].clone(), 0),
+                    (ItemTag::Child, 0, items[1].clone(), 0),
+                ],
+                TrieNode::Leaf(..) if node_type == NodeType::ValueLeaf => vec![
+                    (ItemTag::Key, 0, items[0].clone(), 0),
+                    (ItemTag::Bytes, 0, items[1].clone(), 0),
+                ],
+                TrieNode::Leaf(_, value) => {
+                    let header = items[1][..items[1].len() - value.len()].to_vec();
+                    let mut tagged_items = vec![(ItemTag::Key, 0, items[0].clone(), 0)];
+                    if !header.is_empty() {
+                        tagged_items.push((ItemTag::Value, 0, header, value.len()));
+                    }
+                    if *is_storage {
+                        tagged_items.push((ItemTag::StorageValue, 0, value.clone(), 0));
+                    } else {
+                        let account = rlp::Rlp::new(value);
+                        let header_len = account
+                            .payload_info()
+                            .map_err(|_| invalid(node))?
+                            .header_len;
+                        tagged_items.push((
+                            ItemTag::AccountHeader,
+                            0,
+                            value[..header_len].to_vec(),
+                            value.len() - header_len,
+                        ));
+                        for (index, tag) in [
+                            ItemTag::Nonce,
+                            ItemTag::Balance,
+                            ItemTag::StorageRoot,
+                            ItemTag::CodeHash,
+                        ]
+                        .into_iter()
+                        .enumerate()
+                        {
+                            let item = account.at(index).map_err(|_| invalid(node))?;
+                            tagged_items.push((tag, 0, item.as_raw().to_vec(), 0));
+                        }
+                    }
+                    tagged_items
+                }
+            };
+            let payload_len: usize = items.iter().map(|item| item.len()).sum();
+            let rlp = node.rlp();
+            let list_header = rlp[..rlp.len() - payload_len].to_vec();
+            let list_end = rlp.len();
+            let hash = word_rlc(node.hash().as_bytes());
+            let is_odd = match node {
+                TrieNode::Branch(_) => false,
+                TrieNode::Extension(nibbles, _) | TrieNode::Leaf(nibbles, _) => {
+                    nibbles.len() % 2 == 1
+                }
+            };
+
+            let zero = Value::known(F::ZERO);
+            let mut bytes_rlc = zero;
+            let mut node_rows: Vec<NodeRow<F>> = Vec::with_capacity(list_end);
+            let mut key_acc = zero;
+            let mut key_len = 0;
+            let mut key_pow = Value::known(F::ONE);
+            let mut child_cnt = 0;
+            let [mut children, mut payload] = [zero; 2];
+            let mut payload_size = 0;
+            let [mut nonce, mut balance, mut storage_root, mut code_hash] = [zero; 4];
+            for (tag, nibble, bytes, header_payload_len) in
+                iter::once((ItemTag::ListHeader, 0, list_header, payload_len)).chain(tagged_items)
+            {
+                if tag == ItemTag::Child && bytes != [0x80] && bytes.len() != 33 {
+                    error!("mpt node {:?} with an embedded child", node);
+                    return Err(Error::Synthesis);
+                }
+                let is_header_only = matches!(
+                    tag,
+                    ItemTag::ListHeader | ItemTag::Value | ItemTag::AccountHeader
+                );
+                let multiplier = match tag {
+                    ItemTag::Child
+                    | ItemTag::Balance
+                    | ItemTag::StorageRoot
+                    | ItemTag::CodeHash
+                    | ItemTag::StorageValue => challenges.evm_word(),
+                    ItemTag::Nonce => Value::known(F::from(256)),
+                    _ => challenges.keccak_input(),
+                };
+                let r = challenges.keccak_input();
+
+                let item_rows = RlpItemRow::item_rows(
+                    &bytes,
+                    is_header_only.then_some(header_payload_len),
+                    false,
+                    multiplier,
+                );
+                for (&byte, item) in bytes.iter().zip(item_rows) {
+                    let byte_value = Value::known(F::from(byte as u64));
+                    bytes_rlc = if node_rows.is_empty() {
+                        byte_value
+                    } else {
+                        bytes_rlc * r + byte_value
+                    };
+                    let (hi, lo) = (
+                        Value::known(F::from((byte >> 4) as u64)),
+                        Value::known(F::from((byte & 0xf) as u64)),
+                    );
+                    if tag == ItemTag::Key && !item.is_header {
+                        if item.index == 0 {
+                            if is_odd {
+                                key_acc = key_acc * key_base + lo;
+                                key_len += 1;
+                                key_pow = key_pow * key_base;
+                            }
+                        } else {
+                            key_acc = (key_acc * key_base + hi) * key_base + lo;
+                            key_len += 2;
+                            key_pow = key_pow * key_base * key_base;
+                        }
+                    }
+                    let is_ref = tag == ItemTag::Child && item.is_item_last && bytes.len() == 33;
+                    child_cnt += is_ref as usize;
+                    if item.is_item_last {
+                        let value_acc = item.value_acc;
+                        match (tag, node_type) {
+                            (ItemTag::Child, NodeType::Branch) => {
+                                children = children * r + value_acc
+                            }
+                            (ItemTag::Child, _)
+                            | (ItemTag::StorageValue, _)
+                            | (ItemTag::Bytes, _) => {
+                                payload = value_acc;
+                                payload_size = item.length;
+                            }
+                            (ItemTag::Nonce, _) => nonce = value_acc,
+                            (ItemTag::Balance, _) => balance = value_acc,
+                            (ItemTag::StorageRoot, _) => storage_root = value_acc,
+                            (ItemTag::CodeHash, _) => code_hash = value_acc,
+                            _ => {}
+                        }
+                    }
+                    node_rows.push(NodeRow {
+                        node_type,
+                        tag,
+                        nibble,
+                        byte,
+                        item,
+                        bytes_rlc,
+                        bytes_len: node_rows.len() + 1,
+                        list_end,
+                        is_first: node_rows.is_empty(),
+                        is_last: false,
+                        is_padding: false,
+                        hash,
+                        is_odd,
+                        key_acc,
+                        key_len,
+                        key_pow,
+                        child_cnt,
+                        is_ref,
+                        children,
+                        payload,
+                        payload_len: payload_size,
+                        nonce,
+                        balance,
+                        storage_root,
+                        code_hash,
+                    });
+                }
+            }
+            if let Some(row) = node_rows.last_mut() {
+                row.is_last = true;
+            }
+            debug_assert_eq!(
+                node_rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+                rlp
+            );
+            rows.extend(node_rows);
+        }
+        Ok(rows)
+    }
+
+    /// Assign the nodes, with the flags of the nodes of storage tries, in
+    /// `num_rows` rows.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        nodes: &[(TrieNode, bool)],
+        num_rows: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let rows = self.node_rows(nodes, challenges)?;
+        if rows.len() > num_rows {
+            error!(
+                "mpt node rows {} exceed the available rows {}",
+                rows.len(),
+                num_rows
+            );
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "mpt nodes",
+            |mut region| {
+                // The enabled rows are surrounded by padding rows, which are
+                // queried at Rotation::prev() and Rotation::next().
+                let padding = NodeRow::padding();
+                self.assign_row(&mut region, 0, &padding, false, false)?;
+                for (offset, row) in rows
+                    .iter()
+                    .chain(iter::repeat(&padding))
+                    .take(num_rows)
+                    .enumerate()
+                {
+                    self.assign_row(&mut region, offset + 1, row, true, offset == 0)?;
+                }
+                self.assign_row(&mut region, num_rows + 1, &padding, false, false)
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &NodeRow<F>,
+        q_enable: bool,
+        q_first: bool,
+    ) -> Result<(), Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, q_enable),
+            ("q_first", self.q_first, q_first),
+        ] {
+            region.assign_fixed(
+                || format!("mpt nodes {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        let node_type = (!row.is_padding).then_some(row.node_type);
+        for (name, column, value) in [
+            ("is_list", self.is_list, row.tag == ItemTag::ListHeader),
+            ("is_key", self.is_key, row.tag == ItemTag::Key),
+            ("is_child", self.is_child, row.tag == ItemTag::Child),
+            ("is_value", self.is_value, row.tag == ItemTag::Value),
+            (
+                "is_account",
+                self.is_account,
+                row.tag == ItemTag::AccountHeader,
+            ),
+            ("is_nonce", self.is_nonce, row.tag == ItemTag::Nonce),
+            ("is_balance", self.is_balance, row.tag == ItemTag::Balance),
+            (
+                "is_storage_root",
+                self.is_storage_root,
+                row.tag == ItemTag::StorageRoot,
+            ),
+            (
+                "is_code_hash",
+                self.is_code_hash,
+                row.tag == ItemTag::CodeHash,
+            ),
+            (
+                "is_storage_value",
+                self.is_storage_value,
+                row.tag == ItemTag::StorageValue,
+            ),
+            ("is_bytes", self.is_bytes, row.tag == ItemTag::Bytes),
+            ("is_padding", self.is_padding, row.is_padding),
+            (
+                "is_branch",
+                self.is_branch,
+                node_type == Some(NodeType::Branch),
+            ),
+            (
+                "is_ext",
+                self.is_ext,
+                node_type == Some(NodeType::Extension),
+            ),
+            (
+                "is_account_leaf",
+                self.is_account_leaf,
+                node_type == Some(NodeType::AccountLeaf),
+            ),
+            (
+                "is_storage_leaf",
+                self.is_storage_leaf,
+                node_type == Some(NodeType::StorageLeaf),
+            ),
+            (
+                "is_value_leaf",
+                self.is_value_leaf,
+                node_type == Some(NodeType::ValueLeaf),
+            ),
+            ("is_first", self.is_first, row.is_first),
+            ("is_last", self.is_last, row.is_last),
+            ("is_odd", self.is_odd, row.is_odd),
+            ("is_ref", self.is_ref, row.is_ref),
+        ] {
+            region.assign_advice(
+                || format!("mpt nodes {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("nibble", self.nibble, row.nibble as u64),
+            ("byte", self.byte, row.byte as u64),
+            ("nibble_hi", self.nibble_hi, (row.byte >> 4) as u64),
+            ("nibble_lo", self.nibble_lo, (row.byte & 0xf) as u64),
+            ("bytes_len", self.bytes_len, row.bytes_len as u64),
+            ("list_end", self.list_end, row.list_end as u64),
+            ("key_len", self.key_len, row.key_len as u64),
+            ("payload_len", self.payload_len, row.payload_len as u64),
+            ("child_cnt", self.child_cnt, row.child_cnt as u64),
+        ] {
+            region.assign_advice(
+                || format!("mpt nodes {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("bytes_rlc", self.bytes_rlc, row.bytes_rlc),
+            ("hash", self.hash, row.hash),
+            ("key_acc", self.key_acc, row.key_acc),
+            ("key_pow", self.key_pow, row.key_pow),
+            ("children", self.children, row.children),
+            ("payload", self.payload, row.payload),
+            ("nonce", self.nonce, row.nonce),
+            ("balance", self.balance, row.balance),
+            ("storage_root", self.storage_root, row.storage_root),
+            ("code_hash", self.code_hash, row.code_hash),
+        ] {
+            region.assign_advice(|| format!("mpt nodes {}", name), column, offset, || value)?;
+        }
+        RlpItemChip::construct(self.item).assign(region, offset, &row.item)
+    }
+}
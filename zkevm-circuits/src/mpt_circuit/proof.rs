@@ -0,0 +1,292 @@
+//! Rows of the proofs of the updates of the tries, which follow the paths to
+//! their keys before and after them.
+
+use eth_types::{
+    state_trie::{key_nibbles, TrieUpdate},
+    trie::TrieNode,
+    H256,
+};
+
+/// Kind of the rows of the proofs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum StepKind {
+    /// Branch nodes on both sides, whose children at the nibble of the key
+    /// are the nodes of the next row
+    Branch,
+    /// Extension nodes with the same nibbles, which are a prefix of the
+    /// remaining nibbles of the key, on both sides
+    Extension,
+    /// Leaves of the key on both sides
+    Leaf,
+    /// Same empty child, or leaf or extension node diverging from the key, on
+    /// both sides
+    Absent,
+    /// Empty child before the update, and leaf of the key after it
+    Insert,
+    /// Leaf or extension node diverging from the key before the update, and
+    /// the new node replacing it after it: the branch node, or an extension
+    /// node with the nibbles they share followed by the branch node
+    Split,
+    /// New branch node, with the leaf of the key and the node diverging from
+    /// the key as children
+    SplitBranch,
+    /// Node diverging from the key, with the nibbles after the ones shared
+    /// with the key, before the update, and leaf of the key after it
+    SplitLeaf,
+}
+
+/// Node of a row of a proof, before or after the update
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Side {
+    /// Hash of the node, zero for empty children
+    pub(crate) hash: H256,
+    /// The node, unless it's an empty child or not needed by the row
+    pub(crate) node: Option<TrieNode>,
+}
+
+impl Side {
+    fn new(hash: H256, node: Option<&TrieNode>) -> Self {
+        Self {
+            hash,
+            node: node.cloned(),
+        }
+    }
+}
+
+/// Row of a proof
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ProofRow {
+    /// Kind of the row
+    pub(crate) kind: StepKind,
+    /// Depth of the nodes of the row
+    pub(crate) depth: usize,
+    /// Node before the update
+    pub(crate) old: Side,
+    /// Node after the update
+    pub(crate) new: Side,
+    /// Depth of the end of the nibbles of extension nodes diverging from the
+    /// key, and of the first nibble where split nodes diverge from it
+    pub(crate) depth2: usize,
+    /// Number of nibbles of split nodes shared with the key
+    pub(crate) shared: usize,
+    /// Nibble of split nodes diverging from the key
+    pub(crate) nibble: u8,
+    /// Nibbles of split nodes after the one diverging from the key
+    pub(crate) rest: Vec<u8>,
+    /// Whether the split node is an extension node whose child becomes a
+    /// child of the new branch node
+    pub(crate) is_direct: bool,
+}
+
+impl ProofRow {
+    fn new(kind: StepKind, depth: usize, old: Side, new: Side) -> Self {
+        Self {
+            kind,
+            depth,
+            old,
+            new,
+            depth2: 0,
+            shared: 0,
+            nibble: 0,
+            rest: Vec::new(),
+            is_direct: false,
+        }
+    }
+}
+
+/// Proof of the update of a trie.  The removal of a key is proved as the
+/// insertion of the key in reverse, with the nodes before and after the
+/// update swapped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TrieProof {
+    /// Nibbles of the key
+    pub(crate) nibbles: Vec<u8>,
+    /// Whether the update removes the key
+    pub(crate) is_removal: bool,
+    /// Rows of the proof, from the roots to the leaves of the key or to the
+    /// nodes which prove its absence
+    pub(crate) rows: Vec<ProofRow>,
+}
+
+impl TrieProof {
+    /// Return the proof of the update, or `None` if its paths aren't the ones
+    /// of an update of the key.
+    pub(crate) fn new(update: &TrieUpdate) -> Option<Self> {
+        let nibbles = key_nibbles(&update.key);
+        let (old_path, new_path) = (&update.old_path, &update.new_path);
+        let is_removal = has_leaf(old_path, &nibbles) && !has_leaf(new_path, &nibbles);
+        let mut rows = Vec::new();
+        let (mut old_hash, mut new_hash) = (update.old_root, update.new_root);
+        let mut depth = 0;
+        for index in 0.. {
+            let (old, new) = (old_path.get(index), new_path.get(index));
+            let row = |kind| {
+                ProofRow::new(
+                    kind,
+                    depth,
+                    Side::new(old_hash, old),
+                    Side::new(new_hash, new),
+                )
+            };
+            match (old, new) {
+                (Some(TrieNode::Branch(old_children)), Some(TrieNode::Branch(new_children))) => {
+                    rows.push(row(StepKind::Branch));
+                    let nibble = *nibbles.get(depth)? as usize;
+                    old_hash = child_hash(&old_children[nibble])?;
+                    new_hash = child_hash(&new_children[nibble])?;
+                    depth += 1;
+                }
+                (
+                    Some(TrieNode::Extension(old_shared, old_child)),
+                    Some(TrieNode::Extension(new_shared, new_child)),
+                ) if old_shared == new_shared && nibbles[depth..].starts_with(old_shared) => {
+                    rows.push(row(StepKind::Extension));
+                    old_hash = child_hash(old_child)?;
+                    new_hash = child_hash(new_child)?;
+                    depth += old_shared.len();
+                }
+                (Some(TrieNode::Leaf(old_rest, _)), Some(TrieNode::Leaf(new_rest, _)))
+                    if old_rest[..] == nibbles[depth..] && new_rest == old_rest =>
+                {
+                    rows.push(row(StepKind::Leaf));
+                    break;
+                }
+                _ if old == new => {
+                    let mut row = row(StepKind::Absent);
+                    if let Some(TrieNode::Extension(shared, _)) = old {
+                        row.depth2 = depth + shared.len();
+                    }
+                    rows.push(row);
+                    break;
+                }
+                (None, Some(TrieNode::Leaf(..))) | (Some(TrieNode::Leaf(..)), None) => {
+                    rows.push(row(StepKind::Insert));
+                    break;
+                }
+                (Some(_), Some(_)) => {
+                    let (split, paths) = if is_removal {
+                        (Side::new(new_hash, new), (new_path, old_path))
+                    } else {
+                        (Side::new(old_hash, old), (old_path, new_path))
+                    };
+                    let hash = if is_removal { old_hash } else { new_hash };
+                    for mut row in split_rows(&nibbles, depth, split, hash, &paths.1[index..])? {
+                        if is_removal {
+                            // The child of the split node is merged with its
+                            // parent branch node by the removal.
+                            if row.kind == StepKind::SplitLeaf && row.is_direct {
+                                row.old.node = Some(update.merged.clone()?);
+                            }
+                            std::mem::swap(&mut row.old, &mut row.new);
+                        }
+                        rows.push(row);
+                    }
+                    break;
+                }
+                _ => return None,
+            }
+        }
+        Some(Self {
+            nibbles,
+            is_removal,
+            rows,
+        })
+    }
+}
+
+/// Return the rows of the insertion of the key in the place of the split
+/// node, whose new node has the hash and the path.  The rows have the split
+/// node and its nodes before the update, and the new nodes after it.
+fn split_rows(
+    nibbles: &[u8],
+    depth: usize,
+    split: Side,
+    hash: H256,
+    path: &[TrieNode],
+) -> Option<Vec<ProofRow>> {
+    let key = match &split.node {
+        Some(TrieNode::Leaf(key, _)) | Some(TrieNode::Extension(key, _)) => key.clone(),
+        _ => return None,
+    };
+    let remaining = nibbles.get(depth..)?;
+    let shared = key
+        .iter()
+        .zip(remaining)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (&nibble, rest) = key.get(shared..)?.split_first()?;
+    let is_direct = matches!(split.node, Some(TrieNode::Extension(..))) && rest.is_empty();
+
+    // The new node is the branch node, or an extension node followed by it.
+    let (branch_hash, branch_path) = match (shared, path) {
+        (0, path) => (hash, path),
+        (_, [TrieNode::Extension(prefix, child), path @ ..]) if prefix[..] == key[..shared] => {
+            (child_hash(child)?, path)
+        }
+        _ => return None,
+    };
+    let (children, leaf) = match branch_path {
+        [TrieNode::Branch(children), leaf @ TrieNode::Leaf(..), ..] => (children, leaf),
+        _ => return None,
+    };
+    let leaf_hash = child_hash(&children[*remaining.get(shared)? as usize])?;
+    let sibling_hash = child_hash(&children[nibble as usize])?;
+    let sibling = match &split.node {
+        Some(TrieNode::Extension(_, _)) if is_direct => None,
+        Some(TrieNode::Extension(_, child)) => {
+            Some(TrieNode::Extension(rest.to_vec(), child.clone()))
+        }
+        Some(TrieNode::Leaf(_, value)) => Some(TrieNode::Leaf(rest.to_vec(), value.clone())),
+        _ => return None,
+    };
+    if sibling
+        .as_ref()
+        .map_or(false, |node| node.hash() != sibling_hash)
+    {
+        return None;
+    }
+
+    let mut split_row = ProofRow::new(StepKind::Split, depth, split, Side::new(hash, path.first()));
+    split_row.depth2 = depth + shared;
+    split_row.shared = shared;
+    split_row.nibble = nibble;
+    split_row.rest = rest.to_vec();
+    split_row.is_direct = is_direct;
+    let branch_row = ProofRow::new(
+        StepKind::SplitBranch,
+        depth + shared,
+        Side::default(),
+        Side::new(branch_hash, branch_path.first()),
+    );
+    let mut leaf_row = ProofRow::new(
+        StepKind::SplitLeaf,
+        depth + shared + 1,
+        Side::new(sibling_hash, sibling.as_ref()),
+        Side::new(leaf_hash, Some(leaf)),
+    );
+    leaf_row.is_direct = is_direct;
+    Some(vec![split_row, branch_row, leaf_row])
+}
+
+/// Return the hash of the child with the reference, zero for empty children.
+/// Children embedded in their parent aren't supported.
+fn child_hash(reference: &[u8]) -> Option<H256> {
+    match reference {
+        [0x80] => Some(H256::zero()),
+        [0xa0, hash @ ..] => Some(H256::from_slice(hash)),
+        _ => None,
+    }
+}
+
+/// Return whether the path ends at the leaf of the key with the nibbles.
+fn has_leaf(path: &[TrieNode], nibbles: &[u8]) -> bool {
+    let depth = path
+        .iter()
+        .map(|node| match node {
+            TrieNode::Branch(_) => 1,
+            TrieNode::Extension(shared, _) => shared.len(),
+            TrieNode::Leaf(..) => 0,
+        })
+        .sum::<usize>();
+    matches!(path.last(), Some(TrieNode::Leaf(rest, _)) if nibbles.get(depth..) == Some(rest))
+}
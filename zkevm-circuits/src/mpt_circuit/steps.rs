@@ -0,0 +1,1795 @@
+//! Steps of the proofs of the updates, following the paths of their keys in
+//! the state trie and in the storage tries.
+//!
+//! Every update takes the rows of the proof of the update of the state trie,
+//! followed for storage updates by the rows of the proof of the update of the
+//! storage trie of the account.  Every row has the nodes before and after the
+//! update at a depth of the key, which are looked up in the nodes, and the
+//! nibbles of the key at the depth, which are looked up in the keys.  The
+//! proofs start at the roots of the updates in the `MptTable`, and end at the
+//! values of the updates or at the nodes proving the absence of their keys.
+//!
+//! The a side of the rows is the side before the update of insertions, and
+//! the one after the update of removals, which are proved as insertions with
+//! the sides swapped.  The b side is the other one.
+
+use super::{
+    keys::MptKeysConfig,
+    nodes::MptNodesConfig,
+    proof::{Side, StepKind, TrieProof},
+    MAX_DEGREE,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, MPTProofType, MptTable},
+    util::{Challenges, Expr},
+    witness::MptProof,
+};
+use eth_types::{
+    state_trie::{StateAccount, EMPTY_TRIE_ROOT},
+    trie::TrieNode,
+    Field, ToLittleEndian, Word,
+};
+use ethers_core::utils::rlp;
+use gadgets::util::{not, select, sum};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::marker::PhantomData;
+
+/// Columns of the nodes of the steps on one side of the update
+#[derive(Clone, Copy, Debug)]
+struct SideColumns {
+    hash: Column<Advice>,
+    has_branch: Column<Advice>,
+    has_ext: Column<Advice>,
+    has_leaf: Column<Advice>,
+    // Fields of the node, as in the nodes
+    key: Column<Advice>,
+    key_len: Column<Advice>,
+    payload: Column<Advice>,
+    children: Column<Advice>,
+    nonce: Column<Advice>,
+    balance: Column<Advice>,
+    storage_root: Column<Advice>,
+    code_hash: Column<Advice>,
+    // Child of branch nodes at the nibble of the key
+    child: Column<Advice>,
+    // Values of the key at the end of the proofs: the nonce, balance, storage
+    // root and code hash of accounts, or the value of storage slots followed
+    // by zeros, and the inverse of their fingerprint for present keys.
+    values: [Column<Advice>; 4],
+    values_inv: Column<Advice>,
+}
+
+impl SideColumns {
+    fn new<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        let [has_branch, has_ext, has_leaf, key_len] = [(); 4].map(|_| meta.advice_column());
+        let [hash, key, payload, children, child, values_inv] =
+            [(); 6].map(|_| meta.advice_column_in(SecondPhase));
+        let [nonce, balance, storage_root, code_hash] =
+            [(); 4].map(|_| meta.advice_column_in(SecondPhase));
+        let values = [(); 4].map(|_| meta.advice_column_in(SecondPhase));
+        Self {
+            hash,
+            has_branch,
+            has_ext,
+            has_leaf,
+            key,
+            key_len,
+            payload,
+            children,
+            nonce,
+            balance,
+            storage_root,
+            code_hash,
+            child,
+            values,
+            values_inv,
+        }
+    }
+
+    fn query<F: Field>(&self, meta: &mut VirtualCells<'_, F>, at: Rotation) -> SideExprs<F> {
+        SideExprs {
+            hash: meta.query_advice(self.hash, at),
+            has_branch: meta.query_advice(self.has_branch, at),
+            has_ext: meta.query_advice(self.has_ext, at),
+            has_leaf: meta.query_advice(self.has_leaf, at),
+            key: meta.query_advice(self.key, at),
+            key_len: meta.query_advice(self.key_len, at),
+            payload: meta.query_advice(self.payload, at),
+            children: meta.query_advice(self.children, at),
+            nonce: meta.query_advice(self.nonce, at),
+            balance: meta.query_advice(self.balance, at),
+            storage_root: meta.query_advice(self.storage_root, at),
+            code_hash: meta.query_advice(self.code_hash, at),
+            child: meta.query_advice(self.child, at),
+            values: self.values.map(|column| meta.query_advice(column, at)),
+            values_inv: meta.query_advice(self.values_inv, at),
+        }
+    }
+}
+
+/// Expressions of the nodes of the steps on one side of the update
+#[derive(Clone)]
+struct SideExprs<F> {
+    hash: Expression<F>,
+    has_branch: Expression<F>,
+    has_ext: Expression<F>,
+    has_leaf: Expression<F>,
+    key: Expression<F>,
+    key_len: Expression<F>,
+    payload: Expression<F>,
+    children: Expression<F>,
+    nonce: Expression<F>,
+    balance: Expression<F>,
+    storage_root: Expression<F>,
+    code_hash: Expression<F>,
+    child: Expression<F>,
+    values: [Expression<F>; 4],
+    values_inv: Expression<F>,
+}
+
+impl<F: Field> SideExprs<F> {
+    /// Return the expressions of `when_true` if the selector is 1, and of
+    /// `when_false` if it's 0.
+    fn select(selector: &Expression<F>, when_true: &Self, when_false: &Self) -> Self {
+        let choose = |a: &Expression<F>, b: &Expression<F>| {
+            select::expr(selector.clone(), a.clone(), b.clone())
+        };
+        Self {
+            hash: choose(&when_true.hash, &when_false.hash),
+            has_branch: choose(&when_true.has_branch, &when_false.has_branch),
+            has_ext: choose(&when_true.has_ext, &when_false.has_ext),
+            has_leaf: choose(&when_true.has_leaf, &when_false.has_leaf),
+            key: choose(&when_true.key, &when_false.key),
+            key_len: choose(&when_true.key_len, &when_false.key_len),
+            payload: choose(&when_true.payload, &when_false.payload),
+            children: choose(&when_true.children, &when_false.children),
+            nonce: choose(&when_true.nonce, &when_false.nonce),
+            balance: choose(&when_true.balance, &when_false.balance),
+            storage_root: choose(&when_true.storage_root, &when_false.storage_root),
+            code_hash: choose(&when_true.code_hash, &when_false.code_hash),
+            child: choose(&when_true.child, &when_false.child),
+            values: [0, 1, 2, 3]
+                .map(|index| choose(&when_true.values[index], &when_false.values[index])),
+            values_inv: choose(&when_true.values_inv, &when_false.values_inv),
+        }
+    }
+
+    fn has_node(&self) -> Expression<F> {
+        self.has_branch.clone() + self.has_ext.clone() + self.has_leaf.clone()
+    }
+
+    /// Return the type of the node in the nodes.
+    fn node_type(&self, is_storage: Expression<F>) -> Expression<F> {
+        self.has_ext.clone() + self.has_leaf.clone() * (2.expr() + is_storage)
+    }
+
+    /// Return the values of the leaf node, whose unused fields are zero.
+    fn node_values(&self) -> [Expression<F>; 4] {
+        [
+            self.payload.clone() + self.nonce.clone(),
+            self.balance.clone(),
+            self.storage_root.clone(),
+            self.code_hash.clone(),
+        ]
+    }
+}
+
+/// Values of the nodes of a step on one side of the update
+#[derive(Clone, Copy, Debug)]
+struct SideValues<F> {
+    hash: Value<F>,
+    has_branch: bool,
+    has_ext: bool,
+    has_leaf: bool,
+    key: Value<F>,
+    key_len: usize,
+    payload: Value<F>,
+    children: Value<F>,
+    nonce: Value<F>,
+    balance: Value<F>,
+    storage_root: Value<F>,
+    code_hash: Value<F>,
+    child: Value<F>,
+    values: [Value<F>; 4],
+    values_inv: Value<F>,
+}
+
+impl<F: Field> SideValues<F> {
+    /// Return the values of the node of the side, with the child at the
+    /// nibble of branch nodes.
+    fn new(
+        side: &Side,
+        nibble: u8,
+        is_storage: bool,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Self, Error> {
+        let zero = Value::known(F::ZERO);
+        let r = challenges.keccak_input();
+        let word_rlc = |word: Word| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&word.to_le_bytes(), challenge))
+        };
+        let nibbles_rlc = |nibbles: &[u8]| r.map(|r| rlc::value(nibbles.iter().rev(), r));
+        // Empty children have no bytes after their header.
+        let child_rlc = |reference: &[u8]| word_rlc(Word::from_big_endian(&reference[1..]));
+        let invalid = |node: &TrieNode| {
+            error!("mpt node {:?} is not supported", node);
+            Error::Synthesis
+        };
+
+        let mut values = Self {
+            hash: word_rlc(Word::from_big_endian(side.hash.as_bytes())),
+            has_branch: false,
+            has_ext: false,
+            has_leaf: false,
+            key: zero,
+            key_len: 0,
+            payload: zero,
+            children: zero,
+            nonce: zero,
+            balance: zero,
+            storage_root: zero,
+            code_hash: zero,
+            child: zero,
+            values: [zero; 4],
+            values_inv: zero,
+        };
+        match &side.node {
+            None => {}
+            Some(TrieNode::Branch(children)) => {
+                values.has_branch = true;
+                values.children = children
+                    .iter()
+                    .fold(zero, |acc, child| acc * r + child_rlc(child));
+                values.child = child_rlc(&children[nibble as usize]);
+            }
+            Some(TrieNode::Extension(key, child)) => {
+                values.has_ext = true;
+                values.key = nibbles_rlc(key);
+                values.key_len = key.len();
+                values.payload = child_rlc(child);
+            }
+            Some(node @ TrieNode::Leaf(key, value)) => {
+                values.has_leaf = true;
+                values.key = nibbles_rlc(key);
+                values.key_len = key.len();
+                if is_storage {
+                    let value: Word = rlp::decode(value).map_err(|_| invalid(node))?;
+                    values.payload = word_rlc(value);
+                } else {
+                    let account = StateAccount::from_rlp(value).map_err(|_| invalid(node))?;
+                    values.nonce = Value::known(F::from(account.nonce));
+                    values.balance = word_rlc(account.balance);
+                    values.storage_root =
+                        word_rlc(Word::from_big_endian(account.storage_root.as_bytes()));
+                    values.code_hash =
+                        word_rlc(Word::from_big_endian(account.code_hash.as_bytes()));
+                }
+            }
+        }
+        Ok(values)
+    }
+
+    /// Return the values of the leaf node, whose unused fields are zero.
+    fn node_values(&self) -> [Value<F>; 4] {
+        [
+            self.payload + self.nonce,
+            self.balance,
+            self.storage_root,
+            self.code_hash,
+        ]
+    }
+}
+
+/// Values of an update in all the rows of its proof
+#[derive(Clone, Copy, Debug)]
+struct UpdateValues<F> {
+    // Row of the update in the `MptTable`
+    row: [Value<F>; 7],
+    proof_type: MPTProofType,
+    // Field of the account updated by the update, which is the storage root
+    // of storage updates.
+    old_field: Value<F>,
+    new_field: Value<F>,
+}
+
+impl<F: Field> UpdateValues<F> {
+    fn new(proof: &MptProof, challenges: &Challenges<Value<F>>) -> Self {
+        let row = proof.update.table_assignment(challenges.evm_word()).0;
+        let storage_roots = |old_root: eth_types::H256, new_root: eth_types::H256| {
+            challenges
+                .evm_word()
+                .map(|challenge| {
+                    let root_rlc = |root: eth_types::H256| {
+                        rlc::value(
+                            &Word::from_big_endian(root.as_bytes()).to_le_bytes(),
+                            challenge,
+                        )
+                    };
+                    (root_rlc(old_root), root_rlc(new_root))
+                })
+                .unzip()
+        };
+        let (old_field, new_field) = match (&proof.storage, proof.update.proof_type()) {
+            (Some(storage), _) => storage_roots(storage.old_root, storage.new_root),
+            (None, MPTProofType::StorageCleared) => {
+                storage_roots(proof.old_storage_root, EMPTY_TRIE_ROOT)
+            }
+            (None, _) => (row[6], row[5]),
+        };
+        Self {
+            row,
+            proof_type: proof.update.proof_type(),
+            old_field,
+            new_field,
+        }
+    }
+
+    /// Return the flags of the nonce, balance, storage root and code hash of
+    /// the account updated by the update.
+    fn fields(&self) -> [bool; 4] {
+        let proof_type = self.proof_type;
+        [
+            matches!(proof_type, MPTProofType::NonceMod),
+            matches!(proof_type, MPTProofType::BalanceMod),
+            matches!(
+                proof_type,
+                MPTProofType::StorageMod
+                    | MPTProofType::NonExistingStorageProof
+                    | MPTProofType::StorageCleared
+            ),
+            matches!(
+                proof_type,
+                MPTProofType::CodeHashMod | MPTProofType::NonExistingAccountProof
+            ),
+        ]
+    }
+
+    fn is_non_existing(&self) -> bool {
+        matches!(
+            self.proof_type,
+            MPTProofType::NonExistingAccountProof | MPTProofType::NonExistingStorageProof
+        )
+    }
+
+    fn is_cleared(&self) -> bool {
+        matches!(self.proof_type, MPTProofType::StorageCleared)
+    }
+}
+
+/// Row of the steps
+#[derive(Clone, Copy, Debug)]
+struct StepRow<F> {
+    update: UpdateValues<F>,
+    kind: StepKind,
+    is_first: bool,
+    seg_first: bool,
+    is_storage: bool,
+    is_swap: bool,
+    depth: usize,
+    nibble: u8,
+    prefix: Value<F>,
+    key_rlc: Value<F>,
+    depth2: usize,
+    nibble2: u8,
+    prefix2: Value<F>,
+    shared: usize,
+    x_nibble: u8,
+    x_rest: Value<F>,
+    is_direct: bool,
+    pow_exp1: usize,
+    pow1: Value<F>,
+    pow_exp2: usize,
+    pow2: Value<F>,
+    shared_inv: Value<F>,
+    diverge_inv: Value<F>,
+    old: SideValues<F>,
+    new: SideValues<F>,
+}
+
+/// Config of the steps of the proofs of the updates
+#[derive(Clone, Debug)]
+pub(crate) struct MptStepsConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    is_update: Column<Advice>,
+    // First rows of the updates, and of the proofs of the updates of the
+    // state trie and of the storage tries.
+    is_first: Column<Advice>,
+    seg_first: Column<Advice>,
+    is_storage: Column<Advice>,
+    is_swap: Column<Advice>,
+    // Flags of the kinds of the steps, in the order of `StepKind`
+    kinds: [Column<Advice>; 8],
+    // Flags of the nonce, balance, storage root and code hash updated by the
+    // update
+    fields: [Column<Advice>; 4],
+    is_non_existing: Column<Advice>,
+    // Clear of the storage, which resets the storage root without a proof of
+    // the storage trie
+    is_cleared: Column<Advice>,
+    // Row of the update in the `MptTable`
+    update: [Column<Advice>; 7],
+    old_field: Column<Advice>,
+    new_field: Column<Advice>,
+    // Depth of the nodes, with the nibble of the key at the depth, the RLC of
+    // the nibbles before it and the RLC of all of them.
+    depth: Column<Advice>,
+    nibble: Column<Advice>,
+    prefix: Column<Advice>,
+    key_rlc: Column<Advice>,
+    // Second depth of the key, at the end of the nibbles of diverging
+    // extension nodes and at the nibble where split nodes diverge.
+    depth2: Column<Advice>,
+    nibble2: Column<Advice>,
+    prefix2: Column<Advice>,
+    // Number of nibbles of split nodes shared with the key, with the nibble
+    // diverging from it and the RLC of the nibbles after this one.
+    shared: Column<Advice>,
+    x_nibble: Column<Advice>,
+    x_rest: Column<Advice>,
+    is_direct: Column<Advice>,
+    // Powers of the keccak input challenge
+    pow_exp1: Column<Advice>,
+    pow1: Column<Advice>,
+    pow_exp2: Column<Advice>,
+    pow2: Column<Advice>,
+    shared_inv: Column<Advice>,
+    diverge_inv: Column<Advice>,
+    old: SideColumns,
+    new: SideColumns,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> MptStepsConfig<F> {
+    /// Return a new MptStepsConfig
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        nodes: &MptNodesConfig<F>,
+        keys: &MptKeysConfig<F>,
+        mpt_table: MptTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let [q_enable, q_first] = [(); 2].map(|_| meta.fixed_column());
+        let [is_update, is_first, seg_first, is_storage, is_swap, is_non_existing, is_direct] =
+            [(); 7].map(|_| meta.advice_column());
+        let is_cleared = meta.advice_column();
+        let kinds = [(); 8].map(|_| meta.advice_column());
+        let fields = [(); 4].map(|_| meta.advice_column());
+        let update = [
+            meta.advice_column(),
+            meta.advice_column_in(SecondPhase),
+            meta.advice_column(),
+            meta.advice_column_in(SecondPhase),
+            meta.advice_column_in(SecondPhase),
+            meta.advice_column_in(SecondPhase),
+            meta.advice_column_in(SecondPhase),
+        ];
+        let [depth, nibble, depth2, nibble2, shared, x_nibble, pow_exp1, pow_exp2] =
+            [(); 8].map(|_| meta.advice_column());
+        let [old_field, new_field] = [(); 2].map(|_| meta.advice_column_in(SecondPhase));
+        let [prefix, key_rlc, prefix2, x_rest, pow1, pow2, shared_inv, diverge_inv] =
+            [(); 8].map(|_| meta.advice_column_in(SecondPhase));
+        let (old, new) = (SideColumns::new(meta), SideColumns::new(meta));
+
+        meta.create_gate("mpt steps", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let [is_update_next, is_first_next, seg_first_next, is_storage_next, is_swap_next] =
+                [is_update, is_first, seg_first, is_storage, is_swap]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let [is_update, is_first, seg_first, is_storage, is_swap, is_non_existing, is_cleared] =
+                [
+                    is_update,
+                    is_first,
+                    seg_first,
+                    is_storage,
+                    is_swap,
+                    is_non_existing,
+                    is_cleared,
+                ]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_direct_prev2, shared_prev2, x_rest_prev2] =
+                [is_direct, shared, x_rest].map(|column| meta.query_advice(column, Rotation(-2)));
+            let is_direct = meta.query_advice(is_direct, Rotation::cur());
+            let is_branch_next =
+                meta.query_advice(kinds[StepKind::Branch as usize], Rotation::next());
+            let is_split_branch_next =
+                meta.query_advice(kinds[StepKind::SplitBranch as usize], Rotation::next());
+            let is_split_leaf_next =
+                meta.query_advice(kinds[StepKind::SplitLeaf as usize], Rotation::next());
+            let [is_branch, is_extension, is_leaf, is_absent] = [
+                StepKind::Branch,
+                StepKind::Extension,
+                StepKind::Leaf,
+                StepKind::Absent,
+            ]
+            .map(|kind| meta.query_advice(kinds[kind as usize], Rotation::cur()));
+            let [is_insert, is_split, is_split_branch, is_split_leaf] = [
+                StepKind::Insert,
+                StepKind::Split,
+                StepKind::SplitBranch,
+                StepKind::SplitLeaf,
+            ]
+            .map(|kind| meta.query_advice(kinds[kind as usize], Rotation::cur()));
+            let fields = fields.map(|column| meta.query_advice(column, Rotation::cur()));
+            let update_next = update.map(|column| meta.query_advice(column, Rotation::next()));
+            let [address, storage_key, proof_type, new_root, old_root, new_value, old_value] =
+                update.map(|column| meta.query_advice(column, Rotation::cur()));
+            let [old_field_next, new_field_next] =
+                [old_field, new_field].map(|column| meta.query_advice(column, Rotation::next()));
+            let [old_field, new_field] =
+                [old_field, new_field].map(|column| meta.query_advice(column, Rotation::cur()));
+            let x_nibble_prev = meta.query_advice(x_nibble, Rotation::prev());
+            let [depth_next, prefix_next] =
+                [depth, prefix].map(|column| meta.query_advice(column, Rotation::next()));
+            let [depth, nibble, depth2, nibble2, shared, x_nibble, pow_exp1, pow_exp2] = [
+                depth, nibble, depth2, nibble2, shared, x_nibble, pow_exp1, pow_exp2,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [prefix, key_rlc, prefix2, x_rest, pow1, pow2, shared_inv, diverge_inv] = [
+                prefix,
+                key_rlc,
+                prefix2,
+                x_rest,
+                pow1,
+                pow2,
+                shared_inv,
+                diverge_inv,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let (old_next, new_next) = (
+                old.query(meta, Rotation::next()),
+                new.query(meta, Rotation::next()),
+            );
+            let (old_prev2, new_prev2) =
+                (old.query(meta, Rotation(-2)), new.query(meta, Rotation(-2)));
+            let (old, new) = (
+                old.query(meta, Rotation::cur()),
+                new.query(meta, Rotation::cur()),
+            );
+            let (a, b) = (
+                SideExprs::select(&is_swap, &new, &old),
+                SideExprs::select(&is_swap, &old, &new),
+            );
+            let (a_next, b_next) = (
+                SideExprs::select(&is_swap, &new_next, &old_next),
+                SideExprs::select(&is_swap, &old_next, &new_next),
+            );
+            let x_prev2 = SideExprs::select(&is_swap, &new_prev2, &old_prev2);
+
+            let r = challenges.keccak_input();
+            let empty_root = rlc::expr(
+                &EMPTY_TRIE_ROOT
+                    .to_fixed_bytes()
+                    .iter()
+                    .rev()
+                    .map(|byte| byte.expr())
+                    .collect::<Vec<_>>(),
+                challenges.evm_word(),
+            );
+            let is_account = not::expr(is_storage.clone());
+            let is_not_first = not::expr(is_first.clone());
+            let is_terminal =
+                is_leaf.clone() + is_absent.clone() + is_insert.clone() + is_split_leaf.clone();
+            let is_path = is_branch.clone()
+                + is_extension.clone()
+                + is_split.clone()
+                + is_split_branch.clone();
+            let [is_nonce, is_balance, is_storage_root, is_code_hash] = fields.clone();
+            // The storage proof follows the updates of the storage root, except
+            // for the clears of the storage.
+            let has_storage = is_account.clone() * (is_storage_root.clone() - is_cleared.clone());
+
+            // Flags
+            for flag in [
+                is_update.clone(),
+                is_first.clone(),
+                seg_first.clone(),
+                is_storage.clone(),
+                is_swap.clone(),
+                is_non_existing.clone(),
+                is_cleared.clone(),
+                is_direct.clone(),
+                old.has_node(),
+                new.has_node(),
+            ]
+            .into_iter()
+            .chain(fields.clone())
+            .chain([
+                is_branch.clone(),
+                is_extension.clone(),
+                is_leaf.clone(),
+                is_absent.clone(),
+                is_insert.clone(),
+                is_split.clone(),
+                is_split_branch.clone(),
+                is_split_leaf.clone(),
+            ])
+            .chain([&old, &new].into_iter().flat_map(|side| {
+                [
+                    side.has_branch.clone(),
+                    side.has_ext.clone(),
+                    side.has_leaf.clone(),
+                ]
+            })) {
+                cb.require_boolean("mpt steps flag is boolean", flag);
+            }
+            cb.require_equal(
+                "the updates have a kind of step",
+                is_terminal.clone() + is_path.clone(),
+                is_update.clone(),
+            );
+            cb.require_zero(
+                "is_direct is only set in split steps",
+                is_direct.clone() * not::expr(is_split.clone()),
+            );
+            cb.condition(q_first, |cb| {
+                cb.require_equal(
+                    "the updates start at the first row",
+                    is_first.clone(),
+                    is_update.clone(),
+                );
+                cb.require_equal(
+                    "the proofs start at the first row",
+                    seg_first.clone(),
+                    is_update.clone(),
+                );
+            });
+            cb.condition(not::expr(is_update.clone()), |cb| {
+                cb.require_zero("is_first is only set in updates", is_first.clone());
+                cb.require_zero("seg_first is only set in updates", seg_first.clone());
+                cb.require_zero("the updates are consecutive", is_update_next.clone());
+            });
+            cb.condition(is_first.clone(), |cb| {
+                cb.require_equal(
+                    "the updates start with a proof",
+                    seg_first.clone(),
+                    1.expr(),
+                );
+                cb.require_zero("the updates start in the state trie", is_storage.clone());
+            });
+
+            // Update
+            cb.condition(is_update.clone(), |cb| {
+                cb.require_equal(
+                    "an account field is updated",
+                    sum::expr(fields.clone()),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the proof type is the one of the field",
+                    proof_type.clone(),
+                    is_nonce.clone()
+                        + is_balance.clone() * 2.expr()
+                        + is_code_hash.clone() * 3.expr()
+                        + is_storage_root.clone() * 5.expr()
+                        + is_non_existing.clone()
+                        + is_cleared.clone() * 2.expr(),
+                );
+            });
+            cb.condition(is_cleared.clone(), |cb| {
+                cb.require_equal(
+                    "the clears of the storage update the storage root",
+                    is_storage_root.clone(),
+                    1.expr(),
+                );
+                cb.require_zero(
+                    "the clears of the storage have no old value",
+                    old_value.clone(),
+                );
+                cb.require_equal(
+                    "the clears of the storage have the new value 1",
+                    new_value.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the clears of the storage reset the storage root",
+                    new_field.clone(),
+                    empty_root.clone(),
+                );
+            });
+            cb.condition(is_non_existing.clone(), |cb| {
+                cb.require_zero(
+                    "non existing proofs are for code hashes and storage slots",
+                    is_nonce.clone() + is_balance.clone(),
+                );
+                cb.require_zero("non existing keys have no old value", old_value.clone());
+                cb.require_zero("non existing keys have no new value", new_value.clone());
+            });
+            cb.condition(
+                is_update.clone() * not::expr(is_storage_root.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "the old field is the old value",
+                        old_field.clone(),
+                        old_value.clone(),
+                    );
+                    cb.require_equal(
+                        "the new field is the new value",
+                        new_field.clone(),
+                        new_value.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                is_update_next.clone() * not::expr(is_first_next.clone()),
+                |cb| {
+                    for (value_next, value) in update_next.iter().zip([
+                        &address,
+                        &storage_key,
+                        &proof_type,
+                        &new_root,
+                        &old_root,
+                        &new_value,
+                        &old_value,
+                    ]) {
+                        cb.require_equal(
+                            "the update is the same in all its rows",
+                            value_next.clone(),
+                            value.clone(),
+                        );
+                    }
+                    cb.require_equal(
+                        "old_field is the same in all the rows",
+                        old_field_next.clone(),
+                        old_field.clone(),
+                    );
+                    cb.require_equal(
+                        "new_field is the same in all the rows",
+                        new_field_next.clone(),
+                        new_field.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                is_update_next.clone() * not::expr(seg_first_next.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "is_storage is the same in the proof",
+                        is_storage_next.clone(),
+                        is_storage.clone(),
+                    );
+                    cb.require_equal(
+                        "is_swap is the same in the proof",
+                        is_swap_next.clone(),
+                        is_swap.clone(),
+                    );
+                },
+            );
+            cb.condition(seg_first.clone(), |cb| {
+                cb.require_zero("the proofs start at the roots", depth.clone());
+                cb.require_equal(
+                    "the old root is the one of the update or the old storage root",
+                    old.hash.clone(),
+                    is_storage.clone() * old_field.clone() + is_account.clone() * old_root.clone(),
+                );
+                cb.require_equal(
+                    "the new root is the one of the update or the new storage root",
+                    new.hash.clone(),
+                    is_storage.clone() * new_field.clone() + is_account.clone() * new_root.clone(),
+                );
+            });
+            cb.condition(is_path.clone(), |cb| {
+                cb.require_equal(
+                    "the proof continues in the next row",
+                    is_update_next.clone(),
+                    1.expr(),
+                );
+                cb.require_zero(
+                    "the proof continues in the next row",
+                    seg_first_next.clone(),
+                );
+            });
+            cb.condition(is_terminal.clone() * has_storage.clone(), |cb| {
+                cb.require_equal(
+                    "the storage proof follows",
+                    is_update_next.clone(),
+                    1.expr(),
+                );
+                cb.require_zero("the storage proof follows", is_first_next.clone());
+                cb.require_equal(
+                    "the storage proof follows",
+                    seg_first_next.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the storage proof follows",
+                    is_storage_next.clone(),
+                    1.expr(),
+                );
+            });
+            cb.condition(is_terminal.clone() * not::expr(has_storage), |cb| {
+                cb.require_equal(
+                    "the next update follows",
+                    is_first_next.clone(),
+                    is_update_next.clone(),
+                );
+                cb.require_equal(
+                    "the next update follows",
+                    seg_first_next.clone(),
+                    is_update_next.clone(),
+                );
+            });
+
+            // Branch nodes on both sides, whose children at the nibble of
+            // the key are the next nodes.
+            cb.condition(is_branch.clone(), |cb| {
+                cb.require_equal("old branch", old.has_branch.clone(), 1.expr());
+                cb.require_equal("new branch", new.has_branch.clone(), 1.expr());
+                cb.require_equal(
+                    "pow1 is r^(15 - nibble)",
+                    pow_exp1.clone(),
+                    15.expr() - nibble.clone(),
+                );
+                cb.require_equal(
+                    "the other children of the branches are the same",
+                    old.children.clone() - old.child.clone() * pow1.clone(),
+                    new.children.clone() - new.child.clone() * pow1.clone(),
+                );
+                cb.require_equal(
+                    "the next depth follows the branch",
+                    depth_next.clone(),
+                    depth.clone() + 1.expr(),
+                );
+                cb.require_equal(
+                    "the next old node is the child",
+                    old_next.hash.clone(),
+                    old.child.clone(),
+                );
+                cb.require_equal(
+                    "the next new node is the child",
+                    new_next.hash.clone(),
+                    new.child.clone(),
+                );
+            });
+            // Extension nodes with the same nibbles, which are the next
+            // nibbles of the key, on both sides.
+            cb.condition(is_extension.clone(), |cb| {
+                cb.require_equal("old extension", old.has_ext.clone(), 1.expr());
+                cb.require_equal("new extension", new.has_ext.clone(), 1.expr());
+                cb.require_equal(
+                    "the extensions have the same key",
+                    old.key.clone(),
+                    new.key.clone(),
+                );
+                cb.require_equal(
+                    "the extensions have the same key",
+                    old.key_len.clone(),
+                    new.key_len.clone(),
+                );
+                cb.require_equal("pow1 is r^key_len", pow_exp1.clone(), old.key_len.clone());
+                cb.require_equal(
+                    "the key of the extensions continues the key",
+                    prefix_next.clone(),
+                    prefix.clone() * pow1.clone() + old.key.clone(),
+                );
+                cb.require_equal(
+                    "the next depth follows the extension",
+                    depth_next.clone(),
+                    depth.clone() + old.key_len.clone(),
+                );
+                cb.require_equal(
+                    "the next old node is the child",
+                    old_next.hash.clone(),
+                    old.payload.clone(),
+                );
+                cb.require_equal(
+                    "the next new node is the child",
+                    new_next.hash.clone(),
+                    new.payload.clone(),
+                );
+                cb.require_equal(
+                    "extensions are followed by branches",
+                    is_branch_next.clone(),
+                    1.expr(),
+                );
+            });
+            // Leaves of the key on both sides
+            cb.condition(is_leaf.clone(), |cb| {
+                for side in [&old, &new] {
+                    cb.require_equal("leaf", side.has_leaf.clone(), 1.expr());
+                    cb.require_equal(
+                        "the leaf has the rest of the key",
+                        side.key_len.clone(),
+                        64.expr() - depth.clone(),
+                    );
+                    cb.require_equal(
+                        "the leaf has the rest of the key",
+                        key_rlc.clone(),
+                        prefix.clone() * pow1.clone() + side.key.clone(),
+                    );
+                }
+                cb.require_equal(
+                    "pow1 is r^(64 - depth)",
+                    pow_exp1.clone(),
+                    64.expr() - depth.clone(),
+                );
+            });
+            // Same empty child, or leaf or extension diverging from the key,
+            // on both sides
+            cb.condition(is_absent.clone(), |cb| {
+                cb.require_equal("the node is the same", old.hash.clone(), new.hash.clone());
+                cb.require_equal(
+                    "the node is the same",
+                    old.has_ext.clone(),
+                    new.has_ext.clone(),
+                );
+                cb.require_equal(
+                    "the node is the same",
+                    old.has_leaf.clone(),
+                    new.has_leaf.clone(),
+                );
+                cb.require_zero(
+                    "branches have a child at every nibble",
+                    old.has_branch.clone(),
+                );
+                cb.require_zero("the new node isn't a branch", new.has_branch.clone());
+            });
+            cb.condition(is_absent.clone() * not::expr(old.has_node()), |cb| {
+                cb.require_equal(
+                    "empty children are empty tries at the roots",
+                    old.hash.clone(),
+                    seg_first.clone() * empty_root.clone(),
+                );
+            });
+            cb.condition(is_absent.clone() * old.has_leaf.clone(), |cb| {
+                cb.require_equal(
+                    "the leaf has the length of the rest of the key",
+                    old.key_len.clone(),
+                    64.expr() - depth.clone(),
+                );
+                cb.require_equal(
+                    "pow1 is r^(64 - depth)",
+                    pow_exp1.clone(),
+                    64.expr() - depth.clone(),
+                );
+                cb.require_equal(
+                    "the leaf has another key",
+                    (key_rlc.clone() - prefix.clone() * pow1.clone() - old.key.clone())
+                        * diverge_inv.clone(),
+                    1.expr(),
+                );
+            });
+            cb.condition(is_absent.clone() * old.has_ext.clone(), |cb| {
+                cb.require_equal(
+                    "depth2 is at the end of the extension",
+                    depth2.clone(),
+                    depth.clone() + old.key_len.clone(),
+                );
+                cb.require_equal("pow1 is r^key_len", pow_exp1.clone(), old.key_len.clone());
+                cb.require_equal(
+                    "the extension has other nibbles",
+                    (prefix2.clone() - prefix.clone() * pow1.clone() - old.key.clone())
+                        * diverge_inv.clone(),
+                    1.expr(),
+                );
+            });
+            // Empty child on the a side, and leaf of the key on the b side
+            cb.condition(is_insert.clone() + is_split_leaf.clone(), |cb| {
+                cb.require_equal("leaf", b.has_leaf.clone(), 1.expr());
+                cb.require_equal(
+                    "the leaf has the rest of the key",
+                    b.key_len.clone(),
+                    64.expr() - depth.clone(),
+                );
+                cb.require_equal(
+                    "pow1 is r^(64 - depth)",
+                    pow_exp1.clone(),
+                    64.expr() - depth.clone(),
+                );
+                cb.require_equal(
+                    "the leaf has the rest of the key",
+                    key_rlc.clone(),
+                    prefix.clone() * pow1.clone() + b.key.clone(),
+                );
+            });
+            cb.condition(is_insert.clone(), |cb| {
+                cb.require_zero("empty child", a.has_node());
+                cb.require_equal(
+                    "empty children are empty tries at the roots",
+                    a.hash.clone(),
+                    seg_first.clone() * empty_root.clone(),
+                );
+            });
+
+            // Leaf or extension node diverging from the key on the a side,
+            // and the node replacing it on the b side
+            cb.condition(is_split.clone(), |cb| {
+                cb.require_equal(
+                    "split leaf or extension",
+                    a.has_leaf.clone() + a.has_ext.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "depth2 is after the shared nibbles",
+                    depth2.clone(),
+                    depth.clone() + shared.clone(),
+                );
+                cb.require_equal("pow1 is r^shared", pow_exp1.clone(), shared.clone());
+                cb.require_equal(
+                    "pow2 is r^(key_len - shared - 1)",
+                    pow_exp2.clone(),
+                    a.key_len.clone() - shared.clone() - 1.expr(),
+                );
+                cb.require_zero(
+                    "shared_inv is the inverse of shared",
+                    shared.clone() * (1.expr() - shared.clone() * shared_inv.clone()),
+                );
+                cb.require_equal(
+                    "the split node has the shared nibbles, the diverging one and the rest",
+                    a.key.clone(),
+                    ((prefix2.clone() - prefix.clone() * pow1.clone()) * r.clone()
+                        + x_nibble.clone())
+                        * pow2.clone()
+                        + x_rest.clone(),
+                );
+                cb.require_equal(
+                    "the split node diverges from the key",
+                    (x_nibble.clone() - nibble2.clone()) * diverge_inv.clone(),
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the new branch follows",
+                    is_split_branch_next.clone(),
+                    1.expr(),
+                );
+                cb.require_equal("the new branch follows", depth_next.clone(), depth2.clone());
+            });
+            cb.condition(
+                is_split.clone() * shared.clone() * shared_inv.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "the shared nibbles are in an extension",
+                        b.has_ext.clone(),
+                        1.expr(),
+                    );
+                    cb.require_equal(
+                        "the shared nibbles are in an extension",
+                        b.key.clone(),
+                        prefix2.clone() - prefix.clone() * pow1.clone(),
+                    );
+                    cb.require_equal(
+                        "the shared nibbles are in an extension",
+                        b.key_len.clone(),
+                        shared.clone(),
+                    );
+                    cb.require_equal(
+                        "the new branch is the child",
+                        b_next.hash.clone(),
+                        b.payload.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                is_split.clone() * (1.expr() - shared.clone() * shared_inv.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "the new branch replaces the split node",
+                        b_next.hash.clone(),
+                        b.hash.clone(),
+                    );
+                },
+            );
+            cb.condition(is_direct.clone(), |cb| {
+                cb.require_equal(
+                    "direct split nodes are extensions",
+                    a.has_ext.clone(),
+                    1.expr(),
+                );
+                cb.require_zero("direct split nodes end at the nibble", pow_exp2.clone());
+                cb.require_zero("direct split nodes end at the nibble", x_rest.clone());
+            });
+            // New branch with the leaf of the key and the split node as
+            // children
+            cb.condition(is_split_branch.clone(), |cb| {
+                cb.require_zero("no node before the new branch", a.has_node());
+                cb.require_equal("new branch", b.has_branch.clone(), 1.expr());
+                cb.require_equal(
+                    "pow1 is r^(15 - nibble)",
+                    pow_exp1.clone(),
+                    15.expr() - nibble.clone(),
+                );
+                cb.require_equal(
+                    "pow2 is r^(15 - x_nibble)",
+                    pow_exp2.clone(),
+                    15.expr() - x_nibble_prev.clone(),
+                );
+                cb.require_equal(
+                    "the new branch has the leaf and the split node as children",
+                    b.children.clone(),
+                    b_next.hash.clone() * pow1.clone() + a_next.hash.clone() * pow2.clone(),
+                );
+                cb.require_equal("the leaf follows", is_split_leaf_next.clone(), 1.expr());
+                cb.require_equal(
+                    "the leaf follows",
+                    depth_next.clone(),
+                    depth.clone() + 1.expr(),
+                );
+            });
+            // Split node after the nibble diverging from the key on the a
+            // side, and the leaf of the key on the b side
+            cb.condition(is_split_leaf.clone() * is_direct_prev2.clone(), |cb| {
+                cb.require_equal(
+                    "the child of direct split nodes",
+                    a.hash.clone(),
+                    x_prev2.payload.clone(),
+                );
+            });
+            cb.condition(
+                is_split_leaf.clone() * is_direct_prev2.clone() * is_swap.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "removals merge branches with their parent extension",
+                        a.has_branch.clone(),
+                        1.expr(),
+                    );
+                },
+            );
+            cb.condition(
+                is_split_leaf.clone() * is_direct_prev2.clone() * not::expr(is_swap.clone()),
+                |cb| {
+                    cb.require_zero("the child of direct split nodes", a.has_node());
+                },
+            );
+            cb.condition(
+                is_split_leaf.clone() * not::expr(is_direct_prev2.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "the split node is shortened",
+                        a.has_leaf.clone(),
+                        x_prev2.has_leaf.clone(),
+                    );
+                    cb.require_equal(
+                        "the split node is shortened",
+                        a.has_ext.clone(),
+                        x_prev2.has_ext.clone(),
+                    );
+                    cb.require_equal(
+                        "the split node is shortened",
+                        a.key.clone(),
+                        x_rest_prev2.clone(),
+                    );
+                    cb.require_equal(
+                        "the split node is shortened",
+                        a.key_len.clone(),
+                        x_prev2.key_len.clone() - shared_prev2.clone() - 1.expr(),
+                    );
+                    for (value, value_prev2) in [
+                        (&a.payload, &x_prev2.payload),
+                        (&a.nonce, &x_prev2.nonce),
+                        (&a.balance, &x_prev2.balance),
+                        (&a.storage_root, &x_prev2.storage_root),
+                        (&a.code_hash, &x_prev2.code_hash),
+                    ] {
+                        cb.require_equal(
+                            "the split node keeps its value",
+                            value.clone(),
+                            value_prev2.clone(),
+                        );
+                    }
+                },
+            );
+
+            // Values at the end of the proofs
+            let default_values = [
+                0.expr(),
+                0.expr(),
+                is_account.clone() * empty_root.clone(),
+                0.expr(),
+            ];
+            let fingerprint = |values: &[Expression<F>; 4]| {
+                values[0].clone()
+                    + values[1].clone() * r.clone()
+                    + (values[2].clone() - default_values[2].clone()) * r.clone() * r.clone()
+                    + values[3].clone() * r.clone() * r.clone() * r.clone()
+            };
+            cb.condition(is_leaf.clone(), |cb| {
+                for side in [&old, &new] {
+                    for (value, node_value) in side.values.iter().zip(side.node_values()) {
+                        cb.require_equal(
+                            "the values are the ones of the leaf",
+                            value.clone(),
+                            node_value,
+                        );
+                    }
+                    cb.require_equal(
+                        "the key is present",
+                        fingerprint(&side.values) * side.values_inv.clone(),
+                        1.expr(),
+                    );
+                }
+            });
+            cb.condition(is_absent.clone(), |cb| {
+                for side in [&old, &new] {
+                    for (value, default_value) in side.values.iter().zip(default_values.iter()) {
+                        cb.require_equal(
+                            "absent keys have default values",
+                            value.clone(),
+                            default_value.clone(),
+                        );
+                    }
+                }
+            });
+            cb.condition(is_insert.clone() + is_split_leaf.clone(), |cb| {
+                for (value, default_value) in a.values.iter().zip(default_values.iter()) {
+                    cb.require_equal(
+                        "absent keys have default values",
+                        value.clone(),
+                        default_value.clone(),
+                    );
+                }
+                for (value, node_value) in b.values.iter().zip(b.node_values()) {
+                    cb.require_equal(
+                        "the values are the ones of the leaf",
+                        value.clone(),
+                        node_value,
+                    );
+                }
+                cb.require_equal(
+                    "the key is present",
+                    fingerprint(&b.values) * b.values_inv.clone(),
+                    1.expr(),
+                );
+            });
+            cb.condition(is_terminal.clone() * is_storage.clone(), |cb| {
+                cb.require_equal(
+                    "the old value of the slot",
+                    old.values[0].clone(),
+                    old_value.clone(),
+                );
+                cb.require_equal(
+                    "the new value of the slot",
+                    new.values[0].clone(),
+                    new_value.clone(),
+                );
+            });
+            for (index, field) in fields.iter().enumerate() {
+                cb.condition(
+                    is_terminal.clone() * is_account.clone() * field.clone(),
+                    |cb| {
+                        cb.require_equal(
+                            "the old value of the field",
+                            old.values[index].clone(),
+                            old_field.clone(),
+                        );
+                        cb.require_equal(
+                            "the new value of the field",
+                            new.values[index].clone(),
+                            new_field.clone(),
+                        );
+                    },
+                );
+                cb.condition(
+                    is_terminal.clone() * is_account.clone() * not::expr(field.clone()),
+                    |cb| {
+                        cb.require_equal(
+                            "the other fields are unchanged",
+                            old.values[index].clone(),
+                            new.values[index].clone(),
+                        );
+                    },
+                );
+            }
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.lookup_any("mpt steps mpt table", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            mpt_table
+                .table_exprs(meta)
+                .into_iter()
+                .zip(update)
+                .map(|(input, column)| {
+                    (
+                        input,
+                        enable.clone() * meta.query_advice(column, Rotation::cur()),
+                    )
+                })
+                .collect()
+        });
+        meta.lookup_any("mpt steps key", |meta| {
+            let is_update = meta.query_advice(is_update, Rotation::cur());
+            let is_storage = meta.query_advice(is_storage, Rotation::cur());
+            let [address, storage_key] =
+                [update[0], update[1]].map(|column| meta.query_advice(column, Rotation::cur()));
+            let input = [
+                select::expr(is_storage.clone(), storage_key, address),
+                is_storage,
+                meta.query_advice(depth, Rotation::cur()),
+                meta.query_advice(nibble, Rotation::cur()),
+                meta.query_advice(prefix, Rotation::cur()),
+                meta.query_advice(key_rlc, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keys.key_exprs(meta))
+                .map(|(input, table)| (is_update.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("mpt steps key depth2", |meta| {
+            let enable = meta.query_advice(kinds[StepKind::Absent as usize], Rotation::cur())
+                * meta.query_advice(old.has_ext, Rotation::cur())
+                + meta.query_advice(kinds[StepKind::Split as usize], Rotation::cur());
+            let is_storage = meta.query_advice(is_storage, Rotation::cur());
+            let [address, storage_key] =
+                [update[0], update[1]].map(|column| meta.query_advice(column, Rotation::cur()));
+            let input = [
+                select::expr(is_storage.clone(), storage_key, address),
+                is_storage,
+                meta.query_advice(depth2, Rotation::cur()),
+                meta.query_advice(nibble2, Rotation::cur()),
+                meta.query_advice(prefix2, Rotation::cur()),
+                meta.query_advice(key_rlc, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keys.key_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+        for (pow_exp, pow) in [(pow_exp1, pow1), (pow_exp2, pow2)] {
+            meta.lookup_any("mpt steps power", |meta| {
+                [pow_exp, pow]
+                    .map(|column| meta.query_advice(column, Rotation::cur()))
+                    .into_iter()
+                    .zip(keys.pow_exprs(meta))
+                    .collect()
+            });
+        }
+        for side in [old, new] {
+            meta.lookup_any("mpt steps node", |meta| {
+                let is_storage = meta.query_advice(is_storage, Rotation::cur());
+                let side = side.query(meta, Rotation::cur());
+                let input = [
+                    side.hash.clone(),
+                    side.node_type(is_storage),
+                    side.key.clone(),
+                    side.key_len.clone(),
+                    side.payload.clone(),
+                    side.children.clone(),
+                    side.nonce.clone(),
+                    side.balance.clone(),
+                    side.storage_root.clone(),
+                    side.code_hash.clone(),
+                ];
+                input
+                    .into_iter()
+                    .zip(nodes.node_exprs(meta))
+                    .map(|(input, table)| (side.has_node() * input, table))
+                    .collect()
+            });
+            meta.lookup_any("mpt steps child", |meta| {
+                let has_branch = meta.query_advice(side.has_branch, Rotation::cur());
+                let input = [
+                    meta.query_advice(side.hash, Rotation::cur()),
+                    meta.query_advice(nibble, Rotation::cur()),
+                    meta.query_advice(side.child, Rotation::cur()),
+                ];
+                input
+                    .into_iter()
+                    .zip(nodes.child_exprs(meta))
+                    .map(|(input, table)| (has_branch.clone() * input, table))
+                    .collect()
+            });
+        }
+
+        Self {
+            q_enable,
+            q_first,
+            is_update,
+            is_first,
+            seg_first,
+            is_storage,
+            is_swap,
+            kinds,
+            fields,
+            is_non_existing,
+            is_cleared,
+            update,
+            old_field,
+            new_field,
+            depth,
+            nibble,
+            prefix,
+            key_rlc,
+            depth2,
+            nibble2,
+            prefix2,
+            shared,
+            x_nibble,
+            x_rest,
+            is_direct,
+            pow_exp1,
+            pow1,
+            pow_exp2,
+            pow2,
+            shared_inv,
+            diverge_inv,
+            old,
+            new,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return the rows of the proofs of the updates.
+    fn step_rows(
+        proofs: &[(&MptProof, Vec<TrieProof>)],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<StepRow<F>>, Error> {
+        let r = challenges.keccak_input();
+        let pow = |exponent: usize| r.map(|r| r.pow([exponent as u64, 0, 0, 0]));
+        let invert = |value: Value<F>| value.map(|value| value.invert().unwrap_or(F::ZERO));
+        let empty_root = challenges.evm_word().map(|challenge| {
+            rlc::value(
+                &Word::from_big_endian(EMPTY_TRIE_ROOT.as_bytes()).to_le_bytes(),
+                challenge,
+            )
+        });
+
+        let mut rows = Vec::new();
+        for (proof, trie_proofs) in proofs {
+            let update = UpdateValues::new(proof, challenges);
+            // The proof of the state trie is followed by the one of the
+            // storage trie.
+            for (segment, trie_proof) in trie_proofs.iter().enumerate() {
+                let is_storage = segment > 0;
+                let nibbles = &trie_proof.nibbles;
+                let nibble_at = |depth: usize| nibbles.get(depth).copied().unwrap_or(0);
+                let prefix_at =
+                    |depth: usize| r.map(|r| rlc::value(nibbles[..depth].iter().rev(), r));
+                let key_rlc = prefix_at(nibbles.len());
+                let is_swap = trie_proof.is_removal;
+                let default_values = [
+                    Value::known(F::ZERO),
+                    Value::known(F::ZERO),
+                    if is_storage {
+                        Value::known(F::ZERO)
+                    } else {
+                        empty_root
+                    },
+                    Value::known(F::ZERO),
+                ];
+                let fingerprint = |values: [Value<F>; 4]| {
+                    values[0]
+                        + values[1] * r
+                        + (values[2] - default_values[2]) * r * r
+                        + values[3] * r * r * r
+                };
+                let mut x_nibble_prev = 0;
+                for (index, proof_row) in trie_proof.rows.iter().enumerate() {
+                    let (depth, kind) = (proof_row.depth, proof_row.kind);
+                    let nibble = nibble_at(depth);
+                    let mut old = SideValues::new(&proof_row.old, nibble, is_storage, challenges)?;
+                    let mut new = SideValues::new(&proof_row.new, nibble, is_storage, challenges)?;
+                    let prefix = prefix_at(depth);
+                    let mut row = StepRow {
+                        update,
+                        kind,
+                        is_first: !is_storage && index == 0,
+                        seg_first: index == 0,
+                        is_storage,
+                        is_swap,
+                        depth,
+                        nibble,
+                        prefix,
+                        key_rlc,
+                        depth2: 0,
+                        nibble2: 0,
+                        prefix2: Value::known(F::ZERO),
+                        shared: 0,
+                        x_nibble: 0,
+                        x_rest: Value::known(F::ZERO),
+                        is_direct: false,
+                        pow_exp1: 0,
+                        pow1: Value::known(F::ONE),
+                        pow_exp2: 0,
+                        pow2: Value::known(F::ONE),
+                        shared_inv: Value::known(F::ZERO),
+                        diverge_inv: Value::known(F::ZERO),
+                        old,
+                        new,
+                    };
+                    let (a, b) = if is_swap {
+                        (&mut new, &mut old)
+                    } else {
+                        (&mut old, &mut new)
+                    };
+                    match kind {
+                        StepKind::Branch | StepKind::SplitBranch => {
+                            row.pow_exp1 = 15 - nibble as usize;
+                            if kind == StepKind::SplitBranch {
+                                row.pow_exp2 = 15 - x_nibble_prev as usize;
+                            }
+                        }
+                        StepKind::Extension => row.pow_exp1 = a.key_len,
+                        StepKind::Leaf => {
+                            row.pow_exp1 = 64 - depth;
+                            for side in [a, b] {
+                                side.values = side.node_values();
+                                side.values_inv = invert(fingerprint(side.values));
+                            }
+                        }
+                        StepKind::Absent => {
+                            if a.has_leaf {
+                                row.pow_exp1 = 64 - depth;
+                                row.diverge_inv =
+                                    invert(key_rlc - prefix * pow(row.pow_exp1) - a.key);
+                            } else if a.has_ext {
+                                row.depth2 = proof_row.depth2;
+                                row.nibble2 = nibble_at(row.depth2);
+                                row.prefix2 = prefix_at(row.depth2);
+                                row.pow_exp1 = a.key_len;
+                                row.diverge_inv =
+                                    invert(row.prefix2 - prefix * pow(row.pow_exp1) - a.key);
+                            }
+                            for side in [a, b] {
+                                side.values = default_values;
+                            }
+                        }
+                        StepKind::Insert | StepKind::SplitLeaf => {
+                            row.pow_exp1 = 64 - depth;
+                            a.values = default_values;
+                            b.values = b.node_values();
+                            b.values_inv = invert(fingerprint(b.values));
+                        }
+                        StepKind::Split => {
+                            row.depth2 = proof_row.depth2;
+                            row.nibble2 = nibble_at(row.depth2);
+                            row.prefix2 = prefix_at(row.depth2);
+                            row.shared = proof_row.shared;
+                            row.x_nibble = proof_row.nibble;
+                            row.x_rest = r.map(|r| rlc::value(proof_row.rest.iter().rev(), r));
+                            row.is_direct = proof_row.is_direct;
+                            row.pow_exp1 = proof_row.shared;
+                            row.pow_exp2 = a.key_len - proof_row.shared - 1;
+                            row.shared_inv = invert(Value::known(F::from(proof_row.shared as u64)));
+                            row.diverge_inv = invert(Value::known(
+                                F::from(proof_row.nibble as u64) - F::from(row.nibble2 as u64),
+                            ));
+                        }
+                    }
+                    x_nibble_prev = proof_row.nibble;
+                    row.pow1 = pow(row.pow_exp1);
+                    row.pow2 = pow(row.pow_exp2);
+                    row.old = old;
+                    row.new = new;
+                    rows.push(row);
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Assign the proofs of the updates, with the proofs of the updates of
+    /// the state trie and of the storage trie, in `num_rows` rows.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        proofs: &[(&MptProof, Vec<TrieProof>)],
+        num_rows: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let rows = Self::step_rows(proofs, challenges)?;
+        if rows.len() > num_rows {
+            error!(
+                "mpt step rows {} exceed the available rows {}",
+                rows.len(),
+                num_rows
+            );
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "mpt steps",
+            |mut region| {
+                // The enabled rows are surrounded by padding rows, which are
+                // queried at Rotation::prev() and Rotation::next().
+                self.assign_row(&mut region, 0, None, false, false)?;
+                for offset in 0..num_rows {
+                    self.assign_row(&mut region, offset + 1, rows.get(offset), true, offset == 0)?;
+                }
+                self.assign_row(&mut region, num_rows + 1, None, false, false)
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: Option<&StepRow<F>>,
+        q_enable: bool,
+        q_first: bool,
+    ) -> Result<(), Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, q_enable),
+            ("q_first", self.q_first, q_first),
+        ] {
+            region.assign_fixed(
+                || format!("mpt steps {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        let zero = Value::known(F::ZERO);
+        let flag = |value: bool| Value::known(F::from(value as u64));
+        let number = |value: usize| Value::known(F::from(value as u64));
+        let mut values = vec![
+            ("is_update", self.is_update, flag(row.is_some())),
+            (
+                "is_first",
+                self.is_first,
+                flag(row.map_or(false, |row| row.is_first)),
+            ),
+            (
+                "seg_first",
+                self.seg_first,
+                flag(row.map_or(false, |row| row.seg_first)),
+            ),
+            (
+                "is_storage",
+                self.is_storage,
+                flag(row.map_or(false, |row| row.is_storage)),
+            ),
+            (
+                "is_swap",
+                self.is_swap,
+                flag(row.map_or(false, |row| row.is_swap)),
+            ),
+            (
+                "is_non_existing",
+                self.is_non_existing,
+                flag(row.map_or(false, |row| row.update.is_non_existing())),
+            ),
+            (
+                "is_cleared",
+                self.is_cleared,
+                flag(row.map_or(false, |row| row.update.is_cleared())),
+            ),
+        ];
+        for (index, &column) in self.kinds.iter().enumerate() {
+            let value = row.map_or(false, |row| row.kind as usize == index);
+            values.push(("kind", column, flag(value)));
+        }
+        let fields = row.map_or([false; 4], |row| row.update.fields());
+        for (&column, value) in self.fields.iter().zip(fields) {
+            values.push(("field", column, flag(value)));
+        }
+        let update = row.map_or([zero; 7], |row| row.update.row);
+        for (&column, value) in self.update.iter().zip(update) {
+            values.push(("update", column, value));
+        }
+
+        let number_row = |value: fn(&StepRow<F>) -> usize| number(row.map_or(0, value));
+        let value_row = |value: fn(&StepRow<F>) -> Value<F>| row.map_or(zero, value);
+        values.extend([
+            (
+                "old_field",
+                self.old_field,
+                value_row(|row| row.update.old_field),
+            ),
+            (
+                "new_field",
+                self.new_field,
+                value_row(|row| row.update.new_field),
+            ),
+            ("depth", self.depth, number_row(|row| row.depth)),
+            ("nibble", self.nibble, number_row(|row| row.nibble as usize)),
+            ("prefix", self.prefix, value_row(|row| row.prefix)),
+            ("key_rlc", self.key_rlc, value_row(|row| row.key_rlc)),
+            ("depth2", self.depth2, number_row(|row| row.depth2)),
+            (
+                "nibble2",
+                self.nibble2,
+                number_row(|row| row.nibble2 as usize),
+            ),
+            ("prefix2", self.prefix2, value_row(|row| row.prefix2)),
+            ("shared", self.shared, number_row(|row| row.shared)),
+            (
+                "x_nibble",
+                self.x_nibble,
+                number_row(|row| row.x_nibble as usize),
+            ),
+            ("x_rest", self.x_rest, value_row(|row| row.x_rest)),
+            (
+                "is_direct",
+                self.is_direct,
+                flag(row.map_or(false, |row| row.is_direct)),
+            ),
+            ("pow_exp1", self.pow_exp1, number_row(|row| row.pow_exp1)),
+            ("pow1", self.pow1, value_row(|row| row.pow1)),
+            ("pow_exp2", self.pow_exp2, number_row(|row| row.pow_exp2)),
+            ("pow2", self.pow2, value_row(|row| row.pow2)),
+            (
+                "shared_inv",
+                self.shared_inv,
+                value_row(|row| row.shared_inv),
+            ),
+            (
+                "diverge_inv",
+                self.diverge_inv,
+                value_row(|row| row.diverge_inv),
+            ),
+        ]);
+        for (columns, side) in [
+            (self.old, row.map(|row| row.old)),
+            (self.new, row.map(|row| row.new)),
+        ] {
+            let side_flag =
+                |value: fn(&SideValues<F>) -> bool| flag(side.map_or(false, |side| value(&side)));
+            let side_value =
+                |value: fn(&SideValues<F>) -> Value<F>| side.map_or(zero, |side| value(&side));
+            values.extend([
+                ("hash", columns.hash, side_value(|side| side.hash)),
+                (
+                    "has_branch",
+                    columns.has_branch,
+                    side_flag(|side| side.has_branch),
+                ),
+                ("has_ext", columns.has_ext, side_flag(|side| side.has_ext)),
+                (
+                    "has_leaf",
+                    columns.has_leaf,
+                    side_flag(|side| side.has_leaf),
+                ),
+                ("key", columns.key, side_value(|side| side.key)),
+                (
+                    "key_len",
+                    columns.key_len,
+                    number(side.map_or(0, |side| side.key_len)),
+                ),
+                ("payload", columns.payload, side_value(|side| side.payload)),
+                (
+                    "children",
+                    columns.children,
+                    side_value(|side| side.children),
+                ),
+                ("nonce", columns.nonce, side_value(|side| side.nonce)),
+                ("balance", columns.balance, side_value(|side| side.balance)),
+                (
+                    "storage_root",
+                    columns.storage_root,
+                    side_value(|side| side.storage_root),
+                ),
+                (
+                    "code_hash",
+                    columns.code_hash,
+                    side_value(|side| side.code_hash),
+                ),
+                ("child", columns.child, side_value(|side| side.child)),
+                (
+                    "values_inv",
+                    columns.values_inv,
+                    side_value(|side| side.values_inv),
+                ),
+            ]);
+            let side_values = side.map_or([zero; 4], |side| side.values);
+            for (&column, value) in columns.values.iter().zip(side_values) {
+                values.push(("values", column, value));
+            }
+        }
+
+        for (name, column, value) in values {
+            region.assign_advice(|| format!("mpt steps {}", name), column, offset, || value)?;
+        }
+        Ok(())
+    }
+}
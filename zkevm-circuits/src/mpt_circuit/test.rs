@@ -0,0 +1,180 @@
+use super::*;
+use crate::{
+    table::AccountFieldTag,
+    util::{log2_ceil, unusable_rows},
+    witness::Rw,
+};
+use eth_types::{
+    keccak256,
+    state_trie::{StateAccount, StateTrie},
+    Address, Word, H256,
+};
+use halo2_proofs::{
+    dev::{MockProver, VerifyFailure},
+    halo2curves::bn256::Fr,
+};
+
+#[test]
+fn mpt_circuit_unusable_rows() {
+    assert_eq!(
+        MptCircuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, MptCircuit::<Fr>>(()),
+    )
+}
+
+fn account_row(address: u64, field_tag: AccountFieldTag, value_prev: u64, value: u64) -> Rw {
+    Rw::Account {
+        rw_counter: 0,
+        is_write: true,
+        account_address: Address::from_low_u64_be(address),
+        field_tag,
+        value: Word::from(value),
+        value_prev: Word::from(value_prev),
+    }
+}
+
+fn storage_row(address: u64, storage_key: u64, tx_id: usize, value_prev: u64, value: u64) -> Rw {
+    Rw::AccountStorage {
+        rw_counter: 0,
+        is_write: true,
+        account_address: Address::from_low_u64_be(address),
+        storage_key: Word::from(storage_key),
+        value: Word::from(value),
+        value_prev: Word::from(value_prev),
+        tx_id,
+        committed_value: Word::from(value_prev),
+    }
+}
+
+fn clear_row(address: u64, tx_id: usize) -> Rw {
+    Rw::AccountStorageClear {
+        rw_counter: 0,
+        tx_id,
+        account_address: Address::from_low_u64_be(address),
+    }
+}
+
+/// Return the state trie with the accounts from 1 to `num_accounts`, which
+/// have `num_slots` storage slots.
+fn state_trie(num_accounts: u64, num_slots: u64) -> StateTrie {
+    let mut trie = StateTrie::default();
+    for address in 1..=num_accounts {
+        let address = Address::from_low_u64_be(address);
+        trie.set_account(
+            &address,
+            StateAccount {
+                nonce: 1,
+                balance: Word::from(100),
+                code_hash: H256(keccak256(&[])),
+                ..StateAccount::default()
+            },
+        )
+        .unwrap();
+        for key in 1..=num_slots {
+            trie.set_storage(&address, &Word::from(key), Word::from(key))
+                .unwrap();
+        }
+    }
+    trie
+}
+
+fn mpt_circuit(trie: &mut StateTrie, rows: &[Rw]) -> MptCircuit<Fr> {
+    let updates = MptUpdates::from_state(rows, trie).unwrap();
+    let max_mpt_rows = MptCircuit::<Fr>::min_num_rows(&updates).unwrap();
+    MptCircuit::new(updates, max_mpt_rows)
+}
+
+fn test_mpt_circuit(circuit: MptCircuit<Fr>) -> Result<(), Vec<VerifyFailure>> {
+    let k = log2_ceil((circuit.max_mpt_rows + 2).max(256) + MptCircuit::<Fr>::unusable_rows());
+    let prover = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+    prover.verify_par()
+}
+
+#[test]
+fn mpt_circuit_account_updates() {
+    let mut trie = state_trie(40, 0);
+    let rows = [
+        account_row(1, AccountFieldTag::Nonce, 1, 2),
+        account_row(2, AccountFieldTag::Balance, 100, 50),
+        account_row(3, AccountFieldTag::CodeHash, 0, 0x1234),
+        // New accounts
+        account_row(41, AccountFieldTag::Balance, 0, 10),
+        account_row(42, AccountFieldTag::Nonce, 0, 1),
+        // Non existing account
+        account_row(43, AccountFieldTag::CodeHash, 0, 0),
+        // Removed account
+        account_row(41, AccountFieldTag::Balance, 10, 0),
+    ];
+    assert_eq!(test_mpt_circuit(mpt_circuit(&mut trie, &rows)), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_storage_updates() {
+    let mut trie = state_trie(20, 8);
+    let rows = [
+        storage_row(1, 1, 1, 1, 5),
+        // New slots
+        storage_row(2, 9, 1, 0, 9),
+        storage_row(21, 1, 1, 0, 1),
+        // Non existing slots
+        storage_row(3, 10, 1, 0, 0),
+        storage_row(22, 1, 1, 0, 0),
+        // Removed slots
+        storage_row(4, 2, 1, 2, 0),
+        storage_row(21, 1, 2, 1, 0),
+    ];
+    assert_eq!(test_mpt_circuit(mpt_circuit(&mut trie, &rows)), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_storage_clears() {
+    let mut trie = state_trie(20, 8);
+    let rows = [
+        storage_row(1, 1, 1, 1, 5),
+        clear_row(1, 1),
+        // The slots are empty after the clear.
+        storage_row(1, 1, 2, 0, 3),
+        storage_row(1, 2, 2, 0, 0),
+        clear_row(2, 1),
+        // Empty storage
+        clear_row(21, 1),
+        // Removed account, which only had storage
+        storage_row(22, 1, 1, 0, 1),
+        clear_row(22, 1),
+    ];
+    let mut circuit = mpt_circuit(&mut trie, &rows);
+    assert_eq!(
+        trie.storage(&Address::from_low_u64_be(2), &Word::from(1))
+            .unwrap(),
+        Word::zero()
+    );
+    assert_eq!(test_mpt_circuit(circuit.clone()), Ok(()));
+
+    // The clear must reset the storage root.
+    circuit.updates.proofs[1].account = circuit.updates.proofs[0].account.clone();
+    assert!(test_mpt_circuit(circuit).is_err());
+}
+
+#[test]
+fn mpt_circuit_empty_tries() {
+    let mut trie = StateTrie::default();
+    let rows = [
+        account_row(1, AccountFieldTag::CodeHash, 0, 0),
+        storage_row(1, 1, 1, 0, 0),
+        account_row(1, AccountFieldTag::Balance, 0, 10),
+        storage_row(1, 1, 2, 0, 1),
+    ];
+    assert_eq!(test_mpt_circuit(mpt_circuit(&mut trie, &rows)), Ok(()));
+}
+
+#[test]
+fn mpt_circuit_wrong_proof() {
+    let mut trie = state_trie(40, 0);
+    let rows = [
+        account_row(1, AccountFieldTag::Balance, 100, 200),
+        account_row(2, AccountFieldTag::Balance, 100, 200),
+    ];
+    let mut circuit = mpt_circuit(&mut trie, &rows);
+    circuit.updates.proofs[1].account = circuit.updates.proofs[0].account.clone();
+    assert!(test_mpt_circuit(circuit).is_err());
+}
@@ -1,5 +1,9 @@
 //! Public Input Circuit implementation
+mod block_header;
+mod logs_bloom;
+mod ordered_trie;
 mod param;
+mod trie_values;
 
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
@@ -8,20 +12,35 @@ mod test;
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 pub use dev::PiCircuit as TestPiCircuit;
 
+use block_header::BlockHeaderConfig;
+use bus_mapping::circuit_input_builder::keccak_inputs_pi_circuit;
 use eth_types::{
-    geth_types::{BlockConstants, Transaction},
+    evm_types::MAX_BLOBS_PER_TX,
+    geth_types::{
+        BlockConstants, BlockHeader, Receipt, Transaction, Withdrawal, MAX_WITHDRAWALS_PER_BLOCK,
+    },
     sign_types::SignData,
-    Address, BigEndianHash, Field, Keccak, ToBigEndian, ToLittleEndian, ToScalar, Word, H256,
+    trie::ordered_trie_root,
+    Address, BigEndianHash, Bytes, Field, Keccak, ToBigEndian, ToLittleEndian, ToScalar, Word,
+    H256, H64,
 };
-use halo2_proofs::plonk::{Instance, SecondPhase};
+use ethers_core::types::Bloom;
+use halo2_proofs::plonk::{Expression, Instance, SecondPhase};
+use logs_bloom::LogsBloomConfig;
+use ordered_trie::{OrderedTrieConfig, TrieKind};
 use param::*;
 use std::marker::PhantomData;
+use trie_values::TrieValuesConfig;
 
 use crate::{
-    table::{BlockTable, LookupTable, TxFieldTag, TxTable},
+    evm_circuit::util::rlc,
+    table::{
+        BlockContextFieldTag, BlockTable, KeccakTable, LookupTable, RwTable, TxFieldTag, TxTable,
+        UncleTable, WithdrawalTable,
+    },
     tx_circuit::TX_LEN,
     util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    witness::{self, access_list_addresses_rlc, access_list_storage_keys_rlc},
 };
 use gadgets::{
     is_zero::IsZeroChip,
@@ -46,15 +65,143 @@ pub struct BlockValues {
     difficulty: Word,
     base_fee: Word, // NOTE: BaseFee was added by EIP-1559 and is ignored in legacy headers.
     chain_id: u64,
+    parent_hash: H256,
+    uncles_hash: H256,
+    state_root: H256,
+    transactions_root: H256,
+    receipts_root: H256,
+    logs_bloom: Bloom,
+    gas_used: u64,
+    extra_data: Vec<u8>,
+    mix_hash: H256,
+    nonce: u64,
+    excess_blob_gas: u64,
+    blob_base_fee: Word,
     history_hashes: Vec<H256>,
 }
 
+impl BlockValues {
+    /// Returns the (tag, index, value) rows of the block table after its
+    /// all-zero row, with the words encoded as the RLC of their little-endian
+    /// bytes and the byte strings as the RLC of their bytes.
+    fn table_rows<F: Field>(&self, randomness: F) -> Vec<(BlockContextFieldTag, F, F)> {
+        let hash = |hash: H256| rlc(hash.into_uint().to_le_bytes(), randomness);
+        [
+            (
+                BlockContextFieldTag::Coinbase,
+                F::ZERO,
+                self.coinbase.to_scalar().unwrap(),
+            ),
+            (
+                BlockContextFieldTag::GasLimit,
+                F::ZERO,
+                F::from(self.gas_limit),
+            ),
+            (BlockContextFieldTag::Number, F::ZERO, F::from(self.number)),
+            (
+                BlockContextFieldTag::Timestamp,
+                F::ZERO,
+                F::from(self.timestamp),
+            ),
+            (
+                BlockContextFieldTag::Difficulty,
+                F::ZERO,
+                rlc(self.difficulty.to_le_bytes(), randomness),
+            ),
+            (
+                BlockContextFieldTag::BaseFee,
+                F::ZERO,
+                rlc(self.base_fee.to_le_bytes(), randomness),
+            ),
+            (
+                BlockContextFieldTag::ChainId,
+                F::ZERO,
+                F::from(self.chain_id),
+            ),
+            (
+                BlockContextFieldTag::ParentHash,
+                F::ZERO,
+                hash(self.parent_hash),
+            ),
+            (
+                BlockContextFieldTag::UnclesHash,
+                F::ZERO,
+                hash(self.uncles_hash),
+            ),
+            (
+                BlockContextFieldTag::StateRoot,
+                F::ZERO,
+                hash(self.state_root),
+            ),
+            (
+                BlockContextFieldTag::TransactionsRoot,
+                F::ZERO,
+                hash(self.transactions_root),
+            ),
+            (
+                BlockContextFieldTag::ReceiptsRoot,
+                F::ZERO,
+                hash(self.receipts_root),
+            ),
+            (
+                BlockContextFieldTag::LogsBloom,
+                F::ZERO,
+                rlc::value(self.logs_bloom.as_bytes().iter().rev(), randomness),
+            ),
+            (
+                BlockContextFieldTag::GasUsed,
+                F::ZERO,
+                F::from(self.gas_used),
+            ),
+            (
+                BlockContextFieldTag::ExtraData,
+                F::from(self.extra_data.len() as u64),
+                rlc::value(self.extra_data.iter().rev(), randomness),
+            ),
+            (BlockContextFieldTag::MixHash, F::ZERO, hash(self.mix_hash)),
+            (BlockContextFieldTag::Nonce, F::ZERO, F::from(self.nonce)),
+            (
+                BlockContextFieldTag::ExcessBlobGas,
+                F::ZERO,
+                F::from(self.excess_blob_gas),
+            ),
+            (
+                BlockContextFieldTag::BlobBaseFee,
+                F::ZERO,
+                rlc(self.blob_base_fee.to_le_bytes(), randomness),
+            ),
+            (
+                BlockContextFieldTag::PrevRandao,
+                F::ZERO,
+                hash(self.mix_hash),
+            ),
+        ]
+        .into_iter()
+        .chain(
+            // The hashes of the 256 previous blocks, indexed by their number
+            self.history_hashes
+                .iter()
+                .enumerate()
+                .map(|(i, &prev_hash)| {
+                    (
+                        BlockContextFieldTag::BlockHash,
+                        F::from(self.number + i as u64) - F::from(256),
+                        hash(prev_hash),
+                    )
+                }),
+        )
+        .collect()
+    }
+}
+
 /// Values of the tx table (as in the spec)
 #[derive(Default, Debug, Clone)]
 pub struct TxValues {
     nonce: u64,
     gas: u64, // gas limit
     gas_price: Word,
+    max_fee_per_gas: Word,
+    max_priority_fee_per_gas: Word,
     from_addr: Address,
     to_addr: Address,
     is_create: bool,
@@ -62,14 +209,56 @@ pub struct TxValues {
     call_data_len: u64,
     call_data_gas_cost: u64,
     tx_sign_hash: [u8; 32],
+    access_list_addresses_len: u64,
+    access_list_storage_keys_len: u64,
+    access_list_rlp: Vec<u8>,
+    access_list_addresses: Vec<Address>,
+    access_list_storage_keys: Vec<(Address, Word)>,
+    tx_type: u64,
+    chain_id: u64,
+    sig_r: Word,
+    sig_s: Word,
+    tx_hash: H256,
+    max_fee_per_blob_gas: Word,
+    blob_versioned_hashes: Vec<H256>,
+    blob_versioned_hashes_rlp: Vec<u8>,
+}
+
+impl TxValues {
+    /// Returns the versioned hash of the blob at `index`, or zero when the tx
+    /// has no such blob.
+    fn blob_versioned_hash(&self, index: usize) -> Word {
+        self.blob_versioned_hashes
+            .get(index)
+            .map_or(Word::zero(), |hash| hash.into_uint())
+    }
 }
 
 /// Extra values (not contained in block or tx tables)
 #[derive(Default, Debug, Clone)]
 pub struct ExtraValues {
-    // block_hash: H256,
+    block_hash: H256,
     state_root: H256,
     prev_state_root: H256,
+    transactions_root: H256,
+    receipts_root: H256,
+    withdrawals_root: H256,
+}
+
+impl ExtraValues {
+    /// Returns the names and the values of the extra fields, encoded as the
+    /// RLC of the little-endian bytes of the hashes.
+    fn values<F: Field>(&self, randomness: F) -> [(&'static str, F); EXTRA_LEN] {
+        [
+            ("block.hash", self.block_hash),
+            ("state.root", self.state_root),
+            ("parent_block.state_root", self.prev_state_root),
+            ("transactions.root", self.transactions_root),
+            ("receipts.root", self.receipts_root),
+            ("withdrawals.root", self.withdrawals_root),
+        ]
+        .map(|(name, hash)| (name, rlc(hash.into_uint().to_le_bytes(), randomness)))
+    }
 }
 
 /// PublicData contains all the values that the PiCircuit recieves as input
@@ -82,12 +271,28 @@ pub struct PublicData {
     pub history_hashes: Vec<Word>,
     /// Block Transactions
     pub transactions: Vec<eth_types::Transaction>,
+    /// Receipts of the block transactions
+    pub receipts: Vec<Receipt>,
+    /// Withdrawals of the block (EIP-4895)
+    pub withdrawals: Vec<Withdrawal>,
     /// Block State Root
     pub state_root: H256,
     /// Previous block root
     pub prev_state_root: H256,
     /// Constants related to Ethereum block
     pub block_constants: BlockConstants,
+    /// Hash of the parent block
+    pub parent_hash: H256,
+    /// Headers of the uncles of the block
+    pub uncles: Vec<BlockHeader>,
+    /// Gas used by the transactions of the block
+    pub gas_used: u64,
+    /// Extra data of the block
+    pub extra_data: Bytes,
+    /// Mix hash of the block
+    pub mix_hash: H256,
+    /// Nonce of the block
+    pub nonce: H64,
 }
 
 impl Default for PublicData {
@@ -96,9 +301,17 @@ impl Default for PublicData {
             chain_id: Word::default(),
             history_hashes: vec![],
             transactions: vec![],
+            receipts: vec![],
+            withdrawals: vec![],
             state_root: H256::zero(),
             prev_state_root: H256::zero(),
             block_constants: BlockConstants::default(),
+            parent_hash: H256::zero(),
+            uncles: vec![],
+            gas_used: 0,
+            extra_data: Bytes::default(),
+            mix_hash: H256::zero(),
+            nonce: H64::zero(),
         }
     }
 }
@@ -106,6 +319,7 @@ impl Default for PublicData {
 impl PublicData {
     /// Returns struct with values for the block table
     pub fn get_block_table_values(&self) -> BlockValues {
+        let header = self.header();
         let history_hashes = [
             vec![H256::zero(); 256 - self.history_hashes.len()],
             self.history_hashes
@@ -122,6 +336,18 @@ impl PublicData {
             difficulty: self.block_constants.difficulty,
             base_fee: self.block_constants.base_fee,
             chain_id: self.chain_id.as_u64(),
+            parent_hash: header.parent_hash,
+            uncles_hash: header.uncles_hash,
+            state_root: header.state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            logs_bloom: header.logs_bloom,
+            gas_used: header.gas_used,
+            extra_data: header.extra_data.to_vec(),
+            mix_hash: header.mix_hash,
+            nonce: header.nonce.to_low_u64_be(),
+            excess_blob_gas: self.block_constants.excess_blob_gas.as_u64(),
+            blob_base_fee: self.block_constants.blob_base_fee,
             history_hashes,
         }
     }
@@ -139,9 +365,13 @@ impl PublicData {
                 .expect("Error computing tx_sign_hash");
             let mut msg_hash_le = [0u8; 32];
             msg_hash_le.copy_from_slice(sign_data.msg_hash.to_bytes().as_slice());
+            let (access_list_addresses_len, access_list_storage_keys_len) = tx.access_list_len();
+            let (access_list_addresses, access_list_storage_keys) = tx.access_list_entries();
             tx_vals.push(TxValues {
                 nonce: tx.nonce.as_u64(),
                 gas_price: tx.gas_price,
+                max_fee_per_gas: tx.gas_fee_cap,
+                max_priority_fee_per_gas: tx.gas_tip_cap,
                 gas: tx.gas_limit.as_u64(),
                 from_addr: tx.from,
                 to_addr: tx.to_or_zero(),
@@ -150,6 +380,19 @@ impl PublicData {
                 call_data_len: tx.call_data.len() as u64,
                 call_data_gas_cost: tx.call_data_gas_cost(),
                 tx_sign_hash: msg_hash_le,
+                access_list_addresses_len,
+                access_list_storage_keys_len,
+                access_list_rlp: tx.access_list_rlp(),
+                access_list_addresses,
+                access_list_storage_keys,
+                tx_type: tx.transaction_type,
+                chain_id,
+                sig_r: tx.r,
+                sig_s: tx.s,
+                tx_hash: tx.hash(chain_id),
+                max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+                blob_versioned_hashes: tx.blob_versioned_hashes.clone(),
+                blob_versioned_hashes_rlp: tx.blob_versioned_hashes_rlp(),
             });
         }
         tx_vals
@@ -157,16 +400,78 @@ impl PublicData {
 
     /// Returns struct with the extra values
     pub fn get_extra_values(&self) -> ExtraValues {
+        let header = self.header();
         ExtraValues {
-            // block_hash: self.hash.unwrap_or_else(H256::zero),
+            block_hash: header.hash(),
             state_root: self.state_root,
             prev_state_root: self.prev_state_root,
+            transactions_root: header.transactions_root,
+            receipts_root: header.receipts_root,
+            withdrawals_root: Withdrawal::withdrawals_root(&self.withdrawals),
+        }
+    }
+
+    /// Returns the header of the block, with the uncles hash of its uncles,
+    /// the transactions and receipts roots and the logs bloom of its
+    /// transactions and receipts.
+    pub fn header(&self) -> BlockHeader {
+        BlockHeader {
+            parent_hash: self.parent_hash,
+            uncles_hash: BlockHeader::uncles_hash(&self.uncles),
+            coinbase: self.block_constants.coinbase,
+            state_root: self.state_root,
+            transactions_root: ordered_trie_root(&self.tx_rlps()),
+            receipts_root: ordered_trie_root(&self.receipt_rlps()),
+            logs_bloom: Receipt::block_logs_bloom(&self.receipts),
+            difficulty: self.block_constants.difficulty,
+            number: self.block_constants.number.as_u64(),
+            gas_limit: self.block_constants.gas_limit.as_u64(),
+            gas_used: self.gas_used,
+            timestamp: self.block_constants.timestamp.as_u64(),
+            extra_data: self.extra_data.clone(),
+            mix_hash: self.mix_hash,
+            nonce: self.nonce,
+            base_fee: self.block_constants.base_fee,
         }
     }
 
+    /// Returns the inputs of the keccaks of the block header and of the
+    /// transactions, receipts and withdrawals tries, including the encodings
+    /// of the transactions.
+    pub fn keccak_inputs(&self) -> Vec<Vec<u8>> {
+        let chain_id = self.chain_id.as_u64();
+        let txs = self.txs();
+        let mut inputs = self.tx_rlps();
+        inputs.extend(keccak_inputs_pi_circuit(
+            &self.header(),
+            &self.uncles,
+            &txs,
+            &self.receipts,
+            &self.withdrawals,
+            chain_id,
+        ));
+        inputs
+    }
+
     fn txs(&self) -> Vec<Transaction> {
         self.transactions.iter().map(Transaction::from).collect()
     }
+
+    fn tx_rlps(&self) -> Vec<Vec<u8>> {
+        let chain_id = self.chain_id.as_u64();
+        self.txs()
+            .iter()
+            .map(|tx| tx.rlp_signed(chain_id))
+            .collect()
+    }
+
+    fn receipt_rlps(&self) -> Vec<Vec<u8>> {
+        self.receipts.iter().map(Receipt::rlp).collect()
+    }
+
+    fn withdrawal_rlps(&self) -> Vec<Vec<u8>> {
+        self.withdrawals.iter().map(Withdrawal::rlp).collect()
+    }
 }
 
 /// Config for PiCircuit
@@ -178,9 +483,13 @@ pub struct PiCircuitConfig<F: Field> {
     max_calldata: usize,
 
     q_block_table: Selector,
+    block_tag: Column<Fixed>,
+    q_history: Selector,
+    q_history_last: Selector,
     q_tx_table: Selector,
     q_tx_calldata: Selector,
     q_calldata_start: Selector,
+    q_tx_hash: Selector,
 
     tx_id_inv: Column<Advice>,
     tx_value_inv: Column<Advice>,
@@ -195,16 +504,26 @@ pub struct PiCircuitConfig<F: Field> {
     q_not_end: Selector,
     q_end: Selector,
 
-    pi: Column<Instance>, // rpi_rand, rpi_rlc, chain_ID, state_root, prev_state_root
+    // rpi_rand, rpi_rlc, chain_ID, block_hash, state_root, prev_state_root,
+    // transactions_root, receipts_root
+    pi: Column<Instance>,
+
+    ordered_trie: OrderedTrieConfig<F>,
+    block_header: BlockHeaderConfig<F>,
+    logs_bloom: LogsBloomConfig<F>,
+    trie_values: TrieValuesConfig<F>,
 
     _marker: PhantomData<F>,
     // External tables
     block_table: BlockTable,
     tx_table: TxTable,
+    rw_table: RwTable,
+    withdrawal_table: WithdrawalTable,
+    uncle_table: UncleTable,
 }
 
 /// Circuit configuration arguments
-pub struct PiCircuitConfigArgs {
+pub struct PiCircuitConfigArgs<F: Field> {
     /// Max number of supported transactions
     pub max_txs: usize,
     /// Max number of supported calldata bytes
@@ -213,10 +532,20 @@ pub struct PiCircuitConfigArgs {
     pub tx_table: TxTable,
     /// BlockTable
     pub block_table: BlockTable,
+    /// KeccakTable
+    pub keccak_table: KeccakTable,
+    /// RwTable
+    pub rw_table: RwTable,
+    /// WithdrawalTable
+    pub withdrawal_table: WithdrawalTable,
+    /// UncleTable
+    pub uncle_table: UncleTable,
+    /// Challenges
+    pub challenges: Challenges<Expression<F>>,
 }
 
 impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
-    type ConfigArgs = PiCircuitConfigArgs;
+    type ConfigArgs = PiCircuitConfigArgs<F>;
 
     /// Return a new PiCircuitConfig
     fn new(
@@ -226,13 +555,22 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             max_calldata,
             block_table,
             tx_table,
+            keccak_table,
+            rw_table,
+            withdrawal_table,
+            uncle_table,
+            challenges,
         }: Self::ConfigArgs,
     ) -> Self {
-        let q_block_table = meta.selector();
+        let q_block_table = meta.complex_selector();
+        let block_tag = meta.fixed_column();
+        let q_history = meta.selector();
+        let q_history_last = meta.selector();
 
         let q_tx_table = meta.complex_selector();
         let q_tx_calldata = meta.complex_selector();
         let q_calldata_start = meta.complex_selector();
+        let q_tx_hash = meta.complex_selector();
         // Tx Table
         let tx_id = tx_table.tx_id;
         let tx_value = tx_table.value;
@@ -309,6 +647,35 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             vec![q_block_table * (block_value - rpi_block_value)]
         });
 
+        meta.create_gate("block_table.tag[i] = block_tag[i]", |meta| {
+            // Only the block hashes and the extra data have an index.
+            let q_block_table = meta.query_selector(q_block_table);
+            let block_tag = meta.query_fixed(block_tag, Rotation::cur());
+            let tag = meta.query_advice(block_table.tag, Rotation::cur());
+            let index = meta.query_advice(block_table.index, Rotation::cur());
+            vec![
+                q_block_table.clone() * (tag - block_tag.clone()),
+                q_block_table
+                    * (block_tag.clone() - BlockContextFieldTag::BlockHash.expr())
+                    * (block_tag - BlockContextFieldTag::ExtraData.expr())
+                    * index,
+            ]
+        });
+
+        meta.create_gate("block_table.index[i] = number - 256 + i", |meta| {
+            // The history hashes are the last rows of the block table, and
+            // the number is in its row 3.
+            let q_history = meta.query_selector(q_history);
+            let q_history_last = meta.query_selector(q_history_last);
+            let index = meta.query_advice(block_table.index, Rotation::cur());
+            let index_next = meta.query_advice(block_table.index, Rotation::next());
+            let number = meta.query_advice(block_table.value, Rotation(3 - BLOCK_LEN as i32));
+            vec![
+                q_history * (index_next - index.clone() - 1.expr()),
+                q_history_last * (index + 1.expr() - number),
+            ]
+        });
+
         let offset = BLOCK_LEN + 1 + EXTRA_LEN;
         let tx_table_len = max_txs * TX_LEN + 1;
 
@@ -535,14 +902,74 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             ]
         });
 
+        // Transactions, receipts and withdrawals roots
+        let ordered_trie = OrderedTrieConfig::new(meta, &tx_table, &keccak_table, &challenges);
+
+        // Block hash and uncles hash
+        let block_header = BlockHeaderConfig::new(
+            meta,
+            q_block_table,
+            &block_table,
+            &keccak_table,
+            &uncle_table,
+            &challenges,
+        );
+
+        // Values of the receipts and withdrawals tries
+        let trie_values = TrieValuesConfig::new(
+            meta,
+            &ordered_trie,
+            &tx_table,
+            &rw_table,
+            &withdrawal_table,
+            &challenges,
+        );
+
+        // Logs bloom
+        let logs_bloom = LogsBloomConfig::new(meta, &keccak_table, &trie_values, &challenges);
+
+        // Every transaction is a leaf of the transactions trie, and has a leaf in the
+        // receipts trie at the same index.
+        meta.lookup_any("tx hash in transactions trie", |meta| {
+            let condition =
+                meta.query_selector(q_tx_hash) * not::expr(tx_value_is_zero_config.expr());
+            let input = [
+                TrieKind::Transactions.expr(),
+                meta.query_advice(tx_id, Rotation::cur()) - 1.expr(),
+                meta.query_advice(tx_value, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(ordered_trie.leaf_exprs(meta))
+                .map(|(input, table)| (condition.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("tx receipt in receipts trie", |meta| {
+            let condition =
+                meta.query_selector(q_tx_hash) * not::expr(tx_value_is_zero_config.expr());
+            let input = [
+                TrieKind::Receipts.expr(),
+                meta.query_advice(tx_id, Rotation::cur()) - 1.expr(),
+            ];
+            input
+                .into_iter()
+                .zip(ordered_trie.leaf_exprs(meta))
+                .map(|(input, table)| (condition.clone() * input, table))
+                .collect()
+        });
+
         Self {
             max_txs,
             max_calldata,
             q_block_table,
+            block_tag,
+            q_history,
+            q_history_last,
             block_table,
             q_tx_table,
             q_tx_calldata,
             q_calldata_start,
+            q_tx_hash,
             tx_table,
             tx_id_inv,
             tx_value_inv,
@@ -556,7 +983,14 @@ impl<F: Field> SubCircuitConfig<F> for PiCircuitConfig<F> {
             q_not_end,
             q_end,
             pi,
+            ordered_trie,
+            block_header,
+            logs_bloom,
+            trie_values,
             _marker: PhantomData,
+            rw_table,
+            withdrawal_table,
+            uncle_table,
         }
     }
 }
@@ -633,6 +1067,9 @@ impl<F: Field> PiCircuitConfig<F> {
         tx_value: F,
         raw_pi_vals: &mut [F],
     ) -> Result<(), Error> {
+        if tag == TxFieldTag::TxHash {
+            self.q_tx_hash.enable(region, offset)?;
+        }
         let tx_id = F::from(tx_id as u64);
         // tx_id_inv = (tag - CallDataLength)^(-1)
         let tx_id_inv = if tag != TxFieldTag::CallDataLength {
@@ -818,180 +1255,82 @@ impl<F: Field> PiCircuitConfig<F> {
         Ok(())
     }
 
-    /// Assigns the values for block table in the block_table column
+    /// Assigns the values for block table in the block_table columns
     /// and in the raw_public_inputs column. A copy is also stored in
-    /// a vector for computing RLC(raw_public_inputs)
+    /// a vector for computing RLC(raw_public_inputs).  Returns the
+    /// raw_public_inputs cells of the rows after the all-zero row, with
+    /// their tags.
     fn assign_block_table(
         &self,
         region: &mut Region<'_, F>,
         block_values: BlockValues,
         randomness: F,
         raw_pi_vals: &mut [F],
-    ) -> Result<AssignedCell<F, F>, Error> {
-        let mut offset = 0;
-        for i in 0..BLOCK_LEN + 1 {
-            self.q_block_table.enable(region, offset + i)?;
-        }
-
+    ) -> Result<Vec<(BlockContextFieldTag, AssignedCell<F, F>)>, Error> {
         // zero row
-        region.assign_advice(
-            || "zero",
-            self.block_table.value,
-            offset,
-            || Value::known(F::ZERO),
-        )?;
+        self.q_block_table.enable(region, 0)?;
+        region.assign_fixed(|| "zero", self.block_tag, 0, || Value::known(F::ZERO))?;
+        for column in <BlockTable as LookupTable<F>>::advice_columns(&self.block_table) {
+            region.assign_advice(|| "zero", column, 0, || Value::known(F::ZERO))?;
+        }
         region.assign_advice(
             || "zero",
             self.raw_public_inputs,
-            offset,
+            0,
             || Value::known(F::ZERO),
         )?;
-        raw_pi_vals[offset] = F::ZERO;
-        offset += 1;
-
-        // coinbase
-        let coinbase = block_values.coinbase.to_scalar().unwrap();
-        region.assign_advice(
-            || "coinbase",
-            self.block_table.value,
-            offset,
-            || Value::known(coinbase),
-        )?;
-        region.assign_advice(
-            || "coinbase",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(coinbase),
-        )?;
-        raw_pi_vals[offset] = coinbase;
-        offset += 1;
-
-        // gas_limit
-        let gas_limit = F::from(block_values.gas_limit);
-        region.assign_advice(
-            || "gas_limit",
-            self.block_table.value,
-            offset,
-            || Value::known(gas_limit),
-        )?;
-        region.assign_advice(
-            || "gas_limit",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(gas_limit),
-        )?;
-        raw_pi_vals[offset] = gas_limit;
-        offset += 1;
-
-        // number
-        let number = F::from(block_values.number);
-        region.assign_advice(
-            || "number",
-            self.block_table.value,
-            offset,
-            || Value::known(number),
-        )?;
-        region.assign_advice(
-            || "number",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(number),
-        )?;
-        raw_pi_vals[offset] = number;
-        offset += 1;
-
-        // timestamp
-        let timestamp = F::from(block_values.timestamp);
-        region.assign_advice(
-            || "timestamp",
-            self.block_table.value,
-            offset,
-            || Value::known(timestamp),
-        )?;
-        region.assign_advice(
-            || "timestamp",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(timestamp),
-        )?;
-        raw_pi_vals[offset] = timestamp;
-        offset += 1;
-
-        // difficulty
-        let difficulty = rlc(block_values.difficulty.to_le_bytes(), randomness);
-        region.assign_advice(
-            || "difficulty",
-            self.block_table.value,
-            offset,
-            || Value::known(difficulty),
-        )?;
-        region.assign_advice(
-            || "difficulty",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(difficulty),
-        )?;
-        raw_pi_vals[offset] = difficulty;
-        offset += 1;
-
-        // base_fee
-        let base_fee = rlc(block_values.base_fee.to_le_bytes(), randomness);
-        region.assign_advice(
-            || "base_fee",
-            self.block_table.value,
-            offset,
-            || Value::known(base_fee),
-        )?;
-        region.assign_advice(
-            || "base_fee",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(base_fee),
-        )?;
-        raw_pi_vals[offset] = base_fee;
-        offset += 1;
-
-        // chain_id
-        let chain_id = F::from(block_values.chain_id);
-        region.assign_advice(
-            || "chain_id",
-            self.block_table.value,
-            offset,
-            || Value::known(chain_id),
-        )?;
-        let chain_id_cell = region.assign_advice(
-            || "chain_id",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(chain_id),
-        )?;
-        raw_pi_vals[offset] = chain_id;
-        offset += 1;
-
-        for prev_hash in block_values.history_hashes {
-            let prev_hash = rlc(prev_hash.to_fixed_bytes(), randomness);
-            region.assign_advice(
-                || "prev_hash",
-                self.block_table.value,
+        raw_pi_vals[0] = F::ZERO;
+
+        let rows = block_values.table_rows(randomness);
+        assert_eq!(rows.len(), BLOCK_LEN);
+        let mut cells = Vec::with_capacity(BLOCK_LEN);
+        for (offset, (tag, index, value)) in (1..).zip(rows) {
+            self.q_block_table.enable(region, offset)?;
+            if tag == BlockContextFieldTag::BlockHash {
+                if offset == BLOCK_LEN {
+                    self.q_history_last.enable(region, offset)?;
+                } else {
+                    self.q_history.enable(region, offset)?;
+                }
+            }
+            let tag_value = F::from(tag as u64);
+            region.assign_fixed(
+                || "block_tag",
+                self.block_tag,
                 offset,
-                || Value::known(prev_hash),
+                || Value::known(tag_value),
             )?;
-            region.assign_advice(
-                || "prev_hash",
+            for (name, column, value) in [
+                ("tag", self.block_table.tag, tag_value),
+                ("index", self.block_table.index, index),
+                ("value", self.block_table.value, value),
+            ] {
+                region.assign_advice(
+                    || format!("block_table.{}", name),
+                    column,
+                    offset,
+                    || Value::known(value),
+                )?;
+            }
+            let cell = region.assign_advice(
+                || "raw_pi.block_value",
                 self.raw_public_inputs,
                 offset,
-                || Value::known(prev_hash),
+                || Value::known(value),
             )?;
-            raw_pi_vals[offset] = prev_hash;
-            offset += 1;
+            raw_pi_vals[offset] = value;
+            cells.push((tag, cell));
         }
 
-        Ok(chain_id_cell)
+        Ok(cells)
     }
 
     /// Assigns the extra fields (not in block or tx tables):
+    ///   - block hash
     ///   - state root
     ///   - previous block state root
+    ///   - transactions root
+    ///   - receipts root
     /// to the raw_public_inputs column and stores a copy in a
     /// vector for computing RLC(raw_public_inputs).
     fn assign_extra_fields(
@@ -1000,40 +1339,18 @@ impl<F: Field> PiCircuitConfig<F> {
         extra: ExtraValues,
         randomness: F,
         raw_pi_vals: &mut [F],
-    ) -> Result<[AssignedCell<F, F>; 2], Error> {
-        let mut offset = BLOCK_LEN + 1;
-        // block hash
-        // let block_hash = rlc(extra.block_hash.to_fixed_bytes(), randomness);
-        // region.assign_advice(
-        //     || "block.hash",
-        //     self.raw_public_inputs,
-        //     offset,
-        //     || Ok(block_hash),
-        // )?;
-        // raw_pi_vals[offset] = block_hash;
-        // offset += 1;
-
-        // block state root
-        let state_root = rlc(extra.state_root.to_fixed_bytes(), randomness);
-        let state_root_cell = region.assign_advice(
-            || "state.root",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(state_root),
-        )?;
-        raw_pi_vals[offset] = state_root;
-        offset += 1;
-
-        // previous block state root
-        let prev_state_root = rlc(extra.prev_state_root.to_fixed_bytes(), randomness);
-        let prev_state_root_cell = region.assign_advice(
-            || "parent_block.hash",
-            self.raw_public_inputs,
-            offset,
-            || Value::known(prev_state_root),
-        )?;
-        raw_pi_vals[offset] = prev_state_root;
-        Ok([state_root_cell, prev_state_root_cell])
+    ) -> Result<[AssignedCell<F, F>; EXTRA_LEN], Error> {
+        let mut cells = Vec::with_capacity(EXTRA_LEN);
+        for ((name, value), offset) in extra.values(randomness).into_iter().zip(BLOCK_LEN + 1..) {
+            cells.push(region.assign_advice(
+                || name,
+                self.raw_public_inputs,
+                offset,
+                || Value::known(value),
+            )?);
+            raw_pi_vals[offset] = value;
+        }
+        Ok(cells.try_into().unwrap())
     }
 
     /// Assign `rpi_rlc_acc` and `rand_rpi` columns
@@ -1149,6 +1466,8 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             chain_id: block.context.chain_id,
             history_hashes: block.context.history_hashes.clone(),
             transactions: block.eth_block.transactions.clone(),
+            receipts: block.receipts.clone(),
+            withdrawals: block.withdrawals.clone(),
             state_root: block.eth_block.state_root,
             prev_state_root: H256::from_uint(&block.prev_state_root),
             block_constants: BlockConstants {
@@ -1158,7 +1477,15 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 difficulty: block.context.difficulty,
                 gas_limit: block.context.gas_limit.into(),
                 base_fee: block.context.base_fee,
+                excess_blob_gas: block.context.excess_blob_gas.into(),
+                blob_base_fee: block.context.blob_base_fee,
             },
+            parent_hash: block.eth_block.parent_hash,
+            uncles: block.uncles.clone(),
+            gas_used: block.eth_block.gas_used.low_u64(),
+            extra_data: block.eth_block.extra_data.clone(),
+            mix_hash: block.eth_block.mix_hash.unwrap_or_default(),
+            nonce: block.eth_block.nonce.unwrap_or_default(),
         };
         let rand_rpi = gen_rand_rpi::<F>(
             block.circuits_params.max_txs,
@@ -1180,13 +1507,49 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
         let row_num = |tx_num, calldata_len| {
             BLOCK_LEN + 1 + EXTRA_LEN + 3 * (TX_LEN * tx_num + 1) + calldata_len
         };
+        // The nodes of the tries are decoded in a single region after a
+        // padding row, and followed by another padding row, which takes more
+        // rows than the region of the paths of the nodes.
+        let trie_row_num = |tx_num, calldata_len| {
+            [
+                TrieKind::Transactions,
+                TrieKind::Receipts,
+                TrieKind::Withdrawals,
+            ]
+            .map(|kind| OrderedTrieConfig::<F>::num_rows(kind, tx_num, calldata_len))
+            .iter()
+            .sum::<usize>()
+                + 2
+        };
+        // The encodings of the receipts and withdrawals are assigned in their
+        // own region, between padding rows.
+        let values_row_num = |tx_num| {
+            [TrieKind::Receipts, TrieKind::Withdrawals]
+                .map(|kind| TrieValuesConfig::<F>::num_rows(kind, tx_num))
+                .iter()
+                .sum::<usize>()
+                + 2
+        };
+        // The header and the uncles are assigned in their own region, between
+        // padding rows.
+        let header_row_num = BlockHeaderConfig::<F>::num_rows() + 2;
+        // The blooms of the block and of the receipts, which also hold the
+        // bloom inputs, are assigned in their own region.
+        let bloom_row_num = LogsBloomConfig::<F>::num_rows;
         let calldata_len = block.txs.iter().map(|tx| tx.call_data.len()).sum();
+        let max_txs = block.circuits_params.max_txs;
+        let max_calldata = block.circuits_params.max_calldata;
         (
-            row_num(block.txs.len(), calldata_len),
-            row_num(
-                block.circuits_params.max_txs,
-                block.circuits_params.max_calldata,
-            ),
+            row_num(block.txs.len(), calldata_len)
+                .max(trie_row_num(block.txs.len(), calldata_len))
+                .max(values_row_num(block.txs.len()))
+                .max(header_row_num)
+                .max(bloom_row_num(block.txs.len())),
+            row_num(max_txs, max_calldata)
+                .max(trie_row_num(max_txs, max_calldata))
+                .max(values_row_num(max_txs))
+                .max(header_row_num)
+                .max(bloom_row_num(max_txs)),
         )
     }
 
@@ -1209,24 +1572,20 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
             .rev()
             .fold(F::ZERO, |acc, val| acc * self.rand_rpi + val);
 
-        // let block_hash = public_data
-        //     .eth_block
-        //     .hash
-        //     .unwrap_or_else(H256::zero)
-        //     .to_fixed_bytes();
-        let public_inputs = vec![
+        let extra = self.public_data.get_extra_values();
+        let public_inputs = [
             self.rand_rpi,
             rlc_rpi,
             F::from(self.public_data.chain_id.as_u64()),
-            rlc(
-                self.public_data.state_root.to_fixed_bytes(),
-                self.randomness,
-            ),
-            rlc(
-                self.public_data.prev_state_root.to_fixed_bytes(),
-                self.randomness,
-            ),
-        ];
+        ]
+        .into_iter()
+        .chain(
+            extra
+                .values(self.randomness)
+                .into_iter()
+                .map(|(_, value)| value),
+        )
+        .collect();
 
         vec![public_inputs]
     }
@@ -1235,9 +1594,45 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
     fn synthesize_sub(
         &self,
         config: &Self::Config,
-        _challenges: &Challenges<Value<F>>,
+        challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
     ) -> Result<(), Error> {
+        config
+            .ordered_trie
+            .load_aux_tables(layouter, self.max_txs.max(MAX_WITHDRAWALS_PER_BLOCK))?;
+        config.block_header.load_aux_tables(layouter)?;
+        config.logs_bloom.load_aux_tables(layouter)?;
+        config.trie_values.load_aux_tables(layouter)?;
+        let header_hash = config.block_header.assign(
+            layouter,
+            &self.public_data.header(),
+            &self.public_data.uncles,
+            challenges,
+        )?;
+        let roots = config.ordered_trie.assign(
+            layouter,
+            &[
+                (TrieKind::Transactions, self.public_data.tx_rlps()),
+                (TrieKind::Receipts, self.public_data.receipt_rlps()),
+                (TrieKind::Withdrawals, self.public_data.withdrawal_rlps()),
+            ],
+            self.max_txs,
+            self.max_calldata,
+            challenges,
+        )?;
+        config.trie_values.assign(
+            layouter,
+            &self.public_data.receipts,
+            &self.public_data.withdrawals,
+            self.max_txs,
+            challenges,
+        )?;
+        let logs_bloom = config.logs_bloom.assign(
+            layouter,
+            &self.public_data.receipts,
+            self.max_txs,
+            challenges,
+        )?;
         layouter.assign_region(
             || "fixed u16 table",
             |mut region| {
@@ -1280,21 +1675,62 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
 
                 // Assign block table
                 let block_values = self.public_data.get_block_table_values();
-                let chain_id = config.assign_block_table(
+                let block_cells = config.assign_block_table(
                     &mut region,
                     block_values,
                     self.randomness,
                     &mut raw_pi_vals,
                 )?;
+                let block_cell = |tag| {
+                    block_cells
+                        .iter()
+                        .find(|(cell_tag, _)| *cell_tag == tag)
+                        .map(|(_, cell)| cell.clone())
+                        .expect("the block table has all the fields")
+                };
+                let chain_id = block_cell(BlockContextFieldTag::ChainId);
 
                 // Assign extra fields
                 let extra_vals = self.public_data.get_extra_values();
-                let [state_root, prev_state_root] = config.assign_extra_fields(
+                let extra_cells = config.assign_extra_fields(
                     &mut region,
                     extra_vals,
                     self.randomness,
                     &mut raw_pi_vals,
                 )?;
+                let [block_hash, state_root, _, transactions_root, receipts_root, withdrawals_root] =
+                    &extra_cells;
+                // The roots are the hashes of the roots of the tries, and the
+                // block hash is the hash of the header with the fields of the
+                // block table.
+                region.constrain_equal(roots[0].cell(), transactions_root.cell())?;
+                region.constrain_equal(roots[1].cell(), receipts_root.cell())?;
+                region.constrain_equal(roots[2].cell(), withdrawals_root.cell())?;
+                region.constrain_equal(header_hash.cell(), block_hash.cell())?;
+                for (tag, cell) in [
+                    (BlockContextFieldTag::StateRoot, state_root),
+                    (BlockContextFieldTag::TransactionsRoot, transactions_root),
+                    (BlockContextFieldTag::ReceiptsRoot, receipts_root),
+                ] {
+                    region.constrain_equal(block_cell(tag).cell(), cell.cell())?;
+                }
+                // The logs bloom is the union of the blooms of the logs of the
+                // receipts.
+                region.constrain_equal(
+                    block_cell(BlockContextFieldTag::LogsBloom).cell(),
+                    logs_bloom.cell(),
+                )?;
+                // The prevRandao is the mix hash of the header (EIP-4399).
+                region.constrain_equal(
+                    block_cell(BlockContextFieldTag::PrevRandao).cell(),
+                    block_cell(BlockContextFieldTag::MixHash).cell(),
+                )?;
+                // The parent hash is the last of the history hashes.
+                let (_, last_history_hash) = block_cells.last().expect("BLOCK_LEN > 0");
+                region.constrain_equal(
+                    block_cell(BlockContextFieldTag::ParentHash).cell(),
+                    last_history_hash.cell(),
+                )?;
 
                 let mut offset = 0;
                 // Assign Tx table
@@ -1317,7 +1753,15 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 for i in 0..config.max_txs {
                     let tx = if i < txs.len() { &txs[i] } else { &tx_default };
 
-                    for (tag, value) in &[
+                    let blob_versioned_hashes = (0..MAX_BLOBS_PER_TX).map(|index| {
+                        (
+                            TxFieldTag::BlobVersionedHash,
+                            index,
+                            rlc(tx.blob_versioned_hash(index).to_le_bytes(), self.randomness),
+                        )
+                    });
+
+                    for (tag, index, value) in [
                         (TxFieldTag::Nonce, F::from(tx.nonce)),
                         (TxFieldTag::Gas, F::from(tx.gas)),
                         (
@@ -1343,14 +1787,76 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                             TxFieldTag::TxSignHash,
                             rlc(tx.tx_sign_hash, self.randomness),
                         ),
-                    ] {
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            F::from(tx.access_list_addresses_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            F::from(tx.access_list_storage_keys_len),
+                        ),
+                        (
+                            TxFieldTag::AccessListRLC,
+                            rlc::value(tx.access_list_rlp.iter().rev(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesRLC,
+                            access_list_addresses_rlc(&tx.access_list_addresses, self.randomness),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysRLC,
+                            access_list_storage_keys_rlc(
+                                &tx.access_list_storage_keys,
+                                self.randomness,
+                                self.randomness,
+                            ),
+                        ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            rlc(tx.max_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), self.randomness),
+                        ),
+                        (TxFieldTag::TxType, F::from(tx.tx_type)),
+                        (TxFieldTag::ChainId, F::from(tx.chain_id)),
+                        (
+                            TxFieldTag::SigR,
+                            rlc(tx.sig_r.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::SigS,
+                            rlc(tx.sig_s.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::TxHash,
+                            rlc(tx.tx_hash.into_uint().to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::MaxFeePerBlobGas,
+                            rlc(tx.max_fee_per_blob_gas.to_le_bytes(), self.randomness),
+                        ),
+                        (
+                            TxFieldTag::BlobVersionedHashesLen,
+                            F::from(tx.blob_versioned_hashes.len() as u64),
+                        ),
+                        (
+                            TxFieldTag::BlobVersionedHashesRLC,
+                            rlc::value(tx.blob_versioned_hashes_rlp.iter().rev(), self.randomness),
+                        ),
+                    ]
+                    .into_iter()
+                    .map(|(tag, value)| (tag, 0, value))
+                    .chain(blob_versioned_hashes)
+                    {
                         config.assign_tx_row(
                             &mut region,
                             offset,
                             i + 1,
-                            *tag,
-                            0,
-                            *value,
+                            tag,
+                            index,
+                            value,
                             &mut raw_pi_vals,
                         )?;
                         offset += 1;
@@ -1425,13 +1931,10 @@ impl<F: Field> SubCircuit<F> for PiCircuit<F> {
                 let (rpi_rand, rpi_rlc) =
                     config.assign_rlc_pi(&mut region, self.rand_rpi, raw_pi_vals)?;
 
-                Ok(vec![
-                    rpi_rand,
-                    rpi_rlc,
-                    chain_id,
-                    state_root,
-                    prev_state_root,
-                ])
+                Ok([rpi_rand, rpi_rlc, chain_id]
+                    .into_iter()
+                    .chain(extra_cells.clone())
+                    .collect::<Vec<_>>())
             },
         )?;
 
@@ -1463,38 +1966,15 @@ fn raw_public_inputs_col<F: Field>(
     // zero row
     result[offset] = F::ZERO;
     offset += 1;
-    // coinbase
-    result[offset] = block.coinbase.to_scalar().unwrap();
-    offset += 1;
-    // gas_limit
-    result[offset] = F::from(block.gas_limit);
-    offset += 1;
-    // number
-    result[offset] = F::from(block.number);
-    offset += 1;
-    // timestamp
-    result[offset] = F::from(block.timestamp);
-    offset += 1;
-    // difficulty
-    result[offset] = rlc(block.difficulty.to_le_bytes(), randomness);
-    offset += 1;
-    // base_fee
-    result[offset] = rlc(block.base_fee.to_le_bytes(), randomness);
-    offset += 1;
-    // chain_id
-    result[offset] = F::from(block.chain_id);
-    offset += 1;
-    // Previous block hashes
-    for prev_hash in block.history_hashes {
-        result[offset] = rlc(prev_hash.to_fixed_bytes(), randomness);
+    for (_, _, value) in block.table_rows(randomness) {
+        result[offset] = value;
         offset += 1;
     }
 
     // Insert Extra Values
-    // block Root
-    result[BLOCK_LEN + 1] = rlc(extra.state_root.to_fixed_bytes(), randomness);
-    // parent block hash
-    result[BLOCK_LEN + 2] = rlc(extra.prev_state_root.to_fixed_bytes(), randomness);
+    for ((_, value), offset) in extra.values(randomness).into_iter().zip(BLOCK_LEN + 1..) {
+        result[offset] = value;
+    }
 
     // Insert Tx table
     offset = 0;
@@ -1517,7 +1997,7 @@ fn raw_public_inputs_col<F: Field>(
     for i in 0..max_txs {
         let tx = if i < txs.len() { &txs[i] } else { &tx_default };
 
-        for val in &[
+        for (index, val) in [
             F::from(tx.nonce),
             F::from(tx.gas),
             rlc(tx.gas_price.to_le_bytes(), randomness),
@@ -1528,10 +2008,33 @@ fn raw_public_inputs_col<F: Field>(
             F::from(tx.call_data_len),
             F::from(tx.call_data_gas_cost),
             rlc(tx.tx_sign_hash, randomness),
-        ] {
+            F::from(tx.access_list_addresses_len),
+            F::from(tx.access_list_storage_keys_len),
+            rlc::value(tx.access_list_rlp.iter().rev(), randomness),
+            access_list_addresses_rlc(&tx.access_list_addresses, randomness),
+            access_list_storage_keys_rlc(&tx.access_list_storage_keys, randomness, randomness),
+            rlc(tx.max_fee_per_gas.to_le_bytes(), randomness),
+            rlc(tx.max_priority_fee_per_gas.to_le_bytes(), randomness),
+            F::from(tx.tx_type),
+            F::from(tx.chain_id),
+            rlc(tx.sig_r.to_le_bytes(), randomness),
+            rlc(tx.sig_s.to_le_bytes(), randomness),
+            rlc(tx.tx_hash.into_uint().to_le_bytes(), randomness),
+            rlc(tx.max_fee_per_blob_gas.to_le_bytes(), randomness),
+            F::from(tx.blob_versioned_hashes.len() as u64),
+            rlc::value(tx.blob_versioned_hashes_rlp.iter().rev(), randomness),
+        ]
+        .into_iter()
+        .map(|val| (0, val))
+        .chain((0..MAX_BLOBS_PER_TX).map(|index| {
+            (
+                index,
+                rlc(tx.blob_versioned_hash(index).to_le_bytes(), randomness),
+            )
+        })) {
             result[id_offset + offset] = F::from((i + 1) as u64);
-            result[index_offset + offset] = F::ZERO;
-            result[value_offset + offset] = *val;
+            result[index_offset + offset] = F::from(index as u64);
+            result[value_offset + offset] = val;
 
             offset += 1;
         }
@@ -0,0 +1,1200 @@
+//! Circuit to compute the block hash from the fields of the block table.
+//!
+//! The RLP encoding of the block header is laid out one byte per row, split
+//! into items of consecutive bytes for each field.  The RLP headers of the
+//! items are verified against their lengths, the values of the items are
+//! looked up in the rows of the `BlockTable` assigned by the PI circuit, and
+//! the keccak of the whole encoding is looked up in the `KeccakTable` and
+//! exposed as the block hash.
+//!
+//! The headers of the uncles are decoded in the same way in a second section,
+//! as the items of the RLP list whose keccak is the uncles hash of the block.
+//! Their numbers and coinbases are the only rows of the `UncleTable` besides
+//! the all-zero row and the padding rows, which have a zero number and
+//! coinbase.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    impl_expr,
+    table::{BlockContextFieldTag, BlockTable, KeccakTable, LookupTable, UncleTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{
+    geth_types::{BlockHeader, MAX_UNCLES},
+    Address, Field, ToLittleEndian, ToScalar, Word, H256,
+};
+use ethers_core::utils::rlp;
+use gadgets::{
+    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    rlp_item::{RlpItemChip, RlpItemConfig, RlpItemFlags, RlpItemRow},
+    util::{not, sum},
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector,
+        TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::{iter, marker::PhantomData};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Upper bound of the length of the encoding of a block header: 3 bytes for
+/// the list header, 33 bytes for each of the 5 hashes, the mix hash, the
+/// difficulty, the extra data and the base fee, 21 bytes for the coinbase, 259
+/// bytes for the logs bloom and 9 bytes for each of the 4 integer fields and
+/// the nonce.
+pub(crate) const MAX_BLOCK_HEADER_RLP_LEN: usize = 625;
+
+/// Upper bound of the length of the encoding of the headers of the uncles: 3
+/// bytes for the list header and the encodings of `MAX_UNCLES` headers.
+pub(crate) const MAX_UNCLES_RLP_LEN: usize = 3 + MAX_UNCLES * MAX_BLOCK_HEADER_RLP_LEN;
+
+const MAX_DEGREE: usize = 9;
+
+/// Tag of the items of the encoding of a block header, in their order
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum RlpHeaderTag {
+    /// Rows after the encoding of the header
+    Padding = 0,
+    /// Header of the RLP list of the fields of the header
+    ListHeader,
+    /// Parent hash
+    ParentHash,
+    /// Uncles hash
+    UnclesHash,
+    /// Coinbase
+    Coinbase,
+    /// State root
+    StateRoot,
+    /// Transactions root
+    TransactionsRoot,
+    /// Receipts root
+    ReceiptsRoot,
+    /// Logs bloom
+    LogsBloom,
+    /// Difficulty
+    Difficulty,
+    /// Number
+    Number,
+    /// Gas limit
+    GasLimit,
+    /// Gas used
+    GasUsed,
+    /// Timestamp
+    Timestamp,
+    /// Extra data
+    ExtraData,
+    /// Mix hash
+    MixHash,
+    /// Nonce
+    Nonce,
+    /// Base fee (EIP-1559)
+    BaseFee,
+    /// Header of the RLP list of the headers of the uncles, which are followed
+    /// by the items of each header from its list header to its base fee
+    UnclesList,
+}
+
+impl From<RlpHeaderTag> for usize {
+    fn from(tag: RlpHeaderTag) -> Self {
+        tag as usize
+    }
+}
+
+impl Default for RlpHeaderTag {
+    fn default() -> Self {
+        Self::Padding
+    }
+}
+
+impl_expr!(RlpHeaderTag);
+
+impl RlpHeaderTag {
+    /// Field of the `BlockTable` with the value of the item
+    fn field_tag(&self) -> Option<BlockContextFieldTag> {
+        match self {
+            Self::ParentHash => Some(BlockContextFieldTag::ParentHash),
+            Self::UnclesHash => Some(BlockContextFieldTag::UnclesHash),
+            Self::Coinbase => Some(BlockContextFieldTag::Coinbase),
+            Self::StateRoot => Some(BlockContextFieldTag::StateRoot),
+            Self::TransactionsRoot => Some(BlockContextFieldTag::TransactionsRoot),
+            Self::ReceiptsRoot => Some(BlockContextFieldTag::ReceiptsRoot),
+            Self::LogsBloom => Some(BlockContextFieldTag::LogsBloom),
+            Self::Difficulty => Some(BlockContextFieldTag::Difficulty),
+            Self::Number => Some(BlockContextFieldTag::Number),
+            Self::GasLimit => Some(BlockContextFieldTag::GasLimit),
+            Self::GasUsed => Some(BlockContextFieldTag::GasUsed),
+            Self::Timestamp => Some(BlockContextFieldTag::Timestamp),
+            Self::ExtraData => Some(BlockContextFieldTag::ExtraData),
+            Self::MixHash => Some(BlockContextFieldTag::MixHash),
+            Self::Nonce => Some(BlockContextFieldTag::Nonce),
+            Self::BaseFee => Some(BlockContextFieldTag::BaseFee),
+            Self::Padding | Self::ListHeader | Self::UnclesList => None,
+        }
+    }
+
+    /// Whether the item is a 32 bytes hash
+    fn is_hash(&self) -> bool {
+        matches!(
+            self,
+            Self::ParentHash
+                | Self::UnclesHash
+                | Self::StateRoot
+                | Self::TransactionsRoot
+                | Self::ReceiptsRoot
+                | Self::MixHash
+        )
+    }
+
+    /// Whether the item is a word, whose value is the RLC of its bytes
+    fn is_word(&self) -> bool {
+        self.is_hash() || matches!(self, Self::Difficulty | Self::BaseFee)
+    }
+
+    /// Whether the item is a scalar, which is encoded without leading zeros
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Difficulty
+                | Self::Number
+                | Self::GasLimit
+                | Self::GasUsed
+                | Self::Timestamp
+                | Self::BaseFee
+        )
+    }
+
+    /// Whether the item is a byte string, whose value is the RLC of its bytes
+    fn is_bytes(&self) -> bool {
+        matches!(self, Self::LogsBloom | Self::ExtraData)
+    }
+
+    /// Maximum length of the content of the item
+    fn max_len(&self) -> u64 {
+        match self {
+            Self::Padding | Self::ListHeader | Self::UnclesList => 0,
+            Self::Coinbase => 20,
+            Self::LogsBloom => 256,
+            Self::Number | Self::GasLimit | Self::GasUsed | Self::Timestamp | Self::Nonce => 8,
+            _ => 32,
+        }
+    }
+
+    /// Whether the content of the item always has its maximum length
+    fn is_fixed_len(&self) -> bool {
+        self.is_hash() || matches!(self, Self::Coinbase | Self::LogsBloom | Self::Nonce)
+    }
+}
+
+/// Columns with values that only depend on the tag, which are constrained from
+/// it so that they can be used in low degree expressions and lookups.
+#[derive(Clone, Copy, Debug)]
+struct TagFlags {
+    field_tag: Column<Advice>,
+    has_field: Column<Advice>,
+    is_word: Column<Advice>,
+    is_numeric: Column<Advice>,
+    is_bytes: Column<Advice>,
+    is_list: Column<Advice>,
+    is_extra_data: Column<Advice>,
+    max_len: Column<Advice>,
+    is_fixed_len: Column<Advice>,
+    is_header_end: Column<Advice>,
+    is_uncles_list: Column<Advice>,
+}
+
+impl TagFlags {
+    fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            field_tag: meta.advice_column(),
+            has_field: meta.advice_column(),
+            is_word: meta.advice_column(),
+            is_numeric: meta.advice_column(),
+            is_bytes: meta.advice_column(),
+            is_list: meta.advice_column(),
+            is_extra_data: meta.advice_column(),
+            max_len: meta.advice_column(),
+            is_fixed_len: meta.advice_column(),
+            is_header_end: meta.advice_column(),
+            is_uncles_list: meta.advice_column(),
+        }
+    }
+
+    /// The columns with the function that gives their value for a tag
+    #[allow(clippy::type_complexity)]
+    fn columns(&self) -> [(&'static str, Column<Advice>, fn(RlpHeaderTag) -> u64); 11] {
+        [
+            ("field_tag", self.field_tag, |tag: RlpHeaderTag| {
+                tag.field_tag().map_or(0, |field_tag| field_tag as u64)
+            }),
+            ("has_field", self.has_field, |tag: RlpHeaderTag| {
+                tag.field_tag().is_some() as u64
+            }),
+            ("is_word", self.is_word, |tag: RlpHeaderTag| {
+                tag.is_word() as u64
+            }),
+            ("is_numeric", self.is_numeric, |tag: RlpHeaderTag| {
+                tag.is_numeric() as u64
+            }),
+            ("is_bytes", self.is_bytes, |tag: RlpHeaderTag| {
+                tag.is_bytes() as u64
+            }),
+            ("is_list", self.is_list, |tag: RlpHeaderTag| {
+                matches!(tag, RlpHeaderTag::ListHeader | RlpHeaderTag::UnclesList) as u64
+            }),
+            ("is_extra_data", self.is_extra_data, |tag: RlpHeaderTag| {
+                (tag == RlpHeaderTag::ExtraData) as u64
+            }),
+            ("max_len", self.max_len, |tag: RlpHeaderTag| tag.max_len()),
+            ("is_fixed_len", self.is_fixed_len, |tag: RlpHeaderTag| {
+                tag.is_fixed_len() as u64
+            }),
+            ("is_header_end", self.is_header_end, |tag: RlpHeaderTag| {
+                (tag == RlpHeaderTag::BaseFee) as u64
+            }),
+            (
+                "is_uncles_list",
+                self.is_uncles_list,
+                |tag: RlpHeaderTag| (tag == RlpHeaderTag::UnclesList) as u64,
+            ),
+        ]
+    }
+}
+
+/// Id, number and coinbase of the uncle whose header has a row, which are zero
+/// in the rows of the block header and in the list header of the uncles.
+#[derive(Clone, Copy, Debug, Default)]
+struct UncleValues {
+    id: usize,
+    number: u64,
+    miner: Address,
+}
+
+/// Item of an encoding, with the length of the payload of the list headers
+#[derive(Clone, Debug)]
+struct HeaderItem {
+    tag: RlpHeaderTag,
+    bytes: Vec<u8>,
+    payload_len: Option<usize>,
+    uncle: UncleValues,
+}
+
+/// Witness of a row of the encoding of the block header or of the uncles
+#[derive(Clone, Debug)]
+struct BlockHeaderRow<F> {
+    tag: RlpHeaderTag,
+    byte: u8,
+    item: RlpItemRow<F>,
+    bytes_rlc: Value<F>,
+    bytes_len: usize,
+    list_end: usize,
+    header_end: usize,
+    is_last: bool,
+    is_padding: bool,
+    hash: Value<F>,
+    uncle: UncleValues,
+}
+
+impl<F: Field> BlockHeaderRow<F> {
+    fn padding(uncle_id: usize) -> Self {
+        Self {
+            tag: RlpHeaderTag::Padding,
+            byte: 0,
+            item: RlpItemRow::padding(),
+            bytes_rlc: Value::known(F::ZERO),
+            bytes_len: 0,
+            list_end: 0,
+            header_end: 0,
+            is_last: false,
+            is_padding: true,
+            hash: Value::known(F::ZERO),
+            uncle: UncleValues {
+                id: uncle_id,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+/// Config to verify the RLP encoding and the hash of the block header, and of
+/// the headers of the uncles in the section enabled by `q_uncles`
+#[derive(Clone, Debug)]
+pub(crate) struct BlockHeaderConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_last: Column<Fixed>,
+    q_uncles: Column<Fixed>,
+    tag: BinaryNumberConfig<RlpHeaderTag, 5>,
+    flags: TagFlags,
+    byte: Column<Advice>,
+    // Decoding of the RLP items of the header
+    item: RlpItemConfig,
+    bytes_rlc: Column<Advice>,
+    bytes_len: Column<Advice>,
+    list_end: Column<Advice>,
+    header_end: Column<Advice>,
+    is_last: Column<Advice>,
+    is_padding: Column<Advice>,
+    hash: Column<Advice>,
+    // Uncle whose header has the row
+    uncle_id: Column<Advice>,
+    uncle_number: Column<Advice>,
+    uncle_miner: Column<Advice>,
+    u8_table: TableColumn,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> BlockHeaderConfig<F> {
+    /// Return a new BlockHeaderConfig, whose fields are looked up in the rows
+    /// of the `BlockTable` enabled by `q_block_table`, and whose uncles are
+    /// the rows of the `UncleTable`.
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        q_block_table: Selector,
+        block_table: &BlockTable,
+        keccak_table: &KeccakTable,
+        uncle_table: &UncleTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_last = meta.fixed_column();
+        let q_uncles = meta.fixed_column();
+        let tag = BinaryNumberChip::configure(meta, q_enable, None);
+        let flags = TagFlags::configure(meta);
+        let byte = meta.advice_column();
+        let bytes_rlc = meta.advice_column_in(SecondPhase);
+        let bytes_len = meta.advice_column();
+        let list_end = meta.advice_column();
+        let header_end = meta.advice_column();
+        let is_last = meta.advice_column();
+        let is_padding = meta.advice_column();
+        let hash = meta.advice_column_in(SecondPhase);
+        let [uncle_id, uncle_number, uncle_miner] = [(); 3].map(|_| meta.advice_column());
+        let u8_table = meta.lookup_table_column();
+
+        meta.enable_equality(hash);
+
+        let item = RlpItemChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            byte,
+            |meta| {
+                // Words are accumulated as the RLC of their little-endian
+                // bytes, byte strings as their RLC and other values as
+                // big-endian integers.
+                let [is_word, is_bytes, is_list] = [flags.is_word, flags.is_bytes, flags.is_list]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+                RlpItemFlags {
+                    is_list: is_list.clone(),
+                    is_header_only: is_list,
+                    is_raw: 0.expr(),
+                    multiplier: is_word.clone() * challenges.evm_word()
+                        + is_bytes.clone() * challenges.keccak_input()
+                        + (1.expr() - is_word - is_bytes) * 256.expr(),
+                }
+            },
+            2,
+            u8_table,
+        );
+        let RlpItemConfig {
+            is_header,
+            length,
+            is_item_first,
+            is_item_last,
+            value_acc,
+            ..
+        } = item;
+        let is_content_first = |meta: &mut VirtualCells<'_, F>| item.is_content_first(meta);
+
+        meta.create_gate("block header padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [q_first, q_last] =
+                [q_first, q_last].map(|column| meta.query_fixed(column, Rotation::cur()));
+            let is_padding_next = meta.query_advice(is_padding, Rotation::next());
+            let is_padding = meta.query_advice(is_padding, Rotation::cur());
+            cb.require_boolean("is_padding is boolean", is_padding.clone());
+            let disabled = [is_last, is_item_last, is_header]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let tag = tag.value(Rotation::cur())(meta);
+            cb.condition(is_padding.clone(), |cb| {
+                cb.require_zero("tag is Padding in padding rows", tag);
+                for value in disabled {
+                    cb.require_zero("padding rows are not part of the encoding", value);
+                }
+            });
+            cb.condition(is_padding.clone() * not::expr(q_last.clone()), |cb| {
+                cb.require_equal(
+                    "padding is followed by padding in a section",
+                    is_padding_next,
+                    1.expr(),
+                );
+            });
+            cb.condition(q_first, |cb| {
+                cb.require_zero(
+                    "the encoding starts at the first row of its section",
+                    is_padding.clone(),
+                );
+            });
+            cb.condition(q_last, |cb| {
+                cb.require_equal(
+                    "the encoding ends before the last row of its section",
+                    is_padding,
+                    1.expr(),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("block header tag", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            for (name, column, value) in flags.columns() {
+                let expected = sum::expr(
+                    RlpHeaderTag::iter()
+                        .filter(|&t| value(t) != 0)
+                        .map(|t| tag.value_equals(t, Rotation::cur())(meta) * value(t).expr()),
+                );
+                cb.require_equal(name, meta.query_advice(column, Rotation::cur()), expected);
+            }
+
+            let [q_first, q_uncles] =
+                [q_first, q_uncles].map(|column| meta.query_fixed(column, Rotation::cur()));
+            let [is_item_last, is_last, is_header_end, is_uncles_list] = [
+                is_item_last,
+                is_last,
+                flags.is_header_end,
+                flags.is_uncles_list,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let tag_next = tag.value(Rotation::next())(meta);
+            let tag = tag.value(Rotation::cur())(meta);
+            cb.require_zero(
+                "the block header starts with its list header, and the uncles with theirs",
+                q_first
+                    * (tag.clone()
+                        - RlpHeaderTag::ListHeader.expr()
+                        - q_uncles
+                            * (RlpHeaderTag::UnclesList.expr() - RlpHeaderTag::ListHeader.expr())),
+            );
+            // The base fee ends a header, and the list header of the uncles is
+            // followed by their headers, so both are followed by a list header.
+            cb.require_zero(
+                "the items follow the order of the fields",
+                is_item_last
+                    * not::expr(is_last.clone())
+                    * (tag_next - tag.clone() - 1.expr()
+                        + (is_header_end.clone() + is_uncles_list.clone())
+                            * (tag + 1.expr() - RlpHeaderTag::ListHeader.expr())),
+            );
+            cb.require_zero(
+                "the encoding ends with the base fee of a header or without uncles",
+                is_last * not::expr(is_header_end + is_uncles_list),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("block header row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let is_item_last_prev = meta.query_advice(is_item_last, Rotation::prev());
+            let is_padding_next = meta.query_advice(is_padding, Rotation::next());
+            let [byte_next, bytes_rlc_next, bytes_len_next] = [byte, bytes_rlc, bytes_len]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let header_values = [list_end, hash].map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let header_end_next = meta.query_advice(header_end, Rotation::next());
+            let [byte, bytes_rlc, bytes_len, list_end, header_end] =
+                [byte, bytes_rlc, bytes_len, list_end, header_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_first, is_item_last, is_last] = [is_item_first, is_item_last, is_last]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_header_end, is_uncles_list] = [flags.is_header_end, flags.is_uncles_list]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_uncle_end = is_item_last.clone() * (is_header_end + is_uncles_list);
+
+            cb.require_boolean("is_last is boolean", is_last.clone());
+            cb.require_equal(
+                "an item starts with the header or after the end of the previous item",
+                is_item_first,
+                q_first.clone() + not::expr(q_first.clone()) * is_item_last_prev,
+            );
+
+            cb.condition(q_first, |cb| {
+                cb.require_equal("bytes_len starts at 1", bytes_len.clone(), 1.expr());
+                cb.require_equal("bytes_rlc starts at the byte", bytes_rlc.clone(), byte);
+            });
+            cb.condition(is_last.clone(), |cb| {
+                cb.require_equal(
+                    "the encoding ends at the end of an item",
+                    is_item_last,
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the encoding ends at the end of the list",
+                    bytes_len.clone(),
+                    list_end,
+                );
+                cb.require_equal(
+                    "padding follows the encoding",
+                    is_padding_next.clone(),
+                    1.expr(),
+                );
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                cb.require_zero("the encoding continues in the next row", is_padding_next);
+                for (value, value_next) in header_values {
+                    cb.require_equal(
+                        "the values of the encoding are the same ones in all its rows",
+                        value_next,
+                        value,
+                    );
+                }
+                cb.condition(not::expr(is_uncle_end), |cb| {
+                    cb.require_equal(
+                        "header_end is the same one in all the rows of a header",
+                        header_end_next,
+                        header_end,
+                    );
+                });
+                cb.require_equal(
+                    "bytes_len increases by 1",
+                    bytes_len_next,
+                    bytes_len + 1.expr(),
+                );
+                cb.require_equal(
+                    "bytes_rlc accumulates the bytes",
+                    bytes_rlc_next,
+                    bytes_rlc * challenges.keccak_input() + byte_next,
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("block header item", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let tag_next = tag.value(Rotation::next())(meta);
+            let tag = tag.value(Rotation::cur())(meta);
+            let q_uncles = meta.query_fixed(q_uncles, Rotation::cur());
+            let [is_item_last, length, bytes_len, list_end, header_end] =
+                [is_item_last, length, bytes_len, list_end, header_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_list, max_len, is_fixed_len, is_header_end, is_uncles_list] = [
+                flags.is_list,
+                flags.max_len,
+                flags.is_fixed_len,
+                flags.is_header_end,
+                flags.is_uncles_list,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.condition(not::expr(is_item_last.clone()), |cb| {
+                cb.require_equal("the tag is the same one in the whole item", tag_next, tag);
+            });
+            // The list of the encoding is the one of the block header, or the
+            // one of the headers of the uncles.
+            cb.condition(
+                is_item_last.clone()
+                    * (is_list.clone() * not::expr(q_uncles) + is_uncles_list.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "list_end is the end of the list",
+                        list_end,
+                        bytes_len.clone() + length.clone(),
+                    );
+                },
+            );
+            cb.condition(is_item_last.clone() * (is_list - is_uncles_list), |cb| {
+                cb.require_equal(
+                    "header_end is the end of the list of the fields of the header",
+                    header_end.clone(),
+                    bytes_len.clone() + length.clone(),
+                );
+            });
+            cb.condition(is_item_last.clone() * is_header_end, |cb| {
+                cb.require_equal(
+                    "the base fee ends the list of the fields of the header",
+                    bytes_len,
+                    header_end,
+                );
+            });
+            cb.require_zero(
+                "hashes, the coinbase, the logs bloom and the nonce have a fixed length",
+                is_item_last * is_fixed_len * (length - max_len),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("block header uncles", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [q_first, q_last] =
+                [q_first, q_last].map(|column| meta.query_fixed(column, Rotation::cur()));
+            let [uncle_id_next, uncle_number_next, uncle_miner_next] =
+                [uncle_id, uncle_number, uncle_miner]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let [uncle_id, uncle_number, uncle_miner] = [uncle_id, uncle_number, uncle_miner]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_last, is_padding, value_acc] = [is_item_last, is_padding, value_acc]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_header_end, is_uncles_list] = [flags.is_header_end, flags.is_uncles_list]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_uncle_end = is_item_last.clone() * (is_header_end.clone() + is_uncles_list);
+
+            cb.condition(q_first, |cb| {
+                for value in [uncle_id.clone(), uncle_number.clone(), uncle_miner.clone()] {
+                    cb.require_zero("the list header of the uncles has no uncle", value);
+                }
+            });
+            cb.condition(is_padding.clone(), |cb| {
+                for value in [uncle_number.clone(), uncle_miner.clone()] {
+                    cb.require_zero("padding rows have no uncle", value);
+                }
+            });
+            // The padding rows after the uncles have the next ids, which are
+            // the ones of the padding rows of the `UncleTable`.
+            cb.condition(not::expr(q_last), |cb| {
+                cb.require_equal(
+                    "uncle_id increases after each uncle and in padding rows",
+                    uncle_id_next,
+                    uncle_id.clone() + is_uncle_end.clone() + is_padding.clone(),
+                );
+                cb.condition(not::expr(is_uncle_end + is_padding), |cb| {
+                    for (value, value_next) in [
+                        (uncle_number.clone(), uncle_number_next),
+                        (uncle_miner.clone(), uncle_miner_next),
+                    ] {
+                        cb.require_equal(
+                            "the values of the uncle are the same ones in all its rows",
+                            value_next,
+                            value,
+                        );
+                    }
+                });
+            });
+            cb.require_zero(
+                "a block has at most MAX_UNCLES uncles",
+                is_item_last.clone()
+                    * is_header_end
+                    * (1..=MAX_UNCLES)
+                        .map(|id| uncle_id.clone() - id.expr())
+                        .fold(1.expr(), |acc, factor| acc * factor),
+            );
+            for (field, value) in [
+                (RlpHeaderTag::Number, uncle_number),
+                (RlpHeaderTag::Coinbase, uncle_miner),
+            ] {
+                cb.condition(
+                    is_item_last.clone() * tag.value_equals(field, Rotation::cur())(meta),
+                    |cb| {
+                        cb.require_equal(
+                            "the number and the miner of the uncle are in its header",
+                            value_acc.clone(),
+                            value,
+                        );
+                    },
+                );
+            }
+
+            cb.gate(meta.query_fixed(q_uncles, Rotation::cur()))
+        });
+
+        meta.lookup("block header byte range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(byte, Rotation::cur()),
+                u8_table,
+            )]
+        });
+        meta.lookup("block header numeric leading byte", |meta| {
+            // Scalars are encoded without leading zeros.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_numeric, Rotation::cur())
+                * is_content_first(meta);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - 1.expr()), u8_table)]
+        });
+        meta.lookup("block header string length range", |meta| {
+            // The content of the strings is not longer than their field.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * not::expr(meta.query_advice(flags.is_list, Rotation::cur()));
+            let max_len = meta.query_advice(flags.max_len, Rotation::cur());
+            let length = meta.query_advice(length, Rotation::cur());
+            vec![(enable * (max_len - length), u8_table)]
+        });
+
+        meta.lookup_any("block header field", |meta| {
+            // The extra data is the only field whose index is its length, and
+            // the fields of the uncles are not the ones of the block.
+            let q_block_table = meta.query_selector(q_block_table);
+            let enable = (meta.query_fixed(q_enable, Rotation::cur())
+                - meta.query_fixed(q_uncles, Rotation::cur()))
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * meta.query_advice(flags.has_field, Rotation::cur());
+            let input = [
+                meta.query_advice(flags.field_tag, Rotation::cur()),
+                meta.query_advice(flags.is_extra_data, Rotation::cur())
+                    * meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(value_acc, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(block_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, q_block_table.clone() * table))
+                .collect()
+        });
+        meta.lookup_any("block header uncles hash", |meta| {
+            let q_block_table = meta.query_selector(q_block_table);
+            let enable = meta.query_fixed(q_uncles, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            let input = [
+                BlockContextFieldTag::UnclesHash.expr(),
+                0.expr(),
+                meta.query_advice(hash, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(block_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, q_block_table.clone() * table))
+                .collect()
+        });
+        meta.lookup_any("block header keccak", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(bytes_rlc, Rotation::cur()),
+                meta.query_advice(bytes_len, Rotation::cur()),
+                meta.query_advice(hash, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("uncle table row in uncles", |meta| {
+            // Every row of the `UncleTable` is an uncle of the block, or a
+            // padding row after the uncles, or the all-zero row, which are the
+            // values of the disabled rows.
+            let enable = meta.query_fixed(q_uncles, Rotation::cur())
+                * (meta.query_advice(is_item_last, Rotation::cur())
+                    * meta.query_advice(flags.is_header_end, Rotation::cur())
+                    + meta.query_advice(is_padding, Rotation::cur()));
+            let table = [uncle_id, uncle_number, uncle_miner]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            uncle_table
+                .table_exprs(meta)
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (input, enable.clone() * table))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_last,
+            q_uncles,
+            tag,
+            flags,
+            byte,
+            item,
+            bytes_rlc,
+            bytes_len,
+            list_end,
+            header_end,
+            is_last,
+            is_padding,
+            hash,
+            uncle_id,
+            uncle_number,
+            uncle_miner,
+            u8_table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Load the u8 table.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "block header u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Return the number of rows of the sections of the encodings of the
+    /// header and of the uncles, which are both followed by at least one
+    /// padding row.
+    pub(crate) fn num_rows() -> usize {
+        MAX_BLOCK_HEADER_RLP_LEN + 1 + MAX_UNCLES_RLP_LEN + 1
+    }
+
+    /// Return the header of an RLP list with a payload of `len` bytes.
+    fn list_header(len: usize) -> Vec<u8> {
+        if len < 56 {
+            return vec![0xc0 + len as u8];
+        }
+        let len_be: Vec<u8> = len
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .skip_while(|&byte| byte == 0)
+            .collect();
+        iter::once(0xf7 + len_be.len() as u8)
+            .chain(len_be)
+            .collect()
+    }
+
+    /// Return the items of the encoding of the header.
+    fn header_items(header: &BlockHeader) -> Vec<HeaderItem> {
+        let mut items: Vec<_> = RlpHeaderTag::iter()
+            .filter(|&tag| !matches!(tag, RlpHeaderTag::Padding | RlpHeaderTag::UnclesList))
+            .map(|tag| {
+                let bytes = match tag {
+                    // Set below from the length of the list
+                    RlpHeaderTag::ListHeader => vec![],
+                    RlpHeaderTag::ParentHash => rlp::encode(&header.parent_hash).to_vec(),
+                    RlpHeaderTag::UnclesHash => rlp::encode(&header.uncles_hash).to_vec(),
+                    RlpHeaderTag::Coinbase => rlp::encode(&header.coinbase).to_vec(),
+                    RlpHeaderTag::StateRoot => rlp::encode(&header.state_root).to_vec(),
+                    RlpHeaderTag::TransactionsRoot => {
+                        rlp::encode(&header.transactions_root).to_vec()
+                    }
+                    RlpHeaderTag::ReceiptsRoot => rlp::encode(&header.receipts_root).to_vec(),
+                    RlpHeaderTag::LogsBloom => {
+                        rlp::encode(&header.logs_bloom.as_bytes().to_vec()).to_vec()
+                    }
+                    RlpHeaderTag::Difficulty => rlp::encode(&header.difficulty).to_vec(),
+                    RlpHeaderTag::Number => rlp::encode(&header.number).to_vec(),
+                    RlpHeaderTag::GasLimit => rlp::encode(&header.gas_limit).to_vec(),
+                    RlpHeaderTag::GasUsed => rlp::encode(&header.gas_used).to_vec(),
+                    RlpHeaderTag::Timestamp => rlp::encode(&header.timestamp).to_vec(),
+                    RlpHeaderTag::ExtraData => rlp::encode(&header.extra_data.to_vec()).to_vec(),
+                    RlpHeaderTag::MixHash => rlp::encode(&header.mix_hash).to_vec(),
+                    RlpHeaderTag::Nonce => rlp::encode(&header.nonce).to_vec(),
+                    RlpHeaderTag::BaseFee => rlp::encode(&header.base_fee).to_vec(),
+                    RlpHeaderTag::Padding | RlpHeaderTag::UnclesList => {
+                        unreachable!("only the fields are part of the header")
+                    }
+                };
+                HeaderItem {
+                    tag,
+                    bytes,
+                    payload_len: None,
+                    uncle: UncleValues::default(),
+                }
+            })
+            .collect();
+
+        let list_len: usize = items.iter().map(|item| item.bytes.len()).sum();
+        items[0].bytes = Self::list_header(list_len);
+        items[0].payload_len = Some(list_len);
+        items
+    }
+
+    /// Return the items of the encoding of the uncles: the list header and
+    /// the items of their headers.
+    fn uncles_items(uncles: &[BlockHeader]) -> Vec<HeaderItem> {
+        let headers: Vec<_> = uncles
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, uncle)| {
+                let values = UncleValues {
+                    id: idx + 1,
+                    number: uncle.number,
+                    miner: uncle.coinbase,
+                };
+                Self::header_items(uncle)
+                    .into_iter()
+                    .map(move |item| HeaderItem {
+                        uncle: values,
+                        ..item
+                    })
+            })
+            .collect();
+
+        let list_len: usize = headers.iter().map(|item| item.bytes.len()).sum();
+        iter::once(HeaderItem {
+            tag: RlpHeaderTag::UnclesList,
+            bytes: Self::list_header(list_len),
+            payload_len: Some(list_len),
+            uncle: UncleValues::default(),
+        })
+        .chain(headers)
+        .collect()
+    }
+
+    /// Return the rows of an encoding from its items and its hash.
+    fn encoding_rows(
+        items: Vec<HeaderItem>,
+        hash: H256,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<BlockHeaderRow<F>> {
+        let list_end: usize = items.iter().map(|item| item.bytes.len()).sum();
+        let hash = challenges.evm_word().map(|challenge| {
+            rlc::value(
+                &Word::from_big_endian(hash.as_bytes()).to_le_bytes(),
+                challenge,
+            )
+        });
+
+        let mut rows: Vec<BlockHeaderRow<F>> = Vec::with_capacity(list_end);
+        let mut bytes_rlc = Value::known(F::ZERO);
+        let mut header_end = 0;
+        for HeaderItem {
+            tag,
+            bytes,
+            payload_len,
+            uncle,
+        } in items
+        {
+            let multiplier = if tag.is_word() {
+                challenges.evm_word()
+            } else if tag.is_bytes() {
+                challenges.keccak_input()
+            } else {
+                Value::known(F::from(256))
+            };
+            if tag == RlpHeaderTag::ListHeader {
+                header_end = rows.len() + bytes.len() + payload_len.unwrap_or_default();
+            }
+            let item_rows = RlpItemRow::item_rows(&bytes, payload_len, false, multiplier);
+            for (&byte, item) in bytes.iter().zip(item_rows) {
+                let byte_value = Value::known(F::from(byte as u64));
+                bytes_rlc = if rows.is_empty() {
+                    byte_value
+                } else {
+                    bytes_rlc * challenges.keccak_input() + byte_value
+                };
+                rows.push(BlockHeaderRow {
+                    tag,
+                    byte,
+                    item,
+                    bytes_rlc,
+                    bytes_len: rows.len() + 1,
+                    list_end,
+                    header_end,
+                    is_last: false,
+                    is_padding: false,
+                    hash,
+                    uncle,
+                });
+            }
+        }
+        if let Some(row) = rows.last_mut() {
+            row.is_last = true;
+        }
+        rows
+    }
+
+    /// Return the rows of the encoding of the header.
+    fn header_rows(
+        header: &BlockHeader,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<BlockHeaderRow<F>> {
+        let rows = Self::encoding_rows(Self::header_items(header), header.hash(), challenges);
+        debug_assert_eq!(
+            rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+            header.rlp()
+        );
+        rows
+    }
+
+    /// Return the rows of the encoding of the headers of the uncles.
+    fn uncles_rows(
+        uncles: &[BlockHeader],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<BlockHeaderRow<F>> {
+        let rows = Self::encoding_rows(
+            Self::uncles_items(uncles),
+            BlockHeader::uncles_hash(uncles),
+            challenges,
+        );
+        debug_assert_eq!(
+            rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+            BlockHeader::uncles_rlp(uncles)
+        );
+        rows
+    }
+
+    /// Assign the encodings of the header and of its uncles, and return the
+    /// cell of the hash of the header.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        header: &BlockHeader,
+        uncles: &[BlockHeader],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let sections = [
+            (
+                Self::header_rows(header, challenges),
+                MAX_BLOCK_HEADER_RLP_LEN,
+            ),
+            (Self::uncles_rows(uncles, challenges), MAX_UNCLES_RLP_LEN),
+        ];
+        for (rows, max_len) in sections.iter() {
+            if rows.len() > *max_len {
+                error!(
+                    "block header rows {} exceed the available rows {}",
+                    rows.len(),
+                    max_len
+                );
+                return Err(Error::Synthesis);
+            }
+        }
+
+        layouter.assign_region(
+            || "block header",
+            |mut region| {
+                // The sections are surrounded by padding rows, which are
+                // queried at Rotation::prev() and Rotation::next().
+                self.assign_row(&mut region, 0, &BlockHeaderRow::padding(0), [false; 4])?;
+                let mut offset = 0;
+                let mut hash_cell = None;
+                for (section, (rows, max_len)) in sections.iter().enumerate() {
+                    let is_uncles = section == 1;
+                    let num_rows = max_len + 1;
+                    // The padding rows after the uncles have the next ids.
+                    let padding_id = rows.last().map_or(0, |row| row.uncle.id) + 1;
+                    let padding = (padding_id..).map(BlockHeaderRow::padding);
+                    for (idx, row) in rows
+                        .iter()
+                        .cloned()
+                        .chain(padding)
+                        .take(num_rows)
+                        .enumerate()
+                    {
+                        offset += 1;
+                        let cell = self.assign_row(
+                            &mut region,
+                            offset,
+                            &row,
+                            [true, idx == 0, idx == num_rows - 1, is_uncles],
+                        )?;
+                        if !is_uncles && idx == 0 {
+                            hash_cell = Some(cell);
+                        }
+                    }
+                }
+                self.assign_row(
+                    &mut region,
+                    offset + 1,
+                    &BlockHeaderRow::padding(0),
+                    [false; 4],
+                )?;
+                Ok(hash_cell.expect("the header has a first row"))
+            },
+        )
+    }
+
+    /// Assign the row with the values of the fixed columns q_enable, q_first,
+    /// q_last and q_uncles, and return the cell of the hash.
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &BlockHeaderRow<F>,
+        fixed: [bool; 4],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        for ((name, column), value) in [
+            ("q_enable", self.q_enable),
+            ("q_first", self.q_first),
+            ("q_last", self.q_last),
+            ("q_uncles", self.q_uncles),
+        ]
+        .into_iter()
+        .zip(fixed)
+        {
+            region.assign_fixed(
+                || format!("block header {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        BinaryNumberChip::construct(self.tag).assign(region, offset, &row.tag)?;
+        for (name, column, value) in self.flags.columns() {
+            region.assign_advice(
+                || format!("block header {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value(row.tag))),
+            )?;
+        }
+        for (name, column, value) in [
+            ("byte", self.byte, row.byte as u64),
+            ("bytes_len", self.bytes_len, row.bytes_len as u64),
+            ("list_end", self.list_end, row.list_end as u64),
+            ("header_end", self.header_end, row.header_end as u64),
+            ("is_last", self.is_last, row.is_last as u64),
+            ("is_padding", self.is_padding, row.is_padding as u64),
+            ("uncle_id", self.uncle_id, row.uncle.id as u64),
+            ("uncle_number", self.uncle_number, row.uncle.number),
+        ] {
+            region.assign_advice(
+                || format!("block header {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        region.assign_advice(
+            || "block header uncle_miner",
+            self.uncle_miner,
+            offset,
+            || Value::known(row.uncle.miner.to_scalar().unwrap()),
+        )?;
+        RlpItemChip::construct(self.item).assign(region, offset, &row.item)?;
+        region.assign_advice(
+            || "block header bytes_rlc",
+            self.bytes_rlc,
+            offset,
+            || row.bytes_rlc,
+        )?;
+        region.assign_advice(|| "block header hash", self.hash, offset, || row.hash)
+    }
+}
@@ -1,19 +1,27 @@
 pub use super::PiCircuit;
 use super::*;
+use crate::{
+    table::{TxLogFieldTag, TxReceiptFieldTag},
+    witness::Rw,
+};
+use eth_types::ToWord;
+use std::iter;
 
 /// Public Input Circuit configuration parameters
 #[derive(Default)]
-pub struct PiCircuitParams {
+pub struct PiCircuitParams<F: Field> {
     /// Max Txs
     pub max_txs: usize,
     /// Max Calldata
     pub max_calldata: usize,
+    /// Randomness for RLC encoding, used for all the challenges
+    pub randomness: F,
 }
 
 impl<F: Field> Circuit<F> for PiCircuit<F> {
-    type Config = (PiCircuitConfig<F>, Challenges);
+    type Config = (PiCircuitConfig<F>, KeccakTable);
     type FloorPlanner = SimpleFloorPlanner;
-    type Params = PiCircuitParams;
+    type Params = PiCircuitParams<F>;
 
     fn without_witnesses(&self) -> Self {
         Self::default()
@@ -23,12 +31,22 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
         PiCircuitParams {
             max_txs: self.max_txs,
             max_calldata: self.max_calldata,
+            randomness: self.randomness,
         }
     }
 
     fn configure_with_params(meta: &mut ConstraintSystem<F>, params: Self::Params) -> Self::Config {
         let block_table = BlockTable::construct(meta);
         let tx_table = TxTable::construct(meta);
+        let keccak_table = KeccakTable::construct(meta);
+        let rw_table = RwTable::construct(meta);
+        let withdrawal_table = WithdrawalTable::construct(meta);
+        let uncle_table = UncleTable::construct(meta);
+        // The values of the tables are encoded with the randomness of the
+        // circuit, so it's used for all the challenges.
+        let randomness = Expression::Constant(params.randomness);
+        let challenges =
+            Challenges::mock(randomness.clone(), randomness.clone(), randomness.clone());
         (
             PiCircuitConfig::new(
                 meta,
@@ -37,9 +55,14 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
                     max_calldata: params.max_calldata,
                     block_table,
                     tx_table,
+                    keccak_table: keccak_table.clone(),
+                    rw_table,
+                    withdrawal_table,
+                    uncle_table,
+                    challenges,
                 },
             ),
-            Challenges::construct(meta),
+            keccak_table,
         )
     }
 
@@ -49,10 +72,84 @@ impl<F: Field> Circuit<F> for PiCircuit<F> {
 
     fn synthesize(
         &self,
-        (config, challenges): Self::Config,
+        (config, keccak_table): Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        let challenges = challenges.values(&mut layouter);
+        let randomness = Value::known(self.randomness);
+        let challenges = Challenges::mock(randomness, randomness, randomness);
+        keccak_table.dev_load(
+            &mut layouter,
+            &self.public_data.keccak_inputs(),
+            &challenges,
+        )?;
+        let rws = receipt_rws(&self.public_data.receipts);
+        config
+            .rw_table
+            .load(&mut layouter, &rws, rws.len() + 1, randomness)?;
+        config
+            .withdrawal_table
+            .load(&mut layouter, &self.public_data.withdrawals)?;
+        config
+            .uncle_table
+            .load(&mut layouter, &self.public_data.uncles)?;
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
+
+/// Return the rws written by the EVM circuit for the receipts and their logs,
+/// as they are in the rw table of the block.
+fn receipt_rws(receipts: &[Receipt]) -> Vec<Rw> {
+    let mut rws = vec![];
+    let mut rw_counter = 0;
+    let mut next_rw_counter = || {
+        rw_counter += 1;
+        rw_counter
+    };
+    for (idx, receipt) in receipts.iter().enumerate() {
+        let tx_id = idx + 1;
+        for (log_idx, log) in receipt.logs.iter().enumerate() {
+            let log_id = log_idx as u64 + 1;
+            let fields = iter::once((TxLogFieldTag::Address, 0, log.address.to_word()))
+                .chain(
+                    log.topics
+                        .iter()
+                        .enumerate()
+                        .map(|(index, topic)| (TxLogFieldTag::Topic, index, topic.to_word())),
+                )
+                .chain(
+                    log.data
+                        .iter()
+                        .enumerate()
+                        .map(|(index, byte)| (TxLogFieldTag::Data, index, Word::from(*byte))),
+                );
+            for (field_tag, index, value) in fields {
+                rws.push(Rw::TxLog {
+                    rw_counter: next_rw_counter(),
+                    is_write: true,
+                    tx_id,
+                    log_id,
+                    field_tag,
+                    index,
+                    value,
+                });
+            }
+        }
+        for (field_tag, value) in [
+            (TxReceiptFieldTag::PostStateOrStatus, receipt.status),
+            (
+                TxReceiptFieldTag::CumulativeGasUsed,
+                receipt.cumulative_gas_used,
+            ),
+            (TxReceiptFieldTag::LogLength, receipt.logs.len() as u64),
+        ] {
+            rws.push(Rw::TxReceipt {
+                rw_counter: next_rw_counter(),
+                is_write: true,
+                tx_id,
+                field_tag,
+                value,
+            });
+        }
+    }
+    rws
+}
@@ -0,0 +1,628 @@
+//! Circuit to derive the logs bloom of the block from the addresses and the
+//! topics of the logs of its receipts.
+//!
+//! The rows are shared by two layouts.  The bloom inputs (the address and
+//! the topics of every log) are laid out in blocks of 32 rows holding the
+//! bytes of their keccak, which is looked up in the `KeccakTable`, and the
+//! first three pairs of bytes of every hash select the bits set by the input.
+//! The blooms are laid out one bit per row, 2048 rows for the block followed
+//! by 2048 rows for each receipt, from the most significant bit of their
+//! first byte.  Every bit set by an input is set in the bloom of its receipt,
+//! every bit set in the bloom of a receipt is set by one of its inputs, and
+//! the bits set in the block bloom are the union of the bits set in the
+//! receipt blooms.  The bytes of every bloom are accumulated into its value,
+//! and the value of the block bloom is copied to the block table.
+//!
+//! The bloom inputs are the addresses and the topics of the logs in the
+//! encodings of the receipts, which are verified against the `RwTable` by the
+//! trie values circuit, and the bloom of every receipt is the one in its
+//! encoding.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{KeccakTable, LookupTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{geth_types::Receipt, keccak256, Field};
+use gadgets::util::not;
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::marker::PhantomData;
+
+use super::trie_values::TrieValuesConfig;
+
+/// Number of bits of a logs bloom
+const BLOOM_BITS: usize = 2048;
+
+/// Number of rows of a bloom input: one for each byte of its hash
+const INPUT_ROWS: usize = 32;
+
+const MAX_DEGREE: usize = 9;
+
+/// Address or topic of a log, set in the bloom of the receipt of `tx_id`
+#[derive(Clone, Debug)]
+struct BloomInput<F> {
+    tx_id: usize,
+    input_rlc: Value<F>,
+    input_len: usize,
+    hash: [u8; 32],
+}
+
+/// Witness of a row of the bloom inputs and of the blooms
+#[derive(Clone, Debug)]
+struct LogsBloomRow<F> {
+    is_input: bool,
+    tx_id: usize,
+    input_rlc: Value<F>,
+    input_len: usize,
+    byte: u8,
+    bit: u64,
+    hash_rlc: Value<F>,
+    is_set: bool,
+    byte_acc: u8,
+    bloom_acc: Value<F>,
+}
+
+/// Config to derive the logs blooms of the receipts and of the block
+#[derive(Clone, Debug)]
+pub(crate) struct LogsBloomConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    // Rows of the bloom of the block, and of the blooms of the receipts
+    q_block: Column<Fixed>,
+    q_receipt: Column<Fixed>,
+    // 0 for the rows of the block bloom, and the tx_id of the receipt for the
+    // rows of the receipt blooms
+    owner: Column<Fixed>,
+    // Index of the bit of the row in its bloom, from 2047 down to 0
+    bit_index: Column<Fixed>,
+    q_bloom_first: Column<Fixed>,
+    q_bloom_last: Column<Fixed>,
+    q_byte_first: Column<Fixed>,
+    q_byte_last: Column<Fixed>,
+    q_hash_first: Column<Fixed>,
+    q_hash_last: Column<Fixed>,
+    // First rows of the first three pairs of bytes of a hash
+    q_pair: Column<Fixed>,
+    is_input: Column<Advice>,
+    tx_id: Column<Advice>,
+    input_rlc: Column<Advice>,
+    input_len: Column<Advice>,
+    byte: Column<Advice>,
+    // The 3 low bits of the byte
+    byte_lo: Column<Advice>,
+    // Bit selected by the pair of bytes starting at the row
+    bit: Column<Advice>,
+    hash_rlc: Column<Advice>,
+    is_set: Column<Advice>,
+    byte_acc: Column<Advice>,
+    bloom_acc: Column<Advice>,
+    // (byte, byte & 7), also used as the u8 range table
+    byte_table: [TableColumn; 2],
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> LogsBloomConfig<F> {
+    /// Return a new LogsBloomConfig
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        keccak_table: &KeccakTable,
+        trie_values: &TrieValuesConfig<F>,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let [q_enable, q_first, q_block, q_receipt, owner, bit_index] =
+            [(); 6].map(|_| meta.fixed_column());
+        let [q_bloom_first, q_bloom_last, q_byte_first, q_byte_last] =
+            [(); 4].map(|_| meta.fixed_column());
+        let [q_hash_first, q_hash_last, q_pair] = [(); 3].map(|_| meta.fixed_column());
+        let [is_input, tx_id] = [(); 2].map(|_| meta.advice_column());
+        let input_rlc = meta.advice_column_in(SecondPhase);
+        let [input_len, byte, byte_lo, bit] = [(); 4].map(|_| meta.advice_column());
+        let hash_rlc = meta.advice_column_in(SecondPhase);
+        let [is_set, byte_acc] = [(); 2].map(|_| meta.advice_column());
+        let bloom_acc = meta.advice_column_in(SecondPhase);
+        let byte_table = [(); 2].map(|_| meta.lookup_table_column());
+
+        meta.enable_equality(bloom_acc);
+
+        meta.create_gate("logs bloom input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let q_hash_first = meta.query_fixed(q_hash_first, Rotation::cur());
+            let q_pair = meta.query_fixed(q_pair, Rotation::cur());
+            let input_values = [is_input, tx_id, input_rlc, input_len].map(|column| {
+                (
+                    meta.query_advice(column, Rotation::prev()),
+                    meta.query_advice(column, Rotation::cur()),
+                )
+            });
+            let [is_input, input_len] =
+                [is_input, input_len].map(|column| meta.query_advice(column, Rotation::cur()));
+            let byte_next = meta.query_advice(byte, Rotation::next());
+            let [byte, byte_lo, bit] =
+                [byte, byte_lo, bit].map(|column| meta.query_advice(column, Rotation::cur()));
+            let hash_rlc_prev = meta.query_advice(hash_rlc, Rotation::prev());
+            let hash_rlc = meta.query_advice(hash_rlc, Rotation::cur());
+
+            cb.require_boolean("is_input is boolean", is_input.clone());
+            cb.condition(not::expr(q_hash_first.clone()), |cb| {
+                for (prev, cur) in input_values.clone() {
+                    cb.require_equal("the input is the same in the rows of its hash", cur, prev);
+                }
+                cb.require_equal(
+                    "hash_rlc accumulates the bytes of the hash",
+                    hash_rlc.clone(),
+                    hash_rlc_prev * challenges.evm_word() + byte.clone(),
+                );
+            });
+            cb.condition(q_hash_first.clone(), |cb| {
+                cb.require_equal("hash_rlc starts at the byte", hash_rlc, byte);
+            });
+            let [(is_input_prev, _), ..] = input_values;
+            cb.condition(q_hash_first * not::expr(q_first), |cb| {
+                cb.require_zero(
+                    "the inputs are before the unused rows",
+                    is_input.clone() * not::expr(is_input_prev),
+                );
+            });
+            cb.condition(is_input, |cb| {
+                cb.require_zero(
+                    "the inputs are addresses or topics",
+                    (input_len.clone() - 20.expr()) * (input_len - 32.expr()),
+                );
+            });
+            cb.condition(q_pair, |cb| {
+                cb.require_equal(
+                    "the pair of bytes selects a bit modulo 2048",
+                    bit,
+                    byte_lo * 256.expr() + byte_next,
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("logs bloom bit", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_bloom_first = meta.query_fixed(q_bloom_first, Rotation::cur());
+            let q_byte_first = meta.query_fixed(q_byte_first, Rotation::cur());
+            let q_byte_last = meta.query_fixed(q_byte_last, Rotation::cur());
+            let [byte_acc_prev, bloom_acc_prev] =
+                [byte_acc, bloom_acc].map(|column| meta.query_advice(column, Rotation::prev()));
+            let [is_set, byte_acc, bloom_acc] = [is_set, byte_acc, bloom_acc]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.require_boolean("is_set is boolean", is_set.clone());
+            cb.require_equal(
+                "byte_acc accumulates the bits of the byte",
+                byte_acc.clone(),
+                not::expr(q_byte_first.clone()) * byte_acc_prev * 2.expr() + is_set,
+            );
+            cb.condition(q_bloom_first.clone(), |cb| {
+                cb.require_zero("bloom_acc starts at 0", bloom_acc.clone());
+            });
+            cb.condition(not::expr(q_bloom_first), |cb| {
+                cb.require_equal(
+                    "bloom_acc accumulates the bytes of the bloom",
+                    bloom_acc,
+                    q_byte_last.clone()
+                        * (bloom_acc_prev.clone() * challenges.keccak_input() + byte_acc)
+                        + not::expr(q_byte_last) * bloom_acc_prev,
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.lookup("logs bloom byte range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            [byte, byte_lo]
+                .into_iter()
+                .zip(byte_table)
+                .map(|(column, table)| {
+                    (
+                        q_enable.clone() * meta.query_advice(column, Rotation::cur()),
+                        table,
+                    )
+                })
+                .collect()
+        });
+        meta.lookup_any("logs bloom input keccak", |meta| {
+            let enable = meta.query_fixed(q_hash_last, Rotation::cur())
+                * meta.query_advice(is_input, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(input_rlc, Rotation::cur()),
+                meta.query_advice(input_len, Rotation::cur()),
+                meta.query_advice(hash_rlc, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        // (owner, bit_index, is_set) of the rows of the blooms
+        let bloom_exprs = |meta: &mut VirtualCells<'_, F>| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            [
+                meta.query_fixed(owner, Rotation::cur()),
+                meta.query_fixed(bit_index, Rotation::cur()),
+                meta.query_advice(is_set, Rotation::cur()),
+            ]
+            .map(|value| q_enable.clone() * value)
+        };
+        meta.lookup_any("logs bloom input bit in receipt bloom", |meta| {
+            let enable = meta.query_fixed(q_pair, Rotation::cur())
+                * meta.query_advice(is_input, Rotation::cur());
+            let input = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_advice(bit, Rotation::cur()),
+                1.expr(),
+            ];
+            input
+                .into_iter()
+                .zip(bloom_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("logs bloom receipt bit from input", |meta| {
+            let enable = meta.query_fixed(q_receipt, Rotation::cur())
+                * meta.query_advice(is_set, Rotation::cur());
+            let input = [
+                meta.query_fixed(owner, Rotation::cur()),
+                meta.query_fixed(bit_index, Rotation::cur()),
+            ];
+            let table_enable = meta.query_fixed(q_pair, Rotation::cur())
+                * meta.query_advice(is_input, Rotation::cur());
+            let table = [
+                meta.query_advice(tx_id, Rotation::cur()),
+                meta.query_advice(bit, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.clone() * input, table_enable.clone() * table))
+                .collect()
+        });
+        meta.lookup_any("logs bloom receipt bit in block bloom", |meta| {
+            let enable = meta.query_fixed(q_receipt, Rotation::cur())
+                * meta.query_advice(is_set, Rotation::cur());
+            let input = [
+                0.expr(),
+                meta.query_fixed(bit_index, Rotation::cur()),
+                1.expr(),
+            ];
+            input
+                .into_iter()
+                .zip(bloom_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("logs bloom block bit from receipt", |meta| {
+            let enable = meta.query_fixed(q_block, Rotation::cur())
+                * meta.query_advice(is_set, Rotation::cur());
+            let input = [meta.query_fixed(bit_index, Rotation::cur()), 1.expr()];
+            let table_enable = meta.query_fixed(q_receipt, Rotation::cur());
+            let table = [
+                meta.query_fixed(bit_index, Rotation::cur()),
+                meta.query_advice(is_set, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (enable.clone() * input, table_enable.clone() * table))
+                .collect()
+        });
+
+        // (tx_id, input_rlc, input_len) of the bloom inputs
+        let input_exprs = |meta: &mut VirtualCells<'_, F>| {
+            let enable = meta.query_fixed(q_hash_last, Rotation::cur())
+                * meta.query_advice(is_input, Rotation::cur());
+            [tx_id, input_rlc, input_len]
+                .map(|column| enable.clone() * meta.query_advice(column, Rotation::cur()))
+        };
+        meta.lookup_any("logs bloom input in receipt log", |meta| {
+            input_exprs(meta)
+                .into_iter()
+                .zip(trie_values.bloom_input_exprs(meta))
+                .collect()
+        });
+        meta.lookup_any("logs bloom receipt log in inputs", |meta| {
+            trie_values
+                .bloom_input_exprs(meta)
+                .into_iter()
+                .zip(input_exprs(meta))
+                .collect()
+        });
+        meta.lookup_any("logs bloom of receipt", |meta| {
+            let enable = meta.query_fixed(q_receipt, Rotation::cur())
+                * meta.query_fixed(q_bloom_last, Rotation::cur());
+            let table = [
+                meta.query_fixed(owner, Rotation::cur()),
+                meta.query_advice(bloom_acc, Rotation::cur()),
+            ];
+            trie_values
+                .logs_bloom_exprs(meta)
+                .into_iter()
+                .zip(table)
+                .map(|(input, table)| (input, enable.clone() * table))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            q_block,
+            q_receipt,
+            owner,
+            bit_index,
+            q_bloom_first,
+            q_bloom_last,
+            q_byte_first,
+            q_byte_last,
+            q_hash_first,
+            q_hash_last,
+            q_pair,
+            is_input,
+            tx_id,
+            input_rlc,
+            input_len,
+            byte,
+            byte_lo,
+            bit,
+            hash_rlc,
+            is_set,
+            byte_acc,
+            bloom_acc,
+            byte_table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Load the byte table.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "logs bloom byte table",
+            |mut table| {
+                for byte in 0..256u64 {
+                    for (&column, value) in self.byte_table.iter().zip([byte, byte & 7]) {
+                        table.assign_cell(
+                            || format!("byte table row {}", byte),
+                            column,
+                            byte as usize,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Return the number of rows of the blooms of the block and of up to
+    /// `max_txs` receipts, which also hold up to 64 bloom inputs for each of
+    /// them.
+    pub(crate) fn num_rows(max_txs: usize) -> usize {
+        BLOOM_BITS * (max_txs + 1)
+    }
+
+    /// Return the bloom inputs of the logs of the receipts.
+    fn bloom_inputs(receipts: &[Receipt], challenges: &Challenges<Value<F>>) -> Vec<BloomInput<F>> {
+        receipts
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, receipt)| {
+                receipt
+                    .logs
+                    .iter()
+                    .flat_map(|log| log.bloom_inputs())
+                    .map(move |input| BloomInput {
+                        tx_id: idx + 1,
+                        input_rlc: challenges
+                            .keccak_input()
+                            .map(|challenge| rlc::value(input.iter().rev(), challenge)),
+                        input_len: input.len(),
+                        hash: keccak256(input),
+                    })
+            })
+            .collect()
+    }
+
+    /// Return the rows of the bloom inputs of the receipts and of the blooms
+    /// of the block and of `max_txs` receipts.
+    fn logs_bloom_rows(
+        receipts: &[Receipt],
+        max_txs: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<LogsBloomRow<F>> {
+        let inputs = Self::bloom_inputs(receipts, challenges);
+        let blooms: Vec<[u8; 256]> = [Receipt::block_logs_bloom(receipts).to_fixed_bytes()]
+            .into_iter()
+            .chain(receipts.iter().map(Receipt::logs_bloom))
+            .chain(std::iter::repeat([0; 256]))
+            .take(max_txs + 1)
+            .collect();
+
+        let mut rows: Vec<LogsBloomRow<F>> = Vec::with_capacity(Self::num_rows(max_txs));
+        for offset in 0..Self::num_rows(max_txs) {
+            let input = inputs.get(offset / INPUT_ROWS);
+            let index = offset % INPUT_ROWS;
+            let hash = input.map_or([0; 32], |input| input.hash);
+            let byte = hash[index];
+            let hash_rlc = if index == 0 {
+                Value::known(F::from(byte as u64))
+            } else {
+                rows[offset - 1].hash_rlc * challenges.evm_word()
+                    + Value::known(F::from(byte as u64))
+            };
+
+            let bloom = &blooms[offset / BLOOM_BITS];
+            let position = offset % BLOOM_BITS;
+            let shift = 7 - position % 8;
+            let bloom_byte = bloom[position / 8];
+            let bloom_acc = if position == 0 {
+                Value::known(F::ZERO)
+            } else if shift == 0 {
+                rows[offset - 1].bloom_acc * challenges.keccak_input()
+                    + Value::known(F::from(bloom_byte as u64))
+            } else {
+                rows[offset - 1].bloom_acc
+            };
+
+            rows.push(LogsBloomRow {
+                is_input: input.is_some(),
+                tx_id: input.map_or(0, |input| input.tx_id),
+                input_rlc: input.map_or(Value::known(F::ZERO), |input| input.input_rlc),
+                input_len: input.map_or(0, |input| input.input_len),
+                byte,
+                bit: if index < 6 && index % 2 == 0 {
+                    (byte as u64 & 7) * 256 + hash[index + 1] as u64
+                } else {
+                    0
+                },
+                hash_rlc,
+                is_set: (bloom_byte >> shift) & 1 == 1,
+                byte_acc: bloom_byte >> shift,
+                bloom_acc,
+            });
+        }
+        rows
+    }
+
+    /// Assign the bloom inputs of the logs of the receipts and the blooms of
+    /// the block and of the receipts, and return the cell of the value of the
+    /// block bloom.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        receipts: &[Receipt],
+        max_txs: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        if receipts.len() > max_txs {
+            error!(
+                "receipts {} exceed the maximum number of transactions {}",
+                receipts.len(),
+                max_txs
+            );
+            return Err(Error::Synthesis);
+        }
+        let num_inputs = receipts
+            .iter()
+            .flat_map(|receipt| &receipt.logs)
+            .map(|log| 1 + log.topics.len())
+            .sum::<usize>();
+        if num_inputs * INPUT_ROWS > Self::num_rows(max_txs) {
+            error!(
+                "logs bloom input rows {} exceed the available rows {}",
+                num_inputs * INPUT_ROWS,
+                Self::num_rows(max_txs)
+            );
+            return Err(Error::Synthesis);
+        }
+
+        let rows = Self::logs_bloom_rows(receipts, max_txs, challenges);
+        layouter.assign_region(
+            || "logs bloom",
+            |mut region| {
+                let mut bloom_cell = None;
+                for (offset, row) in rows.iter().enumerate() {
+                    let cell = self.assign_row(&mut region, offset, row)?;
+                    if offset == BLOOM_BITS - 1 {
+                        bloom_cell = Some(cell);
+                    }
+                }
+                Ok(bloom_cell.expect("the block bloom has 2048 rows"))
+            },
+        )
+    }
+
+    /// Assign the row at `offset` with the values of the fixed columns at
+    /// that position, and return the cell of the value of the bloom.
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &LogsBloomRow<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let position = offset % BLOOM_BITS;
+        let index = offset % INPUT_ROWS;
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, 1),
+            ("q_first", self.q_first, (offset == 0) as u64),
+            ("q_block", self.q_block, (offset < BLOOM_BITS) as u64),
+            ("q_receipt", self.q_receipt, (offset >= BLOOM_BITS) as u64),
+            ("owner", self.owner, (offset / BLOOM_BITS) as u64),
+            (
+                "bit_index",
+                self.bit_index,
+                (BLOOM_BITS - 1 - position) as u64,
+            ),
+            ("q_bloom_first", self.q_bloom_first, (position == 0) as u64),
+            (
+                "q_bloom_last",
+                self.q_bloom_last,
+                (position == BLOOM_BITS - 1) as u64,
+            ),
+            ("q_byte_first", self.q_byte_first, (offset % 8 == 0) as u64),
+            ("q_byte_last", self.q_byte_last, (offset % 8 == 7) as u64),
+            ("q_hash_first", self.q_hash_first, (index == 0) as u64),
+            (
+                "q_hash_last",
+                self.q_hash_last,
+                (index == INPUT_ROWS - 1) as u64,
+            ),
+            ("q_pair", self.q_pair, (index < 6 && index % 2 == 0) as u64),
+        ] {
+            region.assign_fixed(
+                || format!("logs bloom {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("is_input", self.is_input, row.is_input as u64),
+            ("tx_id", self.tx_id, row.tx_id as u64),
+            ("input_len", self.input_len, row.input_len as u64),
+            ("byte", self.byte, row.byte as u64),
+            ("byte_lo", self.byte_lo, row.byte as u64 & 7),
+            ("bit", self.bit, row.bit),
+            ("is_set", self.is_set, row.is_set as u64),
+            ("byte_acc", self.byte_acc, row.byte_acc as u64),
+        ] {
+            region.assign_advice(
+                || format!("logs bloom {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("input_rlc", self.input_rlc, row.input_rlc),
+            ("hash_rlc", self.hash_rlc, row.hash_rlc),
+        ] {
+            region.assign_advice(|| format!("logs bloom {}", name), column, offset, || value)?;
+        }
+        region.assign_advice(
+            || "logs bloom bloom_acc",
+            self.bloom_acc,
+            offset,
+            || row.bloom_acc,
+        )
+    }
+}
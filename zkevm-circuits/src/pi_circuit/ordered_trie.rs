@@ -0,0 +1,750 @@
+//! Circuit to verify the roots of the ordered tries of the block: the
+//! transactions, receipts and withdrawals tries, whose keys are the RLP
+//! encodings of the indexes of their values.
+//!
+//! The encodings of the nodes of the tries are decoded by the nodes config of
+//! the MPT circuit, and every node of a trie takes a row with its path from
+//! the root, which looks up the decoded node.  Every node but the root is
+//! looked up as a child of its parent in the nodes of the same trie, at the
+//! nibble of the path after branch parents or after the key of extension
+//! parents, so that the nodes form the trie whose root is the hash of the
+//! first node.  The keys of the leaves are the encodings of their indexes,
+//! and the hashes of the values of the transactions trie are looked up in the
+//! `TxTable`, while the values of the receipts and withdrawals tries are
+//! looked up in their encodings in the trie values circuit.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    impl_expr,
+    mpt_circuit::nodes::{MptNodesConfig, NodeType, TrieType},
+    table::{KeccakTable, LookupTable, TxFieldTag, TxTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{
+    geth_types::MAX_WITHDRAWALS_PER_BLOCK,
+    keccak256,
+    trie::{ordered_trie_key, ordered_trie_nodes, TrieNode},
+    Field, ToLittleEndian, Word,
+};
+use gadgets::util::{not, sum};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::{collections::HashMap, iter};
+
+use super::param::{
+    MAX_RECEIPT_RLP_LEN, MAX_TRIE_NODES_PER_LEAF, MAX_TRIE_OVERHEAD_PER_LEAF,
+    MAX_WITHDRAWAL_RLP_LEN,
+};
+use crate::tx_circuit::MAX_TX_RLP_STATIC_LEN;
+
+const MAX_DEGREE: usize = 9;
+
+/// Ordered tries of the block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum TrieKind {
+    /// Trie of the encodings of the signed transactions
+    Transactions = 1,
+    /// Trie of the encodings of the receipts
+    Receipts,
+    /// Trie of the encodings of the withdrawals (EIP-4895)
+    Withdrawals,
+}
+
+impl_expr!(TrieKind);
+
+/// Witness of the row of a node of a trie
+#[derive(Clone, Debug)]
+struct TrieRow<F> {
+    trie: u64,
+    // Type of the node, or None in padding rows
+    node_type: Option<NodeType>,
+    hash: Value<F>,
+    path_acc: u64,
+    path_len: usize,
+    key_acc: u64,
+    key_len: usize,
+    payload: Value<F>,
+    payload_len: usize,
+    parent_hash: Value<F>,
+    is_branch_child: bool,
+    // Slot of the node in its branch parent
+    nibble: u8,
+    leaf_index: usize,
+    value_hash: Value<F>,
+}
+
+impl<F: Field> TrieRow<F> {
+    fn padding(trie: u64, hash: Value<F>) -> Self {
+        Self {
+            trie,
+            node_type: None,
+            hash,
+            path_acc: 0,
+            path_len: 0,
+            key_acc: 0,
+            key_len: 0,
+            payload: Value::known(F::ZERO),
+            payload_len: 0,
+            parent_hash: Value::known(F::ZERO),
+            is_branch_child: false,
+            nibble: 0,
+            leaf_index: 0,
+            value_hash: Value::known(F::ZERO),
+        }
+    }
+}
+
+/// Config to verify the roots of the ordered tries
+#[derive(Clone, Debug)]
+pub(crate) struct OrderedTrieConfig<F> {
+    q_enable: Column<Fixed>,
+    // First row of the rows of a trie
+    q_first: Column<Fixed>,
+    // TrieKind of the rows of a trie
+    trie: Column<Fixed>,
+    // 1 in the rows of the nodes, and 0 in the padding rows
+    is_node: Column<Advice>,
+    // One-hot flags of the node type
+    is_branch: Column<Advice>,
+    is_ext: Column<Advice>,
+    is_leaf: Column<Advice>,
+    hash: Column<Advice>,
+    // Nibbles of the path from the root to the node, as an integer, and
+    // their number.
+    path_acc: Column<Advice>,
+    path_len: Column<Advice>,
+    // Nibbles of the key of extension and leaf nodes, as an integer, their
+    // number, and 16 to their number.
+    key_acc: Column<Advice>,
+    key_len: Column<Advice>,
+    key_pow: Column<Advice>,
+    // Hash of the child of extension nodes, or RLC of the value of leaf
+    // nodes, and its length.
+    payload: Column<Advice>,
+    payload_len: Column<Advice>,
+    // Hash of the parent of the nodes but the roots, whether it is a branch
+    // node, and the slot of the node in it.
+    parent_hash: Column<Advice>,
+    is_branch_child: Column<Advice>,
+    nibble: Column<Advice>,
+    leaf_index: Column<Advice>,
+    value_hash: Column<Advice>,
+    // Decoding of the encodings of the nodes
+    nodes: MptNodesConfig<F>,
+    // (byte, byte >> 4, byte & 0xf), also used as the u8 range table
+    byte_table: [TableColumn; 3],
+    // (index + 1, integer of the encoding of index, number of its nibbles)
+    // of the keys of the leaves, after an all-zero row.
+    key_table: [TableColumn; 3],
+}
+
+impl<F: Field> OrderedTrieConfig<F> {
+    /// Return a new OrderedTrieConfig
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        tx_table: &TxTable,
+        keccak_table: &KeccakTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let trie = meta.fixed_column();
+        let [is_node, is_branch, is_ext, is_leaf] = [(); 4].map(|_| meta.advice_column());
+        let [path_acc, path_len, key_acc, key_len, key_pow, payload_len] =
+            [(); 6].map(|_| meta.advice_column());
+        let [is_branch_child, nibble, leaf_index] = [(); 3].map(|_| meta.advice_column());
+        let [hash, payload, parent_hash, value_hash] =
+            [(); 4].map(|_| meta.advice_column_in(SecondPhase));
+        let byte_table = [(); 3].map(|_| meta.lookup_table_column());
+        let key_table = [(); 3].map(|_| meta.lookup_table_column());
+
+        meta.enable_equality(hash);
+
+        let nodes = MptNodesConfig::new(
+            meta,
+            TrieType::Ordered,
+            byte_table,
+            keccak_table,
+            challenges,
+        );
+
+        let node_type_expr = |meta: &mut VirtualCells<'_, F>| {
+            meta.query_advice(is_ext, Rotation::cur()) * NodeType::Extension.expr()
+                + meta.query_advice(is_leaf, Rotation::cur()) * NodeType::ValueLeaf.expr()
+        };
+
+        meta.create_gate("ordered trie node", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let q_first_next = meta.query_fixed(q_first, Rotation::next());
+            let is_node_next = meta.query_advice(is_node, Rotation::next());
+            let q_first = meta.query_fixed(q_first, Rotation::cur());
+            let node_flags = [is_branch, is_ext, is_leaf]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_node, is_branch, is_branch_child] = [is_node, is_branch, is_branch_child]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            for flag in node_flags.iter() {
+                cb.require_boolean("node flags are boolean", flag.clone());
+            }
+            cb.require_boolean("is_node is boolean", is_node.clone());
+            cb.require_boolean("is_branch_child is boolean", is_branch_child.clone());
+            cb.require_equal(
+                "one node flag is set in the rows of the nodes",
+                sum::expr(node_flags),
+                is_node.clone(),
+            );
+            cb.require_zero(
+                "padding is followed by padding until the next trie",
+                not::expr(is_node.clone()) * not::expr(q_first_next) * is_node_next,
+            );
+            cb.require_zero(
+                "only the nodes but the roots have a parent",
+                is_branch_child.clone() * (not::expr(is_node.clone()) + q_first.clone()),
+            );
+            cb.require_zero(
+                "the child of an extension node is a branch node",
+                is_node * not::expr(q_first) * not::expr(is_branch_child) * not::expr(is_branch),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("ordered trie root", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_node = meta.query_advice(is_node, Rotation::cur());
+            cb.condition(is_node.clone(), |cb| {
+                cb.require_zero(
+                    "the root is at the empty path",
+                    meta.query_advice(path_acc, Rotation::cur()),
+                );
+                cb.require_zero(
+                    "the root is at the empty path",
+                    meta.query_advice(path_len, Rotation::cur()),
+                );
+            });
+            // The root of the empty trie is the hash of the empty string.
+            let empty_root = Word::from_big_endian(&keccak256([0x80])).to_le_bytes();
+            cb.condition(not::expr(is_node), |cb| {
+                cb.require_equal(
+                    "the hash of the empty trie is the empty root",
+                    meta.query_advice(hash, Rotation::cur()),
+                    rlc::expr(&empty_root.map(|byte| byte.expr()), challenges.evm_word()),
+                );
+            });
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.lookup_any("ordered trie decoded node", |meta| {
+            // The children of branch nodes are looked up on their own, and
+            // the fields of accounts are only set in the state trie.
+            let enable = meta.query_advice(is_node, Rotation::cur());
+            let node = nodes.node_exprs(meta);
+            [
+                (meta.query_advice(hash, Rotation::cur()), 0),
+                (node_type_expr(meta), 1),
+                (meta.query_advice(key_acc, Rotation::cur()), 2),
+                (meta.query_advice(key_len, Rotation::cur()), 3),
+                (meta.query_advice(payload, Rotation::cur()), 4),
+                (meta.query_advice(payload_len, Rotation::cur()), 10),
+                (meta.query_advice(key_pow, Rotation::cur()), 11),
+            ]
+            .into_iter()
+            .map(|(input, index)| (enable.clone() * input, node[index].clone()))
+            .collect()
+        });
+        meta.lookup_any("ordered trie branch child", |meta| {
+            let enable = meta.query_advice(is_branch_child, Rotation::cur());
+            [
+                meta.query_advice(parent_hash, Rotation::cur()),
+                meta.query_advice(nibble, Rotation::cur()),
+                meta.query_advice(hash, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(nodes.child_exprs(meta))
+            .map(|(input, table)| (enable.clone() * input, table))
+            .collect()
+        });
+        meta.lookup_any("ordered trie parent", |meta| {
+            // The path of the children of branch nodes is the path of their
+            // parent followed by their nibble, and the path of the child of
+            // extension nodes is the path of their parent followed by its
+            // key, with the hash of the child in the payload of the parent.
+            let enable = meta.query_advice(is_node, Rotation::cur())
+                * not::expr(meta.query_fixed(q_first, Rotation::cur()));
+            let is_branch_child = meta.query_advice(is_branch_child, Rotation::cur());
+            let trie = meta.query_fixed(trie, Rotation::cur());
+            let [hash, payload] =
+                [hash, payload].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [path_acc, path_len, key_acc, key_len, key_pow, nibble] =
+                [path_acc, path_len, key_acc, key_len, key_pow, nibble]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let input = [
+                trie.clone(),
+                meta.query_advice(parent_hash, Rotation::cur()),
+                not::expr(is_branch_child.clone()) * NodeType::Extension.expr(),
+                is_branch_child.clone()
+                    * (path_acc.clone() - nibble)
+                    * Expression::Constant(F::from(16).invert().unwrap())
+                    + not::expr(is_branch_child.clone()) * path_acc.clone(),
+                path_len.clone() - is_branch_child.clone(),
+                not::expr(is_branch_child) * hash.clone(),
+            ];
+            // The rows out of the enabled ones are in no trie.
+            let enable_parent = meta.query_advice(is_branch, Rotation::cur())
+                + meta.query_advice(is_ext, Rotation::cur());
+            let parent = [
+                trie,
+                hash,
+                node_type_expr(meta),
+                path_acc * key_pow + key_acc,
+                path_len + key_len,
+                payload,
+            ];
+            input
+                .into_iter()
+                .zip(parent)
+                .map(|(input, table)| (enable.clone() * input, enable_parent.clone() * table))
+                .collect()
+        });
+        meta.lookup("ordered trie leaf key", |meta| {
+            let enable = meta.query_advice(is_leaf, Rotation::cur());
+            let [path_acc, path_len, key_acc, key_len, key_pow] =
+                [path_acc, path_len, key_acc, key_len, key_pow]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            [
+                meta.query_advice(leaf_index, Rotation::cur()) + 1.expr(),
+                path_acc * key_pow + key_acc,
+                path_len + key_len,
+            ]
+            .into_iter()
+            .zip(key_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+        meta.lookup_any("ordered trie value keccak", |meta| {
+            let enable = meta.query_advice(is_leaf, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(payload, Rotation::cur()),
+                meta.query_advice(payload_len, Rotation::cur()),
+                meta.query_advice(value_hash, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+
+        meta.lookup_any("ordered trie transaction", |meta| {
+            // The values of the transactions trie are the encodings of the
+            // transactions, whose hashes are in the tx table.
+            let trie = meta.query_fixed(trie, Rotation::cur());
+            let is_transactions = (TrieKind::Receipts.expr() - trie.clone())
+                * (TrieKind::Withdrawals.expr() - trie)
+                * Expression::Constant(F::from(2).invert().unwrap());
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_leaf, Rotation::cur())
+                * is_transactions;
+            [
+                meta.query_advice(leaf_index, Rotation::cur()) + 1.expr(),
+                TxFieldTag::TxHash.expr(),
+                0.expr(),
+                meta.query_advice(value_hash, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(tx_table.table_exprs(meta))
+            .map(|(input, table)| (enable.clone() * input, table))
+            .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            trie,
+            is_node,
+            is_branch,
+            is_ext,
+            is_leaf,
+            hash,
+            path_acc,
+            path_len,
+            key_acc,
+            key_len,
+            key_pow,
+            payload,
+            payload_len,
+            parent_hash,
+            is_branch_child,
+            nibble,
+            leaf_index,
+            value_hash,
+            nodes,
+            byte_table,
+            key_table,
+        }
+    }
+
+    /// Return the (trie, leaf_index, value_hash) expressions of the leaves,
+    /// which are zero out of the rows of the leaf nodes.
+    pub(crate) fn leaf_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 3] {
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.is_leaf, Rotation::cur());
+        [
+            meta.query_fixed(self.trie, Rotation::cur()),
+            meta.query_advice(self.leaf_index, Rotation::cur()),
+            meta.query_advice(self.value_hash, Rotation::cur()),
+        ]
+        .map(|value| enable.clone() * value)
+    }
+
+    /// Return the (trie, leaf_index, value_rlc, value_len) expressions of the
+    /// leaves of the receipts and withdrawals tries, which are zero out of the
+    /// rows of these leaves.  q_enable is left out to keep the degree of the
+    /// lookups low, as the trie is 0 out of the enabled rows.
+    pub(crate) fn value_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 4] {
+        let trie = meta.query_fixed(self.trie, Rotation::cur());
+        let is_transactions = (TrieKind::Receipts.expr() - trie.clone())
+            * (TrieKind::Withdrawals.expr() - trie.clone())
+            * Expression::Constant(F::from(2).invert().unwrap());
+        let enable = meta.query_advice(self.is_leaf, Rotation::cur()) * not::expr(is_transactions);
+        [
+            trie,
+            meta.query_advice(self.leaf_index, Rotation::cur()),
+            meta.query_advice(self.payload, Rotation::cur()),
+            meta.query_advice(self.payload_len, Rotation::cur()),
+        ]
+        .map(|value| enable.clone() * value)
+    }
+
+    /// Load the byte table, the table of the keys of up to `max_leaves`
+    /// leaves and the table of the transitions between the items of the
+    /// nodes.
+    pub(crate) fn load_aux_tables(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        max_leaves: usize,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "ordered trie byte table",
+            |mut table| {
+                for byte in 0..256u64 {
+                    for (&column, value) in
+                        self.byte_table.iter().zip([byte, byte >> 4, byte & 0xf])
+                    {
+                        table.assign_cell(
+                            || format!("byte table row {}", byte),
+                            column,
+                            byte as usize,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        layouter.assign_table(
+            || "ordered trie key table",
+            |mut table| {
+                let keys = (0..max_leaves).map(|index| {
+                    let key = ordered_trie_key(index);
+                    (index as u64 + 1, nibbles_value(&key), key.len() as u64)
+                });
+                for (offset, (index, key, key_len)) in iter::once((0, 0, 0)).chain(keys).enumerate()
+                {
+                    for (&column, value) in self.key_table.iter().zip([index, key, key_len]) {
+                        table.assign_cell(
+                            || format!("key table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )?;
+        self.nodes.load_transition_table(layouter)
+    }
+
+    /// Return the number of rows for the encodings of the nodes of a trie.
+    /// The bound of the encodings of the nodes of every leaf takes the rows
+    /// left unused by the values.
+    pub(crate) fn num_rows(kind: TrieKind, max_txs: usize, max_calldata: usize) -> usize {
+        match kind {
+            TrieKind::Transactions => {
+                max_txs * (MAX_TX_RLP_STATIC_LEN + MAX_TRIE_OVERHEAD_PER_LEAF) + max_calldata
+            }
+            TrieKind::Receipts => max_txs * (MAX_RECEIPT_RLP_LEN + MAX_TRIE_OVERHEAD_PER_LEAF),
+            TrieKind::Withdrawals => {
+                MAX_WITHDRAWALS_PER_BLOCK * (MAX_WITHDRAWAL_RLP_LEN + MAX_TRIE_OVERHEAD_PER_LEAF)
+            }
+        }
+    }
+
+    /// Return the number of rows for the nodes of a trie, which has at least
+    /// the row of its root.
+    fn num_node_rows(kind: TrieKind, max_txs: usize) -> usize {
+        let max_leaves = match kind {
+            TrieKind::Transactions | TrieKind::Receipts => max_txs,
+            TrieKind::Withdrawals => MAX_WITHDRAWALS_PER_BLOCK,
+        };
+        (max_leaves * MAX_TRIE_NODES_PER_LEAF).max(1)
+    }
+
+    /// Return the nodes of the ordered trie of the values, and their rows.
+    fn trie_rows(
+        kind: TrieKind,
+        values: &[Vec<u8>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(Vec<TrieNode>, Vec<TrieRow<F>>), Error> {
+        let nodes = ordered_trie_nodes(values);
+        let leaf_indexes: HashMap<Vec<u8>, usize> = (0..values.len())
+            .map(|index| (ordered_trie_key(index), index))
+            .collect();
+        let word_rlc = |bytes: &[u8]| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&Word::from_big_endian(bytes).to_le_bytes(), challenge))
+        };
+        let check_child = |path: &[u8], child: &[u8]| {
+            if child.len() != 33 {
+                error!(
+                    "ordered trie node at path {:?} with an embedded child",
+                    path
+                );
+                return Err(Error::Synthesis);
+            }
+            Ok(())
+        };
+
+        let zero = Value::known(F::ZERO);
+        // (hash of the parent, whether it is a branch node, slot in the
+        // parent) of the nodes at the paths of the children.
+        let mut parents: HashMap<Vec<u8>, (Value<F>, bool, u8)> = HashMap::new();
+        let mut rows = Vec::with_capacity(nodes.len());
+        for (path, node) in nodes.iter() {
+            let hash = word_rlc(node.hash().as_bytes());
+            let (node_type, key, payload, payload_len, leaf_index, value_hash) = match node {
+                TrieNode::Branch(children) => {
+                    for (nibble, child) in children.iter().enumerate() {
+                        if child != &[0x80] {
+                            let child_path = [path.as_slice(), &[nibble as u8]].concat();
+                            check_child(&child_path, child)?;
+                            parents.insert(child_path, (hash, true, nibble as u8));
+                        }
+                    }
+                    (NodeType::Branch, vec![], zero, 0, 0, zero)
+                }
+                TrieNode::Extension(nibbles, child) => {
+                    let child_path = [path.as_slice(), nibbles.as_slice()].concat();
+                    check_child(&child_path, child)?;
+                    parents.insert(child_path, (hash, false, 0));
+                    (
+                        NodeType::Extension,
+                        nibbles.clone(),
+                        word_rlc(&child[1..]),
+                        32,
+                        0,
+                        zero,
+                    )
+                }
+                TrieNode::Leaf(nibbles, value) => (
+                    NodeType::ValueLeaf,
+                    nibbles.clone(),
+                    challenges
+                        .keccak_input()
+                        .map(|challenge| rlc::value(value.iter().rev(), challenge)),
+                    value.len(),
+                    leaf_indexes[&[path.as_slice(), nibbles.as_slice()].concat()],
+                    word_rlc(&keccak256(value)),
+                ),
+            };
+            let (parent_hash, is_branch_child, nibble) =
+                parents.get(path).copied().unwrap_or((zero, false, 0));
+            rows.push(TrieRow {
+                trie: kind as u64,
+                node_type: Some(node_type),
+                hash,
+                path_acc: nibbles_value(path),
+                path_len: path.len(),
+                key_acc: nibbles_value(&key),
+                key_len: key.len(),
+                payload,
+                payload_len,
+                parent_hash,
+                is_branch_child,
+                nibble,
+                leaf_index,
+                value_hash,
+            });
+        }
+        Ok((nodes.into_iter().map(|(_, node)| node).collect(), rows))
+    }
+
+    /// Assign the nodes of the ordered tries of the values, and return the
+    /// cells of their roots.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        tries: &[(TrieKind, Vec<Vec<u8>>)],
+        max_txs: usize,
+        max_calldata: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let empty_root = challenges.evm_word().map(|challenge| {
+            rlc::value(
+                &Word::from_big_endian(&keccak256([0x80])).to_le_bytes(),
+                challenge,
+            )
+        });
+        let mut nodes = Vec::new();
+        let mut sections = Vec::with_capacity(tries.len());
+        for (kind, values) in tries {
+            let (trie_nodes, rows) = Self::trie_rows(*kind, values, challenges)?;
+            let num_rows = Self::num_node_rows(*kind, max_txs);
+            if rows.len() > num_rows {
+                error!(
+                    "ordered trie {:?} nodes {} exceed the available rows {}",
+                    kind,
+                    rows.len(),
+                    num_rows
+                );
+                return Err(Error::Synthesis);
+            }
+            nodes.extend(trie_nodes.into_iter().map(|node| (node, false)));
+            sections.push((*kind, rows, num_rows));
+        }
+        let num_rows = tries
+            .iter()
+            .map(|(kind, _)| Self::num_rows(*kind, max_txs, max_calldata))
+            .sum();
+        self.nodes.assign(layouter, &nodes, num_rows, challenges)?;
+
+        layouter.assign_region(
+            || "ordered tries",
+            |mut region| {
+                // The enabled rows are surrounded by padding rows, which are
+                // queried at Rotation::next().
+                let padding = TrieRow::padding(0, Value::known(F::ZERO));
+                self.assign_row(&mut region, 0, &padding, false, false)?;
+                let mut offset = 1;
+                let mut roots = Vec::with_capacity(sections.len());
+                for (kind, rows, num_rows) in sections.iter() {
+                    let padding = TrieRow::padding(*kind as u64, empty_root);
+                    for (i, row) in rows
+                        .iter()
+                        .chain(iter::repeat(&padding))
+                        .take(*num_rows)
+                        .enumerate()
+                    {
+                        let hash = self.assign_row(&mut region, offset + i, row, true, i == 0)?;
+                        if i == 0 {
+                            roots.push(hash);
+                        }
+                    }
+                    offset += num_rows;
+                }
+                self.assign_row(&mut region, offset, &padding, false, false)?;
+                Ok(roots)
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &TrieRow<F>,
+        q_enable: bool,
+        q_first: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, q_enable as u64),
+            ("q_first", self.q_first, q_first as u64),
+            ("trie", self.trie, row.trie),
+        ] {
+            region.assign_fixed(
+                || format!("ordered trie {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("is_node", self.is_node, row.node_type.is_some() as u64),
+            (
+                "is_branch",
+                self.is_branch,
+                (row.node_type == Some(NodeType::Branch)) as u64,
+            ),
+            (
+                "is_ext",
+                self.is_ext,
+                (row.node_type == Some(NodeType::Extension)) as u64,
+            ),
+            (
+                "is_leaf",
+                self.is_leaf,
+                (row.node_type == Some(NodeType::ValueLeaf)) as u64,
+            ),
+            ("path_acc", self.path_acc, row.path_acc),
+            ("path_len", self.path_len, row.path_len as u64),
+            ("key_acc", self.key_acc, row.key_acc),
+            ("key_len", self.key_len, row.key_len as u64),
+            (
+                "key_pow",
+                self.key_pow,
+                row.node_type.map_or(0, |_| 16u64.pow(row.key_len as u32)),
+            ),
+            ("payload_len", self.payload_len, row.payload_len as u64),
+            (
+                "is_branch_child",
+                self.is_branch_child,
+                row.is_branch_child as u64,
+            ),
+            ("nibble", self.nibble, row.nibble as u64),
+            ("leaf_index", self.leaf_index, row.leaf_index as u64),
+        ] {
+            region.assign_advice(
+                || format!("ordered trie {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("payload", self.payload, row.payload),
+            ("parent_hash", self.parent_hash, row.parent_hash),
+            ("value_hash", self.value_hash, row.value_hash),
+        ] {
+            region.assign_advice(
+                || format!("ordered trie {}", name),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+        region.assign_advice(|| "ordered trie hash", self.hash, offset, || row.hash)
+    }
+}
+
+/// Value of the nibbles as a big-endian integer
+fn nibbles_value(nibbles: &[u8]) -> u64 {
+    nibbles
+        .iter()
+        .fold(0, |acc, &nibble| acc * 16 + nibble as u64)
+}
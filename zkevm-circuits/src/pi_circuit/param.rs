@@ -1,5 +1,19 @@
 /// Fixed by the spec
-pub(super) const BLOCK_LEN: usize = 7 + 256;
-pub(super) const EXTRA_LEN: usize = 2;
+pub(super) const BLOCK_LEN: usize = 7 + 13 + 256;
+pub(super) const EXTRA_LEN: usize = 6;
 pub(super) const ZERO_BYTE_GAS_COST: u64 = 4;
 pub(super) const NONZERO_BYTE_GAS_COST: u64 = 16;
+/// Bound of the bytes of the encodings of the trie nodes added by each leaf:
+/// a branch node with 16 hash children, an extension node and the headers and
+/// key of the leaf node.
+pub(super) const MAX_TRIE_OVERHEAD_PER_LEAF: usize = 532 + 41 + 13;
+/// Bound of the trie nodes added by each leaf: a branch node, an extension
+/// node and the leaf node.
+pub(super) const MAX_TRIE_NODES_PER_LEAF: usize = 3;
+/// Bound of the length of the encoding of a receipt, which includes the 256
+/// bytes of its logs bloom.
+pub(super) const MAX_RECEIPT_RLP_LEN: usize = 1024;
+/// Bound of the length of the encoding of a withdrawal: the list header, the
+/// index, the validator index and the amount of up to 9 bytes each, and the
+/// address of 21 bytes.
+pub(super) const MAX_WITHDRAWAL_RLP_LEN: usize = 1 + 3 * 9 + 21;
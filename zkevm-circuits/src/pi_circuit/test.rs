@@ -1,11 +1,12 @@
 #![allow(unused_imports)]
 use super::{dev::*, *};
 use crate::util::unusable_rows;
+use eth_types::geth_types::Log;
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
-use mock::{CORRECT_MOCK_TXS, MOCK_CHAIN_ID};
+use mock::{CORRECT_MOCK_TXS, MOCK_CHAIN_ID, MOCK_WITHDRAWALS};
 use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 
@@ -16,10 +17,26 @@ fn pi_circuit_unusable_rows() {
         unusable_rows::<Fr, PiCircuit::<Fr>>(PiCircuitParams {
             max_txs: 2,
             max_calldata: 8,
+            ..Default::default()
         }),
     )
 }
 
+/// Set successful receipts without logs for the transactions.
+fn set_receipts(public_data: &mut PublicData) {
+    public_data.receipts = public_data
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(i, tx)| Receipt {
+            transaction_type: tx.transaction_type.unwrap_or_default().as_u64(),
+            status: 1,
+            cumulative_gas_used: 21000 * (i as u64 + 1),
+            logs: vec![],
+        })
+        .collect();
+}
+
 fn run<F: Field>(
     k: u32,
     max_txs: usize,
@@ -65,6 +82,103 @@ fn test_simple_pi() {
             .transactions
             .push(CORRECT_MOCK_TXS[i].clone().into());
     }
+    set_receipts(&mut public_data);
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
+}
+
+#[test]
+fn test_block_header_pi() {
+    let max_txs = 8;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    for i in 0..2 {
+        public_data
+            .transactions
+            .push(CORRECT_MOCK_TXS[i].clone().into());
+    }
+    set_receipts(&mut public_data);
+    public_data.history_hashes = (1..=3u64).map(Word::from).collect();
+    public_data.parent_hash = H256::from_low_u64_be(3);
+    public_data.block_constants.number = 300.into();
+    public_data.block_constants.gas_limit = 30_000_000.into();
+    public_data.block_constants.timestamp = 1_700_000_000.into();
+    public_data.block_constants.base_fee = 7.into();
+    public_data.gas_used = 42000;
+    public_data.extra_data = vec![0xab; 32].into();
+    public_data.mix_hash = H256::repeat_byte(0x11);
+    public_data.nonce = H64::from_low_u64_be(0x42);
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
+}
+
+#[test]
+fn test_logs_bloom_pi() {
+    let max_txs = 8;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    for i in 0..3 {
+        public_data
+            .transactions
+            .push(CORRECT_MOCK_TXS[i].clone().into());
+    }
+    set_receipts(&mut public_data);
+    let log = |address: u64, topics: &[u64]| Log {
+        address: Address::from_low_u64_be(address),
+        topics: topics
+            .iter()
+            .map(|&topic| H256::from_low_u64_be(topic))
+            .collect(),
+        data: vec![0xcd; 40].into(),
+    };
+    // The second transaction has no logs, and the third one shares the address
+    // and a topic of the first one.
+    public_data.receipts[0].logs = vec![log(0xaa, &[1, 2]), log(0xbb, &[])];
+    public_data.receipts[2].logs = vec![log(0xaa, &[2, 3, 4, 5])];
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
+}
+
+#[test]
+fn test_withdrawals_pi() {
+    let max_txs = 8;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+    set_receipts(&mut public_data);
+    public_data.withdrawals = MOCK_WITHDRAWALS.clone();
+
+    let k = 17;
+    assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
+}
+
+#[test]
+fn test_uncles_pi() {
+    let max_txs = 8;
+    let max_calldata = 200;
+
+    let mut public_data = PublicData::default();
+    public_data
+        .transactions
+        .push(CORRECT_MOCK_TXS[0].clone().into());
+    set_receipts(&mut public_data);
+    public_data.block_constants.number = 300.into();
+    let uncle = |number: u64, miner: u8| BlockHeader {
+        number,
+        coinbase: Address::repeat_byte(miner),
+        base_fee: 7.into(),
+        extra_data: vec![miner; 4].into(),
+        ..Default::default()
+    };
+    public_data.uncles = vec![uncle(299, 0xaa), uncle(297, 0xbb)];
 
     let k = 17;
     assert_eq!(run::<Fr>(k, max_txs, max_calldata, public_data), Ok(()));
@@ -115,6 +229,7 @@ fn variadic_size_check() {
             .transactions
             .push(CORRECT_MOCK_TXS[i].clone().into());
     }
+    set_receipts(&mut pub_dat_1);
 
     let mut pub_dat_2 = PublicData {
         chain_id: *MOCK_CHAIN_ID,
@@ -127,6 +242,7 @@ fn variadic_size_check() {
             .transactions
             .push(CORRECT_MOCK_TXS[i].clone().into());
     }
+    set_receipts(&mut pub_dat_2);
 
     run_size_check::<Fr>(max_txs, max_calldata, [pub_dat_1, pub_dat_2]);
 }
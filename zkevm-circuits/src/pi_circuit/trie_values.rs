@@ -0,0 +1,1565 @@
+//! Circuit to verify the values of the receipts and withdrawals tries.
+//!
+//! The encoding of every receipt and withdrawal is laid out one byte per row,
+//! split into items of consecutive bytes for each field, as the encodings of
+//! the transactions are in the tx hash circuit.  The status, the cumulative
+//! gas used and the number of logs of a receipt are looked up in the
+//! TxReceipt rows of the `RwTable`, and the address, topics and data bytes of
+//! its logs in the TxLog rows, while the fields of a withdrawal are looked up
+//! in the `WithdrawalTable`, and every withdrawal of the table is looked up in
+//! the encodings.  The RLC and the length of every encoding are looked up in
+//! the values of the leaves of its trie at the same index, and the other way
+//! around.
+
+use crate::{
+    evm_circuit::util::constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+    impl_expr,
+    table::{
+        LookupTable, RwTable, TxFieldTag, TxLogFieldTag, TxReceiptFieldTag, TxTable,
+        WithdrawalTable,
+    },
+    tx_circuit::{rlp_list_header, rlp_list_len},
+    util::{build_tx_log_expression, Challenges, Expr},
+};
+use bus_mapping::operation::Target;
+use eth_types::{
+    geth_types::{Receipt, Withdrawal, MAX_WITHDRAWALS_PER_BLOCK},
+    Field, ToScalar,
+};
+use ethers_core::utils::rlp;
+use gadgets::{
+    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    rlp_item::{RlpItemChip, RlpItemConfig, RlpItemFlags, RlpItemRow},
+    util::{not, select, sum},
+};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, TableColumn,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::{iter, marker::PhantomData};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use super::{
+    ordered_trie::{OrderedTrieConfig, TrieKind},
+    param::{MAX_RECEIPT_RLP_LEN, MAX_WITHDRAWAL_RLP_LEN},
+};
+
+const MAX_DEGREE: usize = 9;
+
+/// Tag of the items of the encoding of a receipt or a withdrawal
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+enum ValueTag {
+    /// Rows after the encodings of all the values of a trie
+    Padding = 0,
+    /// Type of the transaction of a typed receipt (EIP-2718), which is not
+    /// RLP encoded
+    TxType,
+    /// Header of the RLP list of the fields of the value
+    ListHeader,
+    /// Status of the transaction
+    Status,
+    /// Gas used by the transactions of the block up to this one
+    CumulativeGasUsed,
+    /// Bloom filter of the logs
+    LogsBloom,
+    /// Header of the RLP list of the logs
+    Logs,
+    /// Header of the RLP list of the fields of a log
+    Log,
+    /// Address of a log
+    LogAddress,
+    /// Header of the RLP list of the topics of a log
+    LogTopics,
+    /// Topic of a log
+    LogTopic,
+    /// Data of a log
+    LogData,
+    /// Index of the withdrawal
+    WithdrawalIndex,
+    /// Index of the validator of the withdrawal
+    ValidatorIndex,
+    /// Recipient of the withdrawal
+    WithdrawalAddress,
+    /// Amount of the withdrawal, in Gwei
+    WithdrawalAmount,
+}
+
+impl From<ValueTag> for usize {
+    fn from(tag: ValueTag) -> Self {
+        tag as usize
+    }
+}
+
+impl Default for ValueTag {
+    fn default() -> Self {
+        Self::Padding
+    }
+}
+
+impl_expr!(ValueTag);
+
+impl ValueTag {
+    /// Tags of the fields of the values of the trie, for a receipt of the
+    /// given type.
+    fn sequence(trie: TrieKind, tx_type: u64) -> &'static [Self] {
+        use ValueTag::*;
+        match (trie, tx_type) {
+            (TrieKind::Receipts, 0) => &[ListHeader, Status, CumulativeGasUsed, LogsBloom, Logs],
+            (TrieKind::Receipts, _) => &[
+                TxType,
+                ListHeader,
+                Status,
+                CumulativeGasUsed,
+                LogsBloom,
+                Logs,
+            ],
+            (TrieKind::Withdrawals, _) => &[
+                ListHeader,
+                WithdrawalIndex,
+                ValidatorIndex,
+                WithdrawalAddress,
+                WithdrawalAmount,
+            ],
+            (TrieKind::Transactions, _) => {
+                unreachable!("the transactions are verified in the tx hash circuit")
+            }
+        }
+    }
+
+    /// TxReceipt field of the `RwTable` with the value of the item
+    fn field_tag(&self) -> Option<TxReceiptFieldTag> {
+        match self {
+            Self::Status => Some(TxReceiptFieldTag::PostStateOrStatus),
+            Self::CumulativeGasUsed => Some(TxReceiptFieldTag::CumulativeGasUsed),
+            _ => None,
+        }
+    }
+
+    /// Whether the item is a scalar, which is encoded without leading zeros
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Status
+                | Self::CumulativeGasUsed
+                | Self::WithdrawalIndex
+                | Self::ValidatorIndex
+                | Self::WithdrawalAmount
+        )
+    }
+
+    /// Whether the item is a string of bytes, whose value is their RLC
+    fn is_bytes(&self) -> bool {
+        matches!(self, Self::LogsBloom | Self::LogData)
+    }
+
+    /// Whether the item is the header of an RLP list
+    fn is_list(&self) -> bool {
+        matches!(
+            self,
+            Self::ListHeader | Self::Logs | Self::Log | Self::LogTopics
+        )
+    }
+
+    /// Pairs of tags of the consecutive items of the encodings of the values
+    /// of the trie, for a receipt of the given type.  The logs repeat, each
+    /// one with any number of topics, and they end after their header or the
+    /// data of a log.
+    fn transitions(trie: TrieKind, tx_type: u64) -> Vec<(Self, Self)> {
+        use ValueTag::*;
+        let mut transitions: Vec<_> = Self::sequence(trie, tx_type)
+            .windows(2)
+            .map(|tags| (tags[0], tags[1]))
+            .collect();
+        if trie == TrieKind::Receipts {
+            transitions.extend([
+                (Logs, Log),
+                (Log, LogAddress),
+                (LogAddress, LogTopics),
+                (LogTopics, LogTopic),
+                (LogTopics, LogData),
+                (LogTopic, LogTopic),
+                (LogTopic, LogData),
+                (LogData, Log),
+            ]);
+        }
+        transitions
+    }
+}
+
+/// Columns with values that only depend on the tag, which are constrained from
+/// it so that they can be used in low degree expressions and lookups.
+#[derive(Clone, Copy, Debug)]
+struct TagFlags {
+    field_tag: Column<Advice>,
+    has_field: Column<Advice>,
+    is_numeric: Column<Advice>,
+    is_bytes: Column<Advice>,
+    is_raw: Column<Advice>,
+    is_list: Column<Advice>,
+    is_list_header: Column<Advice>,
+    is_address: Column<Advice>,
+    is_logs: Column<Advice>,
+    is_log: Column<Advice>,
+    is_log_address: Column<Advice>,
+    is_topics: Column<Advice>,
+    is_topic: Column<Advice>,
+    is_data: Column<Advice>,
+}
+
+impl TagFlags {
+    fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            field_tag: meta.advice_column(),
+            has_field: meta.advice_column(),
+            is_numeric: meta.advice_column(),
+            is_bytes: meta.advice_column(),
+            is_raw: meta.advice_column(),
+            is_list: meta.advice_column(),
+            is_list_header: meta.advice_column(),
+            is_address: meta.advice_column(),
+            is_logs: meta.advice_column(),
+            is_log: meta.advice_column(),
+            is_log_address: meta.advice_column(),
+            is_topics: meta.advice_column(),
+            is_topic: meta.advice_column(),
+            is_data: meta.advice_column(),
+        }
+    }
+
+    /// The columns with the function that gives their value for a tag
+    #[allow(clippy::type_complexity)]
+    fn columns(&self) -> [(&'static str, Column<Advice>, fn(ValueTag) -> u64); 14] {
+        [
+            ("field_tag", self.field_tag, |tag: ValueTag| {
+                tag.field_tag().map_or(0, |field_tag| field_tag as u64)
+            }),
+            ("has_field", self.has_field, |tag: ValueTag| {
+                tag.field_tag().is_some() as u64
+            }),
+            ("is_numeric", self.is_numeric, |tag: ValueTag| {
+                tag.is_numeric() as u64
+            }),
+            ("is_bytes", self.is_bytes, |tag: ValueTag| {
+                tag.is_bytes() as u64
+            }),
+            ("is_raw", self.is_raw, |tag: ValueTag| {
+                (tag == ValueTag::TxType) as u64
+            }),
+            ("is_list", self.is_list, |tag: ValueTag| {
+                tag.is_list() as u64
+            }),
+            ("is_list_header", self.is_list_header, |tag: ValueTag| {
+                (tag == ValueTag::ListHeader) as u64
+            }),
+            ("is_address", self.is_address, |tag: ValueTag| {
+                matches!(tag, ValueTag::LogAddress | ValueTag::WithdrawalAddress) as u64
+            }),
+            ("is_logs", self.is_logs, |tag: ValueTag| {
+                (tag == ValueTag::Logs) as u64
+            }),
+            ("is_log", self.is_log, |tag: ValueTag| {
+                (tag == ValueTag::Log) as u64
+            }),
+            ("is_log_address", self.is_log_address, |tag: ValueTag| {
+                (tag == ValueTag::LogAddress) as u64
+            }),
+            ("is_topics", self.is_topics, |tag: ValueTag| {
+                (tag == ValueTag::LogTopics) as u64
+            }),
+            ("is_topic", self.is_topic, |tag: ValueTag| {
+                (tag == ValueTag::LogTopic) as u64
+            }),
+            ("is_data", self.is_data, |tag: ValueTag| {
+                (tag == ValueTag::LogData) as u64
+            }),
+        ]
+    }
+}
+
+/// Witness of a row of the encoding of a receipt or a withdrawal
+#[derive(Clone, Debug)]
+struct ValueRow<F> {
+    trie: u64,
+    id: usize,
+    tx_type: u64,
+    tag: ValueTag,
+    byte: u8,
+    item: RlpItemRow<F>,
+    content_rlc: Value<F>,
+    bytes_rlc: Value<F>,
+    bytes_len: usize,
+    list_end: usize,
+    log_end: usize,
+    topics_end: usize,
+    log_count: usize,
+    topic_count: usize,
+    withdrawal_index: Value<F>,
+    validator_index: Value<F>,
+    withdrawal_address: Value<F>,
+    is_first: bool,
+    is_last: bool,
+    is_padding: bool,
+}
+
+impl<F: Field> ValueRow<F> {
+    fn padding(trie: u64) -> Self {
+        Self {
+            trie,
+            id: 0,
+            tx_type: 0,
+            tag: ValueTag::Padding,
+            byte: 0,
+            item: RlpItemRow::padding(),
+            content_rlc: Value::known(F::ZERO),
+            bytes_rlc: Value::known(F::ZERO),
+            bytes_len: 0,
+            list_end: 0,
+            log_end: 0,
+            topics_end: 0,
+            log_count: 0,
+            topic_count: 0,
+            withdrawal_index: Value::known(F::ZERO),
+            validator_index: Value::known(F::ZERO),
+            withdrawal_address: Value::known(F::ZERO),
+            is_first: false,
+            is_last: false,
+            is_padding: true,
+        }
+    }
+}
+
+/// Config to verify the encodings of the receipts and the withdrawals
+#[derive(Clone, Debug)]
+pub(crate) struct TrieValuesConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    trie: Column<Fixed>,
+    // tx_id of a receipt, or id of a withdrawal in the `WithdrawalTable`.
+    id: Column<Advice>,
+    tx_type: Column<Advice>,
+    tag: BinaryNumberConfig<ValueTag, 4>,
+    flags: TagFlags,
+    byte: Column<Advice>,
+    // Decoding of the RLP items of the encodings
+    item: RlpItemConfig,
+    // RLC of the content of the item, which is the input of the logs bloom
+    // for the addresses and the topics of the logs.
+    content_rlc: Column<Advice>,
+    bytes_rlc: Column<Advice>,
+    bytes_len: Column<Advice>,
+    list_end: Column<Advice>,
+    // Ends of the current log and of its topics.
+    log_end: Column<Advice>,
+    topics_end: Column<Advice>,
+    at_list_end: IsZeroConfig<F>,
+    at_topics_end: IsZeroConfig<F>,
+    // Number of logs up to the row, which is the log_id of the current log,
+    // and number of topics of the current log up to the row.
+    log_count: Column<Advice>,
+    topic_count: Column<Advice>,
+    // Fields of a withdrawal before its amount, which are the same ones in
+    // all its rows.
+    withdrawal_index: Column<Advice>,
+    validator_index: Column<Advice>,
+    withdrawal_address: Column<Advice>,
+    is_first: Column<Advice>,
+    is_last: Column<Advice>,
+    is_padding: Column<Advice>,
+    u8_table: TableColumn,
+    // (trie, tx_type, tag, next_tag) of the consecutive items of the
+    // encodings, and (trie, tx_type, Padding, tag) of their first items.
+    transition_table: [TableColumn; 4],
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> TrieValuesConfig<F> {
+    /// Return a new TrieValuesConfig
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        ordered_trie: &OrderedTrieConfig<F>,
+        tx_table: &TxTable,
+        rw_table: &RwTable,
+        withdrawal_table: &WithdrawalTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let trie = meta.fixed_column();
+        let id = meta.advice_column();
+        let tx_type = meta.advice_column();
+        let tag = BinaryNumberChip::configure(meta, q_enable, None);
+        let flags = TagFlags::configure(meta);
+        let byte = meta.advice_column();
+        let content_rlc = meta.advice_column_in(SecondPhase);
+        let bytes_rlc = meta.advice_column_in(SecondPhase);
+        let bytes_len = meta.advice_column();
+        let list_end = meta.advice_column();
+        let log_end = meta.advice_column();
+        let topics_end = meta.advice_column();
+        let log_count = meta.advice_column();
+        let topic_count = meta.advice_column();
+        let withdrawal_index = meta.advice_column();
+        let validator_index = meta.advice_column();
+        let withdrawal_address = meta.advice_column();
+        let is_first = meta.advice_column();
+        let is_last = meta.advice_column();
+        let is_padding = meta.advice_column();
+        let u8_table = meta.lookup_table_column();
+        let transition_table = [(); 4].map(|_| meta.lookup_table_column());
+
+        let item = RlpItemChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            byte,
+            |meta| {
+                // Topics are accumulated as the RLC of their little-endian
+                // bytes, strings of bytes as their RLC and other values as
+                // big-endian integers.
+                let [is_topic, is_bytes, is_raw, is_list] =
+                    [flags.is_topic, flags.is_bytes, flags.is_raw, flags.is_list]
+                        .map(|column| meta.query_advice(column, Rotation::cur()));
+                RlpItemFlags {
+                    is_list: is_list.clone(),
+                    is_header_only: is_list,
+                    is_raw,
+                    multiplier: is_topic.clone() * challenges.evm_word()
+                        + is_bytes.clone() * challenges.keccak_input()
+                        + (1.expr() - is_topic - is_bytes) * 256.expr(),
+                }
+            },
+            3,
+            u8_table,
+        );
+        let RlpItemConfig {
+            is_header,
+            length,
+            index,
+            is_item_first,
+            is_item_last,
+            value_acc,
+            ..
+        } = item;
+
+        // 1 in the rows of the receipts trie and 0 in the rows of the
+        // withdrawals trie
+        let is_receipts = |meta: &mut VirtualCells<'_, F>| {
+            TrieKind::Withdrawals.expr() - meta.query_fixed(trie, Rotation::cur())
+        };
+        let is_content_first = |meta: &mut VirtualCells<'_, F>| item.is_content_first(meta);
+
+        let [at_list_end, at_topics_end] = [list_end, topics_end].map(|end| {
+            IsZeroChip::configure(
+                meta,
+                |meta| {
+                    meta.query_fixed(q_enable, Rotation::cur())
+                        * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+                },
+                |meta| {
+                    meta.query_advice(end, Rotation::cur())
+                        - meta.query_advice(bytes_len, Rotation::cur())
+                },
+                meta.advice_column(),
+            )
+        });
+
+        meta.create_gate("trie values padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_padding_next = meta.query_advice(is_padding, Rotation::next());
+            let q_first_next = meta.query_fixed(q_first, Rotation::next());
+            let is_padding = meta.query_advice(is_padding, Rotation::cur());
+            cb.require_boolean("is_padding is boolean", is_padding.clone());
+            let disabled = [is_first, is_last, is_item_last, is_header]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let tag = tag.value(Rotation::cur())(meta);
+            cb.condition(is_padding, |cb| {
+                cb.require_zero(
+                    "padding is followed by padding until the next trie",
+                    not::expr(q_first_next) * not::expr(is_padding_next),
+                );
+                cb.require_zero("tag is Padding in padding rows", tag);
+                for value in disabled {
+                    cb.require_zero("padding rows are not part of a value", value);
+                }
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("trie values first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_first = meta.query_advice(is_first, Rotation::cur());
+            cb.require_equal(
+                "the first row starts the first value unless the trie is empty",
+                is_first.clone(),
+                not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+            cb.require_zero(
+                "the id of the first value is 1",
+                is_first * (meta.query_advice(id, Rotation::cur()) - 1.expr()),
+            );
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("trie values tag", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            for (name, column, value) in flags.columns() {
+                let expected = sum::expr(
+                    ValueTag::iter()
+                        .filter(|&t| value(t) != 0)
+                        .map(|t| tag.value_equals(t, Rotation::cur())(meta) * value(t).expr()),
+                );
+                cb.require_equal(name, meta.query_advice(column, Rotation::cur()), expected);
+            }
+
+            let is_item_last = meta.query_advice(is_item_last, Rotation::cur());
+            let is_tx_type = meta.query_advice(flags.is_raw, Rotation::cur());
+            cb.require_zero(
+                "TxType is a single byte",
+                is_tx_type.clone() * not::expr(is_item_last),
+            );
+            cb.require_zero(
+                "TxType is the type of the tx",
+                is_tx_type
+                    * (meta.query_advice(byte, Rotation::cur())
+                        - meta.query_advice(tx_type, Rotation::cur())),
+            );
+
+            cb.require_zero(
+                "the value ends with the logs of a receipt or the amount of a withdrawal",
+                meta.query_advice(is_last, Rotation::cur())
+                    * not::expr(
+                        meta.query_advice(flags.is_logs, Rotation::cur())
+                            + meta.query_advice(flags.is_data, Rotation::cur())
+                            + tag.value_equals(ValueTag::WithdrawalAmount, Rotation::cur())(meta),
+                    ),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("trie values value", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_item_last_prev = meta.query_advice(is_item_last, Rotation::prev());
+            let q_first_next = meta.query_fixed(q_first, Rotation::next());
+            let [is_first_next, is_padding_next, id_next] = [is_first, is_padding, id]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let [byte_next, bytes_rlc_next, bytes_len_next] = [byte, bytes_rlc, bytes_len]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let values = [
+                id,
+                tx_type,
+                list_end,
+                withdrawal_index,
+                validator_index,
+                withdrawal_address,
+            ]
+            .map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let [id, byte, bytes_rlc, bytes_len, list_end] =
+                [id, byte, bytes_rlc, bytes_len, list_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_first, is_item_last, is_first, is_last] =
+                [is_item_first, is_item_last, is_first, is_last]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            for (name, value) in [
+                ("is_first is boolean", is_first.clone()),
+                ("is_last is boolean", is_last.clone()),
+            ] {
+                cb.require_boolean(name, value);
+            }
+            cb.require_equal(
+                "an item starts with the value or after the end of the previous item",
+                is_item_first,
+                is_first.clone() + not::expr(is_first.clone()) * is_item_last_prev,
+            );
+            cb.require_equal(
+                "a value starts after the end of the previous one unless padding follows",
+                is_first_next,
+                is_last.clone() * not::expr(is_padding_next.clone()),
+            );
+
+            cb.condition(is_first, |cb| {
+                cb.require_equal("bytes_len starts at 1", bytes_len.clone(), 1.expr());
+                cb.require_equal("bytes_rlc starts at the byte", bytes_rlc.clone(), byte);
+            });
+            cb.condition(is_last.clone(), |cb| {
+                cb.require_equal(
+                    "the value ends at the end of an item",
+                    is_item_last,
+                    1.expr(),
+                );
+                cb.require_equal(
+                    "the value ends at the end of the list",
+                    bytes_len.clone(),
+                    list_end,
+                );
+                cb.require_zero(
+                    "the next value of the trie has the next id",
+                    not::expr(q_first_next)
+                        * not::expr(is_padding_next.clone())
+                        * (id_next - id - 1.expr()),
+                );
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                cb.require_zero("the value continues in the next row", is_padding_next);
+                for (value, value_next) in values {
+                    cb.require_equal(
+                        "the values of the value are the same ones in all its rows",
+                        value_next,
+                        value,
+                    );
+                }
+                cb.require_equal(
+                    "bytes_len increases by 1",
+                    bytes_len_next,
+                    bytes_len + 1.expr(),
+                );
+                cb.require_equal(
+                    "bytes_rlc accumulates the bytes",
+                    bytes_rlc_next,
+                    bytes_rlc * challenges.keccak_input() + byte_next,
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("trie values item", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_content_first = is_content_first(meta);
+            let is_logs_bloom = tag.value_equals(ValueTag::LogsBloom, Rotation::cur())(meta);
+            let tag_next = tag.value(Rotation::next())(meta);
+            let tag = tag.value(Rotation::cur())(meta);
+            let [is_header_prev, content_rlc_prev] =
+                [is_header, content_rlc].map(|column| meta.query_advice(column, Rotation::prev()));
+            let [is_header, is_item_first, is_item_last, length, content_rlc] =
+                [is_header, is_item_first, is_item_last, length, content_rlc]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [byte, bytes_len, list_end] = [byte, bytes_len, list_end]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_list_header, is_address, is_topic] =
+                [flags.is_list_header, flags.is_address, flags.is_topic]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.condition(not::expr(is_item_last.clone()), |cb| {
+                cb.require_equal("the tag is the same one in the whole item", tag_next, tag);
+            });
+            cb.condition(is_list_header * is_item_last, |cb| {
+                cb.require_equal(
+                    "list_end is the end of the list",
+                    list_end,
+                    bytes_len + length.clone(),
+                );
+            });
+
+            cb.condition(is_content_first, |cb| {
+                cb.require_equal(
+                    "content_rlc starts at the first byte of the content",
+                    content_rlc.clone(),
+                    byte.clone(),
+                );
+            });
+            cb.condition(
+                not::expr(is_header) * not::expr(is_item_first) * not::expr(is_header_prev),
+                |cb| {
+                    cb.require_equal(
+                        "content_rlc accumulates the content",
+                        content_rlc,
+                        content_rlc_prev * challenges.keccak_input() + byte,
+                    );
+                },
+            );
+            cb.require_zero(
+                "an address has 20 bytes",
+                is_address * (length.clone() - 20.expr()),
+            );
+            cb.require_zero(
+                "a topic has 32 bytes",
+                is_topic * (length.clone() - 32.expr()),
+            );
+            cb.require_zero(
+                "the logs bloom has 256 bytes",
+                is_logs_bloom * (length - 256.expr()),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("trie values logs", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [is_logs, is_log, is_topics, is_topic, is_data] = [
+                flags.is_logs,
+                flags.is_log,
+                flags.is_topics,
+                flags.is_topic,
+                flags.is_data,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_log_next, is_topics_next, is_topic_next] =
+                [flags.is_log, flags.is_topics, flags.is_topic]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let [log_end_next, topics_end_next, log_count_next, topic_count_next] =
+                [log_end, topics_end, log_count, topic_count]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let is_item_first_next = meta.query_advice(is_item_first, Rotation::next());
+            let [log_end, topics_end, log_count, topic_count] =
+                [log_end, topics_end, log_count, topic_count]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_last, is_first, is_last, bytes_len, length, list_end] =
+                [is_item_last, is_first, is_last, bytes_len, length, list_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let at_list_end = at_list_end.expr();
+            let at_topics_end = at_topics_end.expr();
+
+            // The logs are the last field of a receipt, and each log is the
+            // list of its address, the list of its topics and its data, whose
+            // ends are set by the lengths of their headers.
+            cb.condition(is_logs * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "the logs end at the end of the receipt",
+                    bytes_len.clone() + length.clone(),
+                    list_end,
+                );
+                cb.require_equal(
+                    "the receipt has logs until its end",
+                    is_last.clone(),
+                    at_list_end.clone(),
+                );
+            });
+            cb.condition(is_data * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "the data ends at the end of the log",
+                    bytes_len.clone(),
+                    log_end.clone(),
+                );
+                cb.require_equal(
+                    "the receipt has logs until its end",
+                    is_last.clone(),
+                    at_list_end,
+                );
+            });
+            cb.condition(is_log * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "log_end is the end of the log",
+                    log_end.clone(),
+                    bytes_len.clone() + length.clone(),
+                );
+            });
+            cb.condition(is_topics * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "topics_end is the end of the topics",
+                    topics_end.clone(),
+                    bytes_len + length,
+                );
+            });
+            cb.condition((is_topics + is_topic) * is_item_last, |cb| {
+                cb.require_equal(
+                    "the log has topics until the end of its topics",
+                    is_topic_next.clone(),
+                    not::expr(at_topics_end),
+                );
+            });
+
+            cb.condition(is_first, |cb| {
+                cb.require_zero("log_count starts at 0", log_count.clone());
+                cb.require_zero("topic_count starts at 0", topic_count.clone());
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                let is_log_added = is_log_next.clone() * is_item_first_next.clone();
+                cb.require_equal(
+                    "log_count counts the logs",
+                    log_count_next,
+                    log_count + is_log_added.clone(),
+                );
+                cb.require_equal(
+                    "topic_count counts the topics of the log",
+                    topic_count_next,
+                    select::expr(
+                        is_log_added,
+                        0.expr(),
+                        topic_count + is_topic_next * is_item_first_next,
+                    ),
+                );
+                cb.condition(not::expr(is_log_next), |cb| {
+                    cb.require_equal(
+                        "log_end is the same one until the next log",
+                        log_end_next,
+                        log_end,
+                    );
+                });
+                cb.condition(not::expr(is_topics_next), |cb| {
+                    cb.require_equal(
+                        "topics_end is the same one until the next topics",
+                        topics_end_next,
+                        topics_end,
+                    );
+                });
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("trie values withdrawal", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_item_last = meta.query_advice(is_item_last, Rotation::cur());
+            let value_acc = meta.query_advice(value_acc, Rotation::cur());
+            for (name, field, column) in [
+                (
+                    "withdrawal_index is the index of the withdrawal",
+                    ValueTag::WithdrawalIndex,
+                    withdrawal_index,
+                ),
+                (
+                    "validator_index is the index of the validator",
+                    ValueTag::ValidatorIndex,
+                    validator_index,
+                ),
+                (
+                    "withdrawal_address is the recipient of the withdrawal",
+                    ValueTag::WithdrawalAddress,
+                    withdrawal_address,
+                ),
+            ] {
+                cb.condition(
+                    tag.value_equals(field, Rotation::cur())(meta) * is_item_last.clone(),
+                    |cb| {
+                        cb.require_equal(
+                            name,
+                            meta.query_advice(column, Rotation::cur()),
+                            value_acc.clone(),
+                        );
+                    },
+                );
+            }
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.lookup("trie values byte range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(byte, Rotation::cur()),
+                u8_table,
+            )]
+        });
+        meta.lookup("trie values numeric leading byte", |meta| {
+            // Scalars are encoded without leading zeros.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_numeric, Rotation::cur())
+                * is_content_first(meta);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - 1.expr()), u8_table)]
+        });
+
+        meta.lookup("trie values item transition", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * not::expr(meta.query_advice(is_last, Rotation::cur()));
+            [
+                meta.query_fixed(trie, Rotation::cur()),
+                meta.query_advice(tx_type, Rotation::cur()),
+                tag.value(Rotation::cur())(meta),
+                tag.value(Rotation::next())(meta),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+        meta.lookup("trie values first item", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            [
+                meta.query_fixed(trie, Rotation::cur()),
+                meta.query_advice(tx_type, Rotation::cur()),
+                ValueTag::Padding.expr(),
+                tag.value(Rotation::cur())(meta),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+
+        meta.lookup_any("trie values receipt tx type", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur())
+                * is_receipts(meta);
+            [
+                meta.query_advice(id, Rotation::cur()),
+                TxFieldTag::TxType.expr(),
+                0.expr(),
+                meta.query_advice(tx_type, Rotation::cur()),
+            ]
+            .into_iter()
+            .zip(tx_table.table_exprs(meta))
+            .map(|(input, table)| (enable.clone() * input, table))
+            .collect()
+        });
+
+        Self::lookup_rw_table(meta, "trie values receipt field", rw_table, id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * meta.query_advice(flags.has_field, Rotation::cur());
+            (
+                enable,
+                Target::TxReceipt.expr(),
+                0.expr(),
+                meta.query_advice(flags.field_tag, Rotation::cur()),
+                meta.query_advice(value_acc, Rotation::cur()),
+            )
+        });
+        Self::lookup_rw_table(
+            meta,
+            "trie values receipt log length",
+            rw_table,
+            id,
+            |meta| {
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_last, Rotation::cur())
+                    * is_receipts(meta);
+                (
+                    enable,
+                    Target::TxReceipt.expr(),
+                    0.expr(),
+                    TxReceiptFieldTag::LogLength.expr(),
+                    meta.query_advice(log_count, Rotation::cur()),
+                )
+            },
+        );
+        Self::lookup_rw_table(meta, "trie values log address", rw_table, id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * meta.query_advice(flags.is_log_address, Rotation::cur());
+            let address = build_tx_log_expression(
+                0.expr(),
+                TxLogFieldTag::Address.expr(),
+                meta.query_advice(log_count, Rotation::cur()),
+            );
+            (
+                enable,
+                Target::TxLog.expr(),
+                address,
+                0.expr(),
+                meta.query_advice(value_acc, Rotation::cur()),
+            )
+        });
+        Self::lookup_rw_table(meta, "trie values log topic", rw_table, id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * meta.query_advice(flags.is_topic, Rotation::cur());
+            let address = build_tx_log_expression(
+                meta.query_advice(topic_count, Rotation::cur()) - 1.expr(),
+                TxLogFieldTag::Topic.expr(),
+                meta.query_advice(log_count, Rotation::cur()),
+            );
+            (
+                enable,
+                Target::TxLog.expr(),
+                address,
+                0.expr(),
+                meta.query_advice(value_acc, Rotation::cur()),
+            )
+        });
+        Self::lookup_rw_table(meta, "trie values log data", rw_table, id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_data, Rotation::cur())
+                * not::expr(meta.query_advice(is_header, Rotation::cur()));
+            let address = build_tx_log_expression(
+                meta.query_advice(index, Rotation::cur()),
+                TxLogFieldTag::Data.expr(),
+                meta.query_advice(log_count, Rotation::cur()),
+            );
+            (
+                enable,
+                Target::TxLog.expr(),
+                address,
+                0.expr(),
+                meta.query_advice(byte, Rotation::cur()),
+            )
+        });
+
+        // (id, index, validator_index, address, amount) of the withdrawals,
+        // which are zero out of their last rows.
+        let withdrawal_exprs = |meta: &mut VirtualCells<'_, F>| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur())
+                * not::expr(is_receipts(meta));
+            [
+                id,
+                withdrawal_index,
+                validator_index,
+                withdrawal_address,
+                value_acc,
+            ]
+            .map(|column| enable.clone() * meta.query_advice(column, Rotation::cur()))
+        };
+        meta.lookup_any("trie values withdrawal", |meta| {
+            withdrawal_exprs(meta)
+                .into_iter()
+                .zip(withdrawal_table.table_exprs(meta))
+                .collect()
+        });
+        meta.lookup_any("trie values withdrawal of the withdrawal table", |meta| {
+            withdrawal_table
+                .table_exprs(meta)
+                .into_iter()
+                .zip(withdrawal_exprs(meta))
+                .collect()
+        });
+
+        // (trie, leaf_index, value_rlc, value_len) of the values, which are
+        // zero out of their last rows.
+        let value_exprs = |meta: &mut VirtualCells<'_, F>| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            [
+                meta.query_fixed(trie, Rotation::cur()),
+                meta.query_advice(id, Rotation::cur()) - 1.expr(),
+                meta.query_advice(bytes_rlc, Rotation::cur()),
+                meta.query_advice(bytes_len, Rotation::cur()),
+            ]
+            .map(|value| enable.clone() * value)
+        };
+        meta.lookup_any("trie values value in trie", |meta| {
+            value_exprs(meta)
+                .into_iter()
+                .zip(ordered_trie.value_exprs(meta))
+                .collect()
+        });
+        meta.lookup_any("trie values trie value", |meta| {
+            ordered_trie
+                .value_exprs(meta)
+                .into_iter()
+                .zip(value_exprs(meta))
+                .collect()
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            trie,
+            id,
+            tx_type,
+            tag,
+            flags,
+            byte,
+            item,
+            content_rlc,
+            bytes_rlc,
+            bytes_len,
+            list_end,
+            log_end,
+            topics_end,
+            at_list_end,
+            at_topics_end,
+            log_count,
+            topic_count,
+            withdrawal_index,
+            validator_index,
+            withdrawal_address,
+            is_first,
+            is_last,
+            is_padding,
+            u8_table,
+            transition_table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Return (tx_id, input_rlc, input_len) of the addresses and the topics
+    /// of the logs of the receipts, which are the inputs of their logs
+    /// blooms, and zero out of their last rows.
+    pub(crate) fn bloom_input_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 3] {
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.item.is_item_last, Rotation::cur())
+            * (meta.query_advice(self.flags.is_log_address, Rotation::cur())
+                + meta.query_advice(self.flags.is_topic, Rotation::cur()));
+        [self.id, self.content_rlc, self.item.length]
+            .map(|column| enable.clone() * meta.query_advice(column, Rotation::cur()))
+    }
+
+    /// Return (tx_id, bloom_rlc) of the logs blooms of the receipts, which
+    /// are zero out of their last rows.
+    pub(crate) fn logs_bloom_exprs(&self, meta: &mut VirtualCells<'_, F>) -> [Expression<F>; 2] {
+        // The strings of bytes are the logs bloom and the data of the logs.
+        let enable = meta.query_fixed(self.q_enable, Rotation::cur())
+            * meta.query_advice(self.item.is_item_last, Rotation::cur())
+            * (meta.query_advice(self.flags.is_bytes, Rotation::cur())
+                - meta.query_advice(self.flags.is_data, Rotation::cur()));
+        [self.id, self.item.value_acc]
+            .map(|column| enable.clone() * meta.query_advice(column, Rotation::cur()))
+    }
+
+    /// Look up (1, tag, id, address, field_tag, 0, value) as the (is_write,
+    /// tag, id, address, field_tag, storage_key, value) of a row of the
+    /// `RwTable` when enabled.
+    #[allow(clippy::type_complexity)]
+    fn lookup_rw_table(
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        rw_table: &RwTable,
+        id: Column<Advice>,
+        input: impl FnOnce(
+            &mut VirtualCells<'_, F>,
+        ) -> (
+            Expression<F>,
+            Expression<F>,
+            Expression<F>,
+            Expression<F>,
+            Expression<F>,
+        ),
+    ) {
+        meta.lookup_any(name, |meta| {
+            let id = meta.query_advice(id, Rotation::cur());
+            let (enable, tag, address, field_tag, value) = input(meta);
+            [1.expr(), tag, id, address, field_tag, 0.expr(), value]
+                .into_iter()
+                .zip(rw_table.table_exprs(meta).into_iter().skip(1))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+    }
+
+    /// Load the u8 table and the table of the transitions between the items.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "trie values u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        layouter.assign_table(
+            || "trie values transition table",
+            |mut table| {
+                let tries = (0..4)
+                    .map(|tx_type| (TrieKind::Receipts, tx_type))
+                    .chain(iter::once((TrieKind::Withdrawals, 0)));
+                let transitions = iter::once((0, 0, ValueTag::Padding, ValueTag::Padding)).chain(
+                    tries.flat_map(|(trie, tx_type)| {
+                        let sequence = ValueTag::sequence(trie, tx_type);
+                        iter::once((trie as u64, tx_type, ValueTag::Padding, sequence[0])).chain(
+                            ValueTag::transitions(trie, tx_type)
+                                .into_iter()
+                                .map(move |(tag, tag_next)| (trie as u64, tx_type, tag, tag_next)),
+                        )
+                    }),
+                );
+                for (offset, (trie, tx_type, tag, tag_next)) in transitions.enumerate() {
+                    for (&column, value) in self.transition_table.iter().zip([
+                        trie,
+                        tx_type,
+                        tag as u64,
+                        tag_next as u64,
+                    ]) {
+                        table.assign_cell(
+                            || format!("transition table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Return the number of rows for the encodings of the values of the
+    /// receipts or withdrawals trie.
+    pub(crate) fn num_rows(kind: TrieKind, max_txs: usize) -> usize {
+        match kind {
+            TrieKind::Receipts => max_txs * MAX_RECEIPT_RLP_LEN,
+            TrieKind::Withdrawals => MAX_WITHDRAWALS_PER_BLOCK * MAX_WITHDRAWAL_RLP_LEN,
+            TrieKind::Transactions => {
+                unreachable!("the transactions are verified in the tx hash circuit")
+            }
+        }
+    }
+
+    /// Return the items of the encoding of the receipt: its type, the header
+    /// of the list and its fields, and then the header, the address, the
+    /// header of the topics followed by the topics and the data of every log.
+    fn receipt_items(receipt: &Receipt) -> Vec<(ValueTag, Vec<u8>)> {
+        let mut logs = Vec::new();
+        for log in receipt.logs.iter() {
+            let address = rlp::encode(&log.address).to_vec();
+            let topics: Vec<Vec<u8>> = log
+                .topics
+                .iter()
+                .map(|topic| rlp::encode(topic).to_vec())
+                .collect();
+            let topics_len: usize = topics.iter().map(Vec::len).sum();
+            let topics_header = rlp_list_header(topics_len);
+            let data = rlp::encode(&log.data.to_vec()).to_vec();
+            let log_len = address.len() + topics_header.len() + topics_len + data.len();
+            logs.push((ValueTag::Log, rlp_list_header(log_len)));
+            logs.push((ValueTag::LogAddress, address));
+            logs.push((ValueTag::LogTopics, topics_header));
+            logs.extend(topics.into_iter().map(|topic| (ValueTag::LogTopic, topic)));
+            logs.push((ValueTag::LogData, data));
+        }
+        let logs_len: usize = logs.iter().map(|(_, bytes)| bytes.len()).sum();
+        let fields = [
+            (ValueTag::Status, rlp::encode(&receipt.status).to_vec()),
+            (
+                ValueTag::CumulativeGasUsed,
+                rlp::encode(&receipt.cumulative_gas_used).to_vec(),
+            ),
+            (
+                ValueTag::LogsBloom,
+                rlp::encode(&receipt.logs_bloom().to_vec()).to_vec(),
+            ),
+            (ValueTag::Logs, rlp_list_header(logs_len)),
+        ];
+        let list_len = fields.iter().map(|(_, bytes)| bytes.len()).sum::<usize>() + logs_len;
+        let tx_type = (receipt.transaction_type != 0)
+            .then(|| (ValueTag::TxType, vec![receipt.transaction_type as u8]));
+        tx_type
+            .into_iter()
+            .chain(iter::once((
+                ValueTag::ListHeader,
+                rlp_list_header(list_len),
+            )))
+            .chain(fields)
+            .chain(logs)
+            .collect()
+    }
+
+    /// Return the items of the encoding of the withdrawal: the header of the
+    /// list and its fields.
+    fn withdrawal_items(withdrawal: &Withdrawal) -> Vec<(ValueTag, Vec<u8>)> {
+        let fields = [
+            (
+                ValueTag::WithdrawalIndex,
+                rlp::encode(&withdrawal.index).to_vec(),
+            ),
+            (
+                ValueTag::ValidatorIndex,
+                rlp::encode(&withdrawal.validator_index).to_vec(),
+            ),
+            (
+                ValueTag::WithdrawalAddress,
+                rlp::encode(&withdrawal.address).to_vec(),
+            ),
+            (
+                ValueTag::WithdrawalAmount,
+                rlp::encode(&withdrawal.amount).to_vec(),
+            ),
+        ];
+        let list_len = fields.iter().map(|(_, bytes)| bytes.len()).sum();
+        iter::once((ValueTag::ListHeader, rlp_list_header(list_len)))
+            .chain(fields)
+            .collect()
+    }
+
+    /// Return the rows of the encoding of a value of the trie from its items,
+    /// with the index, the validator index and the address of a withdrawal.
+    fn value_rows(
+        trie: TrieKind,
+        id: usize,
+        tx_type: u64,
+        items: Vec<(ValueTag, Vec<u8>)>,
+        withdrawal: [Value<F>; 3],
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<ValueRow<F>> {
+        let list_end: usize = items.iter().map(|(_, bytes)| bytes.len()).sum();
+        let [withdrawal_index, validator_index, withdrawal_address] = withdrawal;
+        let (mut log_end, mut topics_end) = (0, 0);
+        let (mut log_count, mut topic_count) = (0, 0);
+
+        let mut rows: Vec<ValueRow<F>> = Vec::with_capacity(list_end);
+        let mut bytes_rlc = Value::known(F::ZERO);
+        for (tag, bytes) in items {
+            let multiplier = if tag == ValueTag::LogTopic {
+                challenges.evm_word()
+            } else if tag.is_bytes() {
+                challenges.keccak_input()
+            } else {
+                Value::known(F::from(256))
+            };
+            // The transaction type of typed receipts is a raw byte.
+            let item_rows = RlpItemRow::item_rows(
+                &bytes,
+                tag.is_list().then(|| rlp_list_len(&bytes)),
+                tag == ValueTag::TxType,
+                multiplier,
+            );
+            let length = item_rows[0].length;
+            match tag {
+                ValueTag::Log => {
+                    log_end = rows.len() + bytes.len() + length;
+                    log_count += 1;
+                    topic_count = 0;
+                }
+                ValueTag::LogTopics => topics_end = rows.len() + bytes.len() + length,
+                ValueTag::LogTopic => topic_count += 1,
+                _ => (),
+            }
+
+            let mut content_rlc = Value::known(F::ZERO);
+            for (&byte, item) in bytes.iter().zip(item_rows) {
+                let byte_value = Value::known(F::from(byte as u64));
+                content_rlc = if item.is_header {
+                    Value::known(F::ZERO)
+                } else {
+                    content_rlc * challenges.keccak_input() + byte_value
+                };
+                bytes_rlc = if rows.is_empty() {
+                    byte_value
+                } else {
+                    bytes_rlc * challenges.keccak_input() + byte_value
+                };
+                rows.push(ValueRow {
+                    trie: trie as u64,
+                    id,
+                    tx_type,
+                    tag,
+                    byte,
+                    item,
+                    content_rlc,
+                    bytes_rlc,
+                    bytes_len: rows.len() + 1,
+                    list_end,
+                    log_end,
+                    topics_end,
+                    log_count,
+                    topic_count,
+                    withdrawal_index,
+                    validator_index,
+                    withdrawal_address,
+                    is_first: rows.is_empty(),
+                    is_last: false,
+                    is_padding: false,
+                });
+            }
+        }
+        if let Some(row) = rows.last_mut() {
+            row.is_last = true;
+        }
+        rows
+    }
+
+    /// Assign the encodings of the receipts and of the withdrawals.
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        receipts: &[Receipt],
+        withdrawals: &[Withdrawal],
+        max_txs: usize,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let receipt_rows: Vec<ValueRow<F>> = receipts
+            .iter()
+            .enumerate()
+            .flat_map(|(i, receipt)| {
+                let rows = Self::value_rows(
+                    TrieKind::Receipts,
+                    i + 1,
+                    receipt.transaction_type,
+                    Self::receipt_items(receipt),
+                    [Value::known(F::ZERO); 3],
+                    challenges,
+                );
+                debug_assert_eq!(
+                    rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+                    receipt.rlp()
+                );
+                rows
+            })
+            .collect();
+        let withdrawal_rows: Vec<ValueRow<F>> = withdrawals
+            .iter()
+            .enumerate()
+            .flat_map(|(i, withdrawal)| {
+                let address = withdrawal
+                    .address
+                    .to_scalar()
+                    .expect("unexpected Address -> Scalar conversion failure");
+                let rows = Self::value_rows(
+                    TrieKind::Withdrawals,
+                    i + 1,
+                    0,
+                    Self::withdrawal_items(withdrawal),
+                    [
+                        F::from(withdrawal.index.as_u64()),
+                        F::from(withdrawal.validator_index.as_u64()),
+                        address,
+                    ]
+                    .map(Value::known),
+                    challenges,
+                );
+                debug_assert_eq!(
+                    rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+                    withdrawal.rlp()
+                );
+                rows
+            })
+            .collect();
+        let mut sections = Vec::with_capacity(2);
+        for (kind, rows) in [
+            (TrieKind::Receipts, receipt_rows),
+            (TrieKind::Withdrawals, withdrawal_rows),
+        ] {
+            let num_rows = Self::num_rows(kind, max_txs);
+            if rows.len() > num_rows {
+                error!(
+                    "trie values {:?} rows {} exceed the available rows {}",
+                    kind,
+                    rows.len(),
+                    num_rows
+                );
+                return Err(Error::Synthesis);
+            }
+            sections.push((kind, rows, num_rows));
+        }
+
+        layouter.assign_region(
+            || "trie values",
+            |mut region| {
+                // The enabled rows are surrounded by padding rows, which are
+                // queried at Rotation::prev() and Rotation::next().
+                let padding = ValueRow::padding(0);
+                self.assign_row(&mut region, 0, &padding, false, false)?;
+                let mut offset = 1;
+                for (kind, rows, num_rows) in sections.iter() {
+                    let padding = ValueRow::padding(*kind as u64);
+                    for (i, row) in rows
+                        .iter()
+                        .chain(iter::repeat(&padding))
+                        .take(*num_rows)
+                        .enumerate()
+                    {
+                        self.assign_row(&mut region, offset + i, row, true, i == 0)?;
+                    }
+                    offset += num_rows;
+                }
+                self.assign_row(&mut region, offset, &padding, false, false)
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &ValueRow<F>,
+        q_enable: bool,
+        q_first: bool,
+    ) -> Result<(), Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, q_enable as u64),
+            ("q_first", self.q_first, q_first as u64),
+            ("trie", self.trie, row.trie),
+        ] {
+            region.assign_fixed(
+                || format!("trie values {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        BinaryNumberChip::construct(self.tag).assign(region, offset, &row.tag)?;
+        RlpItemChip::construct(self.item).assign(region, offset, &row.item)?;
+        for (name, column, value) in self.flags.columns() {
+            region.assign_advice(
+                || format!("trie values {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value(row.tag))),
+            )?;
+        }
+        for (name, column, value) in [
+            ("id", self.id, row.id as u64),
+            ("tx_type", self.tx_type, row.tx_type),
+            ("byte", self.byte, row.byte as u64),
+            ("bytes_len", self.bytes_len, row.bytes_len as u64),
+            ("list_end", self.list_end, row.list_end as u64),
+            ("log_end", self.log_end, row.log_end as u64),
+            ("topics_end", self.topics_end, row.topics_end as u64),
+            ("log_count", self.log_count, row.log_count as u64),
+            ("topic_count", self.topic_count, row.topic_count as u64),
+            ("is_first", self.is_first, row.is_first as u64),
+            ("is_last", self.is_last, row.is_last as u64),
+            ("is_padding", self.is_padding, row.is_padding as u64),
+        ] {
+            region.assign_advice(
+                || format!("trie values {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("content_rlc", self.content_rlc, row.content_rlc),
+            ("bytes_rlc", self.bytes_rlc, row.bytes_rlc),
+            (
+                "withdrawal_index",
+                self.withdrawal_index,
+                row.withdrawal_index,
+            ),
+            ("validator_index", self.validator_index, row.validator_index),
+            (
+                "withdrawal_address",
+                self.withdrawal_address,
+                row.withdrawal_address,
+            ),
+        ] {
+            region.assign_advice(|| format!("trie values {}", name), column, offset, || value)?;
+        }
+        for (chip, end) in [
+            (&self.at_list_end, row.list_end),
+            (&self.at_topics_end, row.topics_end),
+        ] {
+            IsZeroChip::construct(chip.clone()).assign(
+                region,
+                offset,
+                Value::known(F::from(end as u64) - F::from(row.bytes_len as u64)),
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@
 pub use super::*;
 use crate::super_circuit::{test::block_1tx, SuperCircuit};
 use bus_mapping::circuit_input_builder::CircuitsParams;
+use eth_types::evm_types::{BlockReward, Hardfork};
 use halo2_proofs::{
     circuit::Value,
     dev::MockProver,
@@ -30,6 +31,15 @@ fn test_root_circuit() {
             max_bytecode: 512,
             max_evm_rows: 0,
             max_keccak_rows: 0,
+            max_ecrecover_calls: 0,
+            max_sha256_rows: 0,
+            max_modexp_calls: 0,
+            max_ec_add_calls: 0,
+            max_ec_mul_calls: 0,
+            max_ec_pairing_calls: 0,
+            max_mpt_rows: 0,
+            hardfork: Hardfork::default(),
+            block_reward: BlockReward::default(),
         };
         let (k, circuit, instance, _) =
             SuperCircuit::<_>::build(block_1tx(), circuits_params, TEST_MOCK_RANDOMNESS.into())
@@ -0,0 +1,719 @@
+//! The SHA-256 circuit implementation.
+//!
+//! Each 64 byte block of the padded input takes `NUM_ROWS_PER_BLOCK` rows: 4
+//! rows with the initial state (2 state words per row), 64 rows for the rounds
+//! and 4 rows with the final state.  The state and the message schedule words
+//! are decomposed into bits, which makes the bitwise operations of the
+//! compression function cheap.  On the last row of the last block of a hash
+//! `is_final` is set, and the row can be looked up in the `Sha256Table`.
+mod param;
+mod sha256_multi;
+mod util;
+
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+mod dev;
+#[cfg(any(feature = "test", test))]
+mod test;
+#[cfg(any(feature = "test", test, feature = "test-circuits"))]
+pub use dev::Sha256Circuit as TestSha256Circuit;
+
+use std::marker::PhantomData;
+
+use self::{
+    param::*,
+    sha256_multi::{get_num_sha256_blocks, multi_sha256, Sha256Row},
+    util::*,
+};
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    table::{LookupTable, Sha256Table},
+    util::{Challenges, SubCircuit, SubCircuitConfig},
+    witness,
+};
+use eth_types::Field;
+use gadgets::util::{not, select, sum, Expr};
+use halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::debug;
+
+/// Sha256CircuitConfig
+#[derive(Clone, Debug)]
+pub struct Sha256CircuitConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    q_start: Column<Fixed>,
+    q_input: Column<Fixed>,
+    q_input_first: Column<Fixed>,
+    q_length: Column<Fixed>,
+    q_input_last: Column<Fixed>,
+    q_extend: Column<Fixed>,
+    q_compression: Column<Fixed>,
+    q_end: Column<Fixed>,
+    q_squeeze: Column<Fixed>,
+    round_cst: Column<Fixed>,
+    word_w: [Column<Advice>; NUM_BITS_PER_WORD_W],
+    word_a: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    word_e: [Column<Advice>; NUM_BITS_PER_WORD_EXT],
+    is_paddings: [Column<Advice>; NUM_BYTES_PER_WORD],
+    data_rlcs: [Column<Advice>; NUM_BYTES_PER_WORD - 1],
+    /// The columns for other circuits to lookup SHA-256 hash results
+    pub sha256_table: Sha256Table,
+    _marker: PhantomData<F>,
+}
+
+/// Circuit configuration arguments
+pub struct Sha256CircuitConfigArgs<F: Field> {
+    /// Sha256Table
+    pub sha256_table: Sha256Table,
+    /// Challenges randomness
+    pub challenges: Challenges<Expression<F>>,
+}
+
+impl<F: Field> SubCircuitConfig<F> for Sha256CircuitConfig<F> {
+    type ConfigArgs = Sha256CircuitConfigArgs<F>;
+
+    /// Return a new Sha256CircuitConfig
+    fn new(
+        meta: &mut ConstraintSystem<F>,
+        Self::ConfigArgs {
+            sha256_table,
+            challenges,
+        }: Self::ConfigArgs,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let q_start = meta.fixed_column();
+        let q_input = meta.fixed_column();
+        let q_input_first = meta.fixed_column();
+        let q_length = meta.fixed_column();
+        let q_input_last = meta.fixed_column();
+        let q_extend = meta.fixed_column();
+        let q_compression = meta.fixed_column();
+        let q_end = meta.fixed_column();
+        let q_squeeze = meta.fixed_column();
+        let round_cst = meta.fixed_column();
+        let word_w = array_init::array_init(|_| meta.advice_column());
+        let word_a = array_init::array_init(|_| meta.advice_column());
+        let word_e = array_init::array_init(|_| meta.advice_column());
+        let is_paddings = array_init::array_init(|_| meta.advice_column());
+        let data_rlcs = array_init::array_init(|_| meta.advice_column_in(SecondPhase));
+
+        let is_final = sha256_table.is_enabled;
+        let length = sha256_table.input_len;
+        let data_rlc = sha256_table.input_rlc;
+        let hash_rlc = sha256_table.output_rlc;
+
+        let query_bits = |meta: &mut VirtualCells<F>, columns: &[Column<Advice>], rot: i32| {
+            columns
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation(rot)))
+                .collect::<Vec<_>>()
+        };
+        let start_new_hash = |meta: &mut VirtualCells<F>, rot| {
+            // A new hash is started when the previous hash is done or on the first row
+            meta.query_fixed(q_first, rot) + meta.query_advice(is_final, rot)
+        };
+
+        // Enforce the bits and the flags to be boolean
+        meta.create_gate("boolean checks", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for bit in query_bits(meta, &word_w, 0)
+                .into_iter()
+                .chain(query_bits(meta, &word_a, 0))
+                .chain(query_bits(meta, &word_e, 0))
+            {
+                cb.require_boolean("boolean bit", bit);
+            }
+            for is_padding in query_bits(meta, &is_paddings, 0) {
+                cb.require_boolean("boolean is_padding", is_padding);
+            }
+            cb.require_boolean(
+                "boolean is_final",
+                meta.query_advice(is_final, Rotation::cur()),
+            );
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        // `is_final` can only be set on the last row of a block, which also
+        // disables it on the first row.
+        meta.create_gate("is final", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_zero(
+                "is_final only on the last row of a block",
+                meta.query_advice(is_final, Rotation::cur())
+                    * not::expr(meta.query_fixed(q_squeeze, Rotation::cur())),
+            );
+            cb.gate(1.expr())
+        });
+
+        // The initial state of a block is the final state of the previous block,
+        // or the initial hash value when a new hash is started.
+        meta.create_gate("start", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let start_new_hash = start_new_hash(meta, Rotation::prev());
+            for idx in 0..NUM_START_ROWS {
+                for (name, columns, h) in [
+                    ("initial a", &word_a, H[NUM_START_ROWS - 1 - idx]),
+                    ("initial e", &word_e, H[NUM_STATE_WORDS - 1 - idx]),
+                ] {
+                    let word = decode::expr(&query_bits(meta, columns, idx as i32));
+                    let word_prev =
+                        decode::expr(&query_bits(meta, columns, idx as i32 - NUM_END_ROWS as i32));
+                    cb.require_equal(
+                        name,
+                        word,
+                        select::expr(start_new_hash.expr(), (h as u64).expr(), word_prev),
+                    );
+                }
+            }
+            // Reset the length and the data rlc at the start of a new hash
+            let not_start_new_hash = not::expr(start_new_hash);
+            cb.require_equal(
+                "initial length",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev()) * not_start_new_hash.expr(),
+            );
+            cb.require_equal(
+                "initial data rlc",
+                meta.query_advice(data_rlc, Rotation::cur()),
+                meta.query_advice(data_rlc, Rotation::prev()) * not_start_new_hash,
+            );
+            cb.gate(meta.query_fixed(q_start, Rotation::cur()))
+        });
+
+        // Keep length/data_rlc the same on rows where we don't absorb data
+        meta.create_gate("length and data rlc equality", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            cb.require_equal(
+                "length equality check",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev()),
+            );
+            cb.require_equal(
+                "data_rlc equality check",
+                meta.query_advice(data_rlc, Rotation::cur()),
+                meta.query_advice(data_rlc, Rotation::prev()),
+            );
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    - meta.query_fixed(q_first, Rotation::cur())
+                    - meta.query_fixed(q_start, Rotation::cur())
+                    - meta.query_fixed(q_input, Rotation::cur()),
+            )
+        });
+
+        // Absorb the input words, which are the first 16 words of the message
+        // schedule
+        let w_bits = |meta: &mut VirtualCells<F>, rot| query_bits(meta, &word_w, rot);
+        meta.create_gate("input", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w = w_bits(meta, 0);
+            let is_paddings = query_bits(meta, &is_paddings, 0);
+            // The input words are 32 bits, the big-endian bytes of the word
+            let input_bytes = to_be_bytes(&w[..NUM_BITS_PER_WORD]);
+            for bit in w[NUM_BITS_PER_WORD..].iter() {
+                cb.require_zero("input word is 32 bits", bit.expr());
+            }
+
+            // Length increases by the number of bytes that aren't padding
+            cb.require_equal(
+                "update length",
+                meta.query_advice(length, Rotation::cur()),
+                meta.query_advice(length, Rotation::prev())
+                    + sum::expr(
+                        is_paddings
+                            .iter()
+                            .map(|is_padding| not::expr(is_padding.expr())),
+                    ),
+            );
+
+            // Add the input bytes to `data_rlc`.  It has a variable length
+            // represented by `is_paddings`, which requires intermediate cells to
+            // keep the degree low.
+            let data_rlcs = data_rlcs
+                .iter()
+                .map(|&column| meta.query_advice(column, Rotation::cur()))
+                .chain([meta.query_advice(data_rlc, Rotation::cur())])
+                .collect::<Vec<_>>();
+            let mut data_rlc_prev = meta.query_advice(data_rlc, Rotation::prev());
+            for ((byte, is_padding), data_rlc) in input_bytes
+                .iter()
+                .zip(is_paddings.iter())
+                .zip(data_rlcs.iter())
+            {
+                cb.require_equal(
+                    "intermediate data rlc",
+                    data_rlc.expr(),
+                    select::expr(
+                        is_padding.expr(),
+                        data_rlc_prev.expr(),
+                        data_rlc_prev.expr() * challenges.keccak_input() + byte.expr(),
+                    ),
+                );
+                data_rlc_prev = data_rlc.expr();
+            }
+            cb.gate(meta.query_fixed(q_input, Rotation::cur()))
+        });
+
+        // Padding: the input is followed by 0x80, zero bytes, and the input
+        // length in bits as a 64 bit big-endian integer, which makes the padded
+        // input a multiple of 64 bytes.
+        meta.create_gate("padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let q_input = meta.query_fixed(q_input, Rotation::cur());
+            let q_input_first = meta.query_fixed(q_input_first, Rotation::cur());
+            let q_length = meta.query_fixed(q_length, Rotation::cur());
+            let q_input_last = meta.query_fixed(q_input_last, Rotation::cur());
+            let input_bytes = to_be_bytes(&w_bits(meta, 0)[..NUM_BITS_PER_WORD]);
+            let is_paddings_cur = query_bits(meta, &is_paddings, 0);
+            let last_is_padding = |meta: &mut VirtualCells<F>, rot| {
+                meta.query_advice(is_paddings[NUM_BYTES_PER_WORD - 1], Rotation(rot))
+            };
+
+            // The padding of the first byte of a block continues from the last
+            // byte of the previous block, unless a new hash is started.
+            let last_is_padding_prev_block = last_is_padding(
+                meta,
+                -((NUM_ROWS_PER_BLOCK - NUM_WORDS_TO_ABSORB + 1) as i32),
+            ) * not::expr(start_new_hash(
+                meta,
+                Rotation(-((NUM_START_ROWS + 1) as i32)),
+            ));
+            let last_is_padding_prev_row = last_is_padding(meta, -1);
+
+            // The length bytes are the 8 last padding bytes of the last block,
+            // which is the block where the byte before the length is padding.
+            let is_final_block = (q_length.expr() - q_input_last.expr())
+                * last_is_padding(meta, -1)
+                + q_input_last.expr() * last_is_padding(meta, -2);
+            let not_length = not::expr(is_final_block);
+
+            for (idx, (byte, is_padding)) in
+                input_bytes.iter().zip(is_paddings_cur.iter()).enumerate()
+            {
+                if idx == 0 {
+                    // First byte of the block
+                    let is_first_padding = is_padding.expr() - last_is_padding_prev_block.expr();
+                    cb.condition(q_input_first.expr(), |cb| {
+                        cb.require_boolean("padding step boolean", is_first_padding.expr());
+                    });
+                    cb.condition(q_input_first.expr() * is_padding.expr(), |cb| {
+                        cb.require_equal(
+                            "padding start/intermediate byte",
+                            byte.expr(),
+                            128.expr() * is_first_padding,
+                        );
+                    });
+                    // First byte of the other rows
+                    let is_first_padding = is_padding.expr() - last_is_padding_prev_row.expr();
+                    cb.condition(q_input.expr() - q_input_first.expr(), |cb| {
+                        cb.require_boolean("padding step boolean", is_first_padding.expr());
+                    });
+                    cb.condition(
+                        (q_input.expr() - q_input_first.expr())
+                            * is_padding.expr()
+                            * not_length.expr(),
+                        |cb| {
+                            cb.require_equal(
+                                "padding start/intermediate byte",
+                                byte.expr(),
+                                128.expr() * is_first_padding,
+                            );
+                        },
+                    );
+                } else {
+                    let is_first_padding = is_padding.expr() - is_paddings_cur[idx - 1].expr();
+                    cb.condition(q_input.expr(), |cb| {
+                        cb.require_boolean("padding step boolean", is_first_padding.expr());
+                    });
+                    cb.condition(
+                        q_input.expr() * is_padding.expr() * not_length.expr(),
+                        |cb| {
+                            cb.require_equal(
+                                "padding start/intermediate byte",
+                                byte.expr(),
+                                128.expr() * is_first_padding,
+                            );
+                        },
+                    );
+                }
+            }
+
+            // The last two words of the last block are the length in bits
+            cb.condition(q_input_last * last_is_padding(meta, -2), |cb| {
+                let w_prev = decode::expr(&w_bits(meta, -1)[..NUM_BITS_PER_WORD]);
+                let w = decode::expr(&w_bits(meta, 0)[..NUM_BITS_PER_WORD]);
+                cb.require_equal(
+                    "length in bits",
+                    w_prev * (1u64 << NUM_BITS_PER_WORD).expr() + w,
+                    meta.query_advice(length, Rotation::cur()) * NUM_BITS_PER_BYTE.expr(),
+                );
+            });
+            cb.gate(1.expr())
+        });
+
+        // Message schedule:
+        // w[i] = σ1(w[i - 2]) + w[i - 7] + σ0(w[i - 15]) + w[i - 16]
+        meta.create_gate("message schedule", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let w = decode::expr(&w_bits(meta, 0));
+            let w_2 = w_bits(meta, -2);
+            let w_7 = w_bits(meta, -7);
+            let w_15 = w_bits(meta, -15);
+            let w_16 = w_bits(meta, -16);
+            cb.require_equal(
+                "w extension",
+                w,
+                sigma(&w_2[..NUM_BITS_PER_WORD], ROTR_SHR_S1, true)
+                    + decode::expr(&w_7[..NUM_BITS_PER_WORD])
+                    + sigma(&w_15[..NUM_BITS_PER_WORD], ROTR_SHR_S0, true)
+                    + decode::expr(&w_16[..NUM_BITS_PER_WORD]),
+            );
+            cb.gate(meta.query_fixed(q_extend, Rotation::cur()))
+        });
+
+        // Compression function rounds, where the working variables are the
+        // new `a` and `e` words of the last 4 rounds.
+        meta.create_gate("compression", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let [a, b, c, d] =
+                [-1, -2, -3, -4].map(|rot| query_bits(meta, &word_a[..NUM_BITS_PER_WORD], rot));
+            let [e, f, g, h] =
+                [-1, -2, -3, -4].map(|rot| query_bits(meta, &word_e[..NUM_BITS_PER_WORD], rot));
+            let w = decode::expr(&w_bits(meta, 0)[..NUM_BITS_PER_WORD]);
+            let round_cst = meta.query_fixed(round_cst, Rotation::cur());
+
+            let ch = decode::expr(
+                &(0..NUM_BITS_PER_WORD)
+                    .map(|idx| ch::expr(e[idx].expr(), f[idx].expr(), g[idx].expr()))
+                    .collect::<Vec<_>>(),
+            );
+            let maj = decode::expr(
+                &(0..NUM_BITS_PER_WORD)
+                    .map(|idx| maj::expr(a[idx].expr(), b[idx].expr(), c[idx].expr()))
+                    .collect::<Vec<_>>(),
+            );
+            let t1 = decode::expr(&h) + sigma(&e, ROTR_SIGMA1, false) + ch + round_cst + w;
+            let t2 = sigma(&a, ROTR_SIGMA0, false) + maj;
+
+            cb.require_equal(
+                "new e",
+                decode::expr(&query_bits(meta, &word_e, 0)),
+                decode::expr(&d) + t1.expr(),
+            );
+            cb.require_equal(
+                "new a",
+                decode::expr(&query_bits(meta, &word_a, 0)),
+                t1 + t2,
+            );
+            cb.gate(meta.query_fixed(q_compression, Rotation::cur()))
+        });
+
+        // The final state of the block is the state after the rounds added to
+        // the initial state.
+        meta.create_gate("end", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            for (name, columns) in [("final a", &word_a), ("final e", &word_e)] {
+                let round = query_bits(meta, &columns[..NUM_BITS_PER_WORD], -(NUM_END_ROWS as i32));
+                let start = query_bits(
+                    meta,
+                    &columns[..NUM_BITS_PER_WORD],
+                    -((NUM_ROUNDS + NUM_END_ROWS) as i32),
+                );
+                cb.require_equal(
+                    name,
+                    decode::expr(&query_bits(meta, columns, 0)),
+                    decode::expr(&round) + decode::expr(&start),
+                );
+            }
+            cb.gate(meta.query_fixed(q_end, Rotation::cur()))
+        });
+
+        // Enforce logic for when this block is the last block for a hash
+        meta.create_gate("squeeze", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+            let is_final = meta.query_advice(is_final, Rotation::cur());
+            // The block is the last one when the byte before the length is
+            // padding, so that the length fits in the block.
+            let last_is_padding_in_block = meta.query_advice(
+                is_paddings[NUM_BYTES_PER_WORD - 1],
+                Rotation(-((NUM_ROWS_PER_BLOCK - 1 - NUM_START_ROWS - LAST_DATA_WORD) as i32)),
+            );
+            cb.require_equal(
+                "is_final needs to be the same as the last data byte is_padding",
+                is_final.expr(),
+                last_is_padding_in_block,
+            );
+            // The state words H0..H7 are on the last 4 rows of the block
+            let mut hash_bytes = Vec::new();
+            for columns in [&word_a, &word_e] {
+                for idx in 0..NUM_END_ROWS {
+                    let word = query_bits(meta, &columns[..NUM_BITS_PER_WORD], -(idx as i32));
+                    hash_bytes.extend(to_be_bytes(&word));
+                }
+            }
+            let hash_bytes_le = hash_bytes.into_iter().rev().collect::<Vec<_>>();
+            cb.condition(is_final, |cb| {
+                cb.require_equal(
+                    "hash rlc check",
+                    meta.query_advice(hash_rlc, Rotation::cur()),
+                    rlc::expr(&hash_bytes_le, challenges.evm_word()),
+                );
+            });
+            cb.gate(meta.query_fixed(q_squeeze, Rotation::cur()))
+        });
+
+        debug!("Degree: {}", meta.degree());
+        debug!("Minimum rows: {}", meta.minimum_rows());
+
+        Sha256CircuitConfig {
+            q_enable,
+            q_first,
+            q_start,
+            q_input,
+            q_input_first,
+            q_length,
+            q_input_last,
+            q_extend,
+            q_compression,
+            q_end,
+            q_squeeze,
+            round_cst,
+            word_w,
+            word_a,
+            word_e,
+            is_paddings,
+            data_rlcs,
+            sha256_table,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<F: Field> Sha256CircuitConfig<F> {
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        witness: &[Sha256Row<F>],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assign sha256 rows",
+            |mut region| {
+                for (offset, sha256_row) in witness.iter().enumerate() {
+                    self.set_row(&mut region, offset, sha256_row)?;
+                }
+                self.sha256_table.annotate_columns_in_region(&mut region);
+                self.annotate_circuit(&mut region);
+                Ok(())
+            },
+        )
+    }
+
+    fn set_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &Sha256Row<F>,
+    ) -> Result<(), Error> {
+        // Fixed selectors, from the position of the row in its block
+        let round = (offset > 0)
+            .then(|| ((offset - 1) % NUM_ROWS_PER_BLOCK) as isize - NUM_START_ROWS as isize)
+            .unwrap_or(isize::MIN);
+        let is_round = |range: std::ops::Range<usize>| {
+            round >= range.start as isize && round < range.end as isize
+        };
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, true),
+            ("q_first", self.q_first, offset == 0),
+            ("q_start", self.q_start, round == -(NUM_START_ROWS as isize)),
+            ("q_input", self.q_input, is_round(0..NUM_WORDS_TO_ABSORB)),
+            ("q_input_first", self.q_input_first, round == 0),
+            (
+                "q_length",
+                self.q_length,
+                is_round(NUM_WORDS_TO_ABSORB - 2..NUM_WORDS_TO_ABSORB),
+            ),
+            (
+                "q_input_last",
+                self.q_input_last,
+                round == NUM_WORDS_TO_ABSORB as isize - 1,
+            ),
+            (
+                "q_extend",
+                self.q_extend,
+                is_round(NUM_WORDS_TO_ABSORB..NUM_ROUNDS),
+            ),
+            ("q_compression", self.q_compression, is_round(0..NUM_ROUNDS)),
+            (
+                "q_end",
+                self.q_end,
+                is_round(NUM_ROUNDS..NUM_ROUNDS + NUM_END_ROWS),
+            ),
+            (
+                "q_squeeze",
+                self.q_squeeze,
+                round == (NUM_ROUNDS + NUM_END_ROWS) as isize - 1,
+            ),
+        ] {
+            region.assign_fixed(
+                || format!("assign {} {}", name, offset),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+
+        // Round constant
+        let round_cst = if is_round(0..NUM_ROUNDS) {
+            ROUND_CST[round as usize]
+        } else {
+            0
+        };
+        region.assign_fixed(
+            || format!("assign round cst {}", offset),
+            self.round_cst,
+            offset,
+            || Value::known(F::from(round_cst as u64)),
+        )?;
+
+        self.sha256_table.assign_row(
+            region,
+            offset,
+            [
+                Value::known(F::from(row.is_final as u64)),
+                row.data_rlc(),
+                Value::known(F::from(row.length as u64)),
+                row.hash_rlc,
+            ],
+        )?;
+
+        // Bits and padding flags
+        for (name, columns, bits) in [
+            ("w", &self.word_w[..], &row.w[..]),
+            ("a", &self.word_a[..], &row.a[..]),
+            ("e", &self.word_e[..], &row.e[..]),
+            ("is_padding", &self.is_paddings[..], &row.is_paddings[..]),
+        ] {
+            for (idx, (&column, &bit)) in columns.iter().zip(bits.iter()).enumerate() {
+                region.assign_advice(
+                    || format!("assign {} {} {}", name, idx, offset),
+                    column,
+                    offset,
+                    || Value::known(F::from(bit as u64)),
+                )?;
+            }
+        }
+
+        // Intermediate data rlcs
+        for (idx, (&column, &value)) in self.data_rlcs.iter().zip(row.data_rlcs.iter()).enumerate()
+        {
+            region.assign_advice(
+                || format!("assign data rlc {} {}", idx, offset),
+                column,
+                offset,
+                || value,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn annotate_circuit(&self, region: &mut Region<F>) {
+        region.name_column(|| "SHA256_q_enable", self.q_enable);
+        region.name_column(|| "SHA256_q_first", self.q_first);
+        region.name_column(|| "SHA256_q_start", self.q_start);
+        region.name_column(|| "SHA256_q_input", self.q_input);
+        region.name_column(|| "SHA256_q_input_first", self.q_input_first);
+        region.name_column(|| "SHA256_q_length", self.q_length);
+        region.name_column(|| "SHA256_q_input_last", self.q_input_last);
+        region.name_column(|| "SHA256_q_extend", self.q_extend);
+        region.name_column(|| "SHA256_q_compression", self.q_compression);
+        region.name_column(|| "SHA256_q_end", self.q_end);
+        region.name_column(|| "SHA256_q_squeeze", self.q_squeeze);
+        region.name_column(|| "SHA256_round_cst", self.round_cst);
+    }
+}
+
+/// Sha256Circuit
+#[derive(Default, Clone, Debug)]
+pub struct Sha256Circuit<F: Field> {
+    inputs: Vec<Vec<u8>>,
+    num_rows: usize,
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> SubCircuit<F> for Sha256Circuit<F> {
+    type Config = Sha256CircuitConfig<F>;
+
+    fn unusable_rows() -> usize {
+        SHA256_UNUSABLE_ROWS
+    }
+
+    /// The `block.circuits_params.max_sha256_rows` parameter, when set, sets
+    /// up the circuit to support a fixed number of blocks, independently of
+    /// the blocks required by `inputs`.
+    fn new_from_block(block: &witness::Block<F>) -> Self {
+        Self::new(
+            block.circuits_params.max_sha256_rows,
+            block.sha256_inputs.clone(),
+        )
+    }
+
+    /// Return the minimum number of rows required to prove the block
+    fn min_num_rows_block(block: &witness::Block<F>) -> (usize, usize) {
+        (
+            1 + block
+                .sha256_inputs
+                .iter()
+                .map(|bytes| get_num_sha256_blocks(bytes.len()) * NUM_ROWS_PER_BLOCK)
+                .sum::<usize>(),
+            block.circuits_params.max_sha256_rows,
+        )
+    }
+
+    /// Make the assignments to the Sha256Circuit
+    fn synthesize_sub(
+        &self,
+        config: &Self::Config,
+        challenges: &Challenges<Value<F>>,
+        layouter: &mut impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let witness = self.generate_witness(*challenges);
+        config.assign(layouter, witness.as_slice())
+    }
+}
+
+impl<F: Field> Sha256Circuit<F> {
+    /// Creates a new circuit instance
+    pub fn new(num_rows: usize, inputs: Vec<Vec<u8>>) -> Self {
+        Sha256Circuit {
+            inputs,
+            num_rows,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of SHA-256 blocks that can be done in this circuit
+    pub fn capacity(&self) -> Option<usize> {
+        if self.num_rows > 0 {
+            // Subtract one block for the first row and the unusable rows
+            Some((self.num_rows / NUM_ROWS_PER_BLOCK).saturating_sub(1))
+        } else {
+            None
+        }
+    }
+
+    /// Sets the witness using the data to be hashed
+    pub(crate) fn generate_witness(&self, challenges: Challenges<Value<F>>) -> Vec<Sha256Row<F>> {
+        multi_sha256(self.inputs.as_slice(), challenges, self.capacity())
+            .expect("Too many inputs for given capacity")
+    }
+}
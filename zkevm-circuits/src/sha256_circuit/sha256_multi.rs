@@ -0,0 +1,216 @@
+//! Witness generation of the SHA-256 circuit for multiple inputs.
+
+use super::{param::*, util::*};
+use crate::{evm_circuit::util::rlc, util::Challenges};
+use eth_types::Field;
+use halo2_proofs::{circuit::Value, plonk::Error};
+
+/// Witness values of a single row of the SHA-256 circuit
+#[derive(Clone, Debug)]
+pub(crate) struct Sha256Row<F> {
+    pub(crate) w: [bool; NUM_BITS_PER_WORD_W],
+    pub(crate) a: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) e: [bool; NUM_BITS_PER_WORD_EXT],
+    pub(crate) is_paddings: [bool; NUM_BYTES_PER_WORD],
+    /// The data rlc after each byte of the word, the last one being the
+    /// data rlc of the row
+    pub(crate) data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+    pub(crate) is_final: bool,
+    pub(crate) length: usize,
+    pub(crate) hash_rlc: Value<F>,
+}
+
+impl<F: Field> Sha256Row<F> {
+    fn new(
+        w: u64,
+        a: u64,
+        e: u64,
+        is_paddings: [bool; NUM_BYTES_PER_WORD],
+        data_rlcs: [Value<F>; NUM_BYTES_PER_WORD],
+        length: usize,
+    ) -> Self {
+        Self {
+            w: into_bits(w),
+            a: into_bits(a),
+            e: into_bits(e),
+            is_paddings,
+            data_rlcs,
+            is_final: false,
+            length,
+            hash_rlc: Value::known(F::ZERO),
+        }
+    }
+
+    /// The data rlc of the row
+    pub(crate) fn data_rlc(&self) -> Value<F> {
+        self.data_rlcs[NUM_BYTES_PER_WORD - 1]
+    }
+}
+
+/// Returns the number of blocks needed to hash `length` bytes
+pub(crate) fn get_num_sha256_blocks(length: usize) -> usize {
+    // The input is padded with 0x80 and the 8 bytes of its length in bits
+    (length + 1 + NUM_BYTES_LENGTH + RATE - 1) / RATE
+}
+
+fn sha256<F: Field>(rows: &mut Vec<Sha256Row<F>>, bytes: &[u8], challenges: Challenges<Value<F>>) {
+    let mut padded = bytes.to_vec();
+    padded.push(0x80);
+    while (padded.len() + NUM_BYTES_LENGTH) % RATE != 0 {
+        padded.push(0);
+    }
+    padded.extend_from_slice(&((bytes.len() * NUM_BITS_PER_BYTE) as u64).to_be_bytes());
+    let num_blocks = padded.len() / RATE;
+    debug_assert_eq!(num_blocks, get_num_sha256_blocks(bytes.len()));
+
+    let mask = u32::MAX as u64;
+    // The state words, which may contain the carry of the previous block
+    let mut hs: [u64; NUM_STATE_WORDS] = H.map(|h| h as u64);
+    let mut length = 0;
+    let mut data_rlc = Value::known(F::ZERO);
+
+    for (block_idx, block) in padded.chunks(RATE).enumerate() {
+        let is_final_block = block_idx == num_blocks - 1;
+
+        // Initial state of the block, in the order it's read by the first round
+        for idx in 0..NUM_START_ROWS {
+            rows.push(Sha256Row::new(
+                0,
+                hs[NUM_START_ROWS - 1 - idx],
+                hs[NUM_STATE_WORDS - 1 - idx],
+                [false; NUM_BYTES_PER_WORD],
+                [data_rlc; NUM_BYTES_PER_WORD],
+                length,
+            ));
+        }
+
+        // Rounds
+        let mut ws = Vec::with_capacity(NUM_ROUNDS);
+        let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] =
+            hs.map(|word| (word & mask) as u32);
+        let mut new_as = Vec::with_capacity(NUM_ROUNDS);
+        let mut new_es = Vec::with_capacity(NUM_ROUNDS);
+        for round in 0..NUM_ROUNDS {
+            let mut is_paddings = [false; NUM_BYTES_PER_WORD];
+            let mut data_rlcs = [data_rlc; NUM_BYTES_PER_WORD];
+            let w_ext = if round < NUM_WORDS_TO_ABSORB {
+                let word = &block[round * NUM_BYTES_PER_WORD..(round + 1) * NUM_BYTES_PER_WORD];
+                for (idx, byte) in word.iter().enumerate() {
+                    let byte_idx = block_idx * RATE + round * NUM_BYTES_PER_WORD + idx;
+                    is_paddings[idx] = byte_idx >= bytes.len();
+                    if !is_paddings[idx] {
+                        length += 1;
+                        data_rlc = data_rlc
+                            .zip(challenges.keccak_input())
+                            .map(|(data_rlc, r)| data_rlc * r + F::from(*byte as u64));
+                    }
+                    data_rlcs[idx] = data_rlc;
+                }
+                u32::from_be_bytes(word.try_into().unwrap()) as u64
+            } else {
+                sigma_value(ws[round - 2], ROTR_SHR_S1, true) as u64
+                    + ws[round - 7] as u64
+                    + sigma_value(ws[round - 15], ROTR_SHR_S0, true) as u64
+                    + ws[round - 16] as u64
+            };
+            ws.push((w_ext & mask) as u32);
+
+            let ch = (e & f) ^ (!e & g);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t1 = h as u64
+                + sigma_value(e, ROTR_SIGMA1, false) as u64
+                + ch as u64
+                + ROUND_CST[round] as u64
+                + ws[round] as u64;
+            let t2 = sigma_value(a, ROTR_SIGMA0, false) as u64 + maj as u64;
+            let new_e = d as u64 + t1;
+            let new_a = t1 + t2;
+            new_as.push(new_a);
+            new_es.push(new_e);
+
+            h = g;
+            g = f;
+            f = e;
+            e = (new_e & mask) as u32;
+            d = c;
+            c = b;
+            b = a;
+            a = (new_a & mask) as u32;
+
+            rows.push(Sha256Row::new(
+                w_ext,
+                new_a,
+                new_e,
+                is_paddings,
+                data_rlcs,
+                length,
+            ));
+        }
+
+        // Final state of the block, added to the initial state
+        for idx in 0..NUM_END_ROWS {
+            let round = NUM_ROUNDS - NUM_END_ROWS + idx;
+            let new_a = (new_as[round] & mask) + (hs[NUM_END_ROWS - 1 - idx] & mask);
+            let new_e = (new_es[round] & mask) + (hs[NUM_STATE_WORDS - 1 - idx] & mask);
+            rows.push(Sha256Row::new(
+                0,
+                new_a,
+                new_e,
+                [false; NUM_BYTES_PER_WORD],
+                [data_rlc; NUM_BYTES_PER_WORD],
+                length,
+            ));
+        }
+        for idx in 0..NUM_END_ROWS {
+            let row = &rows[rows.len() - NUM_END_ROWS + idx];
+            hs[NUM_END_ROWS - 1 - idx] = decode::value(&row.a);
+            hs[NUM_STATE_WORDS - 1 - idx] = decode::value(&row.e);
+        }
+
+        if is_final_block {
+            let digest: Vec<u8> = hs
+                .iter()
+                .flat_map(|word| ((word & mask) as u32).to_be_bytes())
+                .collect();
+            let last = rows.last_mut().unwrap();
+            last.is_final = true;
+            last.hash_rlc = challenges
+                .evm_word()
+                .map(|r| rlc::value(digest.iter().rev(), r));
+        }
+    }
+}
+
+/// Generates the witness of the SHA-256 circuit for the given inputs, padded
+/// with hashes of empty inputs up to `capacity` blocks when set.
+pub(crate) fn multi_sha256<F: Field>(
+    bytes: &[Vec<u8>],
+    challenges: Challenges<Value<F>>,
+    capacity: Option<usize>,
+) -> Result<Vec<Sha256Row<F>>, Error> {
+    // Dummy first row so that the first block starts a new hash, only
+    // `is_final` needs to be disabled.
+    let mut rows = vec![Sha256Row::new(
+        0,
+        0,
+        0,
+        [false; NUM_BYTES_PER_WORD],
+        [Value::known(F::ZERO); NUM_BYTES_PER_WORD],
+        0,
+    )];
+    // Actual sha256s
+    for bytes in bytes {
+        sha256(&mut rows, bytes, challenges);
+    }
+    if let Some(capacity) = capacity {
+        // Pad with no data hashes to the expected capacity
+        while rows.len() < 1 + capacity * NUM_ROWS_PER_BLOCK {
+            sha256(&mut rows, &[], challenges);
+        }
+        // Check that we are not over capacity
+        if rows.len() > 1 + capacity * NUM_ROWS_PER_BLOCK {
+            return Err(Error::BoundsFailure);
+        }
+    }
+    Ok(rows)
+}
@@ -0,0 +1,85 @@
+use super::*;
+use crate::util::unusable_rows;
+use eth_types::Field;
+use halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+use log::error;
+
+#[test]
+fn sha256_circuit_unusable_rows() {
+    assert_eq!(
+        Sha256Circuit::<Fr>::unusable_rows(),
+        unusable_rows::<Fr, Sha256Circuit::<Fr>>(()),
+    )
+}
+
+fn verify<F: Field>(k: u32, inputs: Vec<Vec<u8>>, success: bool) {
+    let circuit = Sha256Circuit::new(2usize.pow(k), inputs);
+
+    let prover = MockProver::<F>::run(k, &circuit, vec![]).unwrap();
+    let verify_result = prover.verify();
+    if verify_result.is_ok() != success {
+        if let Some(errors) = verify_result.err() {
+            for error in errors.iter() {
+                error!("{}", error);
+            }
+        }
+        panic!();
+    }
+}
+
+fn test_inputs() -> Vec<Vec<u8>> {
+    vec![
+        vec![],
+        b"abc".to_vec(),
+        // Longest input that fits in a single block
+        (0u8..55).collect::<Vec<_>>(),
+        // Shortest inputs that need the length in a new block
+        (0u8..56).collect::<Vec<_>>(),
+        (0u8..64).collect::<Vec<_>>(),
+        (0u8..119).collect::<Vec<_>>(),
+    ]
+}
+
+#[test]
+fn sha256_circuit_simple() {
+    verify::<Fr>(10, test_inputs(), true);
+}
+
+#[test]
+fn sha256_circuit_hash_rlc() {
+    let challenges = Challenges::mock(
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+        Value::known(Fr::from(0x100)),
+    );
+    let inputs = test_inputs();
+    let rows = multi_sha256(&inputs, challenges, None).unwrap();
+    let final_rows = rows.iter().filter(|row| row.is_final).collect::<Vec<_>>();
+    assert_eq!(final_rows.len(), inputs.len());
+    for (row, input) in final_rows.into_iter().zip(inputs.iter()) {
+        let [_, input_rlc, _, output_rlc] = Sha256Table::assignments(input, &challenges)[0];
+        assert_eq!(row.length, input.len());
+        row.data_rlc()
+            .zip(input_rlc)
+            .assert_if_known(|(data_rlc, input_rlc)| data_rlc == input_rlc);
+        row.hash_rlc
+            .zip(output_rlc)
+            .assert_if_known(|(hash_rlc, output_rlc)| hash_rlc == output_rlc);
+    }
+}
+
+#[test]
+fn variadic_size_check() {
+    let k = 10;
+    let num_rows = 2usize.pow(k);
+    // Empty
+    let circuit = Sha256Circuit::new(num_rows, vec![]);
+    let prover1 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    // Non-empty
+    let circuit = Sha256Circuit::new(num_rows, test_inputs());
+    let prover2 = MockProver::<Fr>::run(k, &circuit, vec![]).unwrap();
+
+    assert_eq!(prover1.fixed(), prover2.fixed());
+    assert_eq!(prover1.permutation(), prover2.permutation());
+}
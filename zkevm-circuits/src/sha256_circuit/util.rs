@@ -0,0 +1,141 @@
+//! Utility functions used in the SHA-256 circuit.
+
+use super::param::*;
+use eth_types::Field;
+use gadgets::util::Expr;
+use halo2_proofs::plonk::Expression;
+
+/// Decodes a little-endian list of bits into its value
+pub(crate) mod decode {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>]) -> Expression<F> {
+        let mut value = 0.expr();
+        let mut multiplier = F::ONE;
+        for bit in bits.iter() {
+            value = value + bit.expr() * multiplier;
+            multiplier *= F::from(2);
+        }
+        value
+    }
+
+    pub(crate) fn value(bits: &[bool]) -> u64 {
+        bits.iter()
+            .rev()
+            .fold(0, |acc, &bit| (acc << 1) + bit as u64)
+    }
+}
+
+/// Rotates the bits of a word to the right
+pub(crate) mod rotate {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        let mut rotated = bits.to_vec();
+        rotated.rotate_left(count);
+        rotated
+    }
+}
+
+/// Shifts the bits of a word to the right
+pub(crate) mod shift {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(bits: &[Expression<F>], count: usize) -> Vec<Expression<F>> {
+        let mut shifted = bits[count..].to_vec();
+        shifted.resize(bits.len(), 0.expr());
+        shifted
+    }
+}
+
+/// XOR of three bits
+pub(crate) mod xor3 {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(
+        a: Expression<F>,
+        b: Expression<F>,
+        c: Expression<F>,
+    ) -> Expression<F> {
+        a.clone() + b.clone() + c.clone()
+            - 2.expr() * (a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone())
+            + 4.expr() * a * b * c
+    }
+}
+
+/// Choose bit: `if e { f } else { g }`
+pub(crate) mod ch {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(
+        e: Expression<F>,
+        f: Expression<F>,
+        g: Expression<F>,
+    ) -> Expression<F> {
+        g.clone() + e * (f - g)
+    }
+}
+
+/// Majority bit of three bits
+pub(crate) mod maj {
+    use super::*;
+
+    pub(crate) fn expr<F: Field>(
+        a: Expression<F>,
+        b: Expression<F>,
+        c: Expression<F>,
+    ) -> Expression<F> {
+        a.clone() * b.clone() + a.clone() * c.clone() + b.clone() * c.clone() - 2.expr() * a * b * c
+    }
+}
+
+/// Sum of the XOR of the three given rotations (or shifts, when `shr` is
+/// set, for the last one) of a word
+pub(crate) fn sigma<F: Field>(
+    bits: &[Expression<F>],
+    [rot_0, rot_1, rot_2]: [usize; 3],
+    shr: bool,
+) -> Expression<F> {
+    let r0 = rotate::expr(bits, rot_0);
+    let r1 = rotate::expr(bits, rot_1);
+    let r2 = if shr {
+        shift::expr(bits, rot_2)
+    } else {
+        rotate::expr(bits, rot_2)
+    };
+    decode::expr(
+        &(0..bits.len())
+            .map(|idx| xor3::expr(r0[idx].clone(), r1[idx].clone(), r2[idx].clone()))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Splits the bits of a word into its big-endian bytes
+pub(crate) fn to_be_bytes<F: Field>(bits: &[Expression<F>]) -> Vec<Expression<F>> {
+    debug_assert_eq!(bits.len(), NUM_BITS_PER_WORD);
+    bits.chunks(NUM_BITS_PER_BYTE)
+        .rev()
+        .map(decode::expr)
+        .collect()
+}
+
+/// Returns the `N` little-endian bits of `value`
+pub(crate) fn into_bits<const N: usize>(value: u64) -> [bool; N] {
+    debug_assert!(
+        N == 64 || value >> N == 0,
+        "value doesn't fit in {} bits",
+        N
+    );
+    std::array::from_fn(|idx| (value >> idx) & 1 == 1)
+}
+
+/// Value of the SHA-256 `Σ`/`σ` functions, with the last operation being a
+/// shift when `shr` is set
+pub(crate) fn sigma_value(x: u32, [rot_0, rot_1, rot_2]: [usize; 3], shr: bool) -> u32 {
+    let last = if shr {
+        x >> rot_2
+    } else {
+        x.rotate_right(rot_2 as u32)
+    };
+    x.rotate_right(rot_0 as u32) ^ x.rotate_right(rot_1 as u32) ^ last
+}
@@ -321,6 +321,7 @@ impl<F: Field> StateCircuitConfig<F> {
                             MPTProofType::StorageMod as u64
                         }
                     }
+                    Rw::AccountStorageClear { .. } => MPTProofType::StorageCleared as u64,
                     Rw::Account { field_tag, .. } => {
                         if pair[0].is_zero_vartime()
                             && pair[1].is_zero_vartime()
@@ -448,7 +449,10 @@ impl<F: Field> SubCircuit<F> for StateCircuit<F> {
     type Config = StateCircuitConfig<F>;
 
     fn new_from_block(block: &witness::Block<F>) -> Self {
-        Self::new(block.rws.clone(), block.circuits_params.max_rws)
+        Self {
+            updates: block.mpt_updates.clone(),
+            ..Self::new(block.rws.clone(), block.circuits_params.max_rws)
+        }
     }
 
     fn unusable_rows() -> usize {
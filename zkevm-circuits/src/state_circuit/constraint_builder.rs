@@ -7,7 +7,7 @@ use crate::{
         param::N_BYTES_WORD,
         util::{math_gadget::generate_lagrange_base_polynomial, not},
     },
-    table::{AccountFieldTag, MPTProofType},
+    table::{AccountFieldTag, MPTProofType, TxAccountFlagFieldTag},
     util::Expr,
 };
 use bus_mapping::operation::Target;
@@ -132,6 +132,12 @@ impl<F: Field> ConstraintBuilder<F> {
         self.condition(q.tag_matches(Target::TxLog), |cb| {
             cb.build_tx_log_constraints(q)
         });
+        self.condition(q.tag_matches(Target::TransientStorage), |cb| {
+            cb.build_transient_storage_constraints(q)
+        });
+        self.condition(q.tag_matches(Target::TxAccountFlag), |cb| {
+            cb.build_tx_account_flag_constraints(q)
+        });
     }
 
     fn build_general_constraints(&mut self, q: &Queries<F>) {
@@ -278,17 +284,39 @@ impl<F: Field> ConstraintBuilder<F> {
 
     fn build_account_storage_constraints(&mut self, q: &Queries<F>) {
         // TODO: cold VS warm
-        // ref. spec 4.0. Unused keys are 0
-        self.require_zero("field_tag is 0 for AccountStorage", q.field_tag());
+        // ref. spec 4.0. Unused keys are 0, except for the field_tag of the
+        // clears of the storage, which are writes of 1 over 0 at storage_key 0.
+        // StorageFieldTag::Clear is 1, so the field_tag is the flag of the
+        // clears.
+        let is_clear = q.field_tag();
+        self.require_boolean(
+            "field_tag is 0 or Clear for AccountStorage",
+            is_clear.clone(),
+        );
+        self.condition(is_clear.clone(), |cb| {
+            cb.require_zero(
+                "storage_key is 0 for the clears of the storage",
+                q.rw_table.storage_key.clone(),
+            );
+            cb.require_equal(
+                "the clears of the storage are writes",
+                q.is_write(),
+                1.expr(),
+            );
+            cb.require_equal("the clears of the storage write 1", q.value(), 1.expr());
+            cb.require_zero("the clears of the storage write over 0", q.initial_value());
+        });
 
         // value = 0 means the leaf doesn't exist. 0->0 transition requires a
         // non-existing proof.
         let is_non_exist = q.is_non_exist();
         self.require_equal(
-            "mpt_proof_type is field_tag or NonExistingStorageProof",
+            "mpt_proof_type is field_tag or NonExistingStorageProof or StorageCleared",
             q.mpt_proof_type(),
-            is_non_exist.expr() * MPTProofType::NonExistingStorageProof.expr()
-                + (1.expr() - is_non_exist) * MPTProofType::StorageMod.expr(),
+            is_clear.clone() * MPTProofType::StorageCleared.expr()
+                + (1.expr() - is_clear)
+                    * (is_non_exist.expr() * MPTProofType::NonExistingStorageProof.expr()
+                        + (1.expr() - is_non_exist) * MPTProofType::StorageMod.expr()),
         );
 
         // ref. spec 4.1. MPT lookup for last access to (address, storage_key)
@@ -322,6 +350,64 @@ impl<F: Field> ConstraintBuilder<F> {
         });
     }
 
+    fn build_transient_storage_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero("field_tag is 0 for TransientStorage", q.field_tag());
+        // The id is the tx_id, so every transaction starts with an empty transient
+        // storage.
+        self.require_zero("initial TransientStorage value is 0", q.initial_value());
+
+        self.require_equal(
+            "state_root is unchanged for TransientStorage",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+    }
+
+    fn build_tx_account_flag_constraints(&mut self, q: &Queries<F>) {
+        self.require_zero(
+            "field_tag is Created or Destructed for TxAccountFlag",
+            (q.field_tag() - TxAccountFlagFieldTag::Created.expr())
+                * (q.field_tag() - TxAccountFlagFieldTag::Destructed.expr()),
+        );
+        self.require_zero(
+            "storage_key is 0 for TxAccountFlag",
+            q.rw_table.storage_key.clone(),
+        );
+        self.require_boolean("TxAccountFlag value is boolean", q.value());
+        self.require_zero("initial TxAccountFlag value is false", q.initial_value());
+
+        self.require_equal(
+            "state_root is unchanged for TxAccountFlag",
+            q.state_root(),
+            q.state_root_prev(),
+        );
+
+        self.condition(q.not_first_access.clone(), |cb| {
+            cb.require_equal(
+                "value column at Rotation::prev() equals value_prev at Rotation::cur()",
+                q.rw_table.value_prev.clone(),
+                q.value_prev_column(),
+            );
+        });
+
+        // Every self destructed account has to be deleted by a DestructAccount step
+        // before the end of the transaction, which resets the flag.
+        self.condition(q.last_access(), |cb| {
+            cb.require_zero(
+                "Destructed flag is reset at the last access",
+                (q.field_tag() - TxAccountFlagFieldTag::Created.expr()) * q.value(),
+            );
+        });
+    }
+
     fn build_tx_access_list_account_constraints(&mut self, q: &Queries<F>) {
         self.require_zero("field_tag is 0 for TxAccessListAccount", q.field_tag());
         self.require_zero(
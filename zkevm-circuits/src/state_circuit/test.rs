@@ -1,7 +1,10 @@
 #![allow(unused_imports)]
 pub use super::{dev::*, *};
 use crate::{
-    table::{AccountFieldTag, CallContextFieldTag, TxLogFieldTag, TxReceiptFieldTag},
+    table::{
+        AccountFieldTag, CallContextFieldTag, TxAccountFlagFieldTag, TxLogFieldTag,
+        TxReceiptFieldTag,
+    },
     util::{unusable_rows, SubCircuit},
     witness::{MptUpdates, Rw, RwMap},
 };
@@ -912,6 +915,142 @@ fn bad_initial_tx_refund_value() {
     );
 }
 
+#[test]
+fn transient_storage_reset_per_tx() {
+    let rows = vec![
+        Rw::TransientStorage {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address: address!("0x0000000000000000000000000000000000000001"),
+            storage_key: U256::one(),
+            value: U256::from(300),
+            value_prev: U256::zero(),
+        },
+        Rw::TransientStorage {
+            rw_counter: 2,
+            is_write: false,
+            tx_id: 1,
+            account_address: address!("0x0000000000000000000000000000000000000001"),
+            storage_key: U256::one(),
+            value: U256::from(300),
+            value_prev: U256::from(300),
+        },
+        Rw::TransientStorage {
+            rw_counter: 3,
+            is_write: false,
+            tx_id: 2,
+            account_address: address!("0x0000000000000000000000000000000000000001"),
+            storage_key: U256::one(),
+            value: U256::zero(),
+            value_prev: U256::zero(),
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn bad_initial_transient_storage_value() {
+    let rows = vec![Rw::TransientStorage {
+        rw_counter: 1,
+        is_write: false,
+        tx_id: 2,
+        account_address: address!("0x0000000000000000000000000000000000000001"),
+        storage_key: U256::one(),
+        value: U256::from(300),
+        value_prev: U256::zero(),
+    }];
+
+    assert_error_matches(verify(rows), "first access reads don't change value");
+}
+
+#[test]
+fn tx_account_flag_destructed_reset() {
+    let account_address = address!("0x0000000000000000000000000000000000000001");
+    let rows = vec![
+        Rw::TxAccountFlag {
+            rw_counter: 1,
+            is_write: true,
+            tx_id: 1,
+            account_address,
+            field_tag: TxAccountFlagFieldTag::Created,
+            value: true,
+            value_prev: false,
+        },
+        Rw::TxAccountFlag {
+            rw_counter: 2,
+            is_write: true,
+            tx_id: 1,
+            account_address,
+            field_tag: TxAccountFlagFieldTag::Destructed,
+            value: true,
+            value_prev: false,
+        },
+        Rw::TxAccountFlag {
+            rw_counter: 3,
+            is_write: true,
+            tx_id: 1,
+            account_address,
+            field_tag: TxAccountFlagFieldTag::Destructed,
+            value: false,
+            value_prev: true,
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
+#[test]
+fn tx_account_flag_destructed_not_reset() {
+    let rows = vec![Rw::TxAccountFlag {
+        rw_counter: 1,
+        is_write: true,
+        tx_id: 1,
+        account_address: address!("0x0000000000000000000000000000000000000001"),
+        field_tag: TxAccountFlagFieldTag::Destructed,
+        value: true,
+        value_prev: false,
+    }];
+
+    assert_error_matches(verify(rows), "Destructed flag is reset at the last access");
+}
+
+#[test]
+fn storage_clear_between_txs() {
+    let account_address = address!("0x0000000000000000000000000000000000000001");
+    let rows = vec![
+        Rw::AccountStorage {
+            rw_counter: 1,
+            is_write: true,
+            account_address,
+            storage_key: U256::one(),
+            value: U256::from(300),
+            value_prev: U256::from(200),
+            tx_id: 1,
+            committed_value: U256::from(200),
+        },
+        Rw::AccountStorageClear {
+            rw_counter: 2,
+            tx_id: 1,
+            account_address,
+        },
+        // The slot is read as 0 after the clear.
+        Rw::AccountStorage {
+            rw_counter: 3,
+            is_write: false,
+            account_address,
+            storage_key: U256::one(),
+            value: U256::zero(),
+            value_prev: U256::zero(),
+            tx_id: 2,
+            committed_value: U256::zero(),
+        },
+    ];
+
+    assert_eq!(verify(rows), Ok(()));
+}
+
 #[test]
 fn bad_initial_tx_log_value() {
     let rows = vec![Rw::TxLog {
@@ -14,8 +14,12 @@
 //! - [x] Copy Circuit
 //! - [x] Exponentiation Circuit
 //! - [ ] Keccak Circuit
-//! - [ ] MPT Circuit
+//! - [x] MPT Circuit
 //! - [x] PublicInputs Circuit
+//! - [x] SHA-256 Circuit
+//! - [x] ModExp Circuit
+//! - [x] EcRecover Circuit
+//! - [x] ECC Circuit
 //!
 //! And the following shared tables, with the circuits that use them:
 //!
@@ -41,14 +45,32 @@
 //!   - [ ] EVM Circuit
 //!   - [x] PublicInputs Circuit
 //! - [ ] MPT Table
-//!   - [ ] MPT Circuit
+//!   - [x] MPT Circuit
 //!   - [ ] State Circuit
 //! - [x] Keccak Table
 //!   - [ ] Keccak Circuit
 //!   - [ ] EVM Circuit
 //!   - [x] Bytecode Circuit
 //!   - [x] Tx Circuit
-//!   - [ ] MPT Circuit
+//!   - [x] MPT Circuit
+//! - [x] SHA-256 Table
+//!   - [x] SHA-256 Circuit
+//!   - [x] EVM Circuit
+//! - [x] ModExp Table
+//!   - [x] ModExp Circuit
+//!   - [x] EVM Circuit
+//! - [x] EcRecover Table
+//!   - [x] EcRecover Circuit
+//!   - [x] EVM Circuit
+//! - [x] EcAdd Table
+//!   - [x] ECC Circuit
+//!   - [x] EVM Circuit
+//! - [x] EcMul Table
+//!   - [x] ECC Circuit
+//!   - [x] EVM Circuit
+//! - [x] EcPairing Table
+//!   - [x] ECC Circuit
+//!   - [x] EVM Circuit
 
 #[cfg(any(feature = "test", test))]
 pub(crate) mod test;
@@ -58,17 +80,24 @@ use crate::{
         BytecodeCircuit, BytecodeCircuitConfig, BytecodeCircuitConfigArgs,
     },
     copy_circuit::{CopyCircuit, CopyCircuitConfig, CopyCircuitConfigArgs},
+    ecc_circuit::{EccCircuit, EccCircuitConfig, EccCircuitConfigArgs},
+    ecrecover_circuit::{EcRecoverCircuit, EcRecoverCircuitConfig, EcRecoverCircuitConfigArgs},
     evm_circuit::{EvmCircuit, EvmCircuitConfig, EvmCircuitConfigArgs},
     exp_circuit::{ExpCircuit, ExpCircuitConfig},
     keccak_circuit::{KeccakCircuit, KeccakCircuitConfig, KeccakCircuitConfigArgs},
+    modexp_circuit::{ModExpCircuit, ModExpCircuitConfig, ModExpCircuitConfigArgs},
+    mpt_circuit::{MptCircuit, MptCircuitConfig, MptCircuitConfigArgs},
     pi_circuit::{PiCircuit, PiCircuitConfig, PiCircuitConfigArgs},
+    sha256_circuit::{Sha256Circuit, Sha256CircuitConfig, Sha256CircuitConfigArgs},
     state_circuit::{StateCircuit, StateCircuitConfig, StateCircuitConfigArgs},
     table::{
-        BlockTable, BytecodeTable, CopyTable, ExpTable, KeccakTable, MptTable, RwTable, TxTable,
+        BlockTable, BytecodeTable, CopyTable, EcAddTable, EcMulTable, EcPairingTable,
+        EcRecoverTable, ExpTable, KeccakTable, ModExpTable, MptTable, RwTable, Sha256Table,
+        TxTable, UncleTable, WithdrawalTable,
     },
     tx_circuit::{TxCircuit, TxCircuitConfig, TxCircuitConfigArgs},
     util::{log2_ceil, Challenges, SubCircuit, SubCircuitConfig},
-    witness::{block_convert, Block, MptUpdates},
+    witness::{block_convert, Block},
 };
 use bus_mapping::{
     circuit_input_builder::{CircuitInputBuilder, CircuitsParams},
@@ -87,6 +116,8 @@ use std::array;
 pub struct SuperCircuitConfig<F: Field> {
     block_table: BlockTable,
     mpt_table: MptTable,
+    withdrawal_table: WithdrawalTable,
+    uncle_table: UncleTable,
     evm_circuit: EvmCircuitConfig<F>,
     state_circuit: StateCircuitConfig<F>,
     tx_circuit: TxCircuitConfig<F>,
@@ -95,6 +126,11 @@ pub struct SuperCircuitConfig<F: Field> {
     keccak_circuit: KeccakCircuitConfig<F>,
     pi_circuit: PiCircuitConfig<F>,
     exp_circuit: ExpCircuitConfig<F>,
+    sha256_circuit: Sha256CircuitConfig<F>,
+    modexp_circuit: ModExpCircuitConfig<F>,
+    mpt_circuit: MptCircuitConfig<F>,
+    ecrecover_circuit: EcRecoverCircuitConfig<F>,
+    ecc_circuit: EccCircuitConfig<F>,
 }
 
 /// Circuit configuration arguments
@@ -128,6 +164,14 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
         let copy_table = CopyTable::construct(meta, q_copy_table);
         let exp_table = ExpTable::construct(meta);
         let keccak_table = KeccakTable::construct(meta);
+        let ecrecover_table = EcRecoverTable::construct(meta);
+        let ec_add_table = EcAddTable::construct(meta);
+        let ec_mul_table = EcMulTable::construct(meta);
+        let ec_pairing_table = EcPairingTable::construct(meta);
+        let sha256_table = Sha256Table::construct(meta);
+        let modexp_table = ModExpTable::construct(meta);
+        let withdrawal_table = WithdrawalTable::construct(meta);
+        let uncle_table = UncleTable::construct(meta);
 
         // Use a mock randomness instead of the randomness derived from the challange
         // (either from mock or real prover) to help debugging assignments.
@@ -155,6 +199,11 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 max_calldata,
                 block_table: block_table.clone(),
                 tx_table: tx_table.clone(),
+                keccak_table: keccak_table.clone(),
+                rw_table,
+                withdrawal_table,
+                uncle_table,
+                challenges: challenges.clone(),
             },
         );
         let tx_circuit = TxCircuitConfig::new(
@@ -193,6 +242,40 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             },
         );
         let exp_circuit = ExpCircuitConfig::new(meta, exp_table);
+        let sha256_circuit = Sha256CircuitConfig::new(
+            meta,
+            Sha256CircuitConfigArgs {
+                sha256_table,
+                challenges: challenges.clone(),
+            },
+        );
+        let modexp_circuit =
+            ModExpCircuitConfig::new(meta, ModExpCircuitConfigArgs { modexp_table });
+        let mpt_circuit = MptCircuitConfig::new(
+            meta,
+            MptCircuitConfigArgs {
+                mpt_table,
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ecrecover_circuit = EcRecoverCircuitConfig::new(
+            meta,
+            EcRecoverCircuitConfigArgs {
+                ecrecover_table,
+                keccak_table: keccak_table.clone(),
+                challenges: challenges.clone(),
+            },
+        );
+        let ecc_circuit = EccCircuitConfig::new(
+            meta,
+            EccCircuitConfigArgs {
+                ec_add_table,
+                ec_mul_table,
+                ec_pairing_table,
+                challenges: challenges.clone(),
+            },
+        );
         let evm_circuit = EvmCircuitConfig::new(
             meta,
             EvmCircuitConfigArgs {
@@ -204,12 +287,22 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
                 copy_table,
                 keccak_table,
                 exp_table,
+                ecrecover_table,
+                sha256_table,
+                modexp_table,
+                ec_add_table,
+                ec_mul_table,
+                ec_pairing_table,
+                withdrawal_table,
+                uncle_table,
             },
         );
 
         Self {
             block_table,
             mpt_table,
+            withdrawal_table,
+            uncle_table,
             evm_circuit,
             state_circuit,
             copy_circuit,
@@ -218,6 +311,11 @@ impl<F: Field> SubCircuitConfig<F> for SuperCircuitConfig<F> {
             keccak_circuit,
             pi_circuit,
             exp_circuit,
+            sha256_circuit,
+            modexp_circuit,
+            mpt_circuit,
+            ecrecover_circuit,
+            ecc_circuit,
         }
     }
 }
@@ -241,6 +339,16 @@ pub struct SuperCircuit<F: Field> {
     pub exp_circuit: ExpCircuit<F>,
     /// Keccak Circuit
     pub keccak_circuit: KeccakCircuit<F>,
+    /// SHA-256 Circuit
+    pub sha256_circuit: Sha256Circuit<F>,
+    /// ModExp Circuit
+    pub modexp_circuit: ModExpCircuit<F>,
+    /// MPT Circuit
+    pub mpt_circuit: MptCircuit<F>,
+    /// EcRecover Circuit
+    pub ecrecover_circuit: EcRecoverCircuit<F>,
+    /// ECC Circuit
+    pub ecc_circuit: EccCircuit<F>,
     /// Circuits Parameters
     pub circuits_params: CircuitsParams,
     /// Mock randomness
@@ -273,6 +381,11 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             CopyCircuit::<F>::unusable_rows(),
             ExpCircuit::<F>::unusable_rows(),
             KeccakCircuit::<F>::unusable_rows(),
+            Sha256Circuit::<F>::unusable_rows(),
+            ModExpCircuit::<F>::unusable_rows(),
+            MptCircuit::<F>::unusable_rows(),
+            EcRecoverCircuit::<F>::unusable_rows(),
+            EccCircuit::<F>::unusable_rows(),
         ])
         .unwrap()
     }
@@ -286,6 +399,11 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         let copy_circuit = CopyCircuit::new_from_block_no_external(block);
         let exp_circuit = ExpCircuit::new_from_block(block);
         let keccak_circuit = KeccakCircuit::new_from_block(block);
+        let sha256_circuit = Sha256Circuit::new_from_block(block);
+        let modexp_circuit = ModExpCircuit::new_from_block(block);
+        let mpt_circuit = MptCircuit::new_from_block(block);
+        let ecrecover_circuit = EcRecoverCircuit::new_from_block(block);
+        let ecc_circuit = EccCircuit::new_from_block(block);
 
         SuperCircuit::<_> {
             evm_circuit,
@@ -296,6 +414,11 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             copy_circuit,
             exp_circuit,
             keccak_circuit,
+            sha256_circuit,
+            modexp_circuit,
+            mpt_circuit,
+            ecrecover_circuit,
+            ecc_circuit,
             circuits_params: block.circuits_params,
             mock_randomness: block.randomness,
         }
@@ -312,6 +435,11 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         instance.extend_from_slice(&self.state_circuit.instance());
         instance.extend_from_slice(&self.exp_circuit.instance());
         instance.extend_from_slice(&self.evm_circuit.instance());
+        instance.extend_from_slice(&self.sha256_circuit.instance());
+        instance.extend_from_slice(&self.modexp_circuit.instance());
+        instance.extend_from_slice(&self.mpt_circuit.instance());
+        instance.extend_from_slice(&self.ecrecover_circuit.instance());
+        instance.extend_from_slice(&self.ecc_circuit.instance());
 
         instance
     }
@@ -326,8 +454,15 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
         let tx = TxCircuit::min_num_rows_block(block);
         let exp = ExpCircuit::min_num_rows_block(block);
         let pi = PiCircuit::min_num_rows_block(block);
-
-        let rows: Vec<(usize, usize)> = vec![evm, state, bytecode, copy, keccak, tx, exp, pi];
+        let sha256 = Sha256Circuit::min_num_rows_block(block);
+        let modexp = ModExpCircuit::min_num_rows_block(block);
+        let mpt = MptCircuit::min_num_rows_block(block);
+        let ecrecover = EcRecoverCircuit::min_num_rows_block(block);
+        let ecc = EccCircuit::min_num_rows_block(block);
+
+        let rows: Vec<(usize, usize)> = vec![
+            evm, state, bytecode, copy, keccak, tx, exp, pi, sha256, modexp, mpt, ecrecover, ecc,
+        ];
         let (rows_without_padding, rows_with_padding): (Vec<usize>, Vec<usize>) =
             rows.into_iter().unzip();
         (
@@ -355,6 +490,16 @@ impl<F: Field> SubCircuit<F> for SuperCircuit<F> {
             .synthesize_sub(&config.copy_circuit, challenges, layouter)?;
         self.exp_circuit
             .synthesize_sub(&config.exp_circuit, challenges, layouter)?;
+        self.sha256_circuit
+            .synthesize_sub(&config.sha256_circuit, challenges, layouter)?;
+        self.modexp_circuit
+            .synthesize_sub(&config.modexp_circuit, challenges, layouter)?;
+        self.mpt_circuit
+            .synthesize_sub(&config.mpt_circuit, challenges, layouter)?;
+        self.ecrecover_circuit
+            .synthesize_sub(&config.ecrecover_circuit, challenges, layouter)?;
+        self.ecc_circuit
+            .synthesize_sub(&config.ecc_circuit, challenges, layouter)?;
         self.evm_circuit
             .synthesize_sub(&config.evm_circuit, challenges, layouter)?;
         self.pi_circuit
@@ -414,7 +559,6 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
             Value::known(block.randomness),
             Value::known(block.randomness),
         );
-        let rws = &self.state_circuit.rows;
 
         config.block_table.load(
             &mut layouter,
@@ -424,10 +568,15 @@ impl<F: Field> Circuit<F> for SuperCircuit<F> {
 
         config.mpt_table.load(
             &mut layouter,
-            &MptUpdates::mock_from(rws),
+            &block.mpt_updates,
             Value::known(block.randomness),
         )?;
 
+        config
+            .withdrawal_table
+            .load(&mut layouter, &block.withdrawals)?;
+        config.uncle_table.load(&mut layouter, &block.uncles)?;
+
         self.synthesize_sub(&config, &challenges, &mut layouter)
     }
 }
@@ -7,7 +7,12 @@ use rand::SeedableRng;
 use rand_chacha::ChaCha20Rng;
 use std::collections::HashMap;
 
-use eth_types::{address, bytecode, geth_types::GethData, Word};
+use eth_types::{
+    address, bytecode,
+    evm_types::{BlockReward, Hardfork},
+    geth_types::GethData,
+    Word,
+};
 
 #[test]
 fn super_circuit_degree() {
@@ -137,6 +142,15 @@ fn serial_test_super_circuit_1tx_1max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover_calls: 0,
+        max_sha256_rows: 0,
+        max_modexp_calls: 0,
+        max_ec_add_calls: 0,
+        max_ec_mul_calls: 0,
+        max_ec_pairing_calls: 0,
+        max_mpt_rows: 0,
+        hardfork: Hardfork::default(),
+        block_reward: BlockReward::default(),
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -153,6 +167,15 @@ fn serial_test_super_circuit_1tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover_calls: 0,
+        max_sha256_rows: 0,
+        max_modexp_calls: 0,
+        max_ec_add_calls: 0,
+        max_ec_mul_calls: 0,
+        max_ec_pairing_calls: 0,
+        max_mpt_rows: 0,
+        hardfork: Hardfork::default(),
+        block_reward: BlockReward::default(),
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -169,6 +192,15 @@ fn serial_test_super_circuit_2tx_2max_tx() {
         max_bytecode: 512,
         max_evm_rows: 0,
         max_keccak_rows: 0,
+        max_ecrecover_calls: 0,
+        max_sha256_rows: 0,
+        max_modexp_calls: 0,
+        max_ec_add_calls: 0,
+        max_ec_mul_calls: 0,
+        max_ec_pairing_calls: 0,
+        max_mpt_rows: 0,
+        hardfork: Hardfork::default(),
+        block_reward: BlockReward::default(),
     };
     test_super_circuit(block, circuits_params, Fr::from(TEST_MOCK_RANDOMNESS));
 }
@@ -9,7 +9,7 @@ use crate::{
         Block, BlockContext, Bytecode, MptUpdateRow, MptUpdates, Rw, RwMap, RwRow, Transaction,
     },
 };
-use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent, CopyStep};
+use bus_mapping::circuit_input_builder::{CopyDataType, CopyEvent};
 use core::iter::once;
 use eth_types::{Field, Keccak, ToLittleEndian, ToScalar, Word, U256};
 use gadgets::{
@@ -31,28 +31,46 @@ pub(crate) mod block_table;
 pub(crate) mod bytecode_table;
 /// copy Table
 pub(crate) mod copy_table;
+/// ECC tables
+pub(crate) mod ecc_table;
+/// ecRecover table
+pub(crate) mod ecrecover_table;
 /// exp(exponentiation) table
 pub(crate) mod exp_table;
 /// keccak table
 pub(crate) mod keccak_table;
+/// modexp table
+pub(crate) mod modexp_table;
 /// mpt table
 pub(crate) mod mpt_table;
 /// rw table
 pub(crate) mod rw_table;
+/// sha256 table
+pub(crate) mod sha256_table;
 /// tx table
 pub(crate) mod tx_table;
+/// uncle table
+pub(crate) mod uncle_table;
+/// withdrawal table
+pub(crate) mod withdrawal_table;
 
 pub(crate) use block_table::{BlockContextFieldTag, BlockTable};
 pub(crate) use bytecode_table::{BytecodeFieldTag, BytecodeTable};
 pub(crate) use copy_table::CopyTable;
+pub(crate) use ecc_table::{EcAddTable, EcMulTable, EcPairingTable};
+pub(crate) use ecrecover_table::EcRecoverTable;
 pub(crate) use exp_table::ExpTable;
 pub(crate) use keccak_table::KeccakTable;
 
+pub(crate) use modexp_table::ModExpTable;
 pub(crate) use mpt_table::{MPTProofType, MptTable};
 pub(crate) use rw_table::RwTable;
+pub(crate) use sha256_table::Sha256Table;
 pub(crate) use tx_table::{
     TxContextFieldTag, TxFieldTag, TxLogFieldTag, TxReceiptFieldTag, TxTable,
 };
+pub(crate) use uncle_table::UncleTable;
+pub(crate) use withdrawal_table::WithdrawalTable;
 
 /// Trait used to define lookup tables
 pub trait LookupTable<F: Field> {
@@ -128,6 +146,26 @@ pub enum AccountFieldTag {
 }
 impl_expr!(AccountFieldTag);
 
+/// Tag for a TxAccountFlagField in RwTable
+#[derive(Clone, Copy, Debug, EnumIter, PartialEq, Eq)]
+pub enum TxAccountFlagFieldTag {
+    /// Created field
+    Created = 1,
+    /// Destructed field
+    Destructed,
+}
+impl_expr!(TxAccountFlagFieldTag);
+
+/// Tag for the clear of the storage of an account in RwTable.  The slots of
+/// the storage have no field tag, so the clear is sorted after the slots of the
+/// account accessed in the same tx.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageFieldTag {
+    /// Clear of the storage of an account deleted at the end of a tx
+    Clear = 1,
+}
+impl_expr!(StorageFieldTag);
+
 /// Tag for a CallContextField in RwTable
 #[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
 pub enum CallContextFieldTag {
@@ -2,7 +2,7 @@ use super::*;
 
 /// Tag to identify the field in a Block Table row
 // Keep the sequence consistent with OpcodeId for scalar
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BlockContextFieldTag {
     /// Coinbase field
     Coinbase = 1,
@@ -21,6 +21,34 @@ pub enum BlockContextFieldTag {
     /// Chain ID field.  Although this is not a field in the block header, we
     /// add it here for convenience.
     ChainId,
+    /// Parent Hash field
+    ParentHash,
+    /// Uncles Hash field
+    UnclesHash,
+    /// State Root field
+    StateRoot,
+    /// Transactions Root field
+    TransactionsRoot,
+    /// Receipts Root field
+    ReceiptsRoot,
+    /// Logs Bloom field, as the RLC of its bytes
+    LogsBloom,
+    /// Gas Used field
+    GasUsed,
+    /// Extra Data field, as the RLC of its bytes, with its length as index
+    ExtraData,
+    /// Mix Hash field
+    MixHash,
+    /// Nonce field
+    Nonce,
+    /// Excess Blob Gas field (EIP-4844)
+    ExcessBlobGas,
+    /// Blob Base Fee field (EIP-4844), which is not a field in the block
+    /// header but is derived from the excess blob gas.
+    BlobBaseFee,
+    /// PrevRandao field (EIP-4399), which is the mix hash of the block and is
+    /// returned by the DIFFICULTY opcode from the merge on.
+    PrevRandao,
 }
 impl_expr!(BlockContextFieldTag);
 
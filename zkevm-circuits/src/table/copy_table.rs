@@ -1,10 +1,11 @@
 use super::*;
 
 type CopyTableRow<F> = [(Value<F>, &'static str); 8];
-type CopyCircuitRow<F> = [(Value<F>, &'static str); 5];
+type CopyCircuitRow<F> = [(Value<F>, &'static str); 7];
 
 /// Copy Table, used to verify copies of byte chunks between Memory, Bytecode,
-/// TxLogs and TxCallData.
+/// TxLogs and TxCallData, and the additions of the tx access list entries to
+/// the access list.
 #[derive(Clone, Copy, Debug)]
 pub struct CopyTable {
     /// Whether the row is the first read-write pair for a copy event.
@@ -13,7 +14,8 @@ pub struct CopyTable {
     /// combination. The ID may be one of the below:
     /// 1. Call ID/Caller ID for CopyDataType::Memory
     /// 2. RLC encoding of bytecode hash for CopyDataType::Bytecode
-    /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog
+    /// 3. Transaction ID for CopyDataType::TxCalldata, CopyDataType::TxLog,
+    ///    CopyDataType::AccessListAddresses, CopyDataType::AccessListStorageKeys
     pub id: Column<Advice>,
     /// The source/destination address for this copy step.  Can be memory
     /// address, byte index in the bytecode, tx call data, and tx log data.
@@ -60,38 +62,69 @@ impl CopyTable {
         challenges: Challenges<Value<F>>,
     ) -> Vec<(CopyDataType, CopyTableRow<F>, CopyCircuitRow<F>)> {
         let mut assignments = Vec::new();
-        // rlc_acc
-        let rlc_acc = {
-            let values = copy_event
+        // The (value, storage_key, is_warm_prev, is_code) of every copy step,
+        // where the value is a byte, or an address in case of an access list
+        let copy_values = if copy_event.is_access_list() {
+            copy_event
+                .access_list
+                .iter()
+                .map(|(address, storage_key, is_warm_prev)| {
+                    (
+                        Value::known(address.to_scalar().unwrap()),
+                        challenges
+                            .evm_word()
+                            .map(|evm_word| rlc::value(&storage_key.to_le_bytes(), evm_word)),
+                        *is_warm_prev,
+                        false,
+                    )
+                })
+                .collect::<Vec<_>>()
+        } else {
+            copy_event
                 .bytes
                 .iter()
-                .map(|(value, _)| *value)
-                .collect::<Vec<u8>>();
-            challenges
-                .keccak_input()
-                .map(|keccak_input| rlc::value(values.iter().rev(), keccak_input))
+                .map(|(value, is_code)| {
+                    (
+                        Value::known(F::from(*value as u64)),
+                        Value::known(F::ZERO),
+                        false,
+                        *is_code,
+                    )
+                })
+                .collect()
         };
+        // The storage keys are accumulated right after the address of their
+        // item
+        let accumulate = |acc: Value<F>, value: Value<F>, storage_key: Value<F>| {
+            let acc = acc * challenges.keccak_input() + value;
+            if copy_event.dst_type == CopyDataType::AccessListStorageKeys {
+                acc * challenges.keccak_input() + storage_key
+            } else {
+                acc
+            }
+        };
+        // rlc_acc
+        let rlc_acc = copy_values
+            .iter()
+            .fold(Value::known(F::ZERO), |acc, (value, storage_key, _, _)| {
+                accumulate(acc, *value, *storage_key)
+            });
         let mut value_acc = Value::known(F::ZERO);
-        for (step_idx, (is_read_step, copy_step)) in copy_event
-            .bytes
+        for (step_idx, (is_read_step, (value, storage_key, is_warm_prev, is_code))) in copy_values
             .iter()
-            .flat_map(|(value, is_code)| {
-                let read_step = CopyStep {
-                    value: *value,
-                    is_code: if copy_event.src_type == CopyDataType::Bytecode {
-                        Some(*is_code)
-                    } else {
-                        None
-                    },
-                };
-                let write_step = CopyStep {
-                    value: *value,
-                    is_code: if copy_event.dst_type == CopyDataType::Bytecode {
-                        Some(*is_code)
-                    } else {
-                        None
-                    },
-                };
+            .flat_map(|&(value, storage_key, is_warm_prev, is_code)| {
+                let read_step = (
+                    value,
+                    storage_key,
+                    is_warm_prev,
+                    copy_event.src_type == CopyDataType::Bytecode && is_code,
+                );
+                let write_step = (
+                    value,
+                    storage_key,
+                    is_warm_prev,
+                    copy_event.dst_type == CopyDataType::Bytecode && is_code,
+                );
                 once((true, read_step)).chain(once((false, write_step)))
             })
             .enumerate()
@@ -99,7 +132,7 @@ impl CopyTable {
             // is_first
             let is_first = Value::known(if step_idx == 0 { F::ONE } else { F::ZERO });
             // is last
-            let is_last = if step_idx == copy_event.bytes.len() * 2 - 1 {
+            let is_last = if step_idx == copy_event.copy_length() * 2 - 1 {
                 Value::known(F::ONE)
             } else {
                 Value::known(F::ZERO)
@@ -142,12 +175,10 @@ impl CopyTable {
             };
 
             // bytes_left
-            let bytes_left = u64::try_from(copy_event.bytes.len() * 2 - step_idx).unwrap() / 2;
-            // value
-            let value = Value::known(F::from(copy_step.value as u64));
+            let bytes_left = u64::try_from(copy_event.copy_length() * 2 - step_idx).unwrap() / 2;
             // value_acc
             if is_read_step {
-                value_acc = value_acc * challenges.keccak_input() + value;
+                value_acc = accumulate(value_acc, value, storage_key);
             }
             // is_pad
             let is_pad = Value::known(F::from(
@@ -155,7 +186,9 @@ impl CopyTable {
             ));
 
             // is_code
-            let is_code = Value::known(copy_step.is_code.map_or(F::ZERO, |v| F::from(v as u64)));
+            let is_code = Value::known(F::from(is_code as u64));
+            // is_warm_prev
+            let is_warm_prev = Value::known(F::from(is_warm_prev as u64));
 
             assignments.push((
                 tag,
@@ -171,7 +204,7 @@ impl CopyTable {
                     (
                         match (copy_event.src_type, copy_event.dst_type) {
                             (CopyDataType::Memory, CopyDataType::Bytecode) => rlc_acc,
-                            (_, CopyDataType::RlcAcc) => rlc_acc,
+                            (CopyDataType::RlcAcc, _) | (_, CopyDataType::RlcAcc) => rlc_acc,
                             _ => Value::known(F::ZERO),
                         },
                         "rlc_acc",
@@ -191,6 +224,8 @@ impl CopyTable {
                     (value_acc, "value_acc"),
                     (is_pad, "is_pad"),
                     (is_code, "is_code"),
+                    (storage_key, "storage_key"),
+                    (is_warm_prev, "is_warm_prev"),
                 ],
             ));
         }
@@ -0,0 +1,304 @@
+use super::*;
+
+use bus_mapping::precompile::{EcAddAuxData, EcMulAuxData, EcPairingAuxData};
+use std::iter;
+
+/// Split a word in its 128-bit lo and hi parts.
+fn word_lo_hi<F: Field>(word: Word) -> [F; 2] {
+    let (lo, hi) = split_u256(&word);
+    [F::from_u128(lo.as_u128()), F::from_u128(hi.as_u128())]
+}
+
+/// Assign the rows of an ECC table, starting with an all-zero row.
+fn dev_load_rows<F: Field, const N: usize>(
+    layouter: &mut impl Layouter<F>,
+    name: &'static str,
+    columns: &[Column<Advice>],
+    rows: impl IntoIterator<Item = [Value<F>; N]>,
+) -> Result<(), Error> {
+    let rows = rows.into_iter().collect_vec();
+    layouter.assign_region(
+        || name,
+        |mut region| {
+            for column in columns.iter() {
+                region.assign_advice(
+                    || format!("{} all-zero row", name),
+                    *column,
+                    0,
+                    || Value::known(F::ZERO),
+                )?;
+            }
+            for (offset, row) in (1..).zip(rows.iter()) {
+                for (&column, value) in columns.iter().zip_eq(row) {
+                    region.assign_advice(
+                        || format!("{} row {}", name, offset),
+                        column,
+                        offset,
+                        || *value,
+                    )?;
+                }
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Lookup table of the calls to the ecAdd precompile, filled by the ECC circuit
+/// and looked up by the EVM circuit.  The coordinates are split in their
+/// 128-bit lo and hi parts, and the point at infinity is (0, 0).
+#[derive(Clone, Copy, Debug)]
+pub struct EcAddTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Lo and hi 128 bits of the x-coordinate of the first point
+    pub p_x: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the y-coordinate of the first point
+    pub p_y: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the x-coordinate of the second point
+    pub q_x: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the y-coordinate of the second point
+    pub q_y: [Column<Advice>; 2],
+    /// Whether both points are valid, otherwise the sum is (0, 0)
+    pub is_valid: Column<Advice>,
+    /// Lo and hi 128 bits of the x-coordinate of the sum
+    pub r_x: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the y-coordinate of the sum
+    pub r_y: [Column<Advice>; 2],
+}
+
+impl<F: Field> LookupTable<F> for EcAddTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        iter::once(self.is_enabled)
+            .chain([self.p_x, self.p_y, self.q_x, self.q_y].concat())
+            .chain(iter::once(self.is_valid))
+            .chain([self.r_x, self.r_y].concat())
+            .map(|column| column.into())
+            .collect()
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        let lo_hi = |name: &&str| [format!("{}_lo", name), format!("{}_hi", name)];
+        iter::once(String::from("is_enabled"))
+            .chain(["p_x", "p_y", "q_x", "q_y"].iter().flat_map(lo_hi))
+            .chain(iter::once(String::from("is_valid")))
+            .chain(["r_x", "r_y"].iter().flat_map(lo_hi))
+            .collect()
+    }
+}
+
+impl EcAddTable {
+    /// Construct a new EcAddTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            p_x: [(); 2].map(|_| meta.advice_column()),
+            p_y: [(); 2].map(|_| meta.advice_column()),
+            q_x: [(); 2].map(|_| meta.advice_column()),
+            q_y: [(); 2].map(|_| meta.advice_column()),
+            is_valid: meta.advice_column(),
+            r_x: [(); 2].map(|_| meta.advice_column()),
+            r_y: [(); 2].map(|_| meta.advice_column()),
+        }
+    }
+
+    /// Generate the ecAdd table assignments from a call to the ecAdd
+    /// precompile.
+    pub fn assignments<F: Field>(aux_data: &EcAddAuxData) -> Vec<[Value<F>; 14]> {
+        let inputs = [aux_data.p_x, aux_data.p_y, aux_data.q_x, aux_data.q_y].map(word_lo_hi::<F>);
+        let outputs = [aux_data.r_x, aux_data.r_y].map(word_lo_hi::<F>);
+
+        vec![iter::once(F::ONE)
+            .chain(inputs.concat())
+            .chain(iter::once(F::from(aux_data.is_valid as u64)))
+            .chain(outputs.concat())
+            .map(Value::known)
+            .collect_vec()
+            .try_into()
+            .unwrap()]
+    }
+
+    /// Provide this function for the case that we want to consume an ecAdd
+    /// table but without running the ECC circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a EcAddAuxData>,
+    ) -> Result<(), Error> {
+        dev_load_rows(
+            layouter,
+            "ec add table",
+            &<EcAddTable as LookupTable<F>>::advice_columns(self),
+            events.into_iter().flat_map(Self::assignments::<F>),
+        )
+    }
+}
+
+/// Lookup table of the calls to the ecMul precompile, filled by the ECC circuit
+/// and looked up by the EVM circuit.  The coordinates and the scalar are split
+/// in their 128-bit lo and hi parts, and the point at infinity is (0, 0).
+#[derive(Clone, Copy, Debug)]
+pub struct EcMulTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Lo and hi 128 bits of the x-coordinate of the point
+    pub p_x: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the y-coordinate of the point
+    pub p_y: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the scalar
+    pub scalar: [Column<Advice>; 2],
+    /// Whether the point is valid, otherwise the product is (0, 0)
+    pub is_valid: Column<Advice>,
+    /// Lo and hi 128 bits of the x-coordinate of the product
+    pub r_x: [Column<Advice>; 2],
+    /// Lo and hi 128 bits of the y-coordinate of the product
+    pub r_y: [Column<Advice>; 2],
+}
+
+impl<F: Field> LookupTable<F> for EcMulTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        iter::once(self.is_enabled)
+            .chain([self.p_x, self.p_y, self.scalar].concat())
+            .chain(iter::once(self.is_valid))
+            .chain([self.r_x, self.r_y].concat())
+            .map(|column| column.into())
+            .collect()
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        let lo_hi = |name: &&str| [format!("{}_lo", name), format!("{}_hi", name)];
+        iter::once(String::from("is_enabled"))
+            .chain(["p_x", "p_y", "scalar"].iter().flat_map(lo_hi))
+            .chain(iter::once(String::from("is_valid")))
+            .chain(["r_x", "r_y"].iter().flat_map(lo_hi))
+            .collect()
+    }
+}
+
+impl EcMulTable {
+    /// Construct a new EcMulTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            p_x: [(); 2].map(|_| meta.advice_column()),
+            p_y: [(); 2].map(|_| meta.advice_column()),
+            scalar: [(); 2].map(|_| meta.advice_column()),
+            is_valid: meta.advice_column(),
+            r_x: [(); 2].map(|_| meta.advice_column()),
+            r_y: [(); 2].map(|_| meta.advice_column()),
+        }
+    }
+
+    /// Generate the ecMul table assignments from a call to the ecMul
+    /// precompile.
+    pub fn assignments<F: Field>(aux_data: &EcMulAuxData) -> Vec<[Value<F>; 12]> {
+        let inputs = [aux_data.p_x, aux_data.p_y, aux_data.scalar].map(word_lo_hi::<F>);
+        let outputs = [aux_data.r_x, aux_data.r_y].map(word_lo_hi::<F>);
+
+        vec![iter::once(F::ONE)
+            .chain(inputs.concat())
+            .chain(iter::once(F::from(aux_data.is_valid as u64)))
+            .chain(outputs.concat())
+            .map(Value::known)
+            .collect_vec()
+            .try_into()
+            .unwrap()]
+    }
+
+    /// Provide this function for the case that we want to consume an ecMul
+    /// table but without running the ECC circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a EcMulAuxData>,
+    ) -> Result<(), Error> {
+        dev_load_rows(
+            layouter,
+            "ec mul table",
+            &<EcMulTable as LookupTable<F>>::advice_columns(self),
+            events.into_iter().flat_map(Self::assignments::<F>),
+        )
+    }
+}
+
+/// Lookup table of the calls to the ecPairing precompile whose input length is a
+/// multiple of the size of a pair, filled by the ECC circuit and looked up by
+/// the EVM circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct EcPairingTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Input as `RLC(reversed(input))`
+    pub input_rlc: Column<Advice>,
+    /// Whether all the points are valid, otherwise the output is false
+    pub is_valid: Column<Advice>,
+    /// Whether the product of the pairings is 1
+    pub output: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for EcPairingTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.input_rlc.into(),
+            self.is_valid.into(),
+            self.output.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("input_rlc"),
+            String::from("is_valid"),
+            String::from("output"),
+        ]
+    }
+}
+
+impl EcPairingTable {
+    /// Construct a new EcPairingTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            input_rlc: meta.advice_column_in(SecondPhase),
+            is_valid: meta.advice_column(),
+            output: meta.advice_column(),
+        }
+    }
+
+    /// Generate the ecPairing table assignments from a call to the ecPairing
+    /// precompile.
+    pub fn assignments<F: Field>(
+        aux_data: &EcPairingAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 4]> {
+        let input_rlc = challenges
+            .keccak_input()
+            .map(|challenge| rlc::value(aux_data.input().iter().rev(), challenge));
+
+        vec![[
+            Value::known(F::ONE),
+            input_rlc,
+            Value::known(F::from(aux_data.is_valid as u64)),
+            Value::known(F::from(aux_data.output as u64)),
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume an
+    /// ecPairing table but without running the ECC circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a EcPairingAuxData>,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        dev_load_rows(
+            layouter,
+            "ec pairing table",
+            &<EcPairingTable as LookupTable<F>>::advice_columns(self),
+            events
+                .into_iter()
+                .flat_map(|event| Self::assignments(event, challenges)),
+        )
+    }
+}
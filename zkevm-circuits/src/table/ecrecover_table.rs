@@ -0,0 +1,129 @@
+use super::*;
+
+use bus_mapping::precompile::EcrecoverAuxData;
+
+/// Lookup table of the calls to the ecRecover precompile, filled by the
+/// ecRecover circuit and looked up by the EVM circuit.  The words are encoded
+/// as `RLC(word.to_le_bytes())` with the evm word challenge.
+#[derive(Clone, Copy, Debug)]
+pub struct EcRecoverTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Keccak hash of the signed message, reduced modulo the secp256k1 order
+    pub msg_hash_rlc: Column<Advice>,
+    /// v-component of the signature
+    pub sig_v_rlc: Column<Advice>,
+    /// r-component of the signature
+    pub sig_r_rlc: Column<Advice>,
+    /// s-component of the signature
+    pub sig_s_rlc: Column<Advice>,
+    /// Recovered address, 0 when the recovery fails
+    pub recovered_addr: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for EcRecoverTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.msg_hash_rlc.into(),
+            self.sig_v_rlc.into(),
+            self.sig_r_rlc.into(),
+            self.sig_s_rlc.into(),
+            self.recovered_addr.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("msg_hash_rlc"),
+            String::from("sig_v_rlc"),
+            String::from("sig_r_rlc"),
+            String::from("sig_s_rlc"),
+            String::from("recovered_addr"),
+        ]
+    }
+}
+
+impl EcRecoverTable {
+    /// Construct a new EcRecoverTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            msg_hash_rlc: meta.advice_column_in(SecondPhase),
+            sig_v_rlc: meta.advice_column_in(SecondPhase),
+            sig_r_rlc: meta.advice_column_in(SecondPhase),
+            sig_s_rlc: meta.advice_column_in(SecondPhase),
+            recovered_addr: meta.advice_column(),
+        }
+    }
+
+    /// Generate the ecRecover table assignments from a call to the ecRecover
+    /// precompile.
+    pub fn assignments<F: Field>(
+        aux_data: &EcrecoverAuxData,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<[Value<F>; 6]> {
+        let [msg_hash_rlc, sig_v_rlc, sig_r_rlc, sig_s_rlc] = [
+            aux_data.msg_hash_reduced(),
+            aux_data.sig_v,
+            aux_data.sig_r,
+            aux_data.sig_s,
+        ]
+        .map(|word| {
+            challenges
+                .evm_word()
+                .map(|challenge| rlc::value(&word.to_le_bytes(), challenge))
+        });
+
+        vec![[
+            Value::known(F::ONE),
+            msg_hash_rlc,
+            sig_v_rlc,
+            sig_r_rlc,
+            sig_s_rlc,
+            Value::known(aux_data.recovered_addr.to_scalar().unwrap()),
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume an ecRecover
+    /// table but without running the ecRecover circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a EcrecoverAuxData> + Clone,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "ecrecover table",
+            |mut region| {
+                let mut offset = 0;
+                let columns = <EcRecoverTable as LookupTable<F>>::advice_columns(self);
+                for column in columns.iter() {
+                    region.assign_advice(
+                        || "ecrecover table all-zero row",
+                        *column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                for event in events.clone() {
+                    for row in Self::assignments(event, challenges) {
+                        for (&column, value) in columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("ecrecover table row {}", offset),
+                                column,
+                                offset,
+                                || value,
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
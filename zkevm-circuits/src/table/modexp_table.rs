@@ -0,0 +1,137 @@
+use super::*;
+
+use bus_mapping::precompile::ModExpEvent;
+
+/// Lookup table of the modular exponentiations of the calls to the MODEXP
+/// precompile, filled by the ModExp circuit and looked up by the EVM circuit.
+/// The words are split in their 128-bit lo and hi parts.
+#[derive(Clone, Copy, Debug)]
+pub struct ModExpTable {
+    /// True when the row is enabled
+    pub is_enabled: Column<Advice>,
+    /// Lo 128 bits of the base
+    pub base_lo: Column<Advice>,
+    /// Hi 128 bits of the base
+    pub base_hi: Column<Advice>,
+    /// Lo 128 bits of the exponent
+    pub exponent_lo: Column<Advice>,
+    /// Hi 128 bits of the exponent
+    pub exponent_hi: Column<Advice>,
+    /// Lo 128 bits of the modulus
+    pub modulus_lo: Column<Advice>,
+    /// Hi 128 bits of the modulus
+    pub modulus_hi: Column<Advice>,
+    /// Lo 128 bits of the result
+    pub output_lo: Column<Advice>,
+    /// Hi 128 bits of the result
+    pub output_hi: Column<Advice>,
+}
+
+impl<F: Field> LookupTable<F> for ModExpTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.is_enabled.into(),
+            self.base_lo.into(),
+            self.base_hi.into(),
+            self.exponent_lo.into(),
+            self.exponent_hi.into(),
+            self.modulus_lo.into(),
+            self.modulus_hi.into(),
+            self.output_lo.into(),
+            self.output_hi.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("is_enabled"),
+            String::from("base_lo"),
+            String::from("base_hi"),
+            String::from("exponent_lo"),
+            String::from("exponent_hi"),
+            String::from("modulus_lo"),
+            String::from("modulus_hi"),
+            String::from("output_lo"),
+            String::from("output_hi"),
+        ]
+    }
+}
+
+impl ModExpTable {
+    /// Construct a new ModExpTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            is_enabled: meta.advice_column(),
+            base_lo: meta.advice_column(),
+            base_hi: meta.advice_column(),
+            exponent_lo: meta.advice_column(),
+            exponent_hi: meta.advice_column(),
+            modulus_lo: meta.advice_column(),
+            modulus_hi: meta.advice_column(),
+            output_lo: meta.advice_column(),
+            output_hi: meta.advice_column(),
+        }
+    }
+
+    /// Generate the modexp table assignments of a modular exponentiation,
+    /// which are the values of its enabled row.
+    pub fn assignments<F: Field>(event: &ModExpEvent) -> Vec<[F; 9]> {
+        let [base, exponent, modulus, output] =
+            [event.base, event.exponent, event.modulus, event.result].map(|word| {
+                let (lo, hi) = split_u256(&word);
+                [F::from_u128(lo.as_u128()), F::from_u128(hi.as_u128())]
+            });
+
+        vec![[
+            F::ONE,
+            base[0],
+            base[1],
+            exponent[0],
+            exponent[1],
+            modulus[0],
+            modulus[1],
+            output[0],
+            output[1],
+        ]]
+    }
+
+    /// Provide this function for the case that we want to consume a modexp
+    /// table but without running the ModExp circuit
+    pub fn dev_load<'a, F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        events: impl IntoIterator<Item = &'a ModExpEvent> + Clone,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "modexp table",
+            |mut region| {
+                let mut offset = 0;
+                let columns = <ModExpTable as LookupTable<F>>::advice_columns(self);
+                for column in columns.iter() {
+                    region.assign_advice(
+                        || "modexp table all-zero row",
+                        *column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                for event in events.clone() {
+                    for row in Self::assignments::<F>(event) {
+                        for (&column, value) in columns.iter().zip_eq(row) {
+                            region.assign_advice(
+                                || format!("modexp table row {}", offset),
+                                column,
+                                offset,
+                                || Value::known(value),
+                            )?;
+                        }
+                        offset += 1;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
@@ -15,6 +15,9 @@ pub enum MPTProofType {
     StorageMod,
     /// Storage does not exist
     NonExistingStorageProof,
+    /// Storage cleared, resetting the storage root of the account to the
+    /// empty root
+    StorageCleared,
 }
 impl_expr!(MPTProofType);
 
@@ -27,6 +27,45 @@ pub enum TxFieldTag {
     /// TxSignHash: Hash of the transaction without the signature, used for
     /// signing.
     TxSignHash,
+    /// Number of addresses in the access list (EIP-2930)
+    AccessListAddressesLen,
+    /// Number of storage keys in the access list (EIP-2930)
+    AccessListStorageKeysLen,
+    /// RLC of the RLP encoding of the access list (EIP-2930)
+    AccessListRLC,
+    /// RLC of the addresses of the access list (EIP-2930), which are added
+    /// to the access list of the tx in BeginTx
+    AccessListAddressesRLC,
+    /// RLC of the storage keys of the access list (EIP-2930), each one
+    /// preceded by the address of its item, which are added to the access
+    /// list of the tx in BeginTx
+    AccessListStorageKeysRLC,
+    /// MaxFeePerGas (EIP-1559), which is GasPrice for legacy transactions
+    MaxFeePerGas,
+    /// MaxPriorityFeePerGas (EIP-1559), which is GasPrice for legacy
+    /// transactions
+    MaxPriorityFeePerGas,
+    /// Transaction type: 0 for legacy, 1 for EIP-2930, 2 for EIP-1559 and 3
+    /// for EIP-4844
+    TxType,
+    /// Chain ID the transaction is signed for
+    ChainId,
+    /// R of the transaction signature
+    SigR,
+    /// S of the transaction signature
+    SigS,
+    /// TxHash: Hash of the signed transaction
+    TxHash,
+    /// MaxFeePerBlobGas (EIP-4844), which is 0 for the transactions without
+    /// blobs
+    MaxFeePerBlobGas,
+    /// Number of blobs of the transaction (EIP-4844)
+    BlobVersionedHashesLen,
+    /// RLC of the RLP encoding of the list of blob versioned hashes (EIP-4844)
+    BlobVersionedHashesRLC,
+    /// Versioned hash of the blob at the index (EIP-4844), which is 0 past
+    /// the blobs of the transaction
+    BlobVersionedHash,
     /// CallData
     CallData,
 }
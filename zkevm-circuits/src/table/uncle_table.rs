@@ -0,0 +1,96 @@
+use super::*;
+
+use eth_types::geth_types::{BlockHeader, MAX_UNCLES};
+
+/// Table with the uncles of the block, whose miners are rewarded at the end of
+/// the block.  It always has `MAX_UNCLES` rows, and the rows after the last
+/// uncle have a zero number, which no uncle can have.  The rows are checked
+/// against the headers of the uncles in the PI circuit.
+#[derive(Clone, Copy, Debug)]
+pub struct UncleTable {
+    /// Position of the uncle in the block, starting at 1
+    pub id: Column<Advice>,
+    /// Number of the uncle
+    pub number: Column<Advice>,
+    /// Beneficiary of the uncle reward
+    pub miner: Column<Advice>,
+}
+
+impl UncleTable {
+    /// Construct a new UncleTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            id: meta.advice_column(),
+            number: meta.advice_column(),
+            miner: meta.advice_column(),
+        }
+    }
+
+    /// Assign the `UncleTable` from the uncles of the block.
+    pub fn load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        uncles: &[BlockHeader],
+    ) -> Result<(), Error> {
+        if uncles.len() > MAX_UNCLES {
+            log::error!(
+                "uncles exceed the maximum: {} > {}",
+                uncles.len(),
+                MAX_UNCLES
+            );
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "uncle table",
+            |mut region| {
+                let mut offset = 0;
+                let columns = <UncleTable as LookupTable<F>>::advice_columns(self);
+                for column in columns.iter() {
+                    region.assign_advice(
+                        || "uncle table all-zero row",
+                        *column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                let padding = BlockHeader::default();
+                for id in 0..MAX_UNCLES {
+                    let uncle = uncles.get(id).unwrap_or(&padding);
+                    let row = [
+                        F::from(id as u64 + 1),
+                        F::from(uncle.number),
+                        uncle.coinbase.to_scalar().unwrap(),
+                    ];
+                    for (&column, value) in columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("uncle table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                    offset += 1;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for UncleTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![self.id.into(), self.number.into(), self.miner.into()]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("id"),
+            String::from("number"),
+            String::from("miner"),
+        ]
+    }
+}
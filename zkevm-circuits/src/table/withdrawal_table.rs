@@ -0,0 +1,99 @@
+use super::*;
+
+use eth_types::geth_types::Withdrawal;
+
+/// Table with the withdrawals (EIP-4895) of the block, which are applied at
+/// the end of the block.
+#[derive(Clone, Copy, Debug)]
+pub struct WithdrawalTable {
+    /// Position of the withdrawal in the block, starting at 1
+    pub id: Column<Advice>,
+    /// Index of the withdrawal
+    pub index: Column<Advice>,
+    /// Index of the validator
+    pub validator_index: Column<Advice>,
+    /// Recipient of the withdrawal
+    pub address: Column<Advice>,
+    /// Amount of the withdrawal, in Gwei
+    pub amount: Column<Advice>,
+}
+
+impl WithdrawalTable {
+    /// Construct a new WithdrawalTable
+    pub fn construct<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            id: meta.advice_column(),
+            index: meta.advice_column(),
+            validator_index: meta.advice_column(),
+            address: meta.advice_column(),
+            amount: meta.advice_column(),
+        }
+    }
+
+    /// Assign the `WithdrawalTable` from the withdrawals of the block.
+    pub fn load<F: Field>(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        withdrawals: &[Withdrawal],
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "withdrawal table",
+            |mut region| {
+                let mut offset = 0;
+                let columns = <WithdrawalTable as LookupTable<F>>::advice_columns(self);
+                for column in columns.iter() {
+                    region.assign_advice(
+                        || "withdrawal table all-zero row",
+                        *column,
+                        offset,
+                        || Value::known(F::ZERO),
+                    )?;
+                }
+                offset += 1;
+
+                for (id, withdrawal) in withdrawals.iter().enumerate() {
+                    let row = [
+                        F::from(id as u64 + 1),
+                        F::from(withdrawal.index.as_u64()),
+                        F::from(withdrawal.validator_index.as_u64()),
+                        withdrawal.address.to_scalar().unwrap(),
+                        F::from(withdrawal.amount.as_u64()),
+                    ];
+                    for (&column, value) in columns.iter().zip_eq(row) {
+                        region.assign_advice(
+                            || format!("withdrawal table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(value),
+                        )?;
+                    }
+                    offset += 1;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+impl<F: Field> LookupTable<F> for WithdrawalTable {
+    fn columns(&self) -> Vec<Column<Any>> {
+        vec![
+            self.id.into(),
+            self.index.into(),
+            self.validator_index.into(),
+            self.address.into(),
+            self.amount.into(),
+        ]
+    }
+
+    fn annotations(&self) -> Vec<String> {
+        vec![
+            String::from("id"),
+            String::from("index"),
+            String::from("validator_index"),
+            String::from("address"),
+            String::from("amount"),
+        ]
+    }
+}
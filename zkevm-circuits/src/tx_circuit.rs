@@ -5,6 +5,7 @@
 // - *_le: Little-Endian bytes
 
 pub mod sign_verify;
+mod tx_hash;
 
 #[cfg(any(feature = "test", test, feature = "test-circuits"))]
 mod dev;
@@ -14,26 +15,37 @@ mod test;
 pub use dev::TxCircuit as TestTxCircuit;
 
 use crate::{
+    evm_circuit::util::rlc,
     table::{KeccakTable, TxFieldTag, TxTable},
     util::{random_linear_combine_word as rlc, Challenges, SubCircuit, SubCircuitConfig},
-    witness,
+    witness::{self, access_list_addresses_rlc, access_list_storage_keys_rlc},
+};
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_TX, geth_types::Transaction, sign_types::SignData, Field,
+    ToLittleEndian, ToScalar, ToWord, Word,
 };
-use eth_types::{geth_types::Transaction, sign_types::SignData, Field, ToLittleEndian, ToScalar};
 use halo2_proofs::{
     circuit::{AssignedCell, Layouter, Region, Value},
-    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
 };
 use itertools::Itertools;
 use log::error;
 use sign_verify::{AssignedSignatureVerify, SignVerifyChip, SignVerifyConfig};
 use std::marker::PhantomData;
+use tx_hash::TxHashConfig;
+pub(crate) use tx_hash::{rlp_list_header, rlp_list_len, MAX_TX_RLP_STATIC_LEN};
 
 /// Number of static fields per tx: [nonce, gas, gas_price,
 /// caller_address, callee_address, is_create, value, call_data_length,
-/// call_data_gas_cost, tx_sign_hash].
+/// call_data_gas_cost, tx_sign_hash, access_list_addresses_len,
+/// access_list_storage_keys_len, access_list_rlc, access_list_addresses_rlc,
+/// access_list_storage_keys_rlc, max_fee_per_gas, max_priority_fee_per_gas,
+/// tx_type, chain_id, sig_r, sig_s, tx_hash, max_fee_per_blob_gas,
+/// blob_versioned_hashes_len, blob_versioned_hashes_rlc] followed by a
+/// blob_versioned_hash for every possible blob.
 /// Note that call data bytes are layed out in the TxTable after all the static
 /// fields arranged by txs.
-pub(crate) const TX_LEN: usize = 10;
+pub(crate) const TX_LEN: usize = 25 + MAX_BLOBS_PER_TX;
 
 /// Config for TxCircuit
 #[derive(Clone, Debug)]
@@ -42,7 +54,9 @@ pub struct TxCircuitConfig<F: Field> {
     tag: Column<Fixed>,
     index: Column<Advice>,
     value: Column<Advice>,
+    q_tx_hash: Selector,
     sign_verify: SignVerifyConfig,
+    tx_hash: TxHashConfig<F>,
     _marker: PhantomData<F>,
     // External tables
     keccak_table: KeccakTable,
@@ -76,6 +90,8 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
         let value = tx_table.value;
         meta.enable_equality(value);
 
+        let q_tx_hash = meta.complex_selector();
+        let tx_hash = TxHashConfig::new(meta, q_tx_hash, &tx_table, &keccak_table, &challenges);
         let sign_verify = SignVerifyConfig::new(meta, keccak_table.clone(), challenges);
 
         Self {
@@ -83,7 +99,9 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
             tag,
             index,
             value,
+            q_tx_hash,
             sign_verify,
+            tx_hash,
             keccak_table,
             _marker: PhantomData,
         }
@@ -91,9 +109,10 @@ impl<F: Field> SubCircuitConfig<F> for TxCircuitConfig<F> {
 }
 
 impl<F: Field> TxCircuitConfig<F> {
-    /// Load ECDSA RangeChip table.
+    /// Load ECDSA RangeChip table and the tables of the tx hash verification.
     pub fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
-        self.sign_verify.load_range(layouter)
+        self.sign_verify.load_range(layouter)?;
+        self.tx_hash.load_aux_tables(layouter)
     }
 
     /// Assigns a tx circuit row and returns the assigned cell of the value in
@@ -168,7 +187,12 @@ impl<F: Field> TxCircuit<F> {
     /// particular size.
     pub fn min_num_rows(txs_len: usize, call_data_len: usize) -> usize {
         let tx_table_len = txs_len * TX_LEN + call_data_len;
-        std::cmp::max(tx_table_len, SignVerifyChip::<F>::min_num_rows(txs_len))
+        // The rows of the tx hash verification are surrounded by two padding
+        // rows.
+        let tx_hash_len = TxHashConfig::<F>::num_rows(txs_len, call_data_len) + 2;
+        tx_table_len
+            .max(tx_hash_len)
+            .max(SignVerifyChip::<F>::min_num_rows(txs_len))
     }
 
     fn assign_tx_table(
@@ -176,7 +200,7 @@ impl<F: Field> TxCircuit<F> {
         config: &TxCircuitConfig<F>,
         challenges: &Challenges<Value<F>>,
         layouter: &mut impl Layouter<F>,
-        assigned_sig_verifs: Vec<AssignedSignatureVerify<F>>,
+        assigned_sig_verifs: &[AssignedSignatureVerify<F>],
     ) -> Result<(), Error> {
         layouter.assign_region(
             || "tx table",
@@ -193,15 +217,32 @@ impl<F: Field> TxCircuit<F> {
                 )?;
                 offset += 1;
                 // Assign al Tx fields except for call data
-                let tx_default = Transaction::default();
+                let tx_default = Transaction::padding(self.chain_id);
                 for (i, assigned_sig_verif) in assigned_sig_verifs.iter().enumerate() {
                     let tx = if i < self.txs.len() {
                         &self.txs[i]
                     } else {
                         &tx_default
                     };
+                    let (access_list_addresses_len, access_list_storage_keys_len) =
+                        tx.access_list_len();
+                    let (access_list_addresses, access_list_storage_keys) =
+                        tx.access_list_entries();
+                    let blob_versioned_hashes = (0..MAX_BLOBS_PER_TX).map(|index| {
+                        let hash = tx
+                            .blob_versioned_hashes
+                            .get(index)
+                            .map_or(Word::zero(), |hash| hash.to_word());
+                        (
+                            TxFieldTag::BlobVersionedHash,
+                            index,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(hash.to_le_bytes(), challenge)),
+                        )
+                    });
 
-                    for (tag, value) in [
+                    for (tag, index, value) in [
                         (TxFieldTag::Nonce, Value::known(F::from(tx.nonce.as_u64()))),
                         (
                             TxFieldTag::Gas,
@@ -243,9 +284,100 @@ impl<F: Field> TxCircuit<F> {
                             TxFieldTag::TxSignHash,
                             assigned_sig_verif.msg_hash_rlc.value().copied(),
                         ),
-                    ] {
+                        (
+                            TxFieldTag::AccessListAddressesLen,
+                            Value::known(F::from(access_list_addresses_len)),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysLen,
+                            Value::known(F::from(access_list_storage_keys_len)),
+                        ),
+                        (
+                            TxFieldTag::AccessListRLC,
+                            challenges.keccak_input().map(|challenge| {
+                                rlc::value(tx.access_list_rlp().iter().rev(), challenge)
+                            }),
+                        ),
+                        (
+                            TxFieldTag::AccessListAddressesRLC,
+                            challenges.keccak_input().map(|challenge| {
+                                access_list_addresses_rlc(&access_list_addresses, challenge)
+                            }),
+                        ),
+                        (
+                            TxFieldTag::AccessListStorageKeysRLC,
+                            challenges.keccak_input().zip(challenges.evm_word()).map(
+                                |(keccak_input, evm_word)| {
+                                    access_list_storage_keys_rlc(
+                                        &access_list_storage_keys,
+                                        keccak_input,
+                                        evm_word,
+                                    )
+                                },
+                            ),
+                        ),
+                        (
+                            TxFieldTag::MaxFeePerGas,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(tx.gas_fee_cap.to_le_bytes(), challenge)),
+                        ),
+                        (
+                            TxFieldTag::MaxPriorityFeePerGas,
+                            challenges
+                                .evm_word()
+                                .map(|challenge| rlc(tx.gas_tip_cap.to_le_bytes(), challenge)),
+                        ),
+                        (
+                            TxFieldTag::TxType,
+                            Value::known(F::from(tx.transaction_type)),
+                        ),
+                        (TxFieldTag::ChainId, Value::known(F::from(self.chain_id))),
+                        (
+                            TxFieldTag::SigR,
+                            assigned_sig_verif.sig_r_rlc.value().copied(),
+                        ),
+                        (
+                            TxFieldTag::SigS,
+                            assigned_sig_verif.sig_s_rlc.value().copied(),
+                        ),
+                        (
+                            TxFieldTag::TxHash,
+                            challenges.evm_word().map(|challenge| {
+                                rlc(
+                                    Word::from_big_endian(tx.hash(self.chain_id).as_bytes())
+                                        .to_le_bytes(),
+                                    challenge,
+                                )
+                            }),
+                        ),
+                        (
+                            TxFieldTag::MaxFeePerBlobGas,
+                            challenges.evm_word().map(|challenge| {
+                                rlc(tx.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                            }),
+                        ),
+                        (
+                            TxFieldTag::BlobVersionedHashesLen,
+                            Value::known(F::from(tx.blob_versioned_hashes.len() as u64)),
+                        ),
+                        (
+                            TxFieldTag::BlobVersionedHashesRLC,
+                            challenges.keccak_input().map(|challenge| {
+                                rlc::value(tx.blob_versioned_hashes_rlp().iter().rev(), challenge)
+                            }),
+                        ),
+                    ]
+                    .into_iter()
+                    .map(|(tag, value)| (tag, 0, value))
+                    .chain(blob_versioned_hashes)
+                    {
                         let assigned_cell =
-                            config.assign_row(&mut region, offset, i + 1, tag, 0, value)?;
+                            config.assign_row(&mut region, offset, i + 1, tag, index, value)?;
+                        // The tx hash is verified against the encoding of the tx
+                        if tag == TxFieldTag::TxHash {
+                            config.q_tx_hash.enable(&mut region, offset)?;
+                        }
                         offset += 1;
 
                         // Ref. spec 0. Copy constraints using fixed offsets between the tx rows and
@@ -259,6 +391,14 @@ impl<F: Field> TxCircuit<F> {
                                 assigned_cell.cell(),
                                 assigned_sig_verif.msg_hash_rlc.cell(),
                             )?,
+                            TxFieldTag::SigR => region.constrain_equal(
+                                assigned_cell.cell(),
+                                assigned_sig_verif.sig_r_rlc.cell(),
+                            )?,
+                            TxFieldTag::SigS => region.constrain_equal(
+                                assigned_cell.cell(),
+                                assigned_sig_verif.sig_s_rlc.cell(),
+                            )?,
                             _ => (),
                         }
                     }
@@ -358,7 +498,16 @@ impl<F: Field> SubCircuit<F> for TxCircuit<F> {
         let assigned_sig_verifs =
             self.sign_verify
                 .assign(&config.sign_verify, layouter, &sign_datas, challenges)?;
-        self.assign_tx_table(config, challenges, layouter, assigned_sig_verifs)?;
+        self.assign_tx_table(config, challenges, layouter, &assigned_sig_verifs)?;
+        config.tx_hash.assign(
+            layouter,
+            &self.txs,
+            &assigned_sig_verifs,
+            self.max_txs,
+            self.max_calldata,
+            self.chain_id,
+            challenges,
+        )?;
         Ok(())
     }
 
@@ -10,7 +10,7 @@ use crate::{
     util::{Challenges, Expr},
 };
 use ecc::{maingate, EccConfig, GeneralEccChip};
-use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey, EcdsaChip};
+use ecdsa::ecdsa::{AssignedEcdsaSig, AssignedPublicKey};
 use eth_types::{
     self, keccak256,
     sign_types::{pk_bytes_le, pk_bytes_swap_endianness, SignData},
@@ -20,10 +20,11 @@ use halo2_proofs::{
     arithmetic::CurveAffine,
     circuit::{AssignedCell, Cell, Layouter, Value},
     halo2curves::{
-        ff::PrimeField,
+        ff::{Field as _, PrimeField},
         group::{Curve, Group},
         secp256k1,
         secp256k1::Secp256k1Affine,
+        Coordinates,
     },
     plonk::{Advice, Column, ConstraintSystem, Error, Expression, SecondPhase, Selector},
     poly::Rotation,
@@ -87,7 +88,7 @@ impl<F: Field> SignVerifyChip<F> {
         let rows_range_chip_table = 295188;
         let rows_ecc_chip_aux = 226;
         let rows_ecdsa_chip_verification = 104471;
-        let rows_signature_address_verify = 76;
+        let rows_signature_address_verify = 92;
         std::cmp::max(
             rows_range_chip_table,
             (rows_ecc_chip_aux + rows_ecdsa_chip_verification + rows_signature_address_verify)
@@ -107,8 +108,8 @@ impl<F: Field> Default for SignVerifyChip<F> {
     }
 }
 
-const NUMBER_OF_LIMBS: usize = 4;
-const BIT_LEN_LIMB: usize = 72;
+pub(crate) const NUMBER_OF_LIMBS: usize = 4;
+pub(crate) const BIT_LEN_LIMB: usize = 72;
 const BIT_LEN_LAST_LIMB: usize = 256 - (NUMBER_OF_LIMBS - 1) * BIT_LEN_LIMB;
 
 /// SignVerify Configuration
@@ -212,7 +213,7 @@ impl SignVerifyConfig {
     }
 
     #[rustfmt::skip]
-    fn configure_rlc<F: Field>(
+    pub(crate) fn configure_rlc<F: Field>(
         meta: &mut ConstraintSystem<F>,
         name: &'static str,
         main_gate_config: MainGateConfig,
@@ -302,19 +303,59 @@ impl<F: Field> Term<F> {
 pub(crate) struct AssignedECDSA<F: Field> {
     pk_x_le: [AssignedValue<F>; 32],
     pk_y_le: [AssignedValue<F>; 32],
+    r_y_parity: AssignedValue<F>,
     msg_hash_le: [AssignedValue<F>; 32],
+    sig_r_le: [AssignedValue<F>; 32],
+    sig_s_le: [AssignedValue<F>; 32],
 }
 
 #[derive(Debug)]
 pub(crate) struct AssignedSignatureVerify<F: Field> {
     pub(crate) address: AssignedValue<F>,
     pub(crate) msg_hash_rlc: AssignedValue<F>,
+    pub(crate) sig_r_rlc: AssignedValue<F>,
+    pub(crate) sig_s_rlc: AssignedValue<F>,
+    /// Parity of the y coordinate of the point R of the signature, which is
+    /// the recovery id, and 0 for padding
+    pub(crate) r_y_parity: AssignedValue<F>,
+}
+
+/// Signature from which the point at infinity is recovered as public key,
+/// along with the point R of the signature, whose x coordinate is `r` and the
+/// parity of whose y coordinate is the recovery id.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct InfinitySignature {
+    pub(crate) msg_hash: secp256k1::Fq,
+    pub(crate) sig_s: secp256k1::Fq,
+    pub(crate) point_r: Secp256k1Affine,
+}
+
+impl InfinitySignature {
+    /// Padding with `msg_hash = s = 1` and `R = G`.
+    fn padding() -> Self {
+        Self {
+            msg_hash: secp256k1::Fq::ONE,
+            sig_s: secp256k1::Fq::ONE,
+            point_r: <Secp256k1Affine as CurveAffine>::CurveExt::generator().to_affine(),
+        }
+    }
+}
+
+/// Proof that the point at infinity is recovered from a signature.
+#[derive(Debug)]
+pub(crate) struct AssignedInfinitySignature<F: Field> {
+    pub(crate) msg_hash_rlc: AssignedValue<F>,
+    pub(crate) sig_r_rlc: AssignedValue<F>,
+    pub(crate) sig_s_rlc: AssignedValue<F>,
+    /// Parity of the y coordinate of the point R of the signature, which is
+    /// the recovery id
+    pub(crate) r_y_parity: AssignedValue<F>,
 }
 
 // Return an array of bytes that corresponds to the little endian representation
 // of the integer, adding the constraints to verify the correctness of the
 // conversion (byte range check included).
-fn integer_to_bytes_le<F: Field, FE: PrimeField>(
+pub(crate) fn integer_to_bytes_le<F: Field, FE: PrimeField>(
     ctx: &mut RegionCtx<'_, F>,
     range_chip: &RangeChip<F>,
     int: &AssignedInteger<FE, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
@@ -342,7 +383,6 @@ struct ChipsRef<'a, F: Field, const NUMBER_OF_LIMBS: usize, const BIT_LEN_LIMB:
     range_chip: &'a RangeChip<F>,
     ecc_chip: &'a GeneralEccChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
     scalar_chip: &'a IntegerChip<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
-    ecdsa_chip: &'a EcdsaChip<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
 }
 
 impl<F: Field> SignVerifyChip<F> {
@@ -370,11 +410,10 @@ impl<F: Field> SignVerifyChip<F> {
         let (sig_r, sig_s) = signature;
 
         let ChipsRef {
-            main_gate: _,
             range_chip,
             ecc_chip,
             scalar_chip,
-            ecdsa_chip,
+            ..
         } = chips;
 
         let integer_r = ecc_chip.new_unassigned_scalar(Value::known(*sig_r));
@@ -394,15 +433,17 @@ impl<F: Field> SignVerifyChip<F> {
         };
         let msg_hash = scalar_chip.assign_integer(ctx, msg_hash, Range::Remainder)?;
 
-        // Convert (msg_hash, pk_x, pk_y) integers to little endian bytes
+        // Convert (msg_hash, pk_x, pk_y, sig_r, sig_s) integers to little endian bytes
         let msg_hash_le = integer_to_bytes_le(ctx, range_chip, &msg_hash)?;
         let pk_x = pk_assigned.point.x();
         let pk_x_le = integer_to_bytes_le(ctx, range_chip, pk_x)?;
         let pk_y = pk_assigned.point.y();
         let pk_y_le = integer_to_bytes_le(ctx, range_chip, pk_y)?;
+        let sig_r_le = integer_to_bytes_le(ctx, range_chip, &sig.r)?;
+        let sig_s_le = integer_to_bytes_le(ctx, range_chip, &sig.s)?;
 
         // Ref. spec SignVerifyChip 4. Verify the ECDSA signature
-        ecdsa_chip.verify(ctx, &sig, &pk_assigned, &msg_hash)?;
+        let r_y_parity = self.verify_ecdsa(ctx, chips, &sig, &pk_assigned, &msg_hash)?;
 
         // TODO: Update once halo2wrong suports the following methods:
         // - `IntegerChip::assign_integer_from_bytes_le`
@@ -411,10 +452,63 @@ impl<F: Field> SignVerifyChip<F> {
         Ok(AssignedECDSA {
             pk_x_le,
             pk_y_le,
+            r_y_parity,
             msg_hash_le,
+            sig_r_le,
+            sig_s_le,
         })
     }
 
+    /// Verify the ECDSA signature as `EcdsaChip::verify` does, and return the
+    /// parity of the y coordinate of the point R = u1 * G + u2 * pk, whose x
+    /// coordinate is r, which is the recovery id of the signature.
+    fn verify_ecdsa(
+        &self,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        sig: &AssignedEcdsaSig<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        pk: &AssignedPublicKey<secp256k1::Fp, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        msg_hash: &AssignedInteger<secp256k1::Fq, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+    ) -> Result<AssignedValue<F>, Error> {
+        let ChipsRef {
+            main_gate,
+            ecc_chip,
+            scalar_chip,
+            ..
+        } = chips;
+        let base_chip = ecc_chip.base_field_chip();
+
+        // 0 < r, s < n, as assert_not_zero includes an in-field check
+        scalar_chip.assert_not_zero(ctx, &sig.r)?;
+        scalar_chip.assert_not_zero(ctx, &sig.s)?;
+
+        // u1 = msg_hash / s and u2 = r / s (mod n)
+        let (s_inv, _) = scalar_chip.invert(ctx, &sig.s)?;
+        let u1 = scalar_chip.mul(ctx, msg_hash, &s_inv)?;
+        let u2 = scalar_chip.mul(ctx, &sig.r, &s_inv)?;
+
+        // R = u1 * G + u2 * pk
+        let generator = ecc_chip.assign_point(
+            ctx,
+            Value::known(<Secp256k1Affine as CurveAffine>::CurveExt::generator().to_affine()),
+        )?;
+        let point_r = ecc_chip.mul_batch_1d_horizontal(
+            ctx,
+            vec![(generator, u1), (pk.point.clone(), u2)],
+            self.window_size,
+        )?;
+
+        // R.x == r (mod n)
+        let r_x = base_chip.reduce(ctx, point_r.x())?;
+        let r_x = scalar_chip.reduce_external(ctx, &r_x)?;
+        scalar_chip.assert_strict_equal(ctx, &r_x, &sig.r)?;
+
+        // The parity is the lowest bit of the y coordinate in the field.
+        let r_y = base_chip.reduce(ctx, point_r.y())?;
+        base_chip.assert_in_field(ctx, &r_y)?;
+        Ok(main_gate.to_bits(ctx, r_y.limbs()[0].as_ref(), BIT_LEN_LIMB)?[0].clone())
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn assign_rlc_le(
         &self,
@@ -460,6 +554,41 @@ impl<F: Field> SignVerifyChip<F> {
         Ok(assigned_rlc)
     }
 
+    /// Assign the RLC of a 32 bytes word from the ecdsa_chip, which is 0 when
+    /// the address is 0 (padding).
+    #[allow(clippy::too_many_arguments)]
+    fn assign_word_rlc_le(
+        &self,
+        config: &SignVerifyConfig,
+        ctx: &mut RegionCtx<F>,
+        chips: &ChipsRef<F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>,
+        name: &str,
+        is_address_zero: &AssignedCell<F, F>,
+        assigned_le: &[AssignedValue<F>; 32],
+        word_le: [u8; 32],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let main_gate = chips.main_gate;
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        let assigned_le = assigned_le
+            .iter()
+            .map(|byte| main_gate.select(ctx, &zero, byte, is_address_zero))
+            .collect::<Result<Vec<_>, _>>()?;
+        let word_le = word_le.map(|byte| Value::known(F::from(byte as u64)));
+        self.assign_rlc_le(
+            config,
+            ctx,
+            chips,
+            name,
+            config.q_rlc_evm_word,
+            challenges.evm_word(),
+            assigned_le
+                .iter()
+                .zip(word_le)
+                .map(|(assigned, byte)| Term::assigned(assigned.cell(), byte)),
+        )
+    }
+
     fn enable_keccak_lookup(
         &self,
         config: &SignVerifyConfig,
@@ -535,30 +664,45 @@ impl<F: Field> SignVerifyChip<F> {
 
         // Ref. spec SignVerifyChip 3. Verify that the signed message in the ecdsa_chip
         // with RLC encoding corresponds to msg_hash_rlc
-        let msg_hash_rlc = {
-            let zero = main_gate.assign_constant(ctx, F::ZERO)?;
-            let assigned_msg_hash_le = assigned_ecdsa
-                .msg_hash_le
-                .iter()
-                .map(|byte| main_gate.select(ctx, &zero, byte, &is_address_zero))
-                .collect::<Result<Vec<_>, _>>()?;
-            let msg_hash_le = (!padding)
+        let msg_hash_rlc = self.assign_word_rlc_le(
+            config,
+            ctx,
+            chips,
+            "msg_hash",
+            &is_address_zero,
+            &assigned_ecdsa.msg_hash_le,
+            (!padding)
                 .then(|| sign_data.msg_hash.to_bytes())
-                .unwrap_or_default()
-                .map(|byte| Value::known(F::from(byte as u64)));
-            self.assign_rlc_le(
-                config,
-                ctx,
-                chips,
-                "msg_hash",
-                config.q_rlc_evm_word,
-                challenges.evm_word(),
-                assigned_msg_hash_le
-                    .iter()
-                    .zip(msg_hash_le)
-                    .map(|(assigned, byte)| Term::assigned(assigned.cell(), byte)),
-            )?
-        };
+                .unwrap_or_default(),
+            challenges,
+        )?;
+
+        // Verify that the signature in the ecdsa_chip with RLC encoding
+        // corresponds to sig_r_rlc and sig_s_rlc
+        let sig_r_rlc = self.assign_word_rlc_le(
+            config,
+            ctx,
+            chips,
+            "sig_r",
+            &is_address_zero,
+            &assigned_ecdsa.sig_r_le,
+            (!padding)
+                .then(|| sign_data.signature.0.to_bytes())
+                .unwrap_or_default(),
+            challenges,
+        )?;
+        let sig_s_rlc = self.assign_word_rlc_le(
+            config,
+            ctx,
+            chips,
+            "sig_s",
+            &is_address_zero,
+            &assigned_ecdsa.sig_s_le,
+            (!padding)
+                .then(|| sign_data.signature.1.to_bytes())
+                .unwrap_or_default(),
+            challenges,
+        )?;
 
         let pk_rlc = {
             let assigned_pk_le = iter::empty()
@@ -595,9 +739,16 @@ impl<F: Field> SignVerifyChip<F> {
 
         self.enable_keccak_lookup(config, ctx, &is_address_zero, &pk_rlc, &pk_hash_rlc)?;
 
+        let zero = main_gate.assign_constant(ctx, F::ZERO)?;
+        let r_y_parity =
+            main_gate.select(ctx, &zero, &assigned_ecdsa.r_y_parity, &is_address_zero)?;
+
         Ok(AssignedSignatureVerify {
             address,
             msg_hash_rlc,
+            sig_r_rlc,
+            sig_s_rlc,
+            r_y_parity,
         })
     }
 
@@ -634,14 +785,11 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )?;
 
-        let ecdsa_chip = EcdsaChip::new(ecc_chip.clone());
-
         let chips = ChipsRef {
             main_gate: &main_gate,
             range_chip: &range_chip,
             ecc_chip: &ecc_chip,
             scalar_chip,
-            ecdsa_chip: &ecdsa_chip,
         };
 
         let assigned_ecdsas = layouter.assign_region(
@@ -686,6 +834,231 @@ impl<F: Field> SignVerifyChip<F> {
             },
         )
     }
+
+    /// Assign the proofs that the x coordinates are not the ones of any point
+    /// of the curve, so that no public key can be recovered from a signature
+    /// with them as `r`.  The x coordinate is off the curve when `x^3 + 7` is
+    /// not a square, which is proved by the square root of `-(x^3 + 7)`, as
+    /// -1 is not a square modulo the base field modulus.  Return the RLCs of
+    /// the x coordinates, which are `None` for padding.
+    pub(crate) fn assign_off_curve(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        xs: &[Option<secp256k1::Fp>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        if xs.len() > self.max_verif {
+            error!("xs.len() = {} > max_verif = {}", xs.len(), self.max_verif);
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let ecc_chip = GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(
+            config.ecc_chip_config(),
+        );
+        let base_chip = ecc_chip.base_field_chip();
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            scalar_chip: ecc_chip.scalar_field_chip(),
+        };
+
+        layouter.assign_region(
+            || "off curve verify",
+            |region| {
+                let mut assigned_xs_rlc = Vec::new();
+                let mut ctx = RegionCtx::new(region, 0);
+                for i in 0..self.max_verif {
+                    // padding with the smallest x coordinate off the curve
+                    let x = xs
+                        .get(i)
+                        .copied()
+                        .flatten()
+                        .unwrap_or_else(off_curve_x_padding);
+                    // The square root is 0 when the x coordinate is on the
+                    // curve, which fails the constraints.
+                    let root = Option::<secp256k1::Fp>::from(
+                        (-(x.square() * x + secp256k1::Fp::from(7))).sqrt(),
+                    )
+                    .unwrap_or_default();
+
+                    let assigned_x = base_chip.assign_integer(
+                        &mut ctx,
+                        ecc_chip.new_unassigned_base(Value::known(x)),
+                        Range::Remainder,
+                    )?;
+                    let assigned_root = base_chip.assign_integer(
+                        &mut ctx,
+                        ecc_chip.new_unassigned_base(Value::known(root)),
+                        Range::Remainder,
+                    )?;
+                    let seven = base_chip.assign_constant(&mut ctx, secp256k1::Fp::from(7))?;
+                    let x_square = base_chip.square(&mut ctx, &assigned_x)?;
+                    let x_cube = base_chip.mul(&mut ctx, &x_square, &assigned_x)?;
+                    let y_square = base_chip.add(&mut ctx, &x_cube, &seven)?;
+                    let root_square = base_chip.square(&mut ctx, &assigned_root)?;
+                    let sum = base_chip.add(&mut ctx, &root_square, &y_square)?;
+                    base_chip.assert_zero(&mut ctx, &sum)?;
+
+                    let x_le = integer_to_bytes_le(&mut ctx, &range_chip, &assigned_x)?;
+                    let assigned_x_rlc = self.assign_rlc_le(
+                        config,
+                        &mut ctx,
+                        &chips,
+                        "x",
+                        config.q_rlc_evm_word,
+                        challenges.evm_word(),
+                        x_le.iter().zip(x.to_bytes()).map(|(assigned, byte)| {
+                            Term::assigned(assigned.cell(), Value::known(F::from(byte as u64)))
+                        }),
+                    )?;
+                    assigned_xs_rlc.push(assigned_x_rlc);
+                }
+                log::debug!("off curve verify: {} rows", ctx.offset());
+                Ok(assigned_xs_rlc)
+            },
+        )
+    }
+
+    /// Assign the proofs that the public key recovered from the signatures is
+    /// the point at infinity, which is `r^-1 * (s * R - msg_hash * G)` for the
+    /// point R whose x coordinate is `r`, so that it's the case when
+    /// `R = msg_hash / s * G`.  Return the RLCs of the message hashes, `r` and
+    /// `s`, along with the parity of the y coordinate of R, which are the ones
+    /// of the padding for `None`.
+    pub(crate) fn assign_infinity(
+        &self,
+        config: &SignVerifyConfig,
+        layouter: &mut impl Layouter<F>,
+        signatures: &[Option<InfinitySignature>],
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<Vec<AssignedInfinitySignature<F>>, Error> {
+        if signatures.len() > self.max_verif {
+            error!(
+                "signatures.len() = {} > max_verif = {}",
+                signatures.len(),
+                self.max_verif
+            );
+            return Err(Error::Synthesis);
+        }
+        let main_gate = MainGate::new(config.main_gate_config.clone());
+        let range_chip = RangeChip::new(config.range_config.clone());
+        let mut ecc_chip = GeneralEccChip::<Secp256k1Affine, F, NUMBER_OF_LIMBS, BIT_LEN_LIMB>::new(
+            config.ecc_chip_config(),
+        );
+
+        layouter.assign_region(
+            || "ecc chip aux for infinity",
+            |region| {
+                let mut ctx = RegionCtx::new(region, 0);
+                ecc_chip.assign_aux_generator(&mut ctx, Value::known(self.aux_generator))?;
+                ecc_chip.assign_aux(&mut ctx, self.window_size, 1)?;
+                log::debug!("ecc chip aux for infinity: {} rows", ctx.offset());
+                Ok(())
+            },
+        )?;
+
+        let base_chip = ecc_chip.base_field_chip();
+        let scalar_chip = ecc_chip.scalar_field_chip();
+        let chips = ChipsRef {
+            main_gate: &main_gate,
+            range_chip: &range_chip,
+            ecc_chip: &ecc_chip,
+            scalar_chip,
+        };
+
+        layouter.assign_region(
+            || "infinity verify",
+            |region| {
+                let mut assigned_signatures = Vec::new();
+                let mut ctx = RegionCtx::new(region, 0);
+                for i in 0..self.max_verif {
+                    let signature = signatures
+                        .get(i)
+                        .copied()
+                        .flatten()
+                        .unwrap_or_else(InfinitySignature::padding);
+
+                    let msg_hash = scalar_chip.assign_integer(
+                        &mut ctx,
+                        ecc_chip.new_unassigned_scalar(Value::known(signature.msg_hash)),
+                        Range::Remainder,
+                    )?;
+                    let sig_s = scalar_chip.assign_integer(
+                        &mut ctx,
+                        ecc_chip.new_unassigned_scalar(Value::known(signature.sig_s)),
+                        Range::Remainder,
+                    )?;
+                    let point_r =
+                        ecc_chip.assign_point(&mut ctx, Value::known(signature.point_r))?;
+
+                    // R == msg_hash / s * G
+                    scalar_chip.assert_not_zero(&mut ctx, &sig_s)?;
+                    let (s_inv, _) = scalar_chip.invert(&mut ctx, &sig_s)?;
+                    let u1 = scalar_chip.mul(&mut ctx, &msg_hash, &s_inv)?;
+                    let generator = ecc_chip.assign_constant(
+                        &mut ctx,
+                        <Secp256k1Affine as CurveAffine>::CurveExt::generator().to_affine(),
+                    )?;
+                    let u1_g = ecc_chip.mul(&mut ctx, &generator, &u1, self.window_size)?;
+                    ecc_chip.assert_equal(&mut ctx, &u1_g, &point_r)?;
+
+                    // r is the x coordinate of R, and the recovery id the
+                    // parity of its y coordinate.
+                    let r_x = base_chip.reduce(&mut ctx, point_r.x())?;
+                    base_chip.assert_in_field(&mut ctx, &r_x)?;
+                    let r_y = base_chip.reduce(&mut ctx, point_r.y())?;
+                    base_chip.assert_in_field(&mut ctx, &r_y)?;
+                    let r_y_parity =
+                        main_gate.to_bits(&mut ctx, r_y.limbs()[0].as_ref(), BIT_LEN_LIMB)?[0]
+                            .clone();
+
+                    let point_r_coordinates =
+                        Option::<Coordinates<_>>::from(signature.point_r.coordinates())
+                            .expect("R is not the point at infinity");
+                    let mut rlcs = Vec::with_capacity(3);
+                    for (name, assigned, bytes) in [
+                        ("msg_hash", &msg_hash, signature.msg_hash.to_bytes()),
+                        ("sig_r", &r_x, point_r_coordinates.x().to_bytes()),
+                        ("sig_s", &sig_s, signature.sig_s.to_bytes()),
+                    ] {
+                        let assigned_le = integer_to_bytes_le(&mut ctx, &range_chip, assigned)?;
+                        rlcs.push(self.assign_rlc_le(
+                            config,
+                            &mut ctx,
+                            &chips,
+                            name,
+                            config.q_rlc_evm_word,
+                            challenges.evm_word(),
+                            assigned_le.iter().zip(bytes).map(|(assigned, byte)| {
+                                Term::assigned(assigned.cell(), Value::known(F::from(byte as u64)))
+                            }),
+                        )?);
+                    }
+                    let [msg_hash_rlc, sig_r_rlc, sig_s_rlc]: [_; 3] = rlcs.try_into().unwrap();
+                    assigned_signatures.push(AssignedInfinitySignature {
+                        msg_hash_rlc,
+                        sig_r_rlc,
+                        sig_s_rlc,
+                        r_y_parity,
+                    });
+                }
+                log::debug!("infinity verify: {} rows", ctx.offset());
+                Ok(assigned_signatures)
+            },
+        )
+    }
+}
+
+/// Return the smallest x coordinate that is not the one of any point of the
+/// curve.
+fn off_curve_x_padding() -> secp256k1::Fp {
+    (0u64..)
+        .map(secp256k1::Fp::from)
+        .find(|x| bool::from((x.square() * x + secp256k1::Fp::from(7)).sqrt().is_none()))
+        .expect("half of the x coordinates are off the curve")
 }
 
 fn pub_key_hash_to_address<F: Field>(pk_hash: &[u8]) -> F {
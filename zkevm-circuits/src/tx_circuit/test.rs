@@ -1,12 +1,14 @@
 #![allow(unused_imports)]
 use super::*;
 use crate::util::{log2_ceil, unusable_rows};
-use eth_types::address;
+use eth_types::{address, word, AccessList, AccessListItem, Bytes, H256};
 use halo2_proofs::{
     dev::{MockProver, VerifyFailure},
     halo2curves::bn256::Fr,
 };
-use mock::AddrOrWallet;
+use mock::{AddrOrWallet, MockTransaction, MOCK_ACCOUNTS, MOCK_BLOB_VERSIONED_HASHES};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
 
 #[test]
 fn tx_circuit_unusable_rows() {
@@ -79,6 +81,119 @@ fn tx_circuit_1tx_2max_tx() {
     assert_eq!(run::<Fr>(vec![tx], chain_id, MAX_TXS, MAX_CALLDATA), Ok(()));
 }
 
+/// Return a tx of the given type signed by a random wallet, with an access
+/// list for the typed txs and blobs for the blob txs.
+fn signed_tx(rng: &mut ChaCha20Rng, transaction_type: u64, input: Bytes) -> Transaction {
+    let mut tx = MockTransaction::default();
+    tx.from(AddrOrWallet::random(rng))
+        .to(MOCK_ACCOUNTS[0])
+        .nonce(0x103u64)
+        .value(word!("0x3e8"))
+        .gas_price(word!("0x4d2"))
+        .input(input)
+        .transaction_type(transaction_type);
+    if transaction_type != 0 {
+        tx.access_list(AccessList(vec![AccessListItem {
+            address: MOCK_ACCOUNTS[1],
+            storage_keys: vec![H256::from_low_u64_be(1)],
+        }]));
+    }
+    if transaction_type >= 2 {
+        tx.max_fee_per_gas(word!("0x4d2"))
+            .max_priority_fee_per_gas(word!("0x2"));
+    }
+    if transaction_type == 3 {
+        tx.max_fee_per_blob_gas(word!("0x7"))
+            .blob_versioned_hashes(MOCK_BLOB_VERSIONED_HASHES.clone());
+    }
+    tx.build().into()
+}
+
+#[test]
+fn tx_circuit_typed_txs() {
+    const MAX_TXS: usize = 4;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let txs = [0, 1, 2]
+        .map(|transaction_type| signed_tx(&mut rng, transaction_type, Bytes::from(b"hello")))
+        .to_vec();
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID.as_u64(), MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_blob_tx() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 32;
+
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let txs = [2, 3]
+        .map(|transaction_type| signed_tx(&mut rng, transaction_type, Bytes::from(b"hello")))
+        .to_vec();
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID.as_u64(), MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_access_list() {
+    const MAX_TXS: usize = 1;
+    const MAX_CALLDATA: usize = 32;
+
+    // An item with several storage keys has a long RLP header, and an item
+    // may have no storage keys.
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let mut tx = MockTransaction::default();
+    tx.from(AddrOrWallet::random(&mut rng))
+        .to(MOCK_ACCOUNTS[0])
+        .nonce(0x103u64)
+        .gas_price(word!("0x4d2"))
+        .transaction_type(1)
+        .access_list(AccessList(vec![
+            AccessListItem {
+                address: MOCK_ACCOUNTS[1],
+                storage_keys: vec![H256::from_low_u64_be(1), H256::zero()],
+            },
+            AccessListItem {
+                address: MOCK_ACCOUNTS[2],
+                storage_keys: vec![],
+            },
+        ]));
+
+    assert_eq!(
+        run::<Fr>(
+            vec![tx.build().into()],
+            mock::MOCK_CHAIN_ID.as_u64(),
+            MAX_TXS,
+            MAX_CALLDATA
+        ),
+        Ok(())
+    );
+}
+
+#[test]
+fn tx_circuit_long_calldata() {
+    const MAX_TXS: usize = 2;
+    const MAX_CALLDATA: usize = 256;
+
+    // Call data of more than 55 bytes has a long RLP header.
+    let mut rng = ChaCha20Rng::seed_from_u64(2u64);
+    let txs = [0, 2]
+        .map(|transaction_type| signed_tx(&mut rng, transaction_type, Bytes::from(vec![0xab; 100])))
+        .to_vec();
+
+    assert_eq!(
+        run::<Fr>(txs, mock::MOCK_CHAIN_ID.as_u64(), MAX_TXS, MAX_CALLDATA),
+        Ok(())
+    );
+}
+
 #[test]
 fn tx_circuit_bad_address() {
     const MAX_TXS: usize = 1;
@@ -0,0 +1,1958 @@
+//! Circuit to verify the hashes of the transactions.
+//!
+//! The encoding of every signed transaction (the RLP list of its fields,
+//! prefixed by its type for typed transactions) is laid out one byte per row,
+//! split into items of consecutive bytes for each field.  The RLP headers of
+//! the items are verified against their lengths, the values of the items are
+//! looked up in the `TxTable`, and the keccak of the whole encoding is looked
+//! up in the `KeccakTable` and exposed as the `TxHash` field of the `TxTable`.
+//!
+//! The access list is split into the items of its addresses and storage keys,
+//! whose numbers and RLCs are looked up in the `TxTable` as the ones added to
+//! the access list of the transaction in the BeginTx step.
+//!
+//! The list of the blob versioned hashes is split into its hashes, which are
+//! looked up in the `TxTable` as the `BlobVersionedHash` fields of the
+//! transaction, and whose number is looked up as its `BlobVersionedHashesLen`.
+//!
+//! The signature values r and s are looked up in the `TxTable`, where they are
+//! copied from the `SignVerifyChip`, and v is the recovery id verified in the
+//! `SignVerifyChip`, which is copied to the rows of the transaction.
+
+use crate::{
+    evm_circuit::util::{
+        constraint_builder::{BaseConstraintBuilder, ConstrainBuilderCommon},
+        rlc,
+    },
+    impl_expr,
+    table::{KeccakTable, LookupTable, TxFieldTag, TxTable},
+    util::{Challenges, Expr},
+};
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_TX, geth_types::Transaction, Field, ToLittleEndian, Word,
+};
+use ethers_core::utils::rlp;
+use gadgets::{
+    binary_number::{BinaryNumberChip, BinaryNumberConfig},
+    is_zero::{IsZeroChip, IsZeroConfig, IsZeroInstruction},
+    rlp_item::{RlpItemChip, RlpItemConfig, RlpItemFlags, RlpItemRow},
+    util::{not, select, sum},
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{
+        Advice, Column, ConstraintSystem, Error, Expression, Fixed, SecondPhase, Selector,
+        TableColumn, VirtualCells,
+    },
+    poly::Rotation,
+};
+use log::error;
+use std::{iter, marker::PhantomData};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+use super::sign_verify::AssignedSignatureVerify;
+
+/// Upper bound of the length of the encoding of a transaction without its
+/// call data and with an empty access list: 1 byte for the type and the empty
+/// access list, 4 bytes for the list and call data headers, 9 bytes for the
+/// chain id, nonce, gas and v, 33 bytes for the fee fields, value, r and s, 21
+/// bytes for the callee address, and 2 bytes for the header of the list of
+/// blob versioned hashes with 33 bytes for each of them.
+pub(crate) const MAX_TX_RLP_STATIC_LEN: usize = 267 + 33 * MAX_BLOBS_PER_TX;
+
+const MAX_DEGREE: usize = 9;
+
+/// Tag of the items of the encoding of a signed transaction
+#[derive(Clone, Copy, Debug, PartialEq, Eq, EnumIter)]
+pub(crate) enum RlpTxTag {
+    /// Rows after the encodings of all the transactions
+    Padding = 0,
+    /// Type of a typed transaction (EIP-2718), which is not RLP encoded
+    TxType,
+    /// Header of the RLP list of the fields of the transaction
+    ListHeader,
+    /// Chain ID
+    ChainId,
+    /// Nonce
+    Nonce,
+    /// Gas price of legacy and EIP-2930 transactions
+    GasPrice,
+    /// Max priority fee per gas (EIP-1559)
+    MaxPriorityFeePerGas,
+    /// Max fee per gas (EIP-1559)
+    MaxFeePerGas,
+    /// Gas limit
+    Gas,
+    /// Callee address, which is empty for contract creations
+    To,
+    /// Value
+    Value,
+    /// Call data
+    Data,
+    /// Header of the RLP list of the access list (EIP-2930)
+    AccessList,
+    /// Header of the RLP list of an item of the access list
+    AccessListItem,
+    /// Address of an item of the access list
+    AccessListAddress,
+    /// Header of the RLP list of the storage keys of an item of the access
+    /// list
+    AccessListStorageKeys,
+    /// Storage key of an item of the access list
+    AccessListStorageKey,
+    /// Max fee per blob gas (EIP-4844)
+    MaxFeePerBlobGas,
+    /// Header of the RLP list of the blob versioned hashes (EIP-4844)
+    BlobVersionedHashes,
+    /// Blob versioned hash
+    BlobVersionedHash,
+    /// V of the signature
+    SigV,
+    /// R of the signature
+    SigR,
+    /// S of the signature
+    SigS,
+}
+
+impl From<RlpTxTag> for usize {
+    fn from(tag: RlpTxTag) -> Self {
+        tag as usize
+    }
+}
+
+impl Default for RlpTxTag {
+    fn default() -> Self {
+        Self::Padding
+    }
+}
+
+impl_expr!(RlpTxTag);
+
+impl RlpTxTag {
+    /// Tags of the items of the encoding of a transaction of the given type
+    fn sequence(tx_type: u64) -> &'static [Self] {
+        use RlpTxTag::*;
+        match tx_type {
+            0 => &[
+                ListHeader, Nonce, GasPrice, Gas, To, Value, Data, SigV, SigR, SigS,
+            ],
+            1 => &[
+                TxType, ListHeader, ChainId, Nonce, GasPrice, Gas, To, Value, Data, AccessList,
+                SigV, SigR, SigS,
+            ],
+            2 => &[
+                TxType,
+                ListHeader,
+                ChainId,
+                Nonce,
+                MaxPriorityFeePerGas,
+                MaxFeePerGas,
+                Gas,
+                To,
+                Value,
+                Data,
+                AccessList,
+                SigV,
+                SigR,
+                SigS,
+            ],
+            _ => &[
+                TxType,
+                ListHeader,
+                ChainId,
+                Nonce,
+                MaxPriorityFeePerGas,
+                MaxFeePerGas,
+                Gas,
+                To,
+                Value,
+                Data,
+                AccessList,
+                MaxFeePerBlobGas,
+                BlobVersionedHashes,
+                SigV,
+                SigR,
+                SigS,
+            ],
+        }
+    }
+
+    /// Field of the `TxTable` with the value of the item
+    fn field_tag(&self) -> Option<TxFieldTag> {
+        match self {
+            Self::ChainId => Some(TxFieldTag::ChainId),
+            Self::Nonce => Some(TxFieldTag::Nonce),
+            Self::GasPrice => Some(TxFieldTag::GasPrice),
+            Self::MaxPriorityFeePerGas => Some(TxFieldTag::MaxPriorityFeePerGas),
+            Self::MaxFeePerGas => Some(TxFieldTag::MaxFeePerGas),
+            Self::Gas => Some(TxFieldTag::Gas),
+            Self::To => Some(TxFieldTag::CalleeAddress),
+            Self::Value => Some(TxFieldTag::Value),
+            Self::MaxFeePerBlobGas => Some(TxFieldTag::MaxFeePerBlobGas),
+            Self::SigR => Some(TxFieldTag::SigR),
+            Self::SigS => Some(TxFieldTag::SigS),
+            _ => None,
+        }
+    }
+
+    /// Whether the item is a word, whose value is the RLC of its bytes
+    fn is_word(&self) -> bool {
+        matches!(
+            self,
+            Self::GasPrice
+                | Self::MaxPriorityFeePerGas
+                | Self::MaxFeePerGas
+                | Self::MaxFeePerBlobGas
+                | Self::Value
+                | Self::SigR
+                | Self::SigS
+                | Self::AccessListStorageKey
+                | Self::BlobVersionedHash
+        )
+    }
+
+    /// Whether the item is a scalar, which is encoded without leading zeros
+    fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::ChainId
+                | Self::Nonce
+                | Self::GasPrice
+                | Self::MaxPriorityFeePerGas
+                | Self::MaxFeePerGas
+                | Self::Gas
+                | Self::Value
+                | Self::MaxFeePerBlobGas
+                | Self::SigV
+                | Self::SigR
+                | Self::SigS
+        )
+    }
+
+    /// Whether the item is taken as raw bytes instead of an RLP string
+    fn is_raw(&self) -> bool {
+        matches!(self, Self::TxType)
+    }
+
+    /// Whether the item is the header of an RLP list
+    fn is_list(&self) -> bool {
+        matches!(
+            self,
+            Self::ListHeader
+                | Self::AccessList
+                | Self::AccessListItem
+                | Self::AccessListStorageKeys
+                | Self::BlobVersionedHashes
+        )
+    }
+
+    /// Whether the item is part of the access list
+    fn is_access_list(&self) -> bool {
+        matches!(
+            self,
+            Self::AccessList
+                | Self::AccessListItem
+                | Self::AccessListAddress
+                | Self::AccessListStorageKeys
+                | Self::AccessListStorageKey
+        )
+    }
+
+    /// Whether the item is part of the list of the blob versioned hashes
+    fn is_blob_hashes(&self) -> bool {
+        matches!(self, Self::BlobVersionedHashes | Self::BlobVersionedHash)
+    }
+
+    /// Pairs of tags of the consecutive items of the encoding of a transaction
+    /// of the given type.  The items of the access list repeat, and the access
+    /// list ends after its header, the header of the storage keys of an item
+    /// or a storage key.  The blob versioned hashes repeat, and their list
+    /// ends after its header or a hash.
+    fn transitions(tx_type: u64) -> Vec<(Self, Self)> {
+        use RlpTxTag::*;
+        Self::sequence(tx_type)
+            .windows(2)
+            .flat_map(|tags| match tags {
+                &[AccessList, next] => vec![
+                    (AccessList, AccessListItem),
+                    (AccessListItem, AccessListAddress),
+                    (AccessListAddress, AccessListStorageKeys),
+                    (AccessListStorageKeys, AccessListStorageKey),
+                    (AccessListStorageKeys, AccessListItem),
+                    (AccessListStorageKey, AccessListStorageKey),
+                    (AccessListStorageKey, AccessListItem),
+                    (AccessList, next),
+                    (AccessListStorageKeys, next),
+                    (AccessListStorageKey, next),
+                ],
+                &[BlobVersionedHashes, next] => vec![
+                    (BlobVersionedHashes, BlobVersionedHash),
+                    (BlobVersionedHash, BlobVersionedHash),
+                    (BlobVersionedHashes, next),
+                    (BlobVersionedHash, next),
+                ],
+                _ => vec![(tags[0], tags[1])],
+            })
+            .collect()
+    }
+}
+
+/// Columns with values that only depend on the tag, which are constrained from
+/// it so that they can be used in low degree expressions and lookups.
+#[derive(Clone, Copy, Debug)]
+struct TagFlags {
+    field_tag: Column<Advice>,
+    has_field: Column<Advice>,
+    is_word: Column<Advice>,
+    is_numeric: Column<Advice>,
+    is_raw: Column<Advice>,
+    is_list: Column<Advice>,
+    is_list_header: Column<Advice>,
+    is_data: Column<Advice>,
+    is_to: Column<Advice>,
+    is_gas_price: Column<Advice>,
+    is_access_list: Column<Advice>,
+    is_access_list_header: Column<Advice>,
+    is_access_list_item: Column<Advice>,
+    is_access_list_address: Column<Advice>,
+    is_storage_keys: Column<Advice>,
+    is_storage_key: Column<Advice>,
+    is_blob_hashes: Column<Advice>,
+    is_blob_hashes_header: Column<Advice>,
+    is_blob_hash: Column<Advice>,
+}
+
+impl TagFlags {
+    fn configure<F: Field>(meta: &mut ConstraintSystem<F>) -> Self {
+        Self {
+            field_tag: meta.advice_column(),
+            has_field: meta.advice_column(),
+            is_word: meta.advice_column(),
+            is_numeric: meta.advice_column(),
+            is_raw: meta.advice_column(),
+            is_list: meta.advice_column(),
+            is_list_header: meta.advice_column(),
+            is_data: meta.advice_column(),
+            is_to: meta.advice_column(),
+            is_gas_price: meta.advice_column(),
+            is_access_list: meta.advice_column(),
+            is_access_list_header: meta.advice_column(),
+            is_access_list_item: meta.advice_column(),
+            is_access_list_address: meta.advice_column(),
+            is_storage_keys: meta.advice_column(),
+            is_storage_key: meta.advice_column(),
+            is_blob_hashes: meta.advice_column(),
+            is_blob_hashes_header: meta.advice_column(),
+            is_blob_hash: meta.advice_column(),
+        }
+    }
+
+    /// The columns with the function that gives their value for a tag
+    #[allow(clippy::type_complexity)]
+    fn columns(&self) -> [(&'static str, Column<Advice>, fn(RlpTxTag) -> u64); 19] {
+        [
+            ("field_tag", self.field_tag, |tag: RlpTxTag| {
+                tag.field_tag().map_or(0, |field_tag| field_tag as u64)
+            }),
+            ("has_field", self.has_field, |tag: RlpTxTag| {
+                tag.field_tag().is_some() as u64
+            }),
+            ("is_word", self.is_word, |tag: RlpTxTag| {
+                tag.is_word() as u64
+            }),
+            ("is_numeric", self.is_numeric, |tag: RlpTxTag| {
+                tag.is_numeric() as u64
+            }),
+            ("is_raw", self.is_raw, |tag: RlpTxTag| tag.is_raw() as u64),
+            ("is_list", self.is_list, |tag: RlpTxTag| {
+                tag.is_list() as u64
+            }),
+            ("is_list_header", self.is_list_header, |tag: RlpTxTag| {
+                (tag == RlpTxTag::ListHeader) as u64
+            }),
+            ("is_data", self.is_data, |tag: RlpTxTag| {
+                (tag == RlpTxTag::Data) as u64
+            }),
+            ("is_to", self.is_to, |tag: RlpTxTag| {
+                (tag == RlpTxTag::To) as u64
+            }),
+            ("is_gas_price", self.is_gas_price, |tag: RlpTxTag| {
+                (tag == RlpTxTag::GasPrice) as u64
+            }),
+            ("is_access_list", self.is_access_list, |tag: RlpTxTag| {
+                tag.is_access_list() as u64
+            }),
+            (
+                "is_access_list_header",
+                self.is_access_list_header,
+                |tag: RlpTxTag| (tag == RlpTxTag::AccessList) as u64,
+            ),
+            (
+                "is_access_list_item",
+                self.is_access_list_item,
+                |tag: RlpTxTag| (tag == RlpTxTag::AccessListItem) as u64,
+            ),
+            (
+                "is_access_list_address",
+                self.is_access_list_address,
+                |tag: RlpTxTag| (tag == RlpTxTag::AccessListAddress) as u64,
+            ),
+            ("is_storage_keys", self.is_storage_keys, |tag: RlpTxTag| {
+                (tag == RlpTxTag::AccessListStorageKeys) as u64
+            }),
+            ("is_storage_key", self.is_storage_key, |tag: RlpTxTag| {
+                (tag == RlpTxTag::AccessListStorageKey) as u64
+            }),
+            ("is_blob_hashes", self.is_blob_hashes, |tag: RlpTxTag| {
+                tag.is_blob_hashes() as u64
+            }),
+            (
+                "is_blob_hashes_header",
+                self.is_blob_hashes_header,
+                |tag: RlpTxTag| (tag == RlpTxTag::BlobVersionedHashes) as u64,
+            ),
+            ("is_blob_hash", self.is_blob_hash, |tag: RlpTxTag| {
+                (tag == RlpTxTag::BlobVersionedHash) as u64
+            }),
+        ]
+    }
+}
+
+/// Witness of a row of the encoding of a transaction
+#[derive(Clone, Debug)]
+struct TxHashRow<F> {
+    tx_id: usize,
+    tx_type: u64,
+    chain_id: u64,
+    sig_v_base: u64,
+    v_parity: u64,
+    tag: RlpTxTag,
+    byte: u8,
+    item: RlpItemRow<F>,
+    bytes_rlc: Value<F>,
+    bytes_len: usize,
+    list_end: usize,
+    access_list_end: usize,
+    item_end: usize,
+    access_list_rlc: Value<F>,
+    access_list_address: Value<F>,
+    addresses_len: u64,
+    addresses_rlc: Value<F>,
+    storage_keys_len: u64,
+    storage_keys_rlc: Value<F>,
+    blob_hashes_end: usize,
+    blob_hashes_rlc: Value<F>,
+    blob_hashes_len: u64,
+    is_first: bool,
+    is_last: bool,
+    is_padding: bool,
+    hash: Value<F>,
+}
+
+impl<F: Field> TxHashRow<F> {
+    fn padding() -> Self {
+        Self {
+            tx_id: 0,
+            tx_type: 0,
+            chain_id: 0,
+            sig_v_base: 0,
+            v_parity: 0,
+            tag: RlpTxTag::Padding,
+            byte: 0,
+            item: RlpItemRow::padding(),
+            bytes_rlc: Value::known(F::ZERO),
+            bytes_len: 0,
+            list_end: 0,
+            access_list_end: 0,
+            item_end: 0,
+            access_list_rlc: Value::known(F::ZERO),
+            access_list_address: Value::known(F::ZERO),
+            addresses_len: 0,
+            addresses_rlc: Value::known(F::ZERO),
+            storage_keys_len: 0,
+            storage_keys_rlc: Value::known(F::ZERO),
+            blob_hashes_end: 0,
+            blob_hashes_rlc: Value::known(F::ZERO),
+            blob_hashes_len: 0,
+            is_first: false,
+            is_last: false,
+            is_padding: true,
+            hash: Value::known(F::ZERO),
+        }
+    }
+}
+
+/// Config to verify the RLP encodings and the hashes of the transactions
+#[derive(Clone, Debug)]
+pub(crate) struct TxHashConfig<F> {
+    q_enable: Column<Fixed>,
+    q_first: Column<Fixed>,
+    tx_id: Column<Advice>,
+    tx_type: Column<Advice>,
+    chain_id: Column<Advice>,
+    // Offset of v in the signature: 2 * chain_id + 35 for legacy transactions
+    // (EIP-155) and 0 for typed transactions.
+    sig_v_base: Column<Advice>,
+    // Recovery id of the signature, copied from the SignVerifyChip.
+    v_parity: Column<Advice>,
+    tag: BinaryNumberConfig<RlpTxTag, 5>,
+    flags: TagFlags,
+    byte: Column<Advice>,
+    // Decoding of the RLP items of the encoding
+    item: RlpItemConfig,
+    bytes_rlc: Column<Advice>,
+    bytes_len: Column<Advice>,
+    list_end: Column<Advice>,
+    // Ends of the access list and of its current item, which is also the end
+    // of the storage keys of the item.
+    access_list_end: Column<Advice>,
+    item_end: Column<Advice>,
+    at_access_list_end: IsZeroConfig<F>,
+    at_item_end: IsZeroConfig<F>,
+    // RLC of the bytes of the access list, which is its AccessListRLC field.
+    access_list_rlc: Column<Advice>,
+    // Address of the current item of the access list.
+    access_list_address: Column<Advice>,
+    // Number and RLC of the addresses and the storage keys of the access list
+    // accumulated up to the row, as they are added to the access list of the
+    // tx in the BeginTx step.
+    addresses_len: Column<Advice>,
+    addresses_rlc: Column<Advice>,
+    storage_keys_len: Column<Advice>,
+    storage_keys_rlc: Column<Advice>,
+    // End of the list of the blob versioned hashes.
+    blob_hashes_end: Column<Advice>,
+    at_blob_hashes_end: IsZeroConfig<F>,
+    // RLC of the bytes of the list of the blob versioned hashes, which is its
+    // BlobVersionedHashesRLC field.
+    blob_hashes_rlc: Column<Advice>,
+    // Number of the blob versioned hashes up to the row, which is the index
+    // of the next hash.
+    blob_hashes_len: Column<Advice>,
+    is_first: Column<Advice>,
+    is_last: Column<Advice>,
+    is_padding: Column<Advice>,
+    hash: Column<Advice>,
+    u8_table: TableColumn,
+    // (tx_type, tag, next_tag) of the consecutive items of the encodings, and
+    // (tx_type, Padding, tag) of their first items.
+    transition_table: [TableColumn; 3],
+    _marker: PhantomData<F>,
+}
+
+impl<F: Field> TxHashConfig<F> {
+    /// Return a new TxHashConfig, which also verifies that the rows of the
+    /// `TxTable` enabled by `q_tx_hash` have the hashes of the transactions.
+    pub(crate) fn new(
+        meta: &mut ConstraintSystem<F>,
+        q_tx_hash: Selector,
+        tx_table: &TxTable,
+        keccak_table: &KeccakTable,
+        challenges: &Challenges<Expression<F>>,
+    ) -> Self {
+        let q_enable = meta.fixed_column();
+        let q_first = meta.fixed_column();
+        let tx_id = meta.advice_column();
+        let tx_type = meta.advice_column();
+        let chain_id = meta.advice_column();
+        let sig_v_base = meta.advice_column();
+        let v_parity = meta.advice_column();
+        meta.enable_equality(v_parity);
+        let tag = BinaryNumberChip::configure(meta, q_enable, None);
+        let flags = TagFlags::configure(meta);
+        let byte = meta.advice_column();
+        let bytes_rlc = meta.advice_column_in(SecondPhase);
+        let bytes_len = meta.advice_column();
+        let list_end = meta.advice_column();
+        let access_list_end = meta.advice_column();
+        let item_end = meta.advice_column();
+        let access_list_rlc = meta.advice_column_in(SecondPhase);
+        let access_list_address = meta.advice_column();
+        let addresses_len = meta.advice_column();
+        let addresses_rlc = meta.advice_column_in(SecondPhase);
+        let storage_keys_len = meta.advice_column();
+        let storage_keys_rlc = meta.advice_column_in(SecondPhase);
+        let blob_hashes_end = meta.advice_column();
+        let blob_hashes_rlc = meta.advice_column_in(SecondPhase);
+        let blob_hashes_len = meta.advice_column();
+        let is_first = meta.advice_column();
+        let is_last = meta.advice_column();
+        let is_padding = meta.advice_column();
+        let hash = meta.advice_column_in(SecondPhase);
+        let u8_table = meta.lookup_table_column();
+        let transition_table = [(); 3].map(|_| meta.lookup_table_column());
+
+        let item = RlpItemChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            byte,
+            |meta| {
+                // Words are accumulated as the RLC of their little-endian
+                // bytes and other values as big-endian integers.
+                let [is_word, is_raw, is_list] = [flags.is_word, flags.is_raw, flags.is_list]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+                RlpItemFlags {
+                    is_list: is_list.clone(),
+                    is_header_only: is_list,
+                    is_raw,
+                    multiplier: is_word.clone() * challenges.evm_word()
+                        + not::expr(is_word) * 256.expr(),
+                }
+            },
+            3,
+            u8_table,
+        );
+        let RlpItemConfig {
+            is_header,
+            is_item_first,
+            is_item_last,
+            length,
+            index,
+            value_acc,
+            ..
+        } = item;
+
+        // 1 for legacy transactions and 0 for the types 1, 2 and 3
+        let is_legacy = |meta: &mut VirtualCells<'_, F>| {
+            let tx_type = meta.query_advice(tx_type, Rotation::cur());
+            (1.expr() - tx_type.clone())
+                * (2.expr() - tx_type.clone())
+                * (3.expr() - tx_type)
+                * Expression::Constant(F::from(6).invert().unwrap())
+        };
+        // 1 for blob transactions (EIP-4844) and 0 for the types 0, 1 and 2
+        let is_blob = |meta: &mut VirtualCells<'_, F>| {
+            let tx_type = meta.query_advice(tx_type, Rotation::cur());
+            tx_type.clone()
+                * (tx_type.clone() - 1.expr())
+                * (tx_type - 2.expr())
+                * Expression::Constant(F::from(6).invert().unwrap())
+        };
+        let is_content_first = |meta: &mut VirtualCells<'_, F>| item.is_content_first(meta);
+
+        let at_access_list_end = IsZeroChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            |meta| {
+                meta.query_advice(access_list_end, Rotation::cur())
+                    - meta.query_advice(bytes_len, Rotation::cur())
+            },
+            meta.advice_column(),
+        );
+        let at_item_end = IsZeroChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            |meta| {
+                meta.query_advice(item_end, Rotation::cur())
+                    - meta.query_advice(bytes_len, Rotation::cur())
+            },
+            meta.advice_column(),
+        );
+        let at_blob_hashes_end = IsZeroChip::configure(
+            meta,
+            |meta| {
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur()))
+            },
+            |meta| {
+                meta.query_advice(blob_hashes_end, Rotation::cur())
+                    - meta.query_advice(bytes_len, Rotation::cur())
+            },
+            meta.advice_column(),
+        );
+
+        meta.create_gate("tx hash padding", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_padding_next = meta.query_advice(is_padding, Rotation::next());
+            let is_padding = meta.query_advice(is_padding, Rotation::cur());
+            cb.require_boolean("is_padding is boolean", is_padding.clone());
+            let disabled = [is_first, is_last, is_item_last, is_header]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let tag = tag.value(Rotation::cur())(meta);
+            cb.condition(is_padding, |cb| {
+                cb.require_equal("padding is followed by padding", is_padding_next, 1.expr());
+                cb.require_zero("tag is Padding in padding rows", tag);
+                for value in disabled {
+                    cb.require_zero("padding rows are not part of a tx", value);
+                }
+            });
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("tx hash first row", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_first = meta.query_advice(is_first, Rotation::cur());
+            cb.require_equal(
+                "the first row starts the first tx unless there are no txs",
+                is_first.clone(),
+                not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            );
+            cb.require_zero(
+                "the tx_id of the first tx is 1",
+                is_first * (meta.query_advice(tx_id, Rotation::cur()) - 1.expr()),
+            );
+
+            cb.gate(meta.query_fixed(q_first, Rotation::cur()))
+        });
+
+        meta.create_gate("tx hash tag", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            for (name, column, value) in flags.columns() {
+                let expected = sum::expr(
+                    RlpTxTag::iter()
+                        .filter(|&t| value(t) != 0)
+                        .map(|t| tag.value_equals(t, Rotation::cur())(meta) * value(t).expr()),
+                );
+                cb.require_equal(name, meta.query_advice(column, Rotation::cur()), expected);
+            }
+
+            let is_item_last = meta.query_advice(is_item_last, Rotation::cur());
+            let is_tx_type = tag.value_equals(RlpTxTag::TxType, Rotation::cur())(meta);
+            cb.require_zero(
+                "TxType is a single byte",
+                is_tx_type.clone() * not::expr(is_item_last.clone()),
+            );
+            cb.require_zero(
+                "TxType is the type of the tx",
+                is_tx_type
+                    * (meta.query_advice(byte, Rotation::cur())
+                        - meta.query_advice(tx_type, Rotation::cur())),
+            );
+
+            cb.condition(
+                tag.value_equals(RlpTxTag::SigV, Rotation::cur())(meta) * is_item_last,
+                |cb| {
+                    cb.require_equal(
+                        "v is the recovery id, offset by 2 * chain_id + 35 for legacy txs",
+                        meta.query_advice(value_acc, Rotation::cur()),
+                        meta.query_advice(sig_v_base, Rotation::cur())
+                            + meta.query_advice(v_parity, Rotation::cur()),
+                    );
+                },
+            );
+
+            cb.require_zero(
+                "the tx ends with S",
+                meta.query_advice(is_last, Rotation::cur())
+                    * not::expr(tag.value_equals(RlpTxTag::SigS, Rotation::cur())(meta)),
+            );
+
+            cb.gate(meta.query_fixed(q_enable, Rotation::cur()))
+        });
+
+        meta.create_gate("tx hash tx", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let is_legacy = is_legacy(meta);
+            let is_item_last_prev = meta.query_advice(is_item_last, Rotation::prev());
+            let [is_first_next, is_padding_next, tx_id_next] = [is_first, is_padding, tx_id]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let [byte_next, bytes_rlc_next, bytes_len_next] = [byte, bytes_rlc, bytes_len]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let tx_values = [
+                tx_id,
+                tx_type,
+                chain_id,
+                sig_v_base,
+                v_parity,
+                list_end,
+                access_list_end,
+                blob_hashes_end,
+                hash,
+            ]
+            .map(|column| {
+                (
+                    meta.query_advice(column, Rotation::cur()),
+                    meta.query_advice(column, Rotation::next()),
+                )
+            });
+            let [tx_id, chain_id, sig_v_base, byte, bytes_rlc, bytes_len, list_end] = [
+                tx_id, chain_id, sig_v_base, byte, bytes_rlc, bytes_len, list_end,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_first, is_item_last, is_first, is_last] =
+                [is_item_first, is_item_last, is_first, is_last]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            for (name, value) in [
+                ("is_first is boolean", is_first.clone()),
+                ("is_last is boolean", is_last.clone()),
+            ] {
+                cb.require_boolean(name, value);
+            }
+            cb.require_equal(
+                "an item starts with the tx or after the end of the previous item",
+                is_item_first,
+                is_first.clone() + not::expr(is_first.clone()) * is_item_last_prev,
+            );
+            cb.require_equal(
+                "a tx starts after the end of the previous one unless padding follows",
+                is_first_next,
+                is_last.clone() * not::expr(is_padding_next.clone()),
+            );
+
+            cb.condition(is_first, |cb| {
+                cb.require_equal("bytes_len starts at 1", bytes_len.clone(), 1.expr());
+                cb.require_equal("bytes_rlc starts at the byte", bytes_rlc.clone(), byte);
+                cb.require_equal(
+                    "sig_v_base is 2 * chain_id + 35 for legacy txs and 0 otherwise",
+                    sig_v_base,
+                    is_legacy * (chain_id * 2.expr() + 35.expr()),
+                );
+            });
+            cb.condition(is_last.clone(), |cb| {
+                cb.require_equal("the tx ends at the end of an item", is_item_last, 1.expr());
+                cb.require_equal(
+                    "the tx ends at the end of the list",
+                    bytes_len.clone(),
+                    list_end,
+                );
+                cb.require_zero(
+                    "the next tx has the next tx_id",
+                    not::expr(is_padding_next.clone()) * (tx_id_next - tx_id - 1.expr()),
+                );
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                cb.require_zero("the tx continues in the next row", is_padding_next);
+                for (value, value_next) in tx_values {
+                    cb.require_equal(
+                        "the values of the tx are the same ones in all its rows",
+                        value_next,
+                        value,
+                    );
+                }
+                cb.require_equal(
+                    "bytes_len increases by 1",
+                    bytes_len_next,
+                    bytes_len + 1.expr(),
+                );
+                cb.require_equal(
+                    "bytes_rlc accumulates the bytes",
+                    bytes_rlc_next,
+                    bytes_rlc * challenges.keccak_input() + byte_next,
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("tx hash item", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let tag_next = tag.value(Rotation::next())(meta);
+            let tag = tag.value(Rotation::cur())(meta);
+            let [is_item_last, length, bytes_len, list_end] =
+                [is_item_last, length, bytes_len, list_end]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_list_header, is_to] = [flags.is_list_header, flags.is_to]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+
+            cb.condition(not::expr(is_item_last.clone()), |cb| {
+                cb.require_equal("the tag is the same one in the whole item", tag_next, tag);
+            });
+            cb.condition(is_list_header * is_item_last, |cb| {
+                cb.require_equal(
+                    "list_end is the end of the list",
+                    list_end,
+                    bytes_len + length.clone(),
+                );
+            });
+            cb.require_zero(
+                "the callee address is empty or has 20 bytes",
+                is_to.clone() * length.clone() * (length.clone() - 20.expr()),
+            );
+            cb.require_zero(
+                "blob txs are not contract creations",
+                is_blob(meta) * is_to * (length - 20.expr()),
+            );
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("tx hash access list", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [is_access_list, is_header, is_item, is_address, is_keys, is_key] = [
+                flags.is_access_list,
+                flags.is_access_list_header,
+                flags.is_access_list_item,
+                flags.is_access_list_address,
+                flags.is_storage_keys,
+                flags.is_storage_key,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_next, is_address_next, is_key_next] = [
+                flags.is_access_list_item,
+                flags.is_access_list_address,
+                flags.is_storage_key,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::next()));
+            let [item_end_next, access_list_address_next, value_acc_next, is_item_last_next] =
+                [item_end, access_list_address, value_acc, is_item_last]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let access_list_rlc_prev = meta.query_advice(access_list_rlc, Rotation::prev());
+            let [addresses_len_next, addresses_rlc_next] = [addresses_len, addresses_rlc]
+                .map(|column| meta.query_advice(column, Rotation::next()));
+            let [storage_keys_len_next, storage_keys_rlc_next] =
+                [storage_keys_len, storage_keys_rlc]
+                    .map(|column| meta.query_advice(column, Rotation::next()));
+            let [addresses_len, addresses_rlc, storage_keys_len, storage_keys_rlc] = [
+                addresses_len,
+                addresses_rlc,
+                storage_keys_len,
+                storage_keys_rlc,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [is_item_first, is_item_last, is_first, is_last] =
+                [is_item_first, is_item_last, is_first, is_last]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [byte, bytes_len, length, value_acc] = [byte, bytes_len, length, value_acc]
+                .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [access_list_end, item_end, access_list_rlc, access_list_address] = [
+                access_list_end,
+                item_end,
+                access_list_rlc,
+                access_list_address,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let at_access_list_end = at_access_list_end.expr();
+            let at_item_end = at_item_end.expr();
+
+            // The access list is a list of items, each one the list of an
+            // address and the list of its storage keys, whose ends are set by
+            // the lengths of their headers.
+            cb.condition(is_header.clone() * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "access_list_end is the end of the access list",
+                    access_list_end,
+                    bytes_len.clone() + length.clone(),
+                );
+                cb.require_equal(
+                    "the access list has items until its end",
+                    is_item_next.clone(),
+                    not::expr(at_access_list_end.clone()),
+                );
+            });
+            cb.condition(
+                (is_item.clone() + is_keys.clone()) * is_item_last.clone(),
+                |cb| {
+                    cb.require_equal(
+                        "item_end is the end of the item and of its storage keys",
+                        item_end.clone(),
+                        bytes_len + length.clone(),
+                    );
+                },
+            );
+            cb.condition(
+                not::expr(is_last.clone()) * not::expr(is_item_next.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "item_end is the same one until the next item",
+                        item_end_next,
+                        item_end,
+                    );
+                },
+            );
+            cb.condition((is_keys + is_key.clone()) * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "the storage keys of the item follow until its end",
+                    is_key_next.clone(),
+                    not::expr(at_item_end.clone()),
+                );
+                cb.require_equal(
+                    "the next item follows until the end of the access list",
+                    is_item_next,
+                    at_item_end * not::expr(at_access_list_end.clone()),
+                );
+            });
+            cb.require_zero(
+                "the address of an item has 20 bytes",
+                is_address.clone() * (length.clone() - 20.expr()),
+            );
+            cb.require_zero("a storage key has 32 bytes", is_key * (length - 32.expr()));
+
+            cb.condition(is_header.clone() * is_item_first.clone(), |cb| {
+                cb.require_equal(
+                    "access_list_rlc starts at the first byte of the access list",
+                    access_list_rlc.clone(),
+                    byte.clone(),
+                );
+            });
+            cb.condition(
+                is_access_list * not::expr(is_header * is_item_first),
+                |cb| {
+                    cb.require_equal(
+                        "access_list_rlc accumulates the bytes of the access list",
+                        access_list_rlc,
+                        access_list_rlc_prev * challenges.keccak_input() + byte,
+                    );
+                },
+            );
+
+            cb.condition(is_address * is_item_last, |cb| {
+                cb.require_equal(
+                    "access_list_address is the address of the item",
+                    access_list_address.clone(),
+                    value_acc,
+                );
+            });
+            cb.condition(
+                not::expr(is_last.clone()) * not::expr(is_address_next.clone()),
+                |cb| {
+                    cb.require_equal(
+                        "access_list_address is the same one until the next address",
+                        access_list_address_next.clone(),
+                        access_list_address,
+                    );
+                },
+            );
+
+            cb.condition(is_first, |cb| {
+                for value in [
+                    addresses_len.clone(),
+                    addresses_rlc.clone(),
+                    storage_keys_len.clone(),
+                    storage_keys_rlc.clone(),
+                ] {
+                    cb.require_zero(
+                        "the addresses and storage keys are accumulated from 0 in the tx",
+                        value,
+                    );
+                }
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                let is_address_added = is_address_next * is_item_last_next.clone();
+                let is_key_added = is_key_next * is_item_last_next;
+                cb.require_equal(
+                    "addresses_len counts the addresses",
+                    addresses_len_next,
+                    addresses_len + is_address_added.clone(),
+                );
+                cb.require_equal(
+                    "addresses_rlc accumulates the addresses",
+                    addresses_rlc_next,
+                    select::expr(
+                        is_address_added,
+                        addresses_rlc.clone() * challenges.keccak_input() + value_acc_next.clone(),
+                        addresses_rlc,
+                    ),
+                );
+                cb.require_equal(
+                    "storage_keys_len counts the storage keys",
+                    storage_keys_len_next,
+                    storage_keys_len + is_key_added.clone(),
+                );
+                cb.require_equal(
+                    "storage_keys_rlc accumulates the storage keys after their addresses",
+                    storage_keys_rlc_next,
+                    select::expr(
+                        is_key_added,
+                        (storage_keys_rlc.clone() * challenges.keccak_input()
+                            + access_list_address_next)
+                            * challenges.keccak_input()
+                            + value_acc_next,
+                        storage_keys_rlc,
+                    ),
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.create_gate("tx hash blob versioned hashes", |meta| {
+            let mut cb = BaseConstraintBuilder::new(MAX_DEGREE);
+
+            let [is_blob_hashes, is_header, is_hash] = [
+                flags.is_blob_hashes,
+                flags.is_blob_hashes_header,
+                flags.is_blob_hash,
+            ]
+            .map(|column| meta.query_advice(column, Rotation::cur()));
+            let is_hash_next = meta.query_advice(flags.is_blob_hash, Rotation::next());
+            let is_item_last_next = meta.query_advice(is_item_last, Rotation::next());
+            let blob_hashes_rlc_prev = meta.query_advice(blob_hashes_rlc, Rotation::prev());
+            let blob_hashes_len_next = meta.query_advice(blob_hashes_len, Rotation::next());
+            let [is_item_first, is_item_last, is_first, is_last] =
+                [is_item_first, is_item_last, is_first, is_last]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let [byte, bytes_len, length] =
+                [byte, bytes_len, length].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [blob_hashes_end, blob_hashes_rlc, blob_hashes_len] =
+                [blob_hashes_end, blob_hashes_rlc, blob_hashes_len]
+                    .map(|column| meta.query_advice(column, Rotation::cur()));
+            let at_blob_hashes_end = at_blob_hashes_end.expr();
+
+            // The list of the blob versioned hashes has hashes of 32 bytes
+            // until the end set by the length of its header.
+            cb.condition(is_header.clone() * is_item_last.clone(), |cb| {
+                cb.require_equal(
+                    "blob_hashes_end is the end of the list of the blob versioned hashes",
+                    blob_hashes_end,
+                    bytes_len + length.clone(),
+                );
+            });
+            cb.condition((is_header.clone() + is_hash.clone()) * is_item_last, |cb| {
+                cb.require_equal(
+                    "the list has blob versioned hashes until its end",
+                    is_hash_next.clone(),
+                    not::expr(at_blob_hashes_end),
+                );
+            });
+            cb.require_zero(
+                "a blob versioned hash has 32 bytes",
+                is_hash * (length - 32.expr()),
+            );
+
+            cb.condition(is_header.clone() * is_item_first.clone(), |cb| {
+                cb.require_equal(
+                    "blob_hashes_rlc starts at the first byte of the list",
+                    blob_hashes_rlc.clone(),
+                    byte.clone(),
+                );
+            });
+            cb.condition(
+                is_blob_hashes * not::expr(is_header * is_item_first),
+                |cb| {
+                    cb.require_equal(
+                        "blob_hashes_rlc accumulates the bytes of the list",
+                        blob_hashes_rlc,
+                        blob_hashes_rlc_prev * challenges.keccak_input() + byte,
+                    );
+                },
+            );
+
+            cb.condition(is_first, |cb| {
+                cb.require_zero(
+                    "the blob versioned hashes are counted from 0 in the tx",
+                    blob_hashes_len.clone(),
+                );
+            });
+            cb.condition(not::expr(is_last), |cb| {
+                cb.require_equal(
+                    "blob_hashes_len counts the blob versioned hashes",
+                    blob_hashes_len_next,
+                    blob_hashes_len + is_hash_next * is_item_last_next,
+                );
+            });
+
+            cb.gate(
+                meta.query_fixed(q_enable, Rotation::cur())
+                    * not::expr(meta.query_advice(is_padding, Rotation::cur())),
+            )
+        });
+
+        meta.lookup("tx hash byte range", |meta| {
+            let q_enable = meta.query_fixed(q_enable, Rotation::cur());
+            vec![(
+                q_enable * meta.query_advice(byte, Rotation::cur()),
+                u8_table,
+            )]
+        });
+        meta.lookup("tx hash numeric leading byte", |meta| {
+            // Scalars are encoded without leading zeros.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_numeric, Rotation::cur())
+                * is_content_first(meta);
+            let byte = meta.query_advice(byte, Rotation::cur());
+            vec![(enable * (byte - 1.expr()), u8_table)]
+        });
+
+        meta.lookup("tx hash item transition", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * not::expr(meta.query_advice(is_last, Rotation::cur()));
+            [
+                meta.query_advice(tx_type, Rotation::cur()),
+                tag.value(Rotation::cur())(meta),
+                tag.value(Rotation::next())(meta),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+        meta.lookup("tx hash first item", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            [
+                meta.query_advice(tx_type, Rotation::cur()),
+                RlpTxTag::Padding.expr(),
+                tag.value(Rotation::cur())(meta),
+            ]
+            .into_iter()
+            .zip(transition_table)
+            .map(|(value, column)| (enable.clone() * value, column))
+            .collect()
+        });
+
+        Self::lookup_tx_table(meta, "tx hash tx type", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            let value = meta.query_advice(tx_type, Rotation::cur());
+            (enable, TxFieldTag::TxType.expr(), 0.expr(), value)
+        });
+        Self::lookup_tx_table(meta, "tx hash chain id", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_first, Rotation::cur());
+            let value = meta.query_advice(chain_id, Rotation::cur());
+            (enable, TxFieldTag::ChainId.expr(), 0.expr(), value)
+        });
+        Self::lookup_tx_table(
+            meta,
+            "tx hash legacy access list",
+            tx_table,
+            tx_id,
+            |meta| {
+                // Legacy txs have an empty access list, whose encoding is 0xc0.
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_first, Rotation::cur())
+                    * is_legacy(meta);
+                (
+                    enable,
+                    TxFieldTag::AccessListRLC.expr(),
+                    0.expr(),
+                    0xc0.expr(),
+                )
+            },
+        );
+        Self::lookup_tx_table(meta, "tx hash access list rlc", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_access_list, Rotation::cur())
+                * at_access_list_end.expr();
+            let value = meta.query_advice(access_list_rlc, Rotation::cur());
+            (enable, TxFieldTag::AccessListRLC.expr(), 0.expr(), value)
+        });
+        for (name, field_tag, column) in [
+            (
+                "tx hash access list addresses len",
+                TxFieldTag::AccessListAddressesLen,
+                addresses_len,
+            ),
+            (
+                "tx hash access list addresses rlc",
+                TxFieldTag::AccessListAddressesRLC,
+                addresses_rlc,
+            ),
+            (
+                "tx hash access list storage keys len",
+                TxFieldTag::AccessListStorageKeysLen,
+                storage_keys_len,
+            ),
+            (
+                "tx hash access list storage keys rlc",
+                TxFieldTag::AccessListStorageKeysRLC,
+                storage_keys_rlc,
+            ),
+            (
+                "tx hash blob versioned hashes len",
+                TxFieldTag::BlobVersionedHashesLen,
+                blob_hashes_len,
+            ),
+        ] {
+            Self::lookup_tx_table(meta, name, tx_table, tx_id, |meta| {
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_last, Rotation::cur());
+                let value = meta.query_advice(column, Rotation::cur());
+                (enable, field_tag.expr(), 0.expr(), value)
+            });
+        }
+        Self::lookup_tx_table(
+            meta,
+            "tx hash blob versioned hash",
+            tx_table,
+            tx_id,
+            |meta| {
+                // The index of the hash is the number of the previous ones.
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(flags.is_blob_hash, Rotation::cur())
+                    * meta.query_advice(is_item_last, Rotation::cur());
+                let index = meta.query_advice(blob_hashes_len, Rotation::cur()) - 1.expr();
+                let value = meta.query_advice(value_acc, Rotation::cur());
+                (enable, TxFieldTag::BlobVersionedHash.expr(), index, value)
+            },
+        );
+        Self::lookup_tx_table(
+            meta,
+            "tx hash blob versioned hashes rlc",
+            tx_table,
+            tx_id,
+            |meta| {
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(flags.is_blob_hashes, Rotation::cur())
+                    * at_blob_hashes_end.expr();
+                let value = meta.query_advice(blob_hashes_rlc, Rotation::cur());
+                (
+                    enable,
+                    TxFieldTag::BlobVersionedHashesRLC.expr(),
+                    0.expr(),
+                    value,
+                )
+            },
+        );
+        // Txs without blobs have no blob fee and an empty list of versioned
+        // hashes, whose encoding is 0xc0.
+        for (name, field_tag, value) in [
+            (
+                "tx hash non-blob max fee per blob gas",
+                TxFieldTag::MaxFeePerBlobGas,
+                0.expr(),
+            ),
+            (
+                "tx hash non-blob versioned hashes",
+                TxFieldTag::BlobVersionedHashesRLC,
+                0xc0.expr(),
+            ),
+        ] {
+            Self::lookup_tx_table(meta, name, tx_table, tx_id, |meta| {
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_first, Rotation::cur())
+                    * not::expr(is_blob(meta));
+                (enable, field_tag.expr(), 0.expr(), value)
+            });
+        }
+        Self::lookup_tx_table(meta, "tx hash field", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur())
+                * meta.query_advice(flags.has_field, Rotation::cur());
+            let field_tag = meta.query_advice(flags.field_tag, Rotation::cur());
+            let value = meta.query_advice(value_acc, Rotation::cur());
+            (enable, field_tag, 0.expr(), value)
+        });
+        for (name, field_tag) in [
+            ("tx hash max fee per gas", TxFieldTag::MaxFeePerGas),
+            (
+                "tx hash max priority fee per gas",
+                TxFieldTag::MaxPriorityFeePerGas,
+            ),
+        ] {
+            // The fee caps of legacy and EIP-2930 txs are the gas price.
+            Self::lookup_tx_table(meta, name, tx_table, tx_id, |meta| {
+                let enable = meta.query_fixed(q_enable, Rotation::cur())
+                    * meta.query_advice(is_item_last, Rotation::cur())
+                    * meta.query_advice(flags.is_gas_price, Rotation::cur());
+                let value = meta.query_advice(value_acc, Rotation::cur());
+                (enable, field_tag.expr(), 0.expr(), value)
+            });
+        }
+        Self::lookup_tx_table(meta, "tx hash call data", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_data, Rotation::cur())
+                * not::expr(meta.query_advice(is_header, Rotation::cur()));
+            let index = meta.query_advice(index, Rotation::cur());
+            let value = meta.query_advice(byte, Rotation::cur());
+            (enable, TxFieldTag::CallData.expr(), index, value)
+        });
+        Self::lookup_tx_table(meta, "tx hash call data length", tx_table, tx_id, |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_data, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur());
+            let value = meta.query_advice(length, Rotation::cur());
+            (enable, TxFieldTag::CallDataLength.expr(), 0.expr(), value)
+        });
+        Self::lookup_tx_table(meta, "tx hash is create", tx_table, tx_id, |meta| {
+            // The callee address is empty for contract creations.
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(flags.is_to, Rotation::cur())
+                * meta.query_advice(is_item_last, Rotation::cur());
+            let value = (20.expr() - meta.query_advice(length, Rotation::cur()))
+                * Expression::Constant(F::from(20).invert().unwrap());
+            (enable, TxFieldTag::IsCreate.expr(), 0.expr(), value)
+        });
+
+        meta.lookup_any("tx hash keccak", |meta| {
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            let input = [
+                1.expr(),
+                meta.query_advice(bytes_rlc, Rotation::cur()),
+                meta.query_advice(bytes_len, Rotation::cur()),
+                meta.query_advice(hash, Rotation::cur()),
+            ];
+            input
+                .into_iter()
+                .zip(keccak_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+        meta.lookup_any("tx hash of the tx table", |meta| {
+            let q_tx_hash = meta.query_selector(q_tx_hash);
+            let enable = meta.query_fixed(q_enable, Rotation::cur())
+                * meta.query_advice(is_last, Rotation::cur());
+            vec![
+                (
+                    q_tx_hash.clone() * meta.query_advice(tx_table.tx_id, Rotation::cur()),
+                    enable.clone() * meta.query_advice(tx_id, Rotation::cur()),
+                ),
+                (
+                    q_tx_hash.clone() * meta.query_advice(tx_table.value, Rotation::cur()),
+                    enable.clone() * meta.query_advice(hash, Rotation::cur()),
+                ),
+                (q_tx_hash, enable),
+            ]
+        });
+
+        Self {
+            q_enable,
+            q_first,
+            tx_id,
+            tx_type,
+            chain_id,
+            sig_v_base,
+            v_parity,
+            tag,
+            flags,
+            byte,
+            item,
+            bytes_rlc,
+            bytes_len,
+            list_end,
+            access_list_end,
+            item_end,
+            at_access_list_end,
+            at_item_end,
+            access_list_rlc,
+            access_list_address,
+            addresses_len,
+            addresses_rlc,
+            storage_keys_len,
+            storage_keys_rlc,
+            blob_hashes_end,
+            at_blob_hashes_end,
+            blob_hashes_rlc,
+            blob_hashes_len,
+            is_first,
+            is_last,
+            is_padding,
+            hash,
+            u8_table,
+            transition_table,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Look up (tx_id, field_tag, index, value) in the `TxTable` when enabled,
+    /// with the tx_id of the encoding.
+    fn lookup_tx_table(
+        meta: &mut ConstraintSystem<F>,
+        name: &'static str,
+        tx_table: &TxTable,
+        tx_id: Column<Advice>,
+        input: impl FnOnce(
+            &mut VirtualCells<'_, F>,
+        ) -> (Expression<F>, Expression<F>, Expression<F>, Expression<F>),
+    ) {
+        meta.lookup_any(name, |meta| {
+            let tx_id = meta.query_advice(tx_id, Rotation::cur());
+            let (enable, field_tag, index, value) = input(meta);
+            [tx_id, field_tag, index, value]
+                .into_iter()
+                .zip(tx_table.table_exprs(meta))
+                .map(|(input, table)| (enable.clone() * input, table))
+                .collect()
+        });
+    }
+
+    /// Load the u8 table and the table of the transitions between the items.
+    pub(crate) fn load_aux_tables(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "tx hash u8 table",
+            |mut table| {
+                for value in 0..256 {
+                    table.assign_cell(
+                        || format!("u8 table row {}", value),
+                        self.u8_table,
+                        value,
+                        || Value::known(F::from(value as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        layouter.assign_table(
+            || "tx hash transition table",
+            |mut table| {
+                let transitions = iter::once((0, RlpTxTag::Padding, RlpTxTag::Padding)).chain(
+                    (0..4).flat_map(|tx_type| {
+                        let sequence = RlpTxTag::sequence(tx_type);
+                        iter::once((tx_type, RlpTxTag::Padding, sequence[0])).chain(
+                            RlpTxTag::transitions(tx_type)
+                                .into_iter()
+                                .map(move |(tag, tag_next)| (tx_type, tag, tag_next)),
+                        )
+                    }),
+                );
+                for (offset, (tx_type, tag, tag_next)) in transitions.enumerate() {
+                    for (&column, value) in
+                        self.transition_table
+                            .iter()
+                            .zip([tx_type, tag as u64, tag_next as u64])
+                    {
+                        table.assign_cell(
+                            || format!("transition table row {}", offset),
+                            column,
+                            offset,
+                            || Value::known(F::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Return the number of rows for the encodings of the transactions.  The
+    /// access lists take the rows left unused by the bound of the other
+    /// fields.
+    pub(crate) fn num_rows(max_txs: usize, max_calldata: usize) -> usize {
+        max_txs * MAX_TX_RLP_STATIC_LEN + max_calldata
+    }
+
+    /// Return the items of the encoding of the signed transaction.
+    fn tx_items(tx: &Transaction, chain_id: u64) -> Vec<(RlpTxTag, Vec<u8>)> {
+        let mut items: Vec<_> = RlpTxTag::sequence(tx.transaction_type)
+            .iter()
+            .flat_map(|&tag| {
+                if tag == RlpTxTag::AccessList {
+                    return Self::access_list_items(tx);
+                }
+                if tag == RlpTxTag::BlobVersionedHashes {
+                    return Self::blob_hashes_items(tx);
+                }
+                let bytes = match tag {
+                    RlpTxTag::TxType => vec![tx.transaction_type as u8],
+                    // Set below from the length of the list
+                    RlpTxTag::ListHeader => vec![],
+                    RlpTxTag::ChainId => rlp::encode(&chain_id).to_vec(),
+                    RlpTxTag::Nonce => rlp::encode(&tx.nonce).to_vec(),
+                    RlpTxTag::GasPrice => rlp::encode(&tx.gas_price).to_vec(),
+                    RlpTxTag::MaxPriorityFeePerGas => rlp::encode(&tx.gas_tip_cap).to_vec(),
+                    RlpTxTag::MaxFeePerGas => rlp::encode(&tx.gas_fee_cap).to_vec(),
+                    RlpTxTag::Gas => rlp::encode(&tx.gas_limit).to_vec(),
+                    RlpTxTag::To => tx.to.map_or(vec![0x80], |to| rlp::encode(&to).to_vec()),
+                    RlpTxTag::Value => rlp::encode(&tx.value).to_vec(),
+                    RlpTxTag::Data => rlp::encode(&tx.call_data.to_vec()).to_vec(),
+                    RlpTxTag::MaxFeePerBlobGas => rlp::encode(&tx.max_fee_per_blob_gas).to_vec(),
+                    RlpTxTag::SigV => rlp::encode(&tx.v).to_vec(),
+                    RlpTxTag::SigR => rlp::encode(&tx.r).to_vec(),
+                    RlpTxTag::SigS => rlp::encode(&tx.s).to_vec(),
+                    RlpTxTag::Padding => unreachable!("padding is not part of a tx"),
+                    _ => unreachable!("{:?} is part of a nested list", tag),
+                };
+                vec![(tag, bytes)]
+            })
+            .collect();
+
+        let list_len: usize = items
+            .iter()
+            .filter(|(tag, _)| !matches!(tag, RlpTxTag::TxType | RlpTxTag::ListHeader))
+            .map(|(_, bytes)| bytes.len())
+            .sum();
+        for (tag, bytes) in items.iter_mut() {
+            if *tag == RlpTxTag::ListHeader {
+                *bytes = rlp_list_header(list_len);
+            }
+        }
+        items
+    }
+
+    /// Return the items of the access list of the transaction: its header,
+    /// and then the header, the address, and the header of the storage keys
+    /// followed by the storage keys of every item.
+    fn access_list_items(tx: &Transaction) -> Vec<(RlpTxTag, Vec<u8>)> {
+        let mut items = Vec::new();
+        for item in tx.access_list.iter().flat_map(|access_list| &access_list.0) {
+            let address = rlp::encode(&item.address).to_vec();
+            let storage_keys: Vec<Vec<u8>> = item
+                .storage_keys
+                .iter()
+                .map(|key| rlp::encode(key).to_vec())
+                .collect();
+            let storage_keys_len: usize = storage_keys.iter().map(Vec::len).sum();
+            let storage_keys_header = rlp_list_header(storage_keys_len);
+            let item_len = address.len() + storage_keys_header.len() + storage_keys_len;
+            items.push((RlpTxTag::AccessListItem, rlp_list_header(item_len)));
+            items.push((RlpTxTag::AccessListAddress, address));
+            items.push((RlpTxTag::AccessListStorageKeys, storage_keys_header));
+            items.extend(
+                storage_keys
+                    .into_iter()
+                    .map(|key| (RlpTxTag::AccessListStorageKey, key)),
+            );
+        }
+        let access_list_len = items.iter().map(|(_, bytes)| bytes.len()).sum();
+        iter::once((RlpTxTag::AccessList, rlp_list_header(access_list_len)))
+            .chain(items)
+            .collect()
+    }
+
+    /// Return the items of the list of the blob versioned hashes of the
+    /// transaction: its header followed by the hashes.
+    fn blob_hashes_items(tx: &Transaction) -> Vec<(RlpTxTag, Vec<u8>)> {
+        let hashes: Vec<Vec<u8>> = tx
+            .blob_versioned_hashes
+            .iter()
+            .map(|hash| rlp::encode(hash).to_vec())
+            .collect();
+        let blob_hashes_len = hashes.iter().map(Vec::len).sum();
+        iter::once((
+            RlpTxTag::BlobVersionedHashes,
+            rlp_list_header(blob_hashes_len),
+        ))
+        .chain(
+            hashes
+                .into_iter()
+                .map(|hash| (RlpTxTag::BlobVersionedHash, hash)),
+        )
+        .collect()
+    }
+
+    /// Return the rows of the encoding of the signed transaction.
+    fn tx_rows(
+        tx: &Transaction,
+        tx_id: usize,
+        chain_id: u64,
+        challenges: &Challenges<Value<F>>,
+    ) -> Vec<TxHashRow<F>> {
+        let items = Self::tx_items(tx, chain_id);
+        let list_end: usize = items.iter().map(|(_, bytes)| bytes.len()).sum();
+        let list_ends: Vec<_> = items
+            .iter()
+            .scan(0, |end, (tag, bytes)| {
+                *end += bytes.len();
+                Some((*tag, *end))
+            })
+            .collect();
+        let [access_list_end, blob_hashes_end] =
+            [RlpTxTag::is_access_list, RlpTxTag::is_blob_hashes].map(|is_list| {
+                list_ends
+                    .iter()
+                    .filter(|(tag, _)| is_list(tag))
+                    .last()
+                    .map_or(0, |(_, end)| *end)
+            });
+        let mut item_end = 0;
+        let sig_v_base = if tx.transaction_type == 0 {
+            2 * chain_id + 35
+        } else {
+            0
+        };
+        let hash = challenges.evm_word().map(|challenge| {
+            rlc::value(
+                &Word::from_big_endian(tx.hash(chain_id).as_bytes()).to_le_bytes(),
+                challenge,
+            )
+        });
+
+        let mut rows: Vec<TxHashRow<F>> = Vec::with_capacity(list_end);
+        let mut bytes_rlc = Value::known(F::ZERO);
+        let mut access_list_rlc = Value::known(F::ZERO);
+        let mut access_list_address = Value::known(F::ZERO);
+        let (mut addresses_len, mut storage_keys_len) = (0, 0);
+        let mut addresses_rlc = Value::known(F::ZERO);
+        let mut storage_keys_rlc = Value::known(F::ZERO);
+        let mut blob_hashes_rlc = Value::known(F::ZERO);
+        let mut blob_hashes_len = 0;
+        for (tag, bytes) in items {
+            let multiplier = if tag.is_word() {
+                challenges.evm_word()
+            } else {
+                Value::known(F::from(256))
+            };
+            let item_rows = RlpItemRow::item_rows(
+                &bytes,
+                tag.is_list().then(|| rlp_list_len(&bytes)),
+                tag.is_raw(),
+                multiplier,
+            );
+            if tag == RlpTxTag::AccessListItem {
+                item_end = rows.len() + bytes.len() + item_rows[0].length;
+            }
+
+            for (idx, (&byte, item)) in bytes.iter().zip(item_rows).enumerate() {
+                let byte_value = Value::known(F::from(byte as u64));
+                let value_acc = item.value_acc;
+                bytes_rlc = if rows.is_empty() {
+                    byte_value
+                } else {
+                    bytes_rlc * challenges.keccak_input() + byte_value
+                };
+                let is_item_last = item.is_item_last;
+                if tag == RlpTxTag::AccessList && idx == 0 {
+                    access_list_rlc = byte_value;
+                } else if tag.is_access_list() {
+                    access_list_rlc = access_list_rlc * challenges.keccak_input() + byte_value;
+                }
+                if tag == RlpTxTag::AccessListAddress && is_item_last {
+                    access_list_address = value_acc;
+                    addresses_len += 1;
+                    addresses_rlc = addresses_rlc * challenges.keccak_input() + value_acc;
+                }
+                if tag == RlpTxTag::AccessListStorageKey && is_item_last {
+                    storage_keys_len += 1;
+                    storage_keys_rlc = (storage_keys_rlc * challenges.keccak_input()
+                        + access_list_address)
+                        * challenges.keccak_input()
+                        + value_acc;
+                }
+                if tag == RlpTxTag::BlobVersionedHashes && idx == 0 {
+                    blob_hashes_rlc = byte_value;
+                } else if tag.is_blob_hashes() {
+                    blob_hashes_rlc = blob_hashes_rlc * challenges.keccak_input() + byte_value;
+                }
+                if tag == RlpTxTag::BlobVersionedHash && is_item_last {
+                    blob_hashes_len += 1;
+                }
+                rows.push(TxHashRow {
+                    tx_id,
+                    tx_type: tx.transaction_type,
+                    chain_id,
+                    sig_v_base,
+                    v_parity: tx.v - sig_v_base,
+                    tag,
+                    byte,
+                    item,
+                    bytes_rlc,
+                    bytes_len: rows.len() + 1,
+                    list_end,
+                    access_list_end,
+                    item_end,
+                    access_list_rlc,
+                    access_list_address,
+                    addresses_len,
+                    addresses_rlc,
+                    storage_keys_len,
+                    storage_keys_rlc,
+                    blob_hashes_end,
+                    blob_hashes_rlc,
+                    blob_hashes_len,
+                    is_first: rows.is_empty(),
+                    is_last: false,
+                    is_padding: false,
+                    hash,
+                });
+            }
+        }
+        if let Some(row) = rows.last_mut() {
+            row.is_last = true;
+        }
+        debug_assert_eq!(
+            rows.iter().map(|row| row.byte).collect::<Vec<_>>(),
+            tx.rlp_signed(chain_id)
+        );
+        rows
+    }
+
+    /// Assign the encodings of the transactions, padded with
+    /// `Transaction::padding` up to `max_txs`, with the recovery ids of their
+    /// signatures copied from the `SignVerifyChip`.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn assign(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        txs: &[Transaction],
+        assigned_sig_verifs: &[AssignedSignatureVerify<F>],
+        max_txs: usize,
+        max_calldata: usize,
+        chain_id: u64,
+        challenges: &Challenges<Value<F>>,
+    ) -> Result<(), Error> {
+        let padding_tx = Transaction::padding(chain_id);
+        let rows: Vec<TxHashRow<F>> = txs
+            .iter()
+            .chain(iter::repeat(&padding_tx))
+            .take(max_txs)
+            .enumerate()
+            .flat_map(|(i, tx)| Self::tx_rows(tx, i + 1, chain_id, challenges))
+            .collect();
+        let num_rows = Self::num_rows(max_txs, max_calldata);
+        if rows.len() > num_rows {
+            error!(
+                "tx hash rows {} exceed the available rows {}",
+                rows.len(),
+                num_rows
+            );
+            return Err(Error::Synthesis);
+        }
+
+        layouter.assign_region(
+            || "tx hash",
+            |mut region| {
+                // The enabled rows are surrounded by padding rows, which are
+                // queried at Rotation::prev() and Rotation::next().
+                let padding = TxHashRow::padding();
+                for (offset, row) in iter::once(&padding)
+                    .chain(rows.iter())
+                    .chain(iter::repeat(&padding))
+                    .take(num_rows + 2)
+                    .enumerate()
+                {
+                    let q_enable = (1..=num_rows).contains(&offset);
+                    let v_parity =
+                        self.assign_row(&mut region, offset, row, q_enable, offset == 1)?;
+                    if row.is_first {
+                        region.constrain_equal(
+                            v_parity.cell(),
+                            assigned_sig_verifs[row.tx_id - 1].r_y_parity.cell(),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn assign_row(
+        &self,
+        region: &mut Region<'_, F>,
+        offset: usize,
+        row: &TxHashRow<F>,
+        q_enable: bool,
+        q_first: bool,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        for (name, column, value) in [
+            ("q_enable", self.q_enable, q_enable),
+            ("q_first", self.q_first, q_first),
+        ] {
+            region.assign_fixed(
+                || format!("tx hash {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value as u64)),
+            )?;
+        }
+        BinaryNumberChip::construct(self.tag).assign(region, offset, &row.tag)?;
+        RlpItemChip::construct(self.item).assign(region, offset, &row.item)?;
+        for (name, column, value) in self.flags.columns() {
+            region.assign_advice(
+                || format!("tx hash {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value(row.tag))),
+            )?;
+        }
+        for (name, column, value) in [
+            ("tx_id", self.tx_id, row.tx_id as u64),
+            ("tx_type", self.tx_type, row.tx_type),
+            ("chain_id", self.chain_id, row.chain_id),
+            ("sig_v_base", self.sig_v_base, row.sig_v_base),
+            ("byte", self.byte, row.byte as u64),
+            ("bytes_len", self.bytes_len, row.bytes_len as u64),
+            ("list_end", self.list_end, row.list_end as u64),
+            (
+                "access_list_end",
+                self.access_list_end,
+                row.access_list_end as u64,
+            ),
+            ("item_end", self.item_end, row.item_end as u64),
+            (
+                "blob_hashes_end",
+                self.blob_hashes_end,
+                row.blob_hashes_end as u64,
+            ),
+            ("blob_hashes_len", self.blob_hashes_len, row.blob_hashes_len),
+            ("addresses_len", self.addresses_len, row.addresses_len),
+            (
+                "storage_keys_len",
+                self.storage_keys_len,
+                row.storage_keys_len,
+            ),
+            ("is_first", self.is_first, row.is_first as u64),
+            ("is_last", self.is_last, row.is_last as u64),
+            ("is_padding", self.is_padding, row.is_padding as u64),
+        ] {
+            region.assign_advice(
+                || format!("tx hash {}", name),
+                column,
+                offset,
+                || Value::known(F::from(value)),
+            )?;
+        }
+        for (name, column, value) in [
+            ("bytes_rlc", self.bytes_rlc, row.bytes_rlc),
+            ("access_list_rlc", self.access_list_rlc, row.access_list_rlc),
+            (
+                "access_list_address",
+                self.access_list_address,
+                row.access_list_address,
+            ),
+            ("addresses_rlc", self.addresses_rlc, row.addresses_rlc),
+            (
+                "storage_keys_rlc",
+                self.storage_keys_rlc,
+                row.storage_keys_rlc,
+            ),
+            ("blob_hashes_rlc", self.blob_hashes_rlc, row.blob_hashes_rlc),
+            ("hash", self.hash, row.hash),
+        ] {
+            region.assign_advice(|| format!("tx hash {}", name), column, offset, || value)?;
+        }
+        for (chip, end) in [
+            (&self.at_access_list_end, row.access_list_end),
+            (&self.at_item_end, row.item_end),
+            (&self.at_blob_hashes_end, row.blob_hashes_end),
+        ] {
+            IsZeroChip::construct(chip.clone()).assign(
+                region,
+                offset,
+                Value::known(F::from(end as u64) - F::from(row.bytes_len as u64)),
+            )?;
+        }
+        region.assign_advice(
+            || "tx hash v_parity",
+            self.v_parity,
+            offset,
+            || Value::known(F::from(row.v_parity)),
+        )
+    }
+}
+
+/// Return the RLP header of a list whose payload has the given length.
+pub(crate) fn rlp_list_header(len: usize) -> Vec<u8> {
+    if len <= 55 {
+        vec![0xc0 + len as u8]
+    } else {
+        let len_be: Vec<u8> = len
+            .to_be_bytes()
+            .iter()
+            .copied()
+            .skip_while(|&byte| byte == 0)
+            .collect();
+        iter::once(0xf7 + len_be.len() as u8)
+            .chain(len_be)
+            .collect()
+    }
+}
+
+/// Return the length of the payload of a list from its RLP header.
+pub(crate) fn rlp_list_len(header: &[u8]) -> usize {
+    if header[0] <= 0xf7 {
+        (header[0] - 0xc0) as usize
+    } else {
+        header[1..]
+            .iter()
+            .fold(0, |len, &byte| len * 256 + byte as usize)
+    }
+}
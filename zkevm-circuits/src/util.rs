@@ -208,7 +208,7 @@ pub(crate) fn is_push(byte: u8) -> bool {
 
 pub(crate) fn get_push_size(byte: u8) -> u64 {
     if is_push(byte) {
-        byte as u64 - OpcodeId::PUSH1.as_u64() + 1
+        byte as u64 - OpcodeId::PUSH0.as_u64()
     } else {
         0u64
     }
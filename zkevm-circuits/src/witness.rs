@@ -7,10 +7,11 @@ pub use block::{block_convert, Block, BlockContext};
 mod bytecode;
 pub use bytecode::Bytecode;
 mod mpt;
-pub use mpt::{MptUpdate, MptUpdateRow, MptUpdates};
+pub use mpt::{MptProof, MptUpdate, MptUpdateRow, MptUpdates};
 mod rw;
 pub use bus_mapping::circuit_input_builder::ExecStep;
 pub use rw::{Rw, RwMap, RwRow};
 mod tx;
 pub use bus_mapping::circuit_input_builder::Call;
 pub use tx::Transaction;
+pub(crate) use tx::{access_list_addresses_rlc, access_list_storage_keys_rlc};
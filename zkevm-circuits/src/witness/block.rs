@@ -8,12 +8,19 @@ use crate::{
 };
 use bus_mapping::{
     circuit_input_builder::{self, CircuitsParams, CopyEvent, ExpEvent},
+    precompile::{
+        EcAddAuxData, EcMulAuxData, EcPairingAuxData, EcrecoverAuxData, ModExpEvent,
+        PrecompileEvent,
+    },
     Error,
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word};
+use eth_types::{
+    geth_types::{BlockHeader, Receipt, Withdrawal},
+    Address, Field, ToLittleEndian, ToScalar, ToWord, Word,
+};
 use halo2_proofs::circuit::Value;
 
-use super::{tx::tx_convert, Bytecode, ExecStep, Rw, RwMap, Transaction};
+use super::{tx::tx_convert, Bytecode, ExecStep, MptUpdates, Rw, RwMap, Transaction};
 
 // TODO: Remove fields that are duplicated in`eth_block`
 /// Block is the struct used by all circuits, which contains all the needed
@@ -24,6 +31,17 @@ pub struct Block<F> {
     pub randomness: F,
     /// Transactions in the block
     pub txs: Vec<Transaction>,
+    /// Receipts of the transactions in the block
+    pub receipts: Vec<Receipt>,
+    /// Headers of the uncles, whose miners are rewarded at the end of the block
+    pub uncles: Vec<BlockHeader>,
+    /// Withdrawals (EIP-4895) applied at the end of the block
+    pub withdrawals: Vec<Withdrawal>,
+    /// Withdrawal steps that follow the last transaction, one for every
+    /// withdrawal of the block.
+    pub withdrawal_steps: Vec<ExecStep>,
+    /// BlockReward step that follows the withdrawals.
+    pub block_reward_step: ExecStep,
     /// EndBlock step that is repeated after the last transaction and before
     /// reaching the last EVM row.
     pub end_block_not_last: ExecStep,
@@ -45,8 +63,24 @@ pub struct Block<F> {
     pub circuits_params: CircuitsParams,
     /// Inputs to the SHA3 opcode
     pub sha3_inputs: Vec<Vec<u8>>,
+    /// Calls to precompiled contracts for the precompile table.
+    pub precompile_events: Vec<PrecompileEvent>,
+    /// Calls to the ecRecover precompile for the ecRecover circuit.
+    pub ecrecover_events: Vec<EcrecoverAuxData>,
+    /// Inputs to the SHA-256 precompile for the SHA-256 circuit.
+    pub sha256_inputs: Vec<Vec<u8>>,
+    /// Calls to the MODEXP precompile for the ModExp circuit.
+    pub modexp_events: Vec<ModExpEvent>,
+    /// Calls to the ecAdd precompile for the ECC circuit.
+    pub ec_add_events: Vec<EcAddAuxData>,
+    /// Calls to the ecMul precompile for the ECC circuit.
+    pub ec_mul_events: Vec<EcMulAuxData>,
+    /// Calls to the ecPairing precompile for the ECC circuit.
+    pub ec_pairing_events: Vec<EcPairingAuxData>,
     /// State root of the previous block
     pub prev_state_root: Word, // TODO: Make this H256
+    /// Updates of the state trie and of the storage tries for the MPT circuit
+    pub mpt_updates: MptUpdates,
     /// Keccak inputs
     pub keccak_inputs: Vec<Vec<u8>>,
     /// Original Block from geth
@@ -82,7 +116,13 @@ impl<F: Field> Block<F> {
         let num_rows_required_for_rw_table: usize = self.circuits_params.max_rws;
         let num_rows_required_for_fixed_table: usize = detect_fixed_table_tags(self)
             .iter()
-            .map(|tag| tag.build::<F>().count())
+            .map(|tag| {
+                tag.build::<F>(
+                    self.circuits_params.hardfork,
+                    self.circuits_params.block_reward,
+                )
+                .count()
+            })
             .sum();
         let num_rows_required_for_bytecode_table: usize = self
             .bytecodes
@@ -90,7 +130,7 @@ impl<F: Field> Block<F> {
             .map(|bytecode| bytecode.bytes.len() + 1)
             .sum();
         let num_rows_required_for_copy_table: usize =
-            self.copy_events.iter().map(|c| c.bytes.len() * 2).sum();
+            self.copy_events.iter().map(|c| c.copy_length() * 2).sum();
         let num_rows_required_for_keccak_table: usize = self.keccak_inputs.len();
         let num_rows_required_for_tx_table: usize =
             self.txs.iter().map(|tx| 9 + tx.call_data.len()).sum();
@@ -142,12 +182,38 @@ pub struct BlockContext {
     pub timestamp: Word,
     /// The difficulty of the blcok
     pub difficulty: Word,
+    /// The prevRandao of the block (EIP-4399), which is its mix hash
+    pub prevrandao: Word,
     /// The base fee, the minimum amount of gas fee for a transaction
     pub base_fee: Word,
     /// The hash of previous blocks
     pub history_hashes: Vec<Word>,
     /// The chain id
     pub chain_id: Word,
+    /// The hash of the parent block
+    pub parent_hash: Word,
+    /// The hash of the uncles of the block
+    pub uncles_hash: Word,
+    /// The state root after the block
+    pub state_root: Word,
+    /// The root of the trie of the transactions
+    pub transactions_root: Word,
+    /// The root of the trie of the receipts
+    pub receipts_root: Word,
+    /// The bloom filter of the logs of the block
+    pub logs_bloom: Vec<u8>,
+    /// The gas used by the transactions of the block
+    pub gas_used: u64,
+    /// The extra data of the block
+    pub extra_data: Vec<u8>,
+    /// The mix hash of the block
+    pub mix_hash: Word,
+    /// The nonce of the block
+    pub nonce: u64,
+    /// The excess blob gas of the block (EIP-4844)
+    pub excess_blob_gas: u64,
+    /// The blob base fee, the price of the blob gas of the block (EIP-4844)
+    pub blob_base_fee: Word,
 }
 
 impl BlockContext {
@@ -194,6 +260,63 @@ impl BlockContext {
                         .map(|randomness| rlc::value(&self.chain_id.to_le_bytes(), randomness)),
                 ],
             ],
+            [
+                (BlockContextFieldTag::ParentHash, self.parent_hash),
+                (BlockContextFieldTag::UnclesHash, self.uncles_hash),
+                (BlockContextFieldTag::StateRoot, self.state_root),
+                (
+                    BlockContextFieldTag::TransactionsRoot,
+                    self.transactions_root,
+                ),
+                (BlockContextFieldTag::ReceiptsRoot, self.receipts_root),
+                (BlockContextFieldTag::MixHash, self.mix_hash),
+                (BlockContextFieldTag::PrevRandao, self.prevrandao),
+            ]
+            .iter()
+            .map(|(tag, hash)| {
+                [
+                    Value::known(F::from(*tag as u64)),
+                    Value::known(F::ZERO),
+                    randomness.map(|randomness| rlc::value(&hash.to_le_bytes(), randomness)),
+                ]
+            })
+            .collect(),
+            vec![
+                [
+                    Value::known(F::from(BlockContextFieldTag::LogsBloom as u64)),
+                    Value::known(F::ZERO),
+                    randomness
+                        .map(|randomness| rlc::value(self.logs_bloom.iter().rev(), randomness)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::GasUsed as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.gas_used)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::ExtraData as u64)),
+                    Value::known(F::from(self.extra_data.len() as u64)),
+                    randomness
+                        .map(|randomness| rlc::value(self.extra_data.iter().rev(), randomness)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::Nonce as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.nonce)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::ExcessBlobGas as u64)),
+                    Value::known(F::ZERO),
+                    Value::known(F::from(self.excess_blob_gas)),
+                ],
+                [
+                    Value::known(F::from(BlockContextFieldTag::BlobBaseFee as u64)),
+                    Value::known(F::ZERO),
+                    randomness.map(|randomness| {
+                        rlc::value(&self.blob_base_fee.to_le_bytes(), randomness)
+                    }),
+                ],
+            ],
             {
                 let len_history = self.history_hashes.len();
                 self.history_hashes
@@ -216,15 +339,29 @@ impl BlockContext {
 
 impl From<&circuit_input_builder::Block> for BlockContext {
     fn from(block: &circuit_input_builder::Block) -> Self {
+        let header = block.header();
         Self {
             coinbase: block.coinbase,
             gas_limit: block.gas_limit,
             number: block.number,
             timestamp: block.timestamp,
             difficulty: block.difficulty,
+            prevrandao: block.prevrandao,
             base_fee: block.base_fee,
             history_hashes: block.history_hashes.clone(),
             chain_id: block.chain_id,
+            parent_hash: header.parent_hash.to_word(),
+            uncles_hash: header.uncles_hash.to_word(),
+            state_root: header.state_root.to_word(),
+            transactions_root: header.transactions_root.to_word(),
+            receipts_root: header.receipts_root.to_word(),
+            logs_bloom: header.logs_bloom.as_bytes().to_vec(),
+            gas_used: header.gas_used,
+            extra_data: header.extra_data.to_vec(),
+            mix_hash: header.mix_hash.to_word(),
+            nonce: header.nonce.to_low_u64_be(),
+            excess_blob_gas: block.excess_blob_gas,
+            blob_base_fee: block.blob_base_fee,
         }
     }
 }
@@ -236,6 +373,8 @@ pub fn block_convert<F: Field>(
 ) -> Result<Block<F>, Error> {
     let rws = RwMap::from(&block.container);
     rws.check_value();
+    let mpt_updates =
+        MptUpdates::from_state(&rws.table_assignments(), &mut block.state_trie.clone())?;
     Ok(Block {
         // randomness: F::from(0x100), // Special value to reveal elements after RLC
         randomness: F::from(0xcafeu64),
@@ -245,8 +384,13 @@ pub fn block_convert<F: Field>(
             .txs()
             .iter()
             .enumerate()
-            .map(|(idx, tx)| tx_convert(tx, idx + 1))
+            .map(|(idx, tx)| tx_convert(tx, idx + 1, block.chain_id.as_u64()))
             .collect(),
+        receipts: block.receipts(),
+        uncles: block.uncles.clone(),
+        withdrawals: block.withdrawals.clone(),
+        withdrawal_steps: block.block_steps.withdrawals.clone(),
+        block_reward_step: block.block_steps.block_reward.clone(),
         end_block_not_last: block.block_steps.end_block_not_last.clone(),
         end_block_last: block.block_steps.end_block_last.clone(),
         bytecodes: code_db
@@ -260,9 +404,17 @@ pub fn block_convert<F: Field>(
         copy_events: block.copy_events.clone(),
         exp_events: block.exp_events.clone(),
         sha3_inputs: block.sha3_inputs.clone(),
+        precompile_events: block.precompile_events.clone(),
+        ecrecover_events: block.ecrecover_events(),
+        sha256_inputs: block.sha256_inputs(),
+        modexp_events: block.modexp_events(),
+        ec_add_events: block.ec_add_events(),
+        ec_mul_events: block.ec_mul_events(),
+        ec_pairing_events: block.ec_pairing_events(),
         circuits_params: block.circuits_params,
         exp_circuit_pad_to: <usize>::default(),
         prev_state_root: block.prev_state_root,
+        mpt_updates,
         keccak_inputs: circuit_input_builder::keccak_inputs(block, code_db)?,
         eth_block: block.eth_block.clone(),
     })
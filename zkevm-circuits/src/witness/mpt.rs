@@ -2,7 +2,10 @@ use crate::{
     evm_circuit::{util::rlc, witness::Rw},
     table::{AccountFieldTag, MPTProofType},
 };
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word};
+use eth_types::{
+    state_trie::{StateTrie, TrieUpdate},
+    Address, BigEndianHash, Field, ToLittleEndian, ToScalar, ToWord, Word, H256,
+};
 use halo2_proofs::circuit::Value;
 use itertools::Itertools;
 use std::collections::BTreeMap;
@@ -18,8 +21,8 @@ pub struct MptUpdate {
 }
 
 impl MptUpdate {
-    fn proof_type<F: Field>(&self) -> F {
-        let proof_type = match self.key {
+    pub(crate) fn proof_type(&self) -> MPTProofType {
+        match self.key {
             Key::AccountStorage { .. } => {
                 if self.old_value.is_zero() && self.new_value.is_zero() {
                     MPTProofType::NonExistingStorageProof
@@ -28,9 +31,33 @@ impl MptUpdate {
                 }
             }
             Key::Account { field_tag, .. } => field_tag.into(),
-        };
-        F::from(proof_type as u64)
+            Key::StorageClear { .. } => MPTProofType::StorageCleared,
+        }
+    }
+
+    /// Return the address of the account of the update.
+    pub(crate) fn address(&self) -> Address {
+        self.key.account_address()
     }
+
+    /// Return the storage slot of storage updates.
+    pub(crate) fn storage_slot(&self) -> Option<Word> {
+        match self.key {
+            Key::Account { .. } | Key::StorageClear { .. } => None,
+            Key::AccountStorage { storage_key, .. } => Some(storage_key),
+        }
+    }
+}
+
+/// The proof of an MPT update: the update of the storage trie of the account
+/// for storage updates, and the update of the state trie
+#[derive(Debug, Clone)]
+pub struct MptProof {
+    pub(crate) update: MptUpdate,
+    pub(crate) storage: Option<TrieUpdate>,
+    pub(crate) account: TrieUpdate,
+    /// Storage root of the account before the update
+    pub(crate) old_storage_root: H256,
 }
 
 /// All the MPT updates in the MptCircuit, accessible by their key
@@ -38,6 +65,9 @@ impl MptUpdate {
 pub struct MptUpdates {
     old_root: Word,
     updates: BTreeMap<Key, MptUpdate>,
+    /// The proofs of the updates, in the order of their roots, which are
+    /// missing in mock updates
+    pub(crate) proofs: Vec<MptProof>,
 }
 
 /// The field element encoding of an MPT update, which is used by the MptTable
@@ -81,7 +111,68 @@ impl MptUpdates {
         MptUpdates {
             updates: map,
             old_root: mock_old_root,
+            proofs: Vec::new(),
+        }
+    }
+
+    /// Return the updates of the rows and their proofs, setting the final
+    /// values of their keys in the state trie in the order of the rows.
+    pub fn from_state(rows: &[Rw], trie: &mut StateTrie) -> Result<Self, eth_types::Error> {
+        let old_root = trie.root().to_word();
+        let mut updates = BTreeMap::new();
+        let mut proofs = Vec::new();
+        for (key, rows) in &rows.iter().group_by(|row| key(row)) {
+            let key_exists = match key {
+                Some(key) => key,
+                None => continue,
+            };
+            let rows: Vec<_> = rows.collect();
+            let (old_value, new_value) = (value_prev(rows[0]), value(rows[rows.len() - 1]));
+            let old_root = trie.root().to_word();
+            let old_storage_root = trie.account(&key_exists.account_address())?.storage_root;
+            let (storage, account) = match key_exists {
+                Key::Account { address, field_tag } => {
+                    let mut account = trie.account(&address)?;
+                    match field_tag {
+                        AccountFieldTag::Nonce => account.nonce = new_value.low_u64(),
+                        AccountFieldTag::Balance => account.balance = new_value,
+                        AccountFieldTag::CodeHash => {
+                            account.code_hash = H256::from_uint(&new_value)
+                        }
+                        AccountFieldTag::NonExisting => {}
+                    }
+                    (None, trie.set_account(&address, account)?)
+                }
+                Key::AccountStorage {
+                    address,
+                    storage_key,
+                    ..
+                } => {
+                    let (storage, account) = trie.set_storage(&address, &storage_key, new_value)?;
+                    (Some(storage), account)
+                }
+                Key::StorageClear { address, .. } => (None, trie.clear_storage(&address)?),
+            };
+            let update = MptUpdate {
+                key: key_exists.set_non_exists(old_value, new_value),
+                old_value,
+                new_value,
+                old_root,
+                new_root: trie.root().to_word(),
+            };
+            updates.insert(key_exists, update);
+            proofs.push(MptProof {
+                update,
+                storage,
+                account,
+                old_storage_root,
+            });
         }
+        Ok(MptUpdates {
+            old_root,
+            updates,
+            proofs,
+        })
     }
 
     pub(crate) fn table_assignments<F: Field>(
@@ -90,28 +181,33 @@ impl MptUpdates {
     ) -> Vec<MptUpdateRow<Value<F>>> {
         self.updates
             .values()
-            .map(|update| {
-                let (new_root, old_root) = randomness
-                    .map(|randomness| update.root_assignments(randomness))
-                    .unzip();
-                let (new_value, old_value) = randomness
-                    .map(|randomness| update.value_assignments(randomness))
-                    .unzip();
-                MptUpdateRow([
-                    Value::known(update.key.address()),
-                    randomness.map(|randomness| update.key.storage_key(randomness)),
-                    Value::known(update.proof_type()),
-                    new_root,
-                    old_root,
-                    new_value,
-                    old_value,
-                ])
-            })
+            .map(|update| update.table_assignment(randomness))
             .collect()
     }
 }
 
 impl MptUpdate {
+    pub(crate) fn table_assignment<F: Field>(
+        &self,
+        randomness: Value<F>,
+    ) -> MptUpdateRow<Value<F>> {
+        let (new_root, old_root) = randomness
+            .map(|randomness| self.root_assignments(randomness))
+            .unzip();
+        let (new_value, old_value) = randomness
+            .map(|randomness| self.value_assignments(randomness))
+            .unzip();
+        MptUpdateRow([
+            Value::known(self.key.address()),
+            randomness.map(|randomness| self.key.storage_key(randomness)),
+            Value::known(F::from(self.proof_type() as u64)),
+            new_root,
+            old_root,
+            new_value,
+            old_value,
+        ])
+    }
+
     pub(crate) fn value_assignments<F: Field>(&self, word_randomness: F) -> (F, F) {
         let assign = |x: Word| match self.key {
             Key::Account {
@@ -144,6 +240,10 @@ enum Key {
         storage_key: Word,
         exists: bool,
     },
+    StorageClear {
+        tx_id: usize,
+        address: Address,
+    },
 }
 
 impl Key {
@@ -174,21 +274,25 @@ impl Key {
                     storage_key,
                     exists: false,
                 },
+                Key::StorageClear { .. } => self,
             }
         } else {
             self
         }
     }
-    fn address<F: Field>(&self) -> F {
+    fn account_address(&self) -> Address {
         match self {
-            Self::Account { address, .. } | Self::AccountStorage { address, .. } => {
-                address.to_scalar().unwrap()
-            }
+            Self::Account { address, .. }
+            | Self::AccountStorage { address, .. }
+            | Self::StorageClear { address, .. } => *address,
         }
     }
+    fn address<F: Field>(&self) -> F {
+        self.account_address().to_scalar().unwrap()
+    }
     fn storage_key<F: Field>(&self, randomness: F) -> F {
         match self {
-            Self::Account { .. } => F::ZERO,
+            Self::Account { .. } | Self::StorageClear { .. } => F::ZERO,
             Self::AccountStorage { storage_key, .. } => {
                 rlc::value(&storage_key.to_le_bytes(), randomness)
             }
@@ -225,6 +329,14 @@ fn key(row: &Rw) -> Option<Key> {
             storage_key: *storage_key,
             exists: true,
         }),
+        Rw::AccountStorageClear {
+            tx_id,
+            account_address,
+            ..
+        } => Some(Key::StorageClear {
+            tx_id: *tx_id,
+            address: *account_address,
+        }),
         _ => None,
     }
 }
@@ -233,6 +345,7 @@ fn value(row: &Rw) -> Word {
     match row {
         Rw::Account { value, .. } => *value,
         Rw::AccountStorage { value, .. } => *value,
+        Rw::AccountStorageClear { .. } => Word::one(),
         _ => unreachable!(),
     }
 }
@@ -241,6 +354,7 @@ fn value_prev(row: &Rw) -> Word {
     match row {
         Rw::Account { value_prev, .. } => *value_prev,
         Rw::AccountStorage { value_prev, .. } => *value_prev,
+        Rw::AccountStorageClear { .. } => Word::zero(),
         _ => unreachable!(),
     }
 }
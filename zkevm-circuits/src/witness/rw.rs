@@ -3,7 +3,10 @@ use std::collections::HashMap;
 
 use bus_mapping::{
     exec_trace::OperationRef,
-    operation::{self, AccountField, CallContextField, Target, TxLogField, TxReceiptField},
+    operation::{
+        self, AccountField, CallContextField, Target, TxAccountFlagField, TxLogField,
+        TxReceiptField,
+    },
 };
 use eth_types::{Address, Field, ToAddress, ToLittleEndian, ToScalar, Word, U256};
 use halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
@@ -11,7 +14,10 @@ use itertools::Itertools;
 
 use crate::{
     evm_circuit::util::rlc,
-    table::{AccountFieldTag, CallContextFieldTag, TxLogFieldTag, TxReceiptFieldTag},
+    table::{
+        AccountFieldTag, CallContextFieldTag, StorageFieldTag, TxAccountFlagFieldTag,
+        TxLogFieldTag, TxReceiptFieldTag,
+    },
     util::build_tx_log_address,
 };
 
@@ -180,6 +186,16 @@ pub enum Rw {
         is_warm: bool,
         is_warm_prev: bool,
     },
+    /// TxAccountFlag
+    TxAccountFlag {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        field_tag: TxAccountFlagFieldTag,
+        value: bool,
+        value_prev: bool,
+    },
     /// TxRefund
     TxRefund {
         rw_counter: usize,
@@ -208,6 +224,23 @@ pub enum Rw {
         tx_id: usize,
         committed_value: Word,
     },
+    /// Clear of the storage of an account deleted at the end of a tx, which
+    /// is a write of 1 over 0
+    AccountStorageClear {
+        rw_counter: usize,
+        tx_id: usize,
+        account_address: Address,
+    },
+    /// TransientStorage
+    TransientStorage {
+        rw_counter: usize,
+        is_write: bool,
+        tx_id: usize,
+        account_address: Address,
+        storage_key: Word,
+        value: Word,
+        value_prev: Word,
+    },
     /// CallContext
     CallContext {
         rw_counter: usize,
@@ -318,6 +351,15 @@ impl Rw {
         }
     }
 
+    pub(crate) fn tx_account_flag_value_pair(&self) -> (bool, bool) {
+        match self {
+            Self::TxAccountFlag {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn tx_refund_value_pair(&self) -> (u64, u64) {
         match self {
             Self::TxRefund {
@@ -360,6 +402,15 @@ impl Rw {
         }
     }
 
+    pub(crate) fn transient_storage_value_pair(&self) -> (Word, Word) {
+        match self {
+            Self::TransientStorage {
+                value, value_prev, ..
+            } => (*value, *value_prev),
+            _ => unreachable!(),
+        }
+    }
+
     pub(crate) fn call_context_value(&self) -> Word {
         match self {
             Self::CallContext { value, .. } => *value,
@@ -450,8 +501,11 @@ impl Rw {
             | Self::Memory { rw_counter, .. }
             | Self::Stack { rw_counter, .. }
             | Self::AccountStorage { rw_counter, .. }
+            | Self::AccountStorageClear { rw_counter, .. }
+            | Self::TransientStorage { rw_counter, .. }
             | Self::TxAccessListAccount { rw_counter, .. }
             | Self::TxAccessListAccountStorage { rw_counter, .. }
+            | Self::TxAccountFlag { rw_counter, .. }
             | Self::TxRefund { rw_counter, .. }
             | Self::Account { rw_counter, .. }
             | Self::CallContext { rw_counter, .. }
@@ -463,11 +517,14 @@ impl Rw {
     pub(crate) fn is_write(&self) -> bool {
         match self {
             Self::Start { .. } => false,
+            Self::AccountStorageClear { .. } => true,
             Self::Memory { is_write, .. }
             | Self::Stack { is_write, .. }
             | Self::AccountStorage { is_write, .. }
+            | Self::TransientStorage { is_write, .. }
             | Self::TxAccessListAccount { is_write, .. }
             | Self::TxAccessListAccountStorage { is_write, .. }
+            | Self::TxAccountFlag { is_write, .. }
             | Self::TxRefund { is_write, .. }
             | Self::Account { is_write, .. }
             | Self::CallContext { is_write, .. }
@@ -481,9 +538,11 @@ impl Rw {
             Self::Start { .. } => Target::Start,
             Self::Memory { .. } => Target::Memory,
             Self::Stack { .. } => Target::Stack,
-            Self::AccountStorage { .. } => Target::Storage,
+            Self::AccountStorage { .. } | Self::AccountStorageClear { .. } => Target::Storage,
+            Self::TransientStorage { .. } => Target::TransientStorage,
             Self::TxAccessListAccount { .. } => Target::TxAccessListAccount,
             Self::TxAccessListAccountStorage { .. } => Target::TxAccessListAccountStorage,
+            Self::TxAccountFlag { .. } => Target::TxAccountFlag,
             Self::TxRefund { .. } => Target::TxRefund,
             Self::Account { .. } => Target::Account,
             Self::CallContext { .. } => Target::CallContext,
@@ -495,8 +554,11 @@ impl Rw {
     pub(crate) fn id(&self) -> Option<usize> {
         match self {
             Self::AccountStorage { tx_id, .. }
+            | Self::AccountStorageClear { tx_id, .. }
+            | Self::TransientStorage { tx_id, .. }
             | Self::TxAccessListAccount { tx_id, .. }
             | Self::TxAccessListAccountStorage { tx_id, .. }
+            | Self::TxAccountFlag { tx_id, .. }
             | Self::TxRefund { tx_id, .. }
             | Self::TxLog { tx_id, .. }
             | Self::TxReceipt { tx_id, .. } => Some(*tx_id),
@@ -515,11 +577,20 @@ impl Rw {
             | Self::TxAccessListAccountStorage {
                 account_address, ..
             }
+            | Self::TxAccountFlag {
+                account_address, ..
+            }
             | Self::Account {
                 account_address, ..
             }
             | Self::AccountStorage {
                 account_address, ..
+            }
+            | Self::AccountStorageClear {
+                account_address, ..
+            }
+            | Self::TransientStorage {
+                account_address, ..
             } => Some(*account_address),
             Self::Memory { memory_address, .. } => Some(U256::from(*memory_address).to_address()),
             Self::Stack { stack_pointer, .. } => {
@@ -546,10 +617,13 @@ impl Rw {
             Self::Account { field_tag, .. } => Some(*field_tag as u64),
             Self::CallContext { field_tag, .. } => Some(*field_tag as u64),
             Self::TxReceipt { field_tag, .. } => Some(*field_tag as u64),
+            Self::TxAccountFlag { field_tag, .. } => Some(*field_tag as u64),
+            Self::AccountStorageClear { .. } => Some(StorageFieldTag::Clear as u64),
             Self::Start { .. }
             | Self::Memory { .. }
             | Self::Stack { .. }
             | Self::AccountStorage { .. }
+            | Self::TransientStorage { .. }
             | Self::TxAccessListAccount { .. }
             | Self::TxAccessListAccountStorage { .. }
             | Self::TxRefund { .. }
@@ -560,6 +634,7 @@ impl Rw {
     pub(crate) fn storage_key(&self) -> Option<Word> {
         match self {
             Self::AccountStorage { storage_key, .. }
+            | Self::TransientStorage { storage_key, .. }
             | Self::TxAccessListAccountStorage { storage_key, .. } => Some(*storage_key),
             Self::Start { .. }
             | Self::CallContext { .. }
@@ -568,14 +643,17 @@ impl Rw {
             | Self::TxRefund { .. }
             | Self::Account { .. }
             | Self::TxAccessListAccount { .. }
+            | Self::TxAccountFlag { .. }
             | Self::TxLog { .. }
             | Self::TxReceipt { .. } => None,
+            Self::AccountStorageClear { .. } => Some(Word::zero()),
         }
     }
 
     pub(crate) fn value_assignment<F: Field>(&self, randomness: F) -> F {
         match self {
             Self::Start { .. } => F::ZERO,
+            Self::AccountStorageClear { .. } => F::ONE,
             Self::CallContext {
                 field_tag, value, ..
             } => {
@@ -596,9 +674,9 @@ impl Rw {
                 }
                 AccountFieldTag::Nonce | AccountFieldTag::NonExisting => value.to_scalar().unwrap(),
             },
-            Self::AccountStorage { value, .. } | Self::Stack { value, .. } => {
-                rlc::value(&value.to_le_bytes(), randomness)
-            }
+            Self::AccountStorage { value, .. }
+            | Self::TransientStorage { value, .. }
+            | Self::Stack { value, .. } => rlc::value(&value.to_le_bytes(), randomness),
 
             Self::TxLog {
                 field_tag, value, ..
@@ -609,6 +687,7 @@ impl Rw {
 
             Self::TxAccessListAccount { is_warm, .. }
             | Self::TxAccessListAccountStorage { is_warm, .. } => F::from(*is_warm as u64),
+            Self::TxAccountFlag { value, .. } => F::from(*value as u64),
             Self::Memory { byte, .. } => F::from(u64::from(*byte)),
             Self::TxRefund { value, .. } | Self::TxReceipt { value, .. } => F::from(*value),
         }
@@ -628,14 +707,16 @@ impl Rw {
                     value_prev.to_scalar().unwrap()
                 }
             }),
-            Self::AccountStorage { value_prev, .. } => {
+            Self::AccountStorage { value_prev, .. } | Self::TransientStorage { value_prev, .. } => {
                 Some(rlc::value(&value_prev.to_le_bytes(), randomness))
             }
             Self::TxAccessListAccount { is_warm_prev, .. }
             | Self::TxAccessListAccountStorage { is_warm_prev, .. } => {
                 Some(F::from(*is_warm_prev as u64))
             }
+            Self::TxAccountFlag { value_prev, .. } => Some(F::from(*value_prev as u64)),
             Self::TxRefund { value_prev, .. } => Some(F::from(*value_prev)),
+            Self::AccountStorageClear { .. } => Some(F::ZERO),
             Self::Start { .. }
             | Self::Stack { .. }
             | Self::Memory { .. }
@@ -700,6 +781,25 @@ impl From<&operation::OperationContainer> for RwMap {
                 })
                 .collect(),
         );
+        rws.insert(
+            Target::TxAccountFlag,
+            container
+                .tx_account_flag
+                .iter()
+                .map(|op| Rw::TxAccountFlag {
+                    rw_counter: op.rwc().into(),
+                    is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
+                    account_address: op.op().address,
+                    field_tag: match op.op().field {
+                        TxAccountFlagField::Created => TxAccountFlagFieldTag::Created,
+                        TxAccountFlagField::Destructed => TxAccountFlagFieldTag::Destructed,
+                    },
+                    value: op.op().value,
+                    value_prev: op.op().value_prev,
+                })
+                .collect(),
+        );
         rws.insert(
             Target::TxRefund,
             container
@@ -738,15 +838,41 @@ impl From<&operation::OperationContainer> for RwMap {
             container
                 .storage
                 .iter()
-                .map(|op| Rw::AccountStorage {
+                .map(|op| {
+                    if op.op().is_clear {
+                        Rw::AccountStorageClear {
+                            rw_counter: op.rwc().into(),
+                            tx_id: op.op().tx_id,
+                            account_address: op.op().address,
+                        }
+                    } else {
+                        Rw::AccountStorage {
+                            rw_counter: op.rwc().into(),
+                            is_write: op.rw().is_write(),
+                            account_address: op.op().address,
+                            storage_key: op.op().key,
+                            value: op.op().value,
+                            value_prev: op.op().value_prev,
+                            tx_id: op.op().tx_id,
+                            committed_value: op.op().committed_value,
+                        }
+                    }
+                })
+                .collect(),
+        );
+        rws.insert(
+            Target::TransientStorage,
+            container
+                .transient_storage
+                .iter()
+                .map(|op| Rw::TransientStorage {
                     rw_counter: op.rwc().into(),
                     is_write: op.rw().is_write(),
+                    tx_id: op.op().tx_id,
                     account_address: op.op().address,
                     storage_key: op.op().key,
                     value: op.op().value,
                     value_prev: op.op().value_prev,
-                    tx_id: op.op().tx_id,
-                    committed_value: op.op().committed_value,
                 })
                 .collect(),
         );
@@ -1,5 +1,7 @@
 use bus_mapping::circuit_input_builder;
-use eth_types::{Address, Field, ToLittleEndian, ToScalar, Word};
+use eth_types::{
+    evm_types::MAX_BLOBS_PER_TX, Address, Field, ToLittleEndian, ToScalar, Word, H256,
+};
 use halo2_proofs::circuit::Value;
 
 use crate::{evm_circuit::util::rlc, table::TxContextFieldTag, util::Challenges};
@@ -11,12 +13,22 @@ use super::{Call, ExecStep};
 pub struct Transaction {
     /// The transaction identifier in the block
     pub id: usize,
+    /// The transaction type: 0 for legacy, 1 for EIP-2930, 2 for EIP-1559 and
+    /// 3 for EIP-4844
+    pub tx_type: u64,
+    /// The chain id the transaction is signed for
+    pub chain_id: u64,
     /// The sender account nonce of the transaction
     pub nonce: u64,
     /// The gas limit of the transaction
     pub gas: u64,
-    /// The gas price
+    /// The gas price, which is the effective gas price for EIP-1559
+    /// transactions
     pub gas_price: Word,
+    /// The max fee per gas (EIP-1559)
+    pub max_fee_per_gas: Word,
+    /// The max priority fee per gas (EIP-1559)
+    pub max_priority_fee_per_gas: Word,
     /// The caller address
     pub caller_address: Address,
     /// The callee address
@@ -31,6 +43,28 @@ pub struct Transaction {
     pub call_data_length: usize,
     /// The gas cost for transaction call data
     pub call_data_gas_cost: u64,
+    /// The number of addresses in the access list
+    pub access_list_addresses_len: u64,
+    /// The number of storage keys in the access list
+    pub access_list_storage_keys_len: u64,
+    /// The RLP encoding of the access list
+    pub access_list_rlp: Vec<u8>,
+    /// The addresses of the access list
+    pub access_list_addresses: Vec<Address>,
+    /// The storage keys of the access list with the address of their item
+    pub access_list_storage_keys: Vec<(Address, Word)>,
+    /// The max fee per blob gas (EIP-4844)
+    pub max_fee_per_blob_gas: Word,
+    /// The versioned hashes of the blobs (EIP-4844)
+    pub blob_versioned_hashes: Vec<H256>,
+    /// The RLP encoding of the list of blob versioned hashes
+    pub blob_versioned_hashes_rlp: Vec<u8>,
+    /// The signature r
+    pub r: Word,
+    /// The signature s
+    pub s: Word,
+    /// The hash of the signed transaction
+    pub hash: H256,
     /// The calls made in the transaction
     pub calls: Vec<Call>,
     /// The steps executioned in the transaction
@@ -44,7 +78,7 @@ impl Transaction {
         &self,
         challenges: Challenges<Value<F>>,
     ) -> [Vec<[Value<F>; 4]>; 2] {
-        let tx_data = vec![
+        let mut tx_data = vec![
             [
                 Value::known(F::from(self.id as u64)),
                 Value::known(F::from(TxContextFieldTag::Nonce as u64)),
@@ -103,7 +137,142 @@ impl Transaction {
                 Value::known(F::ZERO),
                 Value::known(F::from(self.call_data_gas_cost)),
             ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesLen as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.access_list_addresses_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysLen as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.access_list_storage_keys_len)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListRLC as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .keccak_input()
+                    .map(|challenge| rlc::value(self.access_list_rlp.iter().rev(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListAddressesRLC as u64)),
+                Value::known(F::ZERO),
+                challenges.keccak_input().map(|challenge| {
+                    access_list_addresses_rlc(&self.access_list_addresses, challenge)
+                }),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::AccessListStorageKeysRLC as u64)),
+                Value::known(F::ZERO),
+                challenges.keccak_input().zip(challenges.evm_word()).map(
+                    |(keccak_input, evm_word)| {
+                        access_list_storage_keys_rlc(
+                            &self.access_list_storage_keys,
+                            keccak_input,
+                            evm_word,
+                        )
+                    },
+                ),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerGas as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.max_fee_per_gas.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxPriorityFeePerGas as u64)),
+                Value::known(F::ZERO),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_priority_fee_per_gas.to_le_bytes(), challenge)
+                }),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::TxType as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.tx_type)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::ChainId as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.chain_id)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::SigR as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.r.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::SigS as u64)),
+                Value::known(F::ZERO),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&self.s.to_le_bytes(), challenge)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::TxHash as u64)),
+                Value::known(F::ZERO),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(
+                        &Word::from_big_endian(self.hash.as_bytes()).to_le_bytes(),
+                        challenge,
+                    )
+                }),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::MaxFeePerBlobGas as u64)),
+                Value::known(F::ZERO),
+                challenges.evm_word().map(|challenge| {
+                    rlc::value(&self.max_fee_per_blob_gas.to_le_bytes(), challenge)
+                }),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::BlobVersionedHashesLen as u64)),
+                Value::known(F::ZERO),
+                Value::known(F::from(self.blob_versioned_hashes.len() as u64)),
+            ],
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::BlobVersionedHashesRLC as u64)),
+                Value::known(F::ZERO),
+                challenges.keccak_input().map(|challenge| {
+                    rlc::value(self.blob_versioned_hashes_rlp.iter().rev(), challenge)
+                }),
+            ],
         ];
+        // The versioned hashes take a row for every possible blob, so that
+        // the layout of the table doesn't depend on the number of blobs.
+        tx_data.extend((0..MAX_BLOBS_PER_TX).map(|index| {
+            let hash = self
+                .blob_versioned_hashes
+                .get(index)
+                .map_or(Word::zero(), |hash| Word::from_big_endian(hash.as_bytes()));
+            [
+                Value::known(F::from(self.id as u64)),
+                Value::known(F::from(TxContextFieldTag::BlobVersionedHash as u64)),
+                Value::known(F::from(index as u64)),
+                challenges
+                    .evm_word()
+                    .map(|challenge| rlc::value(&hash.to_le_bytes(), challenge)),
+            ]
+        }));
         let tx_calldata = self
             .call_data
             .iter()
@@ -121,12 +290,44 @@ impl Transaction {
     }
 }
 
-pub(super) fn tx_convert(tx: &circuit_input_builder::Transaction, id: usize) -> Transaction {
+/// Return the RLC of the addresses of the access list, as accumulated by the
+/// copy circuit when they are added to the access list of the tx.
+pub(crate) fn access_list_addresses_rlc<F: Field>(addresses: &[Address], keccak_input: F) -> F {
+    addresses.iter().fold(F::ZERO, |acc, address| {
+        acc * keccak_input + address.to_scalar().unwrap()
+    })
+}
+
+/// Return the RLC of the storage keys of the access list, each one preceded
+/// by the address of its item, as accumulated by the copy circuit when they
+/// are added to the access list of the tx.
+pub(crate) fn access_list_storage_keys_rlc<F: Field>(
+    storage_keys: &[(Address, Word)],
+    keccak_input: F,
+    evm_word: F,
+) -> F {
+    storage_keys.iter().fold(F::ZERO, |acc, (address, key)| {
+        (acc * keccak_input + address.to_scalar().unwrap()) * keccak_input
+            + rlc::value(&key.to_le_bytes(), evm_word)
+    })
+}
+
+pub(super) fn tx_convert(
+    tx: &circuit_input_builder::Transaction,
+    id: usize,
+    chain_id: u64,
+) -> Transaction {
+    let (access_list_addresses_len, access_list_storage_keys_len) = tx.tx.access_list_len();
+    let (access_list_addresses, access_list_storage_keys) = tx.tx.access_list_entries();
     Transaction {
         id,
+        tx_type: tx.tx.transaction_type,
+        chain_id,
         nonce: tx.tx.nonce.as_u64(),
         gas: tx.gas(),
         gas_price: tx.tx.gas_price,
+        max_fee_per_gas: tx.tx.gas_fee_cap,
+        max_priority_fee_per_gas: tx.tx.gas_tip_cap,
         caller_address: tx.tx.from,
         callee_address: tx.tx.to_or_contract_addr(),
         is_create: tx.is_create(),
@@ -134,6 +335,17 @@ pub(super) fn tx_convert(tx: &circuit_input_builder::Transaction, id: usize) ->
         call_data: tx.tx.call_data.to_vec(),
         call_data_length: tx.tx.call_data.len(),
         call_data_gas_cost: tx.tx.call_data_gas_cost(),
+        access_list_addresses_len,
+        access_list_storage_keys_len,
+        access_list_rlp: tx.tx.access_list_rlp(),
+        access_list_addresses,
+        access_list_storage_keys,
+        max_fee_per_blob_gas: tx.tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.tx.blob_versioned_hashes.clone(),
+        blob_versioned_hashes_rlp: tx.tx.blob_versioned_hashes_rlp(),
+        r: tx.tx.r,
+        s: tx.tx.s,
+        hash: tx.tx.hash(chain_id),
         calls: tx.calls().to_vec(),
         steps: tx.steps().to_vec(),
     }